@@ -194,7 +194,7 @@ dddd eeee ffff 0123 4567 8901 2345 6789</a>\n\
     <!-- Output pin -->\n\
     <comp lib=\"0\" loc=\"(300,160)\" name=\"Pin\">\n\
       <a name=\"facing\" val=\"west\"/>\n\
-      <a name=\"output\" val=\"true\"/>\n\
+      <a name=\"type\" val=\"output\"/>\n\
       <a name=\"width\" val=\"16\"/>\n\
     </comp>\n\
     \n\
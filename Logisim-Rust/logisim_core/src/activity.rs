@@ -0,0 +1,483 @@
+//! Switching activity and dynamic power estimation.
+//!
+//! An [`ActivityRecorder`] listens to a simulation through its signal
+//! callbacks and counts, for every net, the bits toggling between low and
+//! high and the time its bits spend at each [`Value`]. The toggles on the
+//! nets a component drives make up its switching activity; every one of
+//! them charges or discharges the load capacitance of that component, which
+//! costs `C * V^2 / 2` of energy. Bits that splitters or closed switches
+//! join into one wire are charged once, however many nets share them.
+//!
+//! Changes to and from unknown, error and high impedance values are not
+//! counted as toggles.
+
+use crate::comp::ComponentId;
+use crate::netlist::NodeId;
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::simulation::{SignalChangeCallback, Simulation};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Attribute giving the load capacitance of a component in a .circ file, in
+/// farads, such as `<a name="capacitance" val="15e-12"/>`
+pub const CAPACITANCE_ATTRIBUTE: &str = "capacitance";
+
+/// Supply voltage and load capacitances used to estimate dynamic power
+///
+/// A component with a [`CAPACITANCE_ATTRIBUTE`] in the .circ file the
+/// simulation was loaded from switches that capacitance on every output bit
+/// toggle. For the others it is, in order of precedence, the one set on that
+/// instance, the entry for its component name, or the default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerModel {
+    /// Supply voltage in volts
+    pub voltage: f64,
+    /// Length of one simulation time unit in seconds
+    pub time_unit: f64,
+    /// Load capacitance in farads for components without an entry
+    pub default_capacitance: f64,
+    capacitances: HashMap<String, f64>,
+    instances: HashMap<ComponentId, f64>,
+}
+
+impl Default for PowerModel {
+    /// A 5 V supply, nanosecond time units and the 15 pF load the 74LS
+    /// datasheets specify
+    fn default() -> Self {
+        PowerModel {
+            voltage: 5.0,
+            time_unit: 1e-9,
+            default_capacitance: 15e-12,
+            capacitances: HashMap::new(),
+            instances: HashMap::new(),
+        }
+    }
+}
+
+impl PowerModel {
+    /// Create a model with the default supply and load
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the capacitance for a component name
+    pub fn with(mut self, name: impl Into<String>, capacitance: f64) -> Self {
+        self.set(name, capacitance);
+        self
+    }
+
+    /// Set the capacitance for a component name
+    pub fn set(&mut self, name: impl Into<String>, capacitance: f64) {
+        self.capacitances.insert(name.into(), capacitance);
+    }
+
+    /// Add or replace the capacitance of one component instance
+    pub fn with_instance(mut self, component_id: ComponentId, capacitance: f64) -> Self {
+        self.set_instance(component_id, capacitance);
+        self
+    }
+
+    /// Set the capacitance of one component instance
+    pub fn set_instance(&mut self, component_id: ComponentId, capacitance: f64) {
+        self.instances.insert(component_id, capacitance);
+    }
+
+    /// Get the capacitance a component instance switches per output toggle
+    pub fn capacitance(&self, component_id: ComponentId, name: &str) -> f64 {
+        self.instances
+            .get(&component_id)
+            .or_else(|| self.capacitances.get(name))
+            .copied()
+            .unwrap_or(self.default_capacitance)
+    }
+
+    /// Energy in joules of one output bit toggle across a capacitance
+    pub fn toggle_energy(&self, capacitance: f64) -> f64 {
+        0.5 * capacitance * self.voltage * self.voltage
+    }
+}
+
+/// Time the bits of a net spent at each value, in bit time units
+///
+/// A bus counts every bit, so the times of a 4-bit net add up to four times
+/// the recorded duration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueTimes {
+    /// Time at logic high
+    pub high: u64,
+    /// Time at logic low
+    pub low: u64,
+    /// Time unknown
+    pub unknown: u64,
+    /// Time in error
+    pub error: u64,
+    /// Time at high impedance
+    pub high_z: u64,
+}
+
+impl ValueTimes {
+    /// Get the time spent at a value
+    pub fn get(&self, value: Value) -> u64 {
+        match value {
+            Value::High => self.high,
+            Value::Low => self.low,
+            Value::Unknown => self.unknown,
+            Value::Error => self.error,
+            Value::HighZ => self.high_z,
+        }
+    }
+
+    fn add(&mut self, value: Value, time: u64) {
+        match value {
+            Value::High => self.high += time,
+            Value::Low => self.low += time,
+            Value::Unknown => self.unknown += time,
+            Value::Error => self.error += time,
+            Value::HighZ => self.high_z += time,
+        }
+    }
+}
+
+/// Activity of one net
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetActivity {
+    /// The node
+    pub node: NodeId,
+    /// Name of the node
+    pub name: Option<String>,
+    /// Width of the node
+    pub width: BusWidth,
+    /// Bit toggles between low and high
+    pub toggles: u64,
+    /// Time spent at each value
+    pub time: ValueTimes,
+}
+
+/// Switching activity and power of one component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentActivity {
+    /// The component
+    pub component: ComponentId,
+    /// Name of the component
+    pub name: String,
+    /// Bit toggles on the nets its outputs drive
+    pub toggles: u64,
+    /// Load capacitance in farads switched per toggle
+    pub capacitance: f64,
+    /// Energy in joules spent switching
+    pub energy: f64,
+    /// Average dynamic power in watts
+    pub power: f64,
+}
+
+/// Activity of a circuit over a recorded run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityReport {
+    /// Simulation time covered
+    pub duration: u64,
+    /// Nets, most toggles first
+    pub nets: Vec<NetActivity>,
+    /// Components, highest power first
+    pub components: Vec<ComponentActivity>,
+    /// Bit toggles on all nets
+    pub total_toggles: u64,
+    /// Estimated dynamic power in watts, summed over the components
+    pub dynamic_power: f64,
+}
+
+impl ActivityReport {
+    /// Get the activity of a net
+    pub fn net(&self, node: NodeId) -> Option<&NetActivity> {
+        self.nets.iter().find(|net| net.node == node)
+    }
+
+    /// Get the activity of a component
+    pub fn component(&self, component_id: ComponentId) -> Option<&ComponentActivity> {
+        self.components
+            .iter()
+            .find(|component| component.component == component_id)
+    }
+
+    /// Serialize the report to JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Format a power in the largest unit that keeps it at least one
+fn format_power(watts: f64) -> String {
+    let (scale, unit) = [(1.0, "W"), (1e-3, "mW"), (1e-6, "µW")]
+        .into_iter()
+        .find(|&(scale, _)| watts >= scale)
+        .unwrap_or((1e-9, "nW"));
+    format!("{:.3} {}", watts / scale, unit)
+}
+
+impl fmt::Display for ActivityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Switching activity over {} time units: {} toggles",
+            self.duration, self.total_toggles
+        )?;
+        writeln!(f, "Nets by toggles:")?;
+        for net in self.nets.iter().filter(|net| net.toggles > 0) {
+            match &net.name {
+                Some(name) => writeln!(f, "  {} ({}): {}", net.node, name, net.toggles)?,
+                None => writeln!(f, "  {}: {}", net.node, net.toggles)?,
+            }
+        }
+        writeln!(f, "Components by power:")?;
+        for component in self.components.iter().filter(|c| c.toggles > 0) {
+            writeln!(
+                f,
+                "  {} {}: {} toggles, {}",
+                component.component,
+                component.name,
+                component.toggles,
+                format_power(component.power)
+            )?;
+        }
+        write!(
+            f,
+            "Estimated dynamic power: {}",
+            format_power(self.dynamic_power)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NetRecord {
+    values: Vec<Value>,
+    since: u64,
+    toggles: u64,
+    /// Toggles of each bit
+    bit_toggles: Vec<u64>,
+    time: ValueTimes,
+}
+
+impl NetRecord {
+    fn new(values: Vec<Value>, since: u64) -> Self {
+        NetRecord {
+            bit_toggles: vec![0; values.len()],
+            values,
+            since,
+            toggles: 0,
+            time: ValueTimes::default(),
+        }
+    }
+
+    /// Account for the values held until `time`
+    fn advance(&mut self, time: u64) {
+        let held = time.saturating_sub(self.since);
+        for &value in &self.values {
+            self.time.add(value, held);
+        }
+        self.since = self.since.max(time);
+    }
+
+    fn change(&mut self, time: u64, values: Vec<Value>) {
+        self.advance(time);
+        self.bit_toggles.resize(values.len(), 0);
+        for ((old, new), toggles) in self.values.iter().zip(&values).zip(&mut self.bit_toggles) {
+            if matches!(
+                (old, new),
+                (Value::Low, Value::High) | (Value::High, Value::Low)
+            ) {
+                *toggles += 1;
+                self.toggles += 1;
+            }
+        }
+        self.values = values;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    start: u64,
+    nets: HashMap<NodeId, NetRecord>,
+}
+
+fn bit_values(signal: &Signal) -> Vec<Value> {
+    (0..signal.width().as_u32())
+        .map(|bit| signal.get_bit(bit).unwrap_or(Value::Unknown))
+        .collect()
+}
+
+/// Records the switching activity of a simulation
+///
+/// The recorder is a shared handle: clones see the same recording, so one
+/// can be handed to the simulation while another produces the report.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityRecorder {
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl ActivityRecorder {
+    /// Start recording a simulation from its current time and signals
+    pub fn attach(sim: &mut Simulation) -> Self {
+        let recorder = Self::default();
+        recorder.restart(sim);
+        sim.add_signal_callback(recorder.callback());
+        recorder
+    }
+
+    /// Forget what was recorded and start again from the simulation's
+    /// current time and signals
+    pub fn restart(&self, sim: &Simulation) {
+        let start = sim.current_time().as_u64();
+        let nets = sim
+            .netlist()
+            .get_all_nodes()
+            .iter()
+            .map(|(&id, node)| (id, NetRecord::new(bit_values(node.get_signal()), start)))
+            .collect();
+        if let Ok(mut recording) = self.recording.lock() {
+            *recording = Recording { start, nets };
+        }
+    }
+
+    /// Get a signal callback that feeds this recorder
+    ///
+    /// [`attach`](Self::attach) installs one already; this is for
+    /// simulations the recorder was not attached to.
+    pub fn callback(&self) -> SignalChangeCallback {
+        let recording = Arc::clone(&self.recording);
+        Box::new(move |node_id: NodeId, time: Timestamp, signal: &Signal| {
+            if let Ok(mut recording) = recording.lock() {
+                let start = recording.start;
+                let values = bit_values(signal);
+                recording
+                    .nets
+                    .entry(node_id)
+                    .or_insert_with(|| NetRecord::new(vec![Value::Unknown; values.len()], start))
+                    .change(time.as_u64(), values);
+            }
+        })
+    }
+
+    /// Report the activity recorded up to the simulation's current time
+    pub fn report(&self, sim: &Simulation, model: &PowerModel) -> ActivityReport {
+        let end = sim.current_time().as_u64();
+        let (start, mut records) = match self.recording.lock() {
+            Ok(recording) => (recording.start, recording.nets.clone()),
+            Err(_) => (end, HashMap::new()),
+        };
+        let duration = end.saturating_sub(start);
+        records.values_mut().for_each(|record| record.advance(end));
+
+        let netlist = sim.netlist();
+        let mut nets: Vec<NetActivity> = records
+            .iter()
+            .filter_map(|(&node_id, record)| {
+                let node = netlist.get_node(node_id)?;
+                Some(NetActivity {
+                    node: node_id,
+                    name: node.name.clone(),
+                    width: node.width,
+                    toggles: record.toggles,
+                    time: record.time,
+                })
+            })
+            .collect();
+        nets.sort_by(|a, b| b.toggles.cmp(&a.toggles).then(a.node.cmp(&b.node)));
+
+        let mut toggles: HashMap<ComponentId, u64> = HashMap::new();
+        // The threads each component was charged for, by their first bit
+        let mut charged: HashMap<ComponentId, HashSet<(NodeId, u32)>> = HashMap::new();
+        for connection in netlist.get_all_connections() {
+            let Some(component) = sim.get_component(connection.component_id) else {
+                continue;
+            };
+            let drives = component
+                .get_pin(&connection.pin_name)
+                .is_some_and(|pin| pin.is_output());
+            let count = toggles.entry(connection.component_id).or_default();
+            let Some(record) = records.get(&connection.node_id).filter(|_| drives) else {
+                continue;
+            };
+            let threads = charged.entry(connection.component_id).or_default();
+            for (bit, &bit_toggles) in (0..).zip(&record.bit_toggles) {
+                let thread = netlist.get_thread(connection.node_id, bit);
+                let first = thread
+                    .into_iter()
+                    .min()
+                    .unwrap_or((connection.node_id, bit));
+                if threads.insert(first) {
+                    *count += bit_toggles;
+                }
+            }
+        }
+
+        let seconds = duration as f64 * model.time_unit;
+        let mut components: Vec<ComponentActivity> = toggles
+            .into_iter()
+            .filter_map(|(component_id, toggles)| {
+                let name = sim.get_component(component_id)?.name().to_string();
+                let capacitance = sim
+                    .circuit_source()
+                    .and_then(|source| source.attribute(component_id, CAPACITANCE_ATTRIBUTE))
+                    .and_then(|value| value.trim().parse().ok())
+                    .unwrap_or_else(|| model.capacitance(component_id, &name));
+                let energy = model.toggle_energy(capacitance) * toggles as f64;
+                let power = if seconds > 0.0 { energy / seconds } else { 0.0 };
+                Some(ComponentActivity {
+                    component: component_id,
+                    name,
+                    toggles,
+                    capacitance,
+                    energy,
+                    power,
+                })
+            })
+            .collect();
+        components.sort_by(|a, b| {
+            b.power
+                .total_cmp(&a.power)
+                .then(b.toggles.cmp(&a.toggles))
+                .then(a.component.cmp(&b.component))
+        });
+
+        ActivityReport {
+            duration,
+            total_toggles: nets.iter().map(|net| net.toggles).sum(),
+            dynamic_power: components.iter().map(|component| component.power).sum(),
+            nets,
+            components,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_net_record_counts_full_swings() {
+        let mut record = NetRecord::new(vec![Value::Unknown, Value::Unknown], 0);
+        record.change(5, vec![Value::Low, Value::Low]);
+        record.change(10, vec![Value::High, Value::Low]);
+        record.change(12, vec![Value::Low, Value::High]);
+        record.advance(20);
+
+        assert_eq!(record.toggles, 3);
+        assert_eq!(record.bit_toggles, vec![2, 1]);
+        assert_eq!(record.time.get(Value::Unknown), 10);
+        assert_eq!(record.time.low, 5 + 8 + 7);
+        assert_eq!(record.time.high, 2 + 8);
+    }
+
+    #[test]
+    fn test_power_model_precedence() {
+        let model = PowerModel::new()
+            .with("AND", 2e-12)
+            .with_instance(ComponentId(2), 4e-12);
+        assert_eq!(model.capacitance(ComponentId(1), "AND"), 2e-12);
+        assert_eq!(model.capacitance(ComponentId(2), "AND"), 4e-12);
+        assert_eq!(model.capacitance(ComponentId(3), "OR"), 15e-12);
+        assert_eq!(format_power(0.025), "25.000 mW");
+        assert_eq!(format_power(0.0), "0.000 nW");
+    }
+}
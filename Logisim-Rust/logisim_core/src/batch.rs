@@ -0,0 +1,412 @@
+//! Bit-parallel batch evaluation of combinational circuits.
+//!
+//! Every bit of every net holds a [`Lanes`] word, so 64 independent input
+//! vectors travel through the circuit in a single pass over its components
+//! in levelized order. This makes exhaustive truth-table checks of blocks
+//! with a dozen or more inputs practical.
+//!
+//! Only combinational components implementing
+//! [`Component::evaluate_lanes`](crate::comp::Component::evaluate_lanes) can
+//! take part; the gates of `std::gates` and the multiplexer do.
+
+use crate::comp::{Component, ComponentId};
+use crate::levelized::Schedule;
+use crate::netlist::{Netlist, NodeId};
+use crate::signal::{BusWidth, Lanes, Signal, Value};
+use crate::simulation::SimulationError;
+use std::collections::HashMap;
+
+/// Largest number of input bits `evaluate_exhaustive` enumerates
+pub const MAX_EXHAUSTIVE_BITS: u32 = 24;
+
+/// Fold every input pin of a gate into one output with a lane-wise operation
+pub(crate) fn fold_inputs(
+    inputs: &HashMap<String, Vec<Lanes>>,
+    width: usize,
+    initial: Value,
+    op: impl Fn(Lanes, Lanes) -> Lanes,
+) -> Vec<Lanes> {
+    let mut output = vec![Lanes::splat(initial); width];
+    for words in inputs.values() {
+        for (out, &word) in output.iter_mut().zip(words) {
+            *out = op(*out, word);
+        }
+    }
+    output
+}
+
+/// Parity of every input pin of a gate, unknown wherever an input is not
+/// a definite value
+pub(crate) fn parity(inputs: &HashMap<String, Vec<Lanes>>, width: usize, odd: bool) -> Vec<Lanes> {
+    (0..width)
+        .map(|bit| {
+            let mut ones = 0u64;
+            let mut undefined = 0u64;
+            for words in inputs.values() {
+                let word = words.get(bit).copied().unwrap_or_default();
+                ones ^= word.high;
+                undefined |= !(word.high | word.low);
+            }
+            if !odd {
+                ones = !ones;
+            }
+            Lanes {
+                high: ones & !undefined,
+                low: !ones & !undefined,
+                error: 0,
+                high_z: 0,
+            }
+        })
+        .collect()
+}
+
+/// Compiled circuit evaluating 64 input vectors per pass
+///
+/// Created by [`Simulation::batch`](crate::Simulation::batch). The
+/// evaluator works on the circuit's structure only: nodes start floating,
+/// and the simulation's current values and pending events play no part.
+pub struct BatchEvaluator<'a> {
+    components: &'a HashMap<ComponentId, Box<dyn Component>>,
+    schedule: Schedule,
+    inputs: Vec<(NodeId, BusWidth)>,
+    outputs: Vec<(NodeId, BusWidth)>,
+    /// Electrical thread of every bit of every node
+    node_threads: HashMap<NodeId, Vec<usize>>,
+    /// Pull value of every thread
+    pulls: Vec<Option<Value>>,
+    /// Driver slots on every thread
+    thread_drivers: Vec<Vec<usize>>,
+    /// Driver slots of every bit of every component output pin
+    output_slots: HashMap<(ComponentId, String), Vec<usize>>,
+    /// Driver slots of every bit of every input node
+    input_slots: Vec<Vec<usize>>,
+    /// Thread driven by every driver slot
+    slot_threads: Vec<usize>,
+}
+
+impl<'a> BatchEvaluator<'a> {
+    /// Compile a circuit for batch evaluation
+    pub(crate) fn new(
+        netlist: &Netlist,
+        components: &'a HashMap<ComponentId, Box<dyn Component>>,
+        inputs: &[NodeId],
+        outputs: &[NodeId],
+    ) -> Result<Self, SimulationError> {
+        let mut ids: Vec<&ComponentId> = components.keys().collect();
+        ids.sort();
+        for &id in ids {
+            let component = &components[&id];
+            if component.is_sequential() {
+                return Err(SimulationError::BatchError(format!(
+                    "component {} ({}) is sequential",
+                    id,
+                    component.name()
+                )));
+            }
+            let probe = component
+                .pins()
+                .iter()
+                .filter(|(_, pin)| pin.is_input())
+                .map(|(name, pin)| (name.clone(), vec![Lanes::default(); pin.width.0 as usize]))
+                .collect();
+            if component.evaluate_lanes(&probe).is_none() {
+                return Err(SimulationError::BatchError(format!(
+                    "component {} ({}) has no bit-parallel implementation",
+                    id,
+                    component.name()
+                )));
+            }
+        }
+        let schedule = Schedule::compile(netlist, components).ok_or_else(|| {
+            SimulationError::BatchError("the circuit contains a combinational loop".to_string())
+        })?;
+
+        let width = |node_id: NodeId| {
+            netlist
+                .get_node(node_id)
+                .map(|node| node.width)
+                .ok_or(SimulationError::NodeNotFound(node_id))
+        };
+        let inputs = inputs
+            .iter()
+            .map(|&node_id| Ok((node_id, width(node_id)?)))
+            .collect::<Result<Vec<_>, SimulationError>>()?;
+        let outputs = outputs
+            .iter()
+            .map(|&node_id| Ok((node_id, width(node_id)?)))
+            .collect::<Result<Vec<_>, SimulationError>>()?;
+
+        let mut evaluator = BatchEvaluator {
+            components,
+            schedule,
+            inputs,
+            outputs,
+            node_threads: HashMap::new(),
+            pulls: Vec::new(),
+            thread_drivers: Vec::new(),
+            output_slots: HashMap::new(),
+            input_slots: Vec::new(),
+            slot_threads: Vec::new(),
+        };
+
+        // Group the bits of all nodes into threads
+        let mut node_ids = netlist.get_all_node_ids();
+        node_ids.sort();
+        for &node_id in &node_ids {
+            let node_width = width(node_id)?.0 as usize;
+            evaluator
+                .node_threads
+                .entry(node_id)
+                .or_insert_with(|| vec![usize::MAX; node_width]);
+            for bit in 0..node_width {
+                if evaluator.node_threads[&node_id][bit] != usize::MAX {
+                    continue;
+                }
+                let thread = evaluator.pulls.len();
+                let mut pull = None;
+                for (id, b) in netlist.get_thread(node_id, bit as u32) {
+                    let Some(node) = netlist.get_node(id) else {
+                        continue;
+                    };
+                    pull = pull.or(node.pull);
+                    evaluator
+                        .node_threads
+                        .entry(id)
+                        .or_insert_with(|| vec![usize::MAX; node.width.0 as usize])
+                        [b as usize] = thread;
+                }
+                evaluator.pulls.push(pull);
+                evaluator.thread_drivers.push(Vec::new());
+            }
+        }
+
+        // One driver slot per bit of every output pin and input node
+        let mut connections: Vec<(ComponentId, String, NodeId)> = Vec::new();
+        for (&component_id, component) in components {
+            for connection in netlist.get_component_connections(component_id) {
+                if component
+                    .get_pin(&connection.pin_name)
+                    .is_some_and(|pin| pin.is_output())
+                {
+                    connections.push((
+                        component_id,
+                        connection.pin_name.clone(),
+                        connection.node_id,
+                    ));
+                }
+            }
+        }
+        connections.sort();
+        for (component_id, pin_name, node_id) in connections {
+            let slots = evaluator.add_slots(node_id);
+            evaluator
+                .output_slots
+                .insert((component_id, pin_name), slots);
+        }
+        for index in 0..evaluator.inputs.len() {
+            let slots = evaluator.add_slots(evaluator.inputs[index].0);
+            evaluator.input_slots.push(slots);
+        }
+
+        Ok(evaluator)
+    }
+
+    /// Add a driver slot on every bit of a node
+    fn add_slots(&mut self, node_id: NodeId) -> Vec<usize> {
+        let threads = self.node_threads.get(&node_id).cloned().unwrap_or_default();
+        threads
+            .into_iter()
+            .map(|thread| {
+                let slot = self.slot_threads.len();
+                self.slot_threads.push(thread);
+                self.thread_drivers[thread].push(slot);
+                slot
+            })
+            .collect()
+    }
+
+    /// Total number of input bits
+    pub fn input_bits(&self) -> u32 {
+        self.inputs.iter().map(|(_, width)| width.0).sum()
+    }
+
+    /// Evaluate the circuit for every input vector
+    ///
+    /// Each vector holds one signal per input node, and each result one
+    /// signal per output node, in the order given when compiling.
+    pub fn evaluate(&self, vectors: &[Vec<Signal>]) -> Result<Vec<Vec<Signal>>, SimulationError> {
+        for vector in vectors {
+            if vector.len() != self.inputs.len() {
+                return Err(SimulationError::BatchError(format!(
+                    "expected {} input signals, got {}",
+                    self.inputs.len(),
+                    vector.len()
+                )));
+            }
+            for (signal, &(node, width)) in vector.iter().zip(&self.inputs) {
+                if signal.width() != width {
+                    return Err(SimulationError::WidthMismatch {
+                        node,
+                        expected: width,
+                        actual: signal.width(),
+                    });
+                }
+            }
+        }
+
+        let mut results = Vec::with_capacity(vectors.len());
+        for chunk in vectors.chunks(Lanes::COUNT) {
+            let inputs: Vec<Vec<Lanes>> = self
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(index, &(_, width))| {
+                    (0..width.0)
+                        .map(|bit| {
+                            let mut word = Lanes::default();
+                            for (lane, vector) in chunk.iter().enumerate() {
+                                word.set(
+                                    lane,
+                                    vector[index].get_bit(bit).unwrap_or(Value::Unknown),
+                                );
+                            }
+                            word
+                        })
+                        .collect()
+                })
+                .collect();
+            let outputs = self.evaluate_words(&inputs)?;
+            results.extend((0..chunk.len()).map(|lane| {
+                outputs
+                    .iter()
+                    .map(|words| Signal::new_bus(words.iter().map(|word| word.get(lane)).collect()))
+                    .collect()
+            }));
+        }
+        Ok(results)
+    }
+
+    /// Evaluate the circuit for every assignment of its input bits
+    ///
+    /// Assignment `n` gives the inputs the bits of `n`, the first input
+    /// taking the least significant ones. At most `MAX_EXHAUSTIVE_BITS`
+    /// input bits can be enumerated.
+    pub fn evaluate_exhaustive(&self) -> Result<Vec<Vec<Signal>>, SimulationError> {
+        let bits = self.input_bits();
+        if bits > MAX_EXHAUSTIVE_BITS {
+            return Err(SimulationError::BatchError(format!(
+                "{} input bits are too many to enumerate",
+                bits
+            )));
+        }
+
+        let mut results = Vec::with_capacity(1 << bits);
+        let mut start = 0u64;
+        while start < 1 << bits {
+            let lanes = (Lanes::COUNT as u64).min((1 << bits) - start) as usize;
+            // Lane `l` holds assignment `start + l`
+            let mut offset = 0;
+            let inputs: Vec<Vec<Lanes>> = self
+                .inputs
+                .iter()
+                .map(|&(_, width)| {
+                    let words = (0..width.0)
+                        .map(|bit| {
+                            let mut word = Lanes::splat(Value::Low);
+                            for lane in 0..lanes {
+                                if (start + lane as u64) >> (offset + bit) & 1 == 1 {
+                                    word.set(lane, Value::High);
+                                }
+                            }
+                            word
+                        })
+                        .collect();
+                    offset += width.0;
+                    words
+                })
+                .collect();
+            let outputs = self.evaluate_words(&inputs)?;
+            results.extend((0..lanes).map(|lane| {
+                outputs
+                    .iter()
+                    .map(|words| Signal::new_bus(words.iter().map(|word| word.get(lane)).collect()))
+                    .collect()
+            }));
+            start += Lanes::COUNT as u64;
+        }
+        Ok(results)
+    }
+
+    /// Run one pass with the given words on the input nodes and return the
+    /// words on the output nodes
+    fn evaluate_words(&self, inputs: &[Vec<Lanes>]) -> Result<Vec<Vec<Lanes>>, SimulationError> {
+        let mut slots = vec![Lanes::splat(Value::HighZ); self.slot_threads.len()];
+        for (words, input_slots) in inputs.iter().zip(&self.input_slots) {
+            for (&word, &slot) in words.iter().zip(input_slots) {
+                slots[slot] = word;
+            }
+        }
+        let mut threads: Vec<Lanes> = (0..self.pulls.len())
+            .map(|thread| self.resolve(thread, &slots))
+            .collect();
+
+        for position in 0..self.schedule.len() {
+            let component_id = self.schedule.component_at(position);
+            let component = &self.components[&component_id];
+            let mut pin_words: HashMap<String, Vec<Lanes>> = component
+                .pins()
+                .iter()
+                .filter(|(_, pin)| pin.is_input())
+                .map(|(name, pin)| (name.clone(), vec![Lanes::default(); pin.width.0 as usize]))
+                .collect();
+            for (pin_name, node_id) in self.schedule.inputs(component_id) {
+                if let Some(words) = pin_words.get_mut(pin_name) {
+                    for (word, &thread) in words.iter_mut().zip(&self.node_threads[node_id]) {
+                        *word = threads[thread];
+                    }
+                }
+            }
+
+            let outputs = component.evaluate_lanes(&pin_words).ok_or_else(|| {
+                SimulationError::BatchError(format!(
+                    "component {} has no bit-parallel implementation",
+                    component_id
+                ))
+            })?;
+            for (pin_name, words) in outputs {
+                let Some(output_slots) = self.output_slots.get(&(component_id, pin_name)) else {
+                    continue;
+                };
+                for (&word, &slot) in words.iter().zip(output_slots) {
+                    slots[slot] = word;
+                    let thread = self.slot_threads[slot];
+                    threads[thread] = self.resolve(thread, &slots);
+                }
+            }
+        }
+
+        Ok(self
+            .outputs
+            .iter()
+            .map(|(node_id, _)| {
+                self.node_threads[node_id]
+                    .iter()
+                    .map(|&thread| threads[thread])
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Resolve every driver of a thread, then apply its pull resistor
+    fn resolve(&self, thread: usize, slots: &[Lanes]) -> Lanes {
+        let value = self.thread_drivers[thread]
+            .iter()
+            .fold(Lanes::splat(Value::HighZ), |value, &slot| {
+                value.resolve(slots[slot])
+            });
+        match self.pulls[thread] {
+            Some(pull) => value.pull(pull),
+            None => value,
+        }
+    }
+}
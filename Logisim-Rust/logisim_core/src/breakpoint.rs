@@ -0,0 +1,350 @@
+//! Breakpoints and watch conditions on nodes.
+//!
+//! A breakpoint pairs a [`Condition`] over named nodes with an ID. The
+//! simulation checks its breakpoints after every processed event, and the
+//! `run` family of methods stops as soon as one of them fires.
+
+use crate::netlist::{Netlist, NodeId};
+use crate::signal::{Signal, Timestamp, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Unique identifier for a breakpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BreakpointId(pub u64);
+
+impl fmt::Display for BreakpointId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Breakpoint{}", self.0)
+    }
+}
+
+/// Condition over named nodes that a breakpoint waits for
+///
+/// Level conditions such as `Equals` fire when they become true, not on
+/// every event while they hold. Conditions containing an event term
+/// (`Changes`, `Rising`, `Falling` or `ErrorAppears`) fire every time they
+/// are true, so each matching change is reported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Condition {
+    /// The node carries a fully defined value equal to the given one
+    Equals(String, u64),
+    /// The node's value changes
+    Changes(String),
+    /// The single-bit node goes from low to high
+    Rising(String),
+    /// The single-bit node goes from high to low
+    Falling(String),
+    /// An error value appears on any node
+    ErrorAppears,
+    /// The inner condition is false
+    Not(Box<Condition>),
+    /// Every inner condition is true
+    All(Vec<Condition>),
+    /// At least one inner condition is true
+    Any(Vec<Condition>),
+}
+
+impl Condition {
+    /// The named node carries `value`
+    pub fn equals(node: impl Into<String>, value: u64) -> Self {
+        Condition::Equals(node.into(), value)
+    }
+
+    /// The named node changes
+    pub fn changes(node: impl Into<String>) -> Self {
+        Condition::Changes(node.into())
+    }
+
+    /// The named node has a rising edge
+    pub fn rising(node: impl Into<String>) -> Self {
+        Condition::Rising(node.into())
+    }
+
+    /// The named node has a falling edge
+    pub fn falling(node: impl Into<String>) -> Self {
+        Condition::Falling(node.into())
+    }
+
+    /// Both this condition and `other` are true
+    pub fn and(self, other: Condition) -> Self {
+        Condition::All(vec![self, other])
+    }
+
+    /// This condition or `other` is true
+    pub fn or(self, other: Condition) -> Self {
+        Condition::Any(vec![self, other])
+    }
+
+    /// Whether the condition contains an event term
+    fn is_event(&self) -> bool {
+        match self {
+            Condition::Equals(..) => false,
+            Condition::Changes(_)
+            | Condition::Rising(_)
+            | Condition::Falling(_)
+            | Condition::ErrorAppears => true,
+            Condition::Not(inner) => inner.is_event(),
+            Condition::All(inner) | Condition::Any(inner) => inner.iter().any(Self::is_event),
+        }
+    }
+
+    /// Look up every node the condition names
+    fn resolve(&self, netlist: &Netlist) -> Result<Watch, String> {
+        let node = |name: &String| netlist.find_node_by_name(name).ok_or_else(|| name.clone());
+        Ok(match self {
+            Condition::Equals(name, value) => Watch::Equals(node(name)?, *value),
+            Condition::Changes(name) => Watch::Changes(node(name)?),
+            Condition::Rising(name) => Watch::Edge(node(name)?, Value::Low, Value::High),
+            Condition::Falling(name) => Watch::Edge(node(name)?, Value::High, Value::Low),
+            Condition::ErrorAppears => Watch::ErrorAppears,
+            Condition::Not(inner) => Watch::Not(Box::new(inner.resolve(netlist)?)),
+            Condition::All(inner) => Watch::All(
+                inner
+                    .iter()
+                    .map(|condition| condition.resolve(netlist))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Condition::Any(inner) => Watch::Any(
+                inner
+                    .iter()
+                    .map(|condition| condition.resolve(netlist))
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+}
+
+impl std::ops::Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Condition {
+        Condition::Not(Box::new(self))
+    }
+}
+
+/// A breakpoint that fired
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreakpointHit {
+    /// The breakpoint that fired
+    pub id: BreakpointId,
+    /// Simulation time of the event that made it fire
+    pub time: Timestamp,
+}
+
+/// A condition with its node names resolved
+#[derive(Debug, Clone)]
+enum Watch {
+    Equals(NodeId, u64),
+    Changes(NodeId),
+    Edge(NodeId, Value, Value),
+    ErrorAppears,
+    Not(Box<Watch>),
+    All(Vec<Watch>),
+    Any(Vec<Watch>),
+}
+
+impl Watch {
+    fn nodes(&self, nodes: &mut HashSet<NodeId>) {
+        match self {
+            Watch::Equals(node, _) | Watch::Changes(node) | Watch::Edge(node, ..) => {
+                nodes.insert(*node);
+            }
+            Watch::ErrorAppears => {}
+            Watch::Not(inner) => inner.nodes(nodes),
+            Watch::All(inner) | Watch::Any(inner) => {
+                inner.iter().for_each(|watch| watch.nodes(nodes))
+            }
+        }
+    }
+
+    fn evaluate(&self, check: &Check) -> bool {
+        match self {
+            Watch::Equals(node, value) => check
+                .current(*node)
+                .and_then(Signal::to_u64)
+                .is_some_and(|current| current == *value),
+            Watch::Changes(node) => match (check.previous.get(node), check.current(*node)) {
+                (Some(previous), Some(current)) => previous.bits() != current.bits(),
+                _ => false,
+            },
+            Watch::Edge(node, from, to) => {
+                let previous = check.previous.get(node).and_then(Signal::as_single);
+                let current = check.current(*node).and_then(Signal::as_single);
+                previous == Some(*from) && current == Some(*to)
+            }
+            Watch::ErrorAppears => check.changed.iter().any(|&node| {
+                check.current(node).is_some_and(Signal::has_error)
+                    && !check.previous.get(&node).is_some_and(Signal::has_error)
+            }),
+            Watch::Not(inner) => !inner.evaluate(check),
+            Watch::All(inner) => inner.iter().all(|watch| watch.evaluate(check)),
+            Watch::Any(inner) => inner.iter().any(|watch| watch.evaluate(check)),
+        }
+    }
+}
+
+/// Node values before and after one event
+struct Check<'a> {
+    netlist: &'a Netlist,
+    previous: &'a HashMap<NodeId, Signal>,
+    changed: &'a HashSet<NodeId>,
+}
+
+impl Check<'_> {
+    fn current(&self, node: NodeId) -> Option<&Signal> {
+        self.netlist.get_signal(node)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Breakpoint {
+    id: BreakpointId,
+    condition: Condition,
+    watch: Watch,
+    event: bool,
+    was_true: bool,
+}
+
+/// The breakpoints of a simulation and the node values they compare against
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Breakpoints {
+    next_id: u64,
+    breakpoints: Vec<Breakpoint>,
+    /// Last seen value of every node a breakpoint refers to
+    previous: HashMap<NodeId, Signal>,
+    /// Nodes changed by the current event, or since the last check
+    changed: HashSet<NodeId>,
+}
+
+impl Breakpoints {
+    /// Add a breakpoint, failing with the name of an unknown node
+    pub(crate) fn add(
+        &mut self,
+        condition: Condition,
+        netlist: &Netlist,
+    ) -> Result<BreakpointId, String> {
+        let watch = condition.resolve(netlist)?;
+        let id = BreakpointId(self.next_id);
+        self.next_id += 1;
+        self.breakpoints.push(Breakpoint {
+            id,
+            event: condition.is_event(),
+            condition,
+            watch,
+            was_true: false,
+        });
+        self.sync(netlist);
+        Ok(id)
+    }
+
+    /// Remove a breakpoint, returning its condition
+    pub(crate) fn remove(&mut self, id: BreakpointId) -> Option<Condition> {
+        let index = self.breakpoints.iter().position(|bp| bp.id == id)?;
+        Some(self.breakpoints.remove(index).condition)
+    }
+
+    /// Remove every breakpoint
+    pub(crate) fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.previous.clear();
+        self.changed.clear();
+    }
+
+    /// Every breakpoint with its condition
+    pub(crate) fn list(&self) -> Vec<(BreakpointId, &Condition)> {
+        self.breakpoints
+            .iter()
+            .map(|bp| (bp.id, &bp.condition))
+            .collect()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.breakpoints.is_empty()
+    }
+
+    /// Note that a node changed during the current event
+    pub(crate) fn node_changed(&mut self, node: NodeId) {
+        if !self.breakpoints.is_empty() {
+            self.changed.insert(node);
+        }
+    }
+
+    /// Take the current node values as the baseline for the next check,
+    /// e.g. after the values were replaced wholesale
+    pub(crate) fn sync(&mut self, netlist: &Netlist) {
+        let mut nodes = HashSet::new();
+        for bp in &self.breakpoints {
+            bp.watch.nodes(&mut nodes);
+        }
+        self.previous = nodes
+            .into_iter()
+            .filter_map(|node| Some((node, netlist.get_signal(node)?.clone())))
+            .collect();
+        self.changed.clear();
+        for bp in &mut self.breakpoints {
+            bp.was_true = false;
+        }
+    }
+
+    /// Evaluate every breakpoint after an event and return those that fire
+    pub(crate) fn check(&mut self, netlist: &Netlist, time: Timestamp) -> Vec<BreakpointHit> {
+        let check = Check {
+            netlist,
+            previous: &self.previous,
+            changed: &self.changed,
+        };
+        let mut hits = Vec::new();
+        for bp in &mut self.breakpoints {
+            let is_true = bp.watch.evaluate(&check);
+            if is_true && (bp.event || !bp.was_true) {
+                hits.push(BreakpointHit { id: bp.id, time });
+            }
+            bp.was_true = is_true;
+        }
+
+        for &node in &self.changed {
+            if let Some(previous) = self.previous.get_mut(&node) {
+                if let Some(signal) = netlist.get_signal(node) {
+                    *previous = signal.clone();
+                }
+            }
+        }
+        self.changed.clear();
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::BusWidth;
+
+    #[test]
+    fn test_condition_event_terms() {
+        assert!(!Condition::equals("pc", 0x40).is_event());
+        assert!(Condition::equals("pc", 0x40)
+            .and(Condition::rising("clk"))
+            .is_event());
+        assert!((!Condition::changes("a")).is_event());
+    }
+
+    #[test]
+    fn test_unknown_node_is_rejected() {
+        let mut netlist = Netlist::new();
+        netlist.create_named_node(BusWidth(8), "pc".to_string());
+
+        let mut breakpoints = Breakpoints::default();
+        assert!(breakpoints
+            .add(Condition::equals("pc", 1), &netlist)
+            .is_ok());
+        assert_eq!(
+            breakpoints.add(
+                Condition::equals("pc", 1).or(Condition::changes("sp")),
+                &netlist
+            ),
+            Err("sp".to_string())
+        );
+    }
+}
@@ -28,16 +28,23 @@
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::comp::{Component, ComponentId};
+use crate::circ_migration::{self, MigrationChange};
+use crate::circ_netlist::{
+    attr, attr_u32, component_ports, splitter_bit_map, subcircuit_ports, CircuitNets,
+};
+use crate::circ_registry::{ComponentRegistry, UnsupportedComponent};
+use crate::circ_validation::{self, ValidationIssue};
+use crate::comp::{CircTool, ComponentId};
 use crate::netlist::NodeId;
+use crate::signal::{BusWidth, Value};
 use crate::simulation::Simulation;
-use crate::std::gates::{AndGate, NandGate, NorGate, NotGate, OrGate, XnorGate, XorGate};
-use crate::std::wiring::{Constant, Pin};
+use crate::std::memory::MemContents;
 
 /// Errors that can occur during .circ file processing
 #[derive(Error, Debug)]
@@ -51,9 +58,21 @@ pub enum CircFormatError {
     #[error("Invalid circuit format: {0}")]
     InvalidFormat(String),
 
+    #[error(
+        "Invalid circuit file: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    Validation(Vec<ValidationIssue>),
+
     #[error("Unsupported component: {0}")]
     UnsupportedComponent(String),
 
+    #[error(
+        "Unsupported components: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    UnsupportedComponents(Vec<UnsupportedComponent>),
+
     #[error("Missing required attribute: {0}")]
     MissingAttribute(String),
 
@@ -65,13 +84,19 @@ pub enum CircFormatError {
 
     #[error("ROM parsing error: {0}")]
     RomParsingError(String),
+
+    #[error("Circular circuit reference: {0}")]
+    CircularReference(String),
+
+    #[error("Library not found: {0}")]
+    LibraryNotFound(String),
 }
 
 /// Result type for .circ format operations
 pub type CircResult<T> = Result<T, CircFormatError>;
 
 /// Represents a complete .circ file with all its circuits and metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CircuitFile {
     /// Logisim version that created this file
     pub source_version: String,
@@ -81,18 +106,73 @@ pub struct CircuitFile {
     pub libraries: Vec<LibraryConfig>,
     /// Main circuit name
     pub main_circuit: Option<String>,
-    /// All circuits in the file
-    pub circuits: HashMap<String, CircuitDefinition>,
+    /// All circuits in the file, in file order
+    pub circuits: IndexMap<String, CircuitDefinition>,
     /// VHDL content if any
     pub vhdl_contents: Vec<VhdlContent>,
     /// Project options
     pub options: ProjectOptions,
     /// External circuit files loaded for hierarchical circuits
     pub external_circuits: HashMap<String, CircuitFile>, // lib_name -> external circuit file
+    /// Changes made to bring a file of an older release up to date; not
+    /// written back
+    #[serde(default)]
+    pub migrations: Vec<MigrationChange>,
+}
+
+impl Default for CircuitFile {
+    /// An empty project, as written by the Logisim-Evolution release this
+    /// format follows
+    fn default() -> Self {
+        Self {
+            source_version: "3.8.0".to_string(),
+            version: "1.0".to_string(),
+            libraries: Vec::new(),
+            main_circuit: None,
+            circuits: IndexMap::new(),
+            vhdl_contents: Vec::new(),
+            options: ProjectOptions::default(),
+            external_circuits: HashMap::new(),
+            migrations: Vec::new(),
+        }
+    }
+}
+
+impl CircuitFile {
+    /// Use the circuits of another .circ file as a library
+    ///
+    /// `file` is the library's path relative to this file, under which the
+    /// dependency is declared as a `file#` library so that it is written
+    /// back when the file is saved. Returns the library index components use
+    /// to refer to its circuits.
+    pub fn add_external_library(&mut self, file: &str, library: CircuitFile) -> String {
+        let existing = self
+            .libraries
+            .iter()
+            .find(|lib| lib.external_file.as_deref() == Some(file));
+        let name = match existing {
+            Some(lib) => lib.name.clone(),
+            None => {
+                let name = (0..)
+                    .map(|index: usize| index.to_string())
+                    .find(|name| self.libraries.iter().all(|lib| lib.name != *name))
+                    .unwrap_or_default();
+                self.libraries.push(LibraryConfig {
+                    name: name.clone(),
+                    description: format!("file#{}", file),
+                    tools: Vec::new(),
+                    external_file: Some(file.to_string()),
+                });
+                name
+            }
+        };
+        self.external_circuits.insert(name.clone(), library);
+        name
+    }
 }
 
 /// Library configuration for component tools
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LibraryConfig {
     pub name: String,
     pub description: String,
@@ -101,70 +181,98 @@ pub struct LibraryConfig {
 }
 
 /// Tool configuration within a library
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolConfig {
     pub name: String,
-    pub attributes: HashMap<String, String>,
+    pub attributes: IndexMap<String, String>,
 }
 
 /// Circuit definition with components and connections
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CircuitDefinition {
     pub name: String,
     pub components: Vec<ComponentInstance>,
     pub wires: Vec<WireConnection>,
     pub appearance: Option<CircuitAppearance>,
-    pub attributes: HashMap<String, String>,
+    pub attributes: IndexMap<String, String>,
 }
 
 /// A component instance in a circuit
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ComponentInstance {
     pub library: Option<String>,
     pub name: String,
     pub location: (i32, i32),
-    pub attributes: HashMap<String, String>,
+    pub attributes: IndexMap<String, String>,
     pub facing: Option<String>,
 }
 
 /// Wire connection between points
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WireConnection {
     pub from: (i32, i32),
     pub to: (i32, i32),
 }
 
 /// Circuit appearance information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CircuitAppearance {
     pub custom: bool,
     pub elements: Vec<AppearanceElement>,
 }
 
 /// Appearance element (SVG-like)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppearanceElement {
     pub element_type: String,
-    pub attributes: HashMap<String, String>,
+    pub attributes: IndexMap<String, String>,
+    /// Text content, such as the label of a `text` element
+    pub text: Option<String>,
 }
 
 /// VHDL content block
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VhdlContent {
     pub name: String,
     pub content: String,
 }
 
 /// Project options and settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectOptions {
     pub canvas: CanvasOptions,
     pub simulation: SimulationOptions,
     pub toolbar: ToolbarOptions,
+    /// Tools bound to mouse buttons
+    pub mappings: Vec<MouseMapping>,
+    /// Toolbar contents, where `None` is a separator
+    pub toolbar_items: Vec<Option<ToolReference>>,
+}
+
+impl Default for ProjectOptions {
+    fn default() -> Self {
+        Self {
+            canvas: CanvasOptions {
+                printer_view: false,
+                gate_undefined: "ignore".to_string(),
+                simulation_icons: true,
+            },
+            simulation: SimulationOptions {
+                sim_limit: 1000,
+                sim_rand: 0,
+            },
+            toolbar: ToolbarOptions {
+                zoom_enabled: true,
+                show_zoom: true,
+            },
+            mappings: Vec::new(),
+            toolbar_items: Vec::new(),
+        }
+    }
 }
 
 /// Canvas display options
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CanvasOptions {
     pub printer_view: bool,
     pub gate_undefined: String,
@@ -172,19 +280,35 @@ pub struct CanvasOptions {
 }
 
 /// Simulation engine options
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SimulationOptions {
     pub sim_limit: i32,
     pub sim_rand: i32,
 }
 
 /// Toolbar configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolbarOptions {
     pub zoom_enabled: bool,
     pub show_zoom: bool,
 }
 
+/// A library tool as referenced from the toolbar or a mouse mapping
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolReference {
+    /// Library index, or `None` for the built-in tools of the project
+    pub library: Option<String>,
+    pub name: String,
+    pub attributes: IndexMap<String, String>,
+}
+
+/// A tool bound to a mouse button, such as `Button2` or `Ctrl Button1`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MouseMapping {
+    pub map: String,
+    pub tool: ToolReference,
+}
+
 /// ROM memory contents parser
 #[derive(Debug, Clone)]
 pub struct RomContents {
@@ -290,6 +414,31 @@ impl RomContents {
 
         result
     }
+
+    /// Take the contents of a memory, such as an image loaded by
+    /// [`mem_image`](crate::std::memory::mem_image), up to its last
+    /// non-zero word
+    pub fn from_mem_contents(contents: &MemContents) -> Self {
+        let words = contents.nonzero_words();
+        let length = words.last().map_or(0, |&(address, _)| address + 1);
+        RomContents {
+            addr_width: contents.get_log_length() as u32,
+            data_width: contents.get_width() as u32,
+            data: (0..length)
+                .map(|address| contents.get(address) as u64)
+                .collect(),
+        }
+    }
+
+    /// Build the memory these contents describe
+    pub fn to_mem_contents(&self) -> MemContents {
+        let mut contents =
+            MemContents::create(self.addr_width as i32, self.data_width as i32, false);
+        for (address, &value) in self.data.iter().enumerate() {
+            contents.set(address as i64, value as i64);
+        }
+        contents
+    }
 }
 
 impl std::fmt::Display for RomContents {
@@ -303,24 +452,75 @@ pub struct CircParser;
 
 impl CircParser {
     /// Load a .circ file from a path
+    ///
+    /// The `file#` libraries it uses are loaded as well, relative to the
+    /// file's directory, into [`CircuitFile::external_circuits`]. A missing
+    /// library file is a [`CircFormatError::LibraryNotFound`], and libraries
+    /// that end up using themselves a [`CircFormatError::CircularReference`].
     pub fn load_file<P: AsRef<Path>>(path: P) -> CircResult<CircuitFile> {
-        let path = path.as_ref();
+        Self::load_file_from(path.as_ref(), &mut Vec::new())
+    }
+
+    /// Load a .circ file while the files in `loading` are being loaded,
+    /// outermost first
+    fn load_file_from(path: &Path, loading: &mut Vec<PathBuf>) -> CircResult<CircuitFile> {
         let file = File::open(path)?;
+        let canonical = path.canonicalize()?;
+        if let Some(start) = loading.iter().position(|loaded| *loaded == canonical) {
+            let chain: Vec<String> = loading[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|loaded| {
+                    let name = loaded.file_name().unwrap_or(loaded.as_os_str());
+                    name.to_string_lossy().into_owned()
+                })
+                .collect();
+            return Err(CircFormatError::CircularReference(chain.join(" -> ")));
+        }
+
         let mut reader = BufReader::new(file);
         let mut contents = String::new();
         reader.read_to_string(&mut contents)?;
-        Self::parse_string_with_path(&contents, Some(path))
+        loading.push(canonical);
+        let result = Self::parse_string_with_path(&contents, Some(path), loading);
+        loading.pop();
+        result
+    }
+
+    /// Parse a .circ file from a reader
+    pub fn parse<R: Read>(mut reader: R) -> CircResult<CircuitFile> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents)?;
+        Self::parse_string(&contents)
     }
 
     /// Parse a .circ file from a string
+    ///
+    /// With no path to resolve them against, `file#` libraries are left
+    /// unloaded; use [`CircParser::load_file`] for files that have them.
     pub fn parse_string(xml_content: &str) -> CircResult<CircuitFile> {
-        Self::parse_string_with_path(xml_content, None)
+        Self::parse_string_with_path(xml_content, None, &mut Vec::new())
+    }
+
+    /// Check a .circ file without loading it
+    ///
+    /// Returns every problem [`circ_validation`] finds, each with its line
+    /// and column; only text that is not well-formed XML is an error.
+    pub fn validate_string(xml_content: &str) -> CircResult<Vec<ValidationIssue>> {
+        let doc = roxmltree::Document::parse(xml_content)?;
+        Ok(circ_validation::validate(&doc))
+    }
+
+    /// Check a .circ file on disk without loading it
+    pub fn validate_file<P: AsRef<Path>>(path: P) -> CircResult<Vec<ValidationIssue>> {
+        Self::validate_string(&std::fs::read_to_string(path)?)
     }
 
     /// Parse a .circ file from a string with optional path context for loading external files
     fn parse_string_with_path(
         xml_content: &str,
         doc_path: Option<&Path>,
+        loading: &mut Vec<PathBuf>,
     ) -> CircResult<CircuitFile> {
         let doc = roxmltree::Document::parse(xml_content)?;
         let root = doc.root_element();
@@ -331,6 +531,11 @@ impl CircParser {
             ));
         }
 
+        let issues = circ_validation::validate(&doc);
+        if !issues.is_empty() {
+            return Err(CircFormatError::Validation(issues));
+        }
+
         let source_version = root.attribute("source").unwrap_or("unknown").to_string();
         let version = root.attribute("version").unwrap_or("1.0").to_string();
 
@@ -348,7 +553,7 @@ impl CircParser {
             .map(String::from);
 
         // Parse circuits
-        let mut circuits = HashMap::new();
+        let mut circuits = IndexMap::new();
         for circuit_node in root.children().filter(|n| n.tag_name().name() == "circuit") {
             let circuit = Self::parse_circuit(circuit_node)?;
             circuits.insert(circuit.name.clone(), circuit);
@@ -363,37 +568,30 @@ impl CircParser {
         // Parse options (simplified for now)
         let options = Self::parse_options(&root)?;
 
-        // Load external circuit files for file-based libraries
+        // Load external circuit files for file-based libraries, relative to
+        // the current file's directory
         let mut external_circuits = HashMap::new();
-        for library in &libraries {
-            if let Some(external_file) = &library.external_file {
-                // Try to load the external circuit file relative to the current file's directory
-                if let Some(parent_dir) = doc_path.and_then(|p| p.parent()) {
-                    let external_path = parent_dir.join(external_file);
-                    if external_path.exists() {
-                        match Self::load_file(&external_path) {
-                            Ok(external_circuit_file) => {
-                                external_circuits
-                                    .insert(library.name.clone(), external_circuit_file);
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    "Warning: Failed to load external circuit file {}: {}",
-                                    external_file, e
-                                );
-                            }
-                        }
-                    } else {
-                        eprintln!(
-                            "Warning: External circuit file not found: {}",
-                            external_path.display()
-                        );
-                    }
+        if let Some(doc_path) = doc_path {
+            let dir = doc_path.parent().unwrap_or(Path::new(""));
+            for library in &libraries {
+                let Some(external_file) = &library.external_file else {
+                    continue;
+                };
+                let external_path = dir.join(external_file);
+                if !external_path.is_file() {
+                    return Err(CircFormatError::LibraryNotFound(format!(
+                        "{} used by {} (looked for {})",
+                        library.description,
+                        doc_path.display(),
+                        external_path.display()
+                    )));
                 }
+                let external_circuit_file = Self::load_file_from(&external_path, loading)?;
+                external_circuits.insert(library.name.clone(), external_circuit_file);
             }
         }
 
-        Ok(CircuitFile {
+        let mut circuit_file = CircuitFile {
             source_version,
             version,
             libraries,
@@ -402,7 +600,10 @@ impl CircParser {
             vhdl_contents,
             options,
             external_circuits,
-        })
+            migrations: Vec::new(),
+        };
+        circuit_file.migrations = circ_migration::migrate(&mut circuit_file);
+        Ok(circuit_file)
     }
 
     fn parse_library(lib_node: roxmltree::Node) -> CircResult<LibraryConfig> {
@@ -434,19 +635,25 @@ impl CircParser {
 
     fn parse_tool(tool_node: roxmltree::Node) -> CircResult<ToolConfig> {
         let name = tool_node.attribute("name").unwrap_or("unknown").to_string();
-
-        let mut attributes = HashMap::new();
-        for attr_node in tool_node.children().filter(|n| n.tag_name().name() == "a") {
-            if let (Some(attr_name), Some(attr_value)) =
-                (attr_node.attribute("name"), attr_node.attribute("val"))
-            {
-                attributes.insert(attr_name.to_string(), attr_value.to_string());
-            }
-        }
+        let attributes = Self::parse_attributes(tool_node);
 
         Ok(ToolConfig { name, attributes })
     }
 
+    /// Parse the `<a>` children of a node, in file order
+    ///
+    /// Values that span several lines, such as memory contents, are stored
+    /// as the text of the element instead of in its `val` attribute.
+    fn parse_attributes(node: roxmltree::Node) -> IndexMap<String, String> {
+        node.children()
+            .filter(|n| n.tag_name().name() == "a")
+            .filter_map(|attr_node| {
+                let value = attr_node.attribute("val").or_else(|| attr_node.text())?;
+                Some((attr_node.attribute("name")?.to_string(), value.to_string()))
+            })
+            .collect()
+    }
+
     fn parse_circuit(circuit_node: roxmltree::Node) -> CircResult<CircuitDefinition> {
         let name = circuit_node
             .attribute("name")
@@ -454,17 +661,7 @@ impl CircParser {
             .to_string();
 
         // Parse circuit attributes
-        let mut attributes = HashMap::new();
-        for attr_node in circuit_node
-            .children()
-            .filter(|n| n.tag_name().name() == "a")
-        {
-            if let (Some(attr_name), Some(attr_value)) =
-                (attr_node.attribute("name"), attr_node.attribute("val"))
-            {
-                attributes.insert(attr_name.to_string(), attr_value.to_string());
-            }
-        }
+        let attributes = Self::parse_attributes(circuit_node);
 
         // Parse components
         let mut components = Vec::new();
@@ -484,13 +681,24 @@ impl CircParser {
             wires.push(Self::parse_wire(wire_node)?);
         }
 
-        // Parse appearance (simplified)
+        // Parse appearance
         let appearance = circuit_node
             .children()
             .find(|n| n.tag_name().name() == "appear")
-            .map(|_| CircuitAppearance {
+            .map(|appear_node| CircuitAppearance {
                 custom: true,
-                elements: Vec::new(), // TODO: Parse appearance elements
+                elements: appear_node
+                    .children()
+                    .filter(|n| n.is_element())
+                    .map(|element_node| AppearanceElement {
+                        element_type: element_node.tag_name().name().to_string(),
+                        attributes: element_node
+                            .attributes()
+                            .map(|a| (a.name().to_string(), a.value().to_string()))
+                            .collect(),
+                        text: element_node.text().map(String::from),
+                    })
+                    .collect(),
             });
 
         Ok(CircuitDefinition {
@@ -515,27 +723,8 @@ impl CircParser {
             .ok_or_else(|| CircFormatError::MissingAttribute("component location".to_string()))?;
         let location = Self::parse_location(loc_str)?;
 
-        // Parse component attributes
-        let mut attributes = HashMap::new();
-        for attr_node in comp_node.children().filter(|n| n.tag_name().name() == "a") {
-            if let (Some(attr_name), Some(attr_value)) =
-                (attr_node.attribute("name"), attr_node.attribute("val"))
-            {
-                attributes.insert(attr_name.to_string(), attr_value.to_string());
-            }
-        }
-
-        // Special handling for ROM contents
-        if name == "ROM" {
-            // Look for contents in text content of the node or in child nodes
-            for child in comp_node.children() {
-                if child.tag_name().name() == "a" && child.attribute("name") == Some("contents") {
-                    if let Some(contents_text) = child.text() {
-                        attributes.insert("contents".to_string(), contents_text.to_string());
-                    }
-                }
-            }
-        }
+        // Parse component attributes, including ROM contents
+        let attributes = Self::parse_attributes(comp_node);
 
         let facing = comp_node.attribute("facing").map(String::from);
 
@@ -562,7 +751,7 @@ impl CircParser {
         Ok(WireConnection { from, to })
     }
 
-    fn parse_location(loc_str: &str) -> CircResult<(i32, i32)> {
+    pub(crate) fn parse_location(loc_str: &str) -> CircResult<(i32, i32)> {
         // Format: "(x,y)"
         let trimmed = loc_str.trim_start_matches('(').trim_end_matches(')');
         let parts: Vec<&str> = trimmed.split(',').collect();
@@ -590,24 +779,66 @@ impl CircParser {
         Ok(VhdlContent { name, content })
     }
 
-    fn parse_options(_root: &roxmltree::Node) -> CircResult<ProjectOptions> {
-        // Simplified options parsing - return defaults for now
+    fn parse_options(root: &roxmltree::Node) -> CircResult<ProjectOptions> {
+        let child = |name: &str| root.children().find(|n| n.tag_name().name() == name);
+        let options = child("options")
+            .map(Self::parse_attributes)
+            .unwrap_or_default();
+        let option = |name: &str, default: i32| -> CircResult<i32> {
+            match options.get(name) {
+                Some(value) => value.trim().parse().map_err(|_| {
+                    CircFormatError::InvalidAttributeValue(format!("{} '{}'", name, value))
+                }),
+                None => Ok(default),
+            }
+        };
+
+        let tools = |name: &str| {
+            child(name)
+                .into_iter()
+                .flat_map(|node| node.children().filter(|n| n.is_element()))
+        };
+        let mappings = tools("mappings")
+            .filter(|n| n.tag_name().name() == "tool")
+            .map(|tool_node| MouseMapping {
+                map: tool_node.attribute("map").unwrap_or_default().to_string(),
+                tool: Self::parse_tool_reference(tool_node),
+            })
+            .collect();
+        let toolbar_items = tools("toolbar")
+            .filter_map(|item| match item.tag_name().name() {
+                "tool" => Some(Some(Self::parse_tool_reference(item))),
+                "sep" => Some(None),
+                _ => None,
+            })
+            .collect();
+
+        let defaults = ProjectOptions::default();
         Ok(ProjectOptions {
             canvas: CanvasOptions {
-                printer_view: false,
-                gate_undefined: "ignore".to_string(),
-                simulation_icons: true,
+                gate_undefined: options
+                    .get("gateUndefined")
+                    .cloned()
+                    .unwrap_or(defaults.canvas.gate_undefined),
+                ..defaults.canvas
             },
             simulation: SimulationOptions {
-                sim_limit: 1000,
-                sim_rand: 0,
-            },
-            toolbar: ToolbarOptions {
-                zoom_enabled: true,
-                show_zoom: true,
+                sim_limit: option("simlimit", defaults.simulation.sim_limit)?,
+                sim_rand: option("simrand", defaults.simulation.sim_rand)?,
             },
+            toolbar: defaults.toolbar,
+            mappings,
+            toolbar_items,
         })
     }
+
+    fn parse_tool_reference(tool_node: roxmltree::Node) -> ToolReference {
+        ToolReference {
+            library: tool_node.attribute("lib").map(String::from),
+            name: tool_node.attribute("name").unwrap_or_default().to_string(),
+            attributes: Self::parse_attributes(tool_node),
+        }
+    }
 }
 
 /// Circuit file serializer/writer
@@ -617,7 +848,11 @@ impl CircWriter {
     /// Save a circuit file to a path
     pub fn save_file<P: AsRef<Path>>(circuit_file: &CircuitFile, path: P) -> CircResult<()> {
         let file = File::create(path)?;
-        let mut writer = BufWriter::new(file);
+        Self::write(circuit_file, BufWriter::new(file))
+    }
+
+    /// Write a circuit file to a writer
+    pub fn write<W: Write>(circuit_file: &CircuitFile, mut writer: W) -> CircResult<()> {
         let xml_content = Self::serialize_to_string(circuit_file)?;
         writer.write_all(xml_content.as_bytes())?;
         writer.flush()?;
@@ -625,12 +860,16 @@ impl CircWriter {
     }
 
     /// Serialize a circuit file to XML string
+    ///
+    /// Everything the parser reads is written back, in the same order, so
+    /// that parsing the result gives an identical `CircuitFile`.
     pub fn serialize_to_string(circuit_file: &CircuitFile) -> CircResult<String> {
         let mut xml = String::new();
         xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
         xml.push_str(&format!(
             "<project source=\"{}\" version=\"{}\">\n",
-            circuit_file.source_version, circuit_file.version
+            escape(&circuit_file.source_version),
+            escape(&circuit_file.version)
         ));
 
         xml.push_str("  This file is intended to be loaded by Logisim-evolution.\n\n");
@@ -642,10 +881,10 @@ impl CircWriter {
 
         // Write main circuit reference
         if let Some(main) = &circuit_file.main_circuit {
-            xml.push_str(&format!("  <main name=\"{}\"/>\n", main));
+            xml.push_str(&format!("  <main name=\"{}\"/>\n", escape(main)));
         }
 
-        // Write options (simplified)
+        // Write options, mouse mappings and toolbar
         Self::write_options(&mut xml, &circuit_file.options);
 
         // Write circuits
@@ -662,51 +901,112 @@ impl CircWriter {
         Ok(xml)
     }
 
-    fn write_library(xml: &mut String, lib: &LibraryConfig) {
-        xml.push_str(&format!(
-            "  <lib desc=\"{}\" name=\"{}\">\n",
-            lib.description, lib.name
-        ));
-
-        for tool in &lib.tools {
-            xml.push_str(&format!("    <tool name=\"{}\">\n", tool.name));
-            for (attr_name, attr_value) in &tool.attributes {
+    /// Write `<a>` elements, one per line at the given indentation
+    fn write_attributes(xml: &mut String, indent: &str, attributes: &IndexMap<String, String>) {
+        for (attr_name, attr_value) in attributes {
+            if attr_value.contains('\n') {
+                // Multi-line values such as ROM contents go in the element text
                 xml.push_str(&format!(
-                    "      <a name=\"{}\" val=\"{}\"/>\n",
-                    attr_name, attr_value
+                    "{}<a name=\"{}\">{}</a>\n",
+                    indent,
+                    escape(attr_name),
+                    escape(attr_value)
+                ));
+            } else {
+                xml.push_str(&format!(
+                    "{}<a name=\"{}\" val=\"{}\"/>\n",
+                    indent,
+                    escape(attr_name),
+                    escape(attr_value)
                 ));
             }
+        }
+    }
+
+    /// Write a `<tool>` element of the toolbar or mouse mappings
+    fn write_tool_reference(xml: &mut String, tool: &ToolReference, map: Option<&str>) {
+        xml.push_str("    <tool");
+        if let Some(library) = &tool.library {
+            xml.push_str(&format!(" lib=\"{}\"", escape(library)));
+        }
+        if let Some(map) = map {
+            xml.push_str(&format!(" map=\"{}\"", escape(map)));
+        }
+        xml.push_str(&format!(" name=\"{}\"", escape(&tool.name)));
+        if tool.attributes.is_empty() {
+            xml.push_str("/>\n");
+        } else {
+            xml.push_str(">\n");
+            Self::write_attributes(xml, "      ", &tool.attributes);
             xml.push_str("    </tool>\n");
         }
+    }
 
+    fn write_library(xml: &mut String, lib: &LibraryConfig) {
+        let open = format!(
+            "  <lib desc=\"{}\" name=\"{}\"",
+            escape(&lib.description),
+            escape(&lib.name)
+        );
+        if lib.tools.is_empty() {
+            xml.push_str(&open);
+            xml.push_str("/>\n");
+            return;
+        }
+
+        xml.push_str(&open);
+        xml.push_str(">\n");
+        for tool in &lib.tools {
+            xml.push_str(&format!("    <tool name=\"{}\">\n", escape(&tool.name)));
+            Self::write_attributes(xml, "      ", &tool.attributes);
+            xml.push_str("    </tool>\n");
+        }
         xml.push_str("  </lib>\n");
     }
 
-    fn write_options(xml: &mut String, _options: &ProjectOptions) {
-        // Simplified options writing - just add placeholder
+    fn write_options(xml: &mut String, options: &ProjectOptions) {
         xml.push_str("  <options>\n");
-        xml.push_str("    <a name=\"gateUndefined\" val=\"ignore\"/>\n");
-        xml.push_str("    <a name=\"simlimit\" val=\"1000\"/>\n");
-        xml.push_str("    <a name=\"simrand\" val=\"0\"/>\n");
+        let values = [
+            ("gateUndefined", options.canvas.gate_undefined.clone()),
+            ("simlimit", options.simulation.sim_limit.to_string()),
+            ("simrand", options.simulation.sim_rand.to_string()),
+        ];
+        let values = values
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        Self::write_attributes(xml, "    ", &values);
         xml.push_str("  </options>\n");
+
+        xml.push_str("  <mappings>\n");
+        for mapping in &options.mappings {
+            Self::write_tool_reference(xml, &mapping.tool, Some(&mapping.map));
+        }
+        xml.push_str("  </mappings>\n");
+
+        xml.push_str("  <toolbar>\n");
+        for item in &options.toolbar_items {
+            match item {
+                Some(tool) => Self::write_tool_reference(xml, tool, None),
+                None => xml.push_str("    <sep/>\n"),
+            }
+        }
+        xml.push_str("  </toolbar>\n");
     }
 
     fn write_circuit(xml: &mut String, circuit: &CircuitDefinition) {
-        xml.push_str(&format!("  <circuit name=\"{}\">\n", circuit.name));
+        xml.push_str(&format!("  <circuit name=\"{}\">\n", escape(&circuit.name)));
 
         // Write circuit attributes
-        for (attr_name, attr_value) in &circuit.attributes {
-            xml.push_str(&format!(
-                "    <a name=\"{}\" val=\"{}\"/>\n",
-                attr_name, attr_value
-            ));
-        }
+        Self::write_attributes(xml, "    ", &circuit.attributes);
 
         // Write appearance if custom
         if let Some(appearance) = &circuit.appearance {
             if appearance.custom {
                 xml.push_str("    <appear>\n");
-                // TODO: Write appearance elements
+                for element in &appearance.elements {
+                    Self::write_appearance_element(xml, element);
+                }
                 xml.push_str("    </appear>\n");
             }
         }
@@ -727,30 +1027,40 @@ impl CircWriter {
         xml.push_str("  </circuit>\n");
     }
 
+    fn write_appearance_element(xml: &mut String, element: &AppearanceElement) {
+        xml.push_str(&format!("      <{}", element.element_type));
+        for (attr_name, attr_value) in &element.attributes {
+            xml.push_str(&format!(" {}=\"{}\"", attr_name, escape(attr_value)));
+        }
+        match &element.text {
+            Some(text) => xml.push_str(&format!(">{}</{}>\n", escape(text), element.element_type)),
+            None => xml.push_str("/>\n"),
+        }
+    }
+
     fn write_component(xml: &mut String, comp: &ComponentInstance) {
         let lib_attr = comp
             .library
             .as_ref()
-            .map(|lib| format!(" lib=\"{}\"", lib))
+            .map(|lib| format!(" lib=\"{}\"", escape(lib)))
+            .unwrap_or_default();
+        let facing_attr = comp
+            .facing
+            .as_ref()
+            .map(|facing| format!(" facing=\"{}\"", escape(facing)))
             .unwrap_or_default();
 
         xml.push_str(&format!(
-            "    <comp{} loc=\"({},{})\" name=\"{}\">\n",
-            lib_attr, comp.location.0, comp.location.1, comp.name
+            "    <comp{}{} loc=\"({},{})\" name=\"{}\">\n",
+            lib_attr,
+            facing_attr,
+            comp.location.0,
+            comp.location.1,
+            escape(&comp.name)
         ));
 
-        // Write component attributes
-        for (attr_name, attr_value) in &comp.attributes {
-            if attr_name == "contents" && comp.name == "ROM" {
-                // Special handling for ROM contents - write as text content
-                xml.push_str(&format!("      <a name=\"contents\">{}</a>\n", attr_value));
-            } else {
-                xml.push_str(&format!(
-                    "      <a name=\"{}\" val=\"{}\"/>\n",
-                    attr_name, attr_value
-                ));
-            }
-        }
+        // Write component attributes, ROM contents included
+        Self::write_attributes(xml, "      ", &comp.attributes);
 
         xml.push_str("    </comp>\n");
     }
@@ -758,11 +1068,27 @@ impl CircWriter {
     fn write_vhdl(xml: &mut String, vhdl: &VhdlContent) {
         xml.push_str(&format!(
             "  <vhdl name=\"{}\">{}</vhdl>\n",
-            vhdl.name, vhdl.content
+            escape(&vhdl.name),
+            escape(&vhdl.content)
         ));
     }
 }
 
+/// Escape text for use in XML content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 /// Integration functions to convert between .circ format and simulation structures
 pub struct CircIntegration;
 
@@ -775,6 +1101,19 @@ impl CircIntegration {
 
     /// Convert a CircuitFile to a Simulation
     pub fn circuit_file_to_simulation(circuit_file: &CircuitFile) -> CircResult<Simulation> {
+        Self::circuit_file_to_simulation_with_registry(circuit_file, &ComponentRegistry::standard())
+    }
+
+    /// Convert a CircuitFile to a Simulation, building its components with
+    /// the factories in `registry`
+    ///
+    /// Components the registry cannot build are collected over the whole
+    /// hierarchy and reported together as
+    /// [`CircFormatError::UnsupportedComponents`].
+    pub fn circuit_file_to_simulation_with_registry(
+        circuit_file: &CircuitFile,
+        registry: &ComponentRegistry,
+    ) -> CircResult<Simulation> {
         let mut sim = Simulation::new();
 
         // Find the main circuit
@@ -794,151 +1133,629 @@ impl CircIntegration {
                 ))
             })?;
 
-        // Build the simulation from the main circuit
-        Self::build_circuit_in_simulation(&mut sim, main_circuit, circuit_file)?;
+        // Build the simulation from the main circuit, expanding subcircuits.
+        // Unsupported components are skipped so that all of them are found,
+        // and take precedence over any error that skipping them caused.
+        let mut builder = HierarchyBuilder::new(registry);
+        let main_circuit = ScopedCircuit {
+            file: circuit_file,
+            circuit: main_circuit,
+            qualified_name: main_circuit.name.clone(),
+        };
+        let result = builder.build(&mut sim, &main_circuit, "");
+        if !builder.unsupported.is_empty() {
+            return Err(CircFormatError::UnsupportedComponents(builder.unsupported));
+        }
+        result?;
 
+        sim.set_circuit_source(CircuitSource {
+            circuit_file: circuit_file.clone(),
+            components: builder.origins,
+            tools: builder.tools,
+        });
         Ok(sim)
     }
 
-    fn build_circuit_in_simulation(
-        sim: &mut Simulation,
-        circuit: &CircuitDefinition,
-        _circuit_file: &CircuitFile,
-    ) -> CircResult<()> {
-        // Components will be looked up dynamically based on name
-        use crate::signal::BusWidth;
+    /// Extract a Simulation back to a CircuitFile
+    ///
+    /// The simulation must have been loaded from a .circ file. That file is
+    /// returned with wires, appearances, library declarations and every
+    /// setting the simulation does not model untouched, and with the
+    /// changes made to the simulation since: removed components are
+    /// dropped, the attributes a component now reports differently from
+    /// when it was built, such as the contents of a RAM after it has been
+    /// written, are updated, and components added to the simulation are
+    /// placed in the main circuit. A component of a subcircuit stays while
+    /// any instance of it is still simulated, and takes its attributes from
+    /// the first one. Circuits of `file#` libraries belong to their own
+    /// files and are left as they are, while the library declarations keep
+    /// the dependency on them.
+    ///
+    /// Instance delays are not saved, as Logisim-Evolution has no attribute
+    /// for them.
+    pub fn simulation_to_circuit_file(sim: &Simulation) -> CircResult<CircuitFile> {
+        let source = sim.circuit_source().ok_or_else(|| {
+            CircFormatError::InvalidFormat(
+                "The simulation was not loaded from a .circ file".to_string(),
+            )
+        })?;
 
-        // Create a mapping from locations to node IDs for wire connections
-        let mut location_to_node: HashMap<(i32, i32), NodeId> = HashMap::new();
+        // Components added after loading, by the tool they are saved as
+        let mut added: Vec<ComponentId> = sim
+            .get_all_component_ids()
+            .into_iter()
+            .filter(|id| !source.components.contains_key(id))
+            .collect();
+        added.sort();
+        let (added, unsaved): (Vec<_>, Vec<_>) = added
+            .into_iter()
+            .map(|id| (id, sim.get_component(id).and_then(|c| c.circ_tool())))
+            .partition(|(_, tool)| tool.is_some());
+        if !unsaved.is_empty() {
+            let ids: Vec<String> = unsaved.iter().map(|(id, _)| id.to_string()).collect();
+            return Err(CircFormatError::InvalidFormat(format!(
+                "Components {} have no .circ counterpart",
+                ids.join(", ")
+            )));
+        }
 
-        // First pass: Create components and identify connection points
-        let mut component_ids = Vec::new();
-        for comp_instance in &circuit.components {
-            let component_id = ComponentId((component_ids.len() as u32 + 1).into());
-
-            // Create appropriate component based on name
-            let component: Box<dyn Component> = match comp_instance.name.as_str() {
-                "AND Gate" => Box::new(AndGate::new(component_id)),
-                "OR Gate" => Box::new(OrGate::new(component_id)),
-                "NOT Gate" => Box::new(NotGate::new(component_id)),
-                "NAND Gate" => Box::new(NandGate::new(component_id)),
-                "NOR Gate" => Box::new(NorGate::new(component_id)),
-                "XOR Gate" => Box::new(XorGate::new(component_id)),
-                "XNOR Gate" => Box::new(XnorGate::new(component_id)),
-                "Pin" => {
-                    // Create a basic pin component from std::wiring
-                    Box::new(Pin::new(component_id))
-                }
-                "Constant" => {
-                    // Create a basic constant from std::wiring
-                    Box::new(Constant::new(component_id))
+        // The instances of every simulated component still in the simulation
+        let mut instances: HashMap<(&str, usize), Vec<ComponentId>> = HashMap::new();
+        for (&id, (circuit, index)) in &source.components {
+            let live = instances.entry((circuit.as_str(), *index)).or_default();
+            if sim.get_component(id).is_some() {
+                live.push(id);
+            }
+        }
+
+        let mut circuit_file = source.circuit_file.clone();
+        for circuit in circuit_file.circuits.values_mut() {
+            let mut index = 0;
+            circuit.components.retain_mut(|comp_instance| {
+                let origin = (circuit.name.as_str(), index);
+                index += 1;
+                let Some(live) = instances.get(&origin) else {
+                    // Tunnels, splitters, subcircuit instances and their pins
+                    // are not simulated as components of their own
+                    return true;
+                };
+                let Some(first) = live.iter().min() else {
+                    return false;
+                };
+                let built = source.tools.get(first);
+                let current = sim.get_component(*first).and_then(|c| c.circ_tool());
+                if let (Some(built), Some(current)) = (built, current) {
+                    update_attributes(comp_instance, built, &current);
                 }
-                // For now, return an error for unsupported component types
-                _ => {
-                    return Err(CircFormatError::UnsupportedComponent(format!(
-                        "Component type '{}' is not yet supported in the Rust implementation",
-                        comp_instance.name
-                    )));
+                true
+            });
+        }
+
+        let tools = added.into_iter().filter_map(|(_, tool)| tool).collect();
+        place_components(&mut circuit_file, tools)?;
+        Ok(circuit_file)
+    }
+}
+
+/// Bring the attributes of a placed component up to date, changing only
+/// those its simulated component reports differently from when it was built
+fn update_attributes(comp_instance: &mut ComponentInstance, built: &CircTool, current: &CircTool) {
+    for (name, _) in &built.attributes {
+        if current.attribute(name).is_none() {
+            comp_instance.attributes.shift_remove(name);
+        }
+    }
+    for (name, value) in &current.attributes {
+        if built.attribute(name) != Some(value.as_str()) {
+            comp_instance.attributes.insert(name.clone(), value.clone());
+        }
+    }
+}
+
+/// Place components that were not loaded from a file in its main circuit
+///
+/// They go side by side below everything already drawn, so that their ports
+/// touch neither the drawing nor each other; they are left unconnected.
+/// Libraries the file does not declare yet are added.
+fn place_components(circuit_file: &mut CircuitFile, tools: Vec<CircTool>) -> CircResult<()> {
+    if tools.is_empty() {
+        return Ok(());
+    }
+    let main_circuit_name = circuit_file
+        .main_circuit
+        .clone()
+        .or_else(|| circuit_file.circuits.keys().next().cloned())
+        .ok_or_else(|| CircFormatError::InvalidFormat("No circuits found".to_string()))?;
+
+    let mut placed = Vec::with_capacity(tools.len());
+    for tool in tools {
+        let library = match circuit_file
+            .libraries
+            .iter()
+            .find(|library| library.description == tool.library)
+        {
+            Some(library) => library.name.clone(),
+            None => {
+                let name = circuit_file
+                    .libraries
+                    .iter()
+                    .filter_map(|library| library.name.parse::<u32>().ok())
+                    .max()
+                    .map_or(0, |index| index + 1)
+                    .to_string();
+                circuit_file.libraries.push(LibraryConfig {
+                    name: name.clone(),
+                    description: tool.library.clone(),
+                    tools: Vec::new(),
+                    external_file: None,
+                });
+                name
+            }
+        };
+        placed.push(ComponentInstance {
+            library: Some(library),
+            name: tool.name,
+            location: (0, 0),
+            attributes: tool.attributes.into_iter().collect(),
+            facing: None,
+        });
+    }
+
+    let circuit = circuit_file
+        .circuits
+        .get_mut(&main_circuit_name)
+        .ok_or_else(|| {
+            CircFormatError::InvalidFormat(format!(
+                "Main circuit '{}' not found",
+                main_circuit_name
+            ))
+        })?;
+    // Every point drawn so far, leaving room below subcircuit instances
+    // and labels whose ports are not worked out here
+    let mut points: Vec<(i32, i32)> = circuit
+        .wires
+        .iter()
+        .flat_map(|wire| [wire.from, wire.to])
+        .collect();
+    for comp_instance in &circuit.components {
+        points.push(comp_instance.location);
+        if let Ok(ports) = component_ports(comp_instance) {
+            points.extend(ports.iter().map(|port| port.location));
+        }
+    }
+    let mut left = points.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let top = points.iter().map(|&(_, y)| y).max().unwrap_or(0) + 100;
+
+    for mut comp_instance in placed {
+        // The extent of the ports around the component's location
+        let ports = component_ports(&comp_instance)?;
+        let (mut min_x, mut max_x, mut min_y) = (0, 0, 0);
+        for port in &ports {
+            min_x = min_x.min(port.location.0);
+            max_x = max_x.max(port.location.0);
+            min_y = min_y.min(port.location.1);
+        }
+        comp_instance.location = (left - min_x, top - min_y);
+        left += max_x - min_x + 50;
+        circuit.components.push(comp_instance);
+    }
+    Ok(())
+}
+
+/// The .circ file a simulation was built from, kept so that the simulation
+/// can be written back with
+/// [`CircIntegration::simulation_to_circuit_file`]
+#[derive(Debug, Clone)]
+pub struct CircuitSource {
+    /// The file as it was loaded
+    pub circuit_file: CircuitFile,
+    /// Circuit and index in its component list of every component built
+    /// from the file; subcircuit components have one entry per instance,
+    /// and circuits of `file#` libraries are named after their file, as in
+    /// `alu.circ:ALU`
+    pub components: HashMap<ComponentId, (String, usize)>,
+    /// Library tool every component built from the file reported when it
+    /// was built, telling the settings changed since apart from those the
+    /// file already had
+    pub tools: HashMap<ComponentId, CircTool>,
+}
+
+impl CircuitSource {
+    /// Get an attribute a component built from the file has there,
+    /// including those the simulation has no use for
+    pub fn attribute(&self, component_id: ComponentId, name: &str) -> Option<&str> {
+        let (circuit, index) = self.components.get(&component_id)?;
+        let circuit = Self::find_circuit(&self.circuit_file, circuit)?;
+        let comp_instance = circuit.components.get(*index)?;
+        comp_instance.attributes.get(name).map(String::as_str)
+    }
+
+    /// Look up a circuit by the name [`components`](Self::components) gives
+    /// it, in `file` or the `file#` libraries it loads
+    fn find_circuit<'a>(file: &'a CircuitFile, name: &str) -> Option<&'a CircuitDefinition> {
+        if let Some(circuit) = file.circuits.get(name) {
+            return Some(circuit);
+        }
+        let (external_file, circuit) = name.split_once(':')?;
+        file.external_circuits
+            .iter()
+            .find_map(|(lib_name, external)| {
+                let declared = file.libraries.iter().any(|lib| {
+                    lib.name == *lib_name && lib.external_file.as_deref() == Some(external_file)
+                });
+                if declared {
+                    external.circuits.get(circuit)
+                } else {
+                    Self::find_circuit(external, name)
                 }
-            };
+            })
+    }
+}
 
-            let added_id = sim.add_component(component);
-            component_ids.push(added_id);
+/// Flattens a circuit hierarchy into a single simulation
+///
+/// Every subcircuit instance gets its own copy of the child circuit's
+/// components, so instances never share state. The child's pins are not
+/// simulated as components: their nets are bit-linked to the nets at the
+/// instance's ports instead.
+struct HierarchyBuilder<'a> {
+    registry: &'a ComponentRegistry,
+    next_component_id: u64,
+    /// Qualified names of the circuits being expanded, outermost first
+    stack: Vec<String>,
+    /// Components skipped because the registry cannot build them
+    unsupported: Vec<UnsupportedComponent>,
+    /// Circuit and component index each simulated component was built from
+    origins: HashMap<ComponentId, (String, usize)>,
+    /// Library tool each simulated component reported when it was built
+    tools: HashMap<ComponentId, CircTool>,
+}
+
+/// A circuit together with the file it is declared in
+struct ScopedCircuit<'a> {
+    /// File whose libraries the circuit's components refer to
+    file: &'a CircuitFile,
+    circuit: &'a CircuitDefinition,
+    /// Name telling the circuit apart from those of other files, such as
+    /// `ALU` for a circuit of the loaded file or `alu.circ:ALU` for one of
+    /// a `file#alu.circ` library
+    qualified_name: String,
+}
+
+impl<'a> HierarchyBuilder<'a> {
+    fn new(registry: &'a ComponentRegistry) -> Self {
+        Self {
+            registry,
+            next_component_id: 1,
+            stack: Vec::new(),
+            unsupported: Vec::new(),
+            origins: HashMap::new(),
+            tools: HashMap::new(),
+        }
+    }
 
-            // Create nodes for component pins (simplified approach)
-            let comp_location = comp_instance.location;
+    /// Descriptor of the library a component of `file` comes from, such as
+    /// `#Wiring`
+    fn library_of(file: &CircuitFile, comp_instance: &ComponentInstance) -> String {
+        let Some(index) = comp_instance.library.as_deref() else {
+            // Neither a library tool nor a circuit of this file
+            return "(missing circuit)".to_string();
+        };
+        file.libraries
+            .iter()
+            .find(|library| library.name == index)
+            .map_or_else(
+                || format!("lib {}", index),
+                |library| library.description.clone(),
+            )
+    }
 
-            // Create nodes for standard pin locations relative to component
-            // This is a simplified mapping - real Logisim has complex pin layouts
-            let pin_offsets = match comp_instance.name.as_str() {
-                "AND Gate" => vec![(-30, 0), (-30, 10), (30, 0)], // A, B, Y
-                _ => vec![(0, 0)],                                // Default single node
+    /// Record a component that cannot be built, once per placement
+    fn report_unsupported(
+        &mut self,
+        circuit: &str,
+        comp_instance: &ComponentInstance,
+        library: String,
+        reason: Option<String>,
+    ) {
+        let reported = self.unsupported.iter().any(|unsupported| {
+            unsupported.circuit == circuit
+                && unsupported.name == comp_instance.name
+                && unsupported.location == comp_instance.location
+        });
+        if !reported {
+            self.unsupported.push(UnsupportedComponent {
+                circuit: circuit.to_string(),
+                library,
+                name: comp_instance.name.clone(),
+                location: comp_instance.location,
+                reason,
+            });
+        }
+    }
+
+    /// Look up the circuit a component of `file` instantiates, if it is a
+    /// circuit of that file or of one of its `file#` libraries
+    fn subcircuit_of(
+        file: &'a CircuitFile,
+        comp_instance: &ComponentInstance,
+    ) -> Option<ScopedCircuit<'a>> {
+        let Some(index) = comp_instance.library.as_deref() else {
+            let circuit = file.circuits.get(&comp_instance.name)?;
+            return Some(ScopedCircuit {
+                file,
+                circuit,
+                qualified_name: circuit.name.clone(),
+            });
+        };
+        let library = file.libraries.iter().find(|lib| lib.name == index)?;
+        let external_file = library.external_file.as_deref()?;
+        let external = file.external_circuits.get(index)?;
+        let circuit = external.circuits.get(&comp_instance.name)?;
+        Some(ScopedCircuit {
+            file: external,
+            circuit,
+            qualified_name: format!("{}:{}", external_file, circuit.name),
+        })
+    }
+
+    /// Add `circuit` to the simulation, prefixing its node names with `path`
+    ///
+    /// Returns the node behind each of the circuit's pins, keyed by the pin's
+    /// index in `circuit.components`.
+    fn build(
+        &mut self,
+        sim: &mut Simulation,
+        scoped: &ScopedCircuit<'a>,
+        path: &str,
+    ) -> CircResult<HashMap<usize, NodeId>> {
+        let (file, circuit) = (scoped.file, scoped.circuit);
+        let circuit_name = scoped.qualified_name.as_str();
+        if self.stack.iter().any(|name| name == circuit_name) {
+            let mut chain = self.stack.join(" -> ");
+            chain.push_str(" -> ");
+            chain.push_str(circuit_name);
+            return Err(CircFormatError::CircularReference(chain));
+        }
+        self.stack.push(circuit_name.to_string());
+        let top_level = self.stack.len() == 1;
+
+        // First pass: place the ports of every component. Components are
+        // only built once the nets are known, as some take their width from
+        // the net they are attached to.
+        let mut placed = Vec::new();
+        let mut tunnels = Vec::new();
+        let mut instances: Vec<(usize, ScopedCircuit<'a>, Vec<usize>)> = Vec::new();
+        for comp_instance in &circuit.components {
+            let component_id = ComponentId(self.next_component_id);
+            self.next_component_id += 1;
+
+            let (ports, library) = if let Some(child) = Self::subcircuit_of(file, comp_instance) {
+                let (pins, ports) = subcircuit_ports(comp_instance, child.circuit)?
+                    .into_iter()
+                    .unzip();
+                instances.push((placed.len(), child, pins));
+                (ports, None)
+            } else {
+                let library = Self::library_of(file, comp_instance);
+                let ports = if self.registry.contains(&library, &comp_instance.name) {
+                    component_ports(comp_instance)
+                } else if library.starts_with("file#") {
+                    Err(CircFormatError::UnsupportedComponent(
+                        "no such circuit in the loaded library".to_string(),
+                    ))
+                } else {
+                    Err(CircFormatError::UnsupportedComponent(String::new()))
+                };
+                match ports {
+                    // Pins of a subcircuit are driven through the instance ports
+                    Ok(ports) if comp_instance.name == "Pin" && !top_level => (ports, None),
+                    Ok(ports) => (ports, Some(library)),
+                    Err(CircFormatError::UnsupportedComponent(reason)) => {
+                        // Skipped, so that every other one is found as well
+                        let reason = Some(reason).filter(|reason| !reason.is_empty());
+                        self.report_unsupported(circuit_name, comp_instance, library, reason);
+                        (Vec::new(), None)
+                    }
+                    Err(e) => return Err(e),
+                }
             };
 
-            for (dx, dy) in pin_offsets.into_iter() {
-                let pin_location = (comp_location.0 + dx, comp_location.1 + dy);
-                location_to_node.entry(pin_location).or_insert_with(|| {
-                    let node = sim.netlist_mut().create_named_node(
-                        BusWidth(1),
-                        format!("node_{}_{}", pin_location.0, pin_location.1),
-                    );
-                    node
-                });
+            if comp_instance.name == "Tunnel" {
+                let label = attr(comp_instance, "label").unwrap_or_default();
+                tunnels.push((label.to_string(), comp_instance.location));
             }
+            placed.push((comp_instance, component_id, library, ports));
         }
 
-        // Second pass: Create nodes for wire endpoints
-        for wire in &circuit.wires {
-            for &location in &[wire.from, wire.to] {
-                location_to_node.entry(location).or_insert_with(|| {
-                    let node = sim.netlist_mut().create_named_node(
-                        BusWidth(1),
-                        format!("wire_{}_{}", location.0, location.1),
-                    );
-                    node
-                });
+        // Second pass: merge wires, junctions and tunnels into nets
+        let nets = CircuitNets::extract(
+            &circuit.wires,
+            placed
+                .iter()
+                .flat_map(|(_, _, _, ports)| ports.iter().map(|port| port.location)),
+            &tunnels,
+        );
+
+        // Third pass: create one node per net that reaches a port, named
+        // after the pin or tunnel on it when there is one
+        let mut net_widths: HashMap<usize, BusWidth> = HashMap::new();
+        let mut net_names: HashMap<usize, String> = HashMap::new();
+        for (_, _, _, ports) in &placed {
+            for port in ports.iter().filter(|port| port.width.0 > 0) {
+                let Some(net) = nets.net_of(port.location) else {
+                    continue;
+                };
+                let width = *net_widths.entry(net).or_insert(port.width);
+                if width != port.width {
+                    return Err(CircFormatError::ConnectionError(format!(
+                        "Incompatible widths at ({},{}): {} and {} bits",
+                        port.location.0, port.location.1, width, port.width
+                    )));
+                }
+            }
+        }
+        for kind in ["Pin", "Tunnel"] {
+            for (comp_instance, ..) in placed.iter().filter(|(c, ..)| c.name == kind) {
+                let label = attr(comp_instance, "label").filter(|l| !l.is_empty());
+                if let (Some(label), Some(net)) = (label, nets.net_of(comp_instance.location)) {
+                    net_names.entry(net).or_insert_with(|| label.to_string());
+                }
             }
         }
+        let mut net_widths: Vec<(usize, BusWidth)> = net_widths.into_iter().collect();
+        net_widths.sort();
+        let mut net_nodes: HashMap<usize, NodeId> = HashMap::new();
+        for (net, width) in net_widths {
+            let name = net_names.remove(&net).unwrap_or_else(|| {
+                let (x, y) = nets.points(net)[0];
+                format!("net_{}_{}", x, y)
+            });
+            let name = format!("{}{}", path, name);
+            let node = sim.netlist_mut().create_named_node(width, name);
+            net_nodes.insert(net, node);
+        }
+        let node_at = |location: (i32, i32)| {
+            nets.net_of(location)
+                .and_then(|net| net_nodes.get(&net).copied())
+        };
+
+        // Fourth pass: build the components, giving the ports that take
+        // their width from the net that width
+        let mut component_ids = Vec::with_capacity(placed.len());
+        for (index, (comp_instance, component_id, library, ports)) in placed.iter().enumerate() {
+            let Some(library) = library else {
+                component_ids.push(None);
+                continue;
+            };
+            let net_width = ports
+                .iter()
+                .filter(|port| port.width.0 == 0)
+                .find_map(|port| node_at(port.location))
+                .and_then(|node| sim.netlist().get_node(node))
+                .map(|node| node.width);
+            let built = match net_width {
+                Some(width) => {
+                    let mut comp_instance = (*comp_instance).clone();
+                    comp_instance
+                        .attributes
+                        .insert("width".to_string(), width.0.to_string());
+                    self.registry.create(library, &comp_instance, *component_id)
+                }
+                None => self.registry.create(library, comp_instance, *component_id),
+            };
+            let component = match built {
+                Ok(component) => component,
+                Err(CircFormatError::UnsupportedComponent(reason)) => {
+                    self.report_unsupported(
+                        circuit_name,
+                        comp_instance,
+                        library.clone(),
+                        Some(reason),
+                    );
+                    None
+                }
+                Err(e) => return Err(e),
+            };
 
-        // Third pass: Connect components to nodes (simplified)
-        // This is a placeholder - real connection logic would be much more complex
-        for (i, comp_instance) in circuit.components.iter().enumerate() {
-            let component_id = component_ids[i];
-            let comp_location = comp_instance.location;
-
-            // Connect to nearby nodes (simplified logic)
-            if let Some(&node) = location_to_node.get(&comp_location) {
-                // This is oversimplified - real pin mapping would be component-specific
-                let _ = sim
-                    .netlist_mut()
-                    .connect(component_id, "A".to_string(), node);
+            let component_id = component.map(|component| {
+                if let Some(tool) = component.circ_tool() {
+                    self.tools.insert(component.id(), tool);
+                }
+                sim.add_component(component)
+            });
+            if let Some(component_id) = component_id {
+                self.origins
+                    .insert(component_id, (circuit_name.to_string(), index));
+            }
+            if let (Some(component_id), Some(_)) = (component_id, attr(comp_instance, "delay")) {
+                let delay = attr_u32(comp_instance, "delay", 0)?;
+                // The component was just added, so this cannot fail
+                let _ = sim.set_component_delay(component_id, delay as u64);
             }
+            component_ids.push(component_id);
         }
 
-        Ok(())
-    }
+        // Fifth pass: attach component ports, splitter bits and pull resistors
+        for ((comp_instance, _, _, ports), component_id) in placed.iter().zip(&component_ids) {
+            if let Some(component_id) = *component_id {
+                for port in ports {
+                    if let Some(node) = node_at(port.location) {
+                        sim.connect(component_id, port.name.clone(), node)
+                            .map_err(|e| CircFormatError::ConnectionError(e.to_string()))?;
+                    }
+                }
+            }
 
-    /// Extract a Simulation back to a CircuitFile
-    pub fn simulation_to_circuit_file(_sim: &Simulation) -> CircResult<CircuitFile> {
-        // This would extract the current simulation state back to .circ format
-        // Simplified implementation for now
-        let mut circuits = HashMap::new();
-
-        circuits.insert(
-            "main".to_string(),
-            CircuitDefinition {
-                name: "main".to_string(),
-                components: Vec::new(), // TODO: Extract from simulation
-                wires: Vec::new(),      // TODO: Extract from netlist
-                appearance: None,
-                attributes: HashMap::new(),
-            },
-        );
+            match comp_instance.name.as_str() {
+                "Splitter" => {
+                    let Some(combined) = node_at(comp_instance.location) else {
+                        continue;
+                    };
+                    let bit_map = splitter_bit_map(comp_instance)?;
+                    for (bit, mapping) in bit_map.into_iter().enumerate() {
+                        let Some((end, end_bit)) = mapping else {
+                            continue;
+                        };
+                        if let Some(end_node) = node_at(ports[end + 1].location) {
+                            sim.netlist_mut()
+                                .link_bits(combined, bit as u32, end_node, end_bit)
+                                .map_err(|e| CircFormatError::ConnectionError(e.to_string()))?;
+                        }
+                    }
+                }
+                "Pin" => {
+                    let pull = match attr(comp_instance, "behavior") {
+                        Some("pullup") => Some(Value::High),
+                        Some("pulldown") => Some(Value::Low),
+                        _ => None,
+                    };
+                    if let (Some(pull), Some(node)) = (pull, node_at(comp_instance.location)) {
+                        sim.netlist_mut()
+                            .set_node_pull(node, Some(pull))
+                            .map_err(|e| CircFormatError::ConnectionError(e.to_string()))?;
+                    }
+                }
+                _ => {}
+            }
+        }
 
-        Ok(CircuitFile {
-            source_version: "logisim-rust-0.1.0".to_string(),
-            version: "1.0".to_string(),
-            libraries: Vec::new(), // TODO: Add standard libraries
-            main_circuit: Some("main".to_string()),
-            circuits,
-            vhdl_contents: Vec::new(),
-            options: ProjectOptions {
-                canvas: CanvasOptions {
-                    printer_view: false,
-                    gate_undefined: "ignore".to_string(),
-                    simulation_icons: true,
-                },
-                simulation: SimulationOptions {
-                    sim_limit: 1000,
-                    sim_rand: 0,
-                },
-                toolbar: ToolbarOptions {
-                    zoom_enabled: true,
-                    show_zoom: true,
-                },
-            },
-            external_circuits: HashMap::new(), // No external circuits for simulation-to-file conversion
-        })
+        // Sixth pass: expand subcircuits and join their pins to the ports
+        for (index, child, pins) in instances {
+            let (comp_instance, _, _, ports) = &placed[index];
+            let instance_name = match attr(comp_instance, "label") {
+                Some(label) if !label.is_empty() => label.to_string(),
+                _ => format!(
+                    "{}({},{})",
+                    comp_instance.name, comp_instance.location.0, comp_instance.location.1
+                ),
+            };
+            let child_path = format!("{}{}/", path, instance_name);
+            let child_nodes = self.build(sim, &child, &child_path)?;
+
+            for (pin, port) in pins.iter().zip(ports) {
+                let (Some(outer), Some(&inner)) = (node_at(port.location), child_nodes.get(pin))
+                else {
+                    continue;
+                };
+                for bit in 0..port.width.0 {
+                    sim.netlist_mut()
+                        .link_bits(outer, bit, inner, bit)
+                        .map_err(|e| CircFormatError::ConnectionError(e.to_string()))?;
+                }
+            }
+        }
+
+        let pin_nodes = placed
+            .iter()
+            .enumerate()
+            .filter(|(_, (comp_instance, ..))| comp_instance.name == "Pin")
+            .filter_map(|(index, (comp_instance, ..))| {
+                node_at(comp_instance.location).map(|node| (index, node))
+            })
+            .collect();
+        self.stack.pop();
+        Ok(pin_nodes)
     }
 }
 
@@ -988,6 +1805,28 @@ mod tests {
         assert!(serialized.contains("12 34 56 78"));
     }
 
+    #[test]
+    fn test_rom_contents_from_memory_image() {
+        use crate::std::memory::mem_image::{self, MemImageFormat, MemImageOptions};
+
+        let mut memory = MemContents::create(8, 8, false);
+        let image = ":0300020012AB56E8\n:00000001FF\n";
+        mem_image::load(
+            &mut memory,
+            image.as_bytes(),
+            MemImageFormat::IntelHex,
+            &MemImageOptions::default(),
+        )
+        .unwrap();
+
+        let rom = RomContents::from_mem_contents(&memory);
+        assert_eq!(rom.to_logisim_format(), "addr/data: 8 8\n0 0 12 ab 56\n");
+        assert_eq!(
+            rom.to_mem_contents().nonzero_words(),
+            memory.nonzero_words()
+        );
+    }
+
     #[test]
     fn test_location_parsing() {
         assert_eq!(CircParser::parse_location("(100,200)").unwrap(), (100, 200));
@@ -1000,6 +1839,7 @@ mod tests {
         let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
 <project source=\"test\" version=\"1.0\">\n\
   <lib desc=\"#Wiring\" name=\"0\"/>\n\
+  <lib desc=\"#Gates\" name=\"1\"/>\n\
   <main name=\"main\"/>\n\
   <circuit name=\"main\">\n\
     <comp lib=\"1\" loc=\"(100,100)\" name=\"AND Gate\">\n\
@@ -1025,6 +1865,7 @@ mod tests {
     fn test_circuit_round_trip() {
         let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
 <project source=\"test\" version=\"1.0\">\n\
+  <lib desc=\"#Gates\" name=\"1\"/>\n\
   <main name=\"test\"/>\n\
   <circuit name=\"test\">\n\
     <comp lib=\"1\" loc=\"(50,50)\" name=\"AND Gate\"/>\n\
@@ -0,0 +1,728 @@
+//! Migration of .circ files written by older Logisim releases
+//!
+//! Files from the original Logisim, up to 2.7.1, and from the first
+//! Logisim-Evolution releases, numbered on from 2.13, use library, component
+//! and attribute names that have since changed. [`CircParser`] reads the
+//! `source` version of every file and rewrites these names with the table
+//! below, recording each change in [`CircuitFile::migrations`]. A migrated
+//! file is given the current version, so that it is saved in the current
+//! format.
+//!
+//! As in Logisim-Evolution, the pin attributes are converted in files of
+//! every release before 4.0.0, which includes the current one. The `facing`
+//! of a pin has meant the same, east by default, in every release, so an
+//! output pin keeps its `facing="west"`.
+//!
+//! [`CircParser`]: crate::circ_format::CircParser
+
+use std::collections::HashSet;
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::circ_format::{CircuitFile, LibraryConfig};
+
+/// Library descriptors only older releases write
+pub const LEGACY_LIBRARIES: &[&str] = &["#Legacy"];
+
+/// A Logisim release, such as `2.7.1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LogisimVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub release: u32,
+}
+
+impl LogisimVersion {
+    pub const fn new(major: u32, minor: u32, release: u32) -> Self {
+        Self {
+            major,
+            minor,
+            release,
+        }
+    }
+
+    /// Parse the `source` of a `<project>`, such as `2.7.1` or `3.8.0`
+    ///
+    /// Suffixes such as `-beta` are ignored; a missing release counts as 0.
+    /// Returns `None` when the text does not start with a version number.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim();
+        let end = version
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(version.len());
+        let mut parts = version[..end].split('.').map(str::parse::<u32>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let release = parts.next().unwrap_or(Ok(0)).ok()?;
+        Some(Self::new(major, minor, release))
+    }
+}
+
+impl fmt::Display for LogisimVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.release)
+    }
+}
+
+/// First Logisim-Evolution release, numbered on from the original Logisim
+pub const FIRST_EVOLUTION: LogisimVersion = LogisimVersion::new(2, 13, 0);
+
+/// First release with the current library, component and attribute names
+pub const CURRENT_NAMES: LogisimVersion = LogisimVersion::new(3, 0, 0);
+
+/// First release whose pins only have a type and a behavior
+pub const PIN_BEHAVIOR: LogisimVersion = LogisimVersion::new(4, 0, 0);
+
+/// A change made while migrating a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationChange {
+    /// Circuit of the changed component, or `None` for a library or tool
+    pub circuit: Option<String>,
+    /// Location of the changed component
+    pub location: Option<(i32, i32)>,
+    pub description: String,
+}
+
+impl fmt::Display for MigrationChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description)?;
+        if let Some((x, y)) = self.location {
+            write!(f, " at ({},{})", x, y)?;
+        }
+        if let Some(circuit) = &self.circuit {
+            write!(f, " in circuit '{}'", circuit)?;
+        }
+        Ok(())
+    }
+}
+
+/// Last release writing the `#Base` library with the wiring components and
+/// circuit appearance attributes named `label...`
+const BEFORE_WIRING: LogisimVersion = LogisimVersion::new(2, 6, 3);
+
+/// Tools of the old `#Base` library that stay in the new `#Base`, from
+/// `XmlReader.repairForWiringLibrary`; the other components move to
+/// `#Wiring`, as does `Constant` of `#Gates`
+const BASE_TOOLS: &[&str] = &[
+    "Poke Tool",
+    "Edit Tool",
+    "Select Tool",
+    "Wiring Tool",
+    "Text Tool",
+    "Menu Tool",
+    "Text",
+];
+
+/// A rewrite of the migration table
+enum Rule {
+    /// A library whose components moved to another one
+    Library {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// The `#Base` library split into `#Wiring` and a new `#Base`, ported
+    /// from `XmlReader.repairForWiringLibrary`
+    WiringLibrary,
+    /// Circuit attributes starting with `from` renamed to start with `to`
+    CircuitAttributes {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// A component renamed within its library
+    Component {
+        library: &'static str,
+        from: &'static str,
+        to: &'static str,
+    },
+    /// Attributes of a component replaced by attribute `to`, which takes
+    /// the new value of the first (attribute, old value, new value) entry
+    /// that matches; a `to` the component already has is kept
+    Attributes {
+        library: &'static str,
+        component: &'static str,
+        from: &'static [&'static str],
+        to: &'static str,
+        values: &'static [(&'static str, &'static str, &'static str)],
+    },
+    /// An attribute older releases did not write, added to the listed
+    /// components of a library that lack it
+    Default {
+        library: &'static str,
+        components: &'static [&'static str],
+        attribute: &'static str,
+        value: &'static str,
+    },
+}
+
+/// A rule applying to files written before a release
+struct Migration {
+    before: LogisimVersion,
+    rule: Rule,
+}
+
+/// The migration table, applied in order
+///
+/// The rules follow `XmlReader.considerRepairs` and the version dependent
+/// `getDefaultAttributeValue` of the Logisim-Evolution components.
+const MIGRATIONS: &[Migration] = &[
+    // The circuit appearance attributes became clabel, clabelup and
+    // clabelfont, as the circuit got a label of its own
+    Migration {
+        before: BEFORE_WIRING,
+        rule: Rule::CircuitAttributes {
+            from: "label",
+            to: "clabel",
+        },
+    },
+    Migration {
+        before: BEFORE_WIRING,
+        rule: Rule::WiringLibrary,
+    },
+    // The flip-flops of Logisim 1.0 became those of the Memory library
+    Migration {
+        before: CURRENT_NAMES,
+        rule: Rule::Component {
+            library: "#Legacy",
+            from: "Logisim 1.0 D Flip-Flop",
+            to: "D Flip-Flop",
+        },
+    },
+    Migration {
+        before: CURRENT_NAMES,
+        rule: Rule::Component {
+            library: "#Legacy",
+            from: "Logisim 1.0 J-K Flip-Flop",
+            to: "J-K Flip-Flop",
+        },
+    },
+    Migration {
+        before: CURRENT_NAMES,
+        rule: Rule::Library {
+            from: "#Legacy",
+            to: "#Memory",
+        },
+    },
+    // The memory components of the original Logisim had the shape now
+    // called classic, which places their ports differently
+    Migration {
+        before: FIRST_EVOLUTION,
+        rule: Rule::Default {
+            library: "#Memory",
+            components: &[
+                "D Flip-Flop",
+                "T Flip-Flop",
+                "J-K Flip-Flop",
+                "S-R Flip-Flop",
+                "Register",
+                "Counter",
+                "Shift Register",
+                "Random",
+                "RAM",
+                "ROM",
+            ],
+            attribute: "appearance",
+            value: "classic",
+        },
+    },
+    // Decoders had no enable input by default before 2.6.4, and
+    // multiplexers one up to 3.6.1
+    Migration {
+        before: LogisimVersion::new(2, 6, 4),
+        rule: Rule::Default {
+            library: "#Plexers",
+            components: &["Decoder"],
+            attribute: "enable",
+            value: "false",
+        },
+    },
+    Migration {
+        before: LogisimVersion::new(3, 6, 2),
+        rule: Rule::Default {
+            library: "#Plexers",
+            components: &["Multiplexer", "Demultiplexer"],
+            attribute: "enable",
+            value: "true",
+        },
+    },
+    // Pins were outputs, three-state and pulled by separate attributes,
+    // now consolidated into a type and a behavior, as in
+    // `XmlReader.convertObsoletePinAttributes`
+    Migration {
+        before: PIN_BEHAVIOR,
+        rule: Rule::Attributes {
+            library: "#Wiring",
+            component: "Pin",
+            from: &["output"],
+            to: "type",
+            values: &[("output", "true", "output"), ("output", "false", "input")],
+        },
+    },
+    Migration {
+        before: PIN_BEHAVIOR,
+        rule: Rule::Attributes {
+            library: "#Wiring",
+            component: "Pin",
+            from: &["tristate", "pull"],
+            to: "behavior",
+            values: &[
+                ("pull", "up", "pullup"),
+                ("pull", "down", "pulldown"),
+                ("tristate", "true", "tristate"),
+            ],
+        },
+    },
+];
+
+/// Bring a file written by an older release up to date
+///
+/// Returns the changes made, which are empty for files of current releases
+/// and files whose version is unknown.
+pub fn migrate(circuit_file: &mut CircuitFile) -> Vec<MigrationChange> {
+    let Some(version) = LogisimVersion::parse(&circuit_file.source_version) else {
+        return Vec::new();
+    };
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| version < m.before) {
+        match migration.rule {
+            Rule::Library { from, to } => migrate_library(circuit_file, from, to, &mut changes),
+            Rule::WiringLibrary => split_base_library(circuit_file, &mut changes),
+            Rule::CircuitAttributes { from, to } => {
+                for circuit in circuit_file.circuits.values_mut() {
+                    let renamed = std::mem::take(&mut circuit.attributes)
+                        .into_iter()
+                        .map(|(name, value)| match name.strip_prefix(from) {
+                            Some(rest) => {
+                                let new = format!("{}{}", to, rest);
+                                changes.push(MigrationChange {
+                                    circuit: Some(circuit.name.clone()),
+                                    location: None,
+                                    description: format!(
+                                        "Circuit attribute {} renamed to {}",
+                                        name, new
+                                    ),
+                                });
+                                (new, value)
+                            }
+                            None => (name, value),
+                        })
+                        .collect();
+                    circuit.attributes = renamed;
+                }
+            }
+            Rule::Component { library, from, to } => {
+                let description = format!("{} renamed to {}", from, to);
+                for_each_use(circuit_file, library, from, &mut changes, |name, _| {
+                    *name = to.to_string();
+                    Some(description.clone())
+                });
+            }
+            Rule::Attributes {
+                library,
+                component,
+                from,
+                to,
+                values,
+            } => {
+                for_each_use(
+                    circuit_file,
+                    library,
+                    component,
+                    &mut changes,
+                    |_, attributes| replace_attributes(component, attributes, from, to, values),
+                );
+            }
+            Rule::Default {
+                library,
+                components,
+                attribute,
+                value,
+            } => {
+                for &component in components {
+                    for_each_use(
+                        circuit_file,
+                        library,
+                        component,
+                        &mut changes,
+                        |_, attributes| {
+                            if attributes.contains_key(attribute) {
+                                return None;
+                            }
+                            attributes.insert(attribute.to_string(), value.to_string());
+                            Some(format!("{}: {}=\"{}\" added", component, attribute, value))
+                        },
+                    );
+                }
+            }
+        }
+    }
+    if !changes.is_empty() {
+        circuit_file.source_version = CircuitFile::default().source_version;
+    }
+    changes
+}
+
+/// Replace the `from` attributes of `component` by `to`, returning the
+/// description of the change, or `None` when it has none of them
+fn replace_attributes(
+    component: &str,
+    attributes: &mut IndexMap<String, String>,
+    from: &[&str],
+    to: &str,
+    values: &[(&str, &str, &str)],
+) -> Option<String> {
+    // The new attribute takes the place of the first old one
+    let index = attributes
+        .keys()
+        .position(|name| from.contains(&name.as_str()))?;
+    let removed: Vec<(String, String)> = from
+        .iter()
+        .filter_map(|&name| attributes.shift_remove_entry(name))
+        .collect();
+    let old = removed
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, value))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let new = values.iter().find_map(|&(attribute, value, new)| {
+        removed
+            .iter()
+            .any(|(name, old)| name == attribute && old.eq_ignore_ascii_case(value))
+            .then_some(new)
+    });
+    match new {
+        Some(new) if !attributes.contains_key(to) => {
+            attributes.shift_insert(index, to.to_string(), new.to_string());
+            Some(format!(
+                "{}: {} replaced by {}=\"{}\"",
+                component, old, to, new
+            ))
+        }
+        _ => Some(format!("{}: {} removed", component, old)),
+    }
+}
+
+/// Split the `#Base` library into `#Wiring` and a new `#Base`, ported from
+/// `XmlReader.repairForWiringLibrary`
+///
+/// The old `#Base` becomes `#Wiring` under the same name, and the tools of
+/// [`BASE_TOOLS`] move to a new `#Base` declared after the other
+/// libraries. `Constant` moves from `#Gates` to `#Wiring`, which is
+/// declared anew when the file has no `#Base`.
+fn split_base_library(circuit_file: &mut CircuitFile, changes: &mut Vec<MigrationChange>) {
+    let libraries = &circuit_file.libraries;
+    if libraries.iter().any(|lib| lib.description == "#Wiring") {
+        return;
+    }
+    let find = |description: &str| {
+        libraries
+            .iter()
+            .find(|lib| lib.description == description)
+            .map(|lib| lib.name.clone())
+    };
+    let old_base = find("#Base");
+    let gates = find("#Gates");
+    let next = libraries
+        .iter()
+        .filter_map(|lib| lib.name.parse::<u32>().ok())
+        .max()
+        .map_or(0, |max| max + 1)
+        .to_string();
+
+    let declare =
+        |circuit_file: &mut CircuitFile, changes: &mut Vec<MigrationChange>, description: &str| {
+            circuit_file.libraries.push(LibraryConfig {
+                name: next.clone(),
+                description: description.to_string(),
+                tools: Vec::new(),
+                external_file: None,
+            });
+            changes.push(MigrationChange {
+                circuit: None,
+                location: None,
+                description: format!("Library {} (lib {}) added", description, next),
+            });
+        };
+    let wiring = match &old_base {
+        Some(base) => {
+            for lib in circuit_file
+                .libraries
+                .iter_mut()
+                .filter(|lib| lib.name == *base)
+            {
+                lib.description = "#Wiring".to_string();
+            }
+            changes.push(MigrationChange {
+                circuit: None,
+                location: None,
+                description: format!("Library #Base (lib {}) renamed to #Wiring", base),
+            });
+            declare(circuit_file, changes, "#Base");
+            move_components(circuit_file, base, BASE_TOOLS, &next, changes);
+            base.clone()
+        }
+        None => {
+            declare(circuit_file, changes, "#Wiring");
+            next
+        }
+    };
+    if let Some(gates) = gates {
+        move_components(circuit_file, &gates, &["Constant"], &wiring, changes);
+    }
+}
+
+/// Move the listed components and tools of library `from` to library `to`
+fn move_components(
+    circuit_file: &mut CircuitFile,
+    from: &str,
+    components: &[&str],
+    to: &str,
+    changes: &mut Vec<MigrationChange>,
+) {
+    let moves = |library: &Option<String>, name: &str| {
+        library.as_deref() == Some(from) && components.contains(&name)
+    };
+    let description = |name: &str| format!("{} moved from lib {} to lib {}", name, from, to);
+
+    let mut moved = Vec::new();
+    for lib in circuit_file
+        .libraries
+        .iter_mut()
+        .filter(|lib| lib.name == from)
+    {
+        let (tools, kept) = std::mem::take(&mut lib.tools)
+            .into_iter()
+            .partition(|tool| components.contains(&tool.name.as_str()));
+        lib.tools = kept;
+        moved.extend::<Vec<_>>(tools);
+    }
+    if let Some(lib) = circuit_file.libraries.iter_mut().find(|lib| lib.name == to) {
+        for tool in moved {
+            changes.push(MigrationChange {
+                circuit: None,
+                location: None,
+                description: format!("{} (tool of lib {})", description(&tool.name), from),
+            });
+            lib.tools.push(tool);
+        }
+    }
+    for circuit in circuit_file.circuits.values_mut() {
+        for comp in circuit
+            .components
+            .iter_mut()
+            .filter(|comp| moves(&comp.library, &comp.name))
+        {
+            comp.library = Some(to.to_string());
+            changes.push(MigrationChange {
+                circuit: Some(circuit.name.clone()),
+                location: Some(comp.location),
+                description: description(&comp.name),
+            });
+        }
+    }
+    let options = &mut circuit_file.options;
+    let tools = options
+        .toolbar_items
+        .iter_mut()
+        .flatten()
+        .chain(options.mappings.iter_mut().map(|mapping| &mut mapping.tool));
+    for tool in tools.filter(|tool| moves(&tool.library, &tool.name)) {
+        tool.library = Some(to.to_string());
+        changes.push(MigrationChange {
+            circuit: None,
+            location: None,
+            description: format!("{} (toolbar or mapping tool)", description(&tool.name)),
+        });
+    }
+}
+
+/// Move the components of library `from` to library `to`, merging the two
+/// when the file declares both
+fn migrate_library(
+    circuit_file: &mut CircuitFile,
+    from: &str,
+    to: &str,
+    changes: &mut Vec<MigrationChange>,
+) {
+    let target = circuit_file
+        .libraries
+        .iter()
+        .find(|lib| lib.description == to)
+        .map(|lib| lib.name.clone());
+    let Some(target) = target else {
+        for lib in circuit_file
+            .libraries
+            .iter_mut()
+            .filter(|lib| lib.description == from)
+        {
+            lib.description = to.to_string();
+            changes.push(MigrationChange {
+                circuit: None,
+                location: None,
+                description: format!("Library {} (lib {}) renamed to {}", from, lib.name, to),
+            });
+        }
+        return;
+    };
+
+    let (removed, kept) = std::mem::take(&mut circuit_file.libraries)
+        .into_iter()
+        .partition(|lib| lib.description == from);
+    let removed: Vec<LibraryConfig> = removed;
+    circuit_file.libraries = kept;
+    let merged: Vec<String> = removed.iter().map(|lib| lib.name.clone()).collect();
+    for lib in removed {
+        // Tool settings move along, unless the target has its own
+        let target_lib = circuit_file
+            .libraries
+            .iter_mut()
+            .find(|lib| lib.name == target);
+        if let Some(target_lib) = target_lib {
+            for tool in lib.tools {
+                if target_lib.tools.iter().all(|t| t.name != tool.name) {
+                    target_lib.tools.push(tool);
+                }
+            }
+        }
+        let name = lib.name;
+        changes.push(MigrationChange {
+            circuit: None,
+            location: None,
+            description: format!(
+                "Library {} (lib {}) merged into {} (lib {})",
+                from, name, to, target
+            ),
+        });
+    }
+    let repoint = |library: &mut Option<String>| {
+        if library.as_ref().is_some_and(|name| merged.contains(name)) {
+            *library = Some(target.clone());
+        }
+    };
+    for circuit in circuit_file.circuits.values_mut() {
+        for comp in &mut circuit.components {
+            repoint(&mut comp.library);
+        }
+    }
+    let options = &mut circuit_file.options;
+    for tool in options.toolbar_items.iter_mut().flatten() {
+        repoint(&mut tool.library);
+    }
+    for mapping in &mut options.mappings {
+        repoint(&mut mapping.tool.library);
+    }
+}
+
+/// Apply `change` to every component and tool named `component` from
+/// `library`, recording the description it returns
+fn for_each_use(
+    circuit_file: &mut CircuitFile,
+    library: &str,
+    component: &str,
+    changes: &mut Vec<MigrationChange>,
+    mut change: impl FnMut(&mut String, &mut IndexMap<String, String>) -> Option<String>,
+) {
+    let libraries: HashSet<String> = circuit_file
+        .libraries
+        .iter()
+        .filter(|lib| lib.description == library)
+        .map(|lib| lib.name.clone())
+        .collect();
+    let declared = |name: &Option<String>| name.as_ref().is_some_and(|n| libraries.contains(n));
+
+    for lib in circuit_file
+        .libraries
+        .iter_mut()
+        .filter(|lib| libraries.contains(&lib.name))
+    {
+        for tool in lib.tools.iter_mut().filter(|tool| tool.name == component) {
+            if let Some(description) = change(&mut tool.name, &mut tool.attributes) {
+                changes.push(MigrationChange {
+                    circuit: None,
+                    location: None,
+                    description: format!("{} (tool of lib {})", description, lib.name),
+                });
+            }
+        }
+    }
+    for circuit in circuit_file.circuits.values_mut() {
+        for comp in circuit
+            .components
+            .iter_mut()
+            .filter(|comp| comp.name == component && declared(&comp.library))
+        {
+            if let Some(description) = change(&mut comp.name, &mut comp.attributes) {
+                changes.push(MigrationChange {
+                    circuit: Some(circuit.name.clone()),
+                    location: Some(comp.location),
+                    description,
+                });
+            }
+        }
+    }
+    let options = &mut circuit_file.options;
+    let tools = options
+        .toolbar_items
+        .iter_mut()
+        .flatten()
+        .chain(options.mappings.iter_mut().map(|mapping| &mut mapping.tool));
+    for tool in tools.filter(|tool| tool.name == component && declared(&tool.library)) {
+        if let Some(description) = change(&mut tool.name, &mut tool.attributes) {
+            changes.push(MigrationChange {
+                circuit: None,
+                location: None,
+                description: format!("{} (toolbar or mapping tool)", description),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_parsing() {
+        assert_eq!(
+            LogisimVersion::parse("2.7.1"),
+            Some(LogisimVersion::new(2, 7, 1))
+        );
+        assert_eq!(
+            LogisimVersion::parse("3.8.0-beta"),
+            Some(LogisimVersion::new(3, 8, 0))
+        );
+        assert_eq!(
+            LogisimVersion::parse("2.13"),
+            Some(LogisimVersion::new(2, 13, 0))
+        );
+        assert_eq!(LogisimVersion::parse("unknown"), None);
+        assert!(LogisimVersion::new(2, 7, 1) < FIRST_EVOLUTION);
+        assert!(LogisimVersion::new(2, 15, 0) < CURRENT_NAMES);
+    }
+
+    #[test]
+    fn test_current_files_are_untouched() {
+        let mut circuit_file = CircuitFile::default();
+        circuit_file
+            .libraries
+            .push(crate::circ_format::LibraryConfig {
+                name: "0".to_string(),
+                description: "#Legacy".to_string(),
+                tools: Vec::new(),
+                external_file: None,
+            });
+        let original = circuit_file.clone();
+        assert!(migrate(&mut circuit_file).is_empty());
+        assert_eq!(circuit_file, original);
+
+        circuit_file.source_version = "2.7.1".to_string();
+        let changes = migrate(&mut circuit_file);
+        assert_eq!(
+            changes[0].to_string(),
+            "Library #Legacy (lib 0) renamed to #Memory"
+        );
+        assert_eq!(circuit_file.source_version, "3.8.0");
+        assert!(migrate(&mut circuit_file).is_empty());
+    }
+}
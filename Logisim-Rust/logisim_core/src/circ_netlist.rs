@@ -0,0 +1,1952 @@
+//! Connectivity extraction for .circ circuits
+//!
+//! A Logisim-Evolution circuit is stored as placed components plus straight
+//! wire segments. This module recovers the electrical structure from that
+//! drawing:
+//!
+//! - [`component_ports`] computes where each component's ports sit, using the
+//!   same geometry as the Java factories (facing, size, input count, ...)
+//! - [`CircuitNets`] merges wire segments, T-junctions and same-label tunnels
+//!   into nets
+//! - [`splitter_bit_map`] describes how a splitter wires the bits of its
+//!   combined end to its split ends
+//! - [`subcircuit_ports`] places the ports of a subcircuit instance from the
+//!   child circuit's pins and appearance
+
+use std::collections::HashMap;
+
+use crate::circ_format::{
+    AppearanceElement, CircFormatError, CircResult, CircuitDefinition, ComponentInstance,
+    WireConnection,
+};
+use crate::data::{Direction, Location};
+use crate::signal::BusWidth;
+use crate::std::arith::{BitAdder, BitFinder, Shifter};
+use crate::std::memory::{DLogic, FlipFlopLogic, JKLogic, SRLogic, TLogic};
+use crate::std::plexers::BitSelector;
+
+/// A component port placed in circuit coordinates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortLocation {
+    /// Name of the matching pin on the simulation component
+    pub name: String,
+    /// Absolute location of the port
+    pub location: (i32, i32),
+    /// Bus width of the port, or 0 for a port that takes the width of the
+    /// net it is attached to
+    pub width: BusWidth,
+}
+
+/// Get a raw attribute value of a component instance
+pub(crate) fn attr<'a>(comp: &'a ComponentInstance, name: &str) -> Option<&'a str> {
+    comp.attributes.get(name).map(String::as_str)
+}
+
+/// Parse an integer attribute, falling back to `default` when it is absent
+pub(crate) fn attr_u32(comp: &ComponentInstance, name: &str, default: u32) -> CircResult<u32> {
+    match attr(comp, name) {
+        Some(value) => value.trim().parse().map_err(|_| {
+            CircFormatError::InvalidAttributeValue(format!("{} '{}' on {}", name, value, comp.name))
+        }),
+        None => Ok(default),
+    }
+}
+
+/// Get the data width of a component (the `width` attribute, 1 by default)
+pub(crate) fn attr_width(comp: &ComponentInstance) -> CircResult<BusWidth> {
+    attr_u32(comp, "width", 1).map(BusWidth)
+}
+
+/// Parse a hexadecimal attribute such as `0x1f`, falling back to `default`
+/// when it is absent
+pub(crate) fn attr_hex(comp: &ComponentInstance, name: &str, default: u64) -> CircResult<u64> {
+    match attr(comp, name) {
+        Some(value) => {
+            let digits = value.trim().trim_start_matches("0x");
+            u64::from_str_radix(digits, 16).map_err(|_| {
+                CircFormatError::InvalidAttributeValue(format!(
+                    "{} '{}' on {}",
+                    name, value, comp.name
+                ))
+            })
+        }
+        None => Ok(default),
+    }
+}
+
+/// Get the direction a component faces
+pub(crate) fn attr_facing(comp: &ComponentInstance, default: Direction) -> CircResult<Direction> {
+    match attr(comp, "facing").or(comp.facing.as_deref()) {
+        Some(value) => Direction::parse(value).map_err(CircFormatError::InvalidAttributeValue),
+        None => Ok(default),
+    }
+}
+
+/// Compute the ports of a component instance in circuit coordinates
+///
+/// Components without any electrical ports (such as `Text`) yield an empty
+/// list; components whose geometry is unknown are reported as unsupported.
+pub fn component_ports(comp: &ComponentInstance) -> CircResult<Vec<PortLocation>> {
+    let origin = Location::new(comp.location.0, comp.location.1);
+    let port = |name: &str, location: Location, width: BusWidth| PortLocation {
+        name: name.to_string(),
+        location: (location.get_x(), location.get_y()),
+        width,
+    };
+
+    let ports = match comp.name.as_str() {
+        "AND Gate" | "OR Gate" => gate_ports(comp, origin, 0, false)?,
+        "NAND Gate" | "NOR Gate" => gate_ports(comp, origin, 0, true)?,
+        "XOR Gate" => gate_ports(comp, origin, 10, false)?,
+        "XNOR Gate" => gate_ports(comp, origin, 10, true)?,
+        "Odd Parity" | "Even Parity" => gate_ports(comp, origin, 0, false)?,
+        "NOT Gate" => {
+            let width = attr_width(comp)?;
+            let facing = attr_facing(comp, Direction::East)?;
+            let length = if attr(comp, "size") == Some("20") {
+                20
+            } else {
+                30
+            };
+            vec![
+                port("Y", origin, width),
+                port("A", origin.translate_direction(facing, -length), width),
+            ]
+        }
+        "Buffer" => {
+            let width = attr_width(comp)?;
+            let facing = attr_facing(comp, Direction::East)?;
+            vec![
+                port("Y", origin, width),
+                port("A", origin.translate_direction(facing, -20), width),
+            ]
+        }
+        "Controlled Buffer" | "Controlled Inverter" => {
+            let width = attr_width(comp)?;
+            let back = attr_facing(comp, Direction::East)?.reverse();
+            let side = if attr(comp, "control") == Some("left") {
+                10
+            } else {
+                -10
+            };
+            // Wide inverters leave room for the negation bubble
+            let d = if comp.name == "Controlled Inverter" && attr(comp, "size") != Some("20") {
+                10
+            } else {
+                0
+            };
+            vec![
+                port("Y", origin, width),
+                port("A", origin.translate_direction(back, 20 + d), width),
+                port(
+                    "EN",
+                    origin.translate_direction_with_offset(back, 10 + d, side),
+                    BusWidth(1),
+                ),
+            ]
+        }
+        "Pin" => vec![port("pin", origin, attr_width(comp)?)],
+        "Constant" | "Power" | "Ground" => vec![port("out", origin, attr_width(comp)?)],
+        "Clock" | "POR" => vec![port("out", origin, BusWidth(1))],
+        "Tunnel" => vec![port("tunnel", origin, attr_width(comp)?)],
+        "Splitter" => splitter_ports(comp, origin)?,
+        "Bit Extender" => {
+            let mut ports = vec![
+                port("out", origin, BusWidth(attr_u32(comp, "out_width", 16)?)),
+                port(
+                    "in",
+                    origin.translate(-40, 0),
+                    BusWidth(attr_u32(comp, "in_width", 8)?),
+                ),
+            ];
+            if attr(comp, "type") == Some("input") {
+                ports.push(port("extend", origin.translate(-20, -20), BusWidth(1)));
+            }
+            ports
+        }
+        "Probe" => vec![port("in", origin, BusWidth(0))],
+        "Pull Resistor" => vec![port("out", origin, BusWidth(0))],
+        "NoConnect" => vec![port("pin", origin, attr_width(comp)?)],
+        "Transistor" => switch_ports(comp, origin, &["gate"])?,
+        "Transmission Gate" => switch_ports(comp, origin, &["p_gate", "n_gate"])?,
+        "Multiplexer" => multiplexer_ports(comp, origin)?,
+        "Decoder" => decoder_ports(comp, origin)?,
+        "Demultiplexer" => demultiplexer_ports(comp, origin)?,
+        "Priority Encoder" => priority_encoder_ports(comp, origin)?,
+        "BitSelector" => bit_selector_ports(comp, origin)?,
+        "Adder" | "Subtractor" | "Multiplier" | "Divider" | "Comparator" => {
+            arith_ports(comp, origin)?
+        }
+        "Negator" => {
+            let width = BusWidth(attr_u32(comp, "width", 8)?);
+            offset_ports(
+                origin,
+                &[("Input", (-40, 0), width), ("Output", (0, 0), width)],
+            )
+        }
+        "Shifter" => {
+            let width = BusWidth(attr_u32(comp, "width", 8)?);
+            offset_ports(
+                origin,
+                &[
+                    ("Input", (-40, -10), width),
+                    ("Shift", (-40, 10), Shifter::distance_width(width)),
+                    ("Output", (0, 0), width),
+                ],
+            )
+        }
+        "BitAdder" => bit_adder_ports(comp, origin)?,
+        "BitFinder" => {
+            let width = BusWidth(attr_u32(comp, "width", 8)?);
+            offset_ports(
+                origin,
+                &[
+                    ("Present", (-20, 20), BusWidth(1)),
+                    ("Index", (0, 0), BitFinder::index_width(width)),
+                    ("Input", (-40, 0), width),
+                ],
+            )
+        }
+        "D Flip-Flop" => flip_flop_ports::<DLogic>(comp, origin),
+        "T Flip-Flop" => flip_flop_ports::<TLogic>(comp, origin),
+        "J-K Flip-Flop" => flip_flop_ports::<JKLogic>(comp, origin),
+        "S-R Flip-Flop" => flip_flop_ports::<SRLogic>(comp, origin),
+        "Random" => {
+            let width = BusWidth(attr_u32(comp, "width", 8)?);
+            let one = BusWidth(1);
+            let ports = if classic_appearance(comp) {
+                [
+                    ("OUT", (40, 20), width),
+                    ("CLK", (10, 40), one),
+                    ("EN", (0, 30), one),
+                    ("RST", (30, 40), one),
+                ]
+            } else {
+                [
+                    ("OUT", (80, 80), width),
+                    ("CLK", (0, 50), one),
+                    ("EN", (0, 40), one),
+                    ("RST", (0, 30), one),
+                ]
+            };
+            offset_ports(origin, &ports)
+        }
+        "Register" => register_ports(comp, origin)?,
+        "Counter" => counter_ports(comp, origin)?,
+        "Shift Register" => shift_register_ports(comp, origin)?,
+        "RAM" => ram_ports(comp, origin)?,
+        "ROM" => rom_ports(comp, origin)?,
+        "Text" => Vec::new(),
+        other => {
+            return Err(CircFormatError::UnsupportedComponent(format!(
+                "No port geometry for component type '{}'",
+                other
+            )))
+        }
+    };
+    Ok(ports)
+}
+
+/// Ports of the multi-input gates, ported from `AbstractGate.getInputOffset`
+fn gate_ports(
+    comp: &ComponentInstance,
+    origin: Location,
+    bonus_width: i32,
+    negate_output: bool,
+) -> CircResult<Vec<PortLocation>> {
+    let width = attr_width(comp)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let inputs = attr_u32(comp, "inputs", 2)? as i32;
+    let size = attr_u32(comp, "size", 50)? as i32;
+    let axis_length = size + bonus_width + if negate_output { 10 } else { 0 };
+
+    let (skip_start, skip_dist, skip_lower_even) = if inputs <= 3 {
+        if size < 40 {
+            (-5, 10, 10)
+        } else if size < 60 || inputs <= 2 {
+            (-10, 20, 20)
+        } else {
+            (-15, 30, 30)
+        }
+    } else if inputs == 4 && size >= 60 {
+        (-5, 20, 0)
+    } else {
+        (-5, 10, 10)
+    };
+
+    let mut ports = vec![PortLocation {
+        name: "Y".to_string(),
+        location: comp.location,
+        width,
+    }];
+    for index in 0..inputs {
+        let mut dy;
+        if inputs & 1 == 1 {
+            dy = skip_start * (inputs - 1) + skip_dist * index;
+        } else {
+            dy = skip_start * inputs + skip_dist * index;
+            if index >= inputs / 2 {
+                dy += skip_lower_even;
+            }
+            if inputs == 4 && size >= 60 {
+                dy -= 10;
+            }
+        }
+
+        let mut dx = axis_length;
+        if attr(comp, &format!("negate{}", index)) == Some("true") {
+            dx += 10;
+        }
+
+        let (x, y) = match facing {
+            Direction::North => (dy, dx),
+            Direction::South => (dy, -dx),
+            Direction::West => (dx, dy),
+            Direction::East => (-dx, dy),
+        };
+        let name = match index {
+            0 => "A".to_string(),
+            1 => "B".to_string(),
+            i => format!("I{}", i),
+        };
+        ports.push(PortLocation {
+            name,
+            location: (origin.get_x() + x, origin.get_y() + y),
+            width,
+        });
+    }
+    Ok(ports)
+}
+
+/// Ports of a transistor or transmission gate, ported from their
+/// `updatePorts`
+///
+/// The drain sits on the anchor and the source 40 units behind it; the gate
+/// inputs sit on either side, `gates[0]` on the side chosen by `selloc`.
+fn switch_ports(
+    comp: &ComponentInstance,
+    origin: Location,
+    gates: &[&str],
+) -> CircResult<Vec<PortLocation>> {
+    let width = attr_width(comp)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let (dx, dy) = match facing {
+        Direction::North => (0, 1),
+        Direction::East => (-1, 0),
+        Direction::South => (0, -1),
+        Direction::West => (1, 0),
+    };
+    let top_right = attr(comp, "selloc") != Some("bl");
+    let flip = matches!(facing, Direction::North | Direction::West) == top_right;
+    let near = (20 * (dx - dy), 20 * (dx + dy));
+    let far = (20 * (dx + dy), 20 * (-dx + dy));
+    let sides = if flip { [far, near] } else { [near, far] };
+
+    let mut ports = offset_ports(
+        origin,
+        &[
+            ("drain", (0, 0), width),
+            ("source", (40 * dx, 40 * dy), width),
+        ],
+    );
+    for (gate, offset) in gates.iter().zip(sides) {
+        ports.extend(offset_ports(origin, &[(gate, offset, BusWidth(1))]));
+    }
+    Ok(ports)
+}
+
+/// Ports of a multiplexer, ported from `Multiplexer.updatePorts`
+///
+/// Data inputs are named `input_0`, `input_1`, ...
+fn multiplexer_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let width = attr_width(comp)?;
+    let select = attr_u32(comp, "select", 1)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let wide = attr(comp, "size") != Some("20");
+    let bottom_left = attr(comp, "selloc") != Some("tr");
+    let sel_mult = if bottom_left { 1 } else { -1 };
+    // Java calls east and west facing plexers vertical
+    let vertical = !facing.is_vertical();
+    let inputs = 1i32 << select.min(8);
+
+    let mut ports = Vec::new();
+    let mut sel;
+    if inputs == 2 {
+        let w = if wide { 30 } else { 20 };
+        let s = if wide { 20 } else { 10 };
+        let (end0, end1) = match facing {
+            Direction::West => {
+                sel = (s, sel_mult * 20);
+                ((w, -10), (w, 10))
+            }
+            Direction::North => {
+                sel = (sel_mult * -20, s);
+                ((-10, w), (10, w))
+            }
+            Direction::South => {
+                sel = (sel_mult * -20, -s);
+                ((-10, -w), (10, -w))
+            }
+            Direction::East => {
+                sel = (-s, sel_mult * 20);
+                ((-w, -10), (-w, 10))
+            }
+        };
+        ports.push(("input_0".to_string(), end0, width));
+        ports.push(("input_1".to_string(), end1, width));
+    } else {
+        let w = if wide { 40 } else { 20 };
+        let s = if wide { 20 } else { 10 };
+        let (mut dx, mut dy) = (-(inputs / 2) * 10, -(inputs / 2) * 10);
+        let (mut ddx, mut ddy) = (10, 10);
+        match facing {
+            Direction::West => {
+                dx = w;
+                ddx = 0;
+                sel = (s, sel_mult * (dy + 10 * inputs));
+            }
+            Direction::North => {
+                dy = w;
+                ddy = 0;
+                sel = (sel_mult * dx, s);
+            }
+            Direction::South => {
+                dy = -w;
+                ddy = 0;
+                sel = (sel_mult * dx, -s);
+            }
+            Direction::East => {
+                dx = -w;
+                ddx = 0;
+                sel = (-s, sel_mult * (dy + 10 * inputs));
+            }
+        }
+        for i in 0..inputs {
+            ports.push((format!("input_{}", i), (dx + i * ddx, dy + i * ddy), width));
+        }
+    }
+    if !wide && !vertical && bottom_left && inputs > 2 {
+        sel.0 -= 10;
+    } else if !wide && vertical && !bottom_left && inputs > 2 {
+        sel.1 -= 10;
+    }
+
+    ports.push(("select".to_string(), sel, BusWidth(select)));
+    if attr(comp, "enable") == Some("true") {
+        let enable = Location::new(sel.0, sel.1).translate_direction(facing, 10);
+        let offset = (enable.get_x(), enable.get_y());
+        ports.push(("enable".to_string(), offset, BusWidth(1)));
+    }
+    ports.push(("output".to_string(), (0, 0), width));
+    Ok(ports
+        .into_iter()
+        .map(|(name, (dx, dy), width)| PortLocation {
+            name,
+            location: (origin.get_x() + dx, origin.get_y() + dy),
+            width,
+        })
+        .collect())
+}
+
+/// Ports of a decoder, ported from `Decoder.updatePorts`
+///
+/// Outputs are named `output_0`, `output_1`, ...
+fn decoder_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let select = attr_u32(comp, "select", 1)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let top_right = attr(comp, "selloc") == Some("tr");
+    let outputs = 1i32 << select.min(8);
+    let one = BusWidth(1);
+
+    let mut ports = Vec::new();
+    if outputs == 2 {
+        let (end0, end1) = if facing.is_vertical() {
+            let y = if facing == Direction::North { -10 } else { 10 };
+            if top_right {
+                ((-30, y), (-10, y))
+            } else {
+                ((10, y), (30, y))
+            }
+        } else {
+            let x = if facing == Direction::West { -10 } else { 10 };
+            if top_right {
+                ((x, 10), (x, 30))
+            } else {
+                ((x, -30), (x, -10))
+            }
+        };
+        ports.push(("output_0".to_string(), end0, one));
+        ports.push(("output_1".to_string(), end1, one));
+    } else {
+        let (dx, ddx, dy, ddy) = if facing.is_vertical() {
+            let dy = if facing == Direction::North { -20 } else { 20 };
+            let dx = if top_right { -10 * outputs } else { 0 };
+            (dx, 10, dy, 0)
+        } else {
+            let dx = if facing == Direction::West { -20 } else { 20 };
+            let dy = if top_right { 0 } else { -10 * outputs };
+            (dx, 0, dy, 10)
+        };
+        for i in 0..outputs {
+            ports.push((format!("output_{}", i), (dx + i * ddx, dy + i * ddy), one));
+        }
+    }
+
+    ports.push(("select".to_string(), (0, 0), BusWidth(select)));
+    // The enable input defaults to present since Logisim 2.6.4
+    if attr(comp, "enable") != Some("false") {
+        let enable = Location::new(0, 0).translate_direction(facing, -10);
+        ports.push(("enable".to_string(), (enable.get_x(), enable.get_y()), one));
+    }
+    Ok(ports
+        .into_iter()
+        .map(|(name, (dx, dy), width)| PortLocation {
+            name,
+            location: (origin.get_x() + dx, origin.get_y() + dy),
+            width,
+        })
+        .collect())
+}
+
+/// Ports of a demultiplexer, ported from `Demultiplexer.updatePorts`
+///
+/// Data outputs are named `output_0`, `output_1`, ...
+fn demultiplexer_ports(
+    comp: &ComponentInstance,
+    origin: Location,
+) -> CircResult<Vec<PortLocation>> {
+    let width = attr_width(comp)?;
+    let select = attr_u32(comp, "select", 1)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let sel_mult = if attr(comp, "selloc") != Some("tr") {
+        1
+    } else {
+        -1
+    };
+    let outputs = 1i32 << select.min(8);
+
+    let mut ports = Vec::new();
+    let sel;
+    if outputs == 2 {
+        let (end0, end1) = match facing {
+            Direction::West => {
+                sel = (-20, sel_mult * 20);
+                ((-30, -10), (-30, 10))
+            }
+            Direction::North => {
+                sel = (sel_mult * -20, -20);
+                ((-10, -30), (10, -30))
+            }
+            Direction::South => {
+                sel = (sel_mult * -20, 20);
+                ((-10, 30), (10, 30))
+            }
+            Direction::East => {
+                sel = (20, sel_mult * 20);
+                ((30, -10), (30, 10))
+            }
+        };
+        ports.push(("output_0".to_string(), end0, width));
+        ports.push(("output_1".to_string(), end1, width));
+    } else {
+        let (mut dx, mut dy) = (-(outputs / 2) * 10, -(outputs / 2) * 10);
+        let (mut ddx, mut ddy) = (10, 10);
+        match facing {
+            Direction::West => {
+                dx = -40;
+                ddx = 0;
+                sel = (-20, sel_mult * (dy + 10 * outputs));
+            }
+            Direction::North => {
+                dy = -40;
+                ddy = 0;
+                sel = (sel_mult * dx, -20);
+            }
+            Direction::South => {
+                dy = 40;
+                ddy = 0;
+                sel = (sel_mult * dx, 20);
+            }
+            Direction::East => {
+                dx = 40;
+                ddx = 0;
+                sel = (20, sel_mult * (dy + 10 * outputs));
+            }
+        }
+        for i in 0..outputs {
+            ports.push((format!("output_{}", i), (dx + i * ddx, dy + i * ddy), width));
+        }
+    }
+
+    ports.push(("select".to_string(), sel, BusWidth(select)));
+    if attr(comp, "enable") == Some("true") {
+        let enable = Location::new(sel.0, sel.1).translate_direction(facing, -10);
+        let offset = (enable.get_x(), enable.get_y());
+        ports.push(("enable".to_string(), offset, BusWidth(1)));
+    }
+    ports.push(("input".to_string(), (0, 0), width));
+    Ok(ports
+        .into_iter()
+        .map(|(name, (dx, dy), width)| PortLocation {
+            name,
+            location: (origin.get_x() + dx, origin.get_y() + dy),
+            width,
+        })
+        .collect())
+}
+
+/// Ports of a priority encoder, ported from `PriorityEncoder.updatePorts`
+///
+/// Inputs are named `input_0`, `input_1`, ...
+fn priority_encoder_ports(
+    comp: &ComponentInstance,
+    origin: Location,
+) -> CircResult<Vec<PortLocation>> {
+    let select = attr_u32(comp, "select", 3)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let inputs = 1i32 << select.min(8);
+    let one = BusWidth(1);
+
+    let mut ports = Vec::new();
+    let (enable_in, enable_out, group) = if facing.is_vertical() {
+        let x = -5 * inputs + 10;
+        let y = if facing == Direction::North { 40 } else { -40 };
+        for i in 0..inputs {
+            ports.push((format!("input_{}", i), (x + 10 * i, y), one));
+        }
+        ((x + 10 * inputs, y / 2), (x - 10, y / 2), (10, 0))
+    } else {
+        let x = if facing == Direction::East { -40 } else { 40 };
+        let y = -5 * inputs + 10;
+        for i in 0..inputs {
+            ports.push((format!("input_{}", i), (x, y + 10 * i), one));
+        }
+        ((x / 2, y + 10 * inputs), (x / 2, y - 10), (0, 10))
+    };
+    ports.push(("output".to_string(), (0, 0), BusWidth(select)));
+    ports.push(("enable_in".to_string(), enable_in, one));
+    ports.push(("enable_out".to_string(), enable_out, one));
+    ports.push(("group_signal".to_string(), group, one));
+    Ok(ports
+        .into_iter()
+        .map(|(name, (dx, dy), width)| PortLocation {
+            name,
+            location: (origin.get_x() + dx, origin.get_y() + dy),
+            width,
+        })
+        .collect())
+}
+
+/// Ports of a bit selector, ported from `BitSelector.updatePorts`
+fn bit_selector_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let width = BusWidth(attr_u32(comp, "width", 8)?);
+    let group = BusWidth(attr_u32(comp, "group", 1)?);
+    let bottom_left = attr(comp, "selloc") != Some("tr");
+    let (input, select) = match attr_facing(comp, Direction::East)? {
+        Direction::West => ((30, 0), if bottom_left { (10, -10) } else { (10, 10) }),
+        Direction::North => ((0, 30), if bottom_left { (-10, 10) } else { (10, 10) }),
+        Direction::South => ((0, -30), if bottom_left { (-10, -10) } else { (10, -10) }),
+        Direction::East => ((-30, 0), if bottom_left { (-10, 10) } else { (-10, -10) }),
+    };
+    Ok(offset_ports(
+        origin,
+        &[
+            ("output", (0, 0), group),
+            ("input", input, width),
+            ("select", select, BitSelector::select_width(width, group)),
+        ],
+    ))
+}
+
+/// Ports of a bit adder, ported from `BitAdder.configurePorts`
+///
+/// Inputs are named `Input_0`, `Input_1`, ...
+fn bit_adder_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let width = BusWidth(attr_u32(comp, "width", 8)?);
+    let inputs = attr_u32(comp, "inputs", 1)?;
+    let (y, dy) = match inputs {
+        1 => (0, 10),
+        2 => (-10, 20),
+        3 => (-10, 10),
+        _ => ((inputs as i32 - 1) / 2 * -10, 10),
+    };
+
+    let mut ports = vec![PortLocation {
+        name: "Output".to_string(),
+        location: comp.location,
+        width: BitAdder::output_width(width, inputs),
+    }];
+    for i in 0..inputs as i32 {
+        ports.push(PortLocation {
+            name: format!("Input_{}", i),
+            location: (origin.get_x() - 40, origin.get_y() + y + i * dy),
+            width,
+        });
+    }
+    Ok(ports)
+}
+
+/// Ports of the arithmetic components, ported from the `Adder`,
+/// `Subtractor`, `Multiplier`, `Divider` and `Comparator` constructors
+fn arith_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let width = BusWidth(attr_u32(comp, "width", 8)?);
+    let one = BusWidth(1);
+    let ports = match comp.name.as_str() {
+        "Adder" => [
+            ("Sum", (0, 0), width),
+            ("Carry_In", (-20, -20), one),
+            ("Carry_Out", (-20, 20), one),
+        ],
+        "Subtractor" => [
+            ("Difference", (0, 0), width),
+            ("Borrow_In", (-20, -20), one),
+            ("Borrow_Out", (-20, 20), one),
+        ],
+        "Multiplier" => [
+            ("Product", (0, 0), width),
+            ("Carry_In", (-20, -20), width),
+            ("Carry_Out", (-20, 20), width),
+        ],
+        "Divider" => [
+            ("Quotient", (0, 0), width),
+            ("Upper", (-20, -20), width),
+            ("Remainder", (-20, 20), width),
+        ],
+        _ => [
+            ("Greater", (0, -10), one),
+            ("Equal", (0, 0), one),
+            ("Less", (0, 10), one),
+        ],
+    };
+    let mut all = vec![("A", (-40, -10), width), ("B", (-40, 10), width)];
+    all.extend(ports);
+    Ok(offset_ports(origin, &all))
+}
+
+/// Place ports given by their offsets from the anchor
+fn offset_ports(origin: Location, ports: &[(&str, (i32, i32), BusWidth)]) -> Vec<PortLocation> {
+    ports
+        .iter()
+        .map(|&(name, (dx, dy), width)| PortLocation {
+            name: name.to_string(),
+            location: (origin.get_x() + dx, origin.get_y() + dy),
+            width,
+        })
+        .collect()
+}
+
+/// Check whether a memory component uses the classic appearance rather
+/// than the Logisim-Evolution one
+fn classic_appearance(comp: &ComponentInstance) -> bool {
+    attr(comp, "appearance") == Some("classic")
+}
+
+/// Ports of a flip-flop, ported from `AbstractFlipFlop.updatePorts`
+fn flip_flop_ports<L: FlipFlopLogic>(
+    comp: &ComponentInstance,
+    origin: Location,
+) -> Vec<PortLocation> {
+    let one = BusWidth(1);
+    let classic = classic_appearance(comp);
+    // The data inputs followed by the clock
+    let inputs: &[(i32, i32)] = match (classic, L::INPUTS.len()) {
+        (true, 1) => &[(-40, 20), (-40, 0)],
+        (true, _) => &[(-40, 0), (-40, 20), (-40, 10)],
+        (false, 1) => &[(-10, 10), (-10, 50)],
+        (false, _) => &[(-10, 10), (-10, 30), (-10, 50)],
+    };
+    let outputs = if classic {
+        [(0, 0), (0, 20), (-10, 30), (-30, 30)]
+    } else {
+        [(50, 10), (50, 50), (20, 60), (20, 0)]
+    };
+    let async_inputs = L::ASYNC_INPUTS;
+    let names = L::INPUTS
+        .iter()
+        .chain(&["CLK", "Q", "NQ"])
+        .chain(&async_inputs);
+    let ports: Vec<_> = names
+        .zip(inputs.iter().chain(&outputs))
+        .map(|(&name, &offset)| (name, offset, one))
+        .collect();
+    offset_ports(origin, &ports)
+}
+
+/// Ports of a register, ported from `Register.configurePorts`
+fn register_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let width = BusWidth(attr_u32(comp, "width", 8)?);
+    let one = BusWidth(1);
+    let ports = if classic_appearance(comp) {
+        [
+            ("Q", (0, 0), width),
+            ("D", (-30, 0), width),
+            ("CLK", (-20, 20), one),
+            ("CLR", (-10, 20), one),
+            ("EN", (-30, 10), one),
+        ]
+    } else {
+        [
+            ("Q", (60, 30), width),
+            ("D", (0, 30), width),
+            ("CLK", (0, 70), one),
+            ("CLR", (30, 90), one),
+            ("EN", (0, 50), one),
+        ]
+    };
+    Ok(offset_ports(origin, &ports))
+}
+
+/// Ports of a counter, ported from `Counter.configurePorts`
+fn counter_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let bits = attr_u32(comp, "width", 8)?;
+    let width = BusWidth(bits);
+    let one = BusWidth(1);
+    let ports = if classic_appearance(comp) {
+        [
+            ("OUT", (0, 0), width),
+            ("IN", (-30, 0), width),
+            ("CLK", (-20, 20), one),
+            ("CLR", (-10, 20), one),
+            ("LD", (-30, -10), one),
+            ("UD", (-20, -20), one),
+            ("EN", (-30, 10), one),
+            ("CARRY", (0, 10), one),
+        ]
+    } else {
+        let right = 190 + ((bits as i32 - 8) / 5) * 10;
+        let data_y = if bits == 1 { 120 } else { 110 };
+        [
+            ("OUT", (right, data_y), width),
+            ("IN", (0, data_y), width),
+            ("CLK", (0, 80), one),
+            ("CLR", (0, 20), one),
+            ("LD", (0, 30), one),
+            ("UD", (0, 50), one),
+            ("EN", (0, 70), one),
+            ("CARRY", (right, 50), one),
+        ]
+    };
+    Ok(offset_ports(origin, &ports))
+}
+
+/// Ports of a shift register, ported from `ShiftRegister.configurePorts`
+///
+/// With parallel load, the stage inputs are named `D0`, `D1`, ... and the
+/// stage outputs `Q0`, `Q1`, ...; the Logisim-Evolution appearance has no
+/// output for the last stage besides `Q`.
+fn shift_register_ports(
+    comp: &ComponentInstance,
+    origin: Location,
+) -> CircResult<Vec<PortLocation>> {
+    let width = attr_width(comp)?;
+    let length = attr_u32(comp, "length", 8)? as i32;
+    let parallel = attr(comp, "parallel") != Some("false");
+    let one = BusWidth(1);
+    let mut ports = Vec::new();
+    if classic_appearance(comp) {
+        let right = if parallel { 20 + 10 * length } else { 30 };
+        ports.extend([
+            ("Q".to_string(), (right, 0), width),
+            ("D".to_string(), (0, 0), width),
+            ("SH".to_string(), (0, -10), one),
+            ("CLK".to_string(), (0, 10), one),
+            ("CLR".to_string(), (10, 20), one),
+        ]);
+        if parallel {
+            ports.push(("LD".to_string(), (10, -20), one));
+            for i in 0..length {
+                ports.push((format!("D{}", i), (20 + 10 * i, -20), width));
+                ports.push((format!("Q{}", i), (20 + 10 * i, 20), width));
+            }
+        }
+    } else {
+        ports.extend([
+            ("Q".to_string(), (120, 70 + length * 20), width),
+            ("D".to_string(), (0, 80), width),
+            ("SH".to_string(), (0, 40), one),
+            ("CLK".to_string(), (0, 50), one),
+            ("CLR".to_string(), (0, 20), one),
+        ]);
+        if parallel {
+            ports.push(("LD".to_string(), (0, 30), one));
+            for i in 0..length {
+                ports.push((format!("D{}", i), (0, 90 + 20 * i), width));
+                if i < length - 1 {
+                    ports.push((format!("Q{}", i), (120, 90 + 20 * i), width));
+                }
+            }
+        }
+    }
+    Ok(ports
+        .into_iter()
+        .map(|(name, (dx, dy), width)| PortLocation {
+            name,
+            location: (origin.get_x() + dx, origin.get_y() + dy),
+            width,
+        })
+        .collect())
+}
+
+/// Ports of a single line RAM with a separate data bus and a clock, ported
+/// from `RamAppearance.configurePorts`
+///
+/// With line enables there is no `OE` input and the control inputs sit
+/// higher up on the classic appearance.
+fn ram_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let addr_width = BusWidth(attr_u32(comp, "addrWidth", 8)?);
+    let data_width = BusWidth(attr_u32(comp, "dataWidth", 8)?);
+    let classic = classic_appearance(comp);
+    let line_enables = attr(comp, "enables") == Some("line");
+    let one = BusWidth(1);
+    let control_height = if line_enables && classic { 60 } else { 90 };
+    let data_y = if !classic && data_width.0 == 1 {
+        control_height + 10
+    } else {
+        control_height
+    };
+    let mut ports = vec![
+        ("A", (0, 10), addr_width),
+        ("Q", (240, data_y), data_width),
+        ("D", (0, data_y), data_width),
+    ];
+    if line_enables && classic {
+        ports.extend([("WE", (0, 30), one), ("CLK", (0, 40), one)]);
+    } else if line_enables {
+        ports.extend([("WE", (0, 50), one), ("CLK", (0, 70), one)]);
+    } else {
+        ports.extend([
+            ("OE", (0, 60), one),
+            ("WE", (0, 50), one),
+            ("CLK", (0, 70), one),
+        ]);
+    }
+    if attr(comp, "clearpin") == Some("true") {
+        ports.push(("CLR", (40, 0), one));
+    }
+    Ok(offset_ports(origin, &ports))
+}
+
+/// Ports of a single line ROM, ported from `RamAppearance.configurePorts`
+fn rom_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let addr_width = BusWidth(attr_u32(comp, "addrWidth", 8)?);
+    let data_width = BusWidth(attr_u32(comp, "dataWidth", 8)?);
+    let (right, data_y) = if classic_appearance(comp) {
+        (240, 60)
+    } else if data_width.0 == 1 {
+        (250, 70)
+    } else {
+        (250, 60)
+    };
+    Ok(offset_ports(
+        origin,
+        &[
+            ("A", (0, 10), addr_width),
+            ("Q", (right, data_y), data_width),
+        ],
+    ))
+}
+
+/// Map every bit of a splitter's combined end to `(split end, bit within that end)`
+///
+/// Bits that the splitter leaves unconnected map to `None`. When a `bitN`
+/// attribute is absent the bits are distributed over the ends in order, as
+/// `SplitterAttributes.computeDistribution` does.
+pub fn splitter_bit_map(comp: &ComponentInstance) -> CircResult<Vec<Option<(usize, u32)>>> {
+    let fanout = attr_u32(comp, "fanout", 2)?.max(1);
+    let incoming = attr_u32(comp, "incoming", 2)?;
+    let defaults = default_distribution(fanout, incoming);
+
+    let mut end_widths = vec![0u32; fanout as usize];
+    let mut map = Vec::with_capacity(incoming as usize);
+    for bit in 0..incoming {
+        let end = match attr(comp, &format!("bit{}", bit)) {
+            Some("none") => None,
+            Some(value) => {
+                let end: u32 = value.trim().parse().map_err(|_| {
+                    CircFormatError::InvalidAttributeValue(format!("bit{} '{}'", bit, value))
+                })?;
+                Some(end.min(fanout - 1) as usize)
+            }
+            None => Some(defaults[bit as usize]),
+        };
+        map.push(end.map(|end| {
+            let thread = end_widths[end];
+            end_widths[end] += 1;
+            (end, thread)
+        }));
+    }
+    Ok(map)
+}
+
+/// Default assignment of `bits` bits to `fanout` ends, lowest bits first
+fn default_distribution(fanout: u32, bits: u32) -> Vec<usize> {
+    if fanout >= bits {
+        return (0..bits as usize).collect();
+    }
+    let per_end = bits / fanout;
+    let mut with_extra = bits % fanout;
+    let mut ends = Vec::with_capacity(bits as usize);
+    let mut end = 0;
+    let mut left_in_end = 0;
+    for _ in 0..bits {
+        if left_in_end == 0 {
+            if !ends.is_empty() {
+                end += 1;
+            }
+            left_in_end = per_end;
+            if with_extra > 0 {
+                left_in_end += 1;
+                with_extra -= 1;
+            }
+        }
+        ends.push(end);
+        left_in_end -= 1;
+    }
+    ends
+}
+
+/// Ports of a splitter, ported from `SplitterParameters`
+///
+/// The combined end is named `combined` and the split ends `end0`, `end1`, ...
+fn splitter_ports(comp: &ComponentInstance, origin: Location) -> CircResult<Vec<PortLocation>> {
+    let fanout = attr_u32(comp, "fanout", 2)?.max(1) as i32;
+    let incoming = attr_u32(comp, "incoming", 2)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let gap = attr_u32(comp, "spacing", 1)? as i32 * 10;
+    let justify = match attr(comp, "appear") {
+        Some("center") | Some("legacy") => 0,
+        Some("right") => 1,
+        _ => -1,
+    };
+
+    let width = 20;
+    let (end0_x, end0_y, delta_x, delta_y) =
+        if matches!(facing, Direction::North | Direction::South) {
+            let m = if facing == Direction::North { 1 } else { -1 };
+            let end0_x = if justify == 0 {
+                gap * ((fanout + 1) / 2 - 1)
+            } else if m * justify < 0 {
+                -10
+            } else {
+                10 + gap * (fanout - 1)
+            };
+            (end0_x, -m * width, -gap, 0)
+        } else {
+            let m = if facing == Direction::West { -1 } else { 1 };
+            let end0_y = if justify == 0 {
+                -gap * (fanout / 2)
+            } else if m * justify > 0 {
+                10
+            } else {
+                -(10 + gap * (fanout - 1))
+            };
+            (m * width, end0_y, 0, gap)
+        };
+
+    let mut end_widths = vec![0u32; fanout as usize];
+    for (end, _) in splitter_bit_map(comp)?.into_iter().flatten() {
+        end_widths[end] += 1;
+    }
+
+    let mut ports = vec![PortLocation {
+        name: "combined".to_string(),
+        location: comp.location,
+        width: BusWidth(incoming),
+    }];
+    for (i, end_width) in end_widths.into_iter().enumerate() {
+        let i = i as i32;
+        ports.push(PortLocation {
+            name: format!("end{}", i),
+            location: (
+                origin.get_x() + end0_x + i * delta_x,
+                origin.get_y() + end0_y + i * delta_y,
+            ),
+            width: BusWidth(end_width),
+        });
+    }
+    Ok(ports)
+}
+
+/// Check whether a `Pin` component is an output pin of its circuit
+pub(crate) fn is_output_pin(pin: &ComponentInstance) -> bool {
+    attr(pin, "type") == Some("output") || attr(pin, "output") == Some("true")
+}
+
+/// Compute the ports of a subcircuit instance in circuit coordinates
+///
+/// Every port is backed by a `Pin` of the child circuit; the returned pairs
+/// hold the index of that pin in `child.components`. Ports are placed by the
+/// child's appearance (`classic`, `logisim_evolution` or `custom`) and come
+/// in the same order as `CircuitAppearance.getPortOffsets`.
+pub fn subcircuit_ports(
+    comp: &ComponentInstance,
+    child: &CircuitDefinition,
+) -> CircResult<Vec<(usize, PortLocation)>> {
+    let pins: Vec<(usize, &ComponentInstance)> = child
+        .components
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.name == "Pin")
+        .collect();
+
+    let custom = child.appearance.as_ref().is_some_and(|a| a.custom);
+    let (default_facing, offsets) = match child.attributes.get("appearance").map(String::as_str) {
+        Some("custom") => custom_offsets(child, &pins)?,
+        None if custom => custom_offsets(child, &pins)?,
+        Some("logisim_evolution") => (Direction::East, evolution_offsets(child, &pins)),
+        Some("classic") | None => (Direction::East, classic_offsets(&pins)?),
+        Some(other) => {
+            return Err(CircFormatError::UnsupportedComponent(format!(
+                "Appearance '{}' of subcircuit '{}'",
+                other, child.name
+            )))
+        }
+    };
+
+    let facing = attr_facing(comp, Direction::East)?;
+    let mut ports = Vec::with_capacity(offsets.len());
+    for (index, offset) in offsets {
+        let pin = &child.components[index];
+        let offset = offset.rotate(default_facing, facing, 0, 0);
+        let name = match attr(pin, "label") {
+            Some(label) if !label.is_empty() => label.to_string(),
+            _ => format!("({},{})", pin.location.0, pin.location.1),
+        };
+        ports.push((
+            (offset.get_x(), offset.get_y()),
+            index,
+            PortLocation {
+                name,
+                location: (
+                    comp.location.0 + offset.get_x(),
+                    comp.location.1 + offset.get_y(),
+                ),
+                width: attr_width(pin)?,
+            },
+        ));
+    }
+    ports.sort_by_key(|(offset, _, _)| *offset);
+    Ok(ports
+        .into_iter()
+        .map(|(_, index, port)| (index, port))
+        .collect())
+}
+
+/// Pin offsets from the anchor in the classic rectangular appearance, ported
+/// from `DefaultClassicAppearance`
+fn classic_offsets(pins: &[(usize, &ComponentInstance)]) -> CircResult<Vec<(usize, Location)>> {
+    // Each pin sits on the edge of the box opposite to the way it faces
+    let mut edges: HashMap<Direction, Vec<(usize, Location)>> = HashMap::new();
+    for &(index, pin) in pins {
+        let edge = attr_facing(pin, Direction::East)?.reverse();
+        let location = Location::new(pin.location.0, pin.location.1);
+        edges.entry(edge).or_default().push((index, location));
+    }
+    for (edge, pins) in edges.iter_mut() {
+        if edge.is_vertical() {
+            pins.sort_by_key(|(_, loc)| (loc.get_x(), loc.get_y()));
+        } else {
+            pins.sort_by_key(|(_, loc)| (loc.get_y(), loc.get_x()));
+        }
+    }
+    let count = |edge| edges.get(&edge).map_or(0, Vec::len) as i32;
+    let (north, south) = (count(Direction::North), count(Direction::South));
+    let (east, west) = (count(Direction::East), count(Direction::West));
+    let max_vert = north.max(south);
+    let max_horz = east.max(west);
+
+    let dimension = |max_this: i32, max_others: i32| {
+        if max_this < 3 {
+            30
+        } else if max_others == 0 {
+            10 * max_this
+        } else {
+            10 * max_this + 10
+        }
+    };
+    let offset = |facing: i32, opposite: i32, max_others: i32| {
+        let max_this = facing.max(opposite);
+        let max_offs = match max_this {
+            0 | 1 if max_others == 0 => 15,
+            0..=2 => 10,
+            _ if max_others == 0 => 5,
+            _ => 10,
+        };
+        max_offs + 10 * ((max_this - facing) / 2)
+    };
+    let offs_north = offset(north, south, max_horz);
+    let offs_south = offset(south, north, max_horz);
+    let offs_east = offset(east, west, max_vert);
+    let offs_west = offset(west, east, max_vert);
+    let width = dimension(max_vert, max_horz);
+    let height = dimension(max_horz, max_vert);
+
+    // Position of the anchor relative to the top left corner of the box
+    let (ax, ay) = if east > 0 {
+        (width, offs_east)
+    } else if north > 0 {
+        (offs_north, 0)
+    } else if west > 0 {
+        (0, offs_west)
+    } else if south > 0 {
+        (offs_south, height)
+    } else {
+        (0, 0)
+    };
+
+    let starts = [
+        (Direction::West, (0, offs_west), (0, 10)),
+        (Direction::East, (width, offs_east), (0, 10)),
+        (Direction::North, (offs_north, 0), (10, 0)),
+        (Direction::South, (offs_south, height), (10, 0)),
+    ];
+    let mut offsets = Vec::new();
+    for (edge, (x, y), (dx, dy)) in starts {
+        for (i, &(index, _)) in edges.get(&edge).into_iter().flatten().enumerate() {
+            let i = i as i32;
+            offsets.push((index, Location::new(x + i * dx - ax, y + i * dy - ay)));
+        }
+    }
+    Ok(offsets)
+}
+
+/// Pin offsets from the anchor in the default Logisim-Evolution appearance,
+/// ported from `DefaultEvolutionAppearance`
+///
+/// Inputs line up on the left edge and outputs on the right edge, 20 units
+/// apart; the box width follows the label lengths unless the circuit keeps
+/// the fixed-size box.
+fn evolution_offsets(
+    child: &CircuitDefinition,
+    pins: &[(usize, &ComponentInstance)],
+) -> Vec<(usize, Location)> {
+    // Metrics of the fixed-pitch font the Java implementation sizes boxes with
+    const CHAR_WIDTH: i32 = 8;
+    const PORT_SPACING: i32 = 20;
+
+    let label_width = |pin: &ComponentInstance| {
+        attr(pin, "label").map_or(0, |label| label.chars().count() as i32 * CHAR_WIDTH)
+    };
+    let (mut east, mut west): (Vec<_>, Vec<_>) = pins
+        .iter()
+        .copied()
+        .partition(|(_, pin)| is_output_pin(pin));
+    for side in [&mut east, &mut west] {
+        side.sort_by_key(|(_, pin)| (pin.location.1, pin.location.0));
+    }
+
+    let fixed_size = child
+        .attributes
+        .get("circuitnamedboxfixedsize")
+        .is_none_or(|value| value != "false");
+    let text_width = if fixed_size {
+        25 * CHAR_WIDTH
+    } else {
+        let max_left = west.iter().map(|(_, p)| label_width(p)).max().unwrap_or(0);
+        let max_right = east.iter().map(|(_, p)| label_width(p)).max().unwrap_or(0);
+        let title_width = child.name.chars().count() as i32 * CHAR_WIDTH;
+        (max_left + max_right + 35).max(title_width + 15)
+    };
+    let width = (text_width / 10) * 10 + 20;
+
+    // The anchor sits on the first output row, or the first input row when
+    // there are no outputs
+    let anchor_x = if east.is_empty() { 0 } else { width };
+    let mut offsets = Vec::new();
+    for (x, side) in [(0, &west), (width, &east)] {
+        for (i, &(index, _)) in side.iter().enumerate() {
+            offsets.push((index, Location::new(x - anchor_x, i as i32 * PORT_SPACING)));
+        }
+    }
+    offsets
+}
+
+/// Pin offsets from the anchor in a custom appearance, read from its
+/// `circ-port` and `circ-anchor` elements as `AppearanceSvgReader` does
+fn custom_offsets(
+    child: &CircuitDefinition,
+    pins: &[(usize, &ComponentInstance)],
+) -> CircResult<(Direction, Vec<(usize, Location)>)> {
+    let elements = child
+        .appearance
+        .as_ref()
+        .map_or(&[][..], |a| a.elements.as_slice());
+    let invalid = |what: &str| {
+        CircFormatError::InvalidAttributeValue(format!(
+            "{} in the appearance of circuit '{}'",
+            what, child.name
+        ))
+    };
+    let number = |element: &AppearanceElement, name: &str| -> CircResult<Option<f64>> {
+        element
+            .attributes
+            .get(name)
+            .map(|value| value.trim().parse().map_err(|_| invalid(name)))
+            .transpose()
+    };
+    // Elements are centred on their location when they carry a size
+    let location = |element: &AppearanceElement| -> CircResult<Location> {
+        let x = number(element, "x")?.ok_or_else(|| invalid("x"))?;
+        let y = number(element, "y")?.ok_or_else(|| invalid("y"))?;
+        let (w, h) = match (number(element, "width")?, number(element, "height")?) {
+            (Some(w), Some(h)) => (w, h),
+            _ => (0.0, 0.0),
+        };
+        Ok(Location::new(
+            (x + w / 2.0).round() as i32,
+            (y + h / 2.0).round() as i32,
+        ))
+    };
+
+    let mut anchor = Location::new(0, 0);
+    let mut facing = Direction::East;
+    let mut used = vec![false; pins.len()];
+    let mut offsets = Vec::new();
+    for element in elements {
+        match element.element_type.as_str() {
+            "circ-anchor" | "circ-origin" => {
+                anchor = location(element)?;
+                if let Some(value) = element.attributes.get("facing") {
+                    facing =
+                        Direction::parse(value).map_err(CircFormatError::InvalidAttributeValue)?;
+                }
+            }
+            "circ-port" => {
+                let pin_location = element
+                    .attributes
+                    .get("pin")
+                    .and_then(|pin| Location::parse(pin).ok())
+                    .ok_or_else(|| invalid("circ-port pin"))?;
+                // Older files mark inputs by the size of the port symbol
+                let input = match element.attributes.get("dir") {
+                    Some(dir) => dir == "in",
+                    None => number(element, "width")? == Some(8.0),
+                };
+                let found = pins.iter().enumerate().find(|(i, (_, pin))| {
+                    !used[*i]
+                        && pin.location == (pin_location.get_x(), pin_location.get_y())
+                        && is_output_pin(pin) != input
+                });
+                if let Some((i, &(index, _))) = found {
+                    used[i] = true;
+                    offsets.push((index, location(element)?));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let offsets = offsets
+        .into_iter()
+        .map(|(index, loc)| {
+            (
+                index,
+                Location::new(loc.get_x() - anchor.get_x(), loc.get_y() - anchor.get_y()),
+            )
+        })
+        .collect();
+    Ok((facing, offsets))
+}
+
+/// Disjoint-set forest over circuit points
+#[derive(Debug, Default)]
+struct PointSets {
+    index: HashMap<(i32, i32), usize>,
+    parent: Vec<usize>,
+}
+
+impl PointSets {
+    fn add(&mut self, point: (i32, i32)) -> usize {
+        if let Some(&i) = self.index.get(&point) {
+            return i;
+        }
+        let i = self.parent.len();
+        self.parent.push(i);
+        self.index.insert(point, i);
+        i
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: (i32, i32), b: (i32, i32)) {
+        let (a, b) = (self.add(a), self.add(b));
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[rb] = ra;
+        }
+    }
+}
+
+/// The nets of a circuit: groups of points that are electrically connected
+#[derive(Debug, Clone, Default)]
+pub struct CircuitNets {
+    /// Points of every net, sorted
+    nets: Vec<Vec<(i32, i32)>>,
+    /// Net index of every known point
+    net_of: HashMap<(i32, i32), usize>,
+}
+
+impl CircuitNets {
+    /// Group wire endpoints, component ports and tunnels into nets
+    ///
+    /// Two points are connected when a wire runs between them, when one of
+    /// them lies on a wire leading to the other (T-junctions and overlapping
+    /// collinear segments), or when they are tunnels with the same label.
+    /// Wires that merely cross are not connected, as in Logisim.
+    pub fn extract(
+        wires: &[WireConnection],
+        ports: impl IntoIterator<Item = (i32, i32)>,
+        tunnels: &[(String, (i32, i32))],
+    ) -> Self {
+        let mut sets = PointSets::default();
+        for wire in wires {
+            sets.union(wire.from, wire.to);
+        }
+        for point in ports {
+            sets.add(point);
+        }
+
+        // Points lying inside a segment join that segment's net
+        let mut horizontal: HashMap<i32, Vec<(i32, i32)>> = HashMap::new();
+        let mut vertical: HashMap<i32, Vec<(i32, i32)>> = HashMap::new();
+        for wire in wires {
+            let ((x0, y0), (x1, y1)) = (wire.from, wire.to);
+            if y0 == y1 {
+                horizontal
+                    .entry(y0)
+                    .or_default()
+                    .push((x0.min(x1), x0.max(x1)));
+            } else if x0 == x1 {
+                vertical
+                    .entry(x0)
+                    .or_default()
+                    .push((y0.min(y1), y0.max(y1)));
+            }
+        }
+        let points: Vec<(i32, i32)> = sets.index.keys().copied().collect();
+        for (x, y) in points {
+            for &(x0, x1) in horizontal.get(&y).into_iter().flatten() {
+                if x0 < x && x < x1 {
+                    sets.union((x, y), (x0, y));
+                }
+            }
+            for &(y0, y1) in vertical.get(&x).into_iter().flatten() {
+                if y0 < y && y < y1 {
+                    sets.union((x, y), (x, y0));
+                }
+            }
+        }
+
+        let mut by_label: HashMap<&str, (i32, i32)> = HashMap::new();
+        for (label, point) in tunnels {
+            sets.add(*point);
+            if let Some(&first) = by_label.get(label.as_str()) {
+                sets.union(first, *point);
+            } else {
+                by_label.insert(label, *point);
+            }
+        }
+
+        let mut points: Vec<(i32, i32)> = sets.index.keys().copied().collect();
+        points.sort();
+        let mut root_net = HashMap::new();
+        let mut nets: Vec<Vec<(i32, i32)>> = Vec::new();
+        let mut net_of = HashMap::new();
+        for point in points {
+            let root = sets.find(sets.index[&point]);
+            let net = *root_net.entry(root).or_insert_with(|| {
+                nets.push(Vec::new());
+                nets.len() - 1
+            });
+            nets[net].push(point);
+            net_of.insert(point, net);
+        }
+        CircuitNets { nets, net_of }
+    }
+
+    /// Get the net a point belongs to
+    pub fn net_of(&self, point: (i32, i32)) -> Option<usize> {
+        self.net_of.get(&point).copied()
+    }
+
+    /// Get the points of a net
+    pub fn points(&self, net: usize) -> &[(i32, i32)] {
+        &self.nets[net]
+    }
+
+    /// Get the number of nets
+    pub fn len(&self) -> usize {
+        self.nets.len()
+    }
+
+    /// Check if there are no nets at all
+    pub fn is_empty(&self) -> bool {
+        self.nets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comp(name: &str, location: (i32, i32), attrs: &[(&str, &str)]) -> ComponentInstance {
+        ComponentInstance {
+            library: None,
+            name: name.to_string(),
+            location,
+            attributes: attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            facing: None,
+        }
+    }
+
+    fn wire(from: (i32, i32), to: (i32, i32)) -> WireConnection {
+        WireConnection { from, to }
+    }
+
+    fn locations(ports: &[PortLocation]) -> Vec<(&str, (i32, i32))> {
+        ports
+            .iter()
+            .map(|port| (port.name.as_str(), port.location))
+            .collect()
+    }
+
+    #[test]
+    fn test_gate_port_geometry() {
+        let and = component_ports(&comp("AND Gate", (100, 100), &[])).unwrap();
+        assert_eq!(
+            locations(&and),
+            vec![("Y", (100, 100)), ("A", (50, 80)), ("B", (50, 120))]
+        );
+
+        let narrow = comp(
+            "NAND Gate",
+            (100, 100),
+            &[("size", "30"), ("inputs", "3"), ("facing", "south")],
+        );
+        assert_eq!(
+            locations(&component_ports(&narrow).unwrap()),
+            vec![
+                ("Y", (100, 100)),
+                ("A", (90, 60)),
+                ("B", (100, 60)),
+                ("I2", (110, 60))
+            ]
+        );
+
+        let xor = component_ports(&comp("XOR Gate", (100, 100), &[("width", "8")])).unwrap();
+        assert_eq!(xor[1].location, (40, 80));
+        assert_eq!(xor[1].width, BusWidth(8));
+    }
+
+    #[test]
+    fn test_buffer_port_geometry() {
+        let not = component_ports(&comp("NOT Gate", (100, 100), &[("facing", "west")])).unwrap();
+        assert_eq!(locations(&not), vec![("Y", (100, 100)), ("A", (130, 100))]);
+
+        let buffer = comp("Controlled Buffer", (100, 100), &[("control", "left")]);
+        assert_eq!(
+            locations(&component_ports(&buffer).unwrap()),
+            vec![("Y", (100, 100)), ("A", (80, 100)), ("EN", (90, 90))]
+        );
+        let inverter = comp("Controlled Inverter", (100, 100), &[]);
+        assert_eq!(
+            locations(&component_ports(&inverter).unwrap()),
+            vec![("Y", (100, 100)), ("A", (70, 100)), ("EN", (80, 110))]
+        );
+        let buffer = comp("Buffer", (100, 100), &[("facing", "north")]);
+        assert_eq!(
+            locations(&component_ports(&buffer).unwrap()),
+            vec![("Y", (100, 100)), ("A", (100, 120))]
+        );
+
+        let extender = comp("Bit Extender", (100, 100), &[("type", "input")]);
+        let ports = component_ports(&extender).unwrap();
+        assert_eq!(
+            locations(&ports),
+            vec![("out", (100, 100)), ("in", (60, 100)), ("extend", (80, 80))]
+        );
+        assert_eq!(
+            (ports[0].width, ports[1].width),
+            (BusWidth(16), BusWidth(8))
+        );
+        assert!(component_ports(&comp("Button", (0, 0), &[])).is_err());
+    }
+
+    #[test]
+    fn test_arith_port_geometry() {
+        let adder = component_ports(&comp("Adder", (100, 100), &[("width", "4")])).unwrap();
+        assert_eq!(
+            locations(&adder),
+            vec![
+                ("A", (60, 90)),
+                ("B", (60, 110)),
+                ("Sum", (100, 100)),
+                ("Carry_In", (80, 80)),
+                ("Carry_Out", (80, 120))
+            ]
+        );
+        assert_eq!(adder[2].width, BusWidth(4));
+        assert_eq!(adder[3].width, BusWidth(1));
+
+        let divider = component_ports(&comp("Divider", (100, 100), &[])).unwrap();
+        assert_eq!(
+            locations(&divider)[2..],
+            [
+                ("Quotient", (100, 100)),
+                ("Upper", (80, 80)),
+                ("Remainder", (80, 120))
+            ]
+        );
+
+        let shifter = component_ports(&comp("Shifter", (100, 100), &[("width", "16")])).unwrap();
+        assert_eq!(shifter[1].location, (60, 110));
+        assert_eq!(shifter[1].width, BusWidth(4));
+
+        let adder = comp("BitAdder", (100, 100), &[("width", "4"), ("inputs", "2")]);
+        let ports = component_ports(&adder).unwrap();
+        assert_eq!(
+            locations(&ports),
+            vec![
+                ("Output", (100, 100)),
+                ("Input_0", (60, 90)),
+                ("Input_1", (60, 110))
+            ]
+        );
+        assert_eq!(ports[0].width, BusWidth(4));
+
+        let finder = component_ports(&comp("BitFinder", (100, 100), &[])).unwrap();
+        assert_eq!(finder[0].location, (80, 120));
+        assert_eq!(finder[1].width, BusWidth(3));
+
+        let comparator = component_ports(&comp("Comparator", (100, 100), &[])).unwrap();
+        assert_eq!(
+            locations(&comparator)[2..],
+            [
+                ("Greater", (100, 90)),
+                ("Equal", (100, 100)),
+                ("Less", (100, 110))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plexer_and_switch_port_geometry() {
+        let mux = comp("Multiplexer", (100, 100), &[("enable", "true")]);
+        assert_eq!(
+            locations(&component_ports(&mux).unwrap()),
+            vec![
+                ("input_0", (70, 90)),
+                ("input_1", (70, 110)),
+                ("select", (80, 120)),
+                ("enable", (90, 120)),
+                ("output", (100, 100))
+            ]
+        );
+
+        let decoder = comp("Decoder", (100, 100), &[]);
+        assert_eq!(
+            locations(&component_ports(&decoder).unwrap()),
+            vec![
+                ("output_0", (110, 70)),
+                ("output_1", (110, 90)),
+                ("select", (100, 100)),
+                ("enable", (90, 100))
+            ]
+        );
+        let decoder = comp(
+            "Decoder",
+            (100, 100),
+            &[("select", "2"), ("facing", "south"), ("enable", "false")],
+        );
+        assert_eq!(
+            locations(&component_ports(&decoder).unwrap()),
+            vec![
+                ("output_0", (100, 120)),
+                ("output_1", (110, 120)),
+                ("output_2", (120, 120)),
+                ("output_3", (130, 120)),
+                ("select", (100, 100))
+            ]
+        );
+
+        let demux = comp("Demultiplexer", (100, 100), &[("enable", "true")]);
+        assert_eq!(
+            locations(&component_ports(&demux).unwrap()),
+            vec![
+                ("output_0", (130, 90)),
+                ("output_1", (130, 110)),
+                ("select", (120, 120)),
+                ("enable", (110, 120)),
+                ("input", (100, 100))
+            ]
+        );
+
+        let encoder = comp("Priority Encoder", (100, 100), &[("select", "2")]);
+        assert_eq!(
+            locations(&component_ports(&encoder).unwrap())[3..],
+            [
+                ("input_3", (60, 120)),
+                ("output", (100, 100)),
+                ("enable_in", (80, 130)),
+                ("enable_out", (80, 80)),
+                ("group_signal", (100, 110))
+            ]
+        );
+
+        let selector = comp("BitSelector", (100, 100), &[("group", "4")]);
+        let ports = component_ports(&selector).unwrap();
+        assert_eq!(
+            locations(&ports),
+            vec![
+                ("output", (100, 100)),
+                ("input", (70, 100)),
+                ("select", (90, 110))
+            ]
+        );
+        assert_eq!(ports[2].width, BusWidth(1));
+
+        let transistor = comp("Transistor", (100, 100), &[("width", "4")]);
+        let ports = component_ports(&transistor).unwrap();
+        assert_eq!(
+            locations(&ports),
+            vec![
+                ("drain", (100, 100)),
+                ("source", (60, 100)),
+                ("gate", (80, 80))
+            ]
+        );
+        assert_eq!(ports[1].width, BusWidth(4));
+
+        // Ports that take the width of their net
+        let probe = component_ports(&comp("Probe", (10, 10), &[])).unwrap();
+        assert_eq!(probe[0].width, BusWidth(0));
+    }
+
+    #[test]
+    fn test_memory_port_geometry() {
+        let register = comp("Register", (100, 100), &[("width", "4")]);
+        let ports = component_ports(&register).unwrap();
+        assert_eq!(
+            locations(&ports)[..2],
+            [("Q", (160, 130)), ("D", (100, 130))]
+        );
+        assert_eq!(ports[0].width, BusWidth(4));
+
+        let classic = comp("D Flip-Flop", (100, 100), &[("appearance", "classic")]);
+        assert_eq!(
+            locations(&component_ports(&classic).unwrap())[..3],
+            [("D", (60, 120)), ("CLK", (60, 100)), ("Q", (100, 100))]
+        );
+        let classic = comp("J-K Flip-Flop", (100, 100), &[("appearance", "classic")]);
+        assert_eq!(
+            locations(&component_ports(&classic).unwrap())[..3],
+            [("J", (60, 100)), ("K", (60, 120)), ("CLK", (60, 110))]
+        );
+        let evolution = comp("S-R Flip-Flop", (100, 100), &[]);
+        assert_eq!(
+            locations(&component_ports(&evolution).unwrap())[2..],
+            [
+                ("CLK", (90, 150)),
+                ("Q", (150, 110)),
+                ("NQ", (150, 150)),
+                ("CLR", (120, 160)),
+                ("PRE", (120, 100))
+            ]
+        );
+
+        let random = component_ports(&comp("Random", (100, 100), &[("width", "4")])).unwrap();
+        assert_eq!(
+            locations(&random),
+            vec![
+                ("OUT", (180, 180)),
+                ("CLK", (100, 150)),
+                ("EN", (100, 140)),
+                ("RST", (100, 130))
+            ]
+        );
+        assert_eq!(random[0].width, BusWidth(4));
+
+        // The clear input only exists when enabled
+        let ram = |clear| comp("RAM", (0, 0), &[("clearpin", clear)]);
+        assert_eq!(component_ports(&ram("false")).unwrap().len(), 6);
+        assert_eq!(component_ports(&ram("true")).unwrap().len(), 7);
+        let ram = comp(
+            "RAM",
+            (0, 0),
+            &[("appearance", "classic"), ("enables", "line")],
+        );
+        assert_eq!(
+            locations(&component_ports(&ram).unwrap()),
+            vec![
+                ("A", (0, 10)),
+                ("Q", (240, 60)),
+                ("D", (0, 60)),
+                ("WE", (0, 30)),
+                ("CLK", (0, 40))
+            ]
+        );
+
+        let rom = comp("ROM", (0, 0), &[("appearance", "classic")]);
+        assert_eq!(
+            locations(&component_ports(&rom).unwrap()),
+            vec![("A", (0, 10)), ("Q", (240, 60))]
+        );
+    }
+
+    #[test]
+    fn test_splitter_geometry_and_bits() {
+        let splitter = comp(
+            "Splitter",
+            (100, 100),
+            &[("fanout", "2"), ("incoming", "3"), ("bit2", "none")],
+        );
+        assert_eq!(
+            locations(&component_ports(&splitter).unwrap()),
+            vec![
+                ("combined", (100, 100)),
+                ("end0", (120, 80)),
+                ("end1", (120, 90))
+            ]
+        );
+        assert_eq!(
+            splitter_bit_map(&splitter).unwrap(),
+            vec![Some((0, 0)), Some((0, 1)), None]
+        );
+
+        let wide = comp("Splitter", (0, 0), &[("fanout", "2"), ("incoming", "5")]);
+        let ends: Vec<_> = splitter_bit_map(&wide)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.unwrap().0)
+            .collect();
+        assert_eq!(ends, vec![0, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_net_extraction() {
+        let wires = [
+            wire((0, 0), (100, 0)),
+            // T-junction onto the middle of the first wire
+            wire((50, 0), (50, 50)),
+            // Crosses the first wire without connecting to it
+            wire((70, -20), (70, 20)),
+            // Overlaps the first wire
+            wire((90, 0), (150, 0)),
+        ];
+        let tunnels = [
+            ("CLK".to_string(), (150, 0)),
+            ("CLK".to_string(), (300, 300)),
+            ("RST".to_string(), (400, 400)),
+        ];
+        let nets = CircuitNets::extract(&wires, [(50, 50), (70, 20), (300, 300)], &tunnels);
+
+        let main = nets.net_of((0, 0)).unwrap();
+        assert_eq!(nets.net_of((50, 50)), Some(main));
+        assert_eq!(nets.net_of((150, 0)), Some(main));
+        assert_eq!(nets.net_of((300, 300)), Some(main));
+        assert_ne!(nets.net_of((70, 20)), Some(main));
+        assert_ne!(nets.net_of((400, 400)), Some(main));
+        assert_eq!(nets.net_of((1, 1)), None);
+        assert_eq!(nets.len(), 3);
+    }
+
+    fn circuit(appearance: &str, elements: Vec<AppearanceElement>) -> CircuitDefinition {
+        let input = |x, y, label| comp("Pin", (x, y), &[("label", label)]);
+        CircuitDefinition {
+            name: "child".to_string(),
+            components: vec![
+                input(100, 100, "A"),
+                comp("AND Gate", (200, 110), &[]),
+                comp(
+                    "Pin",
+                    (200, 110),
+                    &[("type", "output"), ("facing", "west"), ("label", "Y")],
+                ),
+                input(100, 120, "B"),
+            ],
+            wires: Vec::new(),
+            appearance: Some(crate::circ_format::CircuitAppearance {
+                custom: !elements.is_empty(),
+                elements,
+            }),
+            attributes: [("appearance".to_string(), appearance.to_string())].into(),
+        }
+    }
+
+    fn element(element_type: &str, attrs: &[(&str, &str)]) -> AppearanceElement {
+        AppearanceElement {
+            element_type: element_type.to_string(),
+            attributes: attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            text: None,
+        }
+    }
+
+    #[test]
+    fn test_subcircuit_port_geometry() {
+        let instance = comp("child", (300, 200), &[]);
+        let ports = |child: &CircuitDefinition, instance: &ComponentInstance| {
+            subcircuit_ports(instance, child)
+                .unwrap()
+                .into_iter()
+                .map(|(pin, port)| (pin, port.location))
+                .collect::<Vec<_>>()
+        };
+
+        let classic = circuit("classic", Vec::new());
+        assert_eq!(
+            ports(&classic, &instance),
+            vec![(0, (270, 200)), (3, (270, 210)), (2, (300, 200))]
+        );
+        let rotated = comp("child", (300, 200), &[("facing", "south")]);
+        assert_eq!(
+            ports(&classic, &rotated),
+            vec![(3, (290, 170)), (0, (300, 170)), (2, (300, 200))]
+        );
+
+        let evolution = circuit("logisim_evolution", Vec::new());
+        assert_eq!(
+            ports(&evolution, &instance),
+            vec![(0, (80, 200)), (3, (80, 220)), (2, (300, 200))]
+        );
+
+        let custom = circuit(
+            "custom",
+            vec![
+                element(
+                    "circ-anchor",
+                    &[("x", "47"), ("y", "47"), ("width", "6"), ("height", "6")],
+                ),
+                element(
+                    "circ-port",
+                    &[("pin", "100,100"), ("dir", "in"), ("x", "30"), ("y", "50")],
+                ),
+                element(
+                    "circ-port",
+                    &[
+                        ("pin", "200,110"),
+                        ("x", "45"),
+                        ("y", "45"),
+                        ("width", "10"),
+                        ("height", "10"),
+                    ],
+                ),
+            ],
+        );
+        assert_eq!(
+            ports(&custom, &instance),
+            vec![(0, (280, 200)), (2, (300, 200))]
+        );
+    }
+}
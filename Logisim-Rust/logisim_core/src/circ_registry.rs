@@ -0,0 +1,855 @@
+//! Component factories for .circ files
+//!
+//! Every component placed in a .circ file names the library it comes from
+//! (`#Wiring`, `#Gates`, `#Memory`, ...) and its tool name within that
+//! library. A [`ComponentRegistry`] maps each such pair to a constructor
+//! that builds the simulation component from the instance's
+//! `<a name=... val=...>` attributes.
+//!
+//! [`ComponentRegistry::standard`] covers the standard library components
+//! that have a simulation model. Anything else is reported as an
+//! [`UnsupportedComponent`] when a project is loaded.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::circ_format::{CircFormatError, CircResult, ComponentInstance, RomContents};
+use crate::circ_netlist::{attr, attr_facing, attr_hex, attr_u32, attr_width, is_output_pin};
+use crate::comp::{Component, ComponentId};
+use crate::data::Direction;
+use crate::signal::{BusWidth, Value};
+use crate::std::arith::{
+    Adder, BitAdder, BitFinder, BitFinderType, Comparator, Divider, Multiplier, Negator, ShiftType,
+    Shifter, Subtractor,
+};
+use crate::std::gates::{
+    AndGate, Buffer, ControlledBuffer, EvenParityGate, NandGate, NorGate, NotGate, OddParityGate,
+    OrGate, XnorGate, XorGate,
+};
+use crate::std::memory::{
+    Counter, DLogic, FlipFlop, FlipFlopLogic, JKLogic, MemContents, OnGoal, Ram, Random, Register,
+    Rom, SRLogic, ShiftRegister, TLogic, Trigger,
+};
+use crate::std::plexers::{BitSelector, Decoder, Demultiplexer, Multiplexer, PriorityEncoder};
+use crate::std::wiring::{
+    BitExtender, BitExtension, Clock, Constant, DoNotConnect, Ground, Pin, PinBehavior, PinType,
+    Power, PowerOnReset, Probe, PullResistor, Transistor, TransistorType, TransmissionGate,
+};
+
+/// Builds the simulation component for a placed component instance
+///
+/// Returns `None` for components that only shape the netlist (tunnels,
+/// splitters) or have no electrical function (text).
+pub type ComponentConstructor =
+    fn(&ComponentInstance, ComponentId) -> CircResult<Option<Box<dyn Component>>>;
+
+/// A placed component that cannot be simulated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedComponent {
+    /// Circuit the component is placed in
+    pub circuit: String,
+    /// Library descriptor, such as `#Arithmetic`
+    pub library: String,
+    /// Tool name within the library
+    pub name: String,
+    /// Location of the component in its circuit
+    pub location: (i32, i32),
+    /// Why the component cannot be built when its kind has a factory, such
+    /// as an attribute setting the simulation model lacks
+    pub reason: Option<String>,
+}
+
+impl fmt::Display for UnsupportedComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} at ({},{}) in circuit '{}'",
+            self.library, self.name, self.location.0, self.location.1, self.circuit
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Component constructors keyed by library descriptor and component name
+#[derive(Clone, Default)]
+pub struct ComponentRegistry {
+    factories: HashMap<(String, String), ComponentConstructor>,
+}
+
+impl ComponentRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with every standard library component that has a
+    /// simulation model
+    pub fn standard() -> Self {
+        let mut registry = Self::new();
+
+        registry.register("#Base", "Text", |_, _| Ok(None));
+
+        registry.register("#Wiring", "Pin", pin);
+        registry.register("#Wiring", "Constant", constant);
+        registry.register("#Wiring", "Power", |comp, id| {
+            let mut power = Power::new(id);
+            power.set_width(attr_width(comp)?);
+            Ok(Some(Box::new(power)))
+        });
+        registry.register("#Wiring", "Ground", |comp, id| {
+            let mut ground = Ground::new(id);
+            ground.set_width(attr_width(comp)?);
+            Ok(Some(Box::new(ground)))
+        });
+        registry.register("#Wiring", "Clock", clock);
+        registry.register("#Wiring", "Tunnel", |_, _| Ok(None));
+        registry.register("#Wiring", "Splitter", |_, _| Ok(None));
+        registry.register("#Wiring", "Probe", |comp, id| {
+            let mut probe = Probe::new(id);
+            probe.set_width(attr_width(comp)?);
+            Ok(Some(Box::new(probe)))
+        });
+        registry.register("#Wiring", "Pull Resistor", pull_resistor);
+        registry.register("#Wiring", "Transistor", |comp, id| {
+            let transistor_type = match attr(comp, "type") {
+                Some("n") => TransistorType::N,
+                _ => TransistorType::P,
+            };
+            let mut transistor = Transistor::new_with_type(id, transistor_type);
+            transistor.set_width(attr_width(comp)?);
+            Ok(Some(Box::new(transistor)))
+        });
+        registry.register("#Wiring", "Transmission Gate", |comp, id| {
+            let mut gate = TransmissionGate::new(id);
+            gate.set_width(attr_width(comp)?);
+            Ok(Some(Box::new(gate)))
+        });
+        // The Java duration is wall-clock time after the simulation starts,
+        // so the default pulse length is kept
+        registry.register("#Wiring", "POR", |_, id| {
+            Ok(Some(Box::new(PowerOnReset::new(id))))
+        });
+        registry.register("#Wiring", "NoConnect", |comp, id| {
+            let mut pin = DoNotConnect::new(id);
+            pin.set_width(attr_width(comp)?);
+            Ok(Some(Box::new(pin)))
+        });
+        registry.register("#Wiring", "Bit Extender", bit_extender);
+
+        registry.register("#Gates", "AND Gate", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                AndGate::new_with_inputs,
+                AndGate::set_width,
+                AndGate::set_negated,
+            )
+        });
+        registry.register("#Gates", "OR Gate", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                OrGate::new_with_inputs,
+                OrGate::set_width,
+                OrGate::set_negated,
+            )
+        });
+        registry.register("#Gates", "NAND Gate", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                NandGate::new_with_inputs,
+                NandGate::set_width,
+                NandGate::set_negated,
+            )
+        });
+        registry.register("#Gates", "NOR Gate", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                NorGate::new_with_inputs,
+                NorGate::set_width,
+                NorGate::set_negated,
+            )
+        });
+        registry.register("#Gates", "XOR Gate", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                XorGate::new_with_inputs,
+                XorGate::set_width,
+                XorGate::set_negated,
+            )
+        });
+        registry.register("#Gates", "XNOR Gate", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                XnorGate::new_with_inputs,
+                XnorGate::set_width,
+                XnorGate::set_negated,
+            )
+        });
+        registry.register("#Gates", "NOT Gate", |comp, id| {
+            Ok(Some(Box::new(NotGate::new_with_width(
+                id,
+                attr_width(comp)?,
+            ))))
+        });
+        registry.register("#Gates", "Controlled Buffer", |comp, id| {
+            let width = attr_width(comp)?;
+            Ok(Some(Box::new(ControlledBuffer::new_with_width(id, width))))
+        });
+        registry.register("#Gates", "Controlled Inverter", |comp, id| {
+            let width = attr_width(comp)?;
+            Ok(Some(Box::new(ControlledBuffer::new_inverter_with_width(
+                id, width,
+            ))))
+        });
+        registry.register("#Gates", "Buffer", |comp, id| {
+            Ok(Some(Box::new(Buffer::new_with_width(
+                id,
+                attr_width(comp)?,
+            ))))
+        });
+        registry.register("#Gates", "Odd Parity", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                OddParityGate::new_with_inputs,
+                OddParityGate::set_width,
+                OddParityGate::set_negated,
+            )
+        });
+        registry.register("#Gates", "Even Parity", |comp, id| {
+            multi_input_gate(
+                comp,
+                id,
+                EvenParityGate::new_with_inputs,
+                EvenParityGate::set_width,
+                EvenParityGate::set_negated,
+            )
+        });
+
+        registry.register("#Plexers", "Multiplexer", multiplexer);
+        registry.register("#Plexers", "Demultiplexer", demultiplexer);
+        registry.register("#Plexers", "Decoder", decoder);
+        registry.register("#Plexers", "Priority Encoder", |comp, id| {
+            let select = attr_select(comp, 3)?;
+            let facing = attr_facing(comp, Direction::East)?;
+            let mut encoder = PriorityEncoder::with_config(id, select, facing);
+            encoder.set_disabled_floating(attr(comp, "disabled") == Some("Z"));
+            Ok(Some(Box::new(encoder)))
+        });
+        registry.register("#Plexers", "BitSelector", |comp, id| {
+            let width = BusWidth(attr_u32(comp, "width", 8)?);
+            let group = BusWidth(attr_u32(comp, "group", 1)?);
+            let facing = attr_facing(comp, Direction::East)?;
+            Ok(Some(Box::new(BitSelector::with_config(
+                id, width, group, facing,
+            ))))
+        });
+
+        registry.register("#Arithmetic", "Adder", |comp, id| {
+            Ok(Some(Box::new(Adder::new_with_width(
+                id,
+                arith_width(comp)?,
+            ))))
+        });
+        registry.register("#Arithmetic", "Subtractor", |comp, id| {
+            let width = arith_width(comp)?;
+            Ok(Some(Box::new(Subtractor::new_with_width(id, width))))
+        });
+        registry.register("#Arithmetic", "Multiplier", |comp, id| {
+            let mut multiplier = Multiplier::new_with_width(id, arith_width(comp)?);
+            multiplier.set_signed(attr(comp, "mode") == Some("twosComplement"));
+            Ok(Some(Box::new(multiplier)))
+        });
+        registry.register("#Arithmetic", "Comparator", |comp, id| {
+            let mut comparator = Comparator::new_with_width(id, arith_width(comp)?);
+            comparator.set_signed(attr(comp, "mode") != Some("unsigned"));
+            Ok(Some(Box::new(comparator)))
+        });
+        registry.register("#Arithmetic", "Divider", |comp, id| {
+            let mut divider = Divider::new_with_width(id, arith_width(comp)?);
+            divider.set_signed(attr(comp, "mode") == Some("twosComplement"));
+            Ok(Some(Box::new(divider)))
+        });
+        registry.register("#Arithmetic", "Negator", |comp, id| {
+            let width = arith_width(comp)?;
+            Ok(Some(Box::new(Negator::new_with_width(id, width))))
+        });
+        registry.register("#Arithmetic", "Shifter", |comp, id| {
+            let shift = attr(comp, "shift")
+                .map_or(Ok(ShiftType::LogicalLeft), ShiftType::parse)
+                .map_err(CircFormatError::InvalidAttributeValue)?;
+            let width = arith_width(comp)?;
+            Ok(Some(Box::new(Shifter::new_with_width(id, width, shift))))
+        });
+        registry.register("#Arithmetic", "BitAdder", |comp, id| {
+            let inputs = attr_u32(comp, "inputs", 1)?;
+            let width = arith_width(comp)?;
+            Ok(Some(Box::new(BitAdder::new_with_inputs(id, width, inputs))))
+        });
+        registry.register("#Arithmetic", "BitFinder", |comp, id| {
+            let finder_type = attr(comp, "type")
+                .map_or(Ok(BitFinderType::LowOne), BitFinderType::parse)
+                .map_err(CircFormatError::InvalidAttributeValue)?;
+            let width = arith_width(comp)?;
+            Ok(Some(Box::new(BitFinder::new_with_width(
+                id,
+                width,
+                finder_type,
+            ))))
+        });
+
+        registry.register("#Memory", DLogic::ID, flip_flop::<DLogic>);
+        registry.register("#Memory", TLogic::ID, flip_flop::<TLogic>);
+        registry.register("#Memory", JKLogic::ID, flip_flop::<JKLogic>);
+        registry.register("#Memory", SRLogic::ID, flip_flop::<SRLogic>);
+        registry.register("#Memory", "Register", |comp, id| {
+            let mut register = Register::new_with_width(id, BusWidth(attr_u32(comp, "width", 8)?));
+            register.set_trigger(attr_trigger(comp)?);
+            Ok(Some(Box::new(register)))
+        });
+        registry.register("#Memory", "Counter", counter);
+        registry.register("#Memory", "Shift Register", shift_register);
+        registry.register("#Memory", "RAM", ram);
+        registry.register("#Memory", "ROM", rom);
+        registry.register("#Memory", "Random", random);
+
+        registry
+    }
+
+    /// Register the constructor for a component of a library, replacing
+    /// any earlier one
+    pub fn register(&mut self, library: &str, name: &str, constructor: ComponentConstructor) {
+        self.factories
+            .insert((library.to_string(), name.to_string()), constructor);
+    }
+
+    /// Check whether a component of a library can be constructed
+    pub fn contains(&self, library: &str, name: &str) -> bool {
+        self.factories
+            .contains_key(&(library.to_string(), name.to_string()))
+    }
+
+    /// Build the simulation component for an instance of a library component
+    pub fn create(
+        &self,
+        library: &str,
+        comp: &ComponentInstance,
+        id: ComponentId,
+    ) -> CircResult<Option<Box<dyn Component>>> {
+        let constructor = self
+            .factories
+            .get(&(library.to_string(), comp.name.clone()))
+            .ok_or_else(|| {
+                CircFormatError::UnsupportedComponent(format!(
+                    "No factory for {} {}",
+                    library, comp.name
+                ))
+            })?;
+        constructor(comp, id)
+    }
+}
+
+impl fmt::Debug for ComponentRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys: Vec<_> = self.factories.keys().collect();
+        keys.sort();
+        f.debug_struct("ComponentRegistry")
+            .field("factories", &keys)
+            .finish()
+    }
+}
+
+/// Error for an attribute setting that the simulation model does not have
+fn unsupported(comp: &ComponentInstance, what: &str) -> CircFormatError {
+    CircFormatError::UnsupportedComponent(format!("{} with {}", comp.name, what))
+}
+
+/// Parse the `trigger` attribute, rising edge by default
+fn attr_trigger(comp: &ComponentInstance) -> CircResult<Trigger> {
+    attr(comp, "trigger")
+        .map_or(Ok(Trigger::Rising), Trigger::parse)
+        .map_err(CircFormatError::InvalidAttributeValue)
+}
+
+/// Parse the width of an arithmetic component, 8 bits by default
+fn arith_width(comp: &ComponentInstance) -> CircResult<BusWidth> {
+    attr_u32(comp, "width", 8).map(BusWidth)
+}
+
+/// Parse the `select` attribute of a plexer, between 1 and 8 bits
+fn attr_select(comp: &ComponentInstance, default: u32) -> CircResult<u8> {
+    let select = attr_u32(comp, "select", default)?;
+    u8::try_from(select)
+        .ok()
+        .filter(|bits| (1..=8).contains(bits))
+        .ok_or_else(|| {
+            CircFormatError::InvalidAttributeValue(format!("select '{}' on {}", select, comp.name))
+        })
+}
+
+/// Fill a memory from the `contents` attribute, if there is one
+fn attr_contents(comp: &ComponentInstance, contents: &mut MemContents) -> CircResult<()> {
+    if let Some(text) = attr(comp, "contents") {
+        let words = RomContents::parse_from_string(text)?.data;
+        for (address, word) in words.into_iter().enumerate() {
+            contents.set(address as i64, word as i64);
+        }
+    }
+    Ok(())
+}
+
+fn multi_input_gate<G: Component + 'static>(
+    comp: &ComponentInstance,
+    id: ComponentId,
+    new: fn(ComponentId, usize) -> G,
+    set_width: fn(&mut G, BusWidth),
+    set_negated: fn(&mut G, usize, bool),
+) -> CircResult<Option<Box<dyn Component>>> {
+    let inputs = attr_u32(comp, "inputs", 2)? as usize;
+    let mut gate = new(id, inputs);
+    set_width(&mut gate, attr_width(comp)?);
+    for index in 0..inputs {
+        let name = format!("negate{}", index);
+        set_negated(&mut gate, index, attr(comp, &name) == Some("true"));
+    }
+    Ok(Some(Box::new(gate)))
+}
+
+fn pin(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let mut pin = Pin::new(id);
+    pin.set_width(attr_width(comp)?);
+    if is_output_pin(comp) {
+        pin.set_pin_type(PinType::Output);
+    }
+    pin.set_behavior(match attr(comp, "behavior") {
+        Some("tristate") => PinBehavior::Tristate,
+        Some("pullup") => PinBehavior::PullUp,
+        Some("pulldown") => PinBehavior::PullDown,
+        _ => PinBehavior::Simple,
+    });
+    pin.set_label(attr(comp, "label").unwrap_or_default());
+    Ok(Some(Box::new(pin)))
+}
+
+fn constant(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let mut constant = Constant::new(id);
+    constant.set_width(attr_width(comp)?);
+    constant.set_value(attr_hex(comp, "value", 1)?);
+    Ok(Some(Box::new(constant)))
+}
+
+fn clock(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let mut clock = Clock::new(id);
+    clock.set_high_duration(attr_u32(comp, "highDuration", 1)? as u64);
+    clock.set_low_duration(attr_u32(comp, "lowDuration", 1)? as u64);
+    clock.set_phase(attr_u32(comp, "phase", 0)? as u64);
+    Ok(Some(Box::new(clock)))
+}
+
+fn pull_resistor(
+    comp: &ComponentInstance,
+    id: ComponentId,
+) -> CircResult<Option<Box<dyn Component>>> {
+    let pull = match attr(comp, "pull") {
+        None | Some("0") => Value::Low,
+        Some("1") => Value::High,
+        Some("X") => Value::Error,
+        Some(other) => {
+            return Err(CircFormatError::InvalidAttributeValue(format!(
+                "pull '{}' on {}",
+                other, comp.name
+            )))
+        }
+    };
+    let mut resistor = PullResistor::new_with_pull(id, pull);
+    resistor.set_width(attr_width(comp)?);
+    Ok(Some(Box::new(resistor)))
+}
+
+fn multiplexer(
+    comp: &ComponentInstance,
+    id: ComponentId,
+) -> CircResult<Option<Box<dyn Component>>> {
+    let select = attr_select(comp, 1)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let mut multiplexer = Multiplexer::with_config(id, select, attr_width(comp)?, facing);
+    multiplexer.set_enable(attr(comp, "enable") == Some("true"));
+    multiplexer.set_tristate(attr(comp, "disabled") == Some("Z"));
+    Ok(Some(Box::new(multiplexer)))
+}
+
+fn demultiplexer(
+    comp: &ComponentInstance,
+    id: ComponentId,
+) -> CircResult<Option<Box<dyn Component>>> {
+    let select = attr_select(comp, 1)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let mut demultiplexer = Demultiplexer::with_config(id, select, attr_width(comp)?, facing);
+    demultiplexer.set_enable(attr(comp, "enable") == Some("true"));
+    demultiplexer.set_tristate(attr(comp, "tristate") == Some("true"));
+    demultiplexer.set_disabled_floating(attr(comp, "disabled") == Some("Z"));
+    Ok(Some(Box::new(demultiplexer)))
+}
+
+fn decoder(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let select = attr_select(comp, 1)?;
+    let facing = attr_facing(comp, Direction::East)?;
+    let mut decoder = Decoder::with_config(id, select, facing);
+    // The enable input defaults to present since Logisim 2.6.4
+    decoder.set_enable(attr(comp, "enable") != Some("false"));
+    decoder.set_tristate(attr(comp, "tristate") == Some("true"));
+    decoder.set_disabled_floating(attr(comp, "disabled") == Some("Z"));
+    Ok(Some(Box::new(decoder)))
+}
+
+fn bit_extender(
+    comp: &ComponentInstance,
+    id: ComponentId,
+) -> CircResult<Option<Box<dyn Component>>> {
+    let in_width = BusWidth(attr_u32(comp, "in_width", 8)?);
+    let out_width = BusWidth(attr_u32(comp, "out_width", 16)?);
+    let extension = attr(comp, "type")
+        .map_or(Ok(BitExtension::Sign), BitExtension::parse)
+        .map_err(CircFormatError::InvalidAttributeValue)?;
+    Ok(Some(Box::new(BitExtender::new_with_widths(
+        id, in_width, out_width, extension,
+    ))))
+}
+
+fn flip_flop<L: FlipFlopLogic>(
+    comp: &ComponentInstance,
+    id: ComponentId,
+) -> CircResult<Option<Box<dyn Component>>> {
+    let trigger = attr_trigger(comp)?;
+    // Only the D and S-R flip-flops can be latches
+    if !L::LEVEL_TRIGGERS && !trigger.is_edge() {
+        return Err(CircFormatError::InvalidAttributeValue(format!(
+            "trigger '{}' on {}",
+            attr(comp, "trigger").unwrap_or_default(),
+            comp.name
+        )));
+    }
+    let mut flip_flop = FlipFlop::<L>::new(id);
+    flip_flop.set_trigger(trigger);
+    Ok(Some(Box::new(flip_flop)))
+}
+
+fn counter(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let mut counter = Counter::new_with_width(id, BusWidth(attr_u32(comp, "width", 8)?));
+    counter
+        .set_trigger(attr_trigger(comp)?)
+        .map_err(CircFormatError::InvalidAttributeValue)?;
+    if attr(comp, "max").is_some() {
+        counter.set_max_value(attr_hex(comp, "max", 0)?);
+    }
+    if let Some(on_goal) = attr(comp, "ongoal") {
+        counter
+            .set_on_goal(OnGoal::parse(on_goal).map_err(CircFormatError::InvalidAttributeValue)?);
+    }
+    Ok(Some(Box::new(counter)))
+}
+
+fn shift_register(
+    comp: &ComponentInstance,
+    id: ComponentId,
+) -> CircResult<Option<Box<dyn Component>>> {
+    let length = attr_u32(comp, "length", 8)? as usize;
+    let mut register = ShiftRegister::new_with_config(id, attr_width(comp)?, length);
+    // Parallel load is on unless the file turns it off
+    register.set_parallel(attr(comp, "parallel") != Some("false"));
+    register
+        .set_trigger(attr_trigger(comp)?)
+        .map_err(CircFormatError::InvalidAttributeValue)?;
+    Ok(Some(Box::new(register)))
+}
+
+fn random(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let mut random = Random::new_with_width(id, BusWidth(attr_u32(comp, "width", 8)?));
+    if let Some(seed) = attr(comp, "seed") {
+        // The seed is a Java int, so it may be negative
+        random.set_seed(seed.trim().parse().map_err(|_| {
+            CircFormatError::InvalidAttributeValue(format!("seed '{}' on {}", seed, comp.name))
+        })?);
+    }
+    random
+        .set_trigger(attr_trigger(comp)?)
+        .map_err(CircFormatError::InvalidAttributeValue)?;
+    Ok(Some(Box::new(random)))
+}
+
+fn ram(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let addr_width = BusWidth(attr_u32(comp, "addrWidth", 8)?);
+    let data_width = BusWidth(attr_u32(comp, "dataWidth", 8)?);
+    if attr(comp, "databus") == Some("bidir") {
+        return Err(unsupported(comp, "a bidirectional data bus"));
+    }
+    if attr(comp, "enables") == Some("line") && attr(comp, "line").is_some_and(|l| l != "single") {
+        return Err(unsupported(comp, "multiple lines"));
+    }
+    if attr(comp, "byteenables") == Some("byteEnables") && data_width.0 >= 9 {
+        return Err(unsupported(comp, "byte enables"));
+    }
+    let trigger = attr_trigger(comp)?;
+    if !trigger.is_edge() {
+        return Err(unsupported(comp, "asynchronous writes"));
+    }
+    let mut ram = Ram::new_with_widths(id, addr_width, data_width);
+    ram.set_trigger(trigger);
+    ram.set_volatile(attr(comp, "type") != Some("nonvolatile"));
+    attr_contents(comp, ram.contents_mut())?;
+    Ok(Some(Box::new(ram)))
+}
+
+fn rom(comp: &ComponentInstance, id: ComponentId) -> CircResult<Option<Box<dyn Component>>> {
+    let addr_width = BusWidth(attr_u32(comp, "addrWidth", 8)?);
+    let data_width = BusWidth(attr_u32(comp, "dataWidth", 8)?);
+    if attr(comp, "line").is_some_and(|line| line != "single") {
+        return Err(unsupported(comp, "multiple lines"));
+    }
+    let mut rom = Rom::new_with_widths(id, addr_width, data_width);
+    attr_contents(comp, rom.contents_mut())?;
+    Ok(Some(Box::new(rom)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::{Signal, Timestamp};
+
+    fn comp(name: &str, attributes: &[(&str, &str)]) -> ComponentInstance {
+        ComponentInstance {
+            library: Some("0".to_string()),
+            name: name.to_string(),
+            location: (0, 0),
+            attributes: attributes
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            facing: None,
+        }
+    }
+
+    #[test]
+    fn test_standard_registry_is_keyed_by_library() {
+        let registry = ComponentRegistry::standard();
+        assert!(registry.contains("#Gates", "AND Gate"));
+        assert!(registry.contains("#Memory", "Register"));
+        assert!(registry.contains("#Arithmetic", "Adder"));
+        assert!(registry.contains("#Plexers", "Decoder"));
+        assert!(registry.contains("#Memory", "ROM"));
+        assert!(!registry.contains("#Wiring", "AND Gate"));
+        assert!(!registry.contains("#I/O", "Button"));
+
+        let err = registry
+            .create("#I/O", &comp("Button", &[]), ComponentId(1))
+            .err()
+            .unwrap();
+        assert!(matches!(err, CircFormatError::UnsupportedComponent(_)));
+    }
+
+    #[test]
+    fn test_constructors_read_attributes() {
+        let registry = ComponentRegistry::standard();
+        let create = |library, comp| registry.create(library, &comp, ComponentId(1)).unwrap();
+
+        let gate = create(
+            "#Gates",
+            comp("OR Gate", &[("inputs", "3"), ("width", "4")]),
+        )
+        .unwrap();
+        assert_eq!(gate.get_pin("I2").unwrap().width, BusWidth(4));
+
+        let counter = create("#Memory", comp("Counter", &[("width", "4")])).unwrap();
+        assert_eq!(counter.get_pin("OUT").unwrap().width, BusWidth(4));
+
+        let ram = create(
+            "#Memory",
+            comp("RAM", &[("addrWidth", "4"), ("dataWidth", "16")]),
+        )
+        .unwrap();
+        assert_eq!(ram.get_pin("A").unwrap().width, BusWidth(4));
+        assert_eq!(ram.get_pin("Q").unwrap().width, BusWidth(16));
+
+        let mux = create("#Plexers", comp("Multiplexer", &[("select", "2")])).unwrap();
+        assert!(mux.get_pin("input_3").is_some());
+
+        let adder = create("#Arithmetic", comp("Adder", &[("width", "4")])).unwrap();
+        assert_eq!(adder.get_pin("Sum").unwrap().width, BusWidth(4));
+
+        let decoder = create("#Plexers", comp("Decoder", &[("select", "3")])).unwrap();
+        assert!(decoder.get_pin("output_7").is_some());
+        assert!(decoder.get_pin("enable").is_some());
+
+        let rom = create(
+            "#Memory",
+            comp(
+                "ROM",
+                &[
+                    ("addrWidth", "4"),
+                    ("dataWidth", "8"),
+                    ("contents", "addr/data: 4 8\n0 2a\n"),
+                ],
+            ),
+        )
+        .unwrap();
+        assert_eq!(rom.get_pin("Q").unwrap().width, BusWidth(8));
+
+        let extender = create(
+            "#Wiring",
+            comp("Bit Extender", &[("in_width", "4"), ("type", "input")]),
+        )
+        .unwrap();
+        assert_eq!(extender.get_pin("in").unwrap().width, BusWidth(4));
+        assert!(extender.get_pin("extend").is_some());
+
+        let demux = create(
+            "#Plexers",
+            comp("Demultiplexer", &[("select", "2"), ("width", "4")]),
+        )
+        .unwrap();
+        assert_eq!(demux.get_pin("output_3").unwrap().width, BusWidth(4));
+
+        let finder = create("#Arithmetic", comp("BitFinder", &[("width", "16")])).unwrap();
+        assert_eq!(finder.get_pin("Index").unwrap().width, BusWidth(4));
+
+        let flip_flop = create("#Memory", comp("J-K Flip-Flop", &[])).unwrap();
+        assert!(flip_flop.get_pin("K").is_some());
+
+        assert!(create("#Wiring", comp("Tunnel", &[])).is_none());
+    }
+
+    #[test]
+    fn test_negated_inputs_and_parallel_load() {
+        let registry = ComponentRegistry::standard();
+        let create = |library, comp| registry.create(library, &comp, ComponentId(1)).unwrap();
+
+        let mut gate = create("#Gates", comp("AND Gate", &[("negate1", "true")])).unwrap();
+        for (name, value) in [("A", Value::High), ("B", Value::Low)] {
+            gate.get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::new_single(value))
+                .unwrap();
+        }
+        let result = gate.update(Timestamp(0));
+        assert_eq!(result.outputs["Y"].as_single(), Some(Value::High));
+
+        // Parallel load is the default
+        let register = create("#Memory", comp("Shift Register", &[("length", "4")])).unwrap();
+        assert!(register.get_pin("LD").is_some());
+        assert!(register.get_pin("D3").is_some());
+        let register = create(
+            "#Memory",
+            comp("Shift Register", &[("length", "4"), ("parallel", "false")]),
+        )
+        .unwrap();
+        assert!(register.get_pin("LD").is_none());
+    }
+
+    #[test]
+    fn test_components_report_their_tool() {
+        let registry = ComponentRegistry::standard();
+        let mut instances: Vec<(String, ComponentInstance)> = registry
+            .factories
+            .keys()
+            .map(|(library, name)| (library.clone(), comp(name, &[])))
+            .collect();
+        instances.extend([
+            (
+                "#Memory".to_string(),
+                comp(
+                    "RAM",
+                    &[
+                        ("type", "nonvolatile"),
+                        ("contents", "addr/data: 8 8\n0 7\n"),
+                    ],
+                ),
+            ),
+            (
+                "#Arithmetic".to_string(),
+                comp("Multiplier", &[("mode", "twosComplement")]),
+            ),
+            (
+                "#Gates".to_string(),
+                comp("XOR Gate", &[("negate0", "true")]),
+            ),
+            (
+                "#Wiring".to_string(),
+                comp("Pull Resistor", &[("pull", "1")]),
+            ),
+            (
+                "#Memory".to_string(),
+                comp("Random", &[("seed", "-7"), ("trigger", "falling")]),
+            ),
+            (
+                "#Arithmetic".to_string(),
+                comp("Shifter", &[("shift", "ar")]),
+            ),
+            (
+                "#Plexers".to_string(),
+                comp("Demultiplexer", &[("tristate", "true"), ("disabled", "Z")]),
+            ),
+        ]);
+
+        // Built again from the attributes it reports, a component reports
+        // the same tool
+        for (library, instance) in instances {
+            let Some(component) = registry
+                .create(&library, &instance, ComponentId(1))
+                .unwrap()
+            else {
+                continue;
+            };
+            let tool = component.circ_tool().unwrap();
+            assert_eq!(
+                (tool.library.as_str(), tool.name.as_str()),
+                (library.as_str(), instance.name.as_str())
+            );
+            for (name, value) in &instance.attributes {
+                assert_eq!(tool.attribute(name), Some(value.as_str()), "{}", name);
+            }
+            let mut rebuilt = comp(&tool.name, &[]);
+            rebuilt.attributes = tool.attributes.iter().cloned().collect();
+            let rebuilt = registry.create(&library, &rebuilt, ComponentId(1)).unwrap();
+            assert_eq!(rebuilt.unwrap().circ_tool(), Some(tool));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_attribute_settings() {
+        let registry = ComponentRegistry::standard();
+        for (library, comp) in [
+            ("#Memory", comp("RAM", &[("databus", "bidir")])),
+            ("#Memory", comp("ROM", &[("line", "dual")])),
+        ] {
+            let err = registry.create(library, &comp, ComponentId(1)).err();
+            assert!(
+                matches!(err, Some(CircFormatError::UnsupportedComponent(_))),
+                "{}",
+                comp.name
+            );
+        }
+
+        // A bad attribute value is an error of its own
+        for (library, comp) in [
+            ("#Memory", comp("Counter", &[("trigger", "high")])),
+            ("#Memory", comp("T Flip-Flop", &[("trigger", "low")])),
+            ("#Memory", comp("Random", &[("seed", "x")])),
+            ("#Arithmetic", comp("Shifter", &[("shift", "up")])),
+        ] {
+            let err = registry.create(library, &comp, ComponentId(1)).err();
+            assert!(
+                matches!(err, Some(CircFormatError::InvalidAttributeValue(_))),
+                "{}",
+                comp.name
+            );
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Validation of .circ documents
+//!
+//! [`CircParser`](crate::circ_format::CircParser) checks every document
+//! before building a [`CircuitFile`](crate::circ_format::CircuitFile) from
+//! it, so that all problems are reported together with their line and column
+//! instead of the first one aborting the load:
+//!
+//! - components and tools using a library the file does not declare, and
+//!   libraries that are neither built into Logisim-Evolution, nor left by an
+//!   older release for [`circ_migration`](crate::circ_migration) to update,
+//!   nor external files
+//! - circuits declared twice under the same name
+//! - malformed `loc`, `from` and `to` coordinates
+//! - bit widths of standard components that are not a number from 1 to 64
+//! - missing required attributes
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use roxmltree::{Document, Node};
+
+use crate::circ_format::CircParser;
+use crate::circ_migration::LEGACY_LIBRARIES;
+
+/// Descriptors of the libraries built into Logisim-Evolution
+pub const BUILTIN_LIBRARIES: &[&str] = &[
+    "#Wiring",
+    "#Gates",
+    "#Plexers",
+    "#Arithmetic",
+    "#Memory",
+    "#I/O",
+    "#TTL",
+    "#TCL",
+    "#Base",
+    "#BFH-Praktika",
+    "#Input/Output-Extra",
+    "#Soc",
+    "#HDL-IP",
+];
+
+/// Attributes holding a bit width, by library and component
+///
+/// Other components may use the same attribute names for something else,
+/// such as the pixel width of an `RGB Video`, so only these are checked.
+const WIDTH_ATTRIBUTES: &[(&str, &[&str], &[&str])] = &[
+    (
+        "#Wiring",
+        &[
+            "Pin",
+            "Probe",
+            "Tunnel",
+            "Pull Resistor",
+            "Constant",
+            "Power",
+            "Ground",
+            "Transistor",
+            "Transmission Gate",
+        ],
+        &["width"],
+    ),
+    ("#Wiring", &["Splitter"], &["incoming"]),
+    ("#Wiring", &["Bit Extender"], &["in_width", "out_width"]),
+    (
+        "#Gates",
+        &[
+            "NOT Gate",
+            "Buffer",
+            "AND Gate",
+            "OR Gate",
+            "NAND Gate",
+            "NOR Gate",
+            "XOR Gate",
+            "XNOR Gate",
+            "Odd Parity",
+            "Even Parity",
+            "Controlled Buffer",
+            "Controlled Inverter",
+        ],
+        &["width"],
+    ),
+    (
+        "#Plexers",
+        &["Multiplexer", "Demultiplexer", "Bit Selector"],
+        &["width"],
+    ),
+    (
+        "#Arithmetic",
+        &[
+            "Adder",
+            "Subtractor",
+            "Multiplier",
+            "Divider",
+            "Negator",
+            "Comparator",
+            "Shifter",
+            "BitAdder",
+            "BitFinder",
+        ],
+        &["width"],
+    ),
+    (
+        "#Memory",
+        &["Register", "Counter", "Shift Register", "Random"],
+        &["width"],
+    ),
+    ("#Memory", &["RAM", "ROM"], &["addrWidth", "dataWidth"]),
+];
+
+/// Get the attributes of a component holding a bit width
+fn width_attributes(library: &str, component: &str) -> &'static [&'static str] {
+    WIDTH_ATTRIBUTES
+        .iter()
+        .find(|(lib, components, _)| *lib == library && components.contains(&component))
+        .map_or(&[], |(_, _, attributes)| attributes)
+}
+
+/// Widest bus Logisim-Evolution supports
+const MAX_WIDTH: u32 = 64;
+
+/// Kind of problem found in a .circ document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationKind {
+    UnknownLibrary,
+    DuplicateCircuit,
+    MalformedLocation,
+    InvalidWidth,
+    MissingAttribute,
+}
+
+/// A problem found in a .circ document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub kind: ValidationKind,
+    /// Line of the offending element or attribute, from 1
+    pub line: u32,
+    /// Column of the offending element or attribute, from 1
+    pub column: u32,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Check a .circ document, returning its problems in document order
+pub fn validate(doc: &Document) -> Vec<ValidationIssue> {
+    let mut validator = Validator {
+        doc,
+        issues: Vec::new(),
+    };
+    validator.project(doc.root_element());
+    validator
+        .issues
+        .sort_by_key(|issue| (issue.line, issue.column));
+    validator.issues
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.tag_name().name() == name)
+}
+
+struct Validator<'a, 'input> {
+    doc: &'a Document<'input>,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'a, 'input> Validator<'a, 'input> {
+    fn report(&mut self, position: usize, kind: ValidationKind, message: String) {
+        let position = self.doc.text_pos_at(position);
+        self.issues.push(ValidationIssue {
+            kind,
+            line: position.row,
+            column: position.col,
+            message,
+        });
+    }
+
+    /// Report a problem with an attribute, at the attribute itself
+    fn report_attribute(&mut self, node: Node, name: &str, kind: ValidationKind, message: String) {
+        let position = node
+            .attribute_node(name)
+            .map_or(node.range().start, |attribute| attribute.range().start);
+        self.report(position, kind, message);
+    }
+
+    /// Get an attribute that must be present
+    fn required(&mut self, node: Node<'a, 'input>, name: &str) -> Option<&'a str> {
+        let value = node.attribute(name);
+        if value.is_none() {
+            self.report(
+                node.range().start,
+                ValidationKind::MissingAttribute,
+                format!(
+                    "Missing attribute '{}' on <{}>",
+                    name,
+                    node.tag_name().name()
+                ),
+            );
+        }
+        value
+    }
+
+    fn location(&mut self, node: Node<'a, 'input>, name: &str) {
+        if let Some(value) = self.required(node, name) {
+            if CircParser::parse_location(value).is_err() {
+                self.report_attribute(
+                    node,
+                    name,
+                    ValidationKind::MalformedLocation,
+                    format!("Malformed location '{}' in '{}'", value, name),
+                );
+            }
+        }
+    }
+
+    /// Check the `<a>` attributes of a component or tool of `library`
+    fn attributes(&mut self, node: Node<'a, 'input>, library: Option<&str>) {
+        let widths = match (library, node.attribute("name")) {
+            (Some(library), Some(component)) => width_attributes(library, component),
+            _ => &[],
+        };
+        for attribute in children(node, "a") {
+            let Some(name) = self.required(attribute, "name") else {
+                continue;
+            };
+            if !widths.contains(&name) {
+                continue;
+            }
+            let value = attribute
+                .attribute("val")
+                .or_else(|| attribute.text())
+                .unwrap_or_default();
+            let width = value.trim().parse::<u32>().ok();
+            if !width.is_some_and(|width| (1..=MAX_WIDTH).contains(&width)) {
+                self.report_attribute(
+                    attribute,
+                    "val",
+                    ValidationKind::InvalidWidth,
+                    format!(
+                        "Invalid {} '{}': expected a number from 1 to {}",
+                        name, value, MAX_WIDTH
+                    ),
+                );
+            }
+        }
+    }
+
+    fn project(&mut self, root: Node<'a, 'input>) {
+        let mut libraries = HashMap::new();
+        for lib in children(root, "lib") {
+            let name = self.required(lib, "name");
+            let desc = self.required(lib, "desc");
+            if let Some(name) = name {
+                libraries.insert(name, desc);
+            }
+            if let Some(desc) = desc {
+                let external = desc.starts_with("file#") || desc.starts_with("jar#");
+                let known = BUILTIN_LIBRARIES.contains(&desc) || LEGACY_LIBRARIES.contains(&desc);
+                if !external && !known {
+                    self.report_attribute(
+                        lib,
+                        "desc",
+                        ValidationKind::UnknownLibrary,
+                        format!("Unknown library '{}'", desc),
+                    );
+                }
+            }
+            for tool in children(lib, "tool") {
+                self.required(tool, "name");
+                self.attributes(tool, desc);
+            }
+        }
+
+        for section in ["mappings", "toolbar"] {
+            for tool in children(root, section).flat_map(|node| children(node, "tool")) {
+                let name = self.required(tool, "name").unwrap_or_default();
+                if let Some(lib) = tool.attribute("lib") {
+                    if !libraries.contains_key(lib) {
+                        self.report_attribute(
+                            tool,
+                            "lib",
+                            ValidationKind::UnknownLibrary,
+                            format!("Tool '{}' uses undeclared library {}", name, lib),
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut circuits = HashSet::new();
+        for circuit in children(root, "circuit") {
+            if let Some(name) = self.required(circuit, "name") {
+                if !circuits.insert(name) {
+                    self.report_attribute(
+                        circuit,
+                        "name",
+                        ValidationKind::DuplicateCircuit,
+                        format!("Circuit '{}' is declared more than once", name),
+                    );
+                }
+            }
+
+            for wire in children(circuit, "wire") {
+                self.location(wire, "from");
+                self.location(wire, "to");
+            }
+            for comp in children(circuit, "comp") {
+                let name = self.required(comp, "name").unwrap_or_default();
+                self.location(comp, "loc");
+                let library = comp.attribute("lib");
+                if let Some(lib) = library {
+                    if !libraries.contains_key(lib) {
+                        self.report_attribute(
+                            comp,
+                            "lib",
+                            ValidationKind::UnknownLibrary,
+                            format!("Component '{}' uses undeclared library {}", name, lib),
+                        );
+                    }
+                }
+                let desc = library.and_then(|lib| libraries.get(lib).copied().flatten());
+                self.attributes(comp, desc);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(xml: &str) -> Vec<(ValidationKind, u32, u32)> {
+        let doc = Document::parse(xml).unwrap();
+        validate(&doc)
+            .into_iter()
+            .map(|issue| (issue.kind, issue.line, issue.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let xml = concat!(
+            "<project source=\"3.8.0\" version=\"1.0\">\n",
+            "  <lib desc=\"#Wiring\" name=\"0\"/>\n",
+            "  <lib desc=\"file#counter.circ\" name=\"1\"/>\n",
+            "  <lib desc=\"#I/O\" name=\"2\"/>\n",
+            "  <circuit name=\"main\">\n",
+            "    <wire from=\"(0,0)\" to=\"(10,0)\"/>\n",
+            "    <comp lib=\"0\" loc=\"(0,0)\" name=\"Pin\">\n",
+            "      <a name=\"width\" val=\"64\"/>\n",
+            "    </comp>\n",
+            "    <comp lib=\"2\" loc=\"(20,0)\" name=\"RGB Video\">\n",
+            "      <a name=\"width\" val=\"128\"/>\n",
+            "    </comp>\n",
+            "    <comp loc=\"(10,0)\" name=\"sub\"/>\n",
+            "  </circuit>\n",
+            "  <circuit name=\"sub\"/>\n",
+            "</project>",
+        );
+        assert!(issues(xml).is_empty());
+    }
+
+    #[test]
+    fn test_issues_have_positions() {
+        let xml = concat!(
+            "<project>\n",
+            "  <lib desc=\"#Nope\" name=\"0\"/>\n",
+            "  <lib desc=\"#Memory\" name=\"1\"/>\n",
+            "  <circuit name=\"main\">\n",
+            "    <wire from=\"(0,0)\" to=\"(10;0)\"/>\n",
+            "    <comp lib=\"3\" loc=\"(0,0)\" name=\"Adder\"/>\n",
+            "    <comp lib=\"1\" loc=\"(10,0)\" name=\"RAM\">\n",
+            "      <a name=\"dataWidth\" val=\"0\"/>\n",
+            "    </comp>\n",
+            "    <comp lib=\"0\" name=\"Pin\"/>\n",
+            "  </circuit>\n",
+            "  <circuit name=\"main\"/>\n",
+            "</project>",
+        );
+        assert_eq!(
+            issues(xml),
+            vec![
+                (ValidationKind::UnknownLibrary, 2, 8),
+                (ValidationKind::MalformedLocation, 5, 24),
+                (ValidationKind::UnknownLibrary, 6, 11),
+                (ValidationKind::InvalidWidth, 8, 27),
+                (ValidationKind::MissingAttribute, 10, 5),
+                (ValidationKind::DuplicateCircuit, 12, 12),
+            ]
+        );
+    }
+}
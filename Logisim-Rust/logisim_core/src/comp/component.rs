@@ -14,7 +14,7 @@
 //! basic interface that all digital components must implement.
 
 use crate::data::{AttributeSet, Bounds, Location};
-use crate::signal::{Signal, Timestamp};
+use crate::signal::{Lanes, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
@@ -58,6 +58,9 @@ pub struct UpdateResult {
     pub delay: u64,
     /// Whether the component state changed
     pub state_changed: bool,
+    /// Delay after which a self-timed component (such as a clock) asks to be
+    /// updated again
+    pub wakeup: Option<u64>,
 }
 
 impl UpdateResult {
@@ -67,6 +70,7 @@ impl UpdateResult {
             outputs: HashMap::new(),
             delay: 0,
             state_changed: false,
+            wakeup: None,
         }
     }
 
@@ -76,6 +80,7 @@ impl UpdateResult {
             outputs,
             delay,
             state_changed: true,
+            wakeup: None,
         }
     }
 
@@ -89,6 +94,11 @@ impl UpdateResult {
     pub fn set_delay(&mut self, delay: u64) {
         self.delay = delay;
     }
+
+    /// Ask for another update `delay` time units from now
+    pub fn request_wakeup(&mut self, delay: u64) {
+        self.wakeup = Some(delay);
+    }
 }
 
 impl Default for UpdateResult {
@@ -97,13 +107,66 @@ impl Default for UpdateResult {
     }
 }
 
-/// Clock edge types for sequential components
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ClockEdge {
-    /// Rising edge (low to high)
-    Rising,
-    /// Falling edge (high to low)
-    Falling,
+/// Setup and hold requirements of a clocked component's data inputs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingConstraints {
+    /// Clock input pin
+    pub clock: String,
+    /// Whether the inputs are sampled on the rising rather than the falling
+    /// clock edge
+    pub rising: bool,
+    /// Data input pins sampled on the edge
+    pub inputs: Vec<String>,
+    /// Time the inputs must be stable before the edge
+    pub setup: u64,
+    /// Time the inputs must stay stable after the edge
+    pub hold: u64,
+}
+
+/// The two pins of a switch component, and whether it conducts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwitchState {
+    /// Pin on one side of the switch
+    pub a: String,
+    /// Pin on the other side of the switch
+    pub b: String,
+    /// Whether the switch currently connects the two pins
+    pub closed: bool,
+}
+
+/// How a component is placed in a .circ file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircTool {
+    /// Library descriptor, such as `#Gates`
+    pub library: String,
+    /// Tool name within the library, such as `AND Gate`
+    pub name: String,
+    /// `<a name=... val=...>` attributes of the current configuration and
+    /// contents, in the form the component's factory in
+    /// [`crate::circ_registry`] reads them
+    pub attributes: Vec<(String, String)>,
+}
+
+impl CircTool {
+    /// Describe a tool of a library with the given attributes
+    pub fn new(library: &str, name: &str, attributes: Vec<(&str, String)>) -> Self {
+        CircTool {
+            library: library.to_string(),
+            name: name.to_string(),
+            attributes: attributes
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        }
+    }
+
+    /// Get the value of an attribute
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
 }
 
 /// Trait that all simulation components must implement
@@ -177,13 +240,75 @@ pub trait Component: std::fmt::Debug + Send + Sync {
     }
 
     /// Check if this component is sequential (has state that depends on clock)
+    ///
+    /// Sequential components watch their own clock inputs; there is no
+    /// simulation-wide clock.
     fn is_sequential(&self) -> bool {
         false // Default: most components are combinational
     }
 
-    /// Handle a clock edge if this is a sequential component
-    fn clock_edge(&mut self, _edge: ClockEdge, _current_time: Timestamp) -> UpdateResult {
-        UpdateResult::new() // Default: no response to clock edges
+    /// Evaluate 64 input vectors at once for batch simulation
+    ///
+    /// `inputs` holds one word per bit of every input pin, each lane of the
+    /// words being an independent vector. Returns the words of every output
+    /// pin, or `None` for components without a bit-parallel implementation.
+    fn evaluate_lanes(
+        &self,
+        _inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        None // Default: not supported
+    }
+
+    /// Get the setup and hold requirements of an edge-triggered component
+    fn timing_constraints(&self) -> Option<TimingConstraints> {
+        None // Default: no clocked inputs
+    }
+
+    /// Capture the internal state of a stateful component for a snapshot
+    ///
+    /// Combinational components have no state beyond the nets around them
+    /// and return `None`.
+    fn save_state(&self) -> Option<serde_json::Value> {
+        None // Default: stateless
+    }
+
+    /// Restore internal state captured by `save_state`
+    fn restore_state(&mut self, _state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        Ok(()) // Default: stateless
+    }
+
+    /// Handle a manual clock tick
+    ///
+    /// Clock sources advance by one half period; every other component
+    /// ignores ticks.
+    fn tick(&mut self, _current_time: Timestamp) -> UpdateResult {
+        UpdateResult::new() // Default: not a clock source
+    }
+
+    /// Get the state of a switch component after its last update
+    ///
+    /// Transistors and transmission gates conduct both ways, so rather than
+    /// driving one side from the other they have the simulation join the
+    /// nets on their two pins while closed. Switch components are only
+    /// simulated by the event-driven engine.
+    fn switch_state(&self) -> Option<SwitchState> {
+        None // Default: not a switch
+    }
+
+    /// Get the value a pull resistor on a pin pulls its net to
+    ///
+    /// The pull applies to floating bits of the net the pin is connected to,
+    /// or acts as a weak driver in IEEE 1164 mode.
+    fn pull_value(&self, _pin_name: &str) -> Option<Value> {
+        None // Default: no pull resistor
+    }
+
+    /// Get the library tool this component is saved as in a .circ file,
+    /// with the attributes of its current configuration
+    ///
+    /// Components without a .circ counterpart return `None`.
+    fn circ_tool(&self) -> Option<CircTool> {
+        None // Default: not saved
     }
 }
 
@@ -237,6 +362,9 @@ mod tests {
 
         result.set_delay(5);
         assert_eq!(result.delay, 5);
+
+        result.request_wakeup(10);
+        assert_eq!(result.wakeup, Some(10));
     }
 
     #[test]
@@ -0,0 +1,118 @@
+//! Propagation delays and delay models.
+//!
+//! A component's output changes reach its nodes after a propagation delay.
+//! The delay of a component instance is, in order of precedence, the delay
+//! set on that instance, the entry for its component name in the
+//! simulation's [`DelayTable`], or the component's own
+//! [`propagation_delay`](crate::comp::Component::propagation_delay).
+//!
+//! Time units are abstract; the library tables below take one unit to be one
+//! nanosecond.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How an output change that is still in flight reacts to a newer one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DelayModel {
+    /// Every change travels to the output on its own, so pulses of any width
+    /// come through
+    #[default]
+    Transport,
+    /// A newer change cancels the one in flight, so pulses shorter than the
+    /// delay are swallowed, as real gates do
+    Inertial,
+}
+
+/// Default propagation delays by component name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelayTable {
+    delays: HashMap<String, u64>,
+}
+
+impl DelayTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Typical 74LS propagation delays in nanoseconds, keyed by chip number
+    /// as the `std::ttl` components name themselves
+    ///
+    /// Each figure is the mean of the typical low-to-high and high-to-low
+    /// delays from the datasheets, rounded to the nearest nanosecond.
+    pub fn ttl_74ls() -> Self {
+        [
+            ("7400", 10),  // Quad 2-input NAND
+            ("7402", 10),  // Quad 2-input NOR
+            ("7404", 10),  // Hex inverter
+            ("7408", 9),   // Quad 2-input AND
+            ("7410", 10),  // Triple 3-input NAND
+            ("7432", 14),  // Quad 2-input OR
+            ("7486", 11),  // Quad 2-input XOR
+            ("7474", 20),  // Dual D flip-flop, clock to output
+            ("74161", 18), // Synchronous 4-bit counter, clock to output
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// Add or replace the delay for a component name
+    pub fn with(mut self, name: impl Into<String>, delay: u64) -> Self {
+        self.set(name, delay);
+        self
+    }
+
+    /// Set the delay for a component name
+    pub fn set(&mut self, name: impl Into<String>, delay: u64) {
+        self.delays.insert(name.into(), delay);
+    }
+
+    /// Get the delay for a component name
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.delays.get(name).copied()
+    }
+
+    /// Add every entry of another table, replacing existing ones
+    pub fn extend(&mut self, other: &DelayTable) {
+        self.delays.extend(
+            other
+                .delays
+                .iter()
+                .map(|(name, &delay)| (name.clone(), delay)),
+        );
+    }
+
+    /// Check if the table has no entries
+    pub fn is_empty(&self) -> bool {
+        self.delays.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, u64)> for DelayTable {
+    fn from_iter<I: IntoIterator<Item = (S, u64)>>(iter: I) -> Self {
+        DelayTable {
+            delays: iter
+                .into_iter()
+                .map(|(name, delay)| (name.into(), delay))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_merge() {
+        let mut table = DelayTable::ttl_74ls();
+        assert_eq!(table.get("7400"), Some(10));
+        assert_eq!(table.get("AND"), None);
+
+        table.extend(&DelayTable::new().with("7400", 15).with("AND", 2));
+        assert_eq!(table.get("7400"), Some(15));
+        assert_eq!(table.get("AND"), Some(2));
+        assert_eq!(table.get("7402"), Some(10));
+    }
+}
@@ -0,0 +1,520 @@
+//! Loading of 32-bit ELF executables into memories.
+//!
+//! This is the counterpart of the `ElfHeader`, `ElfProgramHeader` and
+//! `ElfSectionHeader` classes of Logisim-Evolution's SoC package: an
+//! [`ElfFile`] holds the loadable segments, entry point and symbol table of
+//! a program built by a cross-compiler, such as `riscv32-unknown-elf-gcc`
+//! or `nios2-elf-gcc`, and [`ElfFile::load`] copies its segments into the
+//! ROMs and RAMs of a design, each of which covers a range of the
+//! processor's byte addresses.
+//!
+//! Segments are placed at their physical (load) address, so that
+//! initialized data linked to run from RAM but stored in ROM ends up in the
+//! ROM; the part of a segment past its file size, such as `.bss`, is
+//! zero-filled. Bytes are grouped into words as wide as each memory, in the
+//! byte order of the file.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::std::memory::mem_image::{self, ByteOrder, MemImageError};
+use crate::std::memory::MemContents;
+
+/// Errors reading or loading ELF files
+#[derive(Error, Debug)]
+pub enum ElfError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Not an ELF file")]
+    NotElf,
+
+    #[error("Unsupported ELF file: {0}")]
+    Unsupported(String),
+
+    #[error("Truncated ELF file: {0} extends past the end of the file")]
+    Truncated(String),
+
+    #[error("Segment byte at {address:#010x} is not in any memory")]
+    Unmapped { address: u32 },
+
+    #[error(transparent)]
+    MemImage(#[from] MemImageError),
+}
+
+/// Result type for ELF operations
+pub type ElfResult<T> = Result<T, ElfError>;
+
+/// ELF file type of executables
+const ET_EXEC: u16 = 2;
+/// Program header type of loadable segments
+const PT_LOAD: u32 = 1;
+/// Section header type of symbol tables
+const SHT_SYMTAB: u32 = 2;
+
+/// Processor architecture an ELF file was built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElfMachine {
+    RiscV,
+    Nios2,
+    Other(u16),
+}
+
+impl ElfMachine {
+    fn from_code(code: u16) -> Self {
+        match code {
+            243 => Self::RiscV,
+            113 => Self::Nios2,
+            code => Self::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for ElfMachine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RiscV => write!(f, "RISC-V"),
+            Self::Nios2 => write!(f, "Nios II"),
+            Self::Other(code) => write!(f, "machine {}", code),
+        }
+    }
+}
+
+/// A loadable segment of an ELF file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSegment {
+    /// Address the segment is loaded at
+    pub physical_address: u32,
+    /// Address the segment is used at when the program runs
+    pub virtual_address: u32,
+    /// Bytes stored in the file
+    pub data: Vec<u8>,
+    /// Size in memory, at least that of `data`; the rest is zero-filled
+    pub memory_size: u32,
+    /// `PF_X`, `PF_W` and `PF_R` flags
+    pub flags: u32,
+}
+
+/// Kind of an ELF symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    NoType,
+    Object,
+    Function,
+    Section,
+    File,
+    Other(u8),
+}
+
+/// A symbol of an ELF file's symbol table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSymbol {
+    pub name: String,
+    /// Address of the symbol
+    pub value: u32,
+    /// Size in bytes of the object or function, 0 when unknown
+    pub size: u32,
+    pub kind: SymbolKind,
+    /// Whether the symbol is visible outside its object file
+    pub global: bool,
+}
+
+/// A memory covering a range of byte addresses, to load segments into
+pub struct MemoryRegion<'a> {
+    /// Byte address of the memory's first word
+    pub base: u32,
+    pub contents: &'a mut MemContents,
+}
+
+impl MemoryRegion<'_> {
+    /// Number of bytes the memory holds
+    pub fn size(&self) -> u64 {
+        let word_bytes = (self.contents.get_width().max(1) as u64).div_ceil(8);
+        word_bytes << self.contents.get_log_length()
+    }
+
+    fn contains(&self, address: u32) -> bool {
+        address >= self.base && ((address - self.base) as u64) < self.size()
+    }
+}
+
+/// A 32-bit ELF executable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfFile {
+    pub machine: ElfMachine,
+    pub byte_order: ByteOrder,
+    /// Address of the first instruction
+    pub entry: u32,
+    /// Loadable segments, in file order
+    pub segments: Vec<ElfSegment>,
+    /// Named symbols, by address
+    pub symbols: Vec<ElfSymbol>,
+}
+
+impl ElfFile {
+    /// Read an ELF file
+    pub fn read<P: AsRef<Path>>(path: P) -> ElfResult<Self> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    /// Parse the contents of an ELF file
+    pub fn parse(data: &[u8]) -> ElfResult<Self> {
+        if !data.starts_with(b"\x7fELF") {
+            return Err(ElfError::NotElf);
+        }
+        if data.len() < 52 {
+            return Err(ElfError::Truncated("ELF header".to_string()));
+        }
+        if data[4] != 1 {
+            return Err(ElfError::Unsupported(
+                "only 32-bit files can be loaded".to_string(),
+            ));
+        }
+        let byte_order = match data[5] {
+            1 => ByteOrder::LittleEndian,
+            2 => ByteOrder::BigEndian,
+            other => {
+                return Err(ElfError::Unsupported(format!(
+                    "unknown data encoding {}",
+                    other
+                )))
+            }
+        };
+        let reader = Reader { data, byte_order };
+
+        if reader.u16(16, "ELF header")? != ET_EXEC {
+            return Err(ElfError::Unsupported("not an executable".to_string()));
+        }
+        let machine = ElfMachine::from_code(reader.u16(18, "ELF header")?);
+        let entry = reader.u32(24, "ELF header")?;
+        let program_headers = reader.u32(28, "ELF header")? as usize;
+        let section_headers = reader.u32(32, "ELF header")? as usize;
+        let program_header_size = reader.u16(42, "ELF header")? as usize;
+        let program_header_count = reader.u16(44, "ELF header")? as usize;
+        let section_header_size = reader.u16(46, "ELF header")? as usize;
+        let section_header_count = reader.u16(48, "ELF header")? as usize;
+
+        let mut segments = Vec::new();
+        for index in 0..program_header_count {
+            let header = program_headers + index * program_header_size;
+            let what = format!("program header {}", index);
+            if reader.u32(header, &what)? != PT_LOAD {
+                continue;
+            }
+            let offset = reader.u32(header + 4, &what)? as usize;
+            let file_size = reader.u32(header + 16, &what)? as usize;
+            let segment = ElfSegment {
+                virtual_address: reader.u32(header + 8, &what)?,
+                physical_address: reader.u32(header + 12, &what)?,
+                data: reader
+                    .bytes(offset, file_size, &format!("segment {}", index))?
+                    .to_vec(),
+                memory_size: reader.u32(header + 20, &what)?,
+                flags: reader.u32(header + 24, &what)?,
+            };
+            segments.push(segment);
+        }
+
+        let mut symbols = Vec::new();
+        for index in 0..section_header_count {
+            let header = section_headers + index * section_header_size;
+            let what = format!("section header {}", index);
+            if reader.u32(header + 4, &what)? != SHT_SYMTAB {
+                continue;
+            }
+            let offset = reader.u32(header + 16, &what)? as usize;
+            let size = reader.u32(header + 20, &what)? as usize;
+            let link = reader.u32(header + 24, &what)? as usize;
+            let entry_size = (reader.u32(header + 36, &what)? as usize).max(16);
+            let names = section_headers + link * section_header_size;
+            let names_what = format!("section header {}", link);
+            let names_offset = reader.u32(names + 16, &names_what)? as usize;
+            let names_size = reader.u32(names + 20, &names_what)? as usize;
+            let names = reader.bytes(names_offset, names_size, "string table")?;
+
+            for symbol in (offset..offset + size).step_by(entry_size).skip(1) {
+                let name_offset = reader.u32(symbol, "symbol table")? as usize;
+                let name = names.get(name_offset..).unwrap_or_default();
+                let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+                if name.is_empty() {
+                    continue;
+                }
+                let info = reader.u8(symbol + 12, "symbol table")?;
+                symbols.push(ElfSymbol {
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    value: reader.u32(symbol + 4, "symbol table")?,
+                    size: reader.u32(symbol + 8, "symbol table")?,
+                    kind: match info & 0xf {
+                        0 => SymbolKind::NoType,
+                        1 => SymbolKind::Object,
+                        2 => SymbolKind::Function,
+                        3 => SymbolKind::Section,
+                        4 => SymbolKind::File,
+                        kind => SymbolKind::Other(kind),
+                    },
+                    global: info >> 4 != 0,
+                });
+            }
+        }
+        symbols.sort_by_key(|symbol| symbol.value);
+
+        Ok(Self {
+            machine,
+            byte_order,
+            entry,
+            segments,
+            symbols,
+        })
+    }
+
+    /// Find a symbol by name
+    pub fn symbol(&self, name: &str) -> Option<&ElfSymbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+
+    /// Label for an address: the function, object or label it belongs to
+    ///
+    /// A symbol at exactly that address wins, otherwise the function or
+    /// object whose extent holds it.
+    pub fn symbol_at(&self, address: u32) -> Option<&ElfSymbol> {
+        let labels = self
+            .symbols
+            .iter()
+            .filter(|symbol| !matches!(symbol.kind, SymbolKind::Section | SymbolKind::File));
+        let mut containing = None;
+        for symbol in labels {
+            if symbol.value == address {
+                return Some(symbol);
+            }
+            if symbol.value < address && address - symbol.value < symbol.size {
+                containing = Some(symbol);
+            }
+        }
+        containing
+    }
+
+    /// Replace the contents of memories with the program's segments
+    ///
+    /// Every segment byte, including the zero-filled ones, must fall in one
+    /// of the regions; memories that no segment reaches are cleared.
+    pub fn load(&self, regions: &mut [MemoryRegion]) -> ElfResult<()> {
+        let mut bytes: Vec<BTreeMap<i64, u8>> = vec![BTreeMap::new(); regions.len()];
+        for segment in &self.segments {
+            let size = segment.memory_size.max(segment.data.len() as u32);
+            for offset in 0..size {
+                let address = segment.physical_address.wrapping_add(offset);
+                let region = regions
+                    .iter()
+                    .position(|region| region.contains(address))
+                    .ok_or(ElfError::Unmapped { address })?;
+                let byte = segment.data.get(offset as usize).copied().unwrap_or(0);
+                bytes[region].insert((address - regions[region].base) as i64, byte);
+            }
+        }
+        for (region, bytes) in regions.iter_mut().zip(&bytes) {
+            mem_image::load_bytes(region.contents, bytes, self.byte_order)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads fields of the file's byte order, reporting what is truncated
+struct Reader<'a> {
+    data: &'a [u8],
+    byte_order: ByteOrder,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&self, offset: usize, length: usize, what: &str) -> ElfResult<&'a [u8]> {
+        offset
+            .checked_add(length)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| ElfError::Truncated(what.to_string()))
+    }
+
+    fn u8(&self, offset: usize, what: &str) -> ElfResult<u8> {
+        Ok(self.bytes(offset, 1, what)?[0])
+    }
+
+    fn u16(&self, offset: usize, what: &str) -> ElfResult<u16> {
+        let bytes = self.bytes(offset, 2, what)?.try_into().unwrap_or_default();
+        Ok(match self.byte_order {
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn u32(&self, offset: usize, what: &str) -> ElfResult<u32> {
+        let bytes = self.bytes(offset, 4, what)?.try_into().unwrap_or_default();
+        Ok(match self.byte_order {
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A RISC-V executable with code at 0x0, data loaded at 0x100 for RAM at
+    /// 0x80000000 followed by 8 bytes of `.bss`, and a symbol table
+    fn program() -> Vec<u8> {
+        let code: [u8; 8] = [0x93, 0x00, 0x10, 0x00, 0x6f, 0x00, 0x00, 0x00];
+        let data: [u8; 4] = [0x78, 0x56, 0x34, 0x12];
+        let names = b"\0_start\0counter\0loop\0";
+        // (name, value, size, info)
+        let symbols: [(u32, u32, u32, u8); 4] = [
+            (0, 0, 0, 0),
+            (1, 0x0, 8, 0x12),
+            (8, 0x8000_0000, 4, 0x11),
+            (16, 0x4, 0, 0x00),
+        ];
+
+        let mut out = vec![0u8; 52];
+        let phoff = out.len();
+        out.resize(phoff + 2 * 32, 0);
+        let code_offset = out.len();
+        out.extend(code);
+        let data_offset = out.len();
+        out.extend(data);
+        let symtab_offset = out.len();
+        for (name, value, size, info) in symbols {
+            for field in [name, value, size] {
+                out.extend(field.to_le_bytes());
+            }
+            out.extend([info, 0, 1, 0]);
+        }
+        let strtab_offset = out.len();
+        out.extend(names);
+        let shoff = out.len();
+
+        let put16 = |out: &mut Vec<u8>, at: usize, value: u16| {
+            out[at..at + 2].copy_from_slice(&value.to_le_bytes())
+        };
+        let put32 = |out: &mut Vec<u8>, at: usize, value: u32| {
+            out[at..at + 4].copy_from_slice(&value.to_le_bytes())
+        };
+        out[..8].copy_from_slice(b"\x7fELF\x01\x01\x01\x00");
+        put16(&mut out, 16, ET_EXEC);
+        put16(&mut out, 18, 243);
+        put32(&mut out, 20, 1);
+        put32(&mut out, 24, 0x0);
+        put32(&mut out, 28, phoff as u32);
+        put32(&mut out, 32, shoff as u32);
+        put16(&mut out, 40, 52);
+        put16(&mut out, 42, 32);
+        put16(&mut out, 44, 2);
+        put16(&mut out, 46, 40);
+        put16(&mut out, 48, 3);
+
+        let segments = [
+            (code_offset, 0x0, 0x0, 8, 8, 5),
+            (data_offset, 0x8000_0000, 0x100, 4, 12, 6),
+        ];
+        for (index, (offset, vaddr, paddr, filesz, memsz, flags)) in
+            segments.into_iter().enumerate()
+        {
+            let at = phoff + index * 32;
+            for (field, value) in [PT_LOAD, offset as u32, vaddr, paddr, filesz, memsz, flags]
+                .into_iter()
+                .enumerate()
+            {
+                put32(&mut out, at + 4 * field, value);
+            }
+        }
+
+        // Null section, symbol table linked to the string table
+        out.resize(shoff + 3 * 40, 0);
+        let symtab = shoff + 40;
+        put32(&mut out, symtab + 4, SHT_SYMTAB);
+        put32(&mut out, symtab + 16, symtab_offset as u32);
+        put32(&mut out, symtab + 20, 4 * 16);
+        put32(&mut out, symtab + 24, 2);
+        put32(&mut out, symtab + 36, 16);
+        let strtab = shoff + 80;
+        put32(&mut out, strtab + 4, 3);
+        put32(&mut out, strtab + 16, strtab_offset as u32);
+        put32(&mut out, strtab + 20, names.len() as u32);
+        out
+    }
+
+    #[test]
+    fn test_parse() {
+        let elf = ElfFile::parse(&program()).unwrap();
+        assert_eq!(elf.machine, ElfMachine::RiscV);
+        assert_eq!(elf.byte_order, ByteOrder::LittleEndian);
+        assert_eq!(elf.entry, 0);
+        assert_eq!(elf.segments.len(), 2);
+        assert_eq!(elf.segments[1].physical_address, 0x100);
+        assert_eq!(elf.segments[1].virtual_address, 0x8000_0000);
+        assert_eq!(elf.segments[1].memory_size, 12);
+
+        let names: Vec<&str> = elf.symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["_start", "loop", "counter"]);
+        assert_eq!(elf.symbol("_start").unwrap().kind, SymbolKind::Function);
+        assert!(elf.symbol("counter").unwrap().global);
+        assert_eq!(elf.symbol_at(4).unwrap().name, "loop");
+        assert_eq!(elf.symbol_at(6).unwrap().name, "_start");
+        assert_eq!(elf.symbol_at(0x8000_0002).unwrap().name, "counter");
+        assert!(elf.symbol_at(0x40).is_none());
+    }
+
+    #[test]
+    fn test_load_into_memories() {
+        let elf = ElfFile::parse(&program()).unwrap();
+        let mut rom = MemContents::create(8, 32, false);
+        let mut other = MemContents::create(4, 8, false);
+        other.set(3, 0xff);
+        elf.load(&mut [
+            MemoryRegion {
+                base: 0,
+                contents: &mut rom,
+            },
+            MemoryRegion {
+                base: 0x1000,
+                contents: &mut other,
+            },
+        ])
+        .unwrap();
+        assert_eq!(
+            rom.nonzero_words(),
+            vec![(0, 0x0010_0093), (1, 0x6f), (0x40, 0x1234_5678)]
+        );
+        assert!(other.is_clear());
+
+        // The data segment and its .bss must fit
+        let mut small = MemContents::create(6, 32, false);
+        let err = elf
+            .load(&mut [MemoryRegion {
+                base: 0,
+                contents: &mut small,
+            }])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Segment byte at 0x00000100 is not in any memory"
+        );
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        assert!(matches!(ElfFile::parse(b"v2.0 raw"), Err(ElfError::NotElf)));
+
+        let mut elf64 = program();
+        elf64[4] = 2;
+        assert!(matches!(
+            ElfFile::parse(&elf64),
+            Err(ElfError::Unsupported(_))
+        ));
+
+        let truncated = &program()[..100];
+        assert_eq!(
+            ElfFile::parse(truncated).unwrap_err().to_string(),
+            "Truncated ELF file: segment 0 extends past the end of the file"
+        );
+    }
+}
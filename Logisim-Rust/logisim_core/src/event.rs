@@ -143,7 +143,7 @@ impl Ord for SimulatorEvent {
 }
 
 /// Event queue for managing simulation events
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventQueue {
     /// Priority queue of events (min-heap by time)
     events: BinaryHeap<Reverse<SimulatorEvent>>,
@@ -288,6 +288,13 @@ impl EventQueue {
         self.events = remaining_events;
     }
 
+    /// Remove a queued event, returning whether it was queued
+    pub fn cancel(&mut self, event_id: EventId) -> bool {
+        let len = self.events.len();
+        self.events.retain(|Reverse(event)| event.id != event_id);
+        self.events.len() != len
+    }
+
     /// Get all events currently in the queue (for debugging)
     pub fn get_all_events(&self) -> Vec<&SimulatorEvent> {
         self.events.iter().map(|Reverse(event)| event).collect()
@@ -0,0 +1,276 @@
+//! Stuck-at faults and fault coverage.
+//!
+//! A stuck-at fault holds a net, or a single component pin, at a constant
+//! low or high level whatever drives it. Running a [`TestSet`] against the
+//! fault-free circuit and then once per injected fault shows which faults
+//! the test vectors detect, as used to grade test vectors in design for
+//! testability.
+
+use crate::comp::ComponentId;
+use crate::netlist::NodeId;
+use crate::signal::{Signal, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Level a faulty net or pin is stuck at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum StuckAt {
+    /// Stuck low
+    Zero,
+    /// Stuck high
+    One,
+}
+
+impl StuckAt {
+    /// Get the value the fault holds
+    pub fn value(self) -> Value {
+        match self {
+            StuckAt::Zero => Value::Low,
+            StuckAt::One => Value::High,
+        }
+    }
+}
+
+/// Where a fault sits
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FaultSite {
+    /// A whole net, seen the same by every component on it
+    Node(NodeId),
+    /// One pin of a component: an input reads the stuck value and an output
+    /// drives it, while the rest of the net is unaffected
+    Pin { component: ComponentId, pin: String },
+}
+
+/// A stuck-at fault
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Fault {
+    /// Where the fault sits
+    pub site: FaultSite,
+    /// Level it is stuck at
+    pub stuck_at: StuckAt,
+}
+
+impl Fault {
+    /// A net stuck at a level
+    pub fn node(node: NodeId, stuck_at: StuckAt) -> Self {
+        Fault {
+            site: FaultSite::Node(node),
+            stuck_at,
+        }
+    }
+
+    /// A component pin stuck at a level
+    pub fn pin(component: ComponentId, pin: impl Into<String>, stuck_at: StuckAt) -> Self {
+        Fault {
+            site: FaultSite::Pin {
+                component,
+                pin: pin.into(),
+            },
+            stuck_at,
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.site {
+            FaultSite::Node(node) => write!(f, "{}", node)?,
+            FaultSite::Pin { component, pin } => write!(f, "{}.{}", component, pin)?,
+        }
+        match self.stuck_at {
+            StuckAt::Zero => write!(f, " stuck-at-0"),
+            StuckAt::One => write!(f, " stuck-at-1"),
+        }
+    }
+}
+
+/// Input vectors to apply and the outputs to observe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestSet {
+    /// Nodes the vectors drive
+    pub inputs: Vec<NodeId>,
+    /// Nodes sampled after every vector
+    pub outputs: Vec<NodeId>,
+    /// One signal per input for every vector
+    pub vectors: Vec<Vec<Signal>>,
+    /// Time every vector is held before the outputs are sampled
+    pub period: u64,
+}
+
+impl TestSet {
+    /// Create a test set holding every vector for 100 time units
+    pub fn new(inputs: Vec<NodeId>, outputs: Vec<NodeId>, vectors: Vec<Vec<Signal>>) -> Self {
+        TestSet {
+            inputs,
+            outputs,
+            vectors,
+            period: 100,
+        }
+    }
+
+    /// Set the time every vector is held before the outputs are sampled
+    pub fn with_period(mut self, period: u64) -> Self {
+        self.period = period;
+        self
+    }
+}
+
+/// A fault the test set detects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedFault {
+    /// The fault
+    pub fault: Fault,
+    /// Index of the first vector that detects it
+    pub vector: usize,
+    /// First output that differs for that vector
+    pub output: NodeId,
+}
+
+/// Result of a fault simulation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultReport {
+    /// Faults some vector detects, in the order they were simulated
+    pub detected: Vec<DetectedFault>,
+    /// Faults no vector detects, in the order they were simulated
+    pub undetected: Vec<Fault>,
+}
+
+impl FaultReport {
+    /// Number of faults simulated
+    pub fn total(&self) -> usize {
+        self.detected.len() + self.undetected.len()
+    }
+
+    /// Fraction of the faults detected, 1.0 when there were none
+    pub fn coverage(&self) -> f64 {
+        match self.total() {
+            0 => 1.0,
+            total => self.detected.len() as f64 / total as f64,
+        }
+    }
+}
+
+impl fmt::Display for FaultReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Fault coverage: {}/{} ({:.1}%)",
+            self.detected.len(),
+            self.total(),
+            self.coverage() * 100.0
+        )?;
+        if !self.undetected.is_empty() {
+            write!(f, "\nUndetected faults:")?;
+            for fault in &self.undetected {
+                write!(f, "\n  {}", fault)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stuck values of the faulty pins of every component
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PinFaults {
+    pins: HashMap<ComponentId, HashMap<String, Value>>,
+}
+
+impl PinFaults {
+    pub fn insert(&mut self, component_id: ComponentId, pin: String, value: Value) {
+        self.pins
+            .entry(component_id)
+            .or_default()
+            .insert(pin, value);
+    }
+
+    pub fn remove(&mut self, component_id: ComponentId, pin: &str) -> bool {
+        let Some(pins) = self.pins.get_mut(&component_id) else {
+            return false;
+        };
+        let removed = pins.remove(pin).is_some();
+        if pins.is_empty() {
+            self.pins.remove(&component_id);
+        }
+        removed
+    }
+
+    /// Every faulty pin with its stuck value
+    pub fn iter(&self) -> impl Iterator<Item = (ComponentId, &str, Value)> {
+        self.pins.iter().flat_map(|(&component_id, pins)| {
+            pins.iter()
+                .map(move |(pin, &value)| (component_id, pin.as_str(), value))
+        })
+    }
+
+    /// Signal a component sees or drives through one of its pins
+    pub fn through(&self, component_id: ComponentId, pin: &str, signal: &Signal) -> Signal {
+        match self.pins.get(&component_id).and_then(|pins| pins.get(pin)) {
+            Some(&value) => Signal::new_uniform(signal.width(), value),
+            None => signal.clone(),
+        }
+    }
+
+    /// Replace the outputs a component drives through its faulty pins
+    pub fn apply_outputs(&self, component_id: ComponentId, outputs: &mut HashMap<String, Signal>) {
+        let Some(pins) = self.pins.get(&component_id) else {
+            return;
+        };
+        for (pin, signal) in outputs.iter_mut() {
+            if let Some(&value) = pins.get(pin) {
+                *signal = Signal::new_uniform(signal.width(), value);
+            }
+        }
+    }
+}
+
+/// First output that tells a faulty response from the fault-free one
+///
+/// Only outputs the fault-free circuit drives to fully defined values count,
+/// so a fault is detected when it turns such an output into anything else.
+pub(crate) fn first_difference(good: &[Signal], faulty: &[Signal]) -> Option<usize> {
+    good.iter()
+        .zip(faulty)
+        .position(|(good, faulty)| good.is_fully_defined() && good != faulty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::BusWidth;
+
+    #[test]
+    fn test_detection_needs_defined_good_value() {
+        let low = Signal::new_single(Value::Low);
+        let high = Signal::new_single(Value::High);
+        let unknown = Signal::unknown(BusWidth(1));
+
+        assert_eq!(first_difference(&[low.clone()], &[low.clone()]), None);
+        assert_eq!(
+            first_difference(
+                &[low.clone(), high.clone()],
+                &[low.clone(), unknown.clone()]
+            ),
+            Some(1)
+        );
+        assert_eq!(first_difference(&[unknown], &[high]), None);
+    }
+
+    #[test]
+    fn test_report_coverage() {
+        let mut report = FaultReport::default();
+        assert_eq!(report.coverage(), 1.0);
+
+        report.undetected.push(Fault::node(NodeId(3), StuckAt::One));
+        report.detected.push(DetectedFault {
+            fault: Fault::pin(ComponentId(1), "A", StuckAt::Zero),
+            vector: 0,
+            output: NodeId(2),
+        });
+        assert_eq!(report.coverage(), 0.5);
+        assert_eq!(
+            report.to_string(),
+            "Fault coverage: 1/2 (50.0%)\nUndetected faults:\n  N3 stuck-at-1"
+        );
+    }
+}
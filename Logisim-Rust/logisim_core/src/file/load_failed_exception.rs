@@ -31,8 +31,14 @@ impl From<std::io::Error> for LoadFailedException {
     }
 }
 
-impl From<crate::circ_parser::CircParseError> for LoadFailedException {
-    fn from(error: crate::circ_parser::CircParseError) -> Self {
-        LoadFailedException::ParseError(error.to_string())
+impl From<crate::circ_format::CircFormatError> for LoadFailedException {
+    fn from(error: crate::circ_format::CircFormatError) -> Self {
+        match error {
+            crate::circ_format::CircFormatError::IoError(error) => error.into(),
+            crate::circ_format::CircFormatError::LibraryNotFound(library) => {
+                LoadFailedException::LibraryNotFound(library)
+            }
+            error => LoadFailedException::ParseError(error.to_string()),
+        }
     }
 }
@@ -3,7 +3,7 @@
 //! This module provides file loading functionality equivalent to Java's Loader class
 
 use super::LoadFailedException;
-use crate::{CircParser, CircuitProject};
+use crate::{CircParser, CircuitFile};
 use std::path::Path;
 
 /// Circuit file loader - equivalent to Java's Loader class
@@ -25,7 +25,7 @@ impl Loader {
     pub fn open_logisim_file<P: AsRef<Path>>(
         &mut self,
         path: P,
-    ) -> Result<CircuitProject, LoadFailedException> {
+    ) -> Result<CircuitFile, LoadFailedException> {
         let path = path.as_ref();
 
         // Check if file exists
@@ -42,21 +42,17 @@ impl Loader {
             }
         }
 
-        // Load and parse the circuit file
-        let _parser = CircParser;
-        // For now, return a basic project structure
+        // Load and parse the circuit file, along with the .circ libraries
+        // it uses
         log::info!("Loading circuit file: {}", path.display());
-
-        let project = CircuitProject {
-            source: path.display().to_string(),
-            version: "1.0".to_string(),
-            libraries: Vec::new(),
-            main_circuit: "main".to_string(),
-            options: indexmap::IndexMap::new(),
-            mappings: Vec::new(),
-            toolbar: Vec::new(),
-            circuits: Vec::new(),
-        };
+        let project = CircParser::load_file(path)?;
+        for library in &project.libraries {
+            if let Some(external_file) = &library.external_file {
+                if !self.is_library_loaded(external_file) {
+                    self.libraries.push(external_file.clone());
+                }
+            }
+        }
 
         Ok(project)
     }
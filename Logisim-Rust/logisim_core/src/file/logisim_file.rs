@@ -2,7 +2,7 @@
 //!
 //! This module provides the main file data structure equivalent to Java's LogisimFile class
 
-use crate::CircuitProject;
+use crate::{CircWriter, CircuitFile};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -14,7 +14,7 @@ pub struct LogisimFile {
     pub path: Option<PathBuf>,
 
     /// The main circuit project
-    pub project: CircuitProject,
+    pub project: CircuitFile,
 
     /// File version information
     pub version: String,
@@ -34,16 +34,7 @@ impl LogisimFile {
     pub fn new() -> Self {
         Self {
             path: None,
-            project: CircuitProject {
-                source: "untitled".to_string(),
-                version: "1.0".to_string(),
-                libraries: Vec::new(),
-                main_circuit: "main".to_string(),
-                options: indexmap::IndexMap::new(),
-                mappings: Vec::new(),
-                toolbar: Vec::new(),
-                circuits: Vec::new(),
-            },
+            project: CircuitFile::default(),
             version: "1.0".to_string(),
             dirty: false,
             libraries: Vec::new(),
@@ -52,7 +43,7 @@ impl LogisimFile {
     }
 
     /// Create a Logisim file from a project
-    pub fn from_project(project: CircuitProject) -> Self {
+    pub fn from_project(project: CircuitFile) -> Self {
         Self {
             path: None,
             project,
@@ -144,12 +135,12 @@ impl LogisimFile {
     }
 
     /// Get the main project
-    pub fn get_project(&self) -> &CircuitProject {
+    pub fn get_project(&self) -> &CircuitFile {
         &self.project
     }
 
     /// Get mutable reference to the main project
-    pub fn get_project_mut(&mut self) -> &mut CircuitProject {
+    pub fn get_project_mut(&mut self) -> &mut CircuitFile {
         self.set_dirty(true);
         &mut self.project
     }
@@ -166,10 +157,8 @@ impl LogisimFile {
 
     /// Save the file to a specific path
     pub fn save_to(&mut self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        // Use the circuit serializer to save the file
-        let _serializer = crate::CircSerializer;
-        // For now, just log the save operation
         log::info!("Saving circuit file to: {}", path.display());
+        CircWriter::save_file(&self.project, &path)?;
 
         self.path = Some(path);
         self.dirty = false;
@@ -4,6 +4,7 @@
 //! This module ports functionality from Java com.cburch.hdl.HdlModel and HdlModelListener.
 
 use crate::data::BitWidth;
+use crate::ieee1164::LogicType;
 use std::collections::HashMap;
 
 /// Port description for HDL entities
@@ -53,6 +54,11 @@ impl PortDescription {
     pub fn get_width(&self) -> BitWidth {
         self.width
     }
+
+    /// Get the IEEE 1164 type the port carries, if it is one
+    pub fn get_logic_type(&self) -> Option<LogicType> {
+        LogicType::parse(&self.port_type)
+    }
 }
 
 /// HDL model change event
@@ -5,6 +5,7 @@
 //! critical information such as entity ports and architecture details.
 
 use crate::hdl::model::PortDescription;
+use crate::ieee1164::LogicType;
 use regex::Regex;
 use thiserror::Error;
 
@@ -42,8 +43,7 @@ pub struct VhdlParser {
 impl VhdlParser {
     // Pattern constants equivalent to Java
     const LINE_PATTERN: &'static str = r":\s*(\w+)\s+std_logic";
-    const VECTOR_PATTERN: &'static str =
-        r":\s*(\w+)\s+std_logic_vector\s*\(\s*(\d+)\s+downto\s+(\d+)\s*\)";
+    const VECTOR_PATTERN: &'static str = r"(?i)\(\s*(\d+)\s+(?:downto|to)\s+(\d+)\s*\)";
 
     /// Create a new VHDL parser with the given source code
     pub fn new(source: String) -> VhdlResult<Self> {
@@ -125,7 +125,7 @@ impl VhdlParser {
     /// Extract architecture section
     fn extract_architecture(&mut self) -> VhdlResult<()> {
         let arch_regex = Regex::new(
-            r"(?i)architecture\s+(\w+)\s+of\s+(\w+)\s+is(.*?)(?:end\s+(?:architecture\s+)?\w*\s*;|\z)",
+            r"(?is)architecture\s+(\w+)\s+of\s+(\w+)\s+is(.*?)(?:end\s+(?:architecture\s+)?\w*\s*;|\z)",
         )?;
 
         if let Some(captures) = arch_regex.captures(&self.source) {
@@ -139,14 +139,27 @@ impl VhdlParser {
 
     /// Extract port declarations
     fn extract_ports(&mut self) -> VhdlResult<()> {
-        // Find the port section
-        let port_regex = Regex::new(r"(?i)port\s*\((.*?)\)\s*;")?;
-
-        if let Some(captures) = port_regex.captures(self.source.clone().as_str()) {
-            if let Some(port_section) = captures.get(1) {
-                let port_section_str = port_section.as_str().to_string();
-                self.parse_port_section(&port_section_str)?;
-            }
+        // Find the port section; it runs to the matching parenthesis, as
+        // vector ranges nest their own
+        let port_regex = Regex::new(r"(?i)\bport\s*\(")?;
+
+        if let Some(port_match) = port_regex.find(&self.source) {
+            let body = &self.source[port_match.end()..];
+            let mut depth = 1;
+            let end = body
+                .char_indices()
+                .find(|&(_, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })
+                .map(|(index, _)| index)
+                .ok_or_else(|| VhdlParseError::PortError("Unterminated port list".to_string()))?;
+            let port_section = body[..end].to_string();
+            self.parse_port_section(&port_section)?;
         }
 
         Ok(())
@@ -208,27 +221,24 @@ impl VhdlParser {
 
     /// Extract bit width from port type
     fn extract_width(&self, port_type: &str) -> VhdlResult<i32> {
-        if port_type.to_lowercase().contains("std_logic_vector") {
+        if LogicType::parse(port_type).is_some_and(LogicType::is_vector) {
             let vector_regex = Regex::new(Self::VECTOR_PATTERN)?;
             if let Some(captures) = vector_regex.captures(port_type) {
-                if let (Some(high), Some(low)) = (captures.get(2), captures.get(3)) {
-                    let high_val: i32 = high
+                if let (Some(left), Some(right)) = (captures.get(1), captures.get(2)) {
+                    let left_val: i32 = left
                         .as_str()
                         .parse()
-                        .map_err(|_| VhdlParseError::PortError("Invalid high index".to_string()))?;
-                    let low_val: i32 = low
-                        .as_str()
-                        .parse()
-                        .map_err(|_| VhdlParseError::PortError("Invalid low index".to_string()))?;
-                    return Ok(high_val - low_val + 1);
+                        .map_err(|_| VhdlParseError::PortError("Invalid left index".to_string()))?;
+                    let right_val: i32 = right.as_str().parse().map_err(|_| {
+                        VhdlParseError::PortError("Invalid right index".to_string())
+                    })?;
+                    return Ok((left_val - right_val).abs() + 1);
                 }
             }
             // Default vector width if parsing fails
             Ok(8)
-        } else if port_type.to_lowercase().contains("std_logic") {
-            Ok(1)
         } else {
-            // Unknown type, assume single bit
+            // std_logic and std_ulogic, or an unknown type assumed single bit
             Ok(1)
         }
     }
@@ -464,4 +474,25 @@ end test;"#;
         assert_eq!(outputs[0].get_width_int(), 16);
         assert_eq!(outputs[1].get_width_int(), 32);
     }
+
+    #[test]
+    fn test_std_ulogic_ports() {
+        let vhdl = r#"
+entity test is
+    port (
+        sel : in std_ulogic;
+        bus_in : in std_ulogic_vector(0 to 3);
+        bus_out : out std_logic_vector(3 downto 0)
+    );
+end test;"#;
+
+        let parser = VhdlParser::new(vhdl.to_string()).unwrap();
+
+        let inputs = parser.get_inputs();
+        assert_eq!(inputs[0].get_logic_type(), Some(LogicType::StdULogic));
+        assert_eq!(inputs[1].get_width_int(), 4);
+        let outputs = parser.get_outputs();
+        assert_eq!(outputs[0].get_logic_type(), Some(LogicType::StdLogicVector));
+        assert_eq!(outputs[0].get_width_int(), 4);
+    }
 }
@@ -0,0 +1,353 @@
+//! IEEE 1164 nine-valued logic.
+//!
+//! [`StdLogic`] is the VHDL `std_ulogic` type: besides forcing 0, 1 and X
+//! it has the uninitialized U, high impedance Z, the weak levels L, H and W
+//! and the don't-care `-`. With [`LogicMode::Ieee1164`] a netlist resolves
+//! the drivers of every bit with the standard resolution table, treating
+//! pull resistors as weak drivers, so the nine-valued levels of a node can
+//! be compared against VHDL simulator traces.
+//!
+//! Components still drive the [`Value`]s of a [`Signal`], which map to the
+//! forcing levels U, X, 0, 1 and Z.
+
+use crate::signal::{Signal, Value};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How the drivers of a bit are resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LogicMode {
+    /// Logisim's rules: a pull resistor only applies to a floating bit, and
+    /// conflicting drivers give an error
+    #[default]
+    Logisim,
+    /// The IEEE 1164 resolution table, with pull resistors as weak drivers
+    Ieee1164,
+}
+
+/// One IEEE 1164 `std_ulogic` value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StdLogic {
+    /// `U`: uninitialized
+    U,
+    /// `X`: forcing unknown
+    X,
+    /// `0`: forcing 0
+    Zero,
+    /// `1`: forcing 1
+    One,
+    /// `Z`: high impedance
+    Z,
+    /// `W`: weak unknown
+    W,
+    /// `L`: weak 0
+    L,
+    /// `H`: weak 1
+    H,
+    /// `-`: don't care
+    DontCare,
+}
+
+use StdLogic::{DontCare as D, One as I, Zero as O, H, L, U, W, X, Z};
+
+/// Order of the rows and columns of [`RESOLUTION`]
+const ORDER: [StdLogic; 9] = [U, X, O, I, Z, W, L, H, D];
+
+/// `resolution_table` from the `std_logic_1164` package body
+const RESOLUTION: [[StdLogic; 9]; 9] = [
+    //U  X  0  1  Z  W  L  H  -
+    [U, U, U, U, U, U, U, U, U], // U
+    [U, X, X, X, X, X, X, X, X], // X
+    [U, X, O, X, O, O, O, O, X], // 0
+    [U, X, X, I, I, I, I, I, X], // 1
+    [U, X, O, I, Z, W, L, H, X], // Z
+    [U, X, O, I, W, W, W, W, X], // W
+    [U, X, O, I, L, W, L, W, X], // L
+    [U, X, O, I, H, W, W, H, X], // H
+    [U, X, X, X, X, X, X, X, X], // -
+];
+
+impl StdLogic {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Resolve two values driven onto the same signal
+    pub fn resolve(self, other: StdLogic) -> StdLogic {
+        RESOLUTION[self.index()][other.index()]
+    }
+
+    /// Resolve any number of drivers; a signal nothing drives floats at `Z`
+    pub fn resolve_all(drivers: impl IntoIterator<Item = StdLogic>) -> StdLogic {
+        drivers.into_iter().fold(Z, StdLogic::resolve)
+    }
+
+    /// Strip the strength, as `To_UX01` does: L and H become 0 and 1, and
+    /// Z, W and `-` become X
+    pub fn to_ux01(self) -> StdLogic {
+        match self {
+            U => U,
+            O | L => O,
+            I | H => I,
+            X | Z | W | D => X,
+        }
+    }
+
+    /// `and` of the `std_logic_1164` package
+    pub fn and(self, other: StdLogic) -> StdLogic {
+        match (self.to_ux01(), other.to_ux01()) {
+            (O, _) | (_, O) => O,
+            (U, _) | (_, U) => U,
+            (X, _) | (_, X) => X,
+            _ => I,
+        }
+    }
+
+    /// `or` of the `std_logic_1164` package
+    pub fn or(self, other: StdLogic) -> StdLogic {
+        match (self.to_ux01(), other.to_ux01()) {
+            (I, _) | (_, I) => I,
+            (U, _) | (_, U) => U,
+            (X, _) | (_, X) => X,
+            _ => O,
+        }
+    }
+
+    /// `xor` of the `std_logic_1164` package
+    pub fn xor(self, other: StdLogic) -> StdLogic {
+        match (self.to_ux01(), other.to_ux01()) {
+            (U, _) | (_, U) => U,
+            (X, _) | (_, X) => X,
+            (a, b) => StdLogic::from_bool(a != b),
+        }
+    }
+
+    /// `std_match` of `numeric_std`: `-` matches anything, 0 matches L and
+    /// 1 matches H, and no other level matches at all
+    pub fn std_match(self, other: StdLogic) -> bool {
+        match (self, other) {
+            (D, _) | (_, D) => true,
+            (a, b) => matches!((a.to_ux01(), b.to_ux01()), (O, O) | (I, I)),
+        }
+    }
+
+    /// Convert from boolean to a forcing level
+    pub fn from_bool(value: bool) -> StdLogic {
+        if value {
+            I
+        } else {
+            O
+        }
+    }
+
+    /// The forcing level a component's output value drives
+    pub fn from_value(value: Value) -> StdLogic {
+        match value {
+            Value::High => I,
+            Value::Low => O,
+            Value::Unknown => U,
+            Value::Error => X,
+            Value::HighZ => Z,
+        }
+    }
+
+    /// The weak level a pull resistor to a value drives
+    pub fn weak(value: Value) -> StdLogic {
+        match value {
+            Value::High => H,
+            Value::Low => L,
+            Value::HighZ => Z,
+            Value::Unknown | Value::Error => W,
+        }
+    }
+
+    /// The value components read: weak levels read as their forcing
+    /// counterparts, W as an error and `-` as unknown
+    pub fn to_value(self) -> Value {
+        match self {
+            O | L => Value::Low,
+            I | H => Value::High,
+            U | D => Value::Unknown,
+            X | W => Value::Error,
+            Z => Value::HighZ,
+        }
+    }
+
+    /// Get the VHDL character literal of this value
+    pub fn to_char(self) -> char {
+        match self {
+            U => 'U',
+            X => 'X',
+            O => '0',
+            I => '1',
+            Z => 'Z',
+            W => 'W',
+            L => 'L',
+            H => 'H',
+            D => '-',
+        }
+    }
+
+    /// Parse a value from its VHDL character literal (case-insensitive)
+    pub fn from_char(c: char) -> Option<StdLogic> {
+        let c = c.to_ascii_uppercase();
+        ORDER.into_iter().find(|value| value.to_char() == c)
+    }
+}
+
+impl fmt::Display for StdLogic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// `not` of the `std_logic_1164` package
+impl std::ops::Not for StdLogic {
+    type Output = StdLogic;
+
+    fn not(self) -> StdLogic {
+        match self.to_ux01() {
+            O => I,
+            I => O,
+            other => other,
+        }
+    }
+}
+
+/// Levels of every bit of a signal, least significant bit first
+pub fn from_signal(signal: &Signal) -> Vec<StdLogic> {
+    signal
+        .bits()
+        .iter()
+        .map(|&value| StdLogic::from_value(value))
+        .collect()
+}
+
+/// The signal components read for some levels, least significant bit first
+pub fn to_signal(levels: &[StdLogic]) -> Signal {
+    Signal::new_bus(levels.iter().map(|level| level.to_value()).collect())
+}
+
+/// Format levels, least significant bit first, the way VHDL writes a
+/// `std_logic_vector` literal: most significant bit first
+pub fn format_vector(levels: &[StdLogic]) -> String {
+    levels.iter().rev().map(|level| level.to_char()).collect()
+}
+
+/// Parse a `std_logic_vector` literal, most significant bit first, into
+/// levels least significant bit first; surrounding double quotes are
+/// optional
+pub fn parse_vector(text: &str) -> Option<Vec<StdLogic>> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text);
+    text.chars().rev().map(StdLogic::from_char).collect()
+}
+
+/// VHDL port types carrying IEEE 1164 values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogicType {
+    /// `std_logic`
+    StdLogic,
+    /// `std_ulogic`
+    StdULogic,
+    /// `std_logic_vector`
+    StdLogicVector,
+    /// `std_ulogic_vector`
+    StdULogicVector,
+}
+
+impl LogicType {
+    /// Recognize the type of a port declaration such as
+    /// `STD_LOGIC_VECTOR (7 downto 0)`, ignoring case and any range
+    pub fn parse(port_type: &str) -> Option<LogicType> {
+        let name: String = port_type
+            .trim()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        match name.to_ascii_lowercase().as_str() {
+            "std_logic" => Some(LogicType::StdLogic),
+            "std_ulogic" => Some(LogicType::StdULogic),
+            "std_logic_vector" => Some(LogicType::StdLogicVector),
+            "std_ulogic_vector" => Some(LogicType::StdULogicVector),
+            _ => None,
+        }
+    }
+
+    /// Check if the type is a vector of values
+    pub fn is_vector(self) -> bool {
+        matches!(self, LogicType::StdLogicVector | LogicType::StdULogicVector)
+    }
+
+    /// Check if the type is resolved, allowing several drivers
+    pub fn is_resolved(self) -> bool {
+        matches!(self, LogicType::StdLogic | LogicType::StdLogicVector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolution_table() {
+        assert_eq!(O.resolve(I), X);
+        assert_eq!(H.resolve(O), O);
+        assert_eq!(H.resolve(L), W);
+        assert_eq!(Z.resolve(L), L);
+        assert_eq!(U.resolve(I), U);
+        assert_eq!(D.resolve(Z), X);
+        assert_eq!(StdLogic::resolve_all([]), Z);
+        assert_eq!(StdLogic::resolve_all([Z, H, Z]), H);
+
+        // The table is symmetric
+        for a in ORDER {
+            for b in ORDER {
+                assert_eq!(a.resolve(b), b.resolve(a), "{} {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_logic_operators() {
+        assert_eq!(U.and(O), O);
+        assert_eq!(U.and(I), U);
+        assert_eq!(H.and(I), I);
+        assert_eq!(Z.or(L), X);
+        assert_eq!(W.or(H), I);
+        assert_eq!(L.xor(H), I);
+        assert_eq!(!Z, X);
+        assert_eq!(!L, I);
+
+        assert!(D.std_match(U));
+        assert!(L.std_match(O));
+        assert!(!X.std_match(X));
+        assert!(!Z.std_match(Z));
+    }
+
+    #[test]
+    fn test_vectors_and_types() {
+        let levels = parse_vector("\"10ZH-\"").unwrap();
+        assert_eq!(levels, vec![D, H, Z, O, I]);
+        assert_eq!(format_vector(&levels), "10ZH-");
+        assert_eq!(parse_vector("01Q"), None);
+        assert_eq!(
+            to_signal(&levels),
+            Signal::new_bus(vec![
+                Value::Unknown,
+                Value::High,
+                Value::HighZ,
+                Value::Low,
+                Value::High
+            ])
+        );
+
+        assert_eq!(LogicType::parse("STD_LOGIC"), Some(LogicType::StdLogic));
+        let vector = LogicType::parse("std_ulogic_vector(3 downto 0)").unwrap();
+        assert!(vector.is_vector() && !vector.is_resolved());
+        assert_eq!(LogicType::parse("integer range 0 to 7"), None);
+    }
+}
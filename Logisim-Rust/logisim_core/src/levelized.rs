@@ -0,0 +1,286 @@
+//! Levelized evaluation order for combinational logic.
+//!
+//! The levelized engine sorts the combinational components of a circuit
+//! topologically once, so that every component comes after the components
+//! driving its inputs. A change can then be settled in a single pass in that
+//! order, with zero delay, instead of one `ComponentUpdate` event per
+//! component and gate delay. Sequential components break the order: their
+//! outputs count as primary inputs of the combinational logic.
+
+use crate::comp::{Component, ComponentId};
+use crate::netlist::{Netlist, NodeId};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+
+/// Compiled evaluation order of a circuit
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Schedule {
+    /// Combinational components, each after the components feeding it
+    order: Vec<ComponentId>,
+    /// Position of every combinational component in `order`
+    positions: HashMap<ComponentId, usize>,
+    /// Number of ranks, i.e. the longest combinational path in components
+    depth: usize,
+    /// Input pins of every component with the node each one reads
+    inputs: HashMap<ComponentId, Vec<(String, NodeId)>>,
+    /// Output pins of every component with the node each one drives
+    outputs: HashMap<ComponentId, HashMap<String, NodeId>>,
+    /// Components reading every node
+    readers: HashMap<NodeId, Vec<ComponentId>>,
+}
+
+/// Compilation state of the levelized engine
+#[derive(Debug, Clone, Default)]
+pub(crate) enum Levelization {
+    /// The circuit changed since the last compilation
+    #[default]
+    Stale,
+    /// The combinational logic has been sorted
+    Ready(Box<Schedule>),
+    /// The combinational logic contains a loop or a switch, so the event
+    /// engine is used
+    Cyclic,
+}
+
+impl Schedule {
+    /// Sort the combinational components of a circuit
+    ///
+    /// Returns `None` when the combinational logic contains a loop or a
+    /// switch component, which joins nets in both directions. A component
+    /// reading its own output does not count as a loop.
+    pub(crate) fn compile(
+        netlist: &Netlist,
+        components: &HashMap<ComponentId, Box<dyn Component>>,
+    ) -> Option<Schedule> {
+        if components
+            .values()
+            .any(|component| component.switch_state().is_some())
+        {
+            return None;
+        }
+
+        let mut schedule = Schedule::default();
+        for (&component_id, component) in components {
+            for connection in netlist.get_component_connections(component_id) {
+                let Some(pin) = component.get_pin(&connection.pin_name) else {
+                    continue;
+                };
+                if pin.is_input() {
+                    schedule
+                        .inputs
+                        .entry(component_id)
+                        .or_default()
+                        .push((connection.pin_name.clone(), connection.node_id));
+                    schedule
+                        .readers
+                        .entry(connection.node_id)
+                        .or_default()
+                        .push(component_id);
+                }
+                if pin.is_output() {
+                    schedule
+                        .outputs
+                        .entry(component_id)
+                        .or_default()
+                        .insert(connection.pin_name.clone(), connection.node_id);
+                }
+            }
+        }
+        for readers in schedule.readers.values_mut() {
+            readers.sort();
+            readers.dedup();
+        }
+
+        // Edges between combinational components, following splitters
+        let combinational: HashSet<ComponentId> = components
+            .iter()
+            .filter(|(_, component)| !component.is_sequential())
+            .map(|(&component_id, _)| component_id)
+            .collect();
+        let mut successors: HashMap<ComponentId, HashSet<ComponentId>> = HashMap::new();
+        let mut in_degree: HashMap<ComponentId, usize> =
+            combinational.iter().map(|&id| (id, 0)).collect();
+        for &component_id in &combinational {
+            let driven = schedule.outputs.get(&component_id).into_iter().flatten();
+            for (_, &node_id) in driven {
+                for linked in linked_nodes(netlist, node_id) {
+                    for &reader in schedule.readers.get(&linked).into_iter().flatten() {
+                        if reader != component_id
+                            && combinational.contains(&reader)
+                            && successors.entry(component_id).or_default().insert(reader)
+                        {
+                            *in_degree.get_mut(&reader).unwrap() += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Kahn's algorithm, rank by rank, lowest ID first for a stable order
+        let mut ranks: HashMap<ComponentId, usize> = HashMap::new();
+        let mut ready: BinaryHeap<Reverse<ComponentId>> = in_degree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&id, _)| Reverse(id))
+            .collect();
+        while let Some(Reverse(component_id)) = ready.pop() {
+            let rank = ranks.get(&component_id).copied().unwrap_or(0);
+            schedule.depth = schedule.depth.max(rank + 1);
+            schedule
+                .positions
+                .insert(component_id, schedule.order.len());
+            schedule.order.push(component_id);
+            for &next in successors.get(&component_id).into_iter().flatten() {
+                let next_rank = ranks.entry(next).or_default();
+                *next_rank = (*next_rank).max(rank + 1);
+                let degree = in_degree.get_mut(&next).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+
+        (schedule.order.len() == combinational.len()).then_some(schedule)
+    }
+
+    /// Position of a combinational component in the evaluation order
+    pub(crate) fn position(&self, component_id: ComponentId) -> Option<usize> {
+        self.positions.get(&component_id).copied()
+    }
+
+    /// Number of combinational components
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Combinational component at a position in the evaluation order
+    pub(crate) fn component_at(&self, position: usize) -> ComponentId {
+        self.order[position]
+    }
+
+    /// Number of ranks in the combinational logic
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Input pins of a component with the node each one reads
+    pub(crate) fn inputs(&self, component_id: ComponentId) -> &[(String, NodeId)] {
+        self.inputs
+            .get(&component_id)
+            .map_or(&[], |inputs| inputs.as_slice())
+    }
+
+    /// Node driven by an output pin of a component
+    pub(crate) fn output_node(&self, component_id: ComponentId, pin_name: &str) -> Option<NodeId> {
+        self.outputs
+            .get(&component_id)
+            .and_then(|outputs| outputs.get(pin_name))
+            .copied()
+    }
+
+    /// Output pins of a component with the node each one drives
+    pub(crate) fn outputs(&self, component_id: ComponentId) -> Vec<(&str, NodeId)> {
+        let mut outputs: Vec<(&str, NodeId)> = self
+            .outputs
+            .get(&component_id)
+            .into_iter()
+            .flatten()
+            .map(|(pin_name, &node_id)| (pin_name.as_str(), node_id))
+            .collect();
+        outputs.sort();
+        outputs
+    }
+
+    /// Components reading a node
+    pub(crate) fn readers(&self, node_id: NodeId) -> &[ComponentId] {
+        self.readers
+            .get(&node_id)
+            .map_or(&[], |readers| readers.as_slice())
+    }
+}
+
+/// Components waiting to be evaluated in the current pass
+#[derive(Debug, Default)]
+pub(crate) struct Dirty {
+    /// Positions of combinational components in the evaluation order
+    pub(crate) combinational: BTreeSet<usize>,
+    /// Sequential components, evaluated once the logic has settled
+    pub(crate) sequential: BTreeSet<ComponentId>,
+}
+
+impl Dirty {
+    /// Queue a component for evaluation
+    pub(crate) fn mark(&mut self, schedule: &Schedule, component_id: ComponentId) {
+        match schedule.position(component_id) {
+            Some(position) => {
+                self.combinational.insert(position);
+            }
+            None => {
+                self.sequential.insert(component_id);
+            }
+        }
+    }
+}
+
+/// A node and every node sharing bits with it through splitters
+pub(crate) fn linked_nodes(netlist: &Netlist, node_id: NodeId) -> HashSet<NodeId> {
+    let width = netlist
+        .get_node(node_id)
+        .map_or(0, |node| node.width.as_u32());
+    let mut nodes = HashSet::from([node_id]);
+    for bit in 0..width {
+        nodes.extend(
+            netlist
+                .get_thread(node_id, bit)
+                .into_iter()
+                .map(|(id, _)| id),
+        );
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::BusWidth;
+    use crate::std::gates::{AndGate, NotGate};
+
+    fn chain(feedback: bool) -> (Netlist, HashMap<ComponentId, Box<dyn Component>>) {
+        let mut netlist = Netlist::new();
+        let (a, b, c) = (
+            netlist.create_node(BusWidth(1)),
+            netlist.create_node(BusWidth(1)),
+            netlist.create_node(BusWidth(1)),
+        );
+        let mut components: HashMap<ComponentId, Box<dyn Component>> = HashMap::new();
+        // Added in reverse so the order cannot come from the IDs alone
+        components.insert(ComponentId(1), Box::new(NotGate::new(ComponentId(1))));
+        netlist.connect(ComponentId(1), "A".to_string(), b).unwrap();
+        netlist.connect(ComponentId(1), "Y".to_string(), c).unwrap();
+        components.insert(ComponentId(2), Box::new(AndGate::new(ComponentId(2))));
+        netlist.connect(ComponentId(2), "A".to_string(), a).unwrap();
+        let second = if feedback { c } else { a };
+        netlist
+            .connect(ComponentId(2), "B".to_string(), second)
+            .unwrap();
+        netlist.connect(ComponentId(2), "Y".to_string(), b).unwrap();
+        (netlist, components)
+    }
+
+    #[test]
+    fn test_schedule_orders_drivers_first() {
+        let (netlist, components) = chain(false);
+        let schedule = Schedule::compile(&netlist, &components).unwrap();
+
+        assert_eq!(schedule.component_at(0), ComponentId(2));
+        assert_eq!(schedule.component_at(1), ComponentId(1));
+        assert_eq!(schedule.depth(), 2);
+    }
+
+    #[test]
+    fn test_schedule_rejects_loops() {
+        let (netlist, components) = chain(true);
+        assert!(Schedule::compile(&netlist, &components).is_none());
+    }
+}
@@ -19,59 +19,78 @@
 //!
 //! ```rust
 //! use logisim_core::*;
-//! use logisim_core::comp::AndGate;
+//! use logisim_core::std::gates::AndGate;
 //! use logisim_core::signal::{Value, BusWidth};
 //! use logisim_core::simulation::Simulation;
 //!
 //! // Create a simulation
 //! let mut sim = Simulation::new();
 //!
-//! // Add an AND gate
-//! let gate = Box::new(AndGate::new(ComponentId(1)));
-//! let gate_id = sim.add_component(gate);
+//! // Add an 8-bit wide AND gate
+//! let mut gate = AndGate::new(ComponentId(1));
+//! gate.set_width(BusWidth(8));
+//! let gate_id = sim.add_component(Box::new(gate));
 //!
 //! // Create nodes for connections
-//! let input_a = sim.netlist_mut().create_named_node(BusWidth(1), "A".to_string());
-//! let input_b = sim.netlist_mut().create_named_node(BusWidth(1), "B".to_string());
-//! let output = sim.netlist_mut().create_named_node(BusWidth(1), "Y".to_string());
+//! let input_a = sim.netlist_mut().create_named_node(BusWidth(8), "A".to_string());
+//! let input_b = sim.netlist_mut().create_named_node(BusWidth(8), "B".to_string());
+//! let output = sim.netlist_mut().create_named_node(BusWidth(8), "Y".to_string());
 //!
-//! // Connect the gate
-//! sim.netlist_mut().connect(gate_id, "A".to_string(), input_a).unwrap();
-//! sim.netlist_mut().connect(gate_id, "B".to_string(), input_b).unwrap();
-//! sim.netlist_mut().connect(gate_id, "Y".to_string(), output).unwrap();
+//! // Connect the gate (bus widths are checked)
+//! sim.connect(gate_id, "A".to_string(), input_a).unwrap();
+//! sim.connect(gate_id, "B".to_string(), input_b).unwrap();
+//! sim.connect(gate_id, "Y".to_string(), output).unwrap();
 //!
 //! // Set up initial conditions and run simulation
 //! sim.reset();
-//! sim.schedule_signal_change(Timestamp(10), input_a, Signal::new_single(Value::High), ComponentId(0));
-//! sim.schedule_signal_change(Timestamp(10), input_b, Signal::new_single(Value::High), ComponentId(0));
+//! sim.schedule_signal_change(Timestamp(10), input_a, Signal::from_u64(0xf0, BusWidth(8)), ComponentId(0));
+//! sim.schedule_signal_change(Timestamp(10), input_b, Signal::from_u64(0x3c, BusWidth(8)), ComponentId(0));
 //! sim.run().unwrap();
+//! assert_eq!(sim.get_node_signal(output).unwrap().to_u64(), Some(0x30));
 //! ```
 
+pub mod activity;
+pub mod batch;
+pub mod breakpoint;
 pub mod build_info;
 pub mod circ_format;
-pub mod circ_parser;
-pub mod circ_serializer;
+pub mod circ_migration;
+pub mod circ_netlist;
+pub mod circ_registry;
+pub mod circ_validation;
 pub mod comp;
 pub mod components;
 pub mod contracts;
 pub mod data;
+pub mod delay;
+pub mod elf;
 pub mod event;
+pub mod fault;
 pub mod file;
 pub mod hdl;
+pub mod ieee1164;
 pub mod instance;
 pub mod integrations;
+mod levelized;
 pub mod netlist;
 pub mod prefs;
 pub mod signal;
 pub mod simulation;
+pub mod snapshot;
 pub mod std;
+pub mod timing;
 pub mod tools;
 pub mod util;
+pub mod vcd;
+pub mod violation;
 
 // Re-export core types for convenience
+pub use activity::{ActivityRecorder, ActivityReport, PowerModel};
+pub use batch::BatchEvaluator;
+pub use breakpoint::{BreakpointHit, BreakpointId, Condition};
 pub use build_info::BuildInfo;
-pub use circ_parser::{CircParseError, CircParser, CircuitProject};
-pub use circ_serializer::{CircSerializeError, CircSerializer};
+pub use circ_format::{CircFormatError, CircParser, CircWriter, CircuitFile};
+pub use circ_validation::{ValidationIssue, ValidationKind};
 pub use comp::{
     AbstractComponent, AbstractComponentFactory, Color, Component, ComponentDrawContext,
     ComponentEvent, ComponentFactory, ComponentId, ComponentListener, ComponentUserEvent,
@@ -88,13 +107,17 @@ pub use contracts::{
 pub use data::{
     Attribute, AttributeSet, AttributeValue, BitWidth, Bounds, Direction, Location, StdAttr,
 };
+pub use delay::{DelayModel, DelayTable};
+pub use elf::{ElfError, ElfFile};
 pub use event::{EventQueue, SimulatorEvent};
+pub use fault::{Fault, FaultReport, StuckAt, TestSet};
 pub use file::{LoadFailedException, Loader, LogisimFile};
 pub use hdl::{
     BlifCircuitComponent, BlifContentComponent, BlifParser, HdlContent, HdlContentEditor, HdlFile,
     HdlFileType, HdlLibrary, HdlModel, HdlModelListener, HdlStrings, PortDescription,
     VhdlContentComponent, VhdlEntityComponent, VhdlParser,
 };
+pub use ieee1164::{LogicMode, StdLogic};
 pub use instance::{
     Instance, InstanceComponent, InstanceData, InstanceFactory, InstanceState, Port, PortType,
     PortWidth,
@@ -102,10 +125,12 @@ pub use instance::{
 pub use integrations::{FpgaError, PluginError, TclError, VhdlError};
 pub use netlist::{NetId, Netlist, NodeId};
 pub use prefs::AppPreferences;
-pub use signal::{Bus, BusWidth, Signal, Timestamp, Value};
+pub use signal::{Bus, BusWidth, Lanes, Signal, Timestamp, Value};
 pub use simulation::Simulation;
+pub use snapshot::SimulationSnapshot;
 pub use std::wiring::WiringLibrary;
 pub use std::{base::*, gates::*, io::*};
+pub use timing::{TimingPath, TimingReport};
 pub use tools::{
     Action, BasicLibrary, Canvas, Circuit, CursorType, Library, Project, Selection, Tool,
     ToolError, ToolResult,
@@ -113,3 +138,5 @@ pub use tools::{
 pub use util::{
     Cache, CollectionUtil, FileUtil, LocaleManager, StringCache, StringGetter, StringUtil,
 };
+pub use vcd::{VcdOptions, VcdRecorder};
+pub use violation::{Violation, ViolationAction, ViolationKind};
@@ -5,10 +5,12 @@
 //! the connections between components.
 
 use crate::comp::ComponentId;
-use crate::signal::{BusWidth, Signal};
+use crate::ieee1164::{LogicMode, StdLogic};
+use crate::signal::{BusWidth, Signal, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::Arc;
 
 /// Unique identifier for a node (connection point)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -71,7 +73,7 @@ impl fmt::Display for NetId {
 pub struct Node {
     /// Unique identifier for this node
     pub id: NodeId,
-    /// Current signal at this node
+    /// Current (resolved) signal at this node
     pub signal: Signal,
     /// Bus width of this node
     pub width: BusWidth,
@@ -79,6 +81,48 @@ pub struct Node {
     pub connected_components: HashSet<ComponentId>,
     /// Name for debugging/display
     pub name: Option<String>,
+    /// Contribution of every component currently driving this node
+    #[serde(default)]
+    pub drivers: HashMap<ComponentId, Signal>,
+    /// Value that floating bits are pulled to, if a pull resistor is attached
+    #[serde(default)]
+    pub pull: Option<Value>,
+    /// Bits of this node that are wired to bits of other nodes (e.g. by a splitter)
+    #[serde(default)]
+    pub bit_links: Vec<BitLink>,
+    /// Value every bit is stuck at, whatever drives it, while a fault is
+    /// injected
+    #[serde(default)]
+    pub stuck_at: Option<Value>,
+}
+
+/// A link from one bit of a node to a bit of another node
+///
+/// Linked bits form a single electrical thread: every driver on any bit of
+/// the thread takes part in resolving the value seen on all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitLink {
+    /// Bit of the owning node
+    pub bit: u32,
+    /// Node on the other end of the link
+    pub other_node: NodeId,
+    /// Bit of the other node
+    pub other_bit: u32,
+}
+
+/// A closed switch joining two nodes of the same width
+///
+/// Transistors and transmission gates conduct in both directions, so while
+/// one is closed every bit of either node shares a thread with the same bit
+/// of the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Switch {
+    /// Component whose switch this is
+    pub component_id: ComponentId,
+    /// Node on one side of the switch
+    pub node_a: NodeId,
+    /// Node on the other side of the switch
+    pub node_b: NodeId,
 }
 
 impl Node {
@@ -90,6 +134,10 @@ impl Node {
             width,
             connected_components: HashSet::new(),
             name: None,
+            drivers: HashMap::new(),
+            pull: None,
+            bit_links: Vec::new(),
+            stuck_at: None,
         }
     }
 
@@ -101,6 +149,10 @@ impl Node {
             width,
             connected_components: HashSet::new(),
             name: Some(name),
+            drivers: HashMap::new(),
+            pull: None,
+            bit_links: Vec::new(),
+            stuck_at: None,
         }
     }
 
@@ -128,6 +180,19 @@ impl Node {
         &self.signal
     }
 
+    /// Get the value a component currently drives onto this node
+    pub fn get_driver(&self, component_id: ComponentId) -> Option<&Signal> {
+        self.drivers.get(&component_id)
+    }
+
+    /// Get the bits of other nodes that the given bit of this node is linked to
+    pub fn get_bit_links(&self, bit: u32) -> impl Iterator<Item = (NodeId, u32)> + '_ {
+        self.bit_links
+            .iter()
+            .filter(move |link| link.bit == bit)
+            .map(|link| (link.other_node, link.other_bit))
+    }
+
     /// Get all connected components
     pub fn get_connected_components(&self) -> &HashSet<ComponentId> {
         &self.connected_components
@@ -211,6 +276,9 @@ impl Connection {
     }
 }
 
+/// Every node bit electrically connected to one another
+type Thread = Arc<[(NodeId, u32)]>;
+
 /// The complete netlist representing the circuit
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Netlist {
@@ -224,6 +292,16 @@ pub struct Netlist {
     next_node_id: u64,
     /// Next net ID to assign
     next_net_id: u64,
+    /// How the drivers of a bit are resolved
+    #[serde(default)]
+    logic_mode: LogicMode,
+    /// Switches that are currently closed
+    #[serde(default)]
+    switches: Vec<Switch>,
+    /// Threads resolved so far, shared by every node bit on them; cleared
+    /// whenever bits are linked or a switch opens or closes
+    #[serde(skip)]
+    threads: HashMap<(NodeId, u32), Thread>,
 }
 
 impl Netlist {
@@ -235,7 +313,32 @@ impl Netlist {
             connections: Vec::new(),
             next_node_id: 1,
             next_net_id: 1,
+            logic_mode: LogicMode::default(),
+            switches: Vec::new(),
+            threads: HashMap::new(),
+        }
+    }
+
+    /// Get how the drivers of a bit are resolved
+    pub fn logic_mode(&self) -> LogicMode {
+        self.logic_mode
+    }
+
+    /// Set how the drivers of a bit are resolved and resolve every node again
+    ///
+    /// Returns the nodes whose resolved signal changed.
+    pub fn set_logic_mode(&mut self, logic_mode: LogicMode) -> Vec<NodeId> {
+        self.logic_mode = logic_mode;
+        let mut changed = Vec::new();
+        for node_id in self.get_all_node_ids() {
+            let width = self.nodes[&node_id].width.as_u32();
+            for id in self.resolve_bits(node_id, 0..width) {
+                if !changed.contains(&id) {
+                    changed.push(id);
+                }
+            }
         }
+        changed
     }
 
     /// Create a new node
@@ -322,6 +425,23 @@ impl Netlist {
         Ok(())
     }
 
+    /// Connect a component pin of the given bus width to a node
+    ///
+    /// Fails if the pin width does not match the width of the node.
+    pub fn connect_with_width(
+        &mut self,
+        component_id: ComponentId,
+        pin_name: String,
+        width: BusWidth,
+        node_id: NodeId,
+    ) -> Result<(), &'static str> {
+        let node = self.nodes.get(&node_id).ok_or("Node does not exist")?;
+        if node.width != width {
+            return Err("Bus width mismatch");
+        }
+        self.connect(component_id, pin_name, node_id)
+    }
+
     /// Disconnect a component pin from a node
     pub fn disconnect(
         &mut self,
@@ -339,10 +459,14 @@ impl Netlist {
             }
         });
 
-        // Update the node's connected components
+        // Update the node's connected components and drop any value it drove
         if let Some(node_id) = found_node_id {
             if let Some(node) = self.nodes.get_mut(&node_id) {
                 node.disconnect_component(component_id);
+                if node.drivers.remove(&component_id).is_some() {
+                    let width = node.width.as_u32();
+                    self.resolve_bits(node_id, 0..width);
+                }
             }
             Ok(())
         } else {
@@ -375,11 +499,328 @@ impl Netlist {
         }
     }
 
+    /// Record the value a component drives onto a node
+    ///
+    /// Every bit takes the resolution of all drivers on its thread (the bit
+    /// itself plus any bits linked to it). Returns the nodes whose resolved
+    /// signal changed.
+    pub fn drive_node(
+        &mut self,
+        node_id: NodeId,
+        component_id: ComponentId,
+        signal: Signal,
+    ) -> Result<Vec<NodeId>, &'static str> {
+        let node = self.nodes.get_mut(&node_id).ok_or("Node not found")?;
+        if signal.width() != node.width {
+            return Err("Signal width mismatch");
+        }
+        let width = node.width.as_u32();
+        node.drivers.insert(component_id, signal);
+        Ok(self.resolve_bits(node_id, 0..width))
+    }
+
+    /// Remove a component's contribution to a node
+    ///
+    /// Returns the nodes whose resolved signal changed.
+    pub fn release_node(
+        &mut self,
+        node_id: NodeId,
+        component_id: ComponentId,
+    ) -> Result<Vec<NodeId>, &'static str> {
+        let node = self.nodes.get_mut(&node_id).ok_or("Node not found")?;
+        if node.drivers.remove(&component_id).is_none() {
+            return Ok(Vec::new());
+        }
+        let width = node.width.as_u32();
+        Ok(self.resolve_bits(node_id, 0..width))
+    }
+
+    /// Get the value a component currently drives onto a node
+    pub fn get_node_driver(&self, node_id: NodeId, component_id: ComponentId) -> Option<&Signal> {
+        self.nodes
+            .get(&node_id)
+            .and_then(|node| node.get_driver(component_id))
+    }
+
+    /// Attach a pull-up/pull-down resistor to a node, or remove it with `None`
+    ///
+    /// Returns the nodes whose resolved signal changed.
+    pub fn set_node_pull(
+        &mut self,
+        node_id: NodeId,
+        pull: Option<Value>,
+    ) -> Result<Vec<NodeId>, &'static str> {
+        let node = self.nodes.get_mut(&node_id).ok_or("Node not found")?;
+        node.pull = pull;
+        let width = node.width.as_u32();
+        Ok(self.resolve_bits(node_id, 0..width))
+    }
+
+    /// Hold every bit of a node at a value whatever drives it, or release it
+    /// with `None`
+    ///
+    /// Bits linked to the node are held too. Returns the nodes whose resolved
+    /// signal changed.
+    pub fn set_node_stuck_at(
+        &mut self,
+        node_id: NodeId,
+        stuck_at: Option<Value>,
+    ) -> Result<Vec<NodeId>, &'static str> {
+        let node = self.nodes.get_mut(&node_id).ok_or("Node not found")?;
+        node.stuck_at = stuck_at;
+        let width = node.width.as_u32();
+        Ok(self.resolve_bits(node_id, 0..width))
+    }
+
+    /// Wire one bit of a node to one bit of another node
+    ///
+    /// This is how splitters are modelled: the linked bits share a thread
+    /// and always resolve to the same value.
+    pub fn link_bits(
+        &mut self,
+        node_a: NodeId,
+        bit_a: u32,
+        node_b: NodeId,
+        bit_b: u32,
+    ) -> Result<(), &'static str> {
+        for (node_id, bit) in [(node_a, bit_a), (node_b, bit_b)] {
+            let node = self.nodes.get(&node_id).ok_or("Node not found")?;
+            if bit >= node.width.as_u32() {
+                return Err("Bit index out of range");
+            }
+        }
+        if (node_a, bit_a) == (node_b, bit_b) {
+            return Ok(());
+        }
+
+        if let Some(node) = self.nodes.get_mut(&node_a) {
+            node.bit_links.push(BitLink {
+                bit: bit_a,
+                other_node: node_b,
+                other_bit: bit_b,
+            });
+        }
+        if let Some(node) = self.nodes.get_mut(&node_b) {
+            node.bit_links.push(BitLink {
+                bit: bit_b,
+                other_node: node_a,
+                other_bit: bit_a,
+            });
+        }
+        self.threads.clear();
+
+        // Threads that nothing drives keep their initial value until driven
+        let driven = self.get_thread(node_a, bit_a).iter().any(|(id, _)| {
+            self.nodes
+                .get(id)
+                .is_some_and(|node| !node.drivers.is_empty() || node.pull.is_some())
+        });
+        if driven {
+            self.resolve_bits(node_a, bit_a..bit_a + 1);
+        }
+        Ok(())
+    }
+
+    /// Close a component's switch between two nodes, or open it with `None`
+    ///
+    /// While the switch is closed the drivers on both sides resolve
+    /// together, whichever side they are on. Returns the nodes whose
+    /// resolved signal changed.
+    pub fn set_switch(
+        &mut self,
+        component_id: ComponentId,
+        nodes: Option<(NodeId, NodeId)>,
+    ) -> Result<Vec<NodeId>, &'static str> {
+        if let Some((node_a, node_b)) = nodes {
+            let width_a = self.nodes.get(&node_a).ok_or("Node not found")?.width;
+            let width_b = self.nodes.get(&node_b).ok_or("Node not found")?.width;
+            if width_a != width_b {
+                return Err("Switch width mismatch");
+            }
+        }
+
+        let index = self
+            .switches
+            .iter()
+            .position(|switch| switch.component_id == component_id);
+        let previous = index.map(|i| (self.switches[i].node_a, self.switches[i].node_b));
+        if previous == nodes {
+            return Ok(Vec::new());
+        }
+        if let Some(i) = index {
+            self.switches.remove(i);
+        }
+        if let Some((node_a, node_b)) = nodes {
+            self.switches.push(Switch {
+                component_id,
+                node_a,
+                node_b,
+            });
+        }
+        self.threads.clear();
+
+        // Opening a switch splits the threads again, so both sides resolve
+        let mut changed = Vec::new();
+        for node_id in previous.into_iter().chain(nodes).flat_map(|(a, b)| [a, b]) {
+            let width = match self.nodes.get(&node_id) {
+                Some(node) => node.width.as_u32(),
+                None => continue,
+            };
+            for id in self.resolve_bits(node_id, 0..width) {
+                if !changed.contains(&id) {
+                    changed.push(id);
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Get the switches that are currently closed
+    pub fn get_closed_switches(&self) -> &[Switch] {
+        &self.switches
+    }
+
+    /// Get every node bit electrically connected to the given bit, itself included
+    pub fn get_thread(&self, node_id: NodeId, bit: u32) -> Vec<(NodeId, u32)> {
+        let mut thread = vec![(node_id, bit)];
+        let mut seen: HashSet<(NodeId, u32)> = thread.iter().copied().collect();
+        let mut index = 0;
+        while index < thread.len() {
+            let (id, b) = thread[index];
+            if let Some(node) = self.nodes.get(&id) {
+                for link in node.get_bit_links(b) {
+                    if seen.insert(link) {
+                        thread.push(link);
+                    }
+                }
+            }
+            for switch in &self.switches {
+                let other = if switch.node_a == id {
+                    switch.node_b
+                } else if switch.node_b == id {
+                    switch.node_a
+                } else {
+                    continue;
+                };
+                if seen.insert((other, b)) {
+                    thread.push((other, b));
+                }
+            }
+            index += 1;
+        }
+        thread
+    }
+
+    /// Get the thread through a node bit, looking it up in the cache of
+    /// resolved threads first
+    fn cached_thread(&mut self, node_id: NodeId, bit: u32) -> Thread {
+        if let Some(thread) = self.threads.get(&(node_id, bit)) {
+            return Arc::clone(thread);
+        }
+        let thread: Thread = self.get_thread(node_id, bit).into();
+        for &member in thread.iter() {
+            self.threads.insert(member, Arc::clone(&thread));
+        }
+        thread
+    }
+
+    /// Resolve the threads through the given bits of a node
+    ///
+    /// Returns the nodes whose signal changed.
+    fn resolve_bits(&mut self, node_id: NodeId, bits: std::ops::Range<u32>) -> Vec<NodeId> {
+        let mut changed = Vec::new();
+        for bit in bits {
+            let thread = self.cached_thread(node_id, bit);
+            let value = match self.logic_mode {
+                LogicMode::Logisim => self.resolve_thread(&thread),
+                LogicMode::Ieee1164 => self.resolve_thread_std_logic(&thread).to_value(),
+            };
+
+            for &(id, b) in thread.iter() {
+                if let Some(node) = self.nodes.get_mut(&id) {
+                    if node.signal.get_bit(b) != Some(value) {
+                        let _ = node.signal.set_bit(b, value);
+                        if !changed.contains(&id) {
+                            changed.push(id);
+                        }
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Resolve the drivers of a thread by Logisim's rules
+    fn resolve_thread(&self, thread: &[(NodeId, u32)]) -> Value {
+        let mut value = Value::HighZ;
+        let mut pull = None;
+        let mut stuck_at = None;
+        for &(id, b) in thread {
+            if let Some(node) = self.nodes.get(&id) {
+                for signal in node.drivers.values() {
+                    if let Some(v) = signal.get_bit(b) {
+                        value = value.resolve(v);
+                    }
+                }
+                pull = pull.or(node.pull);
+                stuck_at = stuck_at.or(node.stuck_at);
+            }
+        }
+        if value == Value::HighZ {
+            value = pull.unwrap_or(Value::HighZ);
+        }
+        stuck_at.unwrap_or(value)
+    }
+
+    /// Resolve the drivers of a thread with the IEEE 1164 table, pull
+    /// resistors driving weak levels
+    fn resolve_thread_std_logic(&self, thread: &[(NodeId, u32)]) -> StdLogic {
+        let mut level = StdLogic::Z;
+        let mut stuck_at = None;
+        for &(id, b) in thread {
+            if let Some(node) = self.nodes.get(&id) {
+                for signal in node.drivers.values() {
+                    if let Some(v) = signal.get_bit(b) {
+                        level = level.resolve(StdLogic::from_value(v));
+                    }
+                }
+                if let Some(pull) = node.pull {
+                    level = level.resolve(StdLogic::weak(pull));
+                }
+                stuck_at = stuck_at.or(node.stuck_at);
+            }
+        }
+        stuck_at.map_or(level, StdLogic::from_value)
+    }
+
+    /// Get the IEEE 1164 level of every bit of a node, least significant
+    /// bit first
+    ///
+    /// With [`LogicMode::Ieee1164`] the drivers are resolved again to keep
+    /// the weak levels; otherwise the node's value is converted.
+    pub fn get_node_std_logic(&self, node_id: NodeId) -> Option<Vec<StdLogic>> {
+        let node = self.nodes.get(&node_id)?;
+        Some(match self.logic_mode {
+            LogicMode::Logisim => crate::ieee1164::from_signal(&node.signal),
+            LogicMode::Ieee1164 => (0..node.width.as_u32())
+                .map(|bit| self.resolve_thread_std_logic(&self.get_thread(node_id, bit)))
+                .collect(),
+        })
+    }
+
     /// Get signal at a node
     pub fn get_node_signal(&self, node_id: NodeId) -> Option<&Signal> {
         self.nodes.get(&node_id).map(|node| &node.signal)
     }
 
+    /// Find a node by name
+    pub fn find_node_by_name(&self, name: &str) -> Option<NodeId> {
+        self.nodes
+            .values()
+            .find(|node| node.name.as_deref() == Some(name))
+            .map(|node| node.id)
+    }
+
     /// Get all nodes
     pub fn get_all_nodes(&self) -> &HashMap<NodeId, Node> {
         &self.nodes
@@ -409,6 +850,8 @@ impl Netlist {
         self.nodes.clear();
         self.nets.clear();
         self.connections.clear();
+        self.switches.clear();
+        self.threads.clear();
         self.next_node_id = 1;
         self.next_net_id = 1;
     }
@@ -504,6 +947,199 @@ mod tests {
         assert!(node.connected_components.contains(&component_id));
     }
 
+    #[test]
+    fn test_connect_with_width() {
+        let mut netlist = Netlist::new();
+        let bus = netlist.create_node(BusWidth(8));
+        let component_id = ComponentId(42);
+
+        assert!(netlist
+            .connect_with_width(component_id, "A".to_string(), BusWidth(1), bus)
+            .is_err());
+        assert!(netlist
+            .connect_with_width(component_id, "B".to_string(), BusWidth(8), bus)
+            .is_ok());
+        assert_eq!(netlist.get_pin_node(component_id, "A"), None);
+        assert_eq!(netlist.get_pin_node(component_id, "B"), Some(bus));
+
+        // Node signals keep their width
+        assert!(netlist
+            .set_node_signal(bus, Signal::from_u64(0xa5, BusWidth(8)))
+            .is_ok());
+        assert!(netlist
+            .set_node_signal(bus, Signal::new_single(Value::High))
+            .is_err());
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_u64(), Some(0xa5));
+    }
+
+    #[test]
+    fn test_multi_driver_resolution() {
+        let mut netlist = Netlist::new();
+        let bus = netlist.create_node(BusWidth(4));
+        let (a, b) = (ComponentId(1), ComponentId(2));
+
+        // A floating driver yields to the active one
+        assert_eq!(
+            netlist
+                .drive_node(bus, a, Signal::from_u64(0b1010, BusWidth(4)))
+                .unwrap(),
+            vec![bus]
+        );
+        assert!(netlist
+            .drive_node(bus, b, Signal::high_z(BusWidth(4)))
+            .unwrap()
+            .is_empty());
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_u64(), Some(0b1010));
+
+        // Conflicting bits become errors, agreeing bits survive
+        netlist
+            .drive_node(bus, b, Signal::from_u64(0b0110, BusWidth(4)))
+            .unwrap();
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_string(), "EE10");
+        assert_eq!(netlist.release_node(bus, b).unwrap(), vec![bus]);
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_u64(), Some(0b1010));
+        assert!(netlist.release_node(bus, b).unwrap().is_empty());
+        netlist
+            .drive_node(bus, b, Signal::from_u64(0b0110, BusWidth(4)))
+            .unwrap();
+
+        // Releasing the bus leaves it floating until a pull resistor is attached
+        netlist
+            .drive_node(bus, a, Signal::high_z(BusWidth(4)))
+            .unwrap();
+        netlist
+            .drive_node(bus, b, Signal::high_z(BusWidth(4)))
+            .unwrap();
+        assert!(netlist.get_node_signal(bus).unwrap().is_high_z());
+        assert_eq!(
+            netlist.set_node_pull(bus, Some(Value::Low)).unwrap(),
+            vec![bus]
+        );
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_u64(), Some(0));
+
+        assert!(netlist
+            .drive_node(bus, a, Signal::new_single(Value::High))
+            .is_err());
+    }
+
+    #[test]
+    fn test_linked_bits() {
+        let mut netlist = Netlist::new();
+        let bus = netlist.create_node(BusWidth(2));
+        let lo = netlist.create_node(BusWidth(1));
+        let hi = netlist.create_node(BusWidth(1));
+        netlist.link_bits(bus, 0, lo, 0).unwrap();
+        netlist.link_bits(bus, 1, hi, 0).unwrap();
+        assert!(netlist.link_bits(bus, 2, hi, 0).is_err());
+        assert_eq!(netlist.get_thread(lo, 0), vec![(lo, 0), (bus, 0)]);
+
+        // Driving the wide end reaches both narrow ends
+        let mut changed = netlist
+            .drive_node(bus, ComponentId(1), Signal::from_u64(0b10, BusWidth(2)))
+            .unwrap();
+        changed.sort();
+        assert_eq!(changed, vec![bus, lo, hi]);
+        assert_eq!(netlist.get_node_signal(lo).unwrap().to_u64(), Some(0));
+        assert_eq!(netlist.get_node_signal(hi).unwrap().to_u64(), Some(1));
+
+        // Driving a narrow end conflicts with the wide driver on that bit only
+        netlist
+            .drive_node(hi, ComponentId(2), Signal::new_single(Value::Low))
+            .unwrap();
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_string(), "E0");
+
+        netlist
+            .drive_node(bus, ComponentId(1), Signal::high_z(BusWidth(2)))
+            .unwrap();
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_string(), "0Z");
+
+        // A bit linked later joins the thread that was already resolved
+        let tap = netlist.create_node(BusWidth(1));
+        netlist.link_bits(hi, 0, tap, 0).unwrap();
+        assert_eq!(netlist.get_node_signal(tap).unwrap().to_string(), "0");
+        netlist
+            .drive_node(tap, ComponentId(3), Signal::new_single(Value::High))
+            .unwrap();
+        assert_eq!(netlist.get_node_signal(bus).unwrap().to_string(), "EZ");
+    }
+
+    #[test]
+    fn test_switch_joins_nodes_both_ways() {
+        let mut netlist = Netlist::new();
+        let source = netlist.create_node(BusWidth(1));
+        let drain = netlist.create_node(BusWidth(1));
+        let wide = netlist.create_node(BusWidth(2));
+        let switch = ComponentId(3);
+        assert!(netlist.set_switch(switch, Some((source, wide))).is_err());
+
+        netlist
+            .drive_node(source, ComponentId(1), Signal::new_single(Value::Low))
+            .unwrap();
+        netlist.set_node_pull(drain, Some(Value::High)).unwrap();
+        assert_eq!(
+            netlist.get_node_signal(drain),
+            Some(&Signal::new_single(Value::High))
+        );
+
+        // Closed, the strong low on the source side wins over the pull
+        let changed = netlist.set_switch(switch, Some((source, drain))).unwrap();
+        assert_eq!(changed, vec![drain]);
+        assert_eq!(netlist.get_thread(drain, 0), vec![(drain, 0), (source, 0)]);
+        assert_eq!(
+            netlist.get_node_signal(drain),
+            Some(&Signal::new_single(Value::Low))
+        );
+        assert!(netlist
+            .set_switch(switch, Some((source, drain)))
+            .unwrap()
+            .is_empty());
+
+        // Driving the drain side now conflicts with the source
+        netlist
+            .drive_node(drain, ComponentId(2), Signal::new_single(Value::High))
+            .unwrap();
+        assert_eq!(
+            netlist.get_node_signal(source),
+            Some(&Signal::new_single(Value::Error))
+        );
+
+        let mut changed = netlist.set_switch(switch, None).unwrap();
+        changed.sort();
+        assert_eq!(changed, vec![source, drain]);
+        assert!(netlist.get_closed_switches().is_empty());
+        assert_eq!(
+            netlist.get_node_signal(source),
+            Some(&Signal::new_single(Value::Low))
+        );
+    }
+
+    #[test]
+    fn test_disconnect_removes_driver() {
+        let mut netlist = Netlist::new();
+        let node_id = netlist.create_node(BusWidth(1));
+        let (a, b) = (ComponentId(1), ComponentId(2));
+        netlist.connect(a, "OUT".to_string(), node_id).unwrap();
+        netlist.connect(b, "OUT".to_string(), node_id).unwrap();
+
+        netlist
+            .drive_node(node_id, a, Signal::new_single(Value::High))
+            .unwrap();
+        netlist
+            .drive_node(node_id, b, Signal::new_single(Value::Low))
+            .unwrap();
+        assert_eq!(
+            netlist.get_node_signal(node_id).unwrap().as_single(),
+            Some(Value::Error)
+        );
+
+        netlist.disconnect(b, "OUT").unwrap();
+        assert_eq!(netlist.get_node_driver(node_id, b), None);
+        assert_eq!(
+            netlist.get_node_signal(node_id).unwrap().as_single(),
+            Some(Value::High)
+        );
+    }
+
     #[test]
     fn test_disconnection() {
         let mut netlist = Netlist::new();
@@ -85,7 +85,7 @@ impl fmt::Display for Timestamp {
     }
 }
 
-/// Possible values for a digital signal
+/// Possible values for a single bit of a digital signal
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Value {
     /// Logic high (1)
@@ -165,58 +165,47 @@ impl Value {
         }
     }
 
-    /// Create a Value from a long integer with specified width (simplified for single-bit)
-    pub fn from_long(value: i64, _width: BusWidth) -> Value {
-        if value & 1 != 0 {
-            Value::High
-        } else {
-            Value::Low
+    /// Resolve two values driven onto the same wire
+    ///
+    /// High impedance yields to the other driver, equal values agree and
+    /// conflicting 0/1 drivers produce an error.
+    pub fn resolve(self, other: Value) -> Value {
+        match (self, other) {
+            (Value::HighZ, v) | (v, Value::HighZ) => v,
+            (a, b) if a == b => a,
+            (Value::Error, _) | (_, Value::Error) => Value::Error,
+            (Value::Unknown, _) | (_, Value::Unknown) => Value::Unknown,
+            _ => Value::Error,
         }
     }
 
-    /// Create a Value from individual bits (simplified - just returns first bit)
-    pub fn from_bits(bits: &[Value]) -> Value {
-        bits.first().copied().unwrap_or(Value::Low)
-    }
-
-    /// Convert to long integer value (simplified for single-bit)
-    pub fn to_long_value(&self) -> i64 {
+    /// Get the character used to display this value
+    pub fn to_char(self) -> char {
         match self {
-            Value::High => 1,
-            Value::Low => 0,
-            _ => 0,
+            Value::High => '1',
+            Value::Low => '0',
+            Value::Unknown => 'X',
+            Value::Error => 'E',
+            Value::HighZ => 'Z',
         }
     }
 
-    /// Get a specific bit from a value (simplified)
-    pub fn get_bit(&self, index: usize) -> Value {
-        if index == 0 {
-            *self
-        } else {
-            Value::Low
+    /// Parse a value from its display character (case-insensitive)
+    pub fn from_char(c: char) -> Option<Value> {
+        match c.to_ascii_uppercase() {
+            '1' => Some(Value::High),
+            '0' => Some(Value::Low),
+            'X' | 'U' => Some(Value::Unknown),
+            'E' => Some(Value::Error),
+            'Z' => Some(Value::HighZ),
+            _ => None,
         }
     }
-
-    /// Check if all bits in this value are fully defined (same as is_definite for single bits)
-    pub fn is_fully_defined(&self) -> bool {
-        self.is_definite()
-    }
-
-    /// Get the width of this value (always 1 for single-bit values)
-    pub fn width(&self) -> BusWidth {
-        BusWidth(1)
-    }
 }
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Value::High => write!(f, "1"),
-            Value::Low => write!(f, "0"),
-            Value::Unknown => write!(f, "X"),
-            Value::Error => write!(f, "E"),
-            Value::HighZ => write!(f, "Z"),
-        }
+        write!(f, "{}", self.to_char())
     }
 }
 
@@ -229,31 +218,103 @@ impl std::ops::Not for Value {
 }
 
 /// Represents a signal with a value and timestamp
+///
+/// A signal is a vector of per-bit [`Value`]s of arbitrary width. Bits are
+/// stored least significant first, so bit 0 is the LSB. A single-bit signal
+/// is simply a signal of width 1.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Signal {
-    /// The value carried by this signal
-    value: Value,
+    /// Per-bit values, least significant bit first
+    bits: Vec<Value>,
     /// When this signal was last updated
     timestamp: Timestamp,
 }
 
 impl Signal {
-    /// Create a new signal with a value and timestamp
+    /// Create a new single-bit signal with a value and timestamp
     pub fn new(value: Value, timestamp: Timestamp) -> Self {
-        Signal { value, timestamp }
+        Signal {
+            bits: vec![value],
+            timestamp,
+        }
     }
 
-    /// Create a signal with the current timestamp (0)
+    /// Create a single-bit signal with the current timestamp (0)
     pub fn new_now(value: Value) -> Self {
+        Signal::new(value, Timestamp(0))
+    }
+
+    /// Create a single-bit signal (convenience method)
+    pub fn new_single(value: Value) -> Self {
+        Signal::new(value, Timestamp(0))
+    }
+
+    /// Create a bus signal from per-bit values (least significant bit first)
+    pub fn new_bus(values: Vec<Value>) -> Self {
         Signal {
-            value,
+            bits: values,
             timestamp: Timestamp(0),
         }
     }
 
-    /// Get the value of this signal
-    pub fn value(&self) -> &Value {
-        &self.value
+    /// Create a signal of the given width with every bit set to `value`
+    pub fn new_uniform(width: BusWidth, value: Value) -> Self {
+        Signal::new_bus(vec![value; width.0 as usize])
+    }
+
+    /// Create a bus signal from a slice of per-bit values (least significant bit first)
+    pub fn from_bits(bits: &[Value]) -> Self {
+        Signal::new_bus(bits.to_vec())
+    }
+
+    /// Create an unknown signal of given width
+    pub fn unknown(width: BusWidth) -> Self {
+        Signal::new_uniform(width, Value::Unknown)
+    }
+
+    /// Create a floating (high impedance) signal of given width
+    pub fn high_z(width: BusWidth) -> Self {
+        Signal::new_uniform(width, Value::HighZ)
+    }
+
+    /// Create an error signal of given width
+    pub fn error(width: BusWidth) -> Self {
+        Signal::new_uniform(width, Value::Error)
+    }
+
+    /// Create a signal with all bits low
+    pub fn all_low(width: BusWidth) -> Self {
+        Signal::new_uniform(width, Value::Low)
+    }
+
+    /// Create a signal with all bits high
+    pub fn all_high(width: BusWidth) -> Self {
+        Signal::new_uniform(width, Value::High)
+    }
+
+    /// Create a signal from an unsigned value, truncated to `width` bits
+    ///
+    /// Bits above bit 63 are low.
+    pub fn from_u64(value: u64, width: BusWidth) -> Self {
+        let bits = (0..width.0)
+            .map(|i| Value::from_bool(i < 64 && (value >> i) & 1 != 0))
+            .collect();
+        Signal::new_bus(bits)
+    }
+
+    /// Create a signal from a two's complement value, truncated to `width` bits
+    ///
+    /// Bits above bit 63 are sign-extended.
+    pub fn from_long(value: i64, width: BusWidth) -> Self {
+        let bits = (0..width.0)
+            .map(|i| Value::from_bool((value >> i.min(63)) & 1 != 0))
+            .collect();
+        Signal::new_bus(bits)
+    }
+
+    /// Get the per-bit values of this signal (least significant bit first)
+    pub fn bits(&self) -> &[Value] {
+        &self.bits
     }
 
     /// Get the timestamp of this signal
@@ -261,77 +322,319 @@ impl Signal {
         self.timestamp
     }
 
-    /// Set a new value and timestamp
-    pub fn set(&mut self, value: Value, timestamp: Timestamp) {
-        self.value = value;
+    /// Set the timestamp of this signal
+    pub fn set_timestamp(&mut self, timestamp: Timestamp) {
         self.timestamp = timestamp;
     }
 
     /// Get the width of this signal
     pub fn width(&self) -> BusWidth {
-        self.value.width()
+        BusWidth(self.bits.len() as u32)
     }
 
-    /// Create an unknown signal of given width
-    pub fn unknown(_width: BusWidth) -> Self {
-        Signal::new(Value::Unknown, Timestamp(0))
+    /// Check if every bit of this signal is a definite value
+    pub fn is_definite(&self) -> bool {
+        self.bits.iter().all(|bit| bit.is_definite())
     }
 
-    /// Check if this signal is at a definite value
-    pub fn is_definite(&self) -> bool {
-        self.value.is_fully_defined()
+    /// Check if every bit of this signal is fully defined (alias for `is_definite`)
+    pub fn is_fully_defined(&self) -> bool {
+        self.is_definite()
     }
 
-    /// Create a single-bit signal (convenience method)
-    pub fn new_single(value: Value) -> Self {
-        Signal {
-            value,
-            timestamp: Timestamp(0),
-        }
+    /// Check if every bit of this signal is floating
+    pub fn is_high_z(&self) -> bool {
+        self.bits.iter().all(|&bit| bit == Value::HighZ)
+    }
+
+    /// Check if any bit of this signal is in the error state
+    pub fn has_error(&self) -> bool {
+        self.bits.contains(&Value::Error)
     }
 
     /// Get the value as a single bit (if this is single-bit signal)
     pub fn as_single(&self) -> Option<Value> {
-        Some(self.value)
+        if self.is_single_bit() {
+            Some(self.bits[0])
+        } else {
+            None
+        }
     }
 
     /// Check if this is a single-bit signal
     pub fn is_single_bit(&self) -> bool {
-        true // For now, all signals are single-bit
+        self.bits.len() == 1
     }
 
-    /// Get a specific bit from this signal
-    pub fn get_bit(&self, _index: u32) -> Option<Value> {
-        Some(self.value) // For single-bit signals, always return the value
+    /// Get a specific bit from this signal (bit 0 is the LSB)
+    pub fn get_bit(&self, index: u32) -> Option<Value> {
+        self.bits.get(index as usize).copied()
     }
 
-    /// Create a signal from a u64 value
-    pub fn from_u64(value: u64, _width: BusWidth) -> Self {
-        let signal_value = if value == 0 { Value::Low } else { Value::High };
-        Signal::new_single(signal_value)
+    /// Set a specific bit of this signal (bit 0 is the LSB)
+    pub fn set_bit(&mut self, index: u32, value: Value) -> Result<(), &'static str> {
+        match self.bits.get_mut(index as usize) {
+            Some(bit) => {
+                *bit = value;
+                Ok(())
+            }
+            None => Err("Bit index out of range"),
+        }
     }
 
-    /// Create a bus signal from multiple values
-    pub fn new_bus(values: Vec<Value>) -> Self {
-        // For now, just use the first value as a single-bit signal
-        let value = values.first().copied().unwrap_or(Value::Unknown);
-        Signal::new_single(value)
+    /// Convert to an unsigned integer
+    ///
+    /// Returns `None` if any bit is not definite or the signal is wider than 64 bits.
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.bits.len() > 64 {
+            return None;
+        }
+        self.bits
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |acc, (i, bit)| match bit.to_bool()? {
+                true => Some(acc | (1 << i)),
+                false => Some(acc),
+            })
+    }
+
+    /// Convert to a two's complement integer, sign-extending from the top bit
+    ///
+    /// Returns `None` if any bit is not definite or the signal is wider than 64 bits.
+    pub fn to_i64(&self) -> Option<i64> {
+        let raw = self.to_u64()?;
+        let width = self.bits.len() as u32;
+        if width == 0 || width == 64 {
+            return Some(raw as i64);
+        }
+        let shift = 64 - width;
+        Some(((raw << shift) as i64) >> shift)
     }
 
-    /// Create a signal with all bits low
-    pub fn all_low(_width: BusWidth) -> Self {
-        Signal::new_single(Value::Low)
+    /// Bitwise AND with another signal of the same width
+    ///
+    /// Mismatched widths yield an error signal of the wider width.
+    pub fn and(&self, other: &Signal) -> Signal {
+        self.zip_with(other, Value::and)
     }
 
-    /// Create a signal with all bits high
-    pub fn all_high(_width: BusWidth) -> Self {
-        Signal::new_single(Value::High)
+    /// Bitwise OR with another signal of the same width
+    ///
+    /// Mismatched widths yield an error signal of the wider width.
+    pub fn or(&self, other: &Signal) -> Signal {
+        self.zip_with(other, Value::or)
+    }
+
+    /// Bitwise XOR with another signal of the same width
+    ///
+    /// Mismatched widths yield an error signal of the wider width.
+    pub fn xor(&self, other: &Signal) -> Signal {
+        self.zip_with(other, Value::xor)
+    }
+
+    /// Resolve this signal against another driver of the same wire, bit by bit
+    ///
+    /// Mismatched widths yield an error signal of the wider width.
+    pub fn resolve(&self, other: &Signal) -> Signal {
+        self.zip_with(other, Value::resolve)
+    }
+
+    /// Replace every floating (high impedance) bit with `value`
+    ///
+    /// Used to apply pull-up and pull-down resistors to a resolved wire.
+    pub fn pull(&self, value: Value) -> Signal {
+        let bits = self
+            .bits
+            .iter()
+            .map(|&bit| if bit == Value::HighZ { value } else { bit })
+            .collect();
+        Signal::new_bus(bits)
+    }
+
+    /// Apply `op` to each pair of corresponding bits
+    fn zip_with(&self, other: &Signal, op: impl Fn(Value, Value) -> Value) -> Signal {
+        if self.width() != other.width() {
+            return Signal::error(self.width().max(other.width()));
+        }
+        let bits = self
+            .bits
+            .iter()
+            .zip(&other.bits)
+            .map(|(&a, &b)| op(a, b))
+            .collect();
+        Signal::new_bus(bits)
     }
 }
 
 impl fmt::Display for Signal {
+    /// Formats the bits most significant first, e.g. `01X0`
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.value)
+        for bit in self.bits.iter().rev() {
+            write!(f, "{}", bit)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::ops::Not for &Signal {
+    type Output = Signal;
+
+    fn not(self) -> Signal {
+        Signal::new_bus(self.bits.iter().map(|&bit| !bit).collect())
+    }
+}
+
+impl std::ops::Not for Signal {
+    type Output = Signal;
+
+    fn not(self) -> Signal {
+        !&self
+    }
+}
+
+/// 64 independent values of one bit, used for bit-parallel simulation
+///
+/// Bit `i` of each plane belongs to lane `i`, and a lane is set in at most
+/// one plane. A lane set in none of them is `Value::Unknown`. The operations
+/// give every lane the same result as the matching [`Value`] operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Lanes {
+    /// Lanes holding `Value::High`
+    pub high: u64,
+    /// Lanes holding `Value::Low`
+    pub low: u64,
+    /// Lanes holding `Value::Error`
+    pub error: u64,
+    /// Lanes holding `Value::HighZ`
+    pub high_z: u64,
+}
+
+impl Lanes {
+    /// Number of lanes in a word
+    pub const COUNT: usize = 64;
+
+    /// Every lane holding the same value
+    pub fn splat(value: Value) -> Self {
+        let mut lanes = Lanes::default();
+        if let Some(plane) = lanes.plane_mut(value) {
+            *plane = u64::MAX;
+        }
+        lanes
+    }
+
+    /// Get the value of one lane
+    pub fn get(self, lane: usize) -> Value {
+        let bit = 1u64 << lane;
+        if self.high & bit != 0 {
+            Value::High
+        } else if self.low & bit != 0 {
+            Value::Low
+        } else if self.error & bit != 0 {
+            Value::Error
+        } else if self.high_z & bit != 0 {
+            Value::HighZ
+        } else {
+            Value::Unknown
+        }
+    }
+
+    /// Set the value of one lane
+    pub fn set(&mut self, lane: usize, value: Value) {
+        let bit = 1u64 << lane;
+        self.high &= !bit;
+        self.low &= !bit;
+        self.error &= !bit;
+        self.high_z &= !bit;
+        if let Some(plane) = self.plane_mut(value) {
+            *plane |= bit;
+        }
+    }
+
+    /// Lanes holding `Value::Unknown`
+    pub fn unknown(self) -> u64 {
+        !(self.high | self.low | self.error | self.high_z)
+    }
+
+    /// Take the lanes in `mask` from `self` and the others from `other`
+    pub fn select(self, mask: u64, other: Lanes) -> Lanes {
+        Lanes {
+            high: (self.high & mask) | (other.high & !mask),
+            low: (self.low & mask) | (other.low & !mask),
+            error: (self.error & mask) | (other.error & !mask),
+            high_z: (self.high_z & mask) | (other.high_z & !mask),
+        }
+    }
+
+    /// Lane-wise `Value::and`
+    pub fn and(self, other: Lanes) -> Lanes {
+        let low = self.low | other.low;
+        Lanes {
+            high: self.high & other.high,
+            low,
+            error: (self.error | other.error) & !low,
+            high_z: 0,
+        }
+    }
+
+    /// Lane-wise `Value::or`
+    pub fn or(self, other: Lanes) -> Lanes {
+        let high = self.high | other.high;
+        Lanes {
+            high,
+            low: self.low & other.low,
+            error: (self.error | other.error) & !high,
+            high_z: 0,
+        }
+    }
+
+    /// Lane-wise `Value::xor`
+    pub fn xor(self, other: Lanes) -> Lanes {
+        Lanes {
+            high: (self.high & other.low) | (self.low & other.high),
+            low: (self.high & other.high) | (self.low & other.low),
+            error: self.error | other.error,
+            high_z: 0,
+        }
+    }
+
+    /// Lane-wise `Value::resolve`
+    pub fn resolve(self, other: Lanes) -> Lanes {
+        let both = Lanes {
+            high: self.high & other.high,
+            low: self.low & other.low,
+            error: self.error | other.error | (self.high & other.low) | (self.low & other.high),
+            high_z: 0,
+        };
+        // High impedance yields to the other driver
+        let resolved = other.select(self.high_z, both);
+        self.select(other.high_z & !self.high_z, resolved)
+    }
+
+    /// Replace floating lanes with `value`, as a pull resistor does
+    pub fn pull(self, value: Value) -> Lanes {
+        Lanes::splat(value).select(self.high_z, self)
+    }
+
+    fn plane_mut(&mut self, value: Value) -> Option<&mut u64> {
+        match value {
+            Value::High => Some(&mut self.high),
+            Value::Low => Some(&mut self.low),
+            Value::Error => Some(&mut self.error),
+            Value::HighZ => Some(&mut self.high_z),
+            Value::Unknown => None,
+        }
+    }
+}
+
+impl std::ops::Not for Lanes {
+    type Output = Lanes;
+
+    fn not(self) -> Lanes {
+        Lanes {
+            high: self.low,
+            low: self.high,
+            ..self
+        }
     }
 }
 
@@ -342,6 +645,47 @@ pub type Bus = Signal;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_lanes_match_value_operations() {
+        let values = [
+            Value::High,
+            Value::Low,
+            Value::Unknown,
+            Value::Error,
+            Value::HighZ,
+        ];
+        // Every pair of values side by side in the lanes
+        let (mut a, mut b) = (Lanes::default(), Lanes::default());
+        for (lane, (x, y)) in values
+            .iter()
+            .flat_map(|&x| values.iter().map(move |&y| (x, y)))
+            .enumerate()
+        {
+            a.set(lane, x);
+            b.set(lane, y);
+        }
+
+        for lane in 0..values.len() * values.len() {
+            let (x, y) = (a.get(lane), b.get(lane));
+            assert_eq!(a.and(b).get(lane), x.and(y), "{:?} and {:?}", x, y);
+            assert_eq!(a.or(b).get(lane), x.or(y), "{:?} or {:?}", x, y);
+            assert_eq!(a.xor(b).get(lane), x.xor(y), "{:?} xor {:?}", x, y);
+            assert_eq!(
+                a.resolve(b).get(lane),
+                x.resolve(y),
+                "{:?} resolve {:?}",
+                x,
+                y
+            );
+            assert_eq!((!a).get(lane), !x);
+        }
+        assert_eq!(Lanes::splat(Value::Unknown).unknown(), u64::MAX);
+        assert_eq!(
+            Lanes::splat(Value::HighZ).pull(Value::Low),
+            Lanes::splat(Value::Low)
+        );
+    }
+
     #[test]
     fn test_value_operations() {
         assert_eq!(Value::High.and(Value::High), Value::High);
@@ -379,4 +723,63 @@ mod tests {
 
         assert_eq!(sig.to_u64(), Some(5));
     }
+
+    #[test]
+    fn test_wide_and_signed_values() {
+        let sig = Signal::from_long(-2, BusWidth(8));
+        assert_eq!(sig.to_u64(), Some(0xfe));
+        assert_eq!(sig.to_i64(), Some(-2));
+
+        let wide = Signal::from_long(-1, BusWidth(96));
+        assert_eq!(wide.width(), BusWidth(96));
+        assert_eq!(wide.get_bit(95), Some(Value::High));
+        assert_eq!(wide.to_u64(), None);
+
+        let full = Signal::from_u64(u64::MAX, BusWidth(64));
+        assert_eq!(full.to_u64(), Some(u64::MAX));
+        assert_eq!(full.to_i64(), Some(-1));
+    }
+
+    #[test]
+    fn test_undefined_bits() {
+        let mut sig = Signal::all_low(BusWidth(4));
+        sig.set_bit(2, Value::HighZ).unwrap();
+        assert!(!sig.is_definite());
+        assert_eq!(sig.to_u64(), None);
+        assert!(sig.set_bit(4, Value::High).is_err());
+        assert_eq!(sig.to_string(), "0Z00");
+
+        assert!(Signal::high_z(BusWidth(3)).is_high_z());
+        assert!(Signal::error(BusWidth(2)).has_error());
+    }
+
+    #[test]
+    fn test_bitwise_operations() {
+        let a = Signal::from_u64(0b1100, BusWidth(4));
+        let b = Signal::from_u64(0b1010, BusWidth(4));
+
+        assert_eq!(a.and(&b).to_u64(), Some(0b1000));
+        assert_eq!(a.or(&b).to_u64(), Some(0b1110));
+        assert_eq!(a.xor(&b).to_u64(), Some(0b0110));
+        assert_eq!((!&a).to_u64(), Some(0b0011));
+
+        let narrow = Signal::from_u64(1, BusWidth(2));
+        assert_eq!(a.and(&narrow), Signal::error(BusWidth(4)));
+    }
+
+    #[test]
+    fn test_wire_resolution() {
+        assert_eq!(Value::HighZ.resolve(Value::High), Value::High);
+        assert_eq!(Value::Low.resolve(Value::HighZ), Value::Low);
+        assert_eq!(Value::High.resolve(Value::High), Value::High);
+        assert_eq!(Value::High.resolve(Value::Low), Value::Error);
+        assert_eq!(Value::Unknown.resolve(Value::Low), Value::Unknown);
+        assert_eq!(Value::Error.resolve(Value::HighZ), Value::Error);
+
+        let a = Signal::from_bits(&[Value::High, Value::HighZ, Value::HighZ, Value::Low]);
+        let b = Signal::from_bits(&[Value::HighZ, Value::Low, Value::HighZ, Value::High]);
+        let resolved = a.resolve(&b);
+        assert_eq!(resolved.to_string(), "EZ01");
+        assert_eq!(resolved.pull(Value::High).to_string(), "E101");
+    }
 }
@@ -3,13 +3,29 @@
 //! This module implements the core simulation loop, event processing,
 //! and component management for the digital logic simulator.
 
-use crate::comp::{ClockEdge, Component, ComponentId, UpdateResult};
-use crate::event::{EventQueue, EventType};
+use crate::batch::BatchEvaluator;
+use crate::breakpoint::{BreakpointHit, BreakpointId, Breakpoints, Condition};
+use crate::circ_format::CircuitSource;
+use crate::comp::{Component, ComponentId, SwitchState, UpdateResult};
+use crate::delay::{DelayModel, DelayTable};
+use crate::event::{EventId, EventQueue, EventType};
+use crate::fault::{
+    first_difference, DetectedFault, Fault, FaultReport, FaultSite, PinFaults, StuckAt, TestSet,
+};
+use crate::ieee1164::{LogicMode, StdLogic};
+use crate::levelized::{Dirty, Levelization, Schedule};
 use crate::netlist::{Netlist, NodeId};
-use crate::signal::{Signal, Timestamp, Value};
-use std::collections::HashMap;
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::snapshot::{ComponentState, History, NodeState, SimulationSnapshot};
+use crate::timing::TimingReport;
+use crate::violation::{TimingMonitor, Violation, ViolationAction};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
+/// Pseudo-driver holding oscillating nodes at `Value::Error`
+const OSCILLATION_DRIVER: ComponentId = ComponentId(u64::MAX);
+
 /// Errors that can occur during simulation
 #[derive(Error, Debug)]
 pub enum SimulationError {
@@ -17,12 +33,71 @@ pub enum SimulationError {
     ComponentNotFound(ComponentId),
     #[error("Node not found: {0}")]
     NodeNotFound(NodeId),
-    #[error("Simulation oscillation detected at time {0}")]
-    OscillationDetected(Timestamp),
+    #[error("No node named {0}")]
+    NamedNodeNotFound(String),
+    #[error(
+        "Simulation oscillation detected at time {}: {} components on {} nodes",
+        .0.time,
+        .0.components.len(),
+        .0.nodes.len()
+    )]
+    OscillationDetected(Oscillation),
     #[error("Maximum simulation time exceeded: {0}")]
     TimeoutExceeded(Timestamp),
     #[error("Netlist error: {0}")]
     NetlistError(String),
+    #[error("Width mismatch at node {node}: expected {expected} bits, got {actual}")]
+    WidthMismatch {
+        node: NodeId,
+        expected: BusWidth,
+        actual: BusWidth,
+    },
+    #[error("Snapshot error: {0}")]
+    SnapshotError(String),
+    #[error("Batch evaluation error: {0}")]
+    BatchError(String),
+    #[error("Fault simulation error: {0}")]
+    FaultSimulationError(String),
+    #[error("Timing analysis error: {0}")]
+    TimingAnalysisError(String),
+    #[error("Timing violation: {0}")]
+    TimingViolation(Violation),
+}
+
+/// A feedback loop that kept re-evaluating without settling
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Oscillation {
+    /// Time at which the oscillation was detected
+    pub time: Timestamp,
+    /// Nodes carrying the loop, sorted by ID
+    pub nodes: Vec<NodeId>,
+    /// Components on the loop, sorted by ID
+    pub components: Vec<ComponentId>,
+}
+
+/// What the simulation does once it detects an oscillation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OscillationAction {
+    /// Stop with `SimulationError::OscillationDetected`
+    #[default]
+    Stop,
+    /// Record the oscillation, force its nodes to `Value::Error` until the
+    /// next reset, and carry on
+    ForceError,
+}
+
+/// How the simulation evaluates combinational logic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimulationEngine {
+    /// Every component update is an event of its own, one gate delay after
+    /// its inputs change
+    #[default]
+    EventDriven,
+    /// Combinational logic is sorted once and settled in a single zero-delay
+    /// pass in that order whenever a stimulus, clock or sequential output
+    /// changes. Circuits with combinational loops or switch components use
+    /// the event engine.
+    Levelized,
 }
 
 /// Configuration for simulation behavior
@@ -32,8 +107,28 @@ pub struct SimulationConfig {
     pub max_time: Option<Timestamp>,
     /// Maximum number of events to process
     pub max_events: Option<usize>,
-    /// Oscillation detection threshold (events at same time)
+    /// Number of times one component may be re-evaluated by a single
+    /// propagation before the propagation counts as oscillating
     pub oscillation_threshold: usize,
+    /// What to do once an oscillation is detected
+    pub oscillation_action: OscillationAction,
+    /// Number of snapshots kept for stepping backwards, one per processed
+    /// event; 0 disables the history
+    pub history_depth: usize,
+    /// Engine used for combinational logic
+    pub engine: SimulationEngine,
+    /// Whether output changes in flight are cancelled by newer ones
+    pub delay_model: DelayModel,
+    /// Propagation delays by component name, overriding the components'
+    /// own delays
+    pub delays: DelayTable,
+    /// Pulses shorter than this are reported as glitches; 0 disables the
+    /// glitch check
+    pub glitch_width: u64,
+    /// What to do once a setup, hold or glitch violation is detected
+    pub violation_action: ViolationAction,
+    /// How the drivers of a bit are resolved
+    pub logic_mode: LogicMode,
     /// Enable debug output
     pub debug: bool,
 }
@@ -44,6 +139,14 @@ impl Default for SimulationConfig {
             max_time: Some(Timestamp(10_000)), // Reduced timeout for testing
             max_events: Some(1_000),           // Reduced max events
             oscillation_threshold: 100,        // Reduced oscillation threshold
+            oscillation_action: OscillationAction::Stop,
+            history_depth: 0,
+            engine: SimulationEngine::EventDriven,
+            delay_model: DelayModel::Transport,
+            delays: DelayTable::new(),
+            glitch_width: 0,
+            violation_action: ViolationAction::Record,
+            logic_mode: LogicMode::Logisim,
             debug: false,
         }
     }
@@ -53,7 +156,7 @@ impl Default for SimulationConfig {
 pub type SignalChangeCallback = Box<dyn FnMut(NodeId, Timestamp, &Signal) + Send + Sync>;
 
 /// Statistics collected during simulation
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SimulationStats {
     /// Total events processed
     pub events_processed: usize,
@@ -65,6 +168,113 @@ pub struct SimulationStats {
     pub components_updated: usize,
     /// Number of clock ticks
     pub clock_ticks: usize,
+    /// Setup, hold and glitch violations, in the order they were detected
+    pub violations: Vec<Violation>,
+}
+
+/// Causal bookkeeping used to detect oscillations
+///
+/// Every event belongs to a propagation: the chain of events set off by one
+/// stimulus (an external signal change, a clock tick, a clock's own wake-up
+/// or a reset). Events scheduled while processing an event join its
+/// propagation, so a feedback loop that never settles keeps one propagation
+/// alive and its components' evaluation counts keep growing, while a fast
+/// clock merely starts a new propagation on every edge.
+#[derive(Debug, Default)]
+struct PropagationTracker {
+    /// Next propagation ID to hand out
+    next_id: u64,
+    /// Propagation of the event being processed
+    current: Option<u64>,
+    /// Propagation of every queued event scheduled by another event
+    origins: HashMap<EventId, u64>,
+    /// Number of queued events per live propagation
+    pending: HashMap<u64, usize>,
+    /// Component evaluations per live propagation
+    evaluations: HashMap<u64, HashMap<ComponentId, usize>>,
+    /// Propagations cut short after oscillating
+    stopped: HashSet<u64>,
+}
+
+impl PropagationTracker {
+    /// Start processing an event and return whether it should be skipped
+    fn begin(&mut self, event_id: EventId) -> bool {
+        let propagation = match self.origins.remove(&event_id) {
+            Some(propagation) => {
+                if let Some(pending) = self.pending.get_mut(&propagation) {
+                    *pending -= 1;
+                }
+                propagation
+            }
+            None => self.start(),
+        };
+        self.current = Some(propagation);
+        self.stopped.contains(&propagation)
+    }
+
+    /// Start a new propagation and make it current
+    fn start(&mut self) -> u64 {
+        let propagation = self.next_id;
+        self.next_id += 1;
+        self.pending.insert(propagation, 0);
+        self.current = Some(propagation);
+        propagation
+    }
+
+    /// Forget a queued event that was cancelled before being processed
+    fn cancel(&mut self, event_id: EventId) {
+        if let Some(propagation) = self.origins.remove(&event_id) {
+            if let Some(pending) = self.pending.get_mut(&propagation) {
+                *pending -= 1;
+            }
+        }
+    }
+
+    /// Record that `event_id` was caused by the current event
+    fn follow(&mut self, event_id: EventId) {
+        if let Some(propagation) = self.current {
+            self.origins.insert(event_id, propagation);
+            *self.pending.entry(propagation).or_default() += 1;
+        }
+    }
+
+    /// Count an evaluation of `component_id` by the current propagation
+    fn evaluate(&mut self, component_id: ComponentId) -> usize {
+        let Some(propagation) = self.current else {
+            return 0;
+        };
+        let count = self
+            .evaluations
+            .entry(propagation)
+            .or_default()
+            .entry(component_id)
+            .or_default();
+        *count += 1;
+        *count
+    }
+
+    /// Evaluation counts of the current propagation
+    fn current_evaluations(&self) -> Option<&HashMap<ComponentId, usize>> {
+        self.current.and_then(|p| self.evaluations.get(&p))
+    }
+
+    /// Drop the remaining events of the current propagation
+    fn stop_current(&mut self) {
+        if let Some(propagation) = self.current {
+            self.stopped.insert(propagation);
+        }
+    }
+
+    /// Finish the current event, forgetting propagations with nothing queued
+    fn end(&mut self) {
+        if let Some(propagation) = self.current.take() {
+            if self.pending.get(&propagation) == Some(&0) {
+                self.pending.remove(&propagation);
+                self.evaluations.remove(&propagation);
+                self.stopped.remove(&propagation);
+            }
+        }
+    }
 }
 
 /// Main simulation engine
@@ -79,12 +289,30 @@ pub struct Simulation {
     config: SimulationConfig,
     /// Simulation statistics
     stats: SimulationStats,
-    /// Current clock state for sequential components
-    clock_state: Value,
-    /// Previous clock state for edge detection
-    prev_clock_state: Value,
     /// Signal change callbacks for external observers (e.g., chronogram)
     signal_callbacks: Vec<SignalChangeCallback>,
+    /// Propagation tracking for oscillation detection
+    propagations: PropagationTracker,
+    /// Oscillations detected since the last reset
+    oscillations: Vec<Oscillation>,
+    /// Snapshots taken before recent events, for stepping backwards
+    history: History,
+    /// Conditions that stop `run` and friends
+    breakpoints: Breakpoints,
+    /// Breakpoints fired by the last processed event
+    breakpoint_hits: Vec<BreakpointHit>,
+    /// Evaluation order for the levelized engine
+    levelization: Levelization,
+    /// Delays set on individual component instances
+    component_delays: HashMap<ComponentId, u64>,
+    /// Latest output change in flight from every component to every node
+    pending_outputs: HashMap<(ComponentId, NodeId), (EventId, Signal)>,
+    /// Setup, hold and glitch checks on node changes
+    timing: TimingMonitor,
+    /// Stuck-at faults injected on component pins
+    pin_faults: PinFaults,
+    /// The .circ file the simulation was built from, if any
+    circuit_source: Option<Box<CircuitSource>>,
 }
 
 impl Simulation {
@@ -96,23 +324,43 @@ impl Simulation {
             components: HashMap::new(),
             config: SimulationConfig::default(),
             stats: SimulationStats::default(),
-            clock_state: Value::Low,
-            prev_clock_state: Value::Low,
             signal_callbacks: Vec::new(),
+            propagations: PropagationTracker::default(),
+            oscillations: Vec::new(),
+            history: History::default(),
+            breakpoints: Breakpoints::default(),
+            breakpoint_hits: Vec::new(),
+            levelization: Levelization::Stale,
+            component_delays: HashMap::new(),
+            pending_outputs: HashMap::new(),
+            timing: TimingMonitor::default(),
+            pin_faults: PinFaults::default(),
+            circuit_source: None,
         }
     }
 
     /// Create a new simulation with custom configuration
     pub fn with_config(config: SimulationConfig) -> Self {
+        let mut netlist = Netlist::new();
+        netlist.set_logic_mode(config.logic_mode);
         Simulation {
             event_queue: EventQueue::new(),
-            netlist: Netlist::new(),
+            netlist,
             components: HashMap::new(),
+            history: History::new(config.history_depth),
             config,
             stats: SimulationStats::default(),
-            clock_state: Value::Low,
-            prev_clock_state: Value::Low,
             signal_callbacks: Vec::new(),
+            propagations: PropagationTracker::default(),
+            oscillations: Vec::new(),
+            breakpoints: Breakpoints::default(),
+            breakpoint_hits: Vec::new(),
+            levelization: Levelization::Stale,
+            component_delays: HashMap::new(),
+            pending_outputs: HashMap::new(),
+            timing: TimingMonitor::default(),
+            pin_faults: PinFaults::default(),
+            circuit_source: None,
         }
     }
 
@@ -120,12 +368,40 @@ impl Simulation {
     pub fn add_component(&mut self, component: Box<dyn Component>) -> ComponentId {
         let id = component.id();
         self.components.insert(id, component);
+        self.levelization = Levelization::Stale;
+        self.timing.invalidate();
         id
     }
 
     /// Remove a component from the simulation
+    ///
+    /// Its pins are disconnected, its switch opens and its pull resistors
+    /// come off their nets.
     pub fn remove_component(&mut self, id: ComponentId) -> Option<Box<dyn Component>> {
-        self.components.remove(&id)
+        self.levelization = Levelization::Stale;
+        self.timing.invalidate();
+        self.component_delays.remove(&id);
+        let component = self.components.remove(&id)?;
+
+        let mut changed_nodes = self.netlist.set_switch(id, None).unwrap_or_default();
+        let connections: Vec<(String, NodeId)> = self
+            .netlist
+            .get_component_connections(id)
+            .into_iter()
+            .map(|connection| (connection.pin_name.clone(), connection.node_id))
+            .collect();
+        for (pin_name, node_id) in connections {
+            let _ = self.netlist.disconnect(id, &pin_name);
+            if component.pull_value(&pin_name).is_some() {
+                changed_nodes.extend(
+                    self.netlist
+                        .set_node_pull(node_id, None)
+                        .unwrap_or_default(),
+                );
+            }
+        }
+        self.schedule_readers(self.current_time(), &changed_nodes);
+        Some(component)
     }
 
     /// Get a component by ID
@@ -148,16 +424,187 @@ impl Simulation {
         &mut self.netlist
     }
 
+    /// Connect a component pin to a node, checking that their bus widths match
+    pub fn connect(
+        &mut self,
+        component_id: ComponentId,
+        pin_name: String,
+        node_id: NodeId,
+    ) -> Result<(), SimulationError> {
+        let component = self
+            .components
+            .get(&component_id)
+            .ok_or(SimulationError::ComponentNotFound(component_id))?;
+        let pin_width = component
+            .get_pin(&pin_name)
+            .map(|pin| pin.width)
+            .ok_or_else(|| {
+                SimulationError::NetlistError(format!(
+                    "Pin {} not found on component {}",
+                    pin_name, component_id
+                ))
+            })?;
+        let node_width = self
+            .netlist
+            .get_node(node_id)
+            .map(|node| node.width)
+            .ok_or(SimulationError::NodeNotFound(node_id))?;
+
+        if pin_width != node_width {
+            return Err(SimulationError::WidthMismatch {
+                node: node_id,
+                expected: node_width,
+                actual: pin_width,
+            });
+        }
+
+        let pull = component.pull_value(&pin_name);
+        self.levelization = Levelization::Stale;
+        self.timing.invalidate();
+        self.netlist
+            .connect(component_id, pin_name, node_id)
+            .map_err(|e| SimulationError::NetlistError(e.to_string()))?;
+
+        // A pull resistor pulls the whole net it is connected to
+        if let Some(pull) = pull {
+            let changed_nodes = self
+                .netlist
+                .set_node_pull(node_id, Some(pull))
+                .map_err(|e| SimulationError::NetlistError(e.to_string()))?;
+            self.schedule_readers(self.current_time(), &changed_nodes);
+        }
+        Ok(())
+    }
+
     /// Get current simulation time
     pub fn current_time(&self) -> Timestamp {
         self.event_queue.current_time()
     }
 
+    /// Get the engine actually evaluating combinational logic
+    ///
+    /// This is the configured engine, except that a levelized simulation
+    /// whose combinational logic contains a loop or a switch component
+    /// falls back to the event engine. The circuit is compiled on reset;
+    /// changes made through `netlist_mut` afterwards take effect at the next
+    /// reset.
+    pub fn active_engine(&self) -> SimulationEngine {
+        match self.levelization {
+            Levelization::Ready(_) => SimulationEngine::Levelized,
+            _ => SimulationEngine::EventDriven,
+        }
+    }
+
+    /// Get the number of ranks of combinational logic, if levelized
+    pub fn levelized_depth(&self) -> Option<usize> {
+        match &self.levelization {
+            Levelization::Ready(schedule) => Some(schedule.depth()),
+            _ => None,
+        }
+    }
+
+    /// Compile the circuit for bit-parallel evaluation of many input vectors
+    ///
+    /// The circuit must be loop-free and built only from combinational
+    /// components with a bit-parallel implementation.
+    pub fn batch(
+        &self,
+        inputs: &[NodeId],
+        outputs: &[NodeId],
+    ) -> Result<BatchEvaluator<'_>, SimulationError> {
+        BatchEvaluator::new(&self.netlist, &self.components, inputs, outputs)
+    }
+
+    /// Evaluate the circuit for a list of input assignments
+    ///
+    /// Shorthand for `batch(inputs, outputs)?.evaluate(vectors)`.
+    pub fn evaluate_batch(
+        &self,
+        inputs: &[NodeId],
+        outputs: &[NodeId],
+        vectors: &[Vec<Signal>],
+    ) -> Result<Vec<Vec<Signal>>, SimulationError> {
+        self.batch(inputs, outputs)?.evaluate(vectors)
+    }
+
+    /// Find the longest combinational paths and the maximum clock frequency
+    ///
+    /// Every component instance is charged the delay the simulation would
+    /// apply to it. Circuits with combinational loops cannot be analysed.
+    pub fn timing_report(&self) -> Result<TimingReport, SimulationError> {
+        crate::timing::analyze(&self.netlist, &self.components, |component_id| {
+            let own = self
+                .components
+                .get(&component_id)
+                .map_or(0, |component| component.propagation_delay());
+            self.delay_of(component_id, own)
+        })
+    }
+
+    /// Set the propagation delay of one component instance
+    ///
+    /// The delay overrides both the configured delay table and the
+    /// component's own delay. It applies to output changes scheduled from
+    /// now on, and only in the event engine: the levelized engine settles
+    /// combinational logic with zero delay.
+    pub fn set_component_delay(
+        &mut self,
+        component_id: ComponentId,
+        delay: u64,
+    ) -> Result<(), SimulationError> {
+        if !self.components.contains_key(&component_id) {
+            return Err(SimulationError::ComponentNotFound(component_id));
+        }
+        self.component_delays.insert(component_id, delay);
+        Ok(())
+    }
+
+    /// Get the delay set on a component instance, if any
+    pub fn instance_delay(&self, component_id: ComponentId) -> Option<u64> {
+        self.component_delays.get(&component_id).copied()
+    }
+
+    /// Remove the delay set on a component instance, returning it
+    pub fn clear_component_delay(&mut self, component_id: ComponentId) -> Option<u64> {
+        self.component_delays.remove(&component_id)
+    }
+
+    /// Get the propagation delay a component instance is simulated with
+    pub fn component_delay(&self, component_id: ComponentId) -> Option<u64> {
+        let component = self.components.get(&component_id)?;
+        Some(self.delay_of(component_id, component.propagation_delay()))
+    }
+
+    /// Resolve the delay of a component's output change
+    ///
+    /// `own` is the delay the component asked for, used when neither the
+    /// instance nor the delay table says otherwise.
+    fn delay_of(&self, component_id: ComponentId, own: u64) -> u64 {
+        if let Some(&delay) = self.component_delays.get(&component_id) {
+            return delay;
+        }
+        self.components
+            .get(&component_id)
+            .and_then(|component| self.config.delays.get(component.name()))
+            .unwrap_or(own)
+    }
+
     /// Get simulation statistics
     pub fn stats(&self) -> &SimulationStats {
         &self.stats
     }
 
+    /// Get the setup, hold and glitch violations detected since the last
+    /// reset
+    pub fn violations(&self) -> &[Violation] {
+        &self.stats.violations
+    }
+
+    /// Get the oscillations detected since the last reset
+    pub fn oscillations(&self) -> &[Oscillation] {
+        &self.oscillations
+    }
+
     /// Check if there are events pending in the queue
     pub fn has_pending_events(&self) -> bool {
         !self.event_queue.is_empty()
@@ -168,6 +615,24 @@ impl Simulation {
         self.signal_callbacks.push(callback);
     }
 
+    /// Get the IDs of all components in the simulation, in ascending order
+    pub fn get_all_component_ids(&self) -> Vec<ComponentId> {
+        let mut component_ids: Vec<_> = self.components.keys().copied().collect();
+        component_ids.sort();
+        component_ids
+    }
+
+    /// Get the .circ file the simulation was built from, if it was loaded
+    /// from one
+    pub fn circuit_source(&self) -> Option<&CircuitSource> {
+        self.circuit_source.as_deref()
+    }
+
+    /// Record the .circ file the simulation was built from
+    pub(crate) fn set_circuit_source(&mut self, source: CircuitSource) {
+        self.circuit_source = Some(Box::new(source));
+    }
+
     /// Get all node IDs that have signals (for chronogram signal selection)
     pub fn get_all_node_ids(&self) -> Vec<NodeId> {
         self.netlist.get_all_node_ids()
@@ -178,6 +643,12 @@ impl Simulation {
         self.netlist.get_signal(node_id).cloned()
     }
 
+    /// Get the IEEE 1164 level of every bit of a node, least significant
+    /// bit first
+    pub fn get_node_std_logic(&self, node_id: NodeId) -> Option<Vec<StdLogic>> {
+        self.netlist.get_node_std_logic(node_id)
+    }
+
     /// Reset the simulation to initial state
     pub fn reset(&mut self) {
         // Clear event queue
@@ -188,13 +659,24 @@ impl Simulation {
             component.reset();
         }
 
-        // Reset clock state
-        self.clock_state = Value::Low;
-        self.prev_clock_state = Value::Low;
-
         // Reset statistics
         self.stats = SimulationStats::default();
 
+        // Forget oscillations and release the nodes they forced
+        for oscillation in self.oscillations.drain(..) {
+            for node_id in oscillation.nodes {
+                let _ = self.netlist.release_node(node_id, OSCILLATION_DRIVER);
+            }
+        }
+        self.propagations = PropagationTracker::default();
+        self.pending_outputs.clear();
+        self.history.clear();
+        self.breakpoint_hits.clear();
+        self.breakpoints.sync(&self.netlist);
+        self.levelization = Levelization::Stale;
+        self.timing.invalidate();
+        self.compile();
+
         // Schedule initial reset event
         self.event_queue.schedule_reset(Timestamp(0));
 
@@ -204,7 +686,12 @@ impl Simulation {
     }
 
     /// Step the simulation by one event
+    ///
+    /// Breakpoints fired by the event are reported by `breakpoint_hits`
+    /// until the next step.
     pub fn step(&mut self) -> Result<bool, SimulationError> {
+        self.breakpoint_hits.clear();
+
         // Check if we have any events to process
         if self.event_queue.is_empty() {
             return Ok(false); // No more events
@@ -223,8 +710,32 @@ impl Simulation {
             }
         }
 
-        // Get the next event
+        if self.history.is_enabled() {
+            let snapshot = self.snapshot();
+            self.history.push(snapshot);
+        }
+
+        self.compile();
+        match std::mem::take(&mut self.levelization) {
+            Levelization::Ready(schedule) => {
+                let result = self.step_levelized(&schedule);
+                self.levelization = Levelization::Ready(schedule);
+                if !self.breakpoints.is_empty() {
+                    let time = self.current_time();
+                    self.breakpoint_hits = self.breakpoints.check(&self.netlist, time);
+                }
+                result?;
+                return Ok(true);
+            }
+            levelization => self.levelization = levelization,
+        }
+
+        // Get the next event, dropping what is left of a stopped oscillation
         let event = self.event_queue.pop().unwrap();
+        if self.propagations.begin(event.id) {
+            self.propagations.end();
+            return Ok(true);
+        }
         self.stats.current_time = event.time;
         self.stats.events_processed += 1;
 
@@ -236,37 +747,45 @@ impl Simulation {
         }
 
         // Process the event
-        match event.event_type {
+        let result = match event.event_type {
             EventType::SignalChange {
                 node_id,
                 new_signal,
                 source_component,
             } => {
-                self.process_signal_change(event.time, node_id, new_signal, source_component)?;
-            }
-            EventType::ClockTick => {
-                self.process_clock_tick(event.time)?;
+                self.output_arrived(event.id, source_component, node_id);
+                self.process_signal_change(event.time, node_id, new_signal, source_component)
             }
+            EventType::ClockTick => self.process_clock_tick(event.time),
             EventType::ComponentUpdate { component_id } => {
-                self.process_component_update(event.time, component_id)?;
-            }
-            EventType::Reset => {
-                self.process_reset(event.time)?;
+                self.process_component_update(event.time, component_id)
             }
+            EventType::Reset => self.process_reset(event.time),
+        };
+        self.propagations.end();
+        if !self.breakpoints.is_empty() {
+            self.breakpoint_hits = self.breakpoints.check(&self.netlist, event.time);
         }
+        result?;
 
         Ok(true) // More events may be available
     }
 
-    /// Run the simulation until completion or error
+    /// Run the simulation until completion, error or breakpoint
+    ///
+    /// A circuit with a running clock never settles, so this ends with
+    /// `TimeoutExceeded`; use `run_until` to simulate such circuits.
     pub fn run(&mut self) -> Result<(), SimulationError> {
         while self.step()? {
-            // Continue until no more events or error
+            if !self.breakpoint_hits.is_empty() {
+                break; // Stopped at a breakpoint
+            }
         }
         Ok(())
     }
 
-    /// Run the simulation for a specific number of steps
+    /// Run the simulation for a specific number of steps, stopping early at
+    /// a breakpoint
     pub fn run_steps(&mut self, max_steps: usize) -> Result<usize, SimulationError> {
         let mut steps = 0;
         while steps < max_steps {
@@ -274,11 +793,15 @@ impl Simulation {
                 break; // No more events
             }
             steps += 1;
+            if !self.breakpoint_hits.is_empty() {
+                break; // Stopped at a breakpoint
+            }
         }
         Ok(steps)
     }
 
-    /// Run the simulation until a specific time
+    /// Run the simulation until a specific time, stopping early at a
+    /// breakpoint
     pub fn run_until(&mut self, target_time: Timestamp) -> Result<(), SimulationError> {
         while let Some(next_event_time) = self.event_queue.next_event_time() {
             if next_event_time >= target_time {
@@ -287,6 +810,430 @@ impl Simulation {
             if !self.step()? {
                 break; // No more events
             }
+            if !self.breakpoint_hits.is_empty() {
+                break; // Stopped at a breakpoint
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a breakpoint on named nodes
+    ///
+    /// Every node the condition names must exist.
+    pub fn add_breakpoint(
+        &mut self,
+        condition: Condition,
+    ) -> Result<BreakpointId, SimulationError> {
+        self.breakpoints
+            .add(condition, &self.netlist)
+            .map_err(SimulationError::NamedNodeNotFound)
+    }
+
+    /// Remove a breakpoint, returning its condition
+    pub fn remove_breakpoint(&mut self, id: BreakpointId) -> Option<Condition> {
+        self.breakpoints.remove(id)
+    }
+
+    /// Remove every breakpoint
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Get every breakpoint with its condition
+    pub fn breakpoints(&self) -> Vec<(BreakpointId, &Condition)> {
+        self.breakpoints.list()
+    }
+
+    /// Get the breakpoints fired by the last processed event
+    pub fn breakpoint_hits(&self) -> &[BreakpointHit] {
+        &self.breakpoint_hits
+    }
+
+    /// Capture the complete state of the simulation
+    ///
+    /// Signal callbacks and the configuration are not part of a snapshot.
+    pub fn snapshot(&self) -> SimulationSnapshot {
+        let nodes = self
+            .netlist
+            .get_all_node_ids()
+            .into_iter()
+            .filter_map(|node_id| {
+                let node = self.netlist.get_node(node_id)?;
+                let state = NodeState {
+                    signal: node.signal.clone(),
+                    drivers: node
+                        .drivers
+                        .iter()
+                        .map(|(&component_id, signal)| (component_id, signal.clone()))
+                        .collect(),
+                };
+                Some((node_id, state))
+            })
+            .collect();
+        let components = self
+            .components
+            .iter()
+            .map(|(&component_id, component)| {
+                let state = ComponentState {
+                    pins: component
+                        .pins()
+                        .iter()
+                        .map(|(name, pin)| (name.clone(), pin.signal.clone()))
+                        .collect(),
+                    internal: component.save_state(),
+                };
+                (component_id, state)
+            })
+            .collect();
+
+        SimulationSnapshot {
+            nodes,
+            components,
+            events: self.event_queue.clone(),
+            stats: self.stats.clone(),
+            oscillations: self.oscillations.clone(),
+        }
+    }
+
+    /// Restore a snapshot of this simulation or of another simulation of
+    /// the same circuit
+    ///
+    /// Every node and component in the snapshot must exist. Signal callbacks
+    /// are not told about the restored values, the history is cleared, and
+    /// the restored events count as fresh propagations for oscillation
+    /// detection.
+    pub fn restore(&mut self, snapshot: &SimulationSnapshot) -> Result<(), SimulationError> {
+        self.apply_snapshot(snapshot)?;
+        self.history.clear();
+        Ok(())
+    }
+
+    /// Number of snapshots available for stepping backwards
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Step back over the `events` most recently processed events
+    ///
+    /// Returns the number of events actually undone, which is smaller when
+    /// the history does not reach back far enough.
+    pub fn step_back(&mut self, events: usize) -> Result<usize, SimulationError> {
+        let steps = events.min(self.history.len());
+        self.rewind(steps)?;
+        Ok(steps)
+    }
+
+    /// Step back over the `cycles` most recent rising edges of a clock node
+    ///
+    /// The simulation returns to just before the oldest of those edges.
+    /// Returns the number of cycles actually undone, which is smaller when
+    /// the history does not reach back far enough.
+    pub fn step_back_cycles(
+        &mut self,
+        clock: NodeId,
+        cycles: usize,
+    ) -> Result<usize, SimulationError> {
+        let mut after = self
+            .netlist
+            .get_signal(clock)
+            .and_then(Signal::as_single)
+            .ok_or(SimulationError::NodeNotFound(clock))?;
+
+        let mut edges = 0;
+        let mut steps = 0;
+        for (index, snapshot) in self.history.iter_recent().enumerate() {
+            if edges == cycles {
+                break;
+            }
+            let before = snapshot
+                .node_signal(clock)
+                .and_then(Signal::as_single)
+                .unwrap_or(Value::Unknown);
+            if before == Value::Low && after == Value::High {
+                edges += 1;
+                steps = index + 1;
+            }
+            after = before;
+        }
+
+        self.rewind(steps)?;
+        Ok(edges)
+    }
+
+    /// Return to the state before the `steps` most recent events
+    fn rewind(&mut self, steps: usize) -> Result<(), SimulationError> {
+        if let Some(snapshot) = self.history.pop(steps) {
+            self.apply_snapshot(&snapshot)?;
+        }
+        Ok(())
+    }
+
+    /// Overwrite the current state with a snapshot
+    fn apply_snapshot(&mut self, snapshot: &SimulationSnapshot) -> Result<(), SimulationError> {
+        // Check the circuit matches before changing anything
+        for &node_id in snapshot.nodes.keys() {
+            if self.netlist.get_node(node_id).is_none() {
+                return Err(SimulationError::NodeNotFound(node_id));
+            }
+        }
+        for &component_id in snapshot.components.keys() {
+            if !self.components.contains_key(&component_id) {
+                return Err(SimulationError::ComponentNotFound(component_id));
+            }
+        }
+
+        for (&component_id, state) in &snapshot.components {
+            let component = self
+                .components
+                .get_mut(&component_id)
+                .ok_or(SimulationError::ComponentNotFound(component_id))?;
+            for (name, signal) in &state.pins {
+                if let Some(pin) = component.get_pin_mut(name) {
+                    pin.signal = signal.clone();
+                }
+            }
+            if let Some(internal) = &state.internal {
+                component.restore_state(internal).map_err(|e| {
+                    SimulationError::SnapshotError(format!(
+                        "Cannot restore component {}: {}",
+                        component_id, e
+                    ))
+                })?;
+            }
+        }
+
+        // Switches follow the restored components
+        let switches: Vec<_> = self
+            .components
+            .iter()
+            .filter_map(|(&component_id, component)| {
+                let switch = component.switch_state()?;
+                Some((component_id, self.switch_nodes(component_id, &switch)))
+            })
+            .collect();
+        for (component_id, nodes) in switches {
+            let _ = self.netlist.set_switch(component_id, nodes);
+        }
+
+        for (&node_id, state) in &snapshot.nodes {
+            let node = self
+                .netlist
+                .get_node_mut(node_id)
+                .ok_or(SimulationError::NodeNotFound(node_id))?;
+            node.signal = state.signal.clone();
+            node.drivers = state
+                .drivers
+                .iter()
+                .map(|(&component_id, signal)| (component_id, signal.clone()))
+                .collect();
+        }
+
+        self.event_queue = snapshot.events.clone();
+        self.track_pending_outputs();
+        self.stats = snapshot.stats.clone();
+        self.oscillations = snapshot.oscillations.clone();
+        self.propagations = PropagationTracker::default();
+        self.breakpoint_hits.clear();
+        self.breakpoints.sync(&self.netlist);
+        self.timing.invalidate();
+        Ok(())
+    }
+
+    /// Inject a stuck-at fault
+    ///
+    /// A faulty net holds its level whatever drives it; a faulty pin only
+    /// changes what its own component reads or drives. The components
+    /// affected are re-evaluated at the current time. Faults stay injected
+    /// across resets until removed.
+    pub fn inject_fault(&mut self, fault: &Fault) -> Result<(), SimulationError> {
+        self.set_fault(&fault.site, Some(fault.stuck_at.value()))
+    }
+
+    /// Remove the fault injected at a fault's site, if any
+    pub fn remove_fault(&mut self, fault: &Fault) -> Result<(), SimulationError> {
+        self.set_fault(&fault.site, None)
+    }
+
+    /// Remove every injected fault
+    pub fn clear_faults(&mut self) {
+        for fault in self.injected_faults() {
+            // The sites were just listed, so they exist
+            let _ = self.set_fault(&fault.site, None);
+        }
+    }
+
+    /// Get the injected faults, sorted by site
+    pub fn injected_faults(&self) -> Vec<Fault> {
+        let stuck_at = |value: Value| {
+            if value == Value::High {
+                StuckAt::One
+            } else {
+                StuckAt::Zero
+            }
+        };
+        let mut faults: Vec<Fault> =
+            self.netlist
+                .get_all_nodes()
+                .values()
+                .filter_map(|node| Some(Fault::node(node.id, stuck_at(node.stuck_at?))))
+                .chain(self.pin_faults.iter().map(|(component_id, pin, value)| {
+                    Fault::pin(component_id, pin, stuck_at(value))
+                }))
+                .collect();
+        faults.sort();
+        faults
+    }
+
+    /// List both stuck-at faults of every net and of every connected
+    /// component pin, sorted by site
+    pub fn fault_list(&self) -> Vec<Fault> {
+        let mut sites: Vec<FaultSite> = self
+            .netlist
+            .get_all_node_ids()
+            .into_iter()
+            .map(FaultSite::Node)
+            .collect();
+        for &component_id in self.components.keys() {
+            for connection in self.netlist.get_component_connections(component_id) {
+                sites.push(FaultSite::Pin {
+                    component: component_id,
+                    pin: connection.pin_name.clone(),
+                });
+            }
+        }
+        sites.sort();
+        sites.dedup();
+        sites
+            .into_iter()
+            .flat_map(|site| {
+                [StuckAt::Zero, StuckAt::One].map(|stuck_at| Fault {
+                    site: site.clone(),
+                    stuck_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Run a test set against the fault-free circuit and once per fault, and
+    /// report which faults some vector detects
+    ///
+    /// Every run starts from a reset; vector `i` is applied at
+    /// `i * tests.period` and the outputs are sampled just before the next
+    /// one. A fault is detected when it changes an output the fault-free
+    /// circuit drives to a fully defined value. Faults injected beforehand
+    /// are removed, and the simulation is left reset and fault-free.
+    pub fn fault_coverage(
+        &mut self,
+        faults: &[Fault],
+        tests: &TestSet,
+    ) -> Result<FaultReport, SimulationError> {
+        if let Some(index) = tests
+            .vectors
+            .iter()
+            .position(|vector| vector.len() != tests.inputs.len())
+        {
+            return Err(SimulationError::FaultSimulationError(format!(
+                "Vector {} has {} signals for {} inputs",
+                index,
+                tests.vectors[index].len(),
+                tests.inputs.len()
+            )));
+        }
+
+        self.clear_faults();
+        let good = self.apply_tests(tests)?;
+        let mut report = FaultReport::default();
+        for fault in faults {
+            self.inject_fault(fault)?;
+            let responses = self.apply_tests(tests);
+            self.remove_fault(fault)?;
+            let detection =
+                responses?
+                    .iter()
+                    .zip(&good)
+                    .enumerate()
+                    .find_map(|(vector, (faulty, good))| {
+                        first_difference(good, faulty).map(|output| (vector, tests.outputs[output]))
+                    });
+            match detection {
+                Some((vector, output)) => report.detected.push(DetectedFault {
+                    fault: fault.clone(),
+                    vector,
+                    output,
+                }),
+                None => report.undetected.push(fault.clone()),
+            }
+        }
+        self.reset();
+        Ok(report)
+    }
+
+    /// Apply every vector of a test set from a reset and sample the outputs
+    fn apply_tests(&mut self, tests: &TestSet) -> Result<Vec<Vec<Signal>>, SimulationError> {
+        self.reset();
+        let mut responses = Vec::with_capacity(tests.vectors.len());
+        for (index, vector) in tests.vectors.iter().enumerate() {
+            let start = Timestamp(index as u64 * tests.period);
+            for (&input, signal) in tests.inputs.iter().zip(vector) {
+                self.schedule_signal_change(start, input, signal.clone(), ComponentId(0));
+            }
+            self.run_until(start.add_delay(tests.period))?;
+            let response = tests
+                .outputs
+                .iter()
+                .map(|&output| {
+                    self.get_node_signal(output)
+                        .ok_or(SimulationError::NodeNotFound(output))
+                })
+                .collect::<Result<_, _>>()?;
+            responses.push(response);
+        }
+        Ok(responses)
+    }
+
+    /// Hold a fault site at a value, or release it with `None`, and
+    /// re-evaluate the components affected
+    fn set_fault(&mut self, site: &FaultSite, value: Option<Value>) -> Result<(), SimulationError> {
+        let time = self.current_time();
+        match site {
+            FaultSite::Node(node_id) => {
+                if self.netlist.get_node(*node_id).is_none() {
+                    return Err(SimulationError::NodeNotFound(*node_id));
+                }
+                let changed_nodes = self
+                    .netlist
+                    .set_node_stuck_at(*node_id, value)
+                    .map_err(|e| SimulationError::NetlistError(e.to_string()))?;
+                for changed_node in changed_nodes {
+                    for component_id in self.netlist.get_affected_components(changed_node) {
+                        self.event_queue
+                            .schedule_component_update(time, component_id);
+                    }
+                }
+            }
+            FaultSite::Pin { component, pin } => {
+                let has_pin = self
+                    .components
+                    .get(component)
+                    .ok_or(SimulationError::ComponentNotFound(*component))?
+                    .get_pin(pin)
+                    .is_some();
+                if !has_pin {
+                    return Err(SimulationError::NetlistError(format!(
+                        "Pin {} not found on component {}",
+                        pin, component
+                    )));
+                }
+                match value {
+                    Some(value) => self.pin_faults.insert(*component, pin.clone(), value),
+                    None => {
+                        if !self.pin_faults.remove(*component, pin) {
+                            return Ok(());
+                        }
+                    }
+                }
+                self.event_queue.schedule_component_update(time, *component);
+            }
         }
         Ok(())
     }
@@ -303,91 +1250,274 @@ impl Simulation {
             .schedule_signal_change(time, node_id, signal, source_component);
     }
 
-    /// Schedule a clock tick
+    /// Schedule a manual clock tick
+    ///
+    /// Every clock source advances by one half period at `time`, whether or
+    /// not it is running on its own.
     pub fn schedule_clock_tick(&mut self, time: Timestamp) {
         self.event_queue.schedule_clock_tick(time);
     }
 
     /// Process a signal change event
+    ///
+    /// The new signal is recorded as the source component's contribution to
+    /// the node, and the node takes the resolution of all of its drivers.
     fn process_signal_change(
         &mut self,
         time: Timestamp,
         node_id: NodeId,
         new_signal: Signal,
-        _source_component: ComponentId,
+        source_component: ComponentId,
     ) -> Result<(), SimulationError> {
-        // Check if the signal actually changed to avoid infinite loops
-        let signal_changed = if let Some(current_signal) = self.netlist.get_node_signal(node_id) {
-            current_signal != &new_signal
-        } else {
-            true
-        };
+        let changed_nodes = self.apply_signal(time, node_id, new_signal, source_component)?;
+        if changed_nodes.is_empty() {
+            return Ok(());
+        }
+
+        self.schedule_readers(time, &changed_nodes);
+        self.stats.propagation_steps += 1;
+        Ok(())
+    }
 
-        if !signal_changed {
-            return Ok(()); // No change, no propagation needed
+    /// Schedule the components connected to changed nodes for evaluation
+    fn schedule_readers(&mut self, time: Timestamp, changed_nodes: &[NodeId]) {
+        for &changed_node in changed_nodes {
+            // Get all components affected by this signal change
+            let affected_components = self.netlist.get_affected_components(changed_node);
+
+            if self.config.debug {
+                println!(
+                    "Signal change at node {} affects {} components",
+                    changed_node,
+                    affected_components.len()
+                );
+            }
+
+            // Evaluate the affected components straight away; their own
+            // delays apply to the changes they drive in response
+            for component_id in affected_components {
+                let event_id = self
+                    .event_queue
+                    .schedule_component_update(time, component_id);
+                self.propagations.follow(event_id);
+            }
         }
+    }
 
-        // Update the signal at the node
-        self.netlist
-            .set_node_signal(node_id, new_signal.clone())
+    /// Drive a node and report every node whose resolved value changed
+    ///
+    /// Signal callbacks, breakpoints and the timing checks learn about the
+    /// changes; scheduling the components that read them is left to the
+    /// caller.
+    fn apply_signal(
+        &mut self,
+        time: Timestamp,
+        node_id: NodeId,
+        new_signal: Signal,
+        source_component: ComponentId,
+    ) -> Result<Vec<NodeId>, SimulationError> {
+        // Reject drivers whose bus width does not match the node
+        let node_width = self
+            .netlist
+            .get_node(node_id)
+            .map(|node| node.width)
+            .ok_or(SimulationError::NodeNotFound(node_id))?;
+        if new_signal.width() != node_width {
+            return Err(SimulationError::WidthMismatch {
+                node: node_id,
+                expected: node_width,
+                actual: new_signal.width(),
+            });
+        }
+
+        // Resolve against the other drivers; only a change in the resolved
+        // value needs to propagate, which also avoids infinite loops. Nodes
+        // sharing bits with this one (through splitters) may change as well.
+        let changed_nodes = self
+            .netlist
+            .drive_node(node_id, source_component, new_signal)
             .map_err(|e| SimulationError::NetlistError(e.to_string()))?;
+        self.nodes_changed(time, changed_nodes)
+    }
+
+    /// Report nodes whose resolved value changed to the signal callbacks,
+    /// breakpoints and timing checks
+    fn nodes_changed(
+        &mut self,
+        time: Timestamp,
+        changed_nodes: Vec<NodeId>,
+    ) -> Result<Vec<NodeId>, SimulationError> {
+        self.timing
+            .prepare(&self.netlist, &self.components, self.config.glitch_width);
+
+        let mut stop = None;
+        for &changed_node in &changed_nodes {
+            self.breakpoints.node_changed(changed_node);
+            let signal = self
+                .netlist
+                .get_node_signal(changed_node)
+                .cloned()
+                .ok_or(SimulationError::NodeNotFound(changed_node))?;
+
+            // Trigger signal change callbacks (for chronogram, etc.)
+            for callback in &mut self.signal_callbacks {
+                callback(changed_node, time, &signal);
+            }
 
-        // Trigger signal change callbacks (for chronogram, etc.)
-        for callback in &mut self.signal_callbacks {
-            callback(node_id, time, &new_signal);
+            let violations =
+                self.timing
+                    .node_changed(time, changed_node, &signal, self.config.glitch_width);
+            if self.config.violation_action == ViolationAction::Stop && stop.is_none() {
+                stop = violations.first().cloned();
+            }
+            self.stats.violations.extend(violations);
         }
 
-        // Get all components affected by this signal change
-        let affected_components = self.netlist.get_affected_components(node_id);
+        match stop {
+            Some(violation) => Err(SimulationError::TimingViolation(violation)),
+            None => Ok(changed_nodes),
+        }
+    }
 
+    /// Compile the levelized schedule if it is wanted and out of date
+    fn compile(&mut self) {
+        if self.config.engine != SimulationEngine::Levelized
+            || !matches!(self.levelization, Levelization::Stale)
+        {
+            return;
+        }
+        self.levelization = match Schedule::compile(&self.netlist, &self.components) {
+            Some(schedule) => Levelization::Ready(Box::new(schedule)),
+            None => Levelization::Cyclic,
+        };
         if self.config.debug {
-            println!(
-                "Signal change at node {} affects {} components",
-                node_id,
-                affected_components.len()
-            );
+            println!("Levelized engine: {:?}", self.active_engine());
         }
+    }
 
-        // Schedule updates for all affected components
-        for component_id in affected_components {
-            // Schedule a component update event with a small delay
-            self.event_queue
-                .schedule_component_update(time.add_delay(1), component_id);
+    /// Process every event due at the next event time, then settle the
+    /// combinational logic in one pass and update the sequential components
+    /// whose inputs changed
+    fn step_levelized(&mut self, schedule: &Schedule) -> Result<(), SimulationError> {
+        let Some(time) = self.event_queue.next_event_time() else {
+            return Ok(());
+        };
+        let mut dirty = Dirty::default();
+
+        while self.event_queue.next_event_time() == Some(time) {
+            let event = self.event_queue.pop().unwrap();
+            if self.propagations.begin(event.id) {
+                self.propagations.end();
+                continue;
+            }
+            self.stats.current_time = event.time;
+            self.stats.events_processed += 1;
+
+            let result = match event.event_type {
+                EventType::SignalChange {
+                    node_id,
+                    new_signal,
+                    source_component,
+                } => self
+                    .output_arrived(event.id, source_component, node_id)
+                    .apply_signal(time, node_id, new_signal, source_component)
+                    .map(|changed_nodes| {
+                        for node in changed_nodes {
+                            for &reader in schedule.readers(node) {
+                                dirty.mark(schedule, reader);
+                            }
+                        }
+                    }),
+                EventType::ClockTick => self.process_clock_tick(time),
+                EventType::ComponentUpdate { component_id } => {
+                    dirty.mark(schedule, component_id);
+                    Ok(())
+                }
+                EventType::Reset => self.process_reset(time),
+            };
+            self.propagations.end();
+            result?;
         }
 
+        // Settle the combinational logic; readers always come later in the
+        // order, so a single pass suffices
+        while let Some(position) = dirty.combinational.pop_first() {
+            let component_id = schedule.component_at(position);
+            let result = self.evaluate(schedule, time, component_id)?;
+            if !result.state_changed {
+                continue;
+            }
+            for (pin_name, signal) in result.outputs {
+                let Some(node_id) = schedule.output_node(component_id, &pin_name) else {
+                    continue;
+                };
+                if self.netlist.get_node_driver(node_id, component_id) == Some(&signal) {
+                    continue;
+                }
+                for node in self.apply_signal(time, node_id, signal, component_id)? {
+                    for &reader in schedule.readers(node) {
+                        if reader != component_id {
+                            dirty.mark(schedule, reader);
+                        }
+                    }
+                }
+            }
+            if let Some(wakeup) = result.wakeup {
+                self.event_queue
+                    .schedule_component_update(time.add_delay(wakeup), component_id);
+            }
+        }
+
+        // Sequential components see the settled values and schedule their
+        // outputs as events
+        self.propagations.start();
+        for component_id in std::mem::take(&mut dirty.sequential) {
+            let result = self.evaluate(schedule, time, component_id)?;
+            self.handle_update_result(time, component_id, result)?;
+        }
+        self.propagations.end();
+
         self.stats.propagation_steps += 1;
         Ok(())
     }
 
+    /// Load a component's inputs from the netlist and update it
+    fn evaluate(
+        &mut self,
+        schedule: &Schedule,
+        time: Timestamp,
+        component_id: ComponentId,
+    ) -> Result<UpdateResult, SimulationError> {
+        let component = self
+            .components
+            .get_mut(&component_id)
+            .ok_or(SimulationError::ComponentNotFound(component_id))?;
+        for (pin_name, node_id) in schedule.inputs(component_id) {
+            if let (Some(pin), Some(signal)) = (
+                component.get_pin_mut(pin_name),
+                self.netlist.get_node_signal(*node_id),
+            ) {
+                let _ = pin.set_signal(self.pin_faults.through(component_id, pin_name, signal));
+            }
+        }
+        self.stats.components_updated += 1;
+        let mut result = component.update(time);
+        self.pin_faults
+            .apply_outputs(component_id, &mut result.outputs);
+        Ok(result)
+    }
+
     /// Process a clock tick event
     fn process_clock_tick(&mut self, time: Timestamp) -> Result<(), SimulationError> {
-        self.prev_clock_state = self.clock_state;
-        self.clock_state = match self.clock_state {
-            Value::Low => Value::High,
-            Value::High => Value::Low,
-            _ => Value::Low, // Default to low for unknown states
-        };
-
-        let edge = if self.prev_clock_state == Value::Low && self.clock_state == Value::High {
-            ClockEdge::Rising
-        } else if self.prev_clock_state == Value::High && self.clock_state == Value::Low {
-            ClockEdge::Falling
-        } else {
-            return Ok(()); // No edge detected
-        };
-
         if self.config.debug {
-            println!("Clock {:?} edge at time {}", edge, time);
+            println!("Clock tick at time {}", time);
         }
 
-        // Notify all sequential components of the clock edge
         let component_ids: Vec<_> = self.components.keys().copied().collect();
         for component_id in component_ids {
             if let Some(component) = self.components.get_mut(&component_id) {
-                if component.is_sequential() {
-                    let result = component.clock_edge(edge, time);
-                    self.handle_update_result(time, component_id, result)?;
-                }
+                let result = component.tick(time);
+                self.handle_update_result(time, component_id, result)?;
             }
         }
 
@@ -401,6 +1531,12 @@ impl Simulation {
         time: Timestamp,
         component_id: ComponentId,
     ) -> Result<(), SimulationError> {
+        // A component evaluated over and over by one propagation sits on a
+        // loop that does not settle
+        if self.propagations.evaluate(component_id) > self.config.oscillation_threshold {
+            return self.handle_oscillation(time);
+        }
+
         if let Some(component) = self.components.get_mut(&component_id) {
             // Update component inputs from connected nodes
             let connections = self.netlist.get_component_connections(component_id);
@@ -409,7 +1545,11 @@ impl Simulation {
                     if pin.is_input() {
                         if let Some(node_signal) = self.netlist.get_node_signal(connection.node_id)
                         {
-                            let _ = pin.set_signal(node_signal.clone());
+                            let _ = pin.set_signal(self.pin_faults.through(
+                                component_id,
+                                &connection.pin_name,
+                                node_signal,
+                            ));
                         }
                     }
                 }
@@ -417,6 +1557,10 @@ impl Simulation {
 
             // Update the component
             let result = component.update(time);
+            let switch = component.switch_state();
+            if let Some(switch) = switch {
+                self.apply_switch(time, component_id, &switch)?;
+            }
             self.handle_update_result(time, component_id, result)?;
             self.stats.components_updated += 1;
         } else {
@@ -436,53 +1580,310 @@ impl Simulation {
         for component_id in component_ids {
             if let Some(component) = self.components.get_mut(&component_id) {
                 component.reset();
-                // Schedule initial update after reset
-                self.event_queue
-                    .schedule_component_update(time.add_delay(1), component_id);
+                // Evaluate every component once with its reset state
+                let event_id = self
+                    .event_queue
+                    .schedule_component_update(time, component_id);
+                self.propagations.follow(event_id);
             }
         }
 
         Ok(())
     }
 
+    /// Report the oscillation of the current propagation and stop it
+    ///
+    /// Depending on the configuration the error is returned, or the nodes on
+    /// the loop are held at `Value::Error` and the simulation carries on.
+    fn handle_oscillation(&mut self, time: Timestamp) -> Result<(), SimulationError> {
+        let oscillation = self.find_oscillation(time);
+        self.propagations.stop_current();
+        self.oscillations.push(oscillation.clone());
+
+        if self.config.debug {
+            println!(
+                "Oscillation at time {} through components {:?}",
+                time, oscillation.components
+            );
+        }
+
+        match self.config.oscillation_action {
+            OscillationAction::Stop => Err(SimulationError::OscillationDetected(oscillation)),
+            OscillationAction::ForceError => {
+                // The forced values start a propagation of their own
+                self.propagations.start();
+                for &node_id in &oscillation.nodes {
+                    let width = self
+                        .netlist
+                        .get_node(node_id)
+                        .map(|node| node.width)
+                        .ok_or(SimulationError::NodeNotFound(node_id))?;
+                    self.process_signal_change(
+                        time,
+                        node_id,
+                        Signal::error(width),
+                        OSCILLATION_DRIVER,
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Find the loop behind an oscillation of the current propagation
+    ///
+    /// Components re-evaluated at least half as often as the threshold are
+    /// candidates; of those, the ones that feed back into themselves through
+    /// other candidates form the loop. Components merely downstream of the
+    /// loop are left out.
+    fn find_oscillation(&self, time: Timestamp) -> Oscillation {
+        let threshold = self.config.oscillation_threshold;
+        let candidates: HashSet<ComponentId> = self
+            .propagations
+            .current_evaluations()
+            .map(|evaluations| {
+                evaluations
+                    .iter()
+                    .filter(|(_, &count)| count * 2 >= threshold)
+                    .map(|(&component_id, _)| component_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Edges from a candidate to the candidates reading one of its outputs
+        let mut edges: HashMap<ComponentId, Vec<(NodeId, ComponentId)>> = HashMap::new();
+        for &component_id in &candidates {
+            for node_id in self.pin_nodes(component_id, true) {
+                for &reader in &candidates {
+                    if self.pin_nodes(reader, false).contains(&node_id) {
+                        edges
+                            .entry(component_id)
+                            .or_default()
+                            .push((node_id, reader));
+                    }
+                }
+            }
+        }
+
+        // Candidates reachable from each candidate
+        let reachable: HashMap<ComponentId, HashSet<ComponentId>> = candidates
+            .iter()
+            .map(|&start| {
+                let mut seen = HashSet::new();
+                let mut stack = vec![start];
+                while let Some(component_id) = stack.pop() {
+                    for &(_, next) in edges.get(&component_id).into_iter().flatten() {
+                        if seen.insert(next) {
+                            stack.push(next);
+                        }
+                    }
+                }
+                (start, seen)
+            })
+            .collect();
+
+        let mut components: Vec<ComponentId> = candidates
+            .iter()
+            .copied()
+            .filter(|component_id| reachable[component_id].contains(component_id))
+            .collect();
+        let mut nodes: Vec<NodeId> = edges
+            .iter()
+            .flat_map(|(&from, targets)| targets.iter().map(move |&(node, to)| (from, node, to)))
+            .filter(|&(from, _, to)| {
+                reachable[&from].contains(&from) && reachable[&to].contains(&from)
+            })
+            .map(|(_, node, _)| node)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        // Without a recognisable loop, report every candidate and its outputs
+        if components.is_empty() {
+            components = candidates.into_iter().collect();
+            nodes = components
+                .iter()
+                .flat_map(|&component_id| self.pin_nodes(component_id, true))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+        }
+
+        components.sort();
+        nodes.sort();
+        Oscillation {
+            time,
+            nodes,
+            components,
+        }
+    }
+
+    /// Nodes a component drives (`outputs`) or reads, including every node
+    /// sharing bits with them through splitters
+    fn pin_nodes(&self, component_id: ComponentId, outputs: bool) -> HashSet<NodeId> {
+        let Some(component) = self.components.get(&component_id) else {
+            return HashSet::new();
+        };
+        let mut nodes = HashSet::new();
+        for connection in self.netlist.get_component_connections(component_id) {
+            let matches = component.get_pin(&connection.pin_name).is_some_and(|pin| {
+                if outputs {
+                    pin.is_output()
+                } else {
+                    pin.is_input()
+                }
+            });
+            if !matches {
+                continue;
+            }
+            nodes.insert(connection.node_id);
+            let width = self
+                .netlist
+                .get_node(connection.node_id)
+                .map_or(0, |node| node.width.as_u32());
+            for bit in 0..width {
+                for (node_id, _) in self.netlist.get_thread(connection.node_id, bit) {
+                    nodes.insert(node_id);
+                }
+            }
+        }
+        nodes
+    }
+
+    /// Open or close the switch of a transistor or transmission gate
+    ///
+    /// The nets on either side join or split as soon as the component
+    /// updates; a switch with an unconnected pin stays open.
+    fn apply_switch(
+        &mut self,
+        time: Timestamp,
+        component_id: ComponentId,
+        switch: &SwitchState,
+    ) -> Result<(), SimulationError> {
+        let nodes = self.switch_nodes(component_id, switch);
+        let changed_nodes = self
+            .netlist
+            .set_switch(component_id, nodes)
+            .map_err(|e| SimulationError::NetlistError(e.to_string()))?;
+        if changed_nodes.is_empty() {
+            return Ok(());
+        }
+        let changed_nodes = self.nodes_changed(time, changed_nodes)?;
+        self.schedule_readers(time, &changed_nodes);
+        Ok(())
+    }
+
+    /// Get the nodes a switch joins, or `None` while it is open
+    fn switch_nodes(
+        &self,
+        component_id: ComponentId,
+        switch: &SwitchState,
+    ) -> Option<(NodeId, NodeId)> {
+        if !switch.closed {
+            return None;
+        }
+        let node_a = self.netlist.get_pin_node(component_id, &switch.a)?;
+        let node_b = self.netlist.get_pin_node(component_id, &switch.b)?;
+        Some((node_a, node_b))
+    }
+
     /// Handle the result of a component update
     fn handle_update_result(
         &mut self,
         current_time: Timestamp,
         component_id: ComponentId,
-        result: UpdateResult,
+        mut result: UpdateResult,
     ) -> Result<(), SimulationError> {
+        self.pin_faults
+            .apply_outputs(component_id, &mut result.outputs);
+
+        // A wake-up starts a propagation of its own
+        if let Some(wakeup) = result.wakeup {
+            self.event_queue
+                .schedule_component_update(current_time.add_delay(wakeup), component_id);
+        }
+
         if !result.state_changed {
             return Ok(());
         }
 
         // Process output signals
+        let event_time = current_time.add_delay(self.delay_of(component_id, result.delay));
         for (pin_name, signal) in result.outputs {
             // Find the node connected to this output pin
-            if let Some(node_id) = self.netlist.get_pin_node(component_id, &pin_name) {
-                // Check if this would actually change the signal at the node
-                let should_propagate =
-                    if let Some(current_signal) = self.netlist.get_node_signal(node_id) {
-                        current_signal != &signal
-                    } else {
-                        true
-                    };
-
-                if should_propagate {
-                    // Schedule signal change with appropriate delay
-                    let event_time = current_time.add_delay(result.delay);
-                    self.event_queue.schedule_signal_change(
-                        event_time,
-                        node_id,
-                        signal,
-                        component_id,
-                    );
+            let Some(node_id) = self.netlist.get_pin_node(component_id, &pin_name) else {
+                continue;
+            };
+            let key = (component_id, node_id);
+            if self.config.delay_model == DelayModel::Inertial {
+                // A different change overtakes the one still in flight
+                if let Some((event_id, pending)) = self.pending_outputs.get(&key) {
+                    if pending != &signal {
+                        self.event_queue.cancel(*event_id);
+                        self.propagations.cancel(*event_id);
+                        self.pending_outputs.remove(&key);
+                    }
                 }
             }
+
+            // Only propagate if this changes what the component will drive
+            let latest = match self.pending_outputs.get(&key) {
+                Some((_, pending)) => Some(pending),
+                None => self.netlist.get_node_driver(node_id, component_id),
+            };
+            if latest == Some(&signal) {
+                continue;
+            }
+            let event_id = self.event_queue.schedule_signal_change(
+                event_time,
+                node_id,
+                signal.clone(),
+                component_id,
+            );
+            self.propagations.follow(event_id);
+            self.pending_outputs.insert(key, (event_id, signal));
         }
 
         Ok(())
     }
+
+    /// Note that an output change has reached its node
+    fn output_arrived(
+        &mut self,
+        event_id: EventId,
+        component_id: ComponentId,
+        node_id: NodeId,
+    ) -> &mut Self {
+        let key = (component_id, node_id);
+        if self
+            .pending_outputs
+            .get(&key)
+            .is_some_and(|(pending, _)| *pending == event_id)
+        {
+            self.pending_outputs.remove(&key);
+        }
+        self
+    }
+
+    /// Rebuild the output changes in flight from the event queue
+    fn track_pending_outputs(&mut self) {
+        let mut events = self.event_queue.get_all_events();
+        events.sort_by_key(|event| (event.time, event.serial));
+        self.pending_outputs.clear();
+        for event in events {
+            if let EventType::SignalChange {
+                node_id,
+                new_signal,
+                source_component,
+            } = &event.event_type
+            {
+                self.pending_outputs.insert(
+                    (*source_component, *node_id),
+                    (event.id, new_signal.clone()),
+                );
+            }
+        }
+    }
 }
 
 impl Default for Simulation {
@@ -0,0 +1,127 @@
+//! Simulation snapshots and the history used for stepping backwards.
+//!
+//! A snapshot captures everything needed to resume a simulation from a given
+//! point: node values and their drivers, the pending events, statistics and
+//! the internal state of every stateful component. Snapshots serialize with
+//! serde, so they can be written to disk and restored into a simulation of
+//! the same circuit later on.
+
+use crate::comp::ComponentId;
+use crate::event::EventQueue;
+use crate::netlist::NodeId;
+use crate::signal::{Signal, Timestamp};
+use crate::simulation::{Oscillation, SimulationStats};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Saved value of a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeState {
+    /// Resolved signal on the node
+    pub signal: Signal,
+    /// Contribution of every component driving the node
+    pub drivers: BTreeMap<ComponentId, Signal>,
+}
+
+/// Saved state of a component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentState {
+    /// Signals last seen on the component's pins
+    pub pins: BTreeMap<String, Signal>,
+    /// Internal state reported by `Component::save_state`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal: Option<serde_json::Value>,
+}
+
+/// Complete state of a simulation at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    pub(crate) nodes: BTreeMap<NodeId, NodeState>,
+    pub(crate) components: BTreeMap<ComponentId, ComponentState>,
+    pub(crate) events: EventQueue,
+    pub(crate) stats: SimulationStats,
+    pub(crate) oscillations: Vec<Oscillation>,
+}
+
+impl SimulationSnapshot {
+    /// Simulation time at which the snapshot was taken
+    pub fn time(&self) -> Timestamp {
+        self.events.current_time()
+    }
+
+    /// Number of events processed when the snapshot was taken
+    pub fn events_processed(&self) -> usize {
+        self.stats.events_processed
+    }
+
+    /// Saved signal of a node
+    pub fn node_signal(&self, node_id: NodeId) -> Option<&Signal> {
+        self.nodes.get(&node_id).map(|node| &node.signal)
+    }
+
+    /// Saved state of a node
+    pub fn node(&self, node_id: NodeId) -> Option<&NodeState> {
+        self.nodes.get(&node_id)
+    }
+
+    /// Saved state of a component
+    pub fn component(&self, component_id: ComponentId) -> Option<&ComponentState> {
+        self.components.get(&component_id)
+    }
+}
+
+/// Bounded ring of snapshots, one taken before each processed event
+///
+/// Once full, the oldest snapshot is dropped to make room for a new one.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct History {
+    capacity: usize,
+    snapshots: VecDeque<SimulationSnapshot>,
+}
+
+impl History {
+    /// Create a history keeping at most `capacity` snapshots
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            snapshots: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Whether snapshots are being recorded at all
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Number of snapshots currently kept
+    pub(crate) fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Record a snapshot, dropping the oldest one if the ring is full
+    pub(crate) fn push(&mut self, snapshot: SimulationSnapshot) {
+        if !self.is_enabled() {
+            return;
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Snapshots from the most recent to the oldest
+    pub(crate) fn iter_recent(&self) -> impl Iterator<Item = &SimulationSnapshot> {
+        self.snapshots.iter().rev()
+    }
+
+    /// Remove the `count` most recent snapshots, returning the oldest of them
+    pub(crate) fn pop(&mut self, count: usize) -> Option<SimulationSnapshot> {
+        let keep = self.snapshots.len().checked_sub(count)?;
+        self.snapshots.drain(keep..).next()
+    }
+
+    /// Forget every snapshot
+    pub(crate) fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
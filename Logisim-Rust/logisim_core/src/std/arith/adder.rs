@@ -11,15 +11,21 @@
 //!
 //! Rust port of `com.cburch.logisim.std.arith.Adder`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Unique identifier for the Adder component
+/// Do NOT change as it will prevent project files from loading.
+pub const ADDER_ID: &str = "Adder";
+
 /// Multi-bit Adder component
-/// 
-/// Performs binary addition on two multi-bit inputs with carry-in and carry-out.
-/// Supports configurable bit width and handles overflow correctly.
+///
+/// Adds `A`, `B` and `Carry_In` into `Sum` and `Carry_Out`. A floating or
+/// unknown carry in counts as zero. As in Java, an unknown or error bit in
+/// an operand makes that bit of the sum and every bit above it unknown or
+/// error, since the carry into them is no longer known.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Adder {
     id: ComponentId,
@@ -32,124 +38,83 @@ impl Adder {
     pub fn new(id: ComponentId) -> Self {
         Self::new_with_width(id, BusWidth(8))
     }
-    
+
     /// Create a new adder with specified bit width
     pub fn new_with_width(id: ComponentId, bit_width: BusWidth) -> Self {
         let mut pins = HashMap::new();
-        
+
         // Input pins
         pins.insert("A".to_string(), Pin::new_input("A", bit_width));
         pins.insert("B".to_string(), Pin::new_input("B", bit_width));
-        pins.insert("Carry_In".to_string(), Pin::new_input("Carry_In", BusWidth(1)));
-        
+        pins.insert(
+            "Carry_In".to_string(),
+            Pin::new_input("Carry_In", BusWidth(1)),
+        );
+
         // Output pins
         pins.insert("Sum".to_string(), Pin::new_output("Sum", bit_width));
-        pins.insert("Carry_Out".to_string(), Pin::new_output("Carry_Out", BusWidth(1)));
+        pins.insert(
+            "Carry_Out".to_string(),
+            Pin::new_output("Carry_Out", BusWidth(1)),
+        );
 
-        Adder { id, pins, bit_width }
+        Adder {
+            id,
+            pins,
+            bit_width,
+        }
     }
-    
-    /// Compute the sum with carry propagation
-    /// Returns (sum, carry_out)
-    fn compute_sum(bit_width: BusWidth, value_a: &Value, value_b: &Value, carry_in: &Value) -> (Value, Value) {
-        let width = bit_width.0;
-        
-        // Handle special cases for carry_in
-        let carry_in = match carry_in {
+
+    /// Compute the sum with carry propagation, ported from `Adder.computeSum`
+    ///
+    /// Returns (sum, carry_out).
+    pub fn compute_sum(
+        bit_width: BusWidth,
+        a: &Signal,
+        b: &Signal,
+        carry_in: Value,
+    ) -> (Signal, Value) {
+        let mut carry = match carry_in {
             Value::Unknown | Value::HighZ => Value::Low,
-            _ => carry_in.clone(),
+            other => other,
         };
-        
-        if value_a.is_fully_defined() && value_b.is_fully_defined() && carry_in.is_fully_defined() {
-            // Fast path for fully defined values
-            if width == 64 {
-                // Special handling for 64-bit to avoid overflow
-                let a_val = value_a.to_long_value();
-                let b_val = value_b.to_long_value();
-                let c_val = carry_in.to_long_value();
-                
-                let mask = !(1u64 << 63);
-                let a_sign = (a_val as u64) >> 63 != 0;
-                let b_sign = (b_val as u64) >> 63 != 0;
-                
-                // Calculate carry out from sign bits
-                let masked_a = (a_val as u64) & mask;
-                let masked_b = (b_val as u64) & mask;
-                let c_in_sign = ((masked_a + masked_b + c_val as u64) >> 63) != 0;
-                
-                let carry_out = (a_sign && b_sign) || (a_sign && c_in_sign) || (b_sign && c_in_sign);
-                
-                let sum = a_val.wrapping_add(b_val).wrapping_add(c_val);
-                
-                (
-                    Value::from_long(sum, bit_width),
-                    if carry_out { Value::High } else { Value::Low }
-                )
+        let mut sum = Vec::with_capacity(bit_width.0 as usize);
+        for i in 0..bit_width.0 {
+            let bit_a = a.get_bit(i).unwrap_or(Value::Unknown);
+            let bit_b = b.get_bit(i).unwrap_or(Value::Unknown);
+            if carry == Value::Error || bit_a == Value::Error || bit_b == Value::Error {
+                carry = Value::Error;
+                sum.push(Value::Error);
+            } else if carry == Value::Unknown || !bit_a.is_definite() || !bit_b.is_definite() {
+                carry = Value::Unknown;
+                sum.push(Value::Unknown);
             } else {
-                // Standard addition for widths < 64
-                let sum = value_a.to_long_value() + value_b.to_long_value() + carry_in.to_long_value();
-                let carry_out = ((sum >> width) & 1) != 0;
-                
-                (
-                    Value::from_long(sum, bit_width),
-                    if carry_out { Value::High } else { Value::Low }
-                )
-            }
-        } else {
-            // Bit-by-bit computation for undefined values
-            let mut result_bits = vec![Value::Error; width];
-            let mut carry = carry_in.clone();
-            
-            for i in 0..width {
-                if matches!(carry, Value::Error) {
-                    result_bits[i] = Value::Error;
-                } else if matches!(carry, Value::Unknown) {
-                    result_bits[i] = Value::Unknown;
-                } else {
-                    let bit_a = value_a.get_bit(i);
-                    let bit_b = value_b.get_bit(i);
-                    
-                    if matches!(bit_a, Value::Error) || matches!(bit_b, Value::Error) {
-                        result_bits[i] = Value::Error;
-                        carry = Value::Error;
-                    } else if matches!(bit_a, Value::Unknown) || matches!(bit_b, Value::Unknown) {
-                        result_bits[i] = Value::Unknown;
-                        carry = Value::Unknown;
-                    } else {
-                        // Full adder logic for this bit
-                        let a_bit = matches!(bit_a, Value::High);
-                        let b_bit = matches!(bit_b, Value::High);
-                        let c_bit = matches!(carry, Value::High);
-                        
-                        let sum_bit = a_bit ^ b_bit ^ c_bit;
-                        let carry_bit = (a_bit && b_bit) || (a_bit && c_bit) || (b_bit && c_bit);
-                        
-                        result_bits[i] = if sum_bit { Value::High } else { Value::Low };
-                        carry = if carry_bit { Value::High } else { Value::Low };
-                    }
-                }
+                // Full adder logic for this bit
+                let (a, b, c) = (
+                    bit_a == Value::High,
+                    bit_b == Value::High,
+                    carry == Value::High,
+                );
+                sum.push(Value::from_bool(a ^ b ^ c));
+                carry = Value::from_bool((a && b) || (c && (a || b)));
             }
-            
-            (Value::from_bits(&result_bits), carry)
         }
+        (Signal::new_bus(sum), carry)
     }
-    
+
     /// Get the current bit width
     pub fn bit_width(&self) -> BusWidth {
         self.bit_width
     }
-    
+
     /// Set the bit width (updates pin widths accordingly)
     pub fn set_bit_width(&mut self, width: BusWidth) {
         self.bit_width = width;
-        if let Some(pin) = self.pins.get_mut("A") {
-            pin.set_width(width);
-        }
-        if let Some(pin) = self.pins.get_mut("B") {
-            pin.set_width(width);
-        }
-        if let Some(pin) = self.pins.get_mut("Sum") {
-            pin.set_width(width);
+        for name in ["A", "B", "Sum"] {
+            if let Some(pin) = self.pins.get_mut(name) {
+                pin.width = width;
+                pin.signal = Signal::unknown(width);
+            }
         }
     }
 }
@@ -160,7 +125,7 @@ impl Component for Adder {
     }
 
     fn name(&self) -> &str {
-        "Adder"
+        ADDER_ID
     }
 
     fn pins(&self) -> &HashMap<String, Pin> {
@@ -172,53 +137,41 @@ impl Component for Adder {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get input values
-        let value_a = self.pins.get("A").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        let value_b = self.pins.get("B").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        let carry_in = self.pins.get("Carry_In").map(|p| p.signal().value()).unwrap_or(Value::Low);
-        
-        // Compute sum and carry out
-        let (sum, carry_out) = Self::compute_sum(self.bit_width, &value_a, &value_b, &carry_in);
-        
-        // Update output pins
-        let mut changed = false;
-        if let Some(sum_pin) = self.pins.get_mut("Sum") {
-            if sum_pin.signal().value() != &sum {
-                let _ = sum_pin.set_signal(Signal::new(sum, _current_time));
-                changed = true;
-            }
-        }
-        
-        if let Some(carry_pin) = self.pins.get_mut("Carry_Out") {
-            if carry_pin.signal().value() != &carry_out {
-                let _ = carry_pin.set_signal(Signal::new(carry_out, _current_time));
-                changed = true;
-            }
-        }
-        
-        if changed {
-            UpdateResult::changed()
-        } else {
-            UpdateResult::no_change()
-        }
+        let carry_in = self.pins["Carry_In"]
+            .signal
+            .as_single()
+            .unwrap_or(Value::Unknown);
+        let (sum, carry_out) = Self::compute_sum(
+            self.bit_width,
+            &self.pins["A"].signal,
+            &self.pins["B"].signal,
+            carry_in,
+        );
+
+        let mut result = UpdateResult::new();
+        result.add_output("Sum".to_string(), sum);
+        result.add_output("Carry_Out".to_string(), Signal::new_single(carry_out));
+        result.set_delay(self.propagation_delay());
+        result
     }
 
     fn reset(&mut self) {
-        // Reset all pins to their default states
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
-}
 
-impl Propagator for Adder {
-    fn propagate(&mut self, current_time: Timestamp) {
-        // Calculate propagation delay based on bit width
-        let delay = (self.bit_width.0 + 2) * 1; // PER_DELAY = 1 from Java
-        let propagation_time = current_time + delay as u64;
-        
-        // Perform the update at the calculated time
-        self.update(propagation_time);
+    fn propagation_delay(&self) -> u64 {
+        // (width + 2) * PER_DELAY, with PER_DELAY = 1 as in Java
+        self.bit_width.0 as u64 + 2
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Arithmetic",
+            ADDER_ID,
+            vec![("width", self.bit_width.0.to_string())],
+        ))
     }
 }
 
@@ -226,6 +179,17 @@ impl Propagator for Adder {
 mod tests {
     use super::*;
 
+    fn add(width: u32, a: u64, b: u64, carry_in: Value) -> (Option<u64>, Value) {
+        let width = BusWidth(width);
+        let (sum, carry) = Adder::compute_sum(
+            width,
+            &Signal::from_u64(a, width),
+            &Signal::from_u64(b, width),
+            carry_in,
+        );
+        (sum.to_u64(), carry)
+    }
+
     #[test]
     fn test_adder_creation() {
         let adder = Adder::new(ComponentId(1));
@@ -233,70 +197,68 @@ mod tests {
         assert_eq!(adder.name(), "Adder");
         assert_eq!(adder.bit_width(), BusWidth(8));
         assert_eq!(adder.pins().len(), 5);
+        assert_eq!(adder.propagation_delay(), 10);
     }
 
     #[test]
-    fn test_adder_with_custom_width() {
-        let adder = Adder::new_with_width(ComponentId(1), BusWidth(16));
-        assert_eq!(adder.bit_width(), BusWidth(16));
+    fn test_compute_sum() {
+        assert_eq!(add(8, 5, 3, Value::Low), (Some(8), Value::Low));
+        assert_eq!(add(8, 255, 1, Value::Low), (Some(0), Value::High));
+        assert_eq!(add(8, 5, 3, Value::High), (Some(9), Value::Low));
+        // A floating carry in counts as zero
+        assert_eq!(add(8, 5, 3, Value::HighZ), (Some(8), Value::Low));
+        assert_eq!(add(64, u64::MAX, 1, Value::Low), (Some(0), Value::High));
     }
 
     #[test]
-    fn test_compute_sum_simple() {
-        let a = Value::from_long(5, BusWidth(8));
-        let b = Value::from_long(3, BusWidth(8));
-        let cin = Value::Low;
-        
-        let (sum, cout) = Adder::compute_sum(BusWidth(8), &a, &b, &cin);
-        
-        assert_eq!(sum.to_long_value(), 8);
-        assert_eq!(cout, Value::Low);
-    }
+    fn test_wide_and_undefined_operands() {
+        let width = BusWidth(80);
+        let mut a = Signal::all_low(width);
+        a.set_bit(70, Value::High).unwrap();
+        let (sum, carry) = Adder::compute_sum(width, &a, &a, Value::Low);
+        assert_eq!(sum.get_bit(71), Some(Value::High));
+        assert_eq!(sum.get_bit(70), Some(Value::Low));
+        assert_eq!(carry, Value::Low);
 
-    #[test]
-    fn test_compute_sum_with_carry() {
-        let a = Value::from_long(255, BusWidth(8));  // Max value for 8-bit
-        let b = Value::from_long(1, BusWidth(8));
-        let cin = Value::Low;
-        
-        let (sum, cout) = Adder::compute_sum(BusWidth(8), &a, &b, &cin);
-        
-        assert_eq!(sum.to_long_value(), 0);  // Overflow wraps to 0
-        assert_eq!(cout, Value::High);       // Carry out is set
+        // Bits below the unknown one are still known
+        let a = Signal::new_bus(vec![Value::High, Value::Unknown, Value::Low, Value::Low]);
+        let b = Signal::from_u64(1, BusWidth(4));
+        let (sum, carry) = Adder::compute_sum(BusWidth(4), &a, &b, Value::Low);
+        assert_eq!(
+            sum.bits(),
+            &[Value::Low, Value::Unknown, Value::Unknown, Value::Unknown]
+        );
+        assert_eq!(carry, Value::Unknown);
+
+        let a = Signal::new_bus(vec![Value::Error, Value::Low]);
+        let (sum, carry) = Adder::compute_sum(BusWidth(2), &a, &a, Value::Low);
+        assert_eq!(sum.bits(), &[Value::Error, Value::Error]);
+        assert_eq!(carry, Value::Error);
     }
 
     #[test]
-    fn test_compute_sum_with_carry_in() {
-        let a = Value::from_long(5, BusWidth(8));
-        let b = Value::from_long(3, BusWidth(8));
-        let cin = Value::High;
-        
-        let (sum, cout) = Adder::compute_sum(BusWidth(8), &a, &b, &cin);
-        
-        assert_eq!(sum.to_long_value(), 9);  // 5 + 3 + 1 = 9
-        assert_eq!(cout, Value::Low);
+    fn test_adder_update() {
+        let mut adder = Adder::new_with_width(ComponentId(1), BusWidth(4));
+        adder.reset();
+        for (name, signal) in [
+            ("A", Signal::from_u64(9, BusWidth(4))),
+            ("B", Signal::from_u64(8, BusWidth(4))),
+            ("Carry_In", Signal::new_single(Value::High)),
+        ] {
+            adder.get_pin_mut(name).unwrap().set_signal(signal).unwrap();
+        }
+        let result = adder.update(Timestamp(0));
+        assert_eq!(result.outputs["Sum"].to_u64(), Some(2));
+        assert_eq!(result.outputs["Carry_Out"].as_single(), Some(Value::High));
     }
 
     #[test]
     fn test_bit_width_change() {
         let mut adder = Adder::new(ComponentId(1));
-        
         adder.set_bit_width(BusWidth(16));
         assert_eq!(adder.bit_width(), BusWidth(16));
-        
-        // Check that pin widths were updated
-        assert_eq!(adder.pins().get("A").unwrap().width(), BusWidth(16));
-        assert_eq!(adder.pins().get("B").unwrap().width(), BusWidth(16));
-        assert_eq!(adder.pins().get("Sum").unwrap().width(), BusWidth(16));
-        assert_eq!(adder.pins().get("Carry_In").unwrap().width(), BusWidth(1));
-        assert_eq!(adder.pins().get("Carry_Out").unwrap().width(), BusWidth(1));
+        assert_eq!(adder.pins()["A"].width, BusWidth(16));
+        assert_eq!(adder.pins()["Sum"].width, BusWidth(16));
+        assert_eq!(adder.pins()["Carry_In"].width, BusWidth(1));
     }
-
-    #[test]
-    fn test_component_reset() {
-        let mut adder = Adder::new(ComponentId(1));
-        adder.reset();
-        // Should not panic and should reset all pins
-        assert_eq!(adder.pins().len(), 5);
-    }
-}
\ No newline at end of file
+}
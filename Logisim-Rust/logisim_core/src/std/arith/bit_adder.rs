@@ -7,51 +7,187 @@
  * This is free software released under GNU GPLv3 license
  */
 
-//! BitAdder Implementation (Placeholder)
+//! Bit Adder Implementation
+//!
+//! Rust port of `com.cburch.logisim.std.arith.BitAdder`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Unique identifier for the Bit Adder component
+/// Do NOT change as it will prevent project files from loading.
+pub const BIT_ADDER_ID: &str = "BitAdder";
+
+/// Bit adder component, counting the high bits of its inputs
+///
+/// Drives the number of high bits over all of `Input_0` … `Input_{n-1}` on
+/// `Output`. Output bits that depend on undefined input bits are errors.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BitAdder {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    bit_width: BusWidth,
+    inputs: u32,
 }
 
 impl BitAdder {
+    /// Create a new bit adder with one 8-bit input (default)
     pub fn new(id: ComponentId) -> Self {
+        Self::new_with_inputs(id, BusWidth(8), 1)
+    }
+
+    /// Create a new bit adder with the given number of inputs of the given
+    /// width
+    pub fn new_with_inputs(id: ComponentId, bit_width: BusWidth, inputs: u32) -> Self {
         let mut pins = HashMap::new();
-        pins.insert("A".to_string(), Pin::new_input("A", BusWidth(1)));
-        pins.insert("B".to_string(), Pin::new_input("B", BusWidth(1)));
-        pins.insert("CarryIn".to_string(), Pin::new_input("CarryIn", BusWidth(1)));
-        pins.insert("Sum".to_string(), Pin::new_output("Sum", BusWidth(1)));
-        pins.insert("CarryOut".to_string(), Pin::new_output("CarryOut", BusWidth(1)));
-        
-        BitAdder { id, pins }
+        for i in 0..inputs {
+            let name = format!("Input_{}", i);
+            pins.insert(name.clone(), Pin::new_input(&name, bit_width));
+        }
+        let output_width = Self::output_width(bit_width, inputs);
+        pins.insert(
+            "Output".to_string(),
+            Pin::new_output("Output", output_width),
+        );
+
+        BitAdder {
+            id,
+            pins,
+            bit_width,
+            inputs,
+        }
+    }
+
+    /// Width of the count, the fewest bits that hold the number of input
+    /// bits
+    pub fn output_width(bit_width: BusWidth, inputs: u32) -> BusWidth {
+        let max_bits = bit_width.0 as u64 * inputs as u64;
+        let mut bits = 1;
+        while (1u64 << bits) <= max_bits {
+            bits += 1;
+        }
+        BusWidth(bits)
+    }
+
+    /// Get the width of each input
+    pub fn bit_width(&self) -> BusWidth {
+        self.bit_width
+    }
+
+    /// Get the number of inputs
+    pub fn inputs(&self) -> u32 {
+        self.inputs
     }
 }
 
 impl Component for BitAdder {
-    fn id(&self) -> ComponentId { self.id }
-    fn name(&self) -> &str { "BitAdder" }
-    fn pins(&self) -> &HashMap<String, Pin> { &self.pins }
-    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> { &mut self.pins }
-    
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        BIT_ADDER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        UpdateResult::NoChange // Placeholder
+        // Bits that are definitely high, and bits that are not definitely low
+        let (mut min_count, mut max_count) = (0u64, 0u64);
+        for i in 0..self.inputs {
+            for &bit in self.pins[&format!("Input_{}", i)].signal.bits() {
+                min_count += (bit == Value::High) as u64;
+                max_count += (bit != Value::Low) as u64;
+            }
+        }
+        // Output bits that differ between the possible counts
+        let unknown_mask =
+            (min_count + 1..=max_count).fold(0, |mask, count| mask | (min_count ^ count));
+
+        let output_width = Self::output_width(self.bit_width, self.inputs);
+        let output = (0..output_width.0)
+            .map(|i| {
+                if (unknown_mask >> i) & 1 == 1 {
+                    Value::Error
+                } else {
+                    Value::from_bool((min_count >> i) & 1 == 1)
+                }
+            })
+            .collect();
+
+        let mut result = UpdateResult::new();
+        result.add_output("Output".to_string(), Signal::new_bus(output));
+        result.set_delay(self.propagation_delay());
+        result
     }
-    
+
     fn reset(&mut self) {
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
+
+    fn propagation_delay(&self) -> u64 {
+        // Output width * PER_DELAY, as in Java
+        Self::output_width(self.bit_width, self.inputs).0 as u64
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Arithmetic",
+            BIT_ADDER_ID,
+            vec![
+                ("width", self.bit_width.0.to_string()),
+                ("inputs", self.inputs.to_string()),
+            ],
+        ))
+    }
 }
 
-impl Propagator for BitAdder {
-    fn propagate(&mut self, current_time: Timestamp) {
-        self.update(current_time + 1);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_adder_counts_high_bits() {
+        let mut adder = BitAdder::new_with_inputs(ComponentId(1), BusWidth(4), 2);
+        assert_eq!(adder.get_pin("Output").unwrap().width, BusWidth(4));
+        adder.reset();
+        for (name, value) in [("Input_0", 0b1011), ("Input_1", 0b0110)] {
+            adder
+                .get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::from_u64(value, BusWidth(4)))
+                .unwrap();
+        }
+        let result = adder.update(Timestamp(0));
+        assert_eq!(result.outputs["Output"].to_u64(), Some(5));
+
+        // 4 or 5 high bits: only bit 0 is uncertain
+        use Value::{Error, High, Low, Unknown};
+        let input = Signal::new_bus(vec![Low, High, Unknown, Low]);
+        adder
+            .get_pin_mut("Input_1")
+            .unwrap()
+            .set_signal(input)
+            .unwrap();
+        let result = adder.update(Timestamp(0));
+        assert_eq!(result.outputs["Output"].bits(), &[Error, Low, High, Low]);
+    }
+
+    #[test]
+    fn test_output_width() {
+        assert_eq!(BitAdder::output_width(BusWidth(1), 1), BusWidth(1));
+        assert_eq!(BitAdder::output_width(BusWidth(8), 1), BusWidth(4));
+        assert_eq!(BitAdder::output_width(BusWidth(7), 1), BusWidth(3));
+        assert_eq!(BitAdder::output_width(BusWidth(32), 64), BusWidth(12));
     }
-}
\ No newline at end of file
+}
@@ -7,48 +7,243 @@
  * This is free software released under GNU GPLv3 license
  */
 
-//! BitFinder Implementation (Placeholder)
+//! Bit Finder Implementation
+//!
+//! Rust port of `com.cburch.logisim.std.arith.BitFinder`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
+/// Unique identifier for the Bit Finder component
+/// Do NOT change as it will prevent project files from loading.
+pub const BIT_FINDER_ID: &str = "BitFinder";
+
+/// Which bit a bit finder looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BitFinderType {
+    /// The lowest high bit
+    #[default]
+    LowOne,
+    /// The highest high bit
+    HighOne,
+    /// The lowest low bit
+    LowZero,
+    /// The highest low bit
+    HighZero,
+}
+
+impl BitFinderType {
+    /// Parse the value of a `type` attribute (`low1`, `high1`, `low0`,
+    /// `high0`)
+    pub fn parse(s: &str) -> Result<BitFinderType, String> {
+        match s {
+            "low1" => Ok(BitFinderType::LowOne),
+            "high1" => Ok(BitFinderType::HighOne),
+            "low0" => Ok(BitFinderType::LowZero),
+            "high0" => Ok(BitFinderType::HighZero),
+            _ => Err(format!("Invalid bit finder type: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for BitFinderType {
+    /// Write the value of the `type` attribute
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BitFinderType::LowOne => "low1",
+            BitFinderType::HighOne => "high1",
+            BitFinderType::LowZero => "low0",
+            BitFinderType::HighZero => "high0",
+        })
+    }
+}
+
+/// Bit finder component
+///
+/// Drives the index of the lowest or highest bit of `Input` that is high
+/// or low on `Index`, and whether there is one on `Present`. An undefined
+/// bit met before the wanted one makes both outputs errors.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BitFinder {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    bit_width: BusWidth,
+    finder_type: BitFinderType,
 }
 
 impl BitFinder {
+    /// Create a new 8-bit finder of the lowest high bit (default)
     pub fn new(id: ComponentId) -> Self {
+        Self::new_with_width(id, BusWidth(8), BitFinderType::LowOne)
+    }
+
+    /// Create a new bit finder with specified input width and type
+    pub fn new_with_width(
+        id: ComponentId,
+        bit_width: BusWidth,
+        finder_type: BitFinderType,
+    ) -> Self {
         let mut pins = HashMap::new();
-        pins.insert("Input".to_string(), Pin::new_input("Input", BusWidth(8)));
-        pins.insert("Output".to_string(), Pin::new_output("Output", BusWidth(8)));
-        
-        BitFinder { id, pins }
+        pins.insert("Input".to_string(), Pin::new_input("Input", bit_width));
+        pins.insert(
+            "Present".to_string(),
+            Pin::new_output("Present", BusWidth(1)),
+        );
+        pins.insert(
+            "Index".to_string(),
+            Pin::new_output("Index", Self::index_width(bit_width)),
+        );
+
+        BitFinder {
+            id,
+            pins,
+            bit_width,
+            finder_type,
+        }
+    }
+
+    /// Width of the index, the fewest bits that count up to the highest
+    /// input bit
+    pub fn index_width(bit_width: BusWidth) -> BusWidth {
+        let max_index = bit_width.0.saturating_sub(1) as u64;
+        let mut bits = 1;
+        while (1u64 << bits) <= max_index {
+            bits += 1;
+        }
+        BusWidth(bits)
+    }
+
+    /// Get the input width
+    pub fn bit_width(&self) -> BusWidth {
+        self.bit_width
+    }
+
+    /// Get which bit is looked for
+    pub fn finder_type(&self) -> BitFinderType {
+        self.finder_type
+    }
+
+    /// Find the wanted bit, ported from `BitFinder.propagate`
+    ///
+    /// Returns (present, index).
+    pub fn find(input: &Signal, finder_type: BitFinderType) -> (Value, Signal) {
+        let bits = input.bits();
+        let index_width = Self::index_width(input.width());
+        let (want, high_first) = match finder_type {
+            BitFinderType::LowOne => (Value::High, false),
+            BitFinderType::HighOne => (Value::High, true),
+            BitFinderType::LowZero => (Value::Low, false),
+            BitFinderType::HighZero => (Value::Low, true),
+        };
+        // The first bit, in search order, that is not the unwanted value
+        let skip = want.not();
+        let found = if high_first {
+            bits.iter().rposition(|&bit| bit != skip)
+        } else {
+            bits.iter().position(|&bit| bit != skip)
+        };
+
+        match found {
+            None => (Value::Low, Signal::from_u64(0, index_width)),
+            Some(index) if bits[index] == want => {
+                (Value::High, Signal::from_u64(index as u64, index_width))
+            }
+            Some(_) => (Value::Error, Signal::error(index_width)),
+        }
     }
 }
 
 impl Component for BitFinder {
-    fn id(&self) -> ComponentId { self.id }
-    fn name(&self) -> &str { "BitFinder" }
-    fn pins(&self) -> &HashMap<String, Pin> { &self.pins }
-    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> { &mut self.pins }
-    
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        BIT_FINDER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        UpdateResult::NoChange // Placeholder
+        let (present, index) = Self::find(&self.pins["Input"].signal, self.finder_type);
+
+        let mut result = UpdateResult::new();
+        result.add_output("Present".to_string(), Signal::new_single(present));
+        result.add_output("Index".to_string(), index);
+        result.set_delay(self.propagation_delay());
+        result
     }
-    
+
     fn reset(&mut self) {
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
+
+    fn propagation_delay(&self) -> u64 {
+        // Index width * PER_DELAY, as in Java
+        Self::index_width(self.bit_width).0 as u64
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Arithmetic",
+            BIT_FINDER_ID,
+            vec![
+                ("width", self.bit_width.0.to_string()),
+                ("type", self.finder_type.to_string()),
+            ],
+        ))
+    }
 }
 
-impl Propagator for BitFinder {
-    fn propagate(&mut self, current_time: Timestamp) {
-        self.update(current_time + 1);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_types() {
+        let input = Signal::from_u64(0b0110_1100, BusWidth(8));
+        let cases = [
+            (BitFinderType::LowOne, 2),
+            (BitFinderType::HighOne, 6),
+            (BitFinderType::LowZero, 0),
+            (BitFinderType::HighZero, 7),
+        ];
+        for (finder_type, expected) in cases {
+            let (present, index) = BitFinder::find(&input, finder_type);
+            assert_eq!(present, Value::High);
+            assert_eq!(index.to_u64(), Some(expected));
+            assert_eq!(index.width(), BusWidth(3));
+            let name = finder_type.to_string();
+            assert_eq!(BitFinderType::parse(&name), Ok(finder_type));
+        }
+
+        let (present, index) =
+            BitFinder::find(&Signal::all_low(BusWidth(8)), BitFinderType::LowOne);
+        assert_eq!((present, index.to_u64()), (Value::Low, Some(0)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_find_undefined_bits() {
+        use Value::{High, Low, Unknown};
+        // The unknown bit comes before the high bit
+        let input = Signal::new_bus(vec![Low, Unknown, High, Low]);
+        let (present, index) = BitFinder::find(&input, BitFinderType::LowOne);
+        assert_eq!(present, Value::Error);
+        assert_eq!(index, Signal::error(BusWidth(2)));
+
+        // but after it when searching from the top
+        let (present, index) = BitFinder::find(&input, BitFinderType::HighOne);
+        assert_eq!((present, index.to_u64()), (Value::High, Some(2)));
+    }
+}
@@ -7,51 +7,221 @@
  * This is free software released under GNU GPLv3 license
  */
 
-//! Comparator Implementation (Placeholder)
+//! Comparator Implementation
+//!
+//! Rust port of `com.cburch.logisim.std.arith.Comparator`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Unique identifier for the Comparator component
+/// Do NOT change as it will prevent project files from loading.
+pub const COMPARATOR_ID: &str = "Comparator";
+
+/// Multi-bit Comparator component
+///
+/// Compares `A` with `B` as two's complement numbers, or as unsigned ones
+/// when set to, and drives exactly one of `Greater`, `Equal` and `Less`
+/// high. The highest bit pair that is not 0 or 1 makes all three outputs
+/// unknown or error if no higher bit pair already decided the comparison.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comparator {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    bit_width: BusWidth,
+    signed: bool,
 }
 
 impl Comparator {
+    /// Create a new 8-bit two's complement comparator (default width)
     pub fn new(id: ComponentId) -> Self {
+        Self::new_with_width(id, BusWidth(8))
+    }
+
+    /// Create a new two's complement comparator with specified bit width
+    pub fn new_with_width(id: ComponentId, bit_width: BusWidth) -> Self {
         let mut pins = HashMap::new();
-        pins.insert("A".to_string(), Pin::new_input("A", BusWidth(8)));
-        pins.insert("B".to_string(), Pin::new_input("B", BusWidth(8)));
-        pins.insert("Equal".to_string(), Pin::new_output("Equal", BusWidth(1)));
-        pins.insert("Less".to_string(), Pin::new_output("Less", BusWidth(1)));
-        pins.insert("Greater".to_string(), Pin::new_output("Greater", BusWidth(1)));
-        
-        Comparator { id, pins }
+        pins.insert("A".to_string(), Pin::new_input("A", bit_width));
+        pins.insert("B".to_string(), Pin::new_input("B", bit_width));
+        for name in ["Greater", "Equal", "Less"] {
+            pins.insert(name.to_string(), Pin::new_output(name, BusWidth(1)));
+        }
+
+        Comparator {
+            id,
+            pins,
+            bit_width,
+            signed: true,
+        }
+    }
+
+    /// Get the current bit width
+    pub fn bit_width(&self) -> BusWidth {
+        self.bit_width
+    }
+
+    /// Compare two's complement rather than unsigned numbers
+    pub fn set_signed(&mut self, signed: bool) {
+        self.signed = signed;
+    }
+
+    /// Check whether the inputs are compared as two's complement numbers
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Compare two values, ported from `Comparator.propagate`
+    ///
+    /// Returns the (greater, equal, less) outputs.
+    pub fn compare(a: &Signal, b: &Signal, signed: bool) -> (Value, Value, Value) {
+        let (a_width, b_width) = (a.width().0, b.width().0);
+        for pos in (0..a_width.max(b_width)).rev() {
+            let mut bit_a = a.get_bit(pos).unwrap_or(Value::Error);
+            let mut bit_b = b.get_bit(pos).unwrap_or(Value::Error);
+            // The sign bits weigh the other way round
+            if signed && pos + 1 == a_width && bit_a != bit_b {
+                std::mem::swap(&mut bit_a, &mut bit_b);
+            }
+
+            if bit_a == Value::Error || bit_b == Value::Error {
+                return (Value::Error, Value::Error, Value::Error);
+            } else if !bit_a.is_definite() || !bit_b.is_definite() {
+                return (Value::Unknown, Value::Unknown, Value::Unknown);
+            } else if bit_a != bit_b {
+                return if bit_a == Value::High {
+                    (Value::High, Value::Low, Value::Low)
+                } else {
+                    (Value::Low, Value::Low, Value::High)
+                };
+            }
+        }
+        (Value::Low, Value::High, Value::Low)
     }
 }
 
 impl Component for Comparator {
-    fn id(&self) -> ComponentId { self.id }
-    fn name(&self) -> &str { "Comparator" }
-    fn pins(&self) -> &HashMap<String, Pin> { &self.pins }
-    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> { &mut self.pins }
-    
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        COMPARATOR_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        UpdateResult::NoChange // Placeholder
+        let (greater, equal, less) =
+            Self::compare(&self.pins["A"].signal, &self.pins["B"].signal, self.signed);
+
+        let mut result = UpdateResult::new();
+        result.add_output("Greater".to_string(), Signal::new_single(greater));
+        result.add_output("Equal".to_string(), Signal::new_single(equal));
+        result.add_output("Less".to_string(), Signal::new_single(less));
+        result.set_delay(self.propagation_delay());
+        result
     }
-    
+
     fn reset(&mut self) {
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
+
+    fn propagation_delay(&self) -> u64 {
+        // (width + 2) * PER_DELAY, as in Java
+        self.bit_width.0 as u64 + 2
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let mode = if self.signed {
+            "twosComplement"
+        } else {
+            "unsigned"
+        };
+        Some(CircTool::new(
+            "#Arithmetic",
+            COMPARATOR_ID,
+            vec![
+                ("width", self.bit_width.0.to_string()),
+                ("mode", mode.to_string()),
+            ],
+        ))
+    }
 }
 
-impl Propagator for Comparator {
-    fn propagate(&mut self, current_time: Timestamp) {
-        self.update(current_time + 1);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(a: u64, b: u64, signed: bool) -> (Value, Value, Value) {
+        let width = BusWidth(4);
+        Comparator::compare(
+            &Signal::from_u64(a, width),
+            &Signal::from_u64(b, width),
+            signed,
+        )
+    }
+
+    #[test]
+    fn test_compare() {
+        let greater = (Value::High, Value::Low, Value::Low);
+        let equal = (Value::Low, Value::High, Value::Low);
+        let less = (Value::Low, Value::Low, Value::High);
+
+        assert_eq!(compare(5, 3, false), greater);
+        assert_eq!(compare(5, 5, false), equal);
+        assert_eq!(compare(3, 5, true), less);
+        // 0b1111 is 15 unsigned but -1 in two's complement
+        assert_eq!(compare(0b1111, 1, false), greater);
+        assert_eq!(compare(0b1111, 1, true), less);
+        assert_eq!(compare(0b1110, 0b1111, true), less);
+    }
+
+    #[test]
+    fn test_undefined_bits() {
+        let a = Signal::new_bus(vec![Value::Unknown, Value::High]);
+        let b = Signal::new_bus(vec![Value::Low, Value::Low]);
+        // A higher bit pair decides before the unknown bit is reached
+        assert_eq!(
+            Comparator::compare(&a, &b, false),
+            (Value::High, Value::Low, Value::Low)
+        );
+        let b = Signal::new_bus(vec![Value::Low, Value::High]);
+        assert_eq!(
+            Comparator::compare(&a, &b, false),
+            (Value::Unknown, Value::Unknown, Value::Unknown)
+        );
+        let a = Signal::new_bus(vec![Value::Error, Value::High]);
+        assert_eq!(
+            Comparator::compare(&a, &b, false),
+            (Value::Error, Value::Error, Value::Error)
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_comparator_update() {
+        let mut comparator = Comparator::new(ComponentId(1));
+        assert!(comparator.is_signed());
+        assert_eq!(comparator.propagation_delay(), 10);
+        comparator.reset();
+        for (name, value) in [("A", 7), ("B", 7)] {
+            comparator
+                .get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::from_u64(value, BusWidth(8)))
+                .unwrap();
+        }
+        let result = comparator.update(Timestamp(0));
+        assert_eq!(result.outputs["Equal"].as_single(), Some(Value::High));
+        assert_eq!(result.outputs["Less"].as_single(), Some(Value::Low));
+    }
+}
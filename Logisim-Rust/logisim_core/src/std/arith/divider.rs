@@ -11,91 +11,240 @@
 //!
 //! Rust port of `com.cburch.logisim.std.arith.Divider`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use super::multiplier::Multiplier;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Unique identifier for the Divider component
+/// Do NOT change as it will prevent project files from loading.
+pub const DIVIDER_ID: &str = "Divider";
+
+/// Multi-bit Divider component
+///
+/// Divides the double width number with `Upper` as its high half and `A`
+/// as its low half by `B`, driving the low `width` bits of the quotient on
+/// `Quotient` and of the remainder on `Remainder`. The operands are
+/// unsigned unless the divider is set to two's complement. A floating or
+/// unknown `Upper` counts as zero, and dividing by zero divides by one.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Divider {
     id: ComponentId,
     pins: HashMap<String, Pin>,
     bit_width: BusWidth,
+    signed: bool,
 }
 
 impl Divider {
+    /// Create a new 8-bit unsigned divider (default width)
     pub fn new(id: ComponentId) -> Self {
         Self::new_with_width(id, BusWidth(8))
     }
-    
+
+    /// Create a new unsigned divider with specified bit width
     pub fn new_with_width(id: ComponentId, bit_width: BusWidth) -> Self {
         let mut pins = HashMap::new();
-        pins.insert("Dividend".to_string(), Pin::new_input("Dividend", bit_width));
-        pins.insert("Divisor".to_string(), Pin::new_input("Divisor", bit_width));
-        pins.insert("Quotient".to_string(), Pin::new_output("Quotient", bit_width));
-        pins.insert("Remainder".to_string(), Pin::new_output("Remainder", bit_width));
-        
-        Divider { id, pins, bit_width }
-    }
-    
+        for name in ["A", "B", "Upper"] {
+            pins.insert(name.to_string(), Pin::new_input(name, bit_width));
+        }
+        for name in ["Quotient", "Remainder"] {
+            pins.insert(name.to_string(), Pin::new_output(name, bit_width));
+        }
+
+        Divider {
+            id,
+            pins,
+            bit_width,
+            signed: false,
+        }
+    }
+
+    /// Get the current bit width
     pub fn bit_width(&self) -> BusWidth {
         self.bit_width
     }
-}
 
-impl Component for Divider {
-    fn id(&self) -> ComponentId { self.id }
-    fn name(&self) -> &str { "Divider" }
-    fn pins(&self) -> &HashMap<String, Pin> { &self.pins }
-    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> { &mut self.pins }
-    
-    fn update(&mut self, current_time: Timestamp) -> UpdateResult {
-        let dividend = self.pins.get("Dividend").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        let divisor = self.pins.get("Divisor").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        
-        let (quotient, remainder) = if dividend.is_fully_defined() && divisor.is_fully_defined() {
-            let div_val = divisor.to_long_value();
-            if div_val == 0 {
-                (Value::Error, Value::Error)
+    /// Treat the operands as two's complement rather than unsigned numbers
+    pub fn set_signed(&mut self, signed: bool) {
+        self.signed = signed;
+    }
+
+    /// Check whether the operands are two's complement numbers
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Compute the quotient and remainder, ported from
+    /// `Divider.computeResult`
+    ///
+    /// Returns (quotient, remainder). Undefined operand bits make both
+    /// outputs unknown, or error if any operand carries an error.
+    pub fn compute_quotient(
+        bit_width: BusWidth,
+        a: &Signal,
+        b: &Signal,
+        upper: &Signal,
+        signed: bool,
+    ) -> (Signal, Signal) {
+        let width = bit_width.0.min(64);
+        let floating = upper
+            .bits()
+            .iter()
+            .all(|bit| matches!(bit, Value::Unknown | Value::HighZ));
+        let upper = if floating {
+            Signal::all_low(bit_width)
+        } else {
+            upper.clone()
+        };
+
+        let (Some(a), Some(b), Some(upper)) = (a.to_u64(), b.to_u64(), upper.to_u64()) else {
+            let undefined = if a.has_error() || b.has_error() || upper.has_error() {
+                Signal::error(bit_width)
             } else {
-                let div_result = dividend.to_long_value() / div_val;
-                let rem_result = dividend.to_long_value() % div_val;
-                (
-                    Value::from_long(div_result, self.bit_width),
-                    Value::from_long(rem_result, self.bit_width)
-                )
-            }
+                Signal::unknown(bit_width)
+            };
+            return (undefined.clone(), undefined);
+        };
+
+        // The low `width` bits are kept, so wrapping is exact
+        let (quotient, remainder) = if signed {
+            let extend = |value| Multiplier::extend(width, value, true);
+            let numerator = (extend(upper) << width) | extend(a);
+            let denominator = match extend(b) {
+                0 => 1,
+                b => b,
+            };
+            (
+                numerator.wrapping_div(denominator) as u64,
+                numerator.wrapping_rem(denominator) as u64,
+            )
         } else {
-            (Value::Unknown, Value::Unknown)
+            let numerator = ((upper as u128) << width) | a as u128;
+            let denominator = b.max(1) as u128;
+            (
+                (numerator / denominator) as u64,
+                (numerator % denominator) as u64,
+            )
         };
-        
-        let mut changed = false;
-        if let Some(pin) = self.pins.get_mut("Quotient") {
-            if pin.signal().value() != quotient {
-                pin.set_signal(Signal::new(quotient, current_time));
-                changed = true;
-            }
-        }
-        if let Some(pin) = self.pins.get_mut("Remainder") {
-            if pin.signal().value() != remainder {
-                pin.set_signal(Signal::new(remainder, current_time));
-                changed = true;
-            }
-        }
-        
-        if changed { UpdateResult::Changed } else { UpdateResult::NoChange }
+        (
+            Signal::from_u64(quotient, bit_width),
+            Signal::from_u64(remainder, bit_width),
+        )
+    }
+}
+
+impl Component for Divider {
+    fn id(&self) -> ComponentId {
+        self.id
     }
-    
+
+    fn name(&self) -> &str {
+        DIVIDER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let (quotient, remainder) = Self::compute_quotient(
+            self.bit_width,
+            &self.pins["A"].signal,
+            &self.pins["B"].signal,
+            &self.pins["Upper"].signal,
+            self.signed,
+        );
+
+        let mut result = UpdateResult::new();
+        result.add_output("Quotient".to_string(), quotient);
+        result.add_output("Remainder".to_string(), remainder);
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
     fn reset(&mut self) {
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
+
+    fn propagation_delay(&self) -> u64 {
+        // width * (width + 2) * PER_DELAY, as in Java
+        let width = self.bit_width.0 as u64;
+        width * (width + 2)
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let mode = if self.signed {
+            "twosComplement"
+        } else {
+            "unsigned"
+        };
+        Some(CircTool::new(
+            "#Arithmetic",
+            DIVIDER_ID,
+            vec![
+                ("width", self.bit_width.0.to_string()),
+                ("mode", mode.to_string()),
+            ],
+        ))
+    }
 }
 
-impl Propagator for Divider {
-    fn propagate(&mut self, current_time: Timestamp) {
-        let delay = self.bit_width.0 * 2;
-        self.update(current_time + delay as u64);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn divide(width: u32, upper: u64, a: u64, b: u64, signed: bool) -> (u64, u64) {
+        let width = BusWidth(width);
+        let (quotient, remainder) = Divider::compute_quotient(
+            width,
+            &Signal::from_u64(a, width),
+            &Signal::from_u64(b, width),
+            &Signal::from_u64(upper, width),
+            signed,
+        );
+        (quotient.to_u64().unwrap(), remainder.to_u64().unwrap())
+    }
+
+    #[test]
+    fn test_compute_quotient() {
+        assert_eq!(divide(8, 0, 100, 7, false), (14, 2));
+        // 0x0102 / 3 = 86 remainder 0
+        assert_eq!(divide(8, 1, 2, 3, false), (86, 0));
+        // Dividing by zero divides by one
+        assert_eq!(divide(8, 0, 9, 0, false), (9, 0));
+        // -7 / 2 = -3 remainder -1, rounding toward zero
+        assert_eq!(divide(8, 0xff, 0xf9, 2, true), (0xfd, 0xff));
+        assert_eq!(divide(64, u64::MAX, u64::MAX, u64::MAX, false), (1, 0));
+    }
+
+    #[test]
+    fn test_divider_update() {
+        let mut divider = Divider::new_with_width(ComponentId(1), BusWidth(4));
+        assert_eq!(divider.name(), "Divider");
+        assert_eq!(divider.propagation_delay(), 24);
+        divider.reset();
+        for (name, value) in [("A", 13), ("B", 4)] {
+            divider
+                .get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::from_u64(value, BusWidth(4)))
+                .unwrap();
+        }
+        // The upper half is left floating
+        let result = divider.update(Timestamp(0));
+        assert_eq!(result.outputs["Quotient"].to_u64(), Some(3));
+        assert_eq!(result.outputs["Remainder"].to_u64(), Some(1));
+
+        let b = Signal::new_bus(vec![Value::Low, Value::Error, Value::Low, Value::Low]);
+        divider.get_pin_mut("B").unwrap().set_signal(b).unwrap();
+        let result = divider.update(Timestamp(0));
+        assert_eq!(result.outputs["Quotient"], Signal::error(BusWidth(4)));
     }
-}
\ No newline at end of file
+}
@@ -49,41 +49,24 @@
 //! The arithmetic library maintains behavioral compatibility with the Java
 //! implementation while leveraging Rust's type safety and performance benefits.
 
-pub mod arithmetic_library;
+// The floating-point components and the library still target the old
+// `Propagator` and `Value` APIs and are not built yet
 pub mod adder;
-pub mod subtractor;
-pub mod multiplier;
+pub mod bit_adder;
+pub mod bit_finder;
+pub mod comparator;
 pub mod divider;
+pub mod multiplier;
 pub mod negator;
-pub mod comparator;
 pub mod shifter;
-pub mod bit_adder;
-pub mod bit_finder;
-pub mod fp_adder;
-pub mod fp_subtractor;
-pub mod fp_multiplier;
-pub mod fp_divider;
-pub mod fp_negator;
-pub mod fp_comparator;
-pub mod fp_to_int;
-pub mod int_to_fp;
+pub mod subtractor;
 
-// Re-export all components for easy usage
-pub use arithmetic_library::ArithmeticLibrary;
 pub use adder::Adder;
-pub use subtractor::Subtractor;
-pub use multiplier::Multiplier;
+pub use bit_adder::BitAdder;
+pub use bit_finder::{BitFinder, BitFinderType};
+pub use comparator::Comparator;
 pub use divider::Divider;
+pub use multiplier::Multiplier;
 pub use negator::Negator;
-pub use comparator::Comparator;
-pub use shifter::Shifter;
-pub use bit_adder::BitAdder;
-pub use bit_finder::BitFinder;
-pub use fp_adder::FpAdder;
-pub use fp_subtractor::FpSubtractor;
-pub use fp_multiplier::FpMultiplier;
-pub use fp_divider::FpDivider;
-pub use fp_negator::FpNegator;
-pub use fp_comparator::FpComparator;
-pub use fp_to_int::FpToInt;
-pub use int_to_fp::IntToFp;
\ No newline at end of file
+pub use shifter::{ShiftType, Shifter};
+pub use subtractor::Subtractor;
@@ -11,76 +11,291 @@
 //!
 //! Rust port of `com.cburch.logisim.std.arith.Multiplier`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Unique identifier for the Multiplier component
+/// Do NOT change as it will prevent project files from loading.
+pub const MULTIPLIER_ID: &str = "Multiplier";
+
 /// Multi-bit Multiplier component
+///
+/// Computes `A * B + Carry_In`, all three `width` bits wide, and splits the
+/// double width result into the low half on `Product` and the high half on
+/// `Carry_Out`. The operands are unsigned unless the multiplier is set to
+/// two's complement. A floating or unknown carry in counts as zero.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Multiplier {
     id: ComponentId,
     pins: HashMap<String, Pin>,
     bit_width: BusWidth,
+    signed: bool,
 }
 
 impl Multiplier {
+    /// Create a new 8-bit unsigned multiplier (default width)
     pub fn new(id: ComponentId) -> Self {
         Self::new_with_width(id, BusWidth(8))
     }
-    
+
+    /// Create a new unsigned multiplier with specified bit width
     pub fn new_with_width(id: ComponentId, bit_width: BusWidth) -> Self {
         let mut pins = HashMap::new();
-        pins.insert("A".to_string(), Pin::new_input("A", bit_width));
-        pins.insert("B".to_string(), Pin::new_input("B", bit_width));
-        pins.insert("Product".to_string(), Pin::new_output("Product", BusWidth(bit_width.0 * 2)));
-        
-        Multiplier { id, pins, bit_width }
+        for name in ["A", "B", "Carry_In"] {
+            pins.insert(name.to_string(), Pin::new_input(name, bit_width));
+        }
+        for name in ["Product", "Carry_Out"] {
+            pins.insert(name.to_string(), Pin::new_output(name, bit_width));
+        }
+
+        Multiplier {
+            id,
+            pins,
+            bit_width,
+            signed: false,
+        }
     }
-    
+
+    /// Get the current bit width
     pub fn bit_width(&self) -> BusWidth {
         self.bit_width
     }
-}
 
-impl Component for Multiplier {
-    fn id(&self) -> ComponentId { self.id }
-    fn name(&self) -> &str { "Multiplier" }
-    fn pins(&self) -> &HashMap<String, Pin> { &self.pins }
-    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> { &mut self.pins }
-    
-    fn update(&mut self, current_time: Timestamp) -> UpdateResult {
-        let value_a = self.pins.get("A").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        let value_b = self.pins.get("B").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        
-        let product = if value_a.is_fully_defined() && value_b.is_fully_defined() {
-            let result = value_a.to_long_value() * value_b.to_long_value();
-            Value::from_long(result, BusWidth(self.bit_width.0 * 2))
+    /// Treat the operands as two's complement rather than unsigned numbers
+    pub fn set_signed(&mut self, signed: bool) {
+        self.signed = signed;
+    }
+
+    /// Check whether the operands are two's complement numbers
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Compute the product, ported from `Multiplier.computeProduct`
+    ///
+    /// Returns (product, carry_out). With undefined operand bits, the
+    /// product bits below the lowest one are still computed from the known
+    /// bits, the rest are unknown, or error from the lowest error bit on.
+    pub fn compute_product(
+        bit_width: BusWidth,
+        a: &Signal,
+        b: &Signal,
+        carry_in: &Signal,
+        signed: bool,
+    ) -> (Signal, Signal) {
+        let width = bit_width.0.min(64);
+        let floating = carry_in
+            .bits()
+            .iter()
+            .all(|bit| matches!(bit, Value::Unknown | Value::HighZ));
+        let carry_in = if floating {
+            Signal::all_low(bit_width)
         } else {
-            Value::Unknown
+            carry_in.clone()
         };
-        
-        let mut changed = false;
-        if let Some(pin) = self.pins.get_mut("Product") {
-            if pin.signal().value() != product {
-                pin.set_signal(Signal::new(product, current_time));
-                changed = true;
+
+        let operands = [a, b, &carry_in];
+        // Index of the first bit that is not 0 or 1, and of the first error
+        let first = |undefined: fn(Value) -> bool| {
+            operands
+                .iter()
+                .map(|signal| {
+                    (0..width)
+                        .find(|&i| undefined(signal.get_bit(i).unwrap_or(Value::Error)))
+                        .unwrap_or(width)
+                })
+                .min()
+                .unwrap_or(width)
+        };
+        let known = first(|bit| !bit.is_definite());
+        let error = first(|bit| bit == Value::Error);
+        // The bits below the first undefined one of each operand
+        let value = |signal: &Signal| {
+            let mut value = 0u64;
+            for i in 0..width {
+                match signal.get_bit(i) {
+                    Some(Value::High) => value |= 1 << i,
+                    Some(Value::Low) => {}
+                    _ => break,
+                }
             }
+            Self::extend(width, value, signed)
+        };
+        // Only the low 2 * width bits are kept, so wrapping is exact
+        let result = value(a)
+            .wrapping_mul(value(b))
+            .wrapping_add(value(&carry_in)) as u128;
+
+        let bit = |i: u32| Value::from_bool((result >> i) & 1 == 1);
+        if known == width {
+            let product = (0..width).map(bit).collect();
+            let carry_out = (width..2 * width).map(bit).collect();
+            return (Signal::new_bus(product), Signal::new_bus(carry_out));
+        }
+        let product = (0..width)
+            .map(|i| {
+                if i < known {
+                    bit(i)
+                } else if i < error {
+                    Value::Unknown
+                } else {
+                    Value::Error
+                }
+            })
+            .collect();
+        let carry_out = if error < width {
+            Value::Error
+        } else {
+            Value::Unknown
+        };
+        (
+            Signal::new_bus(product),
+            Signal::new_uniform(BusWidth(width), carry_out),
+        )
+    }
+
+    /// Widen a `width` bit operand, sign extending it in two's complement
+    pub(super) fn extend(width: u32, value: u64, signed: bool) -> i128 {
+        if signed && width > 0 && (value >> (width - 1)) & 1 == 1 {
+            value as i128 - (1i128 << width)
+        } else {
+            value as i128
         }
-        
-        if changed { UpdateResult::Changed } else { UpdateResult::NoChange }
     }
-    
+}
+
+impl Component for Multiplier {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        MULTIPLIER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let (product, carry_out) = Self::compute_product(
+            self.bit_width,
+            &self.pins["A"].signal,
+            &self.pins["B"].signal,
+            &self.pins["Carry_In"].signal,
+            self.signed,
+        );
+
+        let mut result = UpdateResult::new();
+        result.add_output("Product".to_string(), product);
+        result.add_output("Carry_Out".to_string(), carry_out);
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
     fn reset(&mut self) {
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
+
+    fn propagation_delay(&self) -> u64 {
+        // width * (width + 2) * PER_DELAY, as in Java
+        let width = self.bit_width.0 as u64;
+        width * (width + 2)
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let mode = if self.signed {
+            "twosComplement"
+        } else {
+            "unsigned"
+        };
+        Some(CircTool::new(
+            "#Arithmetic",
+            MULTIPLIER_ID,
+            vec![
+                ("width", self.bit_width.0.to_string()),
+                ("mode", mode.to_string()),
+            ],
+        ))
+    }
 }
 
-impl Propagator for Multiplier {
-    fn propagate(&mut self, current_time: Timestamp) {
-        let delay = self.bit_width.0 * self.bit_width.0; // Rough approximation
-        self.update(current_time + delay as u64);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multiply(width: u32, a: u64, b: u64, c: u64, signed: bool) -> (u64, u64) {
+        let width = BusWidth(width);
+        let (product, carry_out) = Multiplier::compute_product(
+            width,
+            &Signal::from_u64(a, width),
+            &Signal::from_u64(b, width),
+            &Signal::from_u64(c, width),
+            signed,
+        );
+        (product.to_u64().unwrap(), carry_out.to_u64().unwrap())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_compute_product() {
+        assert_eq!(multiply(8, 12, 10, 0, false), (120, 0));
+        assert_eq!(multiply(8, 200, 3, 1, false), (601 & 0xff, 601 >> 8));
+        // -1 * 2 = -2, sign extended into the high half
+        assert_eq!(multiply(8, 0xff, 2, 0, true), (0xfe, 0xff));
+        assert_eq!(multiply(8, 0xff, 2, 0, false), (0xfe, 0x01));
+        assert_eq!(
+            multiply(64, u64::MAX, u64::MAX, 0, false),
+            (1, u64::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn test_undefined_operands() {
+        let width = BusWidth(4);
+        let a = Signal::new_bus(vec![Value::High, Value::High, Value::Unknown, Value::Low]);
+        let b = Signal::from_u64(1, width);
+        let (product, carry_out) =
+            Multiplier::compute_product(width, &a, &b, &Signal::unknown(width), false);
+        assert_eq!(
+            product.bits(),
+            &[Value::High, Value::High, Value::Unknown, Value::Unknown]
+        );
+        assert_eq!(carry_out, Signal::unknown(width));
+
+        let a = Signal::new_bus(vec![Value::High, Value::Unknown, Value::Error, Value::Low]);
+        let (product, carry_out) =
+            Multiplier::compute_product(width, &a, &b, &Signal::all_low(width), false);
+        assert_eq!(
+            product.bits(),
+            &[Value::High, Value::Unknown, Value::Error, Value::Error]
+        );
+        assert_eq!(carry_out, Signal::new_uniform(width, Value::Error));
+    }
+
+    #[test]
+    fn test_multiplier_update() {
+        let mut multiplier = Multiplier::new_with_width(ComponentId(1), BusWidth(4));
+        assert_eq!(multiplier.name(), "Multiplier");
+        assert_eq!(multiplier.propagation_delay(), 24);
+        multiplier.reset();
+        for (name, value) in [("A", 7), ("B", 5)] {
+            multiplier
+                .get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::from_u64(value, BusWidth(4)))
+                .unwrap();
+        }
+        // The carry in is left floating
+        let result = multiplier.update(Timestamp(0));
+        assert_eq!(result.outputs["Product"].to_u64(), Some(35 & 0xf));
+        assert_eq!(result.outputs["Carry_Out"].to_u64(), Some(35 >> 4));
+    }
+}
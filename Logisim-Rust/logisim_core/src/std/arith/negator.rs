@@ -11,11 +11,21 @@
 //!
 //! Rust port of `com.cburch.logisim.std.arith.Negator`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Unique identifier for the Negator component
+/// Do NOT change as it will prevent project files from loading.
+pub const NEGATOR_ID: &str = "Negator";
+
+/// Two's complement negator
+///
+/// Drives the negation of `Input` on `Output`. The low bits up to the
+/// first high bit pass through unchanged and the bits above it are
+/// inverted, so a partially defined input still yields the bits it
+/// determines.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Negator {
     id: ComponentId,
@@ -24,62 +34,140 @@ pub struct Negator {
 }
 
 impl Negator {
+    /// Create a new 8-bit negator (default width)
     pub fn new(id: ComponentId) -> Self {
         Self::new_with_width(id, BusWidth(8))
     }
-    
+
+    /// Create a new negator with specified bit width
     pub fn new_with_width(id: ComponentId, bit_width: BusWidth) -> Self {
         let mut pins = HashMap::new();
         pins.insert("Input".to_string(), Pin::new_input("Input", bit_width));
         pins.insert("Output".to_string(), Pin::new_output("Output", bit_width));
-        
-        Negator { id, pins, bit_width }
+
+        Negator {
+            id,
+            pins,
+            bit_width,
+        }
     }
-    
+
+    /// Get the current bit width
     pub fn bit_width(&self) -> BusWidth {
         self.bit_width
     }
-}
 
-impl Component for Negator {
-    fn id(&self) -> ComponentId { self.id }
-    fn name(&self) -> &str { "Negator" }
-    fn pins(&self) -> &HashMap<String, Pin> { &self.pins }
-    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> { &mut self.pins }
-    
-    fn update(&mut self, current_time: Timestamp) -> UpdateResult {
-        let input = self.pins.get("Input").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        
-        let output = if input.is_fully_defined() {
-            // Two's complement negation: ~input + 1
-            let val = input.to_long_value();
-            let negated = (!val).wrapping_add(1);
-            Value::from_long(negated, self.bit_width)
-        } else {
-            Value::Unknown
-        };
-        
-        let mut changed = false;
-        if let Some(pin) = self.pins.get_mut("Output") {
-            if pin.signal().value() != output {
-                pin.set_signal(Signal::new(output, current_time));
-                changed = true;
+    /// Negate a value bit by bit, ported from `Negator.propagate`
+    pub fn negate(input: &Signal) -> Signal {
+        let mut bits = input.bits().to_vec();
+        // Below the first high bit the input passes through, except that
+        // an undefined bit spoils the ones above it
+        let mut fill = Value::Low;
+        let mut pos = 0;
+        while pos < bits.len() {
+            let bit = bits[pos];
+            pos += 1;
+            match bit {
+                Value::Low => bits[pos - 1] = fill,
+                Value::High => {
+                    if fill != Value::Low {
+                        bits[pos - 1] = fill;
+                    }
+                    break;
+                }
+                Value::Error => fill = Value::Error,
+                _ if fill == Value::Low => fill = bit,
+                _ => bits[pos - 1] = fill,
+            }
+        }
+        for bit in &mut bits[pos..] {
+            if bit.is_definite() {
+                *bit = bit.not();
             }
         }
-        
-        if changed { UpdateResult::Changed } else { UpdateResult::NoChange }
+        Signal::new_bus(bits)
+    }
+}
+
+impl Component for Negator {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        NEGATOR_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
     }
-    
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let output = Self::negate(&self.pins["Input"].signal);
+
+        let mut result = UpdateResult::new();
+        result.add_output("Output".to_string(), output);
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
     fn reset(&mut self) {
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
+
+    fn propagation_delay(&self) -> u64 {
+        // (width + 2) * PER_DELAY, as in Java
+        self.bit_width.0 as u64 + 2
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Arithmetic",
+            NEGATOR_ID,
+            vec![("width", self.bit_width.0.to_string())],
+        ))
+    }
 }
 
-impl Propagator for Negator {
-    fn propagate(&mut self, current_time: Timestamp) {
-        let delay = self.bit_width.0 + 2;
-        self.update(current_time + delay as u64);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negate() {
+        let eight = BusWidth(8);
+        for (value, expected) in [(0, 0), (1, 0xff), (5, 0xfb), (0x80, 0x80)] {
+            let output = Negator::negate(&Signal::from_u64(value, eight));
+            assert_eq!(output.to_u64(), Some(expected));
+        }
+
+        // Bits below the first high bit are known even past an unknown bit
+        use Value::{High, Low, Unknown};
+        let input = Signal::new_bus(vec![Low, High, Unknown, Low]);
+        let output = Negator::negate(&input);
+        assert_eq!(output.bits(), &[Low, High, Unknown, High]);
+        let input = Signal::new_bus(vec![Unknown, Low, High, Low]);
+        let output = Negator::negate(&input);
+        assert_eq!(output.bits(), &[Unknown, Unknown, Unknown, High]);
+    }
+
+    #[test]
+    fn test_negator_update() {
+        let mut negator = Negator::new(ComponentId(1));
+        assert_eq!(negator.propagation_delay(), 10);
+        negator.reset();
+        negator
+            .get_pin_mut("Input")
+            .unwrap()
+            .set_signal(Signal::from_u64(3, BusWidth(8)))
+            .unwrap();
+        let result = negator.update(Timestamp(0));
+        assert_eq!(result.outputs["Output"].to_u64(), Some(0xfd));
     }
-}
\ No newline at end of file
+}
@@ -7,49 +7,251 @@
  * This is free software released under GNU GPLv3 license
  */
 
-//! Shifter Implementation (Placeholder)
+//! Shifter Implementation
+//!
+//! Rust port of `com.cburch.logisim.std.arith.Shifter`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
+/// Unique identifier for the Shifter component
+/// Do NOT change as it will prevent project files from loading.
+pub const SHIFTER_ID: &str = "Shifter";
+
+/// How a shifter moves the bits of its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ShiftType {
+    /// Shift toward the high bits, filling with zeros
+    #[default]
+    LogicalLeft,
+    /// Shift toward the low bits, filling with zeros
+    LogicalRight,
+    /// Shift toward the low bits, filling with copies of the top bit
+    ArithmeticRight,
+    /// Rotate toward the high bits
+    RollLeft,
+    /// Rotate toward the low bits
+    RollRight,
+}
+
+impl ShiftType {
+    /// Parse the value of a `shift` attribute (`ll`, `lr`, `ar`, `rl`, `rr`)
+    pub fn parse(s: &str) -> Result<ShiftType, String> {
+        match s {
+            "ll" => Ok(ShiftType::LogicalLeft),
+            "lr" => Ok(ShiftType::LogicalRight),
+            "ar" => Ok(ShiftType::ArithmeticRight),
+            "rl" => Ok(ShiftType::RollLeft),
+            "rr" => Ok(ShiftType::RollRight),
+            _ => Err(format!("Invalid shift type: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for ShiftType {
+    /// Write the value of the `shift` attribute
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ShiftType::LogicalLeft => "ll",
+            ShiftType::LogicalRight => "lr",
+            ShiftType::ArithmeticRight => "ar",
+            ShiftType::RollLeft => "rl",
+            ShiftType::RollRight => "rr",
+        })
+    }
+}
+
+/// Shifter component
+///
+/// Shifts or rotates `Input` by the distance on `Shift` and drives the
+/// result on `Output`. The distance is just wide enough to count up to the
+/// data width; an undefined distance makes the whole output an error.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Shifter {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    bit_width: BusWidth,
+    shift: ShiftType,
 }
 
 impl Shifter {
+    /// Create a new 8-bit logical left shifter (default width)
     pub fn new(id: ComponentId) -> Self {
+        Self::new_with_width(id, BusWidth(8), ShiftType::LogicalLeft)
+    }
+
+    /// Create a new shifter with specified bit width and shift type
+    pub fn new_with_width(id: ComponentId, bit_width: BusWidth, shift: ShiftType) -> Self {
         let mut pins = HashMap::new();
-        pins.insert("Input".to_string(), Pin::new_input("Input", BusWidth(8)));
-        pins.insert("Shift".to_string(), Pin::new_input("Shift", BusWidth(3)));
-        pins.insert("Output".to_string(), Pin::new_output("Output", BusWidth(8)));
-        
-        Shifter { id, pins }
+        pins.insert("Input".to_string(), Pin::new_input("Input", bit_width));
+        pins.insert(
+            "Shift".to_string(),
+            Pin::new_input("Shift", Self::distance_width(bit_width)),
+        );
+        pins.insert("Output".to_string(), Pin::new_output("Output", bit_width));
+
+        Shifter {
+            id,
+            pins,
+            bit_width,
+            shift,
+        }
+    }
+
+    /// Width of the distance input for a data width, the fewest bits that
+    /// count up to it
+    pub fn distance_width(bit_width: BusWidth) -> BusWidth {
+        let mut bits = 1;
+        while bits < 32 && (1u64 << bits) < bit_width.0 as u64 {
+            bits += 1;
+        }
+        BusWidth(bits)
+    }
+
+    /// Get the current bit width
+    pub fn bit_width(&self) -> BusWidth {
+        self.bit_width
+    }
+
+    /// Get how the input is shifted
+    pub fn shift_type(&self) -> ShiftType {
+        self.shift
+    }
+
+    /// Shift a value, ported from `Shifter.propagate`
+    pub fn shift(input: &Signal, distance: &Signal, shift: ShiftType) -> Signal {
+        let bits = input.bits();
+        let width = bits.len();
+        let Some(distance) = distance.to_u64() else {
+            return Signal::error(input.width());
+        };
+        let distance = (distance as usize).min(2 * width);
+        let roll = if distance >= width {
+            distance - width
+        } else {
+            distance
+        };
+
+        let output = (0..width)
+            .map(|i| match shift {
+                ShiftType::LogicalLeft => match i.checked_sub(distance) {
+                    Some(from) => bits[from],
+                    None => Value::Low,
+                },
+                ShiftType::LogicalRight => bits.get(i + distance).copied().unwrap_or(Value::Low),
+                ShiftType::ArithmeticRight => bits
+                    .get(i + distance)
+                    .or(bits.last())
+                    .copied()
+                    .unwrap_or(Value::Low),
+                ShiftType::RollLeft => bits[(i + width - roll) % width],
+                ShiftType::RollRight => bits[(i + roll) % width],
+            })
+            .collect();
+        Signal::new_bus(output)
     }
 }
 
 impl Component for Shifter {
-    fn id(&self) -> ComponentId { self.id }
-    fn name(&self) -> &str { "Shifter" }
-    fn pins(&self) -> &HashMap<String, Pin> { &self.pins }
-    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> { &mut self.pins }
-    
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        SHIFTER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        UpdateResult::NoChange // Placeholder
+        let output = Self::shift(
+            &self.pins["Input"].signal,
+            &self.pins["Shift"].signal,
+            self.shift,
+        );
+
+        let mut result = UpdateResult::new();
+        result.add_output("Output".to_string(), output);
+        result.set_delay(self.propagation_delay());
+        result
     }
-    
+
     fn reset(&mut self) {
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
+
+    fn propagation_delay(&self) -> u64 {
+        // width * 3 * PER_DELAY, as in Java
+        3 * self.bit_width.0 as u64
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Arithmetic",
+            SHIFTER_ID,
+            vec![
+                ("width", self.bit_width.0.to_string()),
+                ("shift", self.shift.to_string()),
+            ],
+        ))
+    }
 }
 
-impl Propagator for Shifter {
-    fn propagate(&mut self, current_time: Timestamp) {
-        self.update(current_time + 1);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shift(value: u64, distance: u64, shift: ShiftType) -> Option<u64> {
+        let width = BusWidth(8);
+        let distance = Signal::from_u64(distance, Shifter::distance_width(width));
+        Shifter::shift(&Signal::from_u64(value, width), &distance, shift).to_u64()
+    }
+
+    #[test]
+    fn test_distance_width() {
+        let widths = [(1, 1), (2, 1), (3, 2), (8, 3), (9, 4), (32, 5), (64, 6)];
+        for (data, distance) in widths {
+            assert_eq!(Shifter::distance_width(BusWidth(data)), BusWidth(distance));
+        }
+    }
+
+    #[test]
+    fn test_shift_types() {
+        use ShiftType::*;
+        assert_eq!(shift(0b1001_0110, 2, LogicalLeft), Some(0b0101_1000));
+        assert_eq!(shift(0b1001_0110, 2, LogicalRight), Some(0b0010_0101));
+        assert_eq!(shift(0b1001_0110, 2, ArithmeticRight), Some(0b1110_0101));
+        assert_eq!(shift(0b1001_0110, 2, RollLeft), Some(0b0101_1010));
+        assert_eq!(shift(0b1001_0110, 2, RollRight), Some(0b1010_0101));
+        assert_eq!(shift(0b1001_0110, 0, RollRight), Some(0b1001_0110));
+        assert_eq!(shift(0b1001_0110, 7, ArithmeticRight), Some(0xff));
+
+        for name in ["ll", "lr", "ar", "rl", "rr"] {
+            assert_eq!(ShiftType::parse(name).unwrap().to_string(), name);
+        }
+    }
+
+    #[test]
+    fn test_shifter_undefined_inputs() {
+        use Value::{High, Low, Unknown};
+        let input = Signal::new_bus(vec![High, Unknown, Low, High]);
+        let distance = Signal::from_u64(1, BusWidth(2));
+        let output = Shifter::shift(&input, &distance, ShiftType::LogicalLeft);
+        assert_eq!(output.bits(), &[Low, High, Unknown, Low]);
+
+        let distance = Signal::new_bus(vec![Unknown, Low]);
+        let output = Shifter::shift(&input, &distance, ShiftType::LogicalLeft);
+        assert_eq!(output, Signal::error(BusWidth(4)));
     }
-}
\ No newline at end of file
+}
@@ -11,16 +11,21 @@
 //!
 //! Rust port of `com.cburch.logisim.std.arith.Subtractor`
 
-use crate::comp::{Component, ComponentId, Pin, Propagator, UpdateResult};
+use super::adder::Adder;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
 use crate::signal::{BusWidth, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use super::adder::Adder;
+
+/// Unique identifier for the Subtractor component
+/// Do NOT change as it will prevent project files from loading.
+pub const SUBTRACTOR_ID: &str = "Subtractor";
 
 /// Multi-bit Subtractor component
-/// 
-/// Performs binary subtraction (A - B) on two multi-bit inputs with borrow-in and borrow-out.
-/// Internally implemented using addition with complement (two's complement arithmetic).
+///
+/// Computes `A - B - Borrow_In` into `Difference` and `Borrow_Out` as the
+/// sum `A + !B + !Borrow_In`, whose carry out is the complement of the
+/// borrow. A floating or unknown borrow in counts as zero.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Subtractor {
     id: ComponentId,
@@ -33,67 +38,66 @@ impl Subtractor {
     pub fn new(id: ComponentId) -> Self {
         Self::new_with_width(id, BusWidth(8))
     }
-    
+
     /// Create a new subtractor with specified bit width
     pub fn new_with_width(id: ComponentId, bit_width: BusWidth) -> Self {
         let mut pins = HashMap::new();
-        
+
         // Input pins (A - B)
-        pins.insert("A".to_string(), Pin::new_input("A", bit_width));          // Minuend
-        pins.insert("B".to_string(), Pin::new_input("B", bit_width));          // Subtrahend
-        pins.insert("Borrow_In".to_string(), Pin::new_input("Borrow_In", BusWidth(1)));
-        
+        pins.insert("A".to_string(), Pin::new_input("A", bit_width));
+        pins.insert("B".to_string(), Pin::new_input("B", bit_width));
+        pins.insert(
+            "Borrow_In".to_string(),
+            Pin::new_input("Borrow_In", BusWidth(1)),
+        );
+
         // Output pins
-        pins.insert("Difference".to_string(), Pin::new_output("Difference", bit_width));
-        pins.insert("Borrow_Out".to_string(), Pin::new_output("Borrow_Out", BusWidth(1)));
+        pins.insert(
+            "Difference".to_string(),
+            Pin::new_output("Difference", bit_width),
+        );
+        pins.insert(
+            "Borrow_Out".to_string(),
+            Pin::new_output("Borrow_Out", BusWidth(1)),
+        );
 
-        Subtractor { id, pins, bit_width }
+        Subtractor {
+            id,
+            pins,
+            bit_width,
+        }
     }
-    
+
     /// Compute the difference using addition with complement
-    /// Returns (difference, borrow_out)
-    /// 
-    /// Subtraction A - B is implemented as A + (~B) + (~borrow_in)
-    /// The borrow_out is the complement of the carry_out from this addition
-    fn compute_difference(
-        bit_width: BusWidth, 
-        value_a: &Value, 
-        value_b: &Value, 
-        borrow_in: &Value
-    ) -> (Value, Value) {
-        // Handle special cases for borrow_in
+    ///
+    /// Returns (difference, borrow_out).
+    pub fn compute_difference(
+        bit_width: BusWidth,
+        a: &Signal,
+        b: &Signal,
+        borrow_in: Value,
+    ) -> (Signal, Value) {
         let borrow_in = match borrow_in {
             Value::Unknown | Value::HighZ => Value::Low,
-            _ => borrow_in.clone(),
+            other => other,
         };
-        
-        // Compute A - B using A + (~B) + (~borrow_in)
-        let not_b = value_b.not();
-        let not_borrow_in = borrow_in.not();
-        
-        // Use the adder's computation logic
-        let (sum, carry_out) = Adder::compute_sum(bit_width, value_a, &not_b, &not_borrow_in);
-        
-        // The result is the sum, and borrow_out is the complement of carry_out
-        (sum, carry_out.not())
+        let (difference, carry_out) = Adder::compute_sum(bit_width, a, &!b, !borrow_in);
+        (difference, !carry_out)
     }
-    
+
     /// Get the current bit width
     pub fn bit_width(&self) -> BusWidth {
         self.bit_width
     }
-    
+
     /// Set the bit width (updates pin widths accordingly)
     pub fn set_bit_width(&mut self, width: BusWidth) {
         self.bit_width = width;
-        if let Some(pin) = self.pins.get_mut("A") {
-            pin.set_width(width);
-        }
-        if let Some(pin) = self.pins.get_mut("B") {
-            pin.set_width(width);
-        }
-        if let Some(pin) = self.pins.get_mut("Difference") {
-            pin.set_width(width);
+        for name in ["A", "B", "Difference"] {
+            if let Some(pin) = self.pins.get_mut(name) {
+                pin.width = width;
+                pin.signal = Signal::unknown(width);
+            }
         }
     }
 }
@@ -104,7 +108,7 @@ impl Component for Subtractor {
     }
 
     fn name(&self) -> &str {
-        "Subtractor"
+        SUBTRACTOR_ID
     }
 
     fn pins(&self) -> &HashMap<String, Pin> {
@@ -115,59 +119,42 @@ impl Component for Subtractor {
         &mut self.pins
     }
 
-    fn update(&mut self, current_time: Timestamp) -> UpdateResult {
-        // Get input values
-        let value_a = self.pins.get("A").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        let value_b = self.pins.get("B").map(|p| p.signal().value()).unwrap_or(Value::Unknown);
-        let borrow_in = self.pins.get("Borrow_In").map(|p| p.signal().value()).unwrap_or(Value::Low);
-        
-        // Compute difference and borrow out
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let borrow_in = self.pins["Borrow_In"]
+            .signal
+            .as_single()
+            .unwrap_or(Value::Unknown);
         let (difference, borrow_out) = Self::compute_difference(
-            self.bit_width, 
-            &value_a, 
-            &value_b, 
-            &borrow_in
+            self.bit_width,
+            &self.pins["A"].signal,
+            &self.pins["B"].signal,
+            borrow_in,
         );
-        
-        // Update output pins
-        let mut changed = false;
-        if let Some(diff_pin) = self.pins.get_mut("Difference") {
-            if diff_pin.signal().value() != difference {
-                diff_pin.set_signal(Signal::new(difference, current_time));
-                changed = true;
-            }
-        }
-        
-        if let Some(borrow_pin) = self.pins.get_mut("Borrow_Out") {
-            if borrow_pin.signal().value() != borrow_out {
-                borrow_pin.set_signal(Signal::new(borrow_out, current_time));
-                changed = true;
-            }
-        }
-        
-        if changed {
-            UpdateResult::Changed
-        } else {
-            UpdateResult::NoChange
-        }
+
+        let mut result = UpdateResult::new();
+        result.add_output("Difference".to_string(), difference);
+        result.add_output("Borrow_Out".to_string(), Signal::new_single(borrow_out));
+        result.set_delay(self.propagation_delay());
+        result
     }
 
     fn reset(&mut self) {
-        // Reset all pins to their default states
         for pin in self.pins.values_mut() {
-            pin.reset();
+            pin.signal = Signal::unknown(pin.width);
         }
     }
-}
 
-impl Propagator for Subtractor {
-    fn propagate(&mut self, current_time: Timestamp) {
-        // Calculate propagation delay based on bit width (slightly more than adder)
-        let delay = (self.bit_width.0 + 4) * 1; // From Java: (data.getWidth() + 4) * Adder.PER_DELAY
-        let propagation_time = current_time + delay as u64;
-        
-        // Perform the update at the calculated time
-        self.update(propagation_time);
+    fn propagation_delay(&self) -> u64 {
+        // (width + 4) * PER_DELAY, as in Java
+        self.bit_width.0 as u64 + 4
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Arithmetic",
+            SUBTRACTOR_ID,
+            vec![("width", self.bit_width.0.to_string())],
+        ))
     }
 }
 
@@ -175,90 +162,59 @@ impl Propagator for Subtractor {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_subtractor_creation() {
-        let subtractor = Subtractor::new(ComponentId(1));
-        assert_eq!(subtractor.id(), ComponentId(1));
-        assert_eq!(subtractor.name(), "Subtractor");
-        assert_eq!(subtractor.bit_width(), BusWidth(8));
-        assert_eq!(subtractor.pins().len(), 5);
-    }
-
-    #[test]
-    fn test_subtractor_with_custom_width() {
-        let subtractor = Subtractor::new_with_width(ComponentId(1), BusWidth(16));
-        assert_eq!(subtractor.bit_width(), BusWidth(16));
-    }
-
-    #[test]
-    fn test_compute_difference_simple() {
-        let a = Value::from_long(10, BusWidth(8));
-        let b = Value::from_long(3, BusWidth(8));
-        let bin = Value::Low;
-        
-        let (diff, bout) = Subtractor::compute_difference(BusWidth(8), &a, &b, &bin);
-        
-        assert_eq!(diff.to_long_value(), 7);  // 10 - 3 = 7
-        assert_eq!(bout, Value::Low);
-    }
-
-    #[test]
-    fn test_compute_difference_with_borrow() {
-        let a = Value::from_long(3, BusWidth(8));
-        let b = Value::from_long(10, BusWidth(8));
-        let bin = Value::Low;
-        
-        let (diff, bout) = Subtractor::compute_difference(BusWidth(8), &a, &b, &bin);
-        
-        // 3 - 10 = -7, which in 8-bit two's complement is 249 (256 - 7)
-        assert_eq!(diff.to_long_value(), 249);
-        assert_eq!(bout, Value::High);
-    }
-
-    #[test]
-    fn test_compute_difference_with_borrow_in() {
-        let a = Value::from_long(10, BusWidth(8));
-        let b = Value::from_long(3, BusWidth(8));
-        let bin = Value::High;
-        
-        let (diff, bout) = Subtractor::compute_difference(BusWidth(8), &a, &b, &bin);
-        
-        assert_eq!(diff.to_long_value(), 6);  // 10 - 3 - 1 = 6
-        assert_eq!(bout, Value::Low);
-    }
-
-    #[test]
-    fn test_compute_difference_zero() {
-        let a = Value::from_long(5, BusWidth(8));
-        let b = Value::from_long(5, BusWidth(8));
-        let bin = Value::Low;
-        
-        let (diff, bout) = Subtractor::compute_difference(BusWidth(8), &a, &b, &bin);
-        
-        assert_eq!(diff.to_long_value(), 0);  // 5 - 5 = 0
-        assert_eq!(bout, Value::Low);
+    fn subtract(width: u32, a: u64, b: u64, borrow_in: Value) -> (Option<u64>, Value) {
+        let width = BusWidth(width);
+        let (difference, borrow) = Subtractor::compute_difference(
+            width,
+            &Signal::from_u64(a, width),
+            &Signal::from_u64(b, width),
+            borrow_in,
+        );
+        (difference.to_u64(), borrow)
     }
 
     #[test]
-    fn test_bit_width_change() {
-        let mut subtractor = Subtractor::new(ComponentId(1));
-        
-        subtractor.set_bit_width(BusWidth(16));
-        assert_eq!(subtractor.bit_width(), BusWidth(16));
-        
-        // Check that pin widths were updated
-        assert_eq!(subtractor.pins().get("A").unwrap().width(), BusWidth(16));
-        assert_eq!(subtractor.pins().get("B").unwrap().width(), BusWidth(16));
-        assert_eq!(subtractor.pins().get("Difference").unwrap().width(), BusWidth(16));
-        assert_eq!(subtractor.pins().get("Borrow_In").unwrap().width(), BusWidth(1));
-        assert_eq!(subtractor.pins().get("Borrow_Out").unwrap().width(), BusWidth(1));
+    fn test_compute_difference() {
+        assert_eq!(subtract(8, 5, 3, Value::Low), (Some(2), Value::Low));
+        assert_eq!(subtract(8, 3, 5, Value::Low), (Some(254), Value::High));
+        assert_eq!(subtract(8, 5, 3, Value::High), (Some(1), Value::Low));
+        assert_eq!(subtract(8, 5, 5, Value::High), (Some(255), Value::High));
+        // A floating borrow in counts as zero
+        assert_eq!(subtract(8, 5, 3, Value::HighZ), (Some(2), Value::Low));
     }
 
     #[test]
-    fn test_component_reset() {
-        let mut subtractor = Subtractor::new(ComponentId(1));
+    fn test_subtractor_update() {
+        let mut subtractor = Subtractor::new_with_width(ComponentId(1), BusWidth(4));
+        assert_eq!(subtractor.name(), "Subtractor");
+        assert_eq!(subtractor.propagation_delay(), 8);
         subtractor.reset();
-        // Should not panic and should reset all pins
-        assert_eq!(subtractor.pins().len(), 5);
+        for (name, signal) in [
+            ("A", Signal::from_u64(9, BusWidth(4))),
+            ("B", Signal::from_u64(2, BusWidth(4))),
+        ] {
+            subtractor
+                .get_pin_mut(name)
+                .unwrap()
+                .set_signal(signal)
+                .unwrap();
+        }
+        let result = subtractor.update(Timestamp(0));
+        assert_eq!(result.outputs["Difference"].to_u64(), Some(7));
+        assert_eq!(result.outputs["Borrow_Out"].as_single(), Some(Value::Low));
+
+        // An unknown operand bit makes every bit from there on unknown
+        let b = Signal::new_bus(vec![Value::Low, Value::Unknown, Value::Low, Value::Low]);
+        subtractor.get_pin_mut("B").unwrap().set_signal(b).unwrap();
+        let result = subtractor.update(Timestamp(0));
+        assert_eq!(result.outputs["Difference"].get_bit(0), Some(Value::High));
+        assert_eq!(
+            result.outputs["Difference"].get_bit(1),
+            Some(Value::Unknown)
+        );
+        assert_eq!(
+            result.outputs["Borrow_Out"].as_single(),
+            Some(Value::Unknown)
+        );
     }
-}
\ No newline at end of file
+}
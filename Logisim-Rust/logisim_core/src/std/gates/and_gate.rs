@@ -11,10 +11,12 @@
 //!
 //! Rust port of `com.cburch.logisim.std.gates.AndGate`
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
-use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use super::negated_inputs::{circ_tool, input_lanes, input_signal, set_negated};
+use crate::batch::fold_inputs;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// AND Gate implementation
 ///
@@ -24,6 +26,9 @@ use std::collections::HashMap;
 pub struct AndGate {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    /// Inputs with a bubble, by pin name
+    #[serde(default)]
+    negated: BTreeSet<String>,
 }
 
 impl AndGate {
@@ -34,7 +39,11 @@ impl AndGate {
         pins.insert("B".to_string(), Pin::new_input("B", BusWidth(1)));
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        AndGate { id, pins }
+        AndGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
     }
 
     /// Create a new AND gate with configurable number of inputs
@@ -56,7 +65,24 @@ impl AndGate {
         // Add output pin
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        AndGate { id, pins }
+        AndGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
+    }
+
+    /// Set the data bit width of every input and the output
+    pub fn set_width(&mut self, width: BusWidth) {
+        for pin in self.pins.values_mut() {
+            pin.width = width;
+            pin.signal = Signal::unknown(width);
+        }
+    }
+
+    /// Negate the `index`th input before the gate combines it
+    pub fn set_negated(&mut self, index: usize, negate: bool) {
+        set_negated(&mut self.negated, index, negate);
     }
 }
 
@@ -78,27 +104,16 @@ impl Component for AndGate {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get all input values
-        let mut inputs = Vec::new();
+        // Compute the bitwise AND of all inputs across the data width
+        let width = self.pins["Y"].width;
+        let mut output_signal = Signal::all_high(width);
         for (name, pin) in &self.pins {
             if name != "Y" {
                 // Skip output pin
-                let value = pin.signal.as_single().unwrap_or(Value::Unknown);
-                inputs.push(value);
+                output_signal = output_signal.and(&input_signal(&self.negated, name, &pin.signal));
             }
         }
 
-        // Compute AND of all inputs
-        let mut output = Value::High;
-        for &input in &inputs {
-            output = output.and(input);
-            if output == Value::Low {
-                break; // Short circuit - if any input is low, output is low
-            }
-        }
-
-        let output_signal = Signal::new_single(output);
-
         let mut result = UpdateResult::new();
         result.add_output("Y".to_string(), output_signal.clone());
         result.set_delay(self.propagation_delay());
@@ -117,9 +132,27 @@ impl Component for AndGate {
         }
     }
 
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let width = self.pins["Y"].width.0 as usize;
+        let output = fold_inputs(
+            &input_lanes(&self.negated, inputs),
+            width,
+            Value::High,
+            Lanes::and,
+        );
+        Some(HashMap::from([("Y".to_string(), output)]))
+    }
+
     fn propagation_delay(&self) -> u64 {
         2 // 2 time units for AND gate
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(circ_tool("AND Gate", &self.pins, &self.negated))
+    }
 }
 
 #[cfg(test)]
@@ -157,7 +190,7 @@ mod tests {
                 .unwrap();
 
             let result = gate.update(Timestamp(0));
-            let outputs = result.get_outputs();
+            let outputs = result.outputs;
 
             if let Some(output_signal) = outputs.get("Y") {
                 let output_value = output_signal.as_single().unwrap();
@@ -191,7 +224,7 @@ mod tests {
             .unwrap();
 
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
@@ -204,11 +237,36 @@ mod tests {
             .set_signal(Signal::new_single(Value::Low))
             .unwrap();
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
             assert_eq!(output_value, Value::Low);
         }
     }
+
+    #[test]
+    fn test_and_gate_negated_input() {
+        let mut gate = AndGate::new(ComponentId(1));
+        gate.set_negated(1, true);
+        for (b, expected) in [(Value::Low, Value::High), (Value::High, Value::Low)] {
+            gate.get_pin_mut("A")
+                .unwrap()
+                .set_signal(Signal::new_single(Value::High))
+                .unwrap();
+            gate.get_pin_mut("B")
+                .unwrap()
+                .set_signal(Signal::new_single(b))
+                .unwrap();
+            let result = gate.update(Timestamp(0));
+            assert_eq!(result.outputs["Y"].as_single(), Some(expected));
+        }
+
+        let inputs = HashMap::from([
+            ("A".to_string(), vec![Lanes::splat(Value::High)]),
+            ("B".to_string(), vec![Lanes::splat(Value::Low)]),
+        ]);
+        let outputs = gate.evaluate_lanes(&inputs).unwrap();
+        assert_eq!(outputs["Y"], vec![Lanes::splat(Value::High)]);
+    }
 }
@@ -10,22 +10,35 @@
 //! Buffer Implementation
 //!
 //! Rust port of `com.cburch.logisim.std.gates.Buffer`
-//! TODO: Full implementation needed
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// Buffer gate implementation (placeholder)
+/// Buffer gate implementation
 ///
 /// A buffer simply passes its input to its output, potentially with some delay.
 /// This is commonly used for signal buffering and driving capability.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Buffer {
     id: ComponentId,
+    pins: HashMap<String, Pin>,
 }
 
 impl Buffer {
+    /// Create a new buffer
     pub fn new(id: ComponentId) -> Self {
-        Buffer { id }
+        Self::new_with_width(id, BusWidth(1))
+    }
+
+    /// Create a new buffer with configurable bit width
+    pub fn new_with_width(id: ComponentId, width: BusWidth) -> Self {
+        let mut pins = HashMap::new();
+        pins.insert("A".to_string(), Pin::new_input("A", width));
+        pins.insert("Y".to_string(), Pin::new_output("Y", width));
+
+        Buffer { id, pins }
     }
 }
 
@@ -38,23 +51,73 @@ impl Component for Buffer {
         "Buffer"
     }
 
-    fn pins(&self) -> &std::collections::HashMap<String, Pin> {
-        todo!("Buffer implementation needed")
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
     }
 
-    fn pins_mut(&mut self) -> &mut std::collections::HashMap<String, Pin> {
-        todo!("Buffer implementation needed")
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
     }
 
-    fn update(&mut self, _current_time: crate::signal::Timestamp) -> UpdateResult {
-        todo!("Buffer implementation needed")
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let output_signal = self.pins["A"].signal.clone();
+
+        let mut result = UpdateResult::new();
+        result.add_output("Y".to_string(), output_signal.clone());
+        result.set_delay(self.propagation_delay());
+
+        if let Some(pin) = self.pins.get_mut("Y") {
+            let _ = pin.set_signal(output_signal);
+        }
+
+        result
     }
 
     fn reset(&mut self) {
-        todo!("Buffer implementation needed")
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let output = inputs.get("A")?.clone();
+        Some(HashMap::from([("Y".to_string(), output)]))
     }
 
     fn propagation_delay(&self) -> u64 {
         1
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let width = self.pins["Y"].width;
+        Some(CircTool::new(
+            "#Gates",
+            "Buffer",
+            vec![("width", width.0.to_string())],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::Value;
+
+    #[test]
+    fn test_buffer_passes_input() {
+        let mut gate = Buffer::new_with_width(ComponentId(1), BusWidth(2));
+        assert_eq!(gate.name(), "Buffer");
+        assert_eq!(gate.pins().len(), 2);
+
+        let input = Signal::new_bus(vec![Value::High, Value::HighZ]);
+        gate.get_pin_mut("A")
+            .unwrap()
+            .set_signal(input.clone())
+            .unwrap();
+        let result = gate.update(Timestamp(0));
+        assert_eq!(result.outputs["Y"], input);
+    }
 }
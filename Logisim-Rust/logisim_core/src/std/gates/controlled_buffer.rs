@@ -10,22 +10,58 @@
 //! Controlled Buffer Implementation
 //!
 //! Rust port of `com.cburch.logisim.std.gates.ControlledBuffer`
-//! TODO: Full implementation needed
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// Controlled buffer implementation (placeholder)
+/// Controlled buffer (tri-state buffer) implementation
 ///
-/// A controlled buffer (tri-state buffer) passes its input to output when enabled,
-/// otherwise presents high impedance.
-#[derive(Debug)]
+/// Passes input `A` to output `Y` while the `EN` control input is high and
+/// releases the output (high impedance) while it is low, so several buffers
+/// can share one bus. As a controlled inverter it passes the complement of
+/// `A` instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControlledBuffer {
     id: ComponentId,
+    pins: HashMap<String, Pin>,
+    /// Whether the input is inverted, making this a Controlled Inverter
+    #[serde(default)]
+    inverter: bool,
 }
 
 impl ControlledBuffer {
+    /// Create a new controlled buffer
     pub fn new(id: ComponentId) -> Self {
-        ControlledBuffer { id }
+        Self::new_with_width(id, BusWidth(1))
+    }
+
+    /// Create a new controlled buffer with configurable data width
+    pub fn new_with_width(id: ComponentId, width: BusWidth) -> Self {
+        let mut pins = HashMap::new();
+        pins.insert("A".to_string(), Pin::new_input("A", width));
+        pins.insert("EN".to_string(), Pin::new_input("EN", BusWidth(1)));
+        pins.insert("Y".to_string(), Pin::new_output("Y", width));
+
+        ControlledBuffer {
+            id,
+            pins,
+            inverter: false,
+        }
+    }
+
+    /// Create a new controlled inverter with configurable data width
+    pub fn new_inverter_with_width(id: ComponentId, width: BusWidth) -> Self {
+        Self {
+            inverter: true,
+            ..Self::new_with_width(id, width)
+        }
+    }
+
+    /// Check whether the input is inverted
+    pub fn is_inverter(&self) -> bool {
+        self.inverter
     }
 }
 
@@ -35,26 +71,119 @@ impl Component for ControlledBuffer {
     }
 
     fn name(&self) -> &str {
-        "Controlled Buffer"
+        if self.inverter {
+            "Controlled Inverter"
+        } else {
+            "Controlled Buffer"
+        }
     }
 
-    fn pins(&self) -> &std::collections::HashMap<String, Pin> {
-        todo!("ControlledBuffer implementation needed")
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
     }
 
-    fn pins_mut(&mut self) -> &mut std::collections::HashMap<String, Pin> {
-        todo!("ControlledBuffer implementation needed")
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
     }
 
-    fn update(&mut self, _current_time: crate::signal::Timestamp) -> UpdateResult {
-        todo!("ControlledBuffer implementation needed")
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let width = self.pins["Y"].width;
+        let output_signal = match self.pins["EN"].signal.as_single() {
+            Some(Value::High) if self.inverter => !&self.pins["A"].signal,
+            Some(Value::High) => self.pins["A"].signal.clone(),
+            Some(Value::Low) => Signal::high_z(width),
+            Some(Value::Error) => Signal::error(width),
+            // An undefined or floating control line leaves the output undefined
+            _ => Signal::unknown(width),
+        };
+
+        let mut result = UpdateResult::new();
+        result.add_output("Y".to_string(), output_signal.clone());
+        result.set_delay(self.propagation_delay());
+
+        if let Some(pin) = self.pins.get_mut("Y") {
+            let _ = pin.set_signal(output_signal);
+        }
+
+        result
     }
 
     fn reset(&mut self) {
-        todo!("ControlledBuffer implementation needed")
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let enable = inputs.get("EN")?.first().copied().unwrap_or_default();
+        let output = inputs
+            .get("A")?
+            .iter()
+            .map(|&word| {
+                let word = if self.inverter { !word } else { word };
+                // Undefined or floating control lanes leave the output undefined
+                let output = word.select(enable.high, Lanes::default());
+                let output = Lanes::splat(Value::HighZ).select(enable.low, output);
+                Lanes::splat(Value::Error).select(enable.error, output)
+            })
+            .collect();
+        Some(HashMap::from([("Y".to_string(), output)]))
     }
 
     fn propagation_delay(&self) -> u64 {
         2
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let width = self.pins["Y"].width;
+        Some(CircTool::new(
+            "#Gates",
+            self.name(),
+            vec![("width", width.0.to_string())],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drive(buffer: &mut ControlledBuffer, a: Signal, en: Value) -> Signal {
+        buffer.get_pin_mut("A").unwrap().set_signal(a).unwrap();
+        buffer
+            .get_pin_mut("EN")
+            .unwrap()
+            .set_signal(Signal::new_single(en))
+            .unwrap();
+        buffer.update(Timestamp(0)).outputs["Y"].clone()
+    }
+
+    #[test]
+    fn test_controlled_buffer_enable() {
+        let mut buffer = ControlledBuffer::new_with_width(ComponentId(1), BusWidth(4));
+        let data = Signal::from_u64(0b1001, BusWidth(4));
+
+        assert_eq!(drive(&mut buffer, data.clone(), Value::High), data);
+        assert!(drive(&mut buffer, data.clone(), Value::Low).is_high_z());
+        assert_eq!(
+            drive(&mut buffer, data.clone(), Value::HighZ),
+            Signal::unknown(BusWidth(4))
+        );
+        assert!(drive(&mut buffer, data, Value::Error).has_error());
+    }
+
+    #[test]
+    fn test_controlled_inverter() {
+        let mut inverter = ControlledBuffer::new_inverter_with_width(ComponentId(1), BusWidth(4));
+        assert_eq!(inverter.name(), "Controlled Inverter");
+        let data = Signal::from_u64(0b1001, BusWidth(4));
+        assert_eq!(
+            drive(&mut inverter, data.clone(), Value::High).to_u64(),
+            Some(0b0110)
+        );
+        assert!(drive(&mut inverter, data, Value::Low).is_high_z());
+    }
 }
@@ -34,6 +34,7 @@ mod controlled_buffer;
 mod even_parity;
 mod gates_library;
 mod nand_gate;
+mod negated_inputs;
 mod nor_gate;
 mod not_gate;
 mod odd_parity;
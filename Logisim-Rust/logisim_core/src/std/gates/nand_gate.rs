@@ -11,10 +11,12 @@
 //!
 //! Rust port of `com.cburch.logisim.std.gates.NandGate`
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
-use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use super::negated_inputs::{circ_tool, input_lanes, input_signal, set_negated};
+use crate::batch::fold_inputs;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// NAND Gate implementation
 ///
@@ -24,6 +26,9 @@ use std::collections::HashMap;
 pub struct NandGate {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    /// Inputs with a bubble, by pin name
+    #[serde(default)]
+    negated: BTreeSet<String>,
 }
 
 impl NandGate {
@@ -34,7 +39,11 @@ impl NandGate {
         pins.insert("B".to_string(), Pin::new_input("B", BusWidth(1)));
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        NandGate { id, pins }
+        NandGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
     }
 
     /// Create a new NAND gate with configurable number of inputs
@@ -56,7 +65,24 @@ impl NandGate {
         // Add output pin
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        NandGate { id, pins }
+        NandGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
+    }
+
+    /// Set the data bit width of every input and the output
+    pub fn set_width(&mut self, width: BusWidth) {
+        for pin in self.pins.values_mut() {
+            pin.width = width;
+            pin.signal = Signal::unknown(width);
+        }
+    }
+
+    /// Negate the `index`th input before the gate combines it
+    pub fn set_negated(&mut self, index: usize, negate: bool) {
+        set_negated(&mut self.negated, index, negate);
     }
 }
 
@@ -78,27 +104,17 @@ impl Component for NandGate {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get all input values
-        let mut inputs = Vec::new();
+        // Compute the bitwise NAND of all inputs (NOT(AND(inputs)))
+        let width = self.pins["Y"].width;
+        let mut and_result = Signal::all_high(width);
         for (name, pin) in &self.pins {
             if name != "Y" {
                 // Skip output pin
-                let value = pin.signal.as_single().unwrap_or(Value::Unknown);
-                inputs.push(value);
+                and_result = and_result.and(&input_signal(&self.negated, name, &pin.signal));
             }
         }
 
-        // Compute NAND of all inputs (NOT(AND(inputs)))
-        let mut and_result = Value::High;
-        for &input in &inputs {
-            and_result = and_result.and(input);
-            if and_result == Value::Low {
-                break; // Short circuit - if any input is low, AND is low
-            }
-        }
-
-        let output = and_result.not(); // Invert the AND result
-        let output_signal = Signal::new_single(output);
+        let output_signal = !and_result; // Invert the AND result
 
         let mut result = UpdateResult::new();
         result.add_output("Y".to_string(), output_signal.clone());
@@ -118,9 +134,28 @@ impl Component for NandGate {
         }
     }
 
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let width = self.pins["Y"].width.0 as usize;
+        let output = fold_inputs(
+            &input_lanes(&self.negated, inputs),
+            width,
+            Value::High,
+            Lanes::and,
+        );
+        let output = output.into_iter().map(|word| !word).collect();
+        Some(HashMap::from([("Y".to_string(), output)]))
+    }
+
     fn propagation_delay(&self) -> u64 {
         2 // 2 time units for NAND gate
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(circ_tool("NAND Gate", &self.pins, &self.negated))
+    }
 }
 
 #[cfg(test)]
@@ -158,7 +193,7 @@ mod tests {
                 .unwrap();
 
             let result = gate.update(Timestamp(0));
-            let outputs = result.get_outputs();
+            let outputs = result.outputs;
 
             if let Some(output_signal) = outputs.get("Y") {
                 let output_value = output_signal.as_single().unwrap();
@@ -192,7 +227,7 @@ mod tests {
             .unwrap();
 
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
@@ -205,7 +240,7 @@ mod tests {
             .set_signal(Signal::new_single(Value::Low))
             .unwrap();
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
@@ -0,0 +1,96 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Negated gate inputs
+//!
+//! Each input of a multi-input gate can carry a bubble (the `negate0`,
+//! `negate1`, ... attributes) that inverts it before the gate combines it,
+//! as `AbstractGate.propagate` does.
+
+use crate::comp::{CircTool, Pin};
+use crate::signal::{Lanes, Signal};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+
+/// Name of the `index`th input pin of a multi-input gate
+pub(crate) fn input_name(index: usize) -> String {
+    match index {
+        0 => "A".to_string(),
+        1 => "B".to_string(),
+        i => format!("I{}", i),
+    }
+}
+
+/// Add or remove the bubble on the `index`th input
+pub(crate) fn set_negated(negated: &mut BTreeSet<String>, index: usize, negate: bool) {
+    if negate {
+        negated.insert(input_name(index));
+    } else {
+        negated.remove(&input_name(index));
+    }
+}
+
+/// The value an input pin feeds into the gate
+pub(crate) fn input_signal<'a>(
+    negated: &BTreeSet<String>,
+    name: &str,
+    signal: &'a Signal,
+) -> Cow<'a, Signal> {
+    if negated.contains(name) {
+        Cow::Owned(!signal)
+    } else {
+        Cow::Borrowed(signal)
+    }
+}
+
+/// The lanes the input pins feed into the gate
+pub(crate) fn input_lanes<'a>(
+    negated: &BTreeSet<String>,
+    inputs: &'a HashMap<String, Vec<Lanes>>,
+) -> Cow<'a, HashMap<String, Vec<Lanes>>> {
+    if negated.is_empty() {
+        return Cow::Borrowed(inputs);
+    }
+    Cow::Owned(
+        inputs
+            .iter()
+            .map(|(name, words)| {
+                let words = if negated.contains(name) {
+                    words.iter().map(|&word| !word).collect()
+                } else {
+                    words.clone()
+                };
+                (name.clone(), words)
+            })
+            .collect(),
+    )
+}
+
+/// The .circ tool of a multi-input gate, with its number of inputs, data
+/// width and bubbles
+pub(crate) fn circ_tool(
+    name: &str,
+    pins: &HashMap<String, Pin>,
+    negated: &BTreeSet<String>,
+) -> CircTool {
+    let inputs = pins.len() - 1;
+    let mut tool = CircTool::new(
+        "#Gates",
+        name,
+        vec![
+            ("inputs", inputs.to_string()),
+            ("width", pins["Y"].width.0.to_string()),
+        ],
+    );
+    for index in (0..inputs).filter(|&index| negated.contains(&input_name(index))) {
+        tool.attributes
+            .push((format!("negate{}", index), "true".to_string()));
+    }
+    tool
+}
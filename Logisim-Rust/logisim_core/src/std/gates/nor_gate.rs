@@ -11,10 +11,12 @@
 //!
 //! Rust port of `com.cburch.logisim.std.gates.NorGate`
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
-use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use super::negated_inputs::{circ_tool, input_lanes, input_signal, set_negated};
+use crate::batch::fold_inputs;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// NOR Gate implementation
 ///
@@ -24,6 +26,9 @@ use std::collections::HashMap;
 pub struct NorGate {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    /// Inputs with a bubble, by pin name
+    #[serde(default)]
+    negated: BTreeSet<String>,
 }
 
 impl NorGate {
@@ -34,7 +39,11 @@ impl NorGate {
         pins.insert("B".to_string(), Pin::new_input("B", BusWidth(1)));
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        NorGate { id, pins }
+        NorGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
     }
 
     /// Create a new NOR gate with configurable number of inputs
@@ -56,7 +65,24 @@ impl NorGate {
         // Add output pin
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        NorGate { id, pins }
+        NorGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
+    }
+
+    /// Set the data bit width of every input and the output
+    pub fn set_width(&mut self, width: BusWidth) {
+        for pin in self.pins.values_mut() {
+            pin.width = width;
+            pin.signal = Signal::unknown(width);
+        }
+    }
+
+    /// Negate the `index`th input before the gate combines it
+    pub fn set_negated(&mut self, index: usize, negate: bool) {
+        set_negated(&mut self.negated, index, negate);
     }
 }
 
@@ -78,27 +104,17 @@ impl Component for NorGate {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get all input values
-        let mut inputs = Vec::new();
+        // Compute the bitwise NOR of all inputs (NOT(OR(inputs)))
+        let width = self.pins["Y"].width;
+        let mut or_result = Signal::all_low(width);
         for (name, pin) in &self.pins {
             if name != "Y" {
                 // Skip output pin
-                let value = pin.signal.as_single().unwrap_or(Value::Unknown);
-                inputs.push(value);
+                or_result = or_result.or(&input_signal(&self.negated, name, &pin.signal));
             }
         }
 
-        // Compute NOR of all inputs (NOT(OR(inputs)))
-        let mut or_result = Value::Low;
-        for &input in &inputs {
-            or_result = or_result.or(input);
-            if or_result == Value::High {
-                break; // Short circuit - if any input is high, OR is high
-            }
-        }
-
-        let output = or_result.not(); // Invert the OR result
-        let output_signal = Signal::new_single(output);
+        let output_signal = !or_result; // Invert the OR result
 
         let mut result = UpdateResult::new();
         result.add_output("Y".to_string(), output_signal.clone());
@@ -118,9 +134,28 @@ impl Component for NorGate {
         }
     }
 
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let width = self.pins["Y"].width.0 as usize;
+        let output = fold_inputs(
+            &input_lanes(&self.negated, inputs),
+            width,
+            Value::Low,
+            Lanes::or,
+        );
+        let output = output.into_iter().map(|word| !word).collect();
+        Some(HashMap::from([("Y".to_string(), output)]))
+    }
+
     fn propagation_delay(&self) -> u64 {
         2 // 2 time units for NOR gate
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(circ_tool("NOR Gate", &self.pins, &self.negated))
+    }
 }
 
 #[cfg(test)]
@@ -158,7 +193,7 @@ mod tests {
                 .unwrap();
 
             let result = gate.update(Timestamp(0));
-            let outputs = result.get_outputs();
+            let outputs = result.outputs;
 
             if let Some(output_signal) = outputs.get("Y") {
                 let output_value = output_signal.as_single().unwrap();
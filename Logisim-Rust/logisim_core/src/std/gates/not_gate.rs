@@ -11,8 +11,8 @@
 //!
 //! Rust port of `com.cburch.logisim.std.gates.NotGate`
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
-use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -64,16 +64,8 @@ impl Component for NotGate {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        let a = self
-            .pins
-            .get("A")
-            .unwrap()
-            .signal
-            .as_single()
-            .unwrap_or(Value::Unknown);
-
-        let output = a.not();
-        let output_signal = Signal::new_single(output);
+        // Invert every bit of the input across the data width
+        let output_signal = !&self.pins["A"].signal;
 
         let mut result = UpdateResult::new();
         result.add_output("Y".to_string(), output_signal.clone());
@@ -92,14 +84,32 @@ impl Component for NotGate {
         }
     }
 
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let output = inputs.get("A")?.iter().map(|&word| !word).collect();
+        Some(HashMap::from([("Y".to_string(), output)]))
+    }
+
     fn propagation_delay(&self) -> u64 {
         1 // 1 time unit for NOT gate
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let width = self.pins["Y"].width;
+        Some(CircTool::new(
+            "#Gates",
+            "NOT Gate",
+            vec![("width", width.0.to_string())],
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::signal::Value;
 
     #[test]
     fn test_not_gate_creation() {
@@ -127,7 +137,7 @@ mod tests {
                 .unwrap();
 
             let result = gate.update(Timestamp(0));
-            let outputs = result.get_outputs();
+            let outputs = result.outputs;
 
             if let Some(output_signal) = outputs.get("Y") {
                 let output_value = output_signal.as_single().unwrap();
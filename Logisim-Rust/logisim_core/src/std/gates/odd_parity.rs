@@ -10,50 +10,107 @@
 //! Odd Parity Gate Implementation
 //!
 //! Rust port of `com.cburch.logisim.std.gates.OddParityGate`
-//! TODO: Full implementation needed
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
+use super::XorGate;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// Odd parity gate implementation (placeholder)
+/// Odd parity gate implementation
 ///
-/// Outputs high when an odd number of inputs are high.
-#[derive(Debug)]
+/// Outputs high when an odd number of inputs are high. Unlike the XOR gate
+/// in Logisim, it never has a one-input-high mode, so it computes the
+/// same function as the XOR gate here and only differs in name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OddParityGate {
-    id: ComponentId,
+    gate: XorGate,
 }
 
 impl OddParityGate {
+    /// Create a new 2-input odd parity gate
     pub fn new(id: ComponentId) -> Self {
-        OddParityGate { id }
+        Self::new_with_inputs(id, 2)
+    }
+
+    /// Create a new odd parity gate with configurable number of inputs
+    pub fn new_with_inputs(id: ComponentId, num_inputs: usize) -> Self {
+        OddParityGate {
+            gate: XorGate::new_with_inputs(id, num_inputs),
+        }
+    }
+
+    /// Set the data bit width of every input and the output
+    pub fn set_width(&mut self, width: BusWidth) {
+        self.gate.set_width(width);
+    }
+
+    /// Negate the `index`th input before the gate combines it
+    pub fn set_negated(&mut self, index: usize, negate: bool) {
+        self.gate.set_negated(index, negate);
     }
 }
 
 impl Component for OddParityGate {
     fn id(&self) -> ComponentId {
-        self.id
+        self.gate.id()
     }
 
     fn name(&self) -> &str {
         "Odd Parity"
     }
 
-    fn pins(&self) -> &std::collections::HashMap<String, Pin> {
-        todo!("OddParityGate implementation needed")
+    fn pins(&self) -> &HashMap<String, Pin> {
+        self.gate.pins()
     }
 
-    fn pins_mut(&mut self) -> &mut std::collections::HashMap<String, Pin> {
-        todo!("OddParityGate implementation needed")
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        self.gate.pins_mut()
     }
 
-    fn update(&mut self, _current_time: crate::signal::Timestamp) -> UpdateResult {
-        todo!("OddParityGate implementation needed")
+    fn update(&mut self, current_time: Timestamp) -> UpdateResult {
+        self.gate.update(current_time)
     }
 
     fn reset(&mut self) {
-        todo!("OddParityGate implementation needed")
+        self.gate.reset();
+    }
+
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        self.gate.evaluate_lanes(inputs)
     }
 
     fn propagation_delay(&self) -> u64 {
-        3
+        self.gate.propagation_delay()
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let mut tool = self.gate.circ_tool()?;
+        tool.name = self.name().to_string();
+        Some(tool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::{Signal, Value};
+
+    #[test]
+    fn test_odd_parity_of_three_inputs() {
+        let mut gate = OddParityGate::new_with_inputs(ComponentId(1), 3);
+        assert_eq!(gate.name(), "Odd Parity");
+        assert_eq!(gate.pins().len(), 4);
+        for name in ["A", "B", "I2"] {
+            gate.get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::new_single(Value::High))
+                .unwrap();
+        }
+        let result = gate.update(Timestamp(0));
+        assert_eq!(result.outputs["Y"].as_single(), Some(Value::High));
     }
 }
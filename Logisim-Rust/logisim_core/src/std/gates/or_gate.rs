@@ -11,10 +11,12 @@
 //!
 //! Rust port of `com.cburch.logisim.std.gates.OrGate`
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
-use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use super::negated_inputs::{circ_tool, input_lanes, input_signal, set_negated};
+use crate::batch::fold_inputs;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// OR Gate implementation
 ///
@@ -24,6 +26,9 @@ use std::collections::HashMap;
 pub struct OrGate {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    /// Inputs with a bubble, by pin name
+    #[serde(default)]
+    negated: BTreeSet<String>,
 }
 
 impl OrGate {
@@ -34,7 +39,11 @@ impl OrGate {
         pins.insert("B".to_string(), Pin::new_input("B", BusWidth(1)));
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        OrGate { id, pins }
+        OrGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
     }
 
     /// Create a new OR gate with configurable number of inputs
@@ -56,7 +65,24 @@ impl OrGate {
         // Add output pin
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        OrGate { id, pins }
+        OrGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
+    }
+
+    /// Set the data bit width of every input and the output
+    pub fn set_width(&mut self, width: BusWidth) {
+        for pin in self.pins.values_mut() {
+            pin.width = width;
+            pin.signal = Signal::unknown(width);
+        }
+    }
+
+    /// Negate the `index`th input before the gate combines it
+    pub fn set_negated(&mut self, index: usize, negate: bool) {
+        set_negated(&mut self.negated, index, negate);
     }
 }
 
@@ -78,27 +104,16 @@ impl Component for OrGate {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get all input values
-        let mut inputs = Vec::new();
+        // Compute the bitwise OR of all inputs across the data width
+        let width = self.pins["Y"].width;
+        let mut output_signal = Signal::all_low(width);
         for (name, pin) in &self.pins {
             if name != "Y" {
                 // Skip output pin
-                let value = pin.signal.as_single().unwrap_or(Value::Unknown);
-                inputs.push(value);
+                output_signal = output_signal.or(&input_signal(&self.negated, name, &pin.signal));
             }
         }
 
-        // Compute OR of all inputs
-        let mut output = Value::Low;
-        for &input in &inputs {
-            output = output.or(input);
-            if output == Value::High {
-                break; // Short circuit - if any input is high, output is high
-            }
-        }
-
-        let output_signal = Signal::new_single(output);
-
         let mut result = UpdateResult::new();
         result.add_output("Y".to_string(), output_signal.clone());
         result.set_delay(self.propagation_delay());
@@ -117,9 +132,27 @@ impl Component for OrGate {
         }
     }
 
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let width = self.pins["Y"].width.0 as usize;
+        let output = fold_inputs(
+            &input_lanes(&self.negated, inputs),
+            width,
+            Value::Low,
+            Lanes::or,
+        );
+        Some(HashMap::from([("Y".to_string(), output)]))
+    }
+
     fn propagation_delay(&self) -> u64 {
         1 // 1 time unit for OR gate
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(circ_tool("OR Gate", &self.pins, &self.negated))
+    }
 }
 
 #[cfg(test)]
@@ -157,7 +190,7 @@ mod tests {
                 .unwrap();
 
             let result = gate.update(Timestamp(0));
-            let outputs = result.get_outputs();
+            let outputs = result.outputs;
 
             if let Some(output_signal) = outputs.get("Y") {
                 let output_value = output_signal.as_single().unwrap();
@@ -191,7 +224,7 @@ mod tests {
             .unwrap();
 
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
@@ -204,7 +237,7 @@ mod tests {
             .set_signal(Signal::new_single(Value::High))
             .unwrap();
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
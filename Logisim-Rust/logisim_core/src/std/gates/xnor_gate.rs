@@ -11,10 +11,12 @@
 //!
 //! Rust port of `com.cburch.logisim.std.gates.XnorGate`
 
-use crate::comp::{Component, ComponentId, Pin, UpdateResult};
-use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use super::negated_inputs::{circ_tool, input_lanes, input_signal, set_negated};
+use crate::batch::parity;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Lanes, Signal, Timestamp, Value};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// XNOR Gate implementation
 ///
@@ -24,6 +26,9 @@ use std::collections::HashMap;
 pub struct XnorGate {
     id: ComponentId,
     pins: HashMap<String, Pin>,
+    /// Inputs with a bubble, by pin name
+    #[serde(default)]
+    negated: BTreeSet<String>,
 }
 
 impl XnorGate {
@@ -34,7 +39,11 @@ impl XnorGate {
         pins.insert("B".to_string(), Pin::new_input("B", BusWidth(1)));
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        XnorGate { id, pins }
+        XnorGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
     }
 
     /// Create a new XNOR gate with configurable number of inputs
@@ -56,7 +65,24 @@ impl XnorGate {
         // Add output pin
         pins.insert("Y".to_string(), Pin::new_output("Y", BusWidth(1)));
 
-        XnorGate { id, pins }
+        XnorGate {
+            id,
+            pins,
+            negated: BTreeSet::new(),
+        }
+    }
+
+    /// Set the data bit width of every input and the output
+    pub fn set_width(&mut self, width: BusWidth) {
+        for pin in self.pins.values_mut() {
+            pin.width = width;
+            pin.signal = Signal::unknown(width);
+        }
+    }
+
+    /// Negate the `index`th input before the gate combines it
+    pub fn set_negated(&mut self, index: usize, negate: bool) {
+        set_negated(&mut self.negated, index, negate);
     }
 }
 
@@ -78,37 +104,38 @@ impl Component for XnorGate {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get all input values
-        let mut inputs = Vec::new();
-        for (name, pin) in &self.pins {
-            if name != "Y" {
-                // Skip output pin
-                let value = pin.signal.as_single().unwrap_or(Value::Unknown);
-                inputs.push(value);
-            }
-        }
-
-        // Compute XNOR of all inputs (even parity / NOT(XOR))
-        let mut high_count = 0;
-        let mut has_unknown = false;
-
-        for &input in &inputs {
-            match input {
-                Value::High => high_count += 1,
-                Value::Unknown | Value::Error | Value::HighZ => has_unknown = true,
-                Value::Low => {} // No effect on XOR
-            }
-        }
-
-        let output = if has_unknown {
-            Value::Unknown
-        } else if high_count % 2 == 0 {
-            Value::High // Even parity for XNOR
-        } else {
-            Value::Low
-        };
-
-        let output_signal = Signal::new_single(output);
+        // Compute XNOR of all inputs bit by bit (even parity / NOT(XOR))
+        let width = self.pins["Y"].width;
+        let bits = (0..width.0)
+            .map(|bit| {
+                let mut high_count = 0;
+                let mut has_unknown = false;
+
+                for (name, pin) in &self.pins {
+                    if name == "Y" {
+                        continue; // Skip output pin
+                    }
+                    match input_signal(&self.negated, name, &pin.signal)
+                        .get_bit(bit)
+                        .unwrap_or(Value::Unknown)
+                    {
+                        Value::High => high_count += 1,
+                        Value::Unknown | Value::Error | Value::HighZ => has_unknown = true,
+                        Value::Low => {} // No effect on XOR
+                    }
+                }
+
+                if has_unknown {
+                    Value::Unknown
+                } else if high_count % 2 == 0 {
+                    Value::High
+                } else {
+                    Value::Low
+                }
+            })
+            .collect();
+
+        let output_signal = Signal::new_bus(bits);
 
         let mut result = UpdateResult::new();
         result.add_output("Y".to_string(), output_signal.clone());
@@ -128,9 +155,24 @@ impl Component for XnorGate {
         }
     }
 
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let width = self.pins["Y"].width.0 as usize;
+        Some(HashMap::from([(
+            "Y".to_string(),
+            parity(&input_lanes(&self.negated, inputs), width, false),
+        )]))
+    }
+
     fn propagation_delay(&self) -> u64 {
         3 // 3 time units for XNOR gate
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(circ_tool("XNOR Gate", &self.pins, &self.negated))
+    }
 }
 
 #[cfg(test)]
@@ -168,7 +210,7 @@ mod tests {
                 .unwrap();
 
             let result = gate.update(Timestamp(0));
-            let outputs = result.get_outputs();
+            let outputs = result.outputs;
 
             if let Some(output_signal) = outputs.get("Y") {
                 let output_value = output_signal.as_single().unwrap();
@@ -202,7 +244,7 @@ mod tests {
             .unwrap();
 
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
@@ -215,7 +257,7 @@ mod tests {
             .set_signal(Signal::new_single(Value::High))
             .unwrap();
         let result = gate.update(Timestamp(0));
-        let outputs = result.get_outputs();
+        let outputs = result.outputs;
 
         if let Some(output_signal) = outputs.get("Y") {
             let output_value = output_signal.as_single().unwrap();
@@ -0,0 +1,180 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Clock trigger detection
+//!
+//! This module implements clock edge and level detection equivalent to
+//! ClockState.java. Every sequential component keeps one of these per clock
+//! input and feeds it the value seen on its own clock pin.
+
+use crate::comp::TimingConstraints;
+use crate::signal::Value;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Condition on the clock input that lets a sequential component act
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Trigger {
+    /// Low to high transition
+    #[default]
+    Rising,
+    /// High to low transition
+    Falling,
+    /// Whenever the clock is high
+    HighLevel,
+    /// Whenever the clock is low
+    LowLevel,
+}
+
+impl Trigger {
+    /// Parse the value of a `trigger` attribute (`rising`, `falling`, `high`, `low`)
+    pub fn parse(s: &str) -> Result<Trigger, String> {
+        match s {
+            "rising" => Ok(Trigger::Rising),
+            "falling" => Ok(Trigger::Falling),
+            "high" => Ok(Trigger::HighLevel),
+            "low" => Ok(Trigger::LowLevel),
+            _ => Err(format!("Invalid trigger: {}", s)),
+        }
+    }
+
+    /// Check if this trigger reacts to clock edges rather than levels
+    pub fn is_edge(self) -> bool {
+        matches!(self, Trigger::Rising | Trigger::Falling)
+    }
+}
+
+impl fmt::Display for Trigger {
+    /// Write the value of the `trigger` attribute
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Trigger::Rising => "rising",
+            Trigger::Falling => "falling",
+            Trigger::HighLevel => "high",
+            Trigger::LowLevel => "low",
+        })
+    }
+}
+
+/// Last value seen on a clock input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockState {
+    last_clock: Value,
+}
+
+impl ClockState {
+    /// Create a clock state whose clock was last seen low
+    pub fn new() -> Self {
+        Self {
+            last_clock: Value::Low,
+        }
+    }
+
+    /// Record a new clock value and check whether it fires `trigger`
+    ///
+    /// Edges only count between defined levels, so a clock coming out of an
+    /// unknown or floating state does not trigger an edge.
+    pub fn update_clock(&mut self, new_clock: Value, trigger: Trigger) -> bool {
+        let old_clock = std::mem::replace(&mut self.last_clock, new_clock);
+        match trigger {
+            Trigger::Rising => old_clock == Value::Low && new_clock == Value::High,
+            Trigger::Falling => old_clock == Value::High && new_clock == Value::Low,
+            Trigger::HighLevel => new_clock == Value::High,
+            Trigger::LowLevel => new_clock == Value::Low,
+        }
+    }
+
+    /// Get the last clock value seen
+    pub fn last_clock(&self) -> Value {
+        self.last_clock
+    }
+}
+
+impl Default for ClockState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Setup and hold times of a sequential component's data inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetupHold {
+    /// Time the inputs must be stable before the active edge
+    pub setup: u64,
+    /// Time the inputs must stay stable after the active edge
+    pub hold: u64,
+}
+
+impl SetupHold {
+    /// Create setup and hold times
+    pub fn new(setup: u64, hold: u64) -> Self {
+        Self { setup, hold }
+    }
+
+    /// Build the constraints on `inputs` sampled by the `CLK` pin
+    ///
+    /// Level triggers have no edge to check against and yield `None`.
+    pub fn constraints(self, trigger: Trigger, inputs: &[&str]) -> Option<TimingConstraints> {
+        trigger.is_edge().then(|| TimingConstraints {
+            clock: "CLK".to_string(),
+            rising: trigger == Trigger::Rising,
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            setup: self.setup,
+            hold: self.hold,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_triggers() {
+        let mut rising = ClockState::new();
+        assert!(rising.update_clock(Value::High, Trigger::Rising));
+        assert!(!rising.update_clock(Value::High, Trigger::Rising));
+        assert!(!rising.update_clock(Value::Unknown, Trigger::Rising));
+        assert!(!rising.update_clock(Value::High, Trigger::Rising));
+        assert!(!rising.update_clock(Value::Low, Trigger::Rising));
+        assert!(rising.update_clock(Value::High, Trigger::Rising));
+
+        let mut falling = ClockState::new();
+        falling.update_clock(Value::High, Trigger::Falling);
+        assert!(falling.update_clock(Value::Low, Trigger::Falling));
+        assert!(!falling.update_clock(Value::Low, Trigger::Falling));
+    }
+
+    #[test]
+    fn test_level_triggers() {
+        let mut state = ClockState::new();
+        assert!(state.update_clock(Value::High, Trigger::HighLevel));
+        assert!(state.update_clock(Value::High, Trigger::HighLevel));
+        assert!(!state.update_clock(Value::Low, Trigger::HighLevel));
+        assert!(state.update_clock(Value::Low, Trigger::LowLevel));
+        assert!(!state.update_clock(Value::HighZ, Trigger::LowLevel));
+    }
+
+    #[test]
+    fn test_setup_hold_constraints() {
+        let times = SetupHold::new(3, 1);
+        let constraints = times.constraints(Trigger::Falling, &["D"]).unwrap();
+        assert!(!constraints.rising);
+        assert_eq!(constraints.inputs, vec!["D".to_string()]);
+        assert_eq!((constraints.setup, constraints.hold), (3, 1));
+        assert!(times.constraints(Trigger::HighLevel, &["D"]).is_none());
+    }
+
+    #[test]
+    fn test_trigger_parse() {
+        assert_eq!(Trigger::parse("falling"), Ok(Trigger::Falling));
+        assert_eq!(Trigger::parse("low"), Ok(Trigger::LowLevel));
+        assert!(Trigger::parse("sideways").is_err());
+    }
+}
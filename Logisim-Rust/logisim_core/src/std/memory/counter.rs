@@ -10,21 +10,295 @@
 //! Counter component
 //!
 //! This module implements counter functionality equivalent to Counter.java.
+//! Counters increment or decrement their value on clock edges.
 
-/// Counter component (placeholder for now)
+use crate::comp::{CircTool, Component, ComponentId, Pin, TimingConstraints, UpdateResult};
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::std::memory::{ClockState, SetupHold, Trigger};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Unique identifier for the Counter component
+/// Do NOT change as it will prevent project files from loading.
+pub const COUNTER_ID: &str = "Counter";
+
+/// What a counter does when it counts past its goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OnGoal {
+    /// Start again from the other end of the range
+    #[default]
+    Wrap,
+    /// Stay at the goal
+    Stay,
+    /// Keep counting through the whole data width
+    Continue,
+    /// Load the value on `IN`
+    Load,
+}
+
+impl OnGoal {
+    /// Parse the value of an `ongoal` attribute (`wrap`, `stay`, `continue`, `load`)
+    pub fn parse(s: &str) -> Result<OnGoal, String> {
+        match s {
+            "wrap" => Ok(OnGoal::Wrap),
+            "stay" => Ok(OnGoal::Stay),
+            "continue" => Ok(OnGoal::Continue),
+            "load" => Ok(OnGoal::Load),
+            _ => Err(format!("Invalid goal behavior: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for OnGoal {
+    /// Write the value of the `ongoal` attribute
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OnGoal::Wrap => "wrap",
+            OnGoal::Stay => "stay",
+            OnGoal::Continue => "continue",
+            OnGoal::Load => "load",
+        })
+    }
+}
+
+/// Counter component
+///
+/// On an edge of its own `CLK` input while `EN` is not low, the counter
+/// loads `IN` if `LD` is high and otherwise counts up, or down when `UD` is
+/// low. `CARRY` is high while the value sits at the goal for the current
+/// direction (the maximum counting up, zero counting down). A high `CLR`
+/// clears the counter regardless of the clock. Loading undefined bits makes
+/// the count unknown until the next clear or load, and `CARRY` with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Counter {
-    // TODO: Implement Counter component
+    id: ComponentId,
+    pins: HashMap<String, Pin>,
+    width: BusWidth,
+    max_value: u64,
+    on_goal: OnGoal,
+    trigger: Trigger,
+    timing: SetupHold,
+    clock: ClockState,
+    value: Signal,
 }
 
 impl Counter {
-    pub fn new() -> Self {
-        Self {}
+    /// Create a new 8-bit rising edge counter
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_width(id, BusWidth(8))
+    }
+
+    /// Create a new rising edge counter counting through the full data width
+    pub fn new_with_width(id: ComponentId, width: BusWidth) -> Self {
+        let mut pins = HashMap::new();
+        pins.insert("IN".to_string(), Pin::new_input("IN", width));
+        for name in ["CLK", "CLR", "LD", "UD", "EN"] {
+            pins.insert(name.to_string(), Pin::new_input(name, BusWidth(1)));
+        }
+        pins.insert("OUT".to_string(), Pin::new_output("OUT", width));
+        pins.insert("CARRY".to_string(), Pin::new_output("CARRY", BusWidth(1)));
+
+        Self {
+            id,
+            pins,
+            width,
+            max_value: width.get_mask(),
+            on_goal: OnGoal::Wrap,
+            trigger: Trigger::Rising,
+            timing: SetupHold::new(3, 1),
+            clock: ClockState::new(),
+            value: Signal::all_low(width),
+        }
+    }
+
+    /// Set the clock edge the counter reacts to
+    ///
+    /// Counters are edge triggered only, so level triggers are rejected.
+    pub fn set_trigger(&mut self, trigger: Trigger) -> Result<(), String> {
+        if !trigger.is_edge() {
+            return Err(format!("Counters cannot use a {:?} trigger", trigger));
+        }
+        self.trigger = trigger;
+        Ok(())
+    }
+
+    /// Get the clock edge the counter reacts to
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    /// Set the setup and hold times of the inputs sampled on the clock edge
+    pub fn set_setup_hold(&mut self, timing: SetupHold) {
+        self.timing = timing;
+    }
+
+    /// Get the setup and hold times of the inputs sampled on the clock edge
+    pub fn setup_hold(&self) -> SetupHold {
+        self.timing
+    }
+
+    /// Set the goal reached when counting up (limited to the data width)
+    pub fn set_max_value(&mut self, max_value: u64) {
+        self.max_value = max_value & self.width.get_mask();
+    }
+
+    /// Get the goal reached when counting up
+    pub fn max_value(&self) -> u64 {
+        self.max_value
+    }
+
+    /// Set what happens when counting past the goal
+    pub fn set_on_goal(&mut self, on_goal: OnGoal) {
+        self.on_goal = on_goal;
+    }
+
+    /// Get what happens when counting past the goal
+    pub fn on_goal(&self) -> OnGoal {
+        self.on_goal
+    }
+
+    /// Get the current count
+    pub fn value(&self) -> &Signal {
+        &self.value
+    }
+
+    fn input(&self, name: &str) -> Value {
+        self.pins[name].signal.as_single().unwrap_or(Value::Unknown)
+    }
+
+    fn loaded_value(&self) -> Signal {
+        self.pins["IN"].signal.pull(Value::Unknown)
+    }
+
+    /// Compute the next count in the given direction
+    ///
+    /// An undefined count stays undefined.
+    fn next_value(&self, up: bool) -> Signal {
+        let Some(value) = self.value.to_u64() else {
+            return self.value.clone();
+        };
+        let mask = self.width.get_mask();
+        let (goal, restart) = if up {
+            (self.max_value, 0)
+        } else {
+            (0, self.max_value)
+        };
+        let step = |value: u64| {
+            if up {
+                value.wrapping_add(1) & mask
+            } else {
+                value.wrapping_sub(1) & mask
+            }
+        };
+
+        let next = if value != goal {
+            step(value)
+        } else {
+            match self.on_goal {
+                OnGoal::Wrap => restart,
+                OnGoal::Stay => value,
+                OnGoal::Continue => step(value),
+                OnGoal::Load => return self.loaded_value(),
+            }
+        };
+        Signal::from_u64(next, self.width)
     }
 }
 
-impl Default for Counter {
-    fn default() -> Self {
-        Self::new()
+impl Component for Counter {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        COUNTER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let triggered = self.clock.update_clock(self.input("CLK"), self.trigger);
+        let up = self.input("UD") != Value::Low;
+
+        if self.input("CLR") == Value::High {
+            self.value = Signal::all_low(self.width);
+        } else if triggered && self.input("EN") != Value::Low {
+            self.value = if self.input("LD") == Value::High {
+                self.loaded_value()
+            } else {
+                self.next_value(up)
+            };
+        }
+
+        let goal = if up { self.max_value } else { 0 };
+        let carry = match self.value.to_u64() {
+            Some(value) => Value::from_bool(value == goal),
+            None => Value::Unknown,
+        };
+
+        let mut result = UpdateResult::new();
+        result.add_output("OUT".to_string(), self.value.clone());
+        result.add_output("CARRY".to_string(), Signal::new_single(carry));
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
+    fn reset(&mut self) {
+        self.clock = ClockState::new();
+        self.value = Signal::all_low(self.width);
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        8
+    }
+
+    fn is_sequential(&self) -> bool {
+        true
+    }
+
+    fn timing_constraints(&self) -> Option<TimingConstraints> {
+        self.timing
+            .constraints(self.trigger, &["IN", "LD", "UD", "EN"])
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value((self.clock, &self.value)).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        let (clock, value): (ClockState, Signal) = Deserialize::deserialize(state)?;
+        if value.width() != self.width {
+            return Err(serde::de::Error::custom(format!(
+                "Counter value has {} bits, expected {}",
+                value.width(),
+                self.width
+            )));
+        }
+        (self.clock, self.value) = (clock, value);
+        Ok(())
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Memory",
+            COUNTER_ID,
+            vec![
+                ("width", self.width.0.to_string()),
+                ("max", format!("{:#x}", self.max_value)),
+                ("ongoal", self.on_goal.to_string()),
+                ("trigger", self.trigger.to_string()),
+            ],
+        ))
     }
 }
 
@@ -32,9 +306,124 @@ impl Default for Counter {
 mod tests {
     use super::*;
 
+    fn set(counter: &mut Counter, name: &str, value: Value) {
+        counter
+            .get_pin_mut(name)
+            .unwrap()
+            .set_signal(Signal::new_single(value))
+            .unwrap();
+    }
+
+    /// Apply one full clock cycle and return the count and carry
+    fn pulse(counter: &mut Counter) -> (u64, Value) {
+        set(counter, "CLK", Value::Low);
+        counter.update(Timestamp(0));
+        set(counter, "CLK", Value::High);
+        let result = counter.update(Timestamp(0));
+        (
+            result.outputs["OUT"].to_u64().unwrap(),
+            result.outputs["CARRY"].as_single().unwrap(),
+        )
+    }
+
     #[test]
-    fn test_counter_placeholder() {
-        let _counter = Counter::new();
-        // TODO: Add real tests when Counter is implemented
+    fn test_counter_wraps() {
+        let mut counter = Counter::new_with_width(ComponentId(1), BusWidth(4));
+        counter.set_max_value(2);
+        counter.reset();
+
+        assert_eq!(pulse(&mut counter), (1, Value::Low));
+        assert_eq!(pulse(&mut counter), (2, Value::High));
+        assert_eq!(pulse(&mut counter), (0, Value::Low));
+
+        set(&mut counter, "UD", Value::Low);
+        assert_eq!(pulse(&mut counter), (2, Value::Low));
+        assert_eq!(pulse(&mut counter), (1, Value::Low));
+        assert_eq!(pulse(&mut counter), (0, Value::High));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_counter_goal_behaviors() {
+        let mut counter = Counter::new_with_width(ComponentId(1), BusWidth(4));
+        counter.set_max_value(1);
+        counter.set_on_goal(OnGoal::Stay);
+        counter.reset();
+        pulse(&mut counter);
+        assert_eq!(pulse(&mut counter), (1, Value::High));
+
+        counter.set_on_goal(OnGoal::Continue);
+        assert_eq!(pulse(&mut counter), (2, Value::Low));
+
+        counter.set_on_goal(OnGoal::Load);
+        counter
+            .get_pin_mut("IN")
+            .unwrap()
+            .set_signal(Signal::from_u64(9, BusWidth(4)))
+            .unwrap();
+        set(&mut counter, "CLR", Value::High);
+        counter.update(Timestamp(0));
+        set(&mut counter, "CLR", Value::Low);
+        pulse(&mut counter);
+        assert_eq!(pulse(&mut counter), (9, Value::Low));
+    }
+
+    #[test]
+    fn test_counter_load_and_enable() {
+        let mut counter = Counter::new_with_width(ComponentId(1), BusWidth(4));
+        counter.reset();
+        counter
+            .get_pin_mut("IN")
+            .unwrap()
+            .set_signal(Signal::from_u64(7, BusWidth(4)))
+            .unwrap();
+
+        set(&mut counter, "LD", Value::High);
+        assert_eq!(pulse(&mut counter).0, 7);
+
+        set(&mut counter, "LD", Value::Low);
+        set(&mut counter, "EN", Value::Low);
+        assert_eq!(pulse(&mut counter).0, 7);
+
+        assert!(counter.set_trigger(Trigger::HighLevel).is_err());
+        assert!(counter.set_trigger(Trigger::Falling).is_ok());
+    }
+
+    #[test]
+    fn test_counter_loads_undefined_bits() {
+        let mut counter = Counter::new_with_width(ComponentId(1), BusWidth(4));
+        counter.reset();
+        counter
+            .get_pin_mut("IN")
+            .unwrap()
+            .set_signal(Signal::new_bus(vec![
+                Value::High,
+                Value::Error,
+                Value::Low,
+                Value::Low,
+            ]))
+            .unwrap();
+        set(&mut counter, "LD", Value::High);
+        set(&mut counter, "CLK", Value::Low);
+        counter.update(Timestamp(0));
+        set(&mut counter, "CLK", Value::High);
+        let result = counter.update(Timestamp(0));
+        assert_eq!(
+            result.outputs["OUT"].bits(),
+            &[Value::High, Value::Error, Value::Low, Value::Low]
+        );
+        assert_eq!(result.outputs["CARRY"].as_single(), Some(Value::Unknown));
+
+        // Counting from an undefined value keeps it undefined
+        set(&mut counter, "LD", Value::Low);
+        set(&mut counter, "CLK", Value::Low);
+        counter.update(Timestamp(0));
+        set(&mut counter, "CLK", Value::High);
+        assert!(!counter.update(Timestamp(0)).outputs["OUT"].is_fully_defined());
+
+        set(&mut counter, "CLR", Value::High);
+        assert_eq!(
+            counter.update(Timestamp(0)).outputs["OUT"].to_u64(),
+            Some(0)
+        );
+    }
+}
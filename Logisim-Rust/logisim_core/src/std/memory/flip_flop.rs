@@ -0,0 +1,268 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Flip-flop core shared by the D, T, J-K and S-R flip-flops
+//!
+//! This module implements the behavior of AbstractFlipFlop.java; each kind
+//! of flip-flop only supplies its data inputs and next state function.
+
+use crate::comp::{CircTool, Component, ComponentId, Pin, TimingConstraints, UpdateResult};
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::std::memory::{ClockState, SetupHold, Trigger};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// The part of a flip-flop that differs between its kinds, ported from the
+/// `computeValue` and `getInputName` overrides of AbstractFlipFlop.java
+pub trait FlipFlopLogic: Debug + Clone + Send + Sync + 'static {
+    /// Tool name of the flip-flop in the `#Memory` library
+    const ID: &'static str;
+
+    /// Names of the data inputs read when the clock triggers
+    const INPUTS: &'static [&'static str];
+
+    /// Whether the flip-flop can be a latch, triggered by a clock level
+    const LEVEL_TRIGGERS: bool;
+
+    /// Names of the asynchronous reset and set inputs
+    const ASYNC_INPUTS: [&'static str; 2] = ["R", "S"];
+
+    /// Compute the next state from the data inputs and the current state
+    ///
+    /// A result other than 0 or 1 leaves the state unchanged.
+    fn next(inputs: &[Value], current: Value) -> Value;
+}
+
+/// Flip-flop of the kind `L`
+///
+/// Updates its state from the data inputs when its own `CLK` input fires
+/// the configured trigger and drives the stored bit on `Q` and its
+/// complement on `NQ`. The asynchronous reset and set inputs override the
+/// clock, with reset winning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FlipFlop<L: FlipFlopLogic> {
+    id: ComponentId,
+    pins: HashMap<String, Pin>,
+    trigger: Trigger,
+    timing: SetupHold,
+    clock: ClockState,
+    value: Value,
+    #[serde(skip)]
+    logic: PhantomData<L>,
+}
+
+impl<L: FlipFlopLogic> FlipFlop<L> {
+    /// Create a new rising edge flip-flop
+    pub fn new(id: ComponentId) -> Self {
+        let mut pins = HashMap::new();
+        for &name in L::INPUTS.iter().chain(&["CLK"]).chain(&L::ASYNC_INPUTS) {
+            pins.insert(name.to_string(), Pin::new_input(name, BusWidth(1)));
+        }
+        for name in ["Q", "NQ"] {
+            pins.insert(name.to_string(), Pin::new_output(name, BusWidth(1)));
+        }
+
+        Self {
+            id,
+            pins,
+            trigger: Trigger::Rising,
+            timing: SetupHold::new(2, 1),
+            clock: ClockState::new(),
+            value: Value::Low,
+            logic: PhantomData,
+        }
+    }
+
+    /// Set the clock condition that updates the state
+    ///
+    /// Level triggers are only meaningful for flip-flops whose
+    /// [`FlipFlopLogic::LEVEL_TRIGGERS`] is set.
+    pub fn set_trigger(&mut self, trigger: Trigger) {
+        self.trigger = trigger;
+    }
+
+    /// Get the clock condition that updates the state
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    /// Set the setup and hold times of the inputs latched on the clock edge
+    pub fn set_setup_hold(&mut self, timing: SetupHold) {
+        self.timing = timing;
+    }
+
+    /// Get the setup and hold times of the inputs latched on the clock edge
+    pub fn setup_hold(&self) -> SetupHold {
+        self.timing
+    }
+
+    /// Get the stored bit
+    pub fn value(&self) -> Value {
+        self.value
+    }
+
+    fn input(&self, name: &str) -> Value {
+        self.pins[name].signal.as_single().unwrap_or(Value::Unknown)
+    }
+}
+
+impl<L: FlipFlopLogic> Component for FlipFlop<L> {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        L::ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        // Always record the clock so edges are not lost while reset or set
+        // are active
+        let triggered = self.clock.update_clock(self.input("CLK"), self.trigger);
+
+        let [reset, set] = L::ASYNC_INPUTS;
+        if self.input(reset) == Value::High {
+            self.value = Value::Low;
+        } else if self.input(set) == Value::High {
+            self.value = Value::High;
+        } else if triggered {
+            let inputs: Vec<Value> = L::INPUTS.iter().map(|name| self.input(name)).collect();
+            let next = L::next(&inputs, self.value);
+            if next.is_definite() {
+                self.value = next;
+            }
+        }
+
+        let mut result = UpdateResult::new();
+        result.add_output("Q".to_string(), Signal::new_single(self.value));
+        result.add_output("NQ".to_string(), Signal::new_single(self.value.not()));
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
+    fn reset(&mut self) {
+        self.clock = ClockState::new();
+        self.value = Value::Low;
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        5
+    }
+
+    fn is_sequential(&self) -> bool {
+        true
+    }
+
+    fn timing_constraints(&self) -> Option<TimingConstraints> {
+        self.timing.constraints(self.trigger, L::INPUTS)
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value((self.clock, self.value)).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        (self.clock, self.value) = Deserialize::deserialize(state)?;
+        Ok(())
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Memory",
+            L::ID,
+            vec![("trigger", self.trigger.to_string())],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::std::memory::{JKFlipFlop, SRFlipFlop, TFlipFlop};
+
+    fn drive<L: FlipFlopLogic>(ff: &mut FlipFlop<L>, inputs: &[(&str, Value)]) -> Value {
+        for (name, value) in inputs {
+            ff.get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::new_single(*value))
+                .unwrap();
+        }
+        ff.update(Timestamp(0));
+        ff.value()
+    }
+
+    /// Apply the data inputs and clock a rising edge
+    fn clock<L: FlipFlopLogic>(ff: &mut FlipFlop<L>, inputs: &[(&str, Value)]) -> Value {
+        drive(ff, &[("CLK", Value::Low)]);
+        drive(ff, inputs);
+        drive(ff, &[("CLK", Value::High)])
+    }
+
+    #[test]
+    fn test_jk_flip_flop() {
+        use Value::{High, Low, Unknown};
+        let mut ff = JKFlipFlop::new(ComponentId(1));
+        ff.reset();
+        assert_eq!(ff.name(), "J-K Flip-Flop");
+
+        assert_eq!(clock(&mut ff, &[("J", High), ("K", Low)]), High);
+        assert_eq!(clock(&mut ff, &[("J", Low), ("K", Low)]), High);
+        assert_eq!(clock(&mut ff, &[("J", High), ("K", High)]), Low);
+        assert_eq!(clock(&mut ff, &[("J", High), ("K", High)]), High);
+        assert_eq!(clock(&mut ff, &[("J", Low), ("K", High)]), Low);
+        // An undefined input keeps the state
+        assert_eq!(clock(&mut ff, &[("J", Unknown), ("K", Low)]), Low);
+    }
+
+    #[test]
+    fn test_sr_flip_flop() {
+        use Value::{High, Low};
+        let mut ff = SRFlipFlop::new(ComponentId(1));
+        ff.reset();
+        assert!(ff.get_pin("CLR").is_some() && ff.get_pin("PRE").is_some());
+
+        assert_eq!(clock(&mut ff, &[("S", High), ("R", Low)]), High);
+        // Setting and resetting at once keeps the state
+        assert_eq!(clock(&mut ff, &[("S", High), ("R", High)]), High);
+        assert_eq!(clock(&mut ff, &[("S", Low), ("R", High)]), Low);
+
+        assert_eq!(drive(&mut ff, &[("R", Low), ("PRE", High)]), High);
+        assert_eq!(drive(&mut ff, &[("CLR", High)]), Low);
+    }
+
+    #[test]
+    fn test_t_flip_flop() {
+        use Value::{High, Low};
+        let mut ff = TFlipFlop::new(ComponentId(1));
+        ff.reset();
+
+        assert_eq!(clock(&mut ff, &[("T", High)]), High);
+        assert_eq!(clock(&mut ff, &[("T", Low)]), High);
+        assert_eq!(clock(&mut ff, &[("T", High)]), Low);
+        assert_eq!(drive(&mut ff, &[("S", High)]), High);
+        assert_eq!(
+            ff.timing_constraints().unwrap().inputs,
+            vec!["T".to_string()]
+        );
+    }
+}
@@ -11,30 +11,36 @@
 //!
 //! This module implements JK Flip-Flop functionality equivalent to JKFlipFlop.java.
 
-/// JK Flip-Flop component (placeholder for now)
-pub struct JKFlipFlop {
-    // TODO: Implement JK Flip-Flop component
-}
+use crate::signal::Value;
+use crate::std::memory::{FlipFlop, FlipFlopLogic};
 
-impl JKFlipFlop {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
+/// Unique identifier for the J-K Flip-Flop component
+/// Do NOT change as it will prevent project files from loading.
+pub const JK_FLIP_FLOP_ID: &str = "J-K Flip-Flop";
 
-impl Default for JKFlipFlop {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+/// J-K Flip-Flop component
+///
+/// On a clock edge, `J` alone sets the stored bit, `K` alone resets it and
+/// both together toggle it. The asynchronous `R` (reset) and `S` (set)
+/// inputs override the clock, with reset winning.
+pub type JKFlipFlop = FlipFlop<JKLogic>;
+
+/// Next state function of the J-K flip-flop
+#[derive(Debug, Clone)]
+pub struct JKLogic;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl FlipFlopLogic for JKLogic {
+    const ID: &'static str = JK_FLIP_FLOP_ID;
+    const INPUTS: &'static [&'static str] = &["J", "K"];
+    const LEVEL_TRIGGERS: bool = false;
 
-    #[test]
-    fn test_jk_flip_flop_placeholder() {
-        let _jk_ff = JKFlipFlop::new();
-        // TODO: Add real tests when JK Flip-Flop is implemented
+    fn next(inputs: &[Value], current: Value) -> Value {
+        match (inputs[0], inputs[1]) {
+            (Value::Low, Value::Low) => current,
+            (Value::Low, Value::High) => Value::Low,
+            (Value::High, Value::Low) => Value::High,
+            (Value::High, Value::High) => current.not(),
+            _ => Value::Unknown,
+        }
     }
-}
\ No newline at end of file
+}
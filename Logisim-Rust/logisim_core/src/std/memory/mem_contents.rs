@@ -13,8 +13,7 @@
 //! It provides paginated memory storage with event notification.
 
 use std::collections::HashMap;
-use std::sync::{Arc, Weak, Mutex};
-use crate::BitWidth;
+use std::sync::{Arc, Mutex, Weak};
 
 /// Listener for memory content changes
 pub trait HexModelListener: Send + Sync {
@@ -22,6 +21,9 @@ pub trait HexModelListener: Send + Sync {
     fn bytes_changed(&mut self, start_addr: i64, num_bytes: i32, old_values: &[i64]);
 }
 
+/// Weak reference to a registered listener
+type ListenerRef = Weak<Mutex<dyn HexModelListener>>;
+
 /// A page of memory data
 #[derive(Clone, Debug)]
 pub struct MemPage {
@@ -31,7 +33,7 @@ pub struct MemPage {
 
 impl MemPage {
     pub fn new(size: usize, width: i32, randomize: bool) -> Self {
-        let mut data = vec![0; size];
+        let data = vec![0; size];
         if randomize {
             // TODO: Implement randomization based on preferences
             // For now, just initialize to zeros
@@ -57,6 +59,10 @@ impl MemPage {
     pub fn length(&self) -> usize {
         self.data.len()
     }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
 }
 
 /// Memory contents with paginated storage
@@ -67,7 +73,7 @@ pub struct MemContents {
     mask: i64,
     pages: HashMap<usize, MemPage>,
     randomize: bool,
-    listeners: Arc<Mutex<Vec<Weak<Mutex<dyn HexModelListener>>>>>,
+    listeners: Arc<Mutex<Vec<ListenerRef>>>,
 }
 
 impl MemContents {
@@ -78,7 +84,7 @@ impl MemContents {
     /// Create new memory contents
     pub fn create(addr_bits: i32, width: i32, randomize: bool) -> Self {
         let mask = if width >= 64 { -1 } else { (1i64 << width) - 1 };
-        
+
         Self {
             width,
             addr_bits,
@@ -100,7 +106,8 @@ impl MemContents {
     pub fn remove_hex_model_listener(&mut self, listener: &Arc<Mutex<dyn HexModelListener>>) {
         if let Ok(mut listeners) = self.listeners.lock() {
             listeners.retain(|weak| {
-                weak.upgrade().map_or(false, |strong| !Arc::ptr_eq(&strong, listener))
+                weak.upgrade()
+                    .is_some_and(|strong| !Arc::ptr_eq(&strong, listener))
             });
         }
     }
@@ -125,7 +132,7 @@ impl MemContents {
         if let Some(page) = self.pages.get(&page_index) {
             let old_values = page.get_range(0, page.length());
             let changed = old_values.iter().any(|&val| (val & self.mask) != 0);
-            
+
             if changed {
                 self.pages.remove(&page_index);
                 let start_addr = (page_index << Self::PAGE_SIZE_BITS) as i64;
@@ -143,7 +150,8 @@ impl MemContents {
         let page_index = (addr as usize) >> Self::PAGE_SIZE_BITS;
         let offset = (addr as usize) & Self::PAGE_MASK;
 
-        self.pages.get(&page_index)
+        self.pages
+            .get(&page_index)
             .map_or(0, |page| page.get(offset) & self.mask)
     }
 
@@ -162,7 +170,10 @@ impl MemContents {
             if masked_value == 0 {
                 return; // No need to create page for zero value
             }
-            self.pages.insert(page_index, MemPage::new(Self::PAGE_SIZE, self.width, self.randomize));
+            self.pages.insert(
+                page_index,
+                MemPage::new(Self::PAGE_SIZE, self.width, self.randomize),
+            );
         }
 
         if let Some(page) = self.pages.get_mut(&page_index) {
@@ -198,7 +209,8 @@ impl MemContents {
 
     /// Get first non-zero offset
     pub fn get_first_offset(&self) -> i64 {
-        self.pages.keys()
+        self.pages
+            .keys()
             .min()
             .map(|&page_index| (page_index << Self::PAGE_SIZE_BITS) as i64)
             .unwrap_or(0)
@@ -206,12 +218,30 @@ impl MemContents {
 
     /// Get last non-zero offset
     pub fn get_last_offset(&self) -> i64 {
-        self.pages.keys()
+        self.pages
+            .keys()
             .max()
-            .map(|&page_index| ((page_index + 1) << Self::PAGE_SIZE_BITS - 1) as i64)
+            .map(|&page_index| (((page_index + 1) << Self::PAGE_SIZE_BITS) - 1) as i64)
             .unwrap_or(0)
     }
 
+    /// Get every non-zero word as `(address, value)`, sorted by address
+    pub fn nonzero_words(&self) -> Vec<(i64, i64)> {
+        let mut words: Vec<(i64, i64)> = self
+            .pages
+            .iter()
+            .flat_map(|(&page_index, page)| {
+                let base = (page_index << Self::PAGE_SIZE_BITS) as i64;
+                (0..page.length()).filter_map(move |offset| {
+                    let value = page.get(offset) & self.mask;
+                    (value != 0).then_some((base + offset as i64, value))
+                })
+            })
+            .collect();
+        words.sort_unstable();
+        words
+    }
+
     /// Check if memory is completely clear
     pub fn is_clear(&self) -> bool {
         self.pages.is_empty()
@@ -235,6 +265,16 @@ impl MemContents {
     }
 }
 
+impl std::fmt::Debug for MemContents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemContents")
+            .field("width", &self.width)
+            .field("addr_bits", &self.addr_bits)
+            .field("pages", &self.pages.len())
+            .finish()
+    }
+}
+
 /// Sub-component for memory contents (equivalent to MemContentsSub.java)
 pub struct MemContentsSub;
 
@@ -262,7 +302,8 @@ mod tests {
 
     impl HexModelListener for TestListener {
         fn bytes_changed(&mut self, start_addr: i64, num_bytes: i32, old_values: &[i64]) {
-            self.events.push((start_addr, num_bytes, old_values.to_vec()));
+            self.events
+                .push((start_addr, num_bytes, old_values.to_vec()));
         }
     }
 
@@ -277,12 +318,12 @@ mod tests {
     #[test]
     fn test_mem_contents_basic_operations() {
         let mut mem = MemContents::create(10, 8, false);
-        
+
         // Test set/get
         mem.set(100, 0xFF);
         assert_eq!(mem.get(100), 0xFF);
         assert!(!mem.is_clear());
-        
+
         // Test masking
         mem.set(101, 0x1FF); // Should be masked to 0xFF for 8-bit width
         assert_eq!(mem.get(101), 0xFF);
@@ -293,7 +334,7 @@ mod tests {
         let mut mem = MemContents::create(10, 8, false);
         mem.set(100, 0xFF);
         assert!(!mem.is_clear());
-        
+
         mem.clear();
         assert!(mem.is_clear());
         assert_eq!(mem.get(100), 0);
@@ -303,7 +344,7 @@ mod tests {
     fn test_mem_contents_fill() {
         let mut mem = MemContents::create(10, 8, false);
         mem.fill(100, 5, 0xAA);
-        
+
         for addr in 100..105 {
             assert_eq!(mem.get(addr), 0xAA);
         }
@@ -315,8 +356,8 @@ mod tests {
         page.set(50, 0xFF);
         assert_eq!(page.get(50), 0xFF);
         assert_eq!(page.length(), 100);
-        
+
         let range = page.get_range(49, 3);
         assert_eq!(range, vec![0, 0xFF, 0]);
     }
-}
\ No newline at end of file
+}
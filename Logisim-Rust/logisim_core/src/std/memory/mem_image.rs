@@ -0,0 +1,1006 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Memory image files
+//!
+//! This module reads and writes the memory image formats of HexFile.java,
+//! together with the formats of common assembler and compiler toolchains,
+//! to and from [`MemContents`]:
+//!
+//! - Logisim `v2.0 raw`: hex words, with `4*ab` for four `ab` words
+//! - Logisim `v3.0 hex words`: hex words, plain or prefixed with an address
+//! - Intel HEX and Motorola S-record
+//! - raw binary, little- or big-endian
+//! - Verilog `$readmemh`, with `@address` jumps
+//!
+//! Text formats hold one memory word per value. The byte formats (Intel
+//! HEX, S-record and binary) hold bytes, which are grouped into words of as
+//! many bytes as the memory width needs, in the byte order of
+//! [`MemImageOptions`]. Image addresses are shifted by the options' offset,
+//! counted in words for text formats and in bytes for byte formats.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use thiserror::Error;
+
+use super::mem_contents::MemContents;
+
+/// Errors reading or writing memory images
+#[derive(Error, Debug)]
+pub enum MemImageError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Line {line}: {message}")]
+    Syntax { line: usize, message: String },
+
+    #[error("Line {line}: checksum mismatch")]
+    Checksum { line: usize },
+
+    #[error("Address {address:#x} is outside the memory")]
+    OutOfRange { address: i64 },
+
+    #[error("Value {value:#x} at address {address:#x} does not fit in {width} bits")]
+    ValueTooWide {
+        address: i64,
+        value: u64,
+        width: i32,
+    },
+
+    #[error("Unknown memory image format")]
+    UnknownFormat,
+}
+
+/// Result type for memory image operations
+pub type MemImageResult<T> = Result<T, MemImageError>;
+
+/// A memory image file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemImageFormat {
+    /// Logisim `v2.0 raw`
+    LogisimRaw,
+    /// Logisim-Evolution `v3.0 hex words`
+    LogisimWords,
+    IntelHex,
+    SRecord,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+    /// Verilog `$readmemh`
+    VerilogHex,
+}
+
+impl MemImageFormat {
+    /// Recognize a text format from the start of a file
+    ///
+    /// Raw binary has no signature, so it is never detected.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(data).ok()?;
+        let first = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+        if first.starts_with("v2.0 raw") {
+            Some(Self::LogisimRaw)
+        } else if first.starts_with("v3.0 hex words") {
+            Some(Self::LogisimWords)
+        } else if first.starts_with(':') {
+            Some(Self::IntelHex)
+        } else if first.starts_with('S') && first[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            Some(Self::SRecord)
+        } else if first.starts_with('@')
+            || first.starts_with("//")
+            || first.starts_with("/*")
+            || first.split_whitespace().all(is_verilog_word)
+        {
+            Some(Self::VerilogHex)
+        } else {
+            None
+        }
+    }
+
+    fn is_byte_format(self) -> bool {
+        matches!(
+            self,
+            Self::IntelHex | Self::SRecord | Self::BinaryLittleEndian | Self::BinaryBigEndian
+        )
+    }
+}
+
+fn is_verilog_word(word: &str) -> bool {
+    word.chars()
+        .all(|c| c.is_ascii_hexdigit() || matches!(c, '_' | 'x' | 'X' | 'z' | 'Z'))
+}
+
+/// Order of the bytes of a word in byte formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ByteOrder {
+    #[default]
+    LittleEndian,
+    BigEndian,
+}
+
+/// How image addresses and bytes map onto memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemImageOptions {
+    /// Added to every image address when loading, and subtracted when
+    /// saving; in words for text formats and in bytes for byte formats
+    pub offset: i64,
+    /// Byte order of words in Intel HEX and S-record files
+    pub byte_order: ByteOrder,
+}
+
+/// Replace the contents of a memory with an image
+pub fn load(
+    contents: &mut MemContents,
+    data: &[u8],
+    format: MemImageFormat,
+    options: &MemImageOptions,
+) -> MemImageResult<()> {
+    let mut loader = Loader::new(options.offset);
+    match format {
+        MemImageFormat::LogisimRaw | MemImageFormat::LogisimWords => {
+            let size = 1i64 << contents.get_log_length();
+            loader.read_logisim(&text(data)?, size)?
+        }
+        MemImageFormat::VerilogHex => loader.read_verilog(&text(data)?)?,
+        MemImageFormat::IntelHex => loader.read_intel_hex(&text(data)?)?,
+        MemImageFormat::SRecord => loader.read_srecord(&text(data)?)?,
+        MemImageFormat::BinaryLittleEndian | MemImageFormat::BinaryBigEndian => {
+            for (address, &byte) in (0..).zip(data) {
+                loader.byte(address, byte)?;
+            }
+        }
+    }
+
+    let byte_order = match format {
+        MemImageFormat::BinaryLittleEndian => ByteOrder::LittleEndian,
+        MemImageFormat::BinaryBigEndian => ByteOrder::BigEndian,
+        _ => options.byte_order,
+    };
+    if format.is_byte_format() {
+        load_bytes(contents, &loader.bytes, byte_order)
+    } else {
+        store(contents, loader.words)
+    }
+}
+
+/// Replace the contents of a memory with bytes, grouped into words, by
+/// byte address in memory
+pub(crate) fn load_bytes(
+    contents: &mut MemContents,
+    bytes: &BTreeMap<i64, u8>,
+    byte_order: ByteOrder,
+) -> MemImageResult<()> {
+    let word_bytes = word_bytes(contents.get_width()) as i64;
+    let mut words: BTreeMap<i64, u64> = BTreeMap::new();
+    for (&address, &byte) in bytes {
+        let index = address % word_bytes;
+        let shift = match byte_order {
+            ByteOrder::LittleEndian => index,
+            ByteOrder::BigEndian => word_bytes - 1 - index,
+        };
+        *words.entry(address / word_bytes).or_default() |= (byte as u64) << (8 * shift);
+    }
+    store(contents, words.into_iter().collect())
+}
+
+fn store(contents: &mut MemContents, words: Vec<(i64, u64)>) -> MemImageResult<()> {
+    contents.clear();
+    let width = contents.get_width();
+    let size = 1i64 << contents.get_log_length();
+    for (address, value) in words {
+        if !(0..size).contains(&address) {
+            return Err(MemImageError::OutOfRange { address });
+        }
+        if width < 64 && value >> width != 0 {
+            return Err(MemImageError::ValueTooWide {
+                address,
+                value,
+                width,
+            });
+        }
+        contents.set(address, value as i64);
+    }
+    Ok(())
+}
+
+/// Replace the contents of a memory with an image file, detecting its
+/// format when `format` is `None`
+pub fn load_file<P: AsRef<Path>>(
+    contents: &mut MemContents,
+    path: P,
+    format: Option<MemImageFormat>,
+    options: &MemImageOptions,
+) -> MemImageResult<()> {
+    let data = std::fs::read(path)?;
+    let format = format
+        .or_else(|| MemImageFormat::detect(&data))
+        .ok_or(MemImageError::UnknownFormat)?;
+    load(contents, &data, format, options)
+}
+
+/// Write the contents of a memory as an image
+///
+/// Words past the last non-zero one are left out, as are runs of zero words
+/// in the formats that have addresses.
+pub fn save(
+    contents: &MemContents,
+    format: MemImageFormat,
+    options: &MemImageOptions,
+) -> MemImageResult<Vec<u8>> {
+    let words = contents.nonzero_words();
+    let word_bytes = word_bytes(contents.get_width()) as i64;
+    let unit = if format.is_byte_format() {
+        word_bytes
+    } else {
+        1
+    };
+    let image_address = |address: i64| -> MemImageResult<u64> {
+        let image = address * unit - options.offset;
+        u64::try_from(image).map_err(|_| MemImageError::OutOfRange { address: image })
+    };
+
+    let mut out = String::new();
+    match format {
+        MemImageFormat::LogisimRaw => {
+            let mut values = Vec::new();
+            for (address, value) in words {
+                values.resize(image_address(address)? as usize, 0);
+                values.push(value as u64);
+            }
+            write_logisim_raw(&mut out, &values);
+        }
+        MemImageFormat::LogisimWords => write_logisim_words(&mut out, contents, &image_address)?,
+        MemImageFormat::VerilogHex => write_verilog(&mut out, contents, &image_address)?,
+        _ => {
+            let byte_order = match format {
+                MemImageFormat::BinaryLittleEndian => ByteOrder::LittleEndian,
+                MemImageFormat::BinaryBigEndian => ByteOrder::BigEndian,
+                _ => options.byte_order,
+            };
+            let mut bytes = BTreeMap::new();
+            for (address, value) in words {
+                let base = image_address(address)?;
+                let word = word_to_bytes(value as u64, word_bytes, byte_order);
+                bytes.extend((base..).zip(word));
+            }
+            match format {
+                MemImageFormat::IntelHex => write_intel_hex(&mut out, &bytes),
+                MemImageFormat::SRecord => write_srecord(&mut out, &bytes),
+                _ => {
+                    let mut data = Vec::new();
+                    for (address, byte) in bytes {
+                        data.resize(address as usize, 0);
+                        data.push(byte);
+                    }
+                    return Ok(data);
+                }
+            }
+        }
+    }
+    Ok(out.into_bytes())
+}
+
+/// Write the contents of a memory to an image file
+pub fn save_file<P: AsRef<Path>>(
+    contents: &MemContents,
+    path: P,
+    format: MemImageFormat,
+    options: &MemImageOptions,
+) -> MemImageResult<()> {
+    std::fs::write(path, save(contents, format, options)?)?;
+    Ok(())
+}
+
+fn text(data: &[u8]) -> MemImageResult<String> {
+    String::from_utf8(data.to_vec()).map_err(|_| MemImageError::Syntax {
+        line: 1,
+        message: "Not a text file".to_string(),
+    })
+}
+
+/// Number of bytes a word of `width` bits takes in byte formats
+fn word_bytes(width: i32) -> usize {
+    (width.max(1) as usize).div_ceil(8)
+}
+
+fn word_to_bytes(value: u64, word_bytes: i64, byte_order: ByteOrder) -> Vec<u8> {
+    let mut bytes: Vec<u8> = (0..word_bytes).map(|i| (value >> (8 * i)) as u8).collect();
+    if byte_order == ByteOrder::BigEndian {
+        bytes.reverse();
+    }
+    bytes
+}
+
+fn syntax(line: usize, message: impl Into<String>) -> MemImageError {
+    MemImageError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+fn parse_hex(line: usize, word: &str) -> MemImageResult<u64> {
+    u64::from_str_radix(word, 16).map_err(|_| syntax(line, format!("Invalid hex value '{}'", word)))
+}
+
+/// Words or bytes read from an image, by address in memory
+struct Loader {
+    offset: i64,
+    words: Vec<(i64, u64)>,
+    bytes: BTreeMap<i64, u8>,
+}
+
+impl Loader {
+    fn new(offset: i64) -> Self {
+        Self {
+            offset,
+            words: Vec::new(),
+            bytes: BTreeMap::new(),
+        }
+    }
+
+    fn word(&mut self, address: i64, value: u64) -> MemImageResult<()> {
+        let shifted = address
+            .checked_add(self.offset)
+            .ok_or(MemImageError::OutOfRange { address })?;
+        self.words.push((shifted, value));
+        Ok(())
+    }
+
+    /// Read `v2.0 raw` and both forms of `v3.0 hex words` into a memory of
+    /// `size` words
+    fn read_logisim(&mut self, text: &str, size: i64) -> MemImageResult<()> {
+        let mut address = 0i64;
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() || (index == 0 && line.starts_with('v')) {
+                continue;
+            }
+            let mut words = line.split_whitespace().peekable();
+            if let Some(prefix) = words.peek().and_then(|word| word.strip_suffix(':')) {
+                address = parse_hex(line_number, prefix)? as i64;
+                words.next();
+            }
+            for word in words {
+                let (count, value) = match word.split_once('*') {
+                    Some((count, value)) => {
+                        let count: i64 = count
+                            .parse()
+                            .map_err(|_| syntax(line_number, format!("Invalid run '{}'", word)))?;
+                        (count, parse_hex(line_number, value)?)
+                    }
+                    None => (1, parse_hex(line_number, word)?),
+                };
+                // Zero runs are skipped over, while other runs must fit in
+                // the memory rather than be read word by word past it
+                if value != 0 && count > 0 {
+                    let start = address.saturating_add(self.offset);
+                    if start.saturating_add(count) > size {
+                        return Err(MemImageError::OutOfRange {
+                            address: start.max(size),
+                        });
+                    }
+                    for i in 0..count {
+                        self.word(address + i, value)?;
+                    }
+                }
+                address = address.saturating_add(count);
+            }
+        }
+        Ok(())
+    }
+
+    /// Read Verilog `$readmemh` data, where x and z digits read as 0
+    fn read_verilog(&mut self, text: &str) -> MemImageResult<()> {
+        let mut address = 0i64;
+        let mut in_comment = false;
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let mut rest = line;
+            let mut code = String::new();
+            while !rest.is_empty() {
+                if in_comment {
+                    match rest.find("*/") {
+                        Some(end) => {
+                            rest = &rest[end + 2..];
+                            in_comment = false;
+                        }
+                        None => rest = "",
+                    }
+                } else if let Some(start) = rest.find("/*") {
+                    code.push_str(&rest[..start]);
+                    code.push(' ');
+                    rest = &rest[start + 2..];
+                    in_comment = true;
+                } else {
+                    code.push_str(rest);
+                    rest = "";
+                }
+            }
+            let code = code.split("//").next().unwrap_or_default();
+
+            for word in code.split_whitespace() {
+                if let Some(jump) = word.strip_prefix('@') {
+                    address = parse_hex(line_number, &jump.replace('_', ""))? as i64;
+                    continue;
+                }
+                if !is_verilog_word(word) {
+                    return Err(syntax(line_number, format!("Invalid hex value '{}'", word)));
+                }
+                let digits: String = word
+                    .chars()
+                    .filter(|&c| c != '_')
+                    .map(|c| if c.is_ascii_hexdigit() { c } else { '0' })
+                    .collect();
+                let value = parse_hex(line_number, &digits)?;
+                if value != 0 {
+                    self.word(address, value)?;
+                }
+                address = address
+                    .checked_add(1)
+                    .ok_or(MemImageError::OutOfRange { address })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The bytes of a record line, checked against their checksum, which
+    /// makes the sum of all bytes `expected_sum`
+    fn record_bytes(line_number: usize, digits: &str, expected_sum: u8) -> MemImageResult<Vec<u8>> {
+        if !digits.len().is_multiple_of(2) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(syntax(line_number, "Invalid record"));
+        }
+        let bytes: Vec<u8> = (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default())
+            .collect();
+        let sum = bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+        if sum != expected_sum {
+            return Err(MemImageError::Checksum { line: line_number });
+        }
+        Ok(bytes)
+    }
+
+    fn byte(&mut self, address: u64, value: u8) -> MemImageResult<()> {
+        let address = address as i64;
+        match address.checked_add(self.offset) {
+            Some(shifted) if shifted >= 0 => {
+                self.bytes.insert(shifted, value);
+                Ok(())
+            }
+            shifted => Err(MemImageError::OutOfRange {
+                address: shifted.unwrap_or(address),
+            }),
+        }
+    }
+
+    fn read_intel_hex(&mut self, text: &str) -> MemImageResult<()> {
+        let mut base = 0u64;
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let digits = line
+                .strip_prefix(':')
+                .ok_or_else(|| syntax(line_number, "Record does not start with ':'"))?;
+            let bytes = Self::record_bytes(line_number, digits, 0)?;
+            if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+                return Err(syntax(line_number, "Record length does not match its data"));
+            }
+            let address = u16::from_be_bytes([bytes[1], bytes[2]]) as u64;
+            let data = &bytes[4..bytes.len() - 1];
+            match bytes[3] {
+                0x00 => {
+                    for (i, &byte) in data.iter().enumerate() {
+                        self.byte(base + address + i as u64, byte)?;
+                    }
+                }
+                0x01 => break,
+                kind @ (0x02 | 0x04) => {
+                    let &[high, low] = data else {
+                        return Err(syntax(
+                            line_number,
+                            format!("Record type {:02X} must hold 2 data bytes", kind),
+                        ));
+                    };
+                    let shift = if kind == 0x02 { 4 } else { 16 };
+                    base = (u16::from_be_bytes([high, low]) as u64) << shift;
+                }
+                // Start addresses only matter to the program's loader
+                0x03 | 0x05 => {}
+                kind => {
+                    return Err(syntax(
+                        line_number,
+                        format!("Invalid record type {:02X}", kind),
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_srecord(&mut self, text: &str) -> MemImageResult<()> {
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut chars = line.chars();
+            let (Some('S'), Some(kind)) = (chars.next(), chars.next()) else {
+                return Err(syntax(line_number, "Record does not start with 'S'"));
+            };
+            let bytes = Self::record_bytes(line_number, chars.as_str(), 0xFF)?;
+            if bytes.is_empty() || bytes.len() != bytes[0] as usize + 1 {
+                return Err(syntax(line_number, "Record length does not match its data"));
+            }
+            let address_bytes = match kind {
+                '1' => 2,
+                '2' => 3,
+                '3' => 4,
+                // Headers, counts and start addresses hold no memory data
+                '0' | '5' | '6' | '7' | '8' | '9' => continue,
+                _ => {
+                    return Err(syntax(
+                        line_number,
+                        format!("Invalid record type S{}", kind),
+                    ))
+                }
+            };
+            if bytes.len() < address_bytes + 2 {
+                return Err(syntax(line_number, "Record too short"));
+            }
+            let address = bytes[1..=address_bytes]
+                .iter()
+                .fold(0u64, |address, &byte| address << 8 | byte as u64);
+            for (i, &byte) in bytes[address_bytes + 1..bytes.len() - 1].iter().enumerate() {
+                self.byte(address + i as u64, byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hex_digits(bits: i32) -> usize {
+    (bits.max(1) as usize).div_ceil(4)
+}
+
+fn write_logisim_raw(out: &mut String, values: &[u64]) {
+    out.push_str("v2.0 raw\n");
+    let mut line = Vec::new();
+    let mut rest = values;
+    while let Some(&value) = rest.first() {
+        let run = rest.iter().take_while(|&&next| next == value).count();
+        if run >= 4 {
+            line.push(format!("{}*{:x}", run, value));
+        } else {
+            line.extend((0..run).map(|_| format!("{:x}", value)));
+        }
+        rest = &rest[run..];
+        if line.len() >= 8 {
+            let _ = writeln!(out, "{}", line.join(" "));
+            line.clear();
+        }
+    }
+    if !line.is_empty() {
+        let _ = writeln!(out, "{}", line.join(" "));
+    }
+}
+
+/// Words per line of `v3.0 hex words addressed` and `$readmemh` files
+const WORDS_PER_LINE: i64 = 16;
+
+fn write_logisim_words(
+    out: &mut String,
+    contents: &MemContents,
+    image_address: &dyn Fn(i64) -> MemImageResult<u64>,
+) -> MemImageResult<()> {
+    out.push_str("v3.0 hex words addressed\n");
+    let address_digits = hex_digits(contents.get_log_length());
+    let value_digits = hex_digits(contents.get_width());
+    let mut lines: Vec<i64> = contents
+        .nonzero_words()
+        .iter()
+        .map(|&(address, _)| address / WORDS_PER_LINE)
+        .collect();
+    lines.dedup();
+    for line in lines {
+        let start = line * WORDS_PER_LINE;
+        let _ = write!(
+            out,
+            "{:0width$x}:",
+            image_address(start)?,
+            width = address_digits
+        );
+        for address in start..start + WORDS_PER_LINE {
+            let _ = write!(
+                out,
+                " {:0width$x}",
+                contents.get(address),
+                width = value_digits
+            );
+        }
+        out.push('\n');
+    }
+    Ok(())
+}
+
+fn write_verilog(
+    out: &mut String,
+    contents: &MemContents,
+    image_address: &dyn Fn(i64) -> MemImageResult<u64>,
+) -> MemImageResult<()> {
+    let value_digits = hex_digits(contents.get_width());
+    let mut next = None;
+    let mut on_line = 0;
+    for (address, value) in contents.nonzero_words() {
+        if next != Some(address) {
+            if on_line > 0 {
+                out.push('\n');
+            }
+            let _ = writeln!(out, "@{:x}", image_address(address)?);
+            on_line = 0;
+        } else if on_line == WORDS_PER_LINE {
+            out.push('\n');
+            on_line = 0;
+        }
+        if on_line > 0 {
+            out.push(' ');
+        }
+        let _ = write!(out, "{:0width$x}", value, width = value_digits);
+        on_line += 1;
+        next = Some(address + 1);
+    }
+    if on_line > 0 {
+        out.push('\n');
+    }
+    Ok(())
+}
+
+/// Split bytes into runs of at most `max` consecutive addresses that do not
+/// cross a multiple of `boundary`
+fn runs(bytes: &BTreeMap<u64, u8>, max: usize, boundary: u64) -> Vec<(u64, Vec<u8>)> {
+    let mut runs: Vec<(u64, Vec<u8>)> = Vec::new();
+    for (&address, &byte) in bytes {
+        match runs.last_mut() {
+            Some((start, data))
+                if *start + data.len() as u64 == address
+                    && data.len() < max
+                    && address % boundary != 0 =>
+            {
+                data.push(byte)
+            }
+            _ => runs.push((address, vec![byte])),
+        }
+    }
+    runs
+}
+
+fn record(out: &mut String, prefix: &str, bytes: &[u8], checksum: impl Fn(u8) -> u8) {
+    let sum = bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    out.push_str(prefix);
+    for byte in bytes {
+        let _ = write!(out, "{:02X}", byte);
+    }
+    let _ = writeln!(out, "{:02X}", checksum(sum));
+}
+
+fn write_intel_hex(out: &mut String, bytes: &BTreeMap<u64, u8>) {
+    let mut base = 0u64;
+    for (address, data) in runs(bytes, 16, 0x10000) {
+        if address >> 16 != base {
+            base = address >> 16;
+            let upper = (base as u16).to_be_bytes();
+            record(out, ":", &[2, 0, 0, 4, upper[0], upper[1]], |sum| {
+                sum.wrapping_neg()
+            });
+        }
+        let low = (address as u16).to_be_bytes();
+        let mut line = vec![data.len() as u8, low[0], low[1], 0];
+        line.extend(data);
+        record(out, ":", &line, |sum| sum.wrapping_neg());
+    }
+    out.push_str(":00000001FF\n");
+}
+
+fn write_srecord(out: &mut String, bytes: &BTreeMap<u64, u8>) {
+    let last = bytes.keys().next_back().copied().unwrap_or(0);
+    let (data_kind, end_kind, address_bytes) = if last > 0xFF_FFFF {
+        ('3', '7', 4)
+    } else if last > 0xFFFF {
+        ('2', '8', 3)
+    } else {
+        ('1', '9', 2)
+    };
+    let checksum = |sum: u8| !sum;
+    record(out, "S0", &[3, 0, 0], checksum);
+    for (address, data) in runs(bytes, 32, u64::MAX) {
+        let mut line = vec![(address_bytes + data.len() + 1) as u8];
+        line.extend(&address.to_be_bytes()[8 - address_bytes..]);
+        line.extend(data);
+        record(out, &format!("S{}", data_kind), &line, checksum);
+    }
+    let mut end = vec![(address_bytes + 1) as u8];
+    end.extend(vec![0; address_bytes]);
+    record(out, &format!("S{}", end_kind), &end, checksum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(addr_bits: i32, width: i32, words: &[(i64, i64)]) -> MemContents {
+        let mut contents = MemContents::create(addr_bits, width, false);
+        for &(address, value) in words {
+            contents.set(address, value);
+        }
+        contents
+    }
+
+    fn loaded(
+        addr_bits: i32,
+        width: i32,
+        data: &str,
+        format: MemImageFormat,
+        options: &MemImageOptions,
+    ) -> MemImageResult<Vec<(i64, i64)>> {
+        let mut contents = MemContents::create(addr_bits, width, false);
+        load(&mut contents, data.as_bytes(), format, options)?;
+        Ok(contents.nonzero_words())
+    }
+
+    #[test]
+    fn test_every_format_round_trips() {
+        let contents = memory(
+            16,
+            16,
+            &[
+                (0, 0x1234),
+                (1, 0xabcd),
+                (2, 7),
+                (3, 7),
+                (4, 7),
+                (5, 7),
+                (0x300, 1),
+            ],
+        );
+        let formats = [
+            MemImageFormat::LogisimRaw,
+            MemImageFormat::LogisimWords,
+            MemImageFormat::IntelHex,
+            MemImageFormat::SRecord,
+            MemImageFormat::BinaryLittleEndian,
+            MemImageFormat::BinaryBigEndian,
+            MemImageFormat::VerilogHex,
+        ];
+        for format in formats {
+            let options = MemImageOptions::default();
+            let data = save(&contents, format, &options).unwrap();
+            if !matches!(
+                format,
+                MemImageFormat::BinaryLittleEndian | MemImageFormat::BinaryBigEndian
+            ) {
+                assert_eq!(MemImageFormat::detect(&data), Some(format));
+            }
+            let mut reloaded = MemContents::create(16, 16, false);
+            reloaded.set(0x500, 9);
+            load(&mut reloaded, &data, format, &options).unwrap();
+            assert_eq!(
+                reloaded.nonzero_words(),
+                contents.nonzero_words(),
+                "{:?}",
+                format
+            );
+        }
+
+        let raw = save(
+            &contents,
+            MemImageFormat::LogisimRaw,
+            &MemImageOptions::default(),
+        );
+        let raw = String::from_utf8(raw.unwrap()).unwrap();
+        assert!(raw.starts_with("v2.0 raw\n1234 abcd 4*7 "));
+    }
+
+    #[test]
+    fn test_known_records() {
+        // Examples with checksums computed by other tools
+        let options = MemImageOptions::default();
+        let words = loaded(
+            16,
+            8,
+            ":10010000214601360121470136007EFE09D2190140\n:00000001FF\n",
+            MemImageFormat::IntelHex,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(words[0], (0x100, 0x21));
+        assert_eq!(words.len(), 15);
+
+        let words = loaded(
+            16,
+            8,
+            "S1137AF00A0A0D0000000000000000000000000061\n",
+            MemImageFormat::SRecord,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(words, vec![(0x7af0, 0x0a), (0x7af1, 0x0a), (0x7af2, 0x0d)]);
+
+        let err = loaded(
+            16,
+            8,
+            ":10010000214601360121470136007EFE09D2190141\n",
+            MemImageFormat::IntelHex,
+            &options,
+        );
+        assert!(matches!(err, Err(MemImageError::Checksum { line: 1 })));
+    }
+
+    #[test]
+    fn test_offsets_and_byte_order() {
+        // A program linked at 0x80000000 into a 32-bit memory of 1024 words
+        let options = MemImageOptions {
+            offset: -0x8000_0000,
+            byte_order: ByteOrder::BigEndian,
+        };
+        let hex = ":0200000480007A\n:0800040012345678DEADBEEFA8\n:00000001FF\n";
+        let words = loaded(10, 32, hex, MemImageFormat::IntelHex, &options).unwrap();
+        assert_eq!(words, vec![(1, 0x12345678), (2, 0xdeadbeef)]);
+
+        let little = MemImageOptions {
+            byte_order: ByteOrder::LittleEndian,
+            ..options
+        };
+        let words = loaded(10, 32, hex, MemImageFormat::IntelHex, &little).unwrap();
+        assert_eq!(words, vec![(1, 0x78563412), (2, 0xefbeadde)]);
+
+        // Writing with the same options gives back the addresses of the image
+        let contents = memory(10, 32, &[(1, 0x12345678), (2, 0xdeadbeef)]);
+        let saved = save(&contents, MemImageFormat::IntelHex, &options).unwrap();
+        assert_eq!(String::from_utf8(saved).unwrap(), hex);
+
+        // A binary placed after a 4-byte header, and a word before it
+        let offset = MemImageOptions {
+            offset: 4,
+            ..MemImageOptions::default()
+        };
+        let format = MemImageFormat::BinaryLittleEndian;
+        let data = save(&contents, format, &offset).unwrap();
+        assert_eq!(data, [0x78, 0x56, 0x34, 0x12, 0xef, 0xbe, 0xad, 0xde]);
+        let mut reloaded = MemContents::create(10, 32, false);
+        load(&mut reloaded, &data, format, &offset).unwrap();
+        assert_eq!(reloaded.nonzero_words(), contents.nonzero_words());
+        let err = save(&memory(10, 32, &[(0, 1)]), format, &offset);
+        assert!(matches!(
+            err,
+            Err(MemImageError::OutOfRange { address: -4 })
+        ));
+    }
+
+    #[test]
+    fn test_verilog_and_words() {
+        let verilog = "// boot code\n@10 0001 00_02 /* skipped\n 99 */ xx03\n@0 f\n";
+        let words = loaded(
+            8,
+            16,
+            verilog,
+            MemImageFormat::VerilogHex,
+            &MemImageOptions::default(),
+        );
+        assert_eq!(
+            words.unwrap(),
+            vec![(0, 0xf), (0x10, 1), (0x11, 2), (0x12, 3)]
+        );
+
+        let addressed = "v3.0 hex words addressed\n00: 1 2 # first\n10: 3*4 5\n";
+        let words = loaded(
+            8,
+            8,
+            addressed,
+            MemImageFormat::LogisimWords,
+            &MemImageOptions::default(),
+        );
+        assert_eq!(
+            words.unwrap(),
+            vec![(0, 1), (1, 2), (0x10, 4), (0x11, 4), (0x12, 4), (0x13, 5)]
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let options = MemImageOptions::default();
+        let err = loaded(
+            4,
+            8,
+            "v2.0 raw\n1ff\n",
+            MemImageFormat::LogisimRaw,
+            &options,
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Value 0x1ff at address 0x0 does not fit in 8 bits"
+        );
+        let err = loaded(
+            4,
+            8,
+            "v2.0 raw\n16*0 1\n",
+            MemImageFormat::LogisimRaw,
+            &options,
+        );
+        assert!(matches!(
+            err,
+            Err(MemImageError::OutOfRange { address: 16 })
+        ));
+        let err = loaded(
+            4,
+            8,
+            "v2.0 raw\n1 zz\n",
+            MemImageFormat::LogisimRaw,
+            &options,
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Line 2: Invalid hex value 'zz'"
+        );
+        assert_eq!(MemImageFormat::detect(b"\x7fELF"), None);
+
+        // Long zero runs are skipped, and other runs stop at the memory end
+        let words = loaded(
+            4,
+            8,
+            "v2.0 raw\n9000000000*0\n",
+            MemImageFormat::LogisimRaw,
+            &options,
+        );
+        assert_eq!(words.unwrap(), vec![]);
+        let err = loaded(
+            4,
+            8,
+            "v2.0 raw\n2 9000000000*1\n",
+            MemImageFormat::LogisimRaw,
+            &options,
+        );
+        assert!(matches!(
+            err,
+            Err(MemImageError::OutOfRange { address: 16 })
+        ));
+
+        let err = loaded(
+            4,
+            8,
+            ":03000004000100F8\n",
+            MemImageFormat::IntelHex,
+            &options,
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Line 1: Record type 04 must hold 2 data bytes"
+        );
+
+        // Addresses at the top of the range stop rather than wrap around
+        let top = "@7fffffffffffffff 1 2\n";
+        let err = loaded(4, 8, top, MemImageFormat::VerilogHex, &options);
+        assert!(matches!(
+            err,
+            Err(MemImageError::OutOfRange { address: i64::MAX })
+        ));
+        let shifted = MemImageOptions {
+            offset: 1,
+            ..MemImageOptions::default()
+        };
+        let err = loaded(4, 8, top, MemImageFormat::VerilogHex, &shifted);
+        assert!(matches!(
+            err,
+            Err(MemImageError::OutOfRange { address: i64::MAX })
+        ));
+    }
+}
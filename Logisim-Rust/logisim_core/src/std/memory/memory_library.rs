@@ -12,10 +12,9 @@
 //! This module implements the memory component library equivalent to MemoryLibrary.java.
 //! It contains all memory-related components like RAM, ROM, registers, flip-flops, etc.
 
-use crate::ComponentId;
 use crate::instance::InstanceFactory;
+use crate::ComponentId;
 use std::collections::HashMap;
-use std::sync::Arc;
 
 /// Unique identifier for the memory library
 /// Do NOT change as it will prevent project files from loading.
@@ -25,15 +24,17 @@ pub const MEMORY_LIBRARY_ID: &str = "Memory";
 pub trait MemoryComponentFactory: Send + Sync {
     /// Create a memory component with the given ID
     fn create_component(&self, component_id: ComponentId) -> Option<Box<dyn InstanceFactory>>;
-    
+
     /// Get the component type identifier
     fn get_id(&self) -> &str;
-    
+
     /// Get the display name
     fn get_display_name(&self) -> String;
-    
+
     /// Get the icon path (optional)
-    fn get_icon_path(&self) -> Option<&str> { None }
+    fn get_icon_path(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// Information about a memory component type
@@ -46,7 +47,12 @@ pub struct MemoryComponentInfo {
 }
 
 impl MemoryComponentInfo {
-    pub fn new(id: String, display_name: String, icon_path: Option<String>, description: String) -> Self {
+    pub fn new(
+        id: String,
+        display_name: String,
+        icon_path: Option<String>,
+        description: String,
+    ) -> Self {
         Self {
             id,
             display_name,
@@ -69,7 +75,7 @@ impl MemoryLibrary {
             factories: HashMap::new(),
             component_infos: Vec::new(),
         };
-        
+
         // Register all memory components
         library.register_components();
         library
@@ -95,20 +101,24 @@ impl MemoryLibrary {
         let id = factory.get_id().to_string();
         let display_name = factory.get_display_name();
         let icon_path = factory.get_icon_path().map(|s| s.to_string());
-        
+
         let info = MemoryComponentInfo::new(
             id.clone(),
             display_name.clone(),
             icon_path,
             display_name, // Use display name as description for now
         );
-        
+
         self.component_infos.push(info);
         self.factories.insert(id, factory);
     }
 
     /// Create a component by ID
-    pub fn create_component(&self, component_id: &str, id: ComponentId) -> Option<Box<dyn InstanceFactory>> {
+    pub fn create_component(
+        &self,
+        component_id: &str,
+        id: ComponentId,
+    ) -> Option<Box<dyn InstanceFactory>> {
         self.factories.get(component_id)?.create_component(id)
     }
 
@@ -116,34 +126,34 @@ impl MemoryLibrary {
     fn register_components(&mut self) {
         // TODO: Register memory components as they are implemented
         // For now, register placeholder factories
-        
+
         // Register D Flip-Flop
         // self.register_factory(Box::new(DFlipFlopFactory));
-        
-        // Register T Flip-Flop  
+
+        // Register T Flip-Flop
         // self.register_factory(Box::new(TFlipFlopFactory));
-        
+
         // Register JK Flip-Flop
         // self.register_factory(Box::new(JKFlipFlopFactory));
-        
+
         // Register SR Flip-Flop
         // self.register_factory(Box::new(SRFlipFlopFactory));
-        
+
         // Register Register
         // self.register_factory(Box::new(RegisterFactory));
-        
+
         // Register Counter
         // self.register_factory(Box::new(CounterFactory));
-        
+
         // Register Shift Register
         // self.register_factory(Box::new(ShiftRegisterFactory));
-        
+
         // Register Random
         // self.register_factory(Box::new(RandomFactory));
-        
+
         // Register RAM
         // self.register_factory(Box::new(RamFactory));
-        
+
         // Register ROM
         // self.register_factory(Box::new(RomFactory));
     }
@@ -227,7 +237,7 @@ mod tests {
 
         let infos = library.get_component_infos();
         assert!(!infos.is_empty());
-        
+
         let test_info = infos.iter().find(|info| info.id == "TestMem");
         assert!(test_info.is_some());
         assert_eq!(test_info.unwrap().display_name, "Test Memory");
@@ -236,9 +246,9 @@ mod tests {
     #[test]
     fn test_memory_library_as_library_trait() {
         let library = MemoryLibrary::new();
-        
+
         // Test basic library functionality
         assert_eq!(library.id(), MEMORY_LIBRARY_ID);
         assert_eq!(library.display_name(), "Memory");
     }
-}
\ No newline at end of file
+}
@@ -13,36 +13,40 @@
 //! `com.cburch.logisim.std.memory` package. These components provide various
 //! types of storage and memory functionality.
 
+// `mem` and `mem_state` still target the unfinished instance API and are not
+// built yet
+pub mod clock_state;
+pub mod counter;
+pub mod d_flip_flop;
+pub mod flip_flop;
+pub mod jk_flip_flop;
 pub mod mem_contents;
-pub mod mem_state;
-pub mod mem;
+pub mod mem_image;
 pub mod memory_library;
-pub mod rom;
 pub mod ram;
+pub mod random;
 pub mod register;
-pub mod d_flip_flop;
-pub mod jk_flip_flop;
+pub mod rom;
+pub mod shift_register;
 pub mod sr_flip_flop;
 pub mod t_flip_flop;
-pub mod counter;
-pub mod shift_register;
-pub mod random;
 
 // Re-export main types
+pub use clock_state::{ClockState, SetupHold, Trigger};
+pub use counter::{Counter, OnGoal};
+pub use d_flip_flop::{DFlipFlop, DLogic};
+pub use flip_flop::{FlipFlop, FlipFlopLogic};
+pub use jk_flip_flop::{JKFlipFlop, JKLogic};
 pub use mem_contents::{MemContents, MemContentsSub};
-pub use mem_state::MemState;
-pub use mem::{MemFactory, MemoryComponent, EnableMode};
+pub use mem_image::{ByteOrder, MemImageError, MemImageFormat, MemImageOptions};
 pub use memory_library::MemoryLibrary;
-pub use rom::RomFactory;
 pub use ram::Ram;
+pub use random::Random;
 pub use register::Register;
-pub use d_flip_flop::DFlipFlop;
-pub use jk_flip_flop::JKFlipFlop;
-pub use sr_flip_flop::SRFlipFlop;
-pub use t_flip_flop::TFlipFlop;
-pub use counter::Counter;
+pub use rom::Rom;
 pub use shift_register::ShiftRegister;
-pub use random::Random;
+pub use sr_flip_flop::{SRFlipFlop, SRLogic};
+pub use t_flip_flop::{TFlipFlop, TLogic};
 
 /// Memory library ID - must match Java implementation
-pub const MEMORY_LIBRARY_ID: &str = "Memory";
\ No newline at end of file
+pub const MEMORY_LIBRARY_ID: &str = "Memory";
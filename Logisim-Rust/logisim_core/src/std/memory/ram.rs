@@ -12,22 +12,246 @@
 //! This module implements RAM functionality equivalent to Ram.java.
 //! RAM provides read/write memory storage.
 
-use crate::std::memory::mem::MemFactory;
+use crate::circ_format::RomContents;
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::std::memory::{ClockState, MemContents, Trigger};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 
-/// RAM component (placeholder for now)
+/// Unique identifier for the RAM component
+/// Do NOT change as it will prevent project files from loading.
+pub const RAM_ID: &str = "RAM";
+
+/// RAM component with separate data input and output
+///
+/// The word addressed by `A` is driven on `Q` unless `OE` is low, which
+/// releases the output. `D` is written to that word when the `CLK` input
+/// fires the configured trigger while `WE` is high. A high `CLR` clears the
+/// whole memory regardless of the clock, and so does a reset unless the RAM
+/// is non-volatile.
+///
+/// Words live in a [`MemContents`] like those of the Java RAM; the bits of a
+/// word written from undefined `D` bits are remembered separately and read
+/// back as unknown.
+#[derive(Debug, Clone)]
 pub struct Ram {
-    // TODO: Implement RAM component
+    id: ComponentId,
+    pins: HashMap<String, Pin>,
+    addr_width: BusWidth,
+    data_width: BusWidth,
+    trigger: Trigger,
+    clock: ClockState,
+    volatile: bool,
+    contents: MemContents,
+    /// Mask of the undefined bits of each word that has any
+    undefined: BTreeMap<i64, u64>,
 }
 
 impl Ram {
-    pub fn new() -> Self {
-        Self {}
+    /// Create a new rising edge RAM of 256 bytes
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_widths(id, BusWidth(8), BusWidth(8))
+    }
+
+    /// Create a new rising edge RAM with the given address and data widths
+    pub fn new_with_widths(id: ComponentId, addr_width: BusWidth, data_width: BusWidth) -> Self {
+        let mut pins = HashMap::new();
+        pins.insert("A".to_string(), Pin::new_input("A", addr_width));
+        pins.insert("D".to_string(), Pin::new_input("D", data_width));
+        for name in ["CLK", "WE", "OE", "CLR"] {
+            pins.insert(name.to_string(), Pin::new_input(name, BusWidth(1)));
+        }
+        pins.insert("Q".to_string(), Pin::new_output("Q", data_width));
+
+        Self {
+            id,
+            pins,
+            addr_width,
+            data_width,
+            trigger: Trigger::Rising,
+            clock: ClockState::new(),
+            volatile: true,
+            contents: MemContents::create(addr_width.0 as i32, data_width.0 as i32, false),
+            undefined: BTreeMap::new(),
+        }
+    }
+
+    /// Set the clock condition that stores data
+    pub fn set_trigger(&mut self, trigger: Trigger) {
+        self.trigger = trigger;
+    }
+
+    /// Get the clock condition that stores data
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    /// Set whether a reset clears the contents
+    pub fn set_volatile(&mut self, volatile: bool) {
+        self.volatile = volatile;
+    }
+
+    /// Check whether a reset clears the contents
+    pub fn is_volatile(&self) -> bool {
+        self.volatile
+    }
+
+    /// Get the address width
+    pub fn addr_width(&self) -> BusWidth {
+        self.addr_width
+    }
+
+    /// Get the data width
+    pub fn data_width(&self) -> BusWidth {
+        self.data_width
+    }
+
+    /// Get the memory contents
+    pub fn contents(&self) -> &MemContents {
+        &self.contents
+    }
+
+    /// Get the memory contents (mutable)
+    ///
+    /// Every word is defined after editing the contents directly.
+    pub fn contents_mut(&mut self) -> &mut MemContents {
+        self.undefined.clear();
+        &mut self.contents
+    }
+
+    /// Store a word, remembering which of its bits are undefined
+    fn write(&mut self, address: u64, data: &Signal) {
+        let (mut value, mut undefined) = (0u64, 0u64);
+        for (bit, &v) in data.bits().iter().enumerate().take(64) {
+            match v {
+                Value::High => value |= 1 << bit,
+                Value::Low => {}
+                _ => undefined |= 1 << bit,
+            }
+        }
+        self.contents.set(address as i64, value as i64);
+        if undefined == 0 {
+            self.undefined.remove(&(address as i64));
+        } else {
+            self.undefined.insert(address as i64, undefined);
+        }
+    }
+
+    /// Read a word, with its undefined bits unknown
+    fn read(&self, address: u64) -> Signal {
+        let mut word = Signal::from_u64(self.contents.get(address as i64) as u64, self.data_width);
+        if let Some(&undefined) = self.undefined.get(&(address as i64)) {
+            for bit in (0..self.data_width.0.min(64)).filter(|bit| undefined >> bit & 1 != 0) {
+                let _ = word.set_bit(bit, Value::Unknown);
+            }
+        }
+        word
+    }
+
+    fn input(&self, name: &str) -> Value {
+        self.pins[name].signal.as_single().unwrap_or(Value::Unknown)
     }
 }
 
-impl Default for Ram {
-    fn default() -> Self {
-        Self::new()
+impl Component for Ram {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        RAM_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let triggered = self.clock.update_clock(self.input("CLK"), self.trigger);
+        let address = self.pins["A"].signal.to_u64();
+
+        if self.input("CLR") == Value::High {
+            self.contents.clear();
+            self.undefined.clear();
+        } else if triggered && self.input("WE") == Value::High {
+            if let Some(address) = address {
+                let data = self.pins["D"].signal.clone();
+                self.write(address, &data);
+            }
+        }
+
+        let output = if self.input("OE") == Value::Low {
+            Signal::high_z(self.data_width)
+        } else if let Some(address) = address {
+            self.read(address)
+        } else {
+            Signal::unknown(self.data_width)
+        };
+
+        let mut result = UpdateResult::new();
+        result.add_output("Q".to_string(), output);
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
+    fn reset(&mut self) {
+        self.clock = ClockState::new();
+        if self.volatile {
+            self.contents.cond_clear();
+            self.undefined.clear();
+        }
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        10
+    }
+
+    fn is_sequential(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value((self.clock, self.contents.nonzero_words(), &self.undefined)).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        let (clock, words, undefined): (ClockState, Vec<(i64, i64)>, BTreeMap<i64, u64>) =
+            Deserialize::deserialize(state)?;
+        self.clock = clock;
+        self.contents.clear();
+        for (address, value) in words {
+            self.contents.set(address, value);
+        }
+        self.undefined = undefined;
+        Ok(())
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let memory_type = if self.volatile {
+            "volatile"
+        } else {
+            "nonvolatile"
+        };
+        let mut attributes = vec![
+            ("addrWidth", self.addr_width.0.to_string()),
+            ("dataWidth", self.data_width.0.to_string()),
+            ("trigger", self.trigger.to_string()),
+            ("type", memory_type.to_string()),
+        ];
+        // Logisim-Evolution itself neither saves nor reads RAM contents
+        if !self.contents.is_clear() {
+            let contents = RomContents::from_mem_contents(&self.contents);
+            attributes.push(("contents", contents.to_logisim_format()));
+        }
+        Some(CircTool::new("#Memory", RAM_ID, attributes))
     }
 }
 
@@ -35,9 +259,136 @@ impl Default for Ram {
 mod tests {
     use super::*;
 
+    fn drive(ram: &mut Ram, inputs: &[(&str, Signal)]) -> Signal {
+        for (name, signal) in inputs {
+            ram.get_pin_mut(name)
+                .unwrap()
+                .set_signal(signal.clone())
+                .unwrap();
+        }
+        ram.update(Timestamp(0)).outputs["Q"].clone()
+    }
+
+    fn bit(value: Value) -> Signal {
+        Signal::new_single(value)
+    }
+
+    #[test]
+    fn test_ram_write_and_read() {
+        let mut ram = Ram::new_with_widths(ComponentId(1), BusWidth(4), BusWidth(8));
+        ram.reset();
+
+        let address = Signal::from_u64(3, BusWidth(4));
+        let data = Signal::from_u64(0x5a, BusWidth(8));
+        drive(
+            &mut ram,
+            &[
+                ("A", address),
+                ("D", data),
+                ("WE", bit(Value::High)),
+                ("CLK", bit(Value::Low)),
+            ],
+        );
+        assert_eq!(
+            drive(&mut ram, &[("CLK", bit(Value::High))]).to_u64(),
+            Some(0x5a)
+        );
+
+        // Without write enable an edge leaves the word alone
+        drive(
+            &mut ram,
+            &[
+                ("D", Signal::from_u64(0x11, BusWidth(8))),
+                ("WE", bit(Value::Low)),
+                ("CLK", bit(Value::Low)),
+            ],
+        );
+        drive(&mut ram, &[("CLK", bit(Value::High))]);
+        assert_eq!(ram.contents().get(3), 0x5a);
+
+        let other = drive(&mut ram, &[("A", Signal::from_u64(4, BusWidth(4)))]);
+        assert_eq!(other.to_u64(), Some(0));
+    }
+
+    #[test]
+    fn test_ram_output_enable_and_clear() {
+        let mut ram = Ram::new_with_widths(ComponentId(1), BusWidth(4), BusWidth(8));
+        ram.reset();
+        ram.contents_mut().set(0, 0x42);
+
+        assert!(drive(&mut ram, &[("A", Signal::from_u64(0, BusWidth(4)))]).is_definite());
+        assert!(drive(&mut ram, &[("OE", bit(Value::Low))]).is_high_z());
+        assert_eq!(
+            drive(
+                &mut ram,
+                &[
+                    ("OE", bit(Value::High)),
+                    ("A", Signal::unknown(BusWidth(4)))
+                ]
+            ),
+            Signal::unknown(BusWidth(8))
+        );
+
+        drive(&mut ram, &[("CLR", bit(Value::High))]);
+        assert!(ram.contents().is_clear());
+    }
+
     #[test]
-    fn test_ram_placeholder() {
-        let _ram = Ram::new();
-        // TODO: Add real tests when RAM is implemented
+    fn test_ram_state_round_trip() {
+        let mut ram = Ram::new_with_widths(ComponentId(1), BusWidth(16), BusWidth(8));
+        ram.reset();
+        ram.contents_mut().set(7, 0x12);
+        ram.contents_mut().set(0x8000, 0x34);
+        let state = ram.save_state().unwrap();
+
+        ram.contents_mut().clear();
+        ram.contents_mut().set(1, 0x56);
+        ram.restore_state(&state).unwrap();
+        assert_eq!(
+            ram.contents().nonzero_words(),
+            vec![(7, 0x12), (0x8000, 0x34)]
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ram_stores_undefined_bits() {
+        let mut ram = Ram::new_with_widths(ComponentId(1), BusWidth(4), BusWidth(4));
+        ram.reset();
+
+        let data = Signal::new_bus(vec![Value::High, Value::Unknown, Value::HighZ, Value::Low]);
+        drive(
+            &mut ram,
+            &[
+                ("A", Signal::from_u64(2, BusWidth(4))),
+                ("D", data),
+                ("WE", bit(Value::High)),
+                ("CLK", bit(Value::Low)),
+            ],
+        );
+        let stored = drive(&mut ram, &[("CLK", bit(Value::High))]);
+        assert_eq!(
+            stored.bits(),
+            &[Value::High, Value::Unknown, Value::Unknown, Value::Low]
+        );
+
+        let state = ram.save_state().unwrap();
+        ram.reset();
+        ram.restore_state(&state).unwrap();
+        let address = Signal::from_u64(2, BusWidth(4));
+        assert_eq!(drive(&mut ram, &[("A", address)]).bits(), stored.bits());
+
+        // Writing a defined word makes it defined again
+        drive(
+            &mut ram,
+            &[
+                ("D", Signal::from_u64(6, BusWidth(4))),
+                ("WE", bit(Value::High)),
+                ("CLK", bit(Value::Low)),
+            ],
+        );
+        assert_eq!(
+            drive(&mut ram, &[("CLK", bit(Value::High))]).to_u64(),
+            Some(6)
+        );
+    }
+}
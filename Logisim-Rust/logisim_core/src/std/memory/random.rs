@@ -10,21 +10,236 @@
 //! Random component
 //!
 //! This module implements random number generator functionality equivalent to Random.java.
+//! The generator is the linear congruential generator of `java.util.Random`,
+//! so a seeded component produces the same sequence as in Logisim.
 
-/// Random component (placeholder for now)
+use crate::comp::{CircTool, Component, ComponentId, Pin, UpdateResult};
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::std::memory::{ClockState, Trigger};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Unique identifier for the Random component
+/// Do NOT change as it will prevent project files from loading.
+pub const RANDOM_ID: &str = "Random";
+
+const MULTIPLIER: u64 = 0x5DEECE66D;
+const ADDEND: u64 = 0xB;
+const MASK: u64 = (1 << 48) - 1;
+
+/// Generator state, ported from `Random.StateData`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Generator {
+    init_seed: u64,
+    cur_seed: u64,
+    value: u32,
+    reset_value: u64,
+    old_reset: Value,
+}
+
+impl Generator {
+    fn new(seed: i32) -> Self {
+        let seed = Self::seed(seed, 0);
+        Self {
+            init_seed: seed,
+            cur_seed: seed,
+            value: seed as u32,
+            reset_value: seed,
+            old_reset: Value::Unknown,
+        }
+    }
+
+    /// The seed a sequence starts from; seed 0 starts a new sequence from
+    /// the current time
+    fn seed(seed: i32, init_seed: u64) -> u64 {
+        if seed != 0 {
+            return seed as i64 as u64;
+        }
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_millis() as u64);
+        let seed = (millis ^ MULTIPLIER) & MASK;
+        if seed == init_seed {
+            (seed + MULTIPLIER) & MASK
+        } else {
+            seed
+        }
+    }
+
+    /// Pick a new reset seed on a rising edge of the reset input
+    fn propagate_reset(&mut self, reset: Value, seed: i32) {
+        if self.old_reset == Value::Low && reset == Value::High {
+            self.reset_value = Self::seed(seed, self.init_seed);
+        }
+        self.old_reset = reset;
+    }
+
+    fn reset(&mut self) {
+        self.init_seed = self.reset_value;
+        self.cur_seed = self.reset_value;
+        self.value = self.reset_value as u32;
+    }
+
+    fn step(&mut self) {
+        self.cur_seed = self.cur_seed.wrapping_mul(MULTIPLIER).wrapping_add(ADDEND) & MASK;
+        self.value = (self.cur_seed >> 12) as u32;
+    }
+}
+
+/// Random number generator component
+///
+/// Steps to the next pseudorandom number on an edge of its own `CLK`
+/// input unless `EN` is low, and drives it on `OUT`. A high `RST` restarts
+/// the sequence from the seed; with seed 0 every reset, and the first
+/// start, picks a new seed from the current time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Random {
-    // TODO: Implement Random component
+    id: ComponentId,
+    pins: HashMap<String, Pin>,
+    width: BusWidth,
+    seed: i32,
+    trigger: Trigger,
+    clock: ClockState,
+    generator: Generator,
 }
 
 impl Random {
-    pub fn new() -> Self {
-        Self {}
+    /// Create a new 8-bit rising edge generator with a time-based seed
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_width(id, BusWidth(8))
+    }
+
+    /// Create a new rising edge generator with a time-based seed
+    pub fn new_with_width(id: ComponentId, width: BusWidth) -> Self {
+        let mut pins = HashMap::new();
+        for name in ["CLK", "EN", "RST"] {
+            pins.insert(name.to_string(), Pin::new_input(name, BusWidth(1)));
+        }
+        pins.insert("OUT".to_string(), Pin::new_output("OUT", width));
+
+        Self {
+            id,
+            pins,
+            width,
+            seed: 0,
+            trigger: Trigger::Rising,
+            clock: ClockState::new(),
+            generator: Generator::new(0),
+        }
+    }
+
+    /// Set the seed of the sequence, 0 for a new seed at every start
+    pub fn set_seed(&mut self, seed: i32) {
+        self.seed = seed;
+        self.generator = Generator::new(seed);
+    }
+
+    /// Get the seed of the sequence
+    pub fn seed(&self) -> i32 {
+        self.seed
+    }
+
+    /// Set the clock edge the generator steps on
+    ///
+    /// The generator is edge triggered only, so level triggers are rejected.
+    pub fn set_trigger(&mut self, trigger: Trigger) -> Result<(), String> {
+        if !trigger.is_edge() {
+            return Err(format!(
+                "Random generators cannot use a {:?} trigger",
+                trigger
+            ));
+        }
+        self.trigger = trigger;
+        Ok(())
+    }
+
+    /// Get the clock edge the generator steps on
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    /// Get the data width
+    pub fn width(&self) -> BusWidth {
+        self.width
+    }
+
+    fn input(&self, name: &str) -> Value {
+        self.pins[name].signal.as_single().unwrap_or(Value::Unknown)
     }
 }
 
-impl Default for Random {
-    fn default() -> Self {
-        Self::new()
+impl Component for Random {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        RANDOM_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let triggered = self.clock.update_clock(self.input("CLK"), self.trigger);
+
+        let reset = self.input("RST");
+        self.generator.propagate_reset(reset, self.seed);
+        if reset == Value::High {
+            self.generator.reset();
+        } else if triggered && self.input("EN") != Value::Low {
+            self.generator.step();
+        }
+
+        // The 32-bit Java int is sign extended to wider outputs
+        let value = self.generator.value as i32 as i64 as u64;
+        let mut result = UpdateResult::new();
+        result.add_output("OUT".to_string(), Signal::from_u64(value, self.width));
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
+    fn reset(&mut self) {
+        self.clock = ClockState::new();
+        self.generator = Generator::new(self.seed);
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        4
+    }
+
+    fn is_sequential(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value((self.clock, self.generator)).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        (self.clock, self.generator) = Deserialize::deserialize(state)?;
+        Ok(())
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Memory",
+            RANDOM_ID,
+            vec![
+                ("width", self.width.0.to_string()),
+                ("seed", self.seed.to_string()),
+                ("trigger", self.trigger.to_string()),
+            ],
+        ))
     }
 }
 
@@ -32,9 +247,49 @@ impl Default for Random {
 mod tests {
     use super::*;
 
+    fn clock(random: &mut Random, inputs: &[(&str, Value)]) -> Option<u64> {
+        for (name, value) in inputs {
+            random
+                .get_pin_mut(name)
+                .unwrap()
+                .set_signal(Signal::new_single(*value))
+                .unwrap();
+        }
+        random.update(Timestamp(0)).outputs["OUT"].to_u64()
+    }
+
     #[test]
-    fn test_random_placeholder() {
-        let _random = Random::new();
-        // TODO: Add real tests when Random is implemented
+    fn test_random_sequence_matches_java() {
+        let mut random = Random::new_with_width(ComponentId(1), BusWidth(32));
+        random.set_seed(42);
+        random.reset();
+        assert_eq!(clock(&mut random, &[("CLK", Value::Low)]), Some(42));
+
+        // Logisim steps the seed itself, without the scrambling that
+        // `java.util.Random` applies to it
+        for expected in [258551260, 971161337, 3145040169] {
+            assert_eq!(clock(&mut random, &[("CLK", Value::High)]), Some(expected));
+            clock(&mut random, &[("CLK", Value::Low)]);
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_random_enable_and_reset() {
+        let mut random = Random::new(ComponentId(1));
+        random.set_seed(7);
+        random.reset();
+        clock(&mut random, &[("CLK", Value::Low), ("EN", Value::Low)]);
+        assert_eq!(clock(&mut random, &[("CLK", Value::High)]), Some(7));
+
+        clock(&mut random, &[("CLK", Value::Low), ("EN", Value::High)]);
+        let first = clock(&mut random, &[("CLK", Value::High)]);
+        assert_ne!(first, Some(7));
+
+        // Reset restarts the sequence from the seed
+        assert_eq!(clock(&mut random, &[("RST", Value::High)]), Some(7));
+        clock(&mut random, &[("RST", Value::Low), ("CLK", Value::Low)]);
+        assert_eq!(clock(&mut random, &[("CLK", Value::High)]), first);
+
+        assert!(random.set_trigger(Trigger::HighLevel).is_err());
+    }
+}
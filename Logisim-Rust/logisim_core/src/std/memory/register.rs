@@ -10,21 +10,174 @@
 //! Register component
 //!
 //! This module implements register functionality equivalent to Register.java.
+//! Registers store multi-bit values and are clocked.
 
-/// Register component (placeholder for now)
+use crate::comp::{CircTool, Component, ComponentId, Pin, TimingConstraints, UpdateResult};
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::std::memory::{ClockState, SetupHold, Trigger};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Unique identifier for the Register component
+/// Do NOT change as it will prevent project files from loading.
+pub const REGISTER_ID: &str = "Register";
+
+/// Register component
+///
+/// Stores `D` when its own `CLK` input fires the configured trigger while
+/// `EN` is not low, and drives the stored value on `Q`. A high `CLR` clears
+/// the register regardless of the clock. Undefined bits of `D` are stored as
+/// unknown, so they reach `Q` instead of being dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Register {
-    // TODO: Implement Register component
+    id: ComponentId,
+    pins: HashMap<String, Pin>,
+    width: BusWidth,
+    trigger: Trigger,
+    timing: SetupHold,
+    clock: ClockState,
+    value: Signal,
 }
 
 impl Register {
-    pub fn new() -> Self {
-        Self {}
+    /// Create a new 8-bit rising edge register
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_width(id, BusWidth(8))
+    }
+
+    /// Create a new rising edge register with the given data width
+    pub fn new_with_width(id: ComponentId, width: BusWidth) -> Self {
+        let mut pins = HashMap::new();
+        pins.insert("D".to_string(), Pin::new_input("D", width));
+        for name in ["CLK", "CLR", "EN"] {
+            pins.insert(name.to_string(), Pin::new_input(name, BusWidth(1)));
+        }
+        pins.insert("Q".to_string(), Pin::new_output("Q", width));
+
+        Self {
+            id,
+            pins,
+            width,
+            trigger: Trigger::Rising,
+            timing: SetupHold::new(3, 1),
+            clock: ClockState::new(),
+            value: Signal::all_low(width),
+        }
+    }
+
+    /// Set the clock condition that loads the register
+    pub fn set_trigger(&mut self, trigger: Trigger) {
+        self.trigger = trigger;
+    }
+
+    /// Get the clock condition that loads the register
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    /// Set the setup and hold times of the inputs loaded on the clock edge
+    pub fn set_setup_hold(&mut self, timing: SetupHold) {
+        self.timing = timing;
+    }
+
+    /// Get the setup and hold times of the inputs loaded on the clock edge
+    pub fn setup_hold(&self) -> SetupHold {
+        self.timing
+    }
+
+    /// Get the data width
+    pub fn width(&self) -> BusWidth {
+        self.width
+    }
+
+    /// Get the stored value
+    pub fn value(&self) -> &Signal {
+        &self.value
+    }
+
+    fn input(&self, name: &str) -> Value {
+        self.pins[name].signal.as_single().unwrap_or(Value::Unknown)
     }
 }
 
-impl Default for Register {
-    fn default() -> Self {
-        Self::new()
+impl Component for Register {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        REGISTER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let triggered = self.clock.update_clock(self.input("CLK"), self.trigger);
+
+        if self.input("CLR") == Value::High {
+            self.value = Signal::all_low(self.width);
+        } else if triggered && self.input("EN") != Value::Low {
+            self.value = self.pins["D"].signal.pull(Value::Unknown);
+        }
+
+        let mut result = UpdateResult::new();
+        result.add_output("Q".to_string(), self.value.clone());
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
+    fn reset(&mut self) {
+        self.clock = ClockState::new();
+        self.value = Signal::all_low(self.width);
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        8
+    }
+
+    fn is_sequential(&self) -> bool {
+        true
+    }
+
+    fn timing_constraints(&self) -> Option<TimingConstraints> {
+        self.timing.constraints(self.trigger, &["D", "EN"])
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value((self.clock, &self.value)).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        let (clock, value): (ClockState, Signal) = Deserialize::deserialize(state)?;
+        if value.width() != self.width {
+            return Err(serde::de::Error::custom(format!(
+                "Register value has {} bits, expected {}",
+                value.width(),
+                self.width
+            )));
+        }
+        (self.clock, self.value) = (clock, value);
+        Ok(())
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Memory",
+            REGISTER_ID,
+            vec![
+                ("width", self.width.0.to_string()),
+                ("trigger", self.trigger.to_string()),
+            ],
+        ))
     }
 }
 
@@ -32,9 +185,91 @@ impl Default for Register {
 mod tests {
     use super::*;
 
+    fn drive(register: &mut Register, inputs: &[(&str, Signal)]) -> Option<u64> {
+        for (name, signal) in inputs {
+            register
+                .get_pin_mut(name)
+                .unwrap()
+                .set_signal(signal.clone())
+                .unwrap();
+        }
+        register.update(Timestamp(0)).outputs["Q"].to_u64()
+    }
+
+    fn bit(value: Value) -> Signal {
+        Signal::new_single(value)
+    }
+
+    #[test]
+    fn test_register_load_and_enable() {
+        let mut register = Register::new_with_width(ComponentId(1), BusWidth(4));
+        register.reset();
+        let data = Signal::from_u64(0xa, BusWidth(4));
+
+        assert_eq!(
+            drive(&mut register, &[("D", data), ("CLK", bit(Value::Low))]),
+            Some(0)
+        );
+        assert_eq!(
+            drive(&mut register, &[("CLK", bit(Value::High))]),
+            Some(0xa)
+        );
+
+        // A low enable holds the value across an edge
+        drive(
+            &mut register,
+            &[
+                ("D", Signal::from_u64(0x3, BusWidth(4))),
+                ("EN", bit(Value::Low)),
+                ("CLK", bit(Value::Low)),
+            ],
+        );
+        assert_eq!(
+            drive(&mut register, &[("CLK", bit(Value::High))]),
+            Some(0xa)
+        );
+    }
+
     #[test]
-    fn test_register_placeholder() {
-        let _register = Register::new();
-        // TODO: Add real tests when Register is implemented
+    fn test_register_clear() {
+        let mut register = Register::new_with_width(ComponentId(1), BusWidth(4));
+        register.set_trigger(Trigger::Falling);
+        register.reset();
+
+        drive(
+            &mut register,
+            &[
+                ("D", Signal::from_u64(0x5, BusWidth(4))),
+                ("CLK", bit(Value::High)),
+            ],
+        );
+        assert_eq!(drive(&mut register, &[("CLK", bit(Value::Low))]), Some(0x5));
+        assert_eq!(drive(&mut register, &[("CLR", bit(Value::High))]), Some(0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_register_keeps_undefined_and_wide_values() {
+        let mut register = Register::new_with_width(ComponentId(1), BusWidth(4));
+        register.reset();
+        let data = Signal::new_bus(vec![Value::High, Value::Unknown, Value::HighZ, Value::Low]);
+        drive(&mut register, &[("D", data), ("CLK", bit(Value::Low))]);
+        drive(&mut register, &[("CLK", bit(Value::High))]);
+        assert_eq!(
+            register.value().bits(),
+            &[Value::High, Value::Unknown, Value::Unknown, Value::Low]
+        );
+
+        let mut wide = Register::new_with_width(ComponentId(2), BusWidth(72));
+        wide.reset();
+        let mut data = Signal::all_low(BusWidth(72));
+        data.set_bit(70, Value::High).unwrap();
+        drive(&mut wide, &[("D", data.clone()), ("CLK", bit(Value::Low))]);
+        drive(&mut wide, &[("CLK", bit(Value::High))]);
+        assert_eq!(wide.value().bits(), data.bits());
+
+        let state = wide.save_state().unwrap();
+        wide.reset();
+        wide.restore_state(&state).unwrap();
+        assert_eq!(wide.value().get_bit(70), Some(Value::High));
+    }
+}
@@ -11,20 +11,224 @@
 //!
 //! This module implements shift register functionality equivalent to ShiftRegister.java.
 
-/// Shift Register component (placeholder for now)
+use crate::comp::{CircTool, Component, ComponentId, Pin, TimingConstraints, UpdateResult};
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::std::memory::{ClockState, SetupHold, Trigger};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Unique identifier for the Shift Register component
+/// Do NOT change as it will prevent project files from loading.
+pub const SHIFT_REGISTER_ID: &str = "Shift Register";
+
+/// Shift Register component
+///
+/// On an edge of its own `CLK` input while `SH` is not low, every stage
+/// takes the value of the previous one and the first stage takes `D`. `Q`
+/// drives the last stage. A high `CLR` clears all stages regardless of the
+/// clock.
+///
+/// With parallel load, a high `LD` on the clock edge loads every stage `i`
+/// from its own input `D<i>` instead of shifting, and output `Q<i>` shows
+/// stage `i`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShiftRegister {
-    // TODO: Implement Shift Register component
+    id: ComponentId,
+    pins: HashMap<String, Pin>,
+    width: BusWidth,
+    trigger: Trigger,
+    timing: SetupHold,
+    clock: ClockState,
+    stages: Vec<Signal>,
+    parallel: bool,
 }
 
 impl ShiftRegister {
-    pub fn new() -> Self {
-        Self {}
+    /// Create a new 1-bit, 8-stage rising edge shift register
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_config(id, BusWidth(1), 8)
+    }
+
+    /// Create a new rising edge shift register with `length` stages of
+    /// `width` bits each
+    pub fn new_with_config(id: ComponentId, width: BusWidth, length: usize) -> Self {
+        let mut pins = HashMap::new();
+        pins.insert("D".to_string(), Pin::new_input("D", width));
+        for name in ["SH", "CLK", "CLR"] {
+            pins.insert(name.to_string(), Pin::new_input(name, BusWidth(1)));
+        }
+        pins.insert("Q".to_string(), Pin::new_output("Q", width));
+
+        Self {
+            id,
+            pins,
+            width,
+            trigger: Trigger::Rising,
+            timing: SetupHold::new(2, 1),
+            clock: ClockState::new(),
+            stages: vec![Signal::from_u64(0, width); length.max(1)],
+            parallel: false,
+        }
+    }
+
+    /// Add or remove the parallel load input and the per-stage inputs and
+    /// outputs
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+        let mut pins = vec![Pin::new_input("LD", BusWidth(1))];
+        for i in 0..self.stages.len() {
+            pins.push(Pin::new_input(format!("D{}", i), self.width));
+            pins.push(Pin::new_output(format!("Q{}", i), self.width));
+        }
+        for pin in pins {
+            if parallel {
+                self.pins.insert(pin.name.clone(), pin);
+            } else {
+                self.pins.remove(&pin.name);
+            }
+        }
+    }
+
+    /// Check whether the register has parallel load
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Set the clock edge the register shifts on
+    ///
+    /// Shift registers are edge triggered only, so level triggers are
+    /// rejected.
+    pub fn set_trigger(&mut self, trigger: Trigger) -> Result<(), String> {
+        if !trigger.is_edge() {
+            return Err(format!(
+                "Shift registers cannot use a {:?} trigger",
+                trigger
+            ));
+        }
+        self.trigger = trigger;
+        Ok(())
+    }
+
+    /// Get the clock edge the register shifts on
+    pub fn trigger(&self) -> Trigger {
+        self.trigger
+    }
+
+    /// Set the setup and hold times of the inputs sampled on the clock edge
+    pub fn set_setup_hold(&mut self, timing: SetupHold) {
+        self.timing = timing;
+    }
+
+    /// Get the setup and hold times of the inputs sampled on the clock edge
+    pub fn setup_hold(&self) -> SetupHold {
+        self.timing
+    }
+
+    /// Get the number of stages
+    pub fn length(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Get the stored stages, first stage first
+    pub fn stages(&self) -> &[Signal] {
+        &self.stages
+    }
+
+    fn input(&self, name: &str) -> Value {
+        self.pins[name].signal.as_single().unwrap_or(Value::Unknown)
+    }
+
+    fn clear(&mut self) {
+        let zero = Signal::from_u64(0, self.width);
+        self.stages
+            .iter_mut()
+            .for_each(|stage| *stage = zero.clone());
     }
 }
 
-impl Default for ShiftRegister {
-    fn default() -> Self {
-        Self::new()
+impl Component for ShiftRegister {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        SHIFT_REGISTER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let triggered = self.clock.update_clock(self.input("CLK"), self.trigger);
+
+        if self.input("CLR") == Value::High {
+            self.clear();
+        } else if triggered && self.parallel && self.input("LD") == Value::High {
+            for (i, stage) in self.stages.iter_mut().enumerate() {
+                *stage = self.pins[&format!("D{}", i)].signal.clone();
+            }
+        } else if triggered && self.input("SH") != Value::Low {
+            self.stages.rotate_right(1);
+            self.stages[0] = self.pins["D"].signal.clone();
+        }
+
+        let mut result = UpdateResult::new();
+        let last = self.stages[self.stages.len() - 1].clone();
+        result.add_output("Q".to_string(), last);
+        if self.parallel {
+            for (i, stage) in self.stages.iter().enumerate() {
+                result.add_output(format!("Q{}", i), stage.clone());
+            }
+        }
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
+    fn reset(&mut self) {
+        self.clock = ClockState::new();
+        self.clear();
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        5
+    }
+
+    fn is_sequential(&self) -> bool {
+        true
+    }
+
+    fn timing_constraints(&self) -> Option<TimingConstraints> {
+        self.timing.constraints(self.trigger, &["D", "SH", "LD"])
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value((self.clock, &self.stages)).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        (self.clock, self.stages) = Deserialize::deserialize(state)?;
+        Ok(())
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Memory",
+            SHIFT_REGISTER_ID,
+            vec![
+                ("width", self.width.0.to_string()),
+                ("length", self.length().to_string()),
+                ("parallel", self.parallel.to_string()),
+                ("trigger", self.trigger.to_string()),
+            ],
+        ))
     }
 }
 
@@ -32,9 +236,84 @@ impl Default for ShiftRegister {
 mod tests {
     use super::*;
 
+    fn set(register: &mut ShiftRegister, name: &str, value: Value) {
+        register
+            .get_pin_mut(name)
+            .unwrap()
+            .set_signal(Signal::new_single(value))
+            .unwrap();
+    }
+
+    /// Apply one full clock cycle with `d` on the input and return `Q`
+    fn pulse(register: &mut ShiftRegister, d: Value) -> Value {
+        set(register, "D", d);
+        set(register, "CLK", Value::Low);
+        register.update(Timestamp(0));
+        set(register, "CLK", Value::High);
+        register.update(Timestamp(0)).outputs["Q"]
+            .as_single()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_shift_register_shifts_through_stages() {
+        let mut register = ShiftRegister::new_with_config(ComponentId(1), BusWidth(1), 3);
+        register.reset();
+
+        assert_eq!(pulse(&mut register, Value::High), Value::Low);
+        assert_eq!(pulse(&mut register, Value::Low), Value::Low);
+        assert_eq!(pulse(&mut register, Value::Low), Value::High);
+        assert_eq!(pulse(&mut register, Value::Low), Value::Low);
+    }
+
+    #[test]
+    fn test_shift_register_hold_and_clear() {
+        let mut register = ShiftRegister::new_with_config(ComponentId(1), BusWidth(1), 2);
+        register.reset();
+        pulse(&mut register, Value::High);
+
+        // Without SH the stages keep their values
+        set(&mut register, "SH", Value::Low);
+        pulse(&mut register, Value::Low);
+        assert_eq!(register.stages()[0], Signal::new_single(Value::High));
+
+        set(&mut register, "SH", Value::High);
+        assert_eq!(pulse(&mut register, Value::Low), Value::High);
+
+        set(&mut register, "CLR", Value::High);
+        register.update(Timestamp(0));
+        assert!(register
+            .stages()
+            .iter()
+            .all(|stage| *stage == Signal::new_single(Value::Low)));
+        assert!(register.set_trigger(Trigger::LowLevel).is_err());
+    }
+
     #[test]
-    fn test_shift_register_placeholder() {
-        let _shift_reg = ShiftRegister::new();
-        // TODO: Add real tests when Shift Register is implemented
+    fn test_shift_register_parallel_load() {
+        let mut register = ShiftRegister::new_with_config(ComponentId(1), BusWidth(4), 3);
+        register.set_parallel(true);
+        assert!(register.is_parallel());
+        assert_eq!(register.pins().len(), 5 + 1 + 2 * 3);
+        register.reset();
+
+        for i in 0..3 {
+            register
+                .get_pin_mut(&format!("D{}", i))
+                .unwrap()
+                .set_signal(Signal::from_u64(i as u64 + 5, BusWidth(4)))
+                .unwrap();
+        }
+        set(&mut register, "LD", Value::High);
+        set(&mut register, "CLK", Value::Low);
+        register.update(Timestamp(0));
+        set(&mut register, "CLK", Value::High);
+        let result = register.update(Timestamp(0));
+        assert_eq!(result.outputs["Q"].to_u64(), Some(7));
+        assert_eq!(result.outputs["Q0"].to_u64(), Some(5));
+        assert_eq!(result.outputs["Q1"].to_u64(), Some(6));
+
+        register.set_parallel(false);
+        assert_eq!(register.pins().len(), 5);
     }
-}
\ No newline at end of file
+}
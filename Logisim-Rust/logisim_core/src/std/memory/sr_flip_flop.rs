@@ -11,30 +11,38 @@
 //!
 //! This module implements SR Flip-Flop functionality equivalent to SRFlipFlop.java.
 
-/// SR Flip-Flop component (placeholder for now)
-pub struct SRFlipFlop {
-    // TODO: Implement SR Flip-Flop component
-}
+use crate::signal::Value;
+use crate::std::memory::{FlipFlop, FlipFlopLogic};
 
-impl SRFlipFlop {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
+/// Unique identifier for the S-R Flip-Flop component
+/// Do NOT change as it will prevent project files from loading.
+pub const SR_FLIP_FLOP_ID: &str = "S-R Flip-Flop";
 
-impl Default for SRFlipFlop {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+/// S-R Flip-Flop component
+///
+/// When the clock triggers, `S` alone sets the stored bit and `R` alone
+/// resets it; both together leave it unchanged. Since `S` and `R` are the
+/// data inputs, the asynchronous inputs are named `CLR` (reset) and `PRE`
+/// (set). They override the clock, with reset winning.
+pub type SRFlipFlop = FlipFlop<SRLogic>;
+
+/// Next state function of the S-R flip-flop
+#[derive(Debug, Clone)]
+pub struct SRLogic;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl FlipFlopLogic for SRLogic {
+    const ID: &'static str = SR_FLIP_FLOP_ID;
+    const INPUTS: &'static [&'static str] = &["S", "R"];
+    const LEVEL_TRIGGERS: bool = true;
+    const ASYNC_INPUTS: [&'static str; 2] = ["CLR", "PRE"];
 
-    #[test]
-    fn test_sr_flip_flop_placeholder() {
-        let _sr_ff = SRFlipFlop::new();
-        // TODO: Add real tests when SR Flip-Flop is implemented
+    fn next(inputs: &[Value], current: Value) -> Value {
+        match (inputs[0], inputs[1]) {
+            (Value::Low, Value::Low) => current,
+            (Value::Low, Value::High) => Value::Low,
+            (Value::High, Value::Low) => Value::High,
+            (Value::High, Value::High) => Value::Error,
+            _ => Value::Unknown,
+        }
     }
-}
\ No newline at end of file
+}
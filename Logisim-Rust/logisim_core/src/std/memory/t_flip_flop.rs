@@ -11,30 +11,39 @@
 //!
 //! This module implements T Flip-Flop functionality equivalent to TFlipFlop.java.
 
-/// T Flip-Flop component (placeholder for now)
-pub struct TFlipFlop {
-    // TODO: Implement T Flip-Flop component
-}
+use crate::signal::Value;
+use crate::std::memory::{FlipFlop, FlipFlopLogic};
 
-impl TFlipFlop {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
+/// Unique identifier for the T Flip-Flop component
+/// Do NOT change as it will prevent project files from loading.
+pub const T_FLIP_FLOP_ID: &str = "T Flip-Flop";
 
-impl Default for TFlipFlop {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+/// T Flip-Flop component
+///
+/// Toggles the stored bit on a clock edge while `T` is high. The
+/// asynchronous `R` (reset) and `S` (set) inputs override the clock, with
+/// reset winning.
+pub type TFlipFlop = FlipFlop<TLogic>;
+
+/// Next state function of the T flip-flop
+#[derive(Debug, Clone)]
+pub struct TLogic;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl FlipFlopLogic for TLogic {
+    const ID: &'static str = T_FLIP_FLOP_ID;
+    const INPUTS: &'static [&'static str] = &["T"];
+    const LEVEL_TRIGGERS: bool = false;
 
-    #[test]
-    fn test_t_flip_flop_placeholder() {
-        let _t_ff = TFlipFlop::new();
-        // TODO: Add real tests when T Flip-Flop is implemented
+    fn next(inputs: &[Value], current: Value) -> Value {
+        let current = if current == Value::Unknown {
+            Value::Low
+        } else {
+            current
+        };
+        if inputs[0] == Value::High {
+            current.not()
+        } else {
+            current
+        }
     }
-}
\ No newline at end of file
+}
@@ -16,17 +16,23 @@
 //! ## Organization
 //!
 //! The module is organized to mirror the Java package structure:
+//! - `arith`: Adders and other arithmetic components (ArithmeticLibrary)
 //! - `base`: Basic utilities and text components (BaseLibrary)
 //! - `gates`: Logic gates and related components (GatesLibrary)
+//! - `memory`: Flip-flops, registers, counters and memories (MemoryLibrary)
+//! - `plexers`: Multiplexers and related components (PlexersLibrary)
 //!
 //! ## Migration Status
 //!
 //! This represents the standard component library migration from Java to Rust,
 //! focusing on providing 1:1 functional equivalence with the original implementation.
 
+pub mod arith;
 pub mod base;
 pub mod gates;
 pub mod io;
+pub mod memory;
+pub mod plexers;
 pub mod wiring;
 
 // Re-export commonly used types
@@ -7,73 +7,58 @@
  * This is free software released under GNU GPLv3 license
  */
 
-//! Bit Selector component implementation
+//! Bit selector component implementation
 //!
-//! A bit selector extracts a range of bits from an input bus and outputs them.
-//! It can select a single bit or a range of bits from a wider bus.
+//! A bit selector splits its input into groups of bits and outputs the group chosen by its
+//! select input.
 
+use super::multiplexer::DELAY;
 use crate::{
-    comp::{Component, ComponentId, Pin, Propagator, UpdateResult},
-    data::{BitWidth, Bounds, Direction, Location},
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    data::Direction,
     signal::{BusWidth, Signal, Timestamp, Value},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Bit Selector component for bit extraction
+/// Bit selector component
 ///
-/// A bit selector takes a multi-bit input and outputs a selected range of bits.
-/// The selection can be a single bit or multiple contiguous bits.
+/// Group `i` holds input bits `i * group` and up. The group chosen by
+/// `select` is driven on `output`, with bits past the top of the input
+/// reading low.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BitSelector {
     /// Unique component identifier
     id: ComponentId,
-    /// Component pins (input and output)
+    /// Component pins (input, output, and select)
     pins: HashMap<String, Pin>,
-    /// Width of the input bus
-    input_width: BusWidth,
-    /// Width of the output (number of bits to select)
-    output_width: BusWidth,
-    /// Starting bit index for selection (0-based, from LSB)
-    start_bit: u8,
+    /// Input width in bits
+    data_width: BusWidth,
+    /// Output width in bits
+    group_width: BusWidth,
     /// Component facing direction
     facing: Direction,
-    /// Component bounds for rendering
-    bounds: Bounds,
 }
 
 impl BitSelector {
-    /// Create a new bit selector with the given ID
+    /// Create a new bit selector of one bit out of eight
     pub fn new(id: ComponentId) -> Self {
-        let mut selector = BitSelector {
-            id,
-            pins: HashMap::new(),
-            input_width: BusWidth(8), // Default 8-bit input
-            output_width: BusWidth(1), // Default 1-bit output
-            start_bit: 0, // Start from LSB
-            facing: Direction::East,
-            bounds: Bounds::new(0, 0, 40, 30),
-        };
-        selector.update_pins();
-        selector
+        Self::with_config(id, BusWidth(8), BusWidth(1), Direction::East)
     }
 
     /// Create a new bit selector with specified parameters
     pub fn with_config(
         id: ComponentId,
-        input_width: BusWidth,
-        output_width: BusWidth,
-        start_bit: u8,
+        data_width: BusWidth,
+        group_width: BusWidth,
         facing: Direction,
     ) -> Self {
         let mut selector = BitSelector {
             id,
             pins: HashMap::new(),
-            input_width,
-            output_width,
-            start_bit,
+            data_width,
+            group_width: BusWidth(group_width.0.max(1)),
             facing,
-            bounds: Bounds::new(0, 0, 40, 30),
         };
         selector.update_pins();
         selector
@@ -82,158 +67,49 @@ impl BitSelector {
     /// Update pin configuration based on current settings
     fn update_pins(&mut self) {
         self.pins.clear();
-        
-        // Add input pin
-        let input_pin = Pin::new(
-            "Input".to_string(),
-            self.input_width,
-            crate::comp::PinDirection::Input,
-            Location::new(0, 15),
+        self.pins.insert(
+            "input".to_string(),
+            Pin::new_input("input", self.data_width),
         );
-        self.pins.insert("input".to_string(), input_pin);
-        
-        // Add output pin
-        let output_pin = Pin::new(
-            "Output".to_string(),
-            self.output_width,
-            crate::comp::PinDirection::Output,
-            Location::new(40, 15),
+        self.pins.insert(
+            "output".to_string(),
+            Pin::new_output("output", self.group_width),
+        );
+        self.pins.insert(
+            "select".to_string(),
+            Pin::new_input(
+                "select",
+                Self::select_width(self.data_width, self.group_width),
+            ),
         );
-        self.pins.insert("output".to_string(), output_pin);
     }
 
-    /// Set the input width
-    pub fn set_input_width(&mut self, width: BusWidth) {
-        if width != self.input_width {
-            self.input_width = width;
-            self.validate_parameters();
-            self.update_pins();
-        }
+    /// Width of the select input, enough to number every group, ported
+    /// from `BitSelector.updatePorts`
+    pub fn select_width(data_width: BusWidth, group_width: BusWidth) -> BusWidth {
+        let groups = data_width.0.div_ceil(group_width.0.max(1));
+        BusWidth(groups.saturating_sub(1).checked_ilog2().unwrap_or(0) + 1)
     }
 
     /// Get the input width
-    pub fn input_width(&self) -> BusWidth {
-        self.input_width
-    }
-
-    /// Set the output width
-    pub fn set_output_width(&mut self, width: BusWidth) {
-        if width != self.output_width {
-            self.output_width = width;
-            self.validate_parameters();
-            self.update_pins();
-        }
+    pub fn data_width(&self) -> BusWidth {
+        self.data_width
     }
 
     /// Get the output width
-    pub fn output_width(&self) -> BusWidth {
-        self.output_width
-    }
-
-    /// Set the starting bit index
-    pub fn set_start_bit(&mut self, start_bit: u8) {
-        if start_bit != self.start_bit {
-            self.start_bit = start_bit;
-            self.validate_parameters();
-        }
-    }
-
-    /// Get the starting bit index
-    pub fn start_bit(&self) -> u8 {
-        self.start_bit
+    pub fn group_width(&self) -> BusWidth {
+        self.group_width
     }
 
     /// Set the facing direction
     pub fn set_facing(&mut self, facing: Direction) {
-        if facing != self.facing {
-            self.facing = facing;
-            self.update_pins();
-        }
+        self.facing = facing;
     }
 
     /// Get the facing direction
     pub fn facing(&self) -> Direction {
         self.facing
     }
-
-    /// Validate that the parameters are consistent and adjust if necessary
-    fn validate_parameters(&mut self) {
-        // Ensure start_bit is within input range
-        if self.start_bit >= self.input_width.bits() as u8 {
-            self.start_bit = 0;
-        }
-        
-        // Ensure output width doesn't exceed available bits from start_bit
-        let max_output_width = self.input_width.bits() - self.start_bit as u32;
-        if self.output_width.bits() > max_output_width {
-            self.output_width = BusWidth(max_output_width);
-        }
-        
-        // Ensure output width is at least 1 bit
-        if self.output_width.bits() == 0 {
-            self.output_width = BusWidth(1);
-        }
-    }
-
-    /// Get the ending bit index (inclusive)
-    pub fn end_bit(&self) -> u8 {
-        (self.start_bit + self.output_width.bits() as u8 - 1).min(self.input_width.bits() as u8 - 1)
-    }
-
-    /// Extract bits from a value based on current configuration
-    fn extract_bits(&self, input_value: &Value) -> Value {
-        match input_value {
-            Value::Zero => {
-                // All bits are zero, so output is zero
-                Value::Zero
-            }
-            Value::One => {
-                // Input is a single one bit
-                if self.input_width.bits() == 1 {
-                    if self.start_bit == 0 {
-                        Value::One
-                    } else {
-                        Value::Zero // Start bit is out of range
-                    }
-                } else {
-                    // For multi-bit interpretation of Value::One, typically means all bits are 1
-                    if self.output_width.bits() == 1 {
-                        Value::One
-                    } else {
-                        Value::Binary(vec![true; self.output_width.bits() as usize])
-                    }
-                }
-            }
-            Value::Binary(bits) => {
-                let start_idx = self.start_bit as usize;
-                let output_bits = self.output_width.bits() as usize;
-                
-                // Extract the requested bits
-                let mut output_bits_vec = Vec::with_capacity(output_bits);
-                for i in 0..output_bits {
-                    let bit_idx = start_idx + i;
-                    if bit_idx < bits.len() {
-                        output_bits_vec.push(bits[bit_idx]);
-                    } else {
-                        // Pad with false if we're reading beyond the input
-                        output_bits_vec.push(false);
-                    }
-                }
-                
-                if output_bits == 1 {
-                    if output_bits_vec[0] {
-                        Value::One
-                    } else {
-                        Value::Zero
-                    }
-                } else {
-                    Value::Binary(output_bits_vec)
-                }
-            }
-            Value::HighImpedance => Value::HighImpedance,
-            Value::Error => Value::Error,
-        }
-    }
 }
 
 impl Component for BitSelector {
@@ -242,7 +118,7 @@ impl Component for BitSelector {
     }
 
     fn name(&self) -> &str {
-        "Bit Selector"
+        "BitSelector"
     }
 
     fn pins(&self) -> &HashMap<String, Pin> {
@@ -254,44 +130,50 @@ impl Component for BitSelector {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get input signal
-        let input_value = if let Some(input_pin) = self.pins.get("input") {
-            match &input_pin.signal {
-                Some(signal) => signal.value(),
-                None => return UpdateResult::NoChange, // No input signal
+        // Ported from `BitSelector.propagate`
+        let input = &self.pins["input"].signal;
+        let output = match self.pins["select"].signal.to_u64() {
+            Some(index) => {
+                let start = index.saturating_mul(self.group_width.0 as u64);
+                let bits = (0..self.group_width.0 as u64)
+                    .map(|i| match u32::try_from(start + i) {
+                        Ok(bit) if bit < self.data_width.0 => {
+                            input.get_bit(bit).unwrap_or(Value::Error)
+                        }
+                        _ => Value::Low,
+                    })
+                    .collect();
+                Signal::new_bus(bits)
             }
-        } else {
-            return UpdateResult::NoChange;
+            None => Signal::unknown(self.group_width),
         };
 
-        // Extract the selected bits
-        let output_value = self.extract_bits(input_value);
-
-        // Set output
-        if let Some(output_pin) = self.pins.get_mut("output") {
-            let output_signal = Signal::new(self.output_width, output_value);
-            output_pin.signal = Some(output_signal);
-            UpdateResult::Changed
-        } else {
-            UpdateResult::NoChange
-        }
+        let mut result = UpdateResult::new();
+        result.add_output("output".to_string(), output);
+        result.set_delay(self.propagation_delay());
+        result
     }
 
     fn reset(&mut self) {
-        // Clear all pin signals
         for pin in self.pins.values_mut() {
-            pin.signal = None;
+            pin.signal = Signal::unknown(pin.width);
         }
     }
 
     fn propagation_delay(&self) -> u64 {
-        super::plexers_library::PlexersLibrary::DELAY
+        DELAY
     }
-}
 
-impl Propagator for BitSelector {
-    fn propagate(&mut self, timestamp: Timestamp) -> UpdateResult {
-        self.update(timestamp)
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Plexers",
+            "BitSelector",
+            vec![
+                ("facing", self.facing.to_string()),
+                ("width", self.data_width.0.to_string()),
+                ("group", self.group_width.0.to_string()),
+            ],
+        ))
     }
 }
 
@@ -299,139 +181,51 @@ impl Propagator for BitSelector {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_bit_selector_creation() {
-        let selector = BitSelector::new(ComponentId(1));
-        assert_eq!(selector.id(), ComponentId(1));
-        assert_eq!(selector.name(), "Bit Selector");
-        assert_eq!(selector.input_width(), BusWidth(8));
-        assert_eq!(selector.output_width(), BusWidth(1));
-        assert_eq!(selector.start_bit(), 0);
-    }
-
-    #[test]
-    fn test_bit_selector_configuration() {
-        let mut selector = BitSelector::new(ComponentId(1));
-        
-        // Test setting input width
-        selector.set_input_width(BusWidth(16));
-        assert_eq!(selector.input_width(), BusWidth(16));
-        
-        // Test setting output width
-        selector.set_output_width(BusWidth(4));
-        assert_eq!(selector.output_width(), BusWidth(4));
-        
-        // Test setting start bit
-        selector.set_start_bit(2);
-        assert_eq!(selector.start_bit(), 2);
-        assert_eq!(selector.end_bit(), 5); // start_bit + output_width - 1
-        
-        // Test setting facing direction
-        selector.set_facing(Direction::North);
-        assert_eq!(selector.facing(), Direction::North);
-    }
-
-    #[test]
-    fn test_bit_selector_parameter_validation() {
-        let mut selector = BitSelector::new(ComponentId(1));
-        selector.set_input_width(BusWidth(8));
-        
-        // Test start bit validation - should clamp to valid range
-        selector.set_start_bit(10); // Beyond input width
-        assert_eq!(selector.start_bit(), 0); // Should be reset to 0
-        
-        // Test output width validation
-        selector.set_start_bit(6);
-        selector.set_output_width(BusWidth(8)); // Too wide for remaining bits
-        assert_eq!(selector.output_width(), BusWidth(2)); // Should be clamped to available bits
-    }
-
-    #[test]
-    fn test_bit_selector_pins() {
-        let selector = BitSelector::new(ComponentId(1));
-        let pins = selector.pins();
-        
-        // Should have 1 input + 1 output = 2 pins
-        assert_eq!(pins.len(), 2);
-        assert!(pins.contains_key("input"));
-        assert!(pins.contains_key("output"));
-    }
-
-    #[test]
-    fn test_bit_selector_reset() {
-        let mut selector = BitSelector::new(ComponentId(1));
-        
-        // Set some pin signals
-        if let Some(pin) = selector.pins.get_mut("input") {
-            pin.signal = Some(Signal::new(BusWidth(8), Value::Binary(vec![true, false, true, false, false, false, false, false])));
-        }
-        
-        // Reset should clear all signals
-        selector.reset();
-        
-        for pin in selector.pins().values() {
-            assert!(pin.signal.is_none());
-        }
-    }
-
-    #[test]
-    fn test_bit_selector_propagation_delay() {
-        let selector = BitSelector::new(ComponentId(1));
-        assert_eq!(selector.propagation_delay(), 3);
+    fn select(selector: &mut BitSelector, input: u64, index: u64) -> Option<u64> {
+        let input = Signal::from_u64(input, selector.data_width());
+        let width = BitSelector::select_width(selector.data_width(), selector.group_width());
+        let index = Signal::from_u64(index, width);
+        selector
+            .get_pin_mut("input")
+            .unwrap()
+            .set_signal(input)
+            .unwrap();
+        selector
+            .get_pin_mut("select")
+            .unwrap()
+            .set_signal(index)
+            .unwrap();
+        selector.update(Timestamp(0)).outputs["output"].to_u64()
     }
 
     #[test]
-    fn test_bit_extraction() {
-        let mut selector = BitSelector::new(ComponentId(1));
-        selector.set_input_width(BusWidth(8));
-        selector.set_output_width(BusWidth(4));
-        selector.set_start_bit(2);
-        
-        // Test extracting bits from binary value
-        let input_bits = vec![true, false, true, true, false, true, false, false]; // 0b00101101
-        let input_value = Value::Binary(input_bits);
-        let output_value = selector.extract_bits(&input_value);
-        
-        // Should extract bits 2-5: [true, true, false, true]
-        match output_value {
-            Value::Binary(bits) => {
-                assert_eq!(bits.len(), 4);
-                assert_eq!(bits, vec![true, true, false, true]);
-            }
-            _ => panic!("Expected binary output"),
+    fn test_select_width() {
+        let cases = [
+            (8, 1, 3),
+            (8, 4, 1),
+            (8, 8, 1),
+            (9, 4, 2),
+            (32, 1, 5),
+            (1, 1, 1),
+        ];
+        for (data, group, expected) in cases {
+            let width = BitSelector::select_width(BusWidth(data), BusWidth(group));
+            assert_eq!(width, BusWidth(expected));
         }
     }
 
     #[test]
-    fn test_single_bit_extraction() {
+    fn test_bit_selector_selects_group() {
         let mut selector = BitSelector::new(ComponentId(1));
-        selector.set_input_width(BusWidth(8));
-        selector.set_output_width(BusWidth(1));
-        selector.set_start_bit(3);
-        
-        // Test extracting single bit
-        let input_bits = vec![true, false, true, true, false, true, false, false]; // bit 3 is true
-        let input_value = Value::Binary(input_bits);
-        let output_value = selector.extract_bits(&input_value);
-        
-        assert_eq!(output_value, Value::One);
-        
-        // Test extracting single bit that is false
-        selector.set_start_bit(1); // bit 1 is false
-        let output_value = selector.extract_bits(&input_value);
-        assert_eq!(output_value, Value::Zero);
-    }
+        assert_eq!(selector.name(), "BitSelector");
+        assert_eq!(select(&mut selector, 0b0100_0000, 6), Some(1));
+        assert_eq!(select(&mut selector, 0b0100_0000, 5), Some(0));
 
-    #[test] 
-    fn test_error_propagation() {
-        let selector = BitSelector::new(ComponentId(1));
-        
-        // Test error value propagation
-        let output_value = selector.extract_bits(&Value::Error);
-        assert_eq!(output_value, Value::Error);
-        
-        // Test high impedance propagation
-        let output_value = selector.extract_bits(&Value::HighImpedance);
-        assert_eq!(output_value, Value::HighImpedance);
+        // The top group of a 10-bit input is padded with zeros
+        let mut selector =
+            BitSelector::with_config(ComponentId(1), BusWidth(10), BusWidth(4), Direction::East);
+        assert_eq!(select(&mut selector, 0x3a5, 1), Some(0xa));
+        assert_eq!(select(&mut selector, 0x3a5, 2), Some(0x3));
+        assert_eq!(select(&mut selector, 0x3a5, 3), Some(0));
     }
-}
\ No newline at end of file
+}
@@ -12,9 +12,10 @@
 //! A decoder is an address decoder that activates one output based on a binary input address.
 //! For an n-bit input, it has 2^n outputs where only one output is active (high) at a time.
 
+use super::multiplexer::DELAY;
 use crate::{
-    comp::{Component, ComponentId, Pin, Propagator, UpdateResult},
-    data::{BitWidth, Bounds, Direction, Location},
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    data::Direction,
     signal::{BusWidth, Signal, Timestamp, Value},
 };
 use serde::{Deserialize, Serialize};
@@ -22,56 +23,43 @@ use std::collections::HashMap;
 
 /// Decoder component for address decoding
 ///
-/// A decoder takes an n-bit address input and activates one of 2^n outputs.
-/// Only the output corresponding to the input address is set to high; all others are low.
+/// A decoder takes an n-bit select input and activates one of 2^n outputs.
+/// Only the output matching the select value is high; the others are low,
+/// or floating when the decoder is three-state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Decoder {
     /// Unique component identifier
     id: ComponentId,
-    /// Component pins (input, outputs, and optional enable)
+    /// Component pins (select, outputs, and optional enable)
     pins: HashMap<String, Pin>,
-    /// Number of input address bits (determines number of outputs)
-    input_bits: u8,
+    /// Number of select bits (determines number of outputs)
+    select_bits: u8,
     /// Component facing direction
     facing: Direction,
-    /// Whether to use three-state outputs
+    /// Whether the outputs that are not selected float
     tristate: bool,
+    /// Whether the outputs float rather than go low while disabled
+    disabled_floating: bool,
     /// Whether component has enable input
     enable: bool,
-    /// Component bounds for rendering
-    bounds: Bounds,
 }
 
 impl Decoder {
     /// Create a new decoder with the given ID
     pub fn new(id: ComponentId) -> Self {
-        let mut decoder = Decoder {
-            id,
-            pins: HashMap::new(),
-            input_bits: 2, // Default to 2-bit input (4 outputs)
-            facing: Direction::East,
-            tristate: false,
-            enable: false,
-            bounds: Bounds::new(0, 0, 40, 50),
-        };
-        decoder.update_pins();
-        decoder
+        Self::with_config(id, 1, Direction::East)
     }
 
     /// Create a new decoder with specified parameters
-    pub fn with_config(
-        id: ComponentId,
-        input_bits: u8,
-        facing: Direction,
-    ) -> Self {
+    pub fn with_config(id: ComponentId, select_bits: u8, facing: Direction) -> Self {
         let mut decoder = Decoder {
             id,
             pins: HashMap::new(),
-            input_bits: input_bits.clamp(1, 8),
+            select_bits: select_bits.clamp(1, 8),
             facing,
             tristate: false,
+            disabled_floating: false,
             enable: false,
-            bounds: Bounds::new(0, 0, 40, 50),
         };
         decoder.update_pins();
         decoder
@@ -80,61 +68,79 @@ impl Decoder {
     /// Update pin configuration based on current settings
     fn update_pins(&mut self) {
         self.pins.clear();
-        
-        let num_outputs = 1 << self.input_bits; // 2^input_bits
-        
-        // Add address input
-        let input_pin = Pin::new(
-            "Address".to_string(),
-            BusWidth(self.input_bits as u32),
-            crate::comp::PinDirection::Input,
-            Location::new(0, 25),
-        );
-        self.pins.insert("address".to_string(), input_pin);
-        
-        // Add outputs (one for each possible address)
-        for i in 0..num_outputs {
+
+        // One output per select value
+        for i in 0..self.num_outputs() {
             let pin_name = format!("output_{}", i);
-            let pin = Pin::new(
-                format!("Output {}", i),
-                BusWidth(1), // Each output is single bit
-                crate::comp::PinDirection::Output,
-                Location::new(40, 5 + i * 5),
-            );
+            let pin = Pin::new_output(&pin_name, BusWidth(1));
             self.pins.insert(pin_name, pin);
         }
-        
-        // Add enable input if enabled
+
+        self.pins.insert(
+            "select".to_string(),
+            Pin::new_input("select", BusWidth(self.select_bits as u32)),
+        );
         if self.enable {
-            let enable_pin = Pin::new(
-                "Enable".to_string(),
-                BusWidth(1),
-                crate::comp::PinDirection::Input,
-                Location::new(20, 0),
-            );
-            self.pins.insert("enable".to_string(), enable_pin);
+            self.pins
+                .insert("enable".to_string(), Pin::new_input("enable", BusWidth(1)));
+        }
+    }
+
+    /// Compute the outputs for the current inputs, ported from
+    /// `Decoder.propagate`
+    ///
+    /// Returns the index of the selected output, if any, and the value of
+    /// every other output.
+    fn outputs(&self) -> (Option<usize>, Value) {
+        let others = if self.tristate {
+            Value::HighZ
+        } else {
+            Value::Low
+        };
+        let enable = if self.enable {
+            self.pins["enable"]
+                .signal
+                .as_single()
+                .unwrap_or(Value::Unknown)
+        } else {
+            Value::High
+        };
+        if enable == Value::Low {
+            let disabled = if self.disabled_floating {
+                Value::HighZ
+            } else {
+                Value::Low
+            };
+            return (None, disabled);
+        }
+        if enable == Value::Error {
+            return (None, Value::Error);
+        }
+
+        let select = &self.pins["select"].signal;
+        match select.to_u64() {
+            Some(index) => (Some(index as usize), others),
+            None if select.has_error() => (None, Value::Error),
+            None => (None, Value::Unknown),
         }
     }
 
-    /// Set the number of input bits
-    pub fn set_input_bits(&mut self, bits: u8) {
-        if bits != self.input_bits && bits >= 1 && bits <= 8 {
-            self.input_bits = bits;
+    /// Set the number of select bits
+    pub fn set_select_bits(&mut self, bits: u8) {
+        if bits != self.select_bits && (1..=8).contains(&bits) {
+            self.select_bits = bits;
             self.update_pins();
         }
     }
 
-    /// Get the number of input bits
-    pub fn input_bits(&self) -> u8 {
-        self.input_bits
+    /// Get the number of select bits
+    pub fn select_bits(&self) -> u8 {
+        self.select_bits
     }
 
     /// Set the facing direction
     pub fn set_facing(&mut self, facing: Direction) {
-        if facing != self.facing {
-            self.facing = facing;
-            self.update_pins();
-        }
+        self.facing = facing;
     }
 
     /// Get the facing direction
@@ -142,16 +148,26 @@ impl Decoder {
         self.facing
     }
 
-    /// Set tristate output mode
+    /// Set whether the outputs that are not selected float
     pub fn set_tristate(&mut self, tristate: bool) {
         self.tristate = tristate;
     }
 
-    /// Check if tristate output is enabled
+    /// Check whether the outputs that are not selected float
     pub fn tristate(&self) -> bool {
         self.tristate
     }
 
+    /// Set whether the outputs float rather than go low while disabled
+    pub fn set_disabled_floating(&mut self, floating: bool) {
+        self.disabled_floating = floating;
+    }
+
+    /// Check whether the outputs float while disabled
+    pub fn disabled_floating(&self) -> bool {
+        self.disabled_floating
+    }
+
     /// Set enable input
     pub fn set_enable(&mut self, enable: bool) {
         if enable != self.enable {
@@ -165,9 +181,9 @@ impl Decoder {
         self.enable
     }
 
-    /// Calculate the number of outputs based on input bits
+    /// Calculate the number of outputs based on select bits
     pub fn num_outputs(&self) -> usize {
-        1 << self.input_bits
+        1 << self.select_bits
     }
 }
 
@@ -189,132 +205,43 @@ impl Component for Decoder {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get address input signal
-        let address_value = if let Some(address_pin) = self.pins.get("address") {
-            match &address_pin.signal {
-                Some(signal) => signal.value(),
-                None => return UpdateResult::NoChange, // No address signal
-            }
-        } else {
-            return UpdateResult::NoChange;
-        };
-
-        // Check if enabled (if enable pin exists)
-        if self.enable {
-            if let Some(enable_pin) = self.pins.get("enable") {
-                match &enable_pin.signal {
-                    Some(signal) => {
-                        if signal.value() == Value::Zero {
-                            // Component is disabled, set all outputs to zero or high impedance
-                            let disabled_value = if self.tristate {
-                                Value::HighImpedance
-                            } else {
-                                Value::Zero
-                            };
-                            
-                            for i in 0..self.num_outputs() {
-                                let output_pin_name = format!("output_{}", i);
-                                if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                                    let output_signal = Signal::new(BusWidth(1), disabled_value.clone());
-                                    output_pin.signal = Some(output_signal);
-                                }
-                            }
-                            return UpdateResult::Changed;
-                        }
-                    }
-                    None => return UpdateResult::NoChange,
-                }
-            }
-        }
-
-        // Convert address value to index
-        let address_index = match address_value {
-            Value::Zero => 0,
-            Value::One => {
-                if self.input_bits == 1 {
-                    1
-                } else {
-                    // For multi-bit inputs, Value::One represents the value 1
-                    1
-                }
-            }
-            Value::Binary(bits) => {
-                // Convert binary representation to index
-                bits.iter().enumerate().fold(0usize, |acc, (i, &bit)| {
-                    acc + if bit { 1 << i } else { 0 }
-                })
-            }
-            Value::HighImpedance | Value::Error => {
-                // Invalid address signal - set all outputs to error or zero
-                let error_value = if self.tristate {
-                    Value::HighImpedance
-                } else {
-                    Value::Zero
-                };
-                
-                for i in 0..self.num_outputs() {
-                    let output_pin_name = format!("output_{}", i);
-                    if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                        let output_signal = Signal::new(BusWidth(1), error_value.clone());
-                        output_pin.signal = Some(output_signal);
-                    }
-                }
-                return UpdateResult::Changed;
-            }
-        };
-
-        // Ensure address index is within valid range
-        if address_index >= self.num_outputs() {
-            // Invalid address - set all outputs to zero
-            for i in 0..self.num_outputs() {
-                let output_pin_name = format!("output_{}", i);
-                if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                    let output_signal = Signal::new(BusWidth(1), Value::Zero);
-                    output_pin.signal = Some(output_signal);
-                }
-            }
-            return UpdateResult::Changed;
-        }
-
-        // Set outputs: addressed output gets high, others get low
-        let mut changed = false;
+        let (selected, others) = self.outputs();
+        let mut result = UpdateResult::new();
         for i in 0..self.num_outputs() {
-            let output_pin_name = format!("output_{}", i);
-            if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                let output_value = if i == address_index {
-                    Value::One // Active output
-                } else {
-                    Value::Zero // Inactive outputs
-                };
-                
-                let output_signal = Signal::new(BusWidth(1), output_value);
-                output_pin.signal = Some(output_signal);
-                changed = true;
-            }
-        }
-
-        if changed {
-            UpdateResult::Changed
-        } else {
-            UpdateResult::NoChange
+            let value = if selected == Some(i) {
+                Value::High
+            } else {
+                others
+            };
+            result.add_output(format!("output_{}", i), Signal::new_single(value));
         }
+        result.set_delay(self.propagation_delay());
+        result
     }
 
     fn reset(&mut self) {
-        // Clear all pin signals
         for pin in self.pins.values_mut() {
-            pin.signal = None;
+            pin.signal = Signal::unknown(pin.width);
         }
     }
 
     fn propagation_delay(&self) -> u64 {
-        super::plexers_library::PlexersLibrary::DELAY
+        DELAY
     }
-}
 
-impl Propagator for Decoder {
-    fn propagate(&mut self, timestamp: Timestamp) -> UpdateResult {
-        self.update(timestamp)
+    fn circ_tool(&self) -> Option<CircTool> {
+        let disabled = if self.disabled_floating { "Z" } else { "0" };
+        Some(CircTool::new(
+            "#Plexers",
+            "Decoder",
+            vec![
+                ("facing", self.facing.to_string()),
+                ("select", self.select_bits.to_string()),
+                ("tristate", self.tristate.to_string()),
+                ("disabled", disabled.to_string()),
+                ("enable", self.enable.to_string()),
+            ],
+        ))
     }
 }
 
@@ -322,105 +249,70 @@ impl Propagator for Decoder {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_decoder_creation() {
-        let decoder = Decoder::new(ComponentId(1));
-        assert_eq!(decoder.id(), ComponentId(1));
-        assert_eq!(decoder.name(), "Decoder");
-        assert_eq!(decoder.input_bits(), 2);
-        assert_eq!(decoder.num_outputs(), 4);
-    }
-
-    #[test]
-    fn test_decoder_configuration() {
-        let mut decoder = Decoder::new(ComponentId(1));
-        
-        // Test setting input bits
-        decoder.set_input_bits(3);
-        assert_eq!(decoder.input_bits(), 3);
-        assert_eq!(decoder.num_outputs(), 8);
-        
-        // Test setting facing direction
-        decoder.set_facing(Direction::North);
-        assert_eq!(decoder.facing(), Direction::North);
-        
-        // Test enabling tristate
-        decoder.set_tristate(true);
-        assert!(decoder.tristate());
-        
-        // Test enabling enable input
-        decoder.set_enable(true);
-        assert!(decoder.enable());
+    fn decode(decoder: &mut Decoder, select: Signal) -> Vec<Value> {
+        decoder
+            .get_pin_mut("select")
+            .unwrap()
+            .set_signal(select)
+            .unwrap();
+        let result = decoder.update(Timestamp(0));
+        (0..decoder.num_outputs())
+            .map(|i| {
+                result.outputs[&format!("output_{}", i)]
+                    .as_single()
+                    .unwrap()
+            })
+            .collect()
     }
 
     #[test]
     fn test_decoder_pins() {
-        let decoder = Decoder::new(ComponentId(1));
-        let pins = decoder.pins();
-        
-        // Should have 1 address input + 4 outputs = 5 pins
-        assert_eq!(pins.len(), 5);
-        assert!(pins.contains_key("address"));
-        assert!(pins.contains_key("output_0"));
-        assert!(pins.contains_key("output_1"));
-        assert!(pins.contains_key("output_2"));
-        assert!(pins.contains_key("output_3"));
-        
-        // Test with more input bits
-        let mut decoder = Decoder::new(ComponentId(2));
-        decoder.set_input_bits(3);
-        let pins = decoder.pins();
-        
-        // Should have 1 address input + 8 outputs = 9 pins
-        assert_eq!(pins.len(), 9);
-        for i in 0..8 {
-            assert!(pins.contains_key(&format!("output_{}", i)));
-        }
-    }
-
-    #[test]
-    fn test_decoder_with_enable() {
-        let mut decoder = Decoder::new(ComponentId(1));
+        let mut decoder = Decoder::with_config(ComponentId(1), 2, Direction::East);
+        assert_eq!(decoder.name(), "Decoder");
+        assert_eq!(decoder.num_outputs(), 4);
+        // 4 outputs + 1 select = 5 pins
+        assert_eq!(decoder.pins().len(), 5);
         decoder.set_enable(true);
-        let pins = decoder.pins();
-        
-        // Should have 1 address input + 4 outputs + 1 enable = 6 pins
-        assert_eq!(pins.len(), 6);
-        assert!(pins.contains_key("enable"));
+        assert!(decoder.pins().contains_key("enable"));
+        assert_eq!(decoder.propagation_delay(), 3);
     }
 
     #[test]
-    fn test_decoder_reset() {
-        let mut decoder = Decoder::new(ComponentId(1));
-        
-        // Set some pin signals
-        if let Some(pin) = decoder.pins.get_mut("address") {
-            pin.signal = Some(Signal::new(BusWidth(2), Value::Binary(vec![true, false])));
-        }
-        
-        // Reset should clear all signals
-        decoder.reset();
-        
-        for pin in decoder.pins().values() {
-            assert!(pin.signal.is_none());
-        }
-    }
+    fn test_decoder_selects_output() {
+        use Value::{Error, High, HighZ, Low, Unknown};
+        let mut decoder = Decoder::with_config(ComponentId(1), 2, Direction::East);
 
-    #[test]
-    fn test_decoder_propagation_delay() {
-        let decoder = Decoder::new(ComponentId(1));
-        assert_eq!(decoder.propagation_delay(), 3);
+        let outputs = decode(&mut decoder, Signal::from_u64(2, BusWidth(2)));
+        assert_eq!(outputs, vec![Low, Low, High, Low]);
+        let outputs = decode(&mut decoder, Signal::unknown(BusWidth(2)));
+        assert_eq!(outputs, vec![Unknown; 4]);
+        let outputs = decode(&mut decoder, Signal::error(BusWidth(2)));
+        assert_eq!(outputs, vec![Error; 4]);
+
+        decoder.set_tristate(true);
+        let outputs = decode(&mut decoder, Signal::from_u64(1, BusWidth(2)));
+        assert_eq!(outputs, vec![HighZ, High, HighZ, HighZ]);
     }
 
     #[test]
-    fn test_decoder_single_bit_input() {
+    fn test_decoder_disabled() {
         let mut decoder = Decoder::new(ComponentId(1));
-        decoder.set_input_bits(1);
-        assert_eq!(decoder.num_outputs(), 2);
-        
-        let pins = decoder.pins();
-        assert_eq!(pins.len(), 3); // 1 address input + 2 outputs
-        assert!(pins.contains_key("output_0"));
-        assert!(pins.contains_key("output_1"));
+        decoder.set_enable(true);
+        decoder
+            .get_pin_mut("enable")
+            .unwrap()
+            .set_signal(Signal::new_single(Value::Low))
+            .unwrap();
+        let select = Signal::from_u64(1, BusWidth(1));
+        assert_eq!(
+            decode(&mut decoder, select.clone()),
+            vec![Value::Low, Value::Low]
+        );
+
+        decoder.set_disabled_floating(true);
+        assert_eq!(
+            decode(&mut decoder, select),
+            vec![Value::HighZ, Value::HighZ]
+        );
     }
-}
\ No newline at end of file
+}
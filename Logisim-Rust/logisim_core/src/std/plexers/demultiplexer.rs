@@ -12,9 +12,10 @@
 //! A demultiplexer (DEMUX) is a data router that routes a single input to one of several outputs
 //! based on a selection signal. The selection signal determines which output receives the input data.
 
+use super::multiplexer::DELAY;
 use crate::{
-    comp::{Component, ComponentId, Pin, Propagator, UpdateResult},
-    data::{BitWidth, Bounds, Direction, Location},
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    data::Direction,
     signal::{BusWidth, Signal, Timestamp, Value},
 };
 use serde::{Deserialize, Serialize};
@@ -23,12 +24,13 @@ use std::collections::HashMap;
 /// Demultiplexer component for data routing
 ///
 /// A demultiplexer routes a single input to one of 2^n outputs based on an n-bit selection signal.
-/// Only the selected output receives the input data; other outputs are set to zero or high impedance.
+/// Only the selected output receives the input data; the others are low,
+/// or floating when the demultiplexer is three-state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Demultiplexer {
     /// Unique component identifier
     id: ComponentId,
-    /// Component pins (input, outputs, and select)
+    /// Component pins (input, outputs, select, and optional enable)
     pins: HashMap<String, Pin>,
     /// Number of select bits (determines number of outputs)
     select_bits: u8,
@@ -36,29 +38,18 @@ pub struct Demultiplexer {
     data_width: BusWidth,
     /// Component facing direction
     facing: Direction,
-    /// Whether to use three-state outputs
+    /// Whether the outputs that are not selected float
     tristate: bool,
+    /// Whether the outputs float rather than go low while disabled
+    disabled_floating: bool,
     /// Whether component has enable input
     enable: bool,
-    /// Component bounds for rendering
-    bounds: Bounds,
 }
 
 impl Demultiplexer {
     /// Create a new demultiplexer with the given ID
     pub fn new(id: ComponentId) -> Self {
-        let mut demultiplexer = Demultiplexer {
-            id,
-            pins: HashMap::new(),
-            select_bits: 1,
-            data_width: BusWidth(1),
-            facing: Direction::East,
-            tristate: false,
-            enable: false,
-            bounds: Bounds::new(0, 0, 40, 30),
-        };
-        demultiplexer.update_pins();
-        demultiplexer
+        Self::with_config(id, 1, BusWidth(1), Direction::East)
     }
 
     /// Create a new demultiplexer with specified parameters
@@ -75,8 +66,8 @@ impl Demultiplexer {
             data_width,
             facing,
             tristate: false,
+            disabled_floating: false,
             enable: false,
-            bounds: Bounds::new(0, 0, 40, 30),
         };
         demultiplexer.update_pins();
         demultiplexer
@@ -85,54 +76,72 @@ impl Demultiplexer {
     /// Update pin configuration based on current settings
     fn update_pins(&mut self) {
         self.pins.clear();
-        
-        let num_outputs = 1 << self.select_bits; // 2^select_bits
-        
-        // Add input
-        let input_pin = Pin::new(
-            "Input".to_string(),
-            self.data_width,
-            crate::comp::PinDirection::Input,
-            Location::new(0, 15),
-        );
-        self.pins.insert("input".to_string(), input_pin);
-        
-        // Add data outputs
-        for i in 0..num_outputs {
+
+        // Data outputs, one per select value
+        for i in 0..self.num_outputs() {
             let pin_name = format!("output_{}", i);
-            let pin = Pin::new(
-                format!("Output {}", i),
-                self.data_width,
-                crate::comp::PinDirection::Output,
-                Location::new(40, 10 + i * 10),
-            );
+            let pin = Pin::new_output(&pin_name, self.data_width);
             self.pins.insert(pin_name, pin);
         }
-        
-        // Add select input
-        let select_pin = Pin::new(
-            "Select".to_string(),
-            BusWidth(self.select_bits as u32),
-            crate::comp::PinDirection::Input,
-            Location::new(20, 30),
+
+        self.pins.insert(
+            "input".to_string(),
+            Pin::new_input("input", self.data_width),
+        );
+        self.pins.insert(
+            "select".to_string(),
+            Pin::new_input("select", BusWidth(self.select_bits as u32)),
         );
-        self.pins.insert("select".to_string(), select_pin);
-        
-        // Add enable input if enabled
         if self.enable {
-            let enable_pin = Pin::new(
-                "Enable".to_string(),
-                BusWidth(1),
-                crate::comp::PinDirection::Input,
-                Location::new(20, 0),
-            );
-            self.pins.insert("enable".to_string(), enable_pin);
+            self.pins
+                .insert("enable".to_string(), Pin::new_input("enable", BusWidth(1)));
+        }
+    }
+
+    /// Compute the outputs for the current inputs, ported from
+    /// `Demultiplexer.propagate`
+    ///
+    /// Returns the index of the selected output, if any, and the value of
+    /// every other output.
+    fn outputs(&self) -> (Option<usize>, Signal) {
+        let enable = if self.enable {
+            self.pins["enable"]
+                .signal
+                .as_single()
+                .unwrap_or(Value::Unknown)
+        } else {
+            Value::High
+        };
+        if enable == Value::Low {
+            let disabled = if self.disabled_floating {
+                Value::HighZ
+            } else {
+                Value::Low
+            };
+            return (None, Signal::new_uniform(self.data_width, disabled));
+        }
+        if enable == Value::Error {
+            return (None, Signal::error(self.data_width));
+        }
+
+        let select = &self.pins["select"].signal;
+        match select.to_u64() {
+            Some(index) => {
+                let others = if self.tristate {
+                    Signal::high_z(self.data_width)
+                } else {
+                    Signal::all_low(self.data_width)
+                };
+                (Some(index as usize), others)
+            }
+            None if select.has_error() => (None, Signal::error(self.data_width)),
+            None => (None, Signal::unknown(self.data_width)),
         }
     }
 
     /// Set the number of select bits
     pub fn set_select_bits(&mut self, bits: u8) {
-        if bits != self.select_bits && bits >= 1 && bits <= 8 {
+        if bits != self.select_bits && (1..=8).contains(&bits) {
             self.select_bits = bits;
             self.update_pins();
         }
@@ -143,14 +152,6 @@ impl Demultiplexer {
         self.select_bits
     }
 
-    /// Set the data width
-    pub fn set_data_width(&mut self, width: BusWidth) {
-        if width != self.data_width {
-            self.data_width = width;
-            self.update_pins();
-        }
-    }
-
     /// Get the data width
     pub fn data_width(&self) -> BusWidth {
         self.data_width
@@ -158,10 +159,7 @@ impl Demultiplexer {
 
     /// Set the facing direction
     pub fn set_facing(&mut self, facing: Direction) {
-        if facing != self.facing {
-            self.facing = facing;
-            self.update_pins();
-        }
+        self.facing = facing;
     }
 
     /// Get the facing direction
@@ -169,16 +167,26 @@ impl Demultiplexer {
         self.facing
     }
 
-    /// Set tristate output mode
+    /// Set whether the outputs that are not selected float
     pub fn set_tristate(&mut self, tristate: bool) {
         self.tristate = tristate;
     }
 
-    /// Check if tristate output is enabled
+    /// Check whether the outputs that are not selected float
     pub fn tristate(&self) -> bool {
         self.tristate
     }
 
+    /// Set whether the outputs float rather than go low while disabled
+    pub fn set_disabled_floating(&mut self, floating: bool) {
+        self.disabled_floating = floating;
+    }
+
+    /// Check whether the outputs float while disabled
+    pub fn disabled_floating(&self) -> bool {
+        self.disabled_floating
+    }
+
     /// Set enable input
     pub fn set_enable(&mut self, enable: bool) {
         if enable != self.enable {
@@ -216,131 +224,44 @@ impl Component for Demultiplexer {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get input signal
-        let input_value = if let Some(input_pin) = self.pins.get("input") {
-            match &input_pin.signal {
-                Some(signal) => signal.value().clone(),
-                None => Value::HighImpedance, // No input signal
-            }
-        } else {
-            return UpdateResult::NoChange;
-        };
-
-        // Get select signal
-        let select_value = if let Some(select_pin) = self.pins.get("select") {
-            match &select_pin.signal {
-                Some(signal) => signal.value(),
-                None => return UpdateResult::NoChange, // No select signal
-            }
-        } else {
-            return UpdateResult::NoChange;
-        };
-
-        // Check if enabled (if enable pin exists)
-        if self.enable {
-            if let Some(enable_pin) = self.pins.get("enable") {
-                match &enable_pin.signal {
-                    Some(signal) => {
-                        if signal.value() == Value::Zero {
-                            // Component is disabled, set all outputs to high impedance or zero
-                            let disabled_value = if self.tristate {
-                                Value::HighImpedance
-                            } else {
-                                Value::Zero
-                            };
-                            
-                            for i in 0..self.num_outputs() {
-                                let output_pin_name = format!("output_{}", i);
-                                if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                                    let output_signal = Signal::new(self.data_width, disabled_value.clone());
-                                    output_pin.signal = Some(output_signal);
-                                }
-                            }
-                            return UpdateResult::Changed;
-                        }
-                    }
-                    None => return UpdateResult::NoChange,
-                }
-            }
-        }
-
-        // Convert select value to index
-        let select_index = match select_value {
-            Value::Zero => 0,
-            Value::One => 1,
-            Value::Binary(bits) => {
-                // Convert binary representation to index
-                bits.iter().enumerate().fold(0usize, |acc, (i, &bit)| {
-                    acc + if bit { 1 << i } else { 0 }
-                })
-            }
-            Value::HighImpedance | Value::Error => {
-                // Invalid select signal - set all outputs to error
-                for i in 0..self.num_outputs() {
-                    let output_pin_name = format!("output_{}", i);
-                    if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                        let output_signal = Signal::new(self.data_width, Value::Error);
-                        output_pin.signal = Some(output_signal);
-                    }
-                }
-                return UpdateResult::Changed;
-            }
-        };
-
-        // Ensure select index is within valid range
-        if select_index >= self.num_outputs() {
-            // Invalid select index - set all outputs to error
-            for i in 0..self.num_outputs() {
-                let output_pin_name = format!("output_{}", i);
-                if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                    let output_signal = Signal::new(self.data_width, Value::Error);
-                    output_pin.signal = Some(output_signal);
-                }
-            }
-            return UpdateResult::Changed;
-        }
-
-        // Set outputs: selected output gets input value, others get zero/high-impedance
-        let mut changed = false;
+        let (selected, others) = self.outputs();
+        let mut result = UpdateResult::new();
         for i in 0..self.num_outputs() {
-            let output_pin_name = format!("output_{}", i);
-            if let Some(output_pin) = self.pins.get_mut(&output_pin_name) {
-                let output_value = if i == select_index {
-                    input_value.clone()
-                } else if self.tristate {
-                    Value::HighImpedance
-                } else {
-                    Value::Zero
-                };
-                
-                let output_signal = Signal::new(self.data_width, output_value);
-                output_pin.signal = Some(output_signal);
-                changed = true;
-            }
-        }
-
-        if changed {
-            UpdateResult::Changed
-        } else {
-            UpdateResult::NoChange
+            let value = if selected == Some(i) {
+                self.pins["input"].signal.clone()
+            } else {
+                others.clone()
+            };
+            result.add_output(format!("output_{}", i), value);
         }
+        result.set_delay(self.propagation_delay());
+        result
     }
 
     fn reset(&mut self) {
-        // Clear all pin signals
         for pin in self.pins.values_mut() {
-            pin.signal = None;
+            pin.signal = Signal::unknown(pin.width);
         }
     }
 
     fn propagation_delay(&self) -> u64 {
-        super::plexers_library::PlexersLibrary::DELAY
+        DELAY
     }
-}
 
-impl Propagator for Demultiplexer {
-    fn propagate(&mut self, timestamp: Timestamp) -> UpdateResult {
-        self.update(timestamp)
+    fn circ_tool(&self) -> Option<CircTool> {
+        let disabled = if self.disabled_floating { "Z" } else { "0" };
+        Some(CircTool::new(
+            "#Plexers",
+            "Demultiplexer",
+            vec![
+                ("facing", self.facing.to_string()),
+                ("select", self.select_bits.to_string()),
+                ("width", self.data_width.0.to_string()),
+                ("tristate", self.tristate.to_string()),
+                ("disabled", disabled.to_string()),
+                ("enable", self.enable.to_string()),
+            ],
+        ))
     }
 }
 
@@ -348,98 +269,70 @@ impl Propagator for Demultiplexer {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_demultiplexer_creation() {
-        let demux = Demultiplexer::new(ComponentId(1));
-        assert_eq!(demux.id(), ComponentId(1));
-        assert_eq!(demux.name(), "Demultiplexer");
-        assert_eq!(demux.select_bits(), 1);
-        assert_eq!(demux.num_outputs(), 2);
-        assert_eq!(demux.data_width(), BusWidth(1));
+    fn route(demux: &mut Demultiplexer, input: Signal, select: Signal) -> Vec<Signal> {
+        demux
+            .get_pin_mut("input")
+            .unwrap()
+            .set_signal(input)
+            .unwrap();
+        demux
+            .get_pin_mut("select")
+            .unwrap()
+            .set_signal(select)
+            .unwrap();
+        let result = demux.update(Timestamp(0));
+        (0..demux.num_outputs())
+            .map(|i| result.outputs[&format!("output_{}", i)].clone())
+            .collect()
     }
 
     #[test]
-    fn test_demultiplexer_configuration() {
-        let mut demux = Demultiplexer::new(ComponentId(1));
-        
-        // Test setting select bits
-        demux.set_select_bits(2);
-        assert_eq!(demux.select_bits(), 2);
-        assert_eq!(demux.num_outputs(), 4);
-        
-        // Test setting data width
-        demux.set_data_width(BusWidth(8));
-        assert_eq!(demux.data_width(), BusWidth(8));
-        
-        // Test setting facing direction
-        demux.set_facing(Direction::North);
-        assert_eq!(demux.facing(), Direction::North);
-        
-        // Test enabling tristate
-        demux.set_tristate(true);
-        assert!(demux.tristate());
-        
-        // Test enabling enable input
+    fn test_demultiplexer_pins() {
+        let mut demux = Demultiplexer::with_config(ComponentId(1), 2, BusWidth(4), Direction::East);
+        assert_eq!(demux.name(), "Demultiplexer");
+        // 4 outputs + input + select = 6 pins
+        assert_eq!(demux.pins().len(), 6);
+        assert_eq!(demux.get_pin("output_3").unwrap().width, BusWidth(4));
         demux.set_enable(true);
-        assert!(demux.enable());
+        assert!(demux.pins().contains_key("enable"));
+        assert_eq!(demux.propagation_delay(), 3);
     }
 
     #[test]
-    fn test_demultiplexer_pins() {
-        let demux = Demultiplexer::new(ComponentId(1));
-        let pins = demux.pins();
-        
-        // Should have 1 input + 2 outputs + 1 select = 4 pins
-        assert_eq!(pins.len(), 4);
-        assert!(pins.contains_key("input"));
-        assert!(pins.contains_key("output_0"));
-        assert!(pins.contains_key("output_1"));
-        assert!(pins.contains_key("select"));
-        
-        // Test with more select bits
-        let mut demux = Demultiplexer::new(ComponentId(2));
-        demux.set_select_bits(2);
-        let pins = demux.pins();
-        
-        // Should have 1 input + 4 outputs + 1 select = 6 pins
-        assert_eq!(pins.len(), 6);
-        assert!(pins.contains_key("output_0"));
-        assert!(pins.contains_key("output_1"));
-        assert!(pins.contains_key("output_2"));
-        assert!(pins.contains_key("output_3"));
-    }
+    fn test_demultiplexer_routes_input() {
+        let width = BusWidth(4);
+        let mut demux = Demultiplexer::with_config(ComponentId(1), 2, width, Direction::East);
+        let input = Signal::from_u64(0xa, width);
 
-    #[test]
-    fn test_demultiplexer_with_enable() {
-        let mut demux = Demultiplexer::new(ComponentId(1));
-        demux.set_enable(true);
-        let pins = demux.pins();
-        
-        // Should have 1 input + 2 outputs + 1 select + 1 enable = 5 pins
-        assert_eq!(pins.len(), 5);
-        assert!(pins.contains_key("enable"));
-    }
+        let outputs = route(&mut demux, input.clone(), Signal::from_u64(2, BusWidth(2)));
+        let low = Signal::all_low(width);
+        assert_eq!(outputs, vec![low.clone(), low.clone(), input.clone(), low]);
 
-    #[test]
-    fn test_demultiplexer_reset() {
-        let mut demux = Demultiplexer::new(ComponentId(1));
-        
-        // Set some pin signals
-        if let Some(pin) = demux.pins.get_mut("input") {
-            pin.signal = Some(Signal::new(BusWidth(1), Value::One));
-        }
-        
-        // Reset should clear all signals
-        demux.reset();
-        
-        for pin in demux.pins().values() {
-            assert!(pin.signal.is_none());
-        }
+        let outputs = route(&mut demux, input.clone(), Signal::unknown(BusWidth(2)));
+        assert_eq!(outputs, vec![Signal::unknown(width); 4]);
+
+        demux.set_tristate(true);
+        let outputs = route(&mut demux, input.clone(), Signal::from_u64(0, BusWidth(2)));
+        assert_eq!(outputs[0], input);
+        assert_eq!(outputs[1], Signal::high_z(width));
     }
 
     #[test]
-    fn test_demultiplexer_propagation_delay() {
-        let demux = Demultiplexer::new(ComponentId(1));
-        assert_eq!(demux.propagation_delay(), 3);
+    fn test_demultiplexer_disabled() {
+        let mut demux = Demultiplexer::new(ComponentId(1));
+        demux.set_enable(true);
+        demux
+            .get_pin_mut("enable")
+            .unwrap()
+            .set_signal(Signal::new_single(Value::Low))
+            .unwrap();
+        let input = Signal::new_single(Value::High);
+        let select = Signal::from_u64(1, BusWidth(1));
+        let outputs = route(&mut demux, input.clone(), select.clone());
+        assert_eq!(outputs, vec![Signal::new_single(Value::Low); 2]);
+
+        demux.set_disabled_floating(true);
+        let outputs = route(&mut demux, input, select);
+        assert_eq!(outputs, vec![Signal::new_single(Value::HighZ); 2]);
     }
-}
\ No newline at end of file
+}
@@ -10,11 +10,11 @@
 //! Plexers Library
 //!
 //! This module contains the implementation of plexer components (multiplexers, demultiplexers,
-//! decoders, encoders, and bit selectors) equivalent to the Java package 
+//! decoders, encoders, and bit selectors) equivalent to the Java package
 //! `com.cburch.logisim.std.plexers`.
 //!
 //! ## Components
-//! 
+//!
 //! - **Multiplexer**: Data selector that routes one of several inputs to a single output
 //! - **Demultiplexer**: Data router that routes a single input to one of several outputs  
 //! - **Decoder**: Address decoder that activates one output based on binary input
@@ -29,17 +29,16 @@
 //! - Uses standardized pin naming and positioning
 //! - Supports configurable attributes like bit width and orientation
 
+// The library still targets the old `Propagator` and factory APIs and is
+// not built yet
 pub mod bit_selector;
 pub mod decoder;
 pub mod demultiplexer;
 pub mod multiplexer;
-pub mod plexers_library;
 pub mod priority_encoder;
 
-// Re-export all component types
 pub use bit_selector::BitSelector;
 pub use decoder::Decoder;
 pub use demultiplexer::Demultiplexer;
 pub use multiplexer::Multiplexer;
-pub use plexers_library::PlexersLibrary;
-pub use priority_encoder::PriorityEncoder;
\ No newline at end of file
+pub use priority_encoder::PriorityEncoder;
@@ -13,13 +13,16 @@
 //! based on a selection signal. The selection signal determines which input is connected to the output.
 
 use crate::{
-    comp::{Component, ComponentId, Pin, Propagator, UpdateResult},
-    data::{BitWidth, Bounds, Direction, Location},
-    signal::{BusWidth, Signal, Timestamp, Value},
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    data::{Bounds, Direction},
+    signal::{BusWidth, Lanes, Signal, Timestamp, Value},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Propagation delay shared by the plexers
+pub(crate) const DELAY: u64 = 3;
+
 /// Multiplexer component for data selection
 ///
 /// A multiplexer selects one of 2^n inputs based on an n-bit selection signal.
@@ -76,7 +79,7 @@ impl Multiplexer {
             facing,
             tristate: false,
             enable: false,
-            bounds: Bounds::new(0, 0, 40, 30),
+            bounds: Bounds::create(0, 0, 40, 30),
         };
         multiplexer.update_pins();
         multiplexer
@@ -85,54 +88,67 @@ impl Multiplexer {
     /// Update pin configuration based on current settings
     fn update_pins(&mut self) {
         self.pins.clear();
-        
-        let num_inputs = 1 << self.select_bits; // 2^select_bits
-        
-        // Add data inputs
-        for i in 0..num_inputs {
+
+        // Data inputs, one per select value
+        for i in 0..self.num_inputs() {
             let pin_name = format!("input_{}", i);
-            let pin = Pin::new(
-                format!("Input {}", i),
-                self.data_width,
-                crate::comp::PinDirection::Input,
-                Location::new(0, 10 + i * 10),
-            );
+            let pin = Pin::new_input(&pin_name, self.data_width);
             self.pins.insert(pin_name, pin);
         }
-        
-        // Add output
-        let output_pin = Pin::new(
-            "Output".to_string(),
-            self.data_width,
-            crate::comp::PinDirection::Output,
-            Location::new(40, 15),
+
+        self.pins.insert(
+            "output".to_string(),
+            Pin::new_output("output", self.data_width),
         );
-        self.pins.insert("output".to_string(), output_pin);
-        
-        // Add select input
-        let select_pin = Pin::new(
-            "Select".to_string(),
-            BusWidth(self.select_bits as u32),
-            crate::comp::PinDirection::Input,
-            Location::new(20, 30),
+        self.pins.insert(
+            "select".to_string(),
+            Pin::new_input("select", BusWidth(self.select_bits as u32)),
         );
-        self.pins.insert("select".to_string(), select_pin);
-        
-        // Add enable input if enabled
         if self.enable {
-            let enable_pin = Pin::new(
-                "Enable".to_string(),
-                BusWidth(1),
-                crate::comp::PinDirection::Input,
-                Location::new(20, 0),
-            );
-            self.pins.insert("enable".to_string(), enable_pin);
+            self.pins
+                .insert("enable".to_string(), Pin::new_input("enable", BusWidth(1)));
+        }
+    }
+
+    /// Compute the output for the current inputs
+    ///
+    /// A low enable drives zero, or floats the output when it is
+    /// three-state. An undefined select drives an error if it carries one
+    /// and an unknown value otherwise.
+    fn output(&self) -> Signal {
+        let enable = if self.enable {
+            self.pins["enable"]
+                .signal
+                .as_single()
+                .unwrap_or(Value::Unknown)
+        } else {
+            Value::High
+        };
+        if enable == Value::Low {
+            return if self.tristate {
+                Signal::high_z(self.data_width)
+            } else {
+                Signal::all_low(self.data_width)
+            };
+        }
+        if enable == Value::Error {
+            return Signal::error(self.data_width);
+        }
+
+        let select = &self.pins["select"].signal;
+        match select.to_u64() {
+            Some(index) => self
+                .pins
+                .get(&format!("input_{}", index))
+                .map_or_else(|| Signal::error(self.data_width), |pin| pin.signal.clone()),
+            None if select.has_error() => Signal::error(self.data_width),
+            None => Signal::unknown(self.data_width),
         }
     }
 
     /// Set the number of select bits
     pub fn set_select_bits(&mut self, bits: u8) {
-        if bits != self.select_bits && bits >= 1 && bits <= 8 {
+        if bits != self.select_bits && (1..=8).contains(&bits) {
             self.select_bits = bits;
             self.update_pins();
         }
@@ -216,105 +232,92 @@ impl Component for Multiplexer {
     }
 
     fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
-        // Get select signal
-        let select_value = if let Some(select_pin) = self.pins.get("select") {
-            match &select_pin.signal {
-                Some(signal) => signal.value(),
-                None => return UpdateResult::NoChange, // No select signal
-            }
-        } else {
-            return UpdateResult::NoChange;
-        };
-
-        // Check if enabled (if enable pin exists)
-        if self.enable {
-            if let Some(enable_pin) = self.pins.get("enable") {
-                match &enable_pin.signal {
-                    Some(signal) => {
-                        if signal.value() == Value::Zero {
-                            // Component is disabled, set output to high impedance or zero
-                            if let Some(output_pin) = self.pins.get_mut("output") {
-                                let output_value = if self.tristate {
-                                    Value::HighImpedance
-                                } else {
-                                    Value::Zero
-                                };
-                                let output_signal = Signal::new(self.data_width, output_value);
-                                output_pin.signal = Some(output_signal);
-                                return UpdateResult::Changed;
-                            }
-                        }
-                    }
-                    None => return UpdateResult::NoChange,
-                }
-            }
-        }
-
-        // Convert select value to index
-        let select_index = match select_value {
-            Value::Zero => 0,
-            Value::One => 1,
-            Value::Binary(bits) => {
-                // Convert binary representation to index
-                bits.iter().enumerate().fold(0usize, |acc, (i, &bit)| {
-                    acc + if bit { 1 << i } else { 0 }
-                })
-            }
-            Value::HighImpedance | Value::Error => {
-                // Invalid select signal
-                if let Some(output_pin) = self.pins.get_mut("output") {
-                    let output_signal = Signal::new(self.data_width, Value::Error);
-                    output_pin.signal = Some(output_signal);
-                }
-                return UpdateResult::Changed;
-            }
-        };
-
-        // Ensure select index is within valid range
-        if select_index >= self.num_inputs() {
-            if let Some(output_pin) = self.pins.get_mut("output") {
-                let output_signal = Signal::new(self.data_width, Value::Error);
-                output_pin.signal = Some(output_signal);
-            }
-            return UpdateResult::Changed;
-        }
-
-        // Get the selected input signal
-        let input_pin_name = format!("input_{}", select_index);
-        let input_value = if let Some(input_pin) = self.pins.get(&input_pin_name) {
-            match &input_pin.signal {
-                Some(signal) => signal.value().clone(),
-                None => Value::HighImpedance, // No input signal
-            }
-        } else {
-            Value::Error // Should not happen
-        };
-
-        // Set output to selected input value
-        if let Some(output_pin) = self.pins.get_mut("output") {
-            let output_signal = Signal::new(self.data_width, input_value);
-            output_pin.signal = Some(output_signal);
-            UpdateResult::Changed
-        } else {
-            UpdateResult::NoChange
+        let output = self.output();
+        let mut result = UpdateResult::new();
+        result.add_output("output".to_string(), output.clone());
+        result.set_delay(self.propagation_delay());
+        if let Some(pin) = self.pins.get_mut("output") {
+            pin.signal = output;
         }
+        result
     }
 
     fn reset(&mut self) {
-        // Clear all pin signals
         for pin in self.pins.values_mut() {
-            pin.signal = None;
+            pin.signal = Signal::unknown(pin.width);
         }
     }
 
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        // Lanes whose select is fully defined, and those selecting an error
+        let select = inputs.get("select")?;
+        let defined = select
+            .iter()
+            .fold(u64::MAX, |mask, word| mask & (word.high | word.low));
+        let error = select.iter().fold(0, |mask, word| mask | word.error) & !defined;
+        let matches: Vec<u64> = (0..self.num_inputs())
+            .map(|index| {
+                select
+                    .iter()
+                    .enumerate()
+                    .fold(defined, |mask, (bit, word)| {
+                        mask & if index >> bit & 1 == 1 {
+                            word.high
+                        } else {
+                            word.low
+                        }
+                    })
+            })
+            .collect();
+        let enable = match self.enable {
+            true => inputs.get("enable")?.first().copied().unwrap_or_default(),
+            false => Lanes::splat(Value::High),
+        };
+        let disabled = Lanes::splat(if self.tristate {
+            Value::HighZ
+        } else {
+            Value::Low
+        });
+
+        let output = (0..self.data_width.0 as usize)
+            .map(|bit| {
+                let mut word = Lanes::default();
+                for (index, &mask) in matches.iter().enumerate() {
+                    let input = inputs.get(&format!("input_{}", index))?;
+                    word = input
+                        .get(bit)
+                        .copied()
+                        .unwrap_or_default()
+                        .select(mask, word);
+                }
+                let word = Lanes::splat(Value::Error).select(error, word);
+                let word = disabled.select(enable.low, word);
+                Some(Lanes::splat(Value::Error).select(enable.error, word))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(HashMap::from([("output".to_string(), output)]))
+    }
+
     fn propagation_delay(&self) -> u64 {
-        super::plexers_library::PlexersLibrary::DELAY
+        DELAY
     }
-}
 
-impl Propagator for Multiplexer {
-    fn propagate(&mut self, timestamp: Timestamp) -> UpdateResult {
-        self.update(timestamp)
+    fn circ_tool(&self) -> Option<CircTool> {
+        let disabled = if self.tristate { "Z" } else { "0" };
+        Some(CircTool::new(
+            "#Plexers",
+            "Multiplexer",
+            vec![
+                ("facing", self.facing.to_string()),
+                ("select", self.select_bits.to_string()),
+                ("width", self.data_width.0.to_string()),
+                ("disabled", disabled.to_string()),
+                ("enable", self.enable.to_string()),
+            ],
+        ))
     }
 }
 
@@ -335,24 +338,24 @@ mod tests {
     #[test]
     fn test_multiplexer_configuration() {
         let mut mux = Multiplexer::new(ComponentId(1));
-        
+
         // Test setting select bits
         mux.set_select_bits(2);
         assert_eq!(mux.select_bits(), 2);
         assert_eq!(mux.num_inputs(), 4);
-        
+
         // Test setting data width
         mux.set_data_width(BusWidth(8));
         assert_eq!(mux.data_width(), BusWidth(8));
-        
+
         // Test setting facing direction
         mux.set_facing(Direction::North);
         assert_eq!(mux.facing(), Direction::North);
-        
+
         // Test enabling tristate
         mux.set_tristate(true);
         assert!(mux.tristate());
-        
+
         // Test enabling enable input
         mux.set_enable(true);
         assert!(mux.enable());
@@ -362,19 +365,19 @@ mod tests {
     fn test_multiplexer_pins() {
         let mux = Multiplexer::new(ComponentId(1));
         let pins = mux.pins();
-        
+
         // Should have 2 inputs + 1 output + 1 select = 4 pins
         assert_eq!(pins.len(), 4);
         assert!(pins.contains_key("input_0"));
         assert!(pins.contains_key("input_1"));
         assert!(pins.contains_key("output"));
         assert!(pins.contains_key("select"));
-        
+
         // Test with more select bits
         let mut mux = Multiplexer::new(ComponentId(2));
         mux.set_select_bits(2);
         let pins = mux.pins();
-        
+
         // Should have 4 inputs + 1 output + 1 select = 6 pins
         assert_eq!(pins.len(), 6);
         assert!(pins.contains_key("input_0"));
@@ -388,7 +391,7 @@ mod tests {
         let mut mux = Multiplexer::new(ComponentId(1));
         mux.set_enable(true);
         let pins = mux.pins();
-        
+
         // Should have 2 inputs + 1 output + 1 select + 1 enable = 5 pins
         assert_eq!(pins.len(), 5);
         assert!(pins.contains_key("enable"));
@@ -397,23 +400,63 @@ mod tests {
     #[test]
     fn test_multiplexer_reset() {
         let mut mux = Multiplexer::new(ComponentId(1));
-        
-        // Set some pin signals
-        if let Some(pin) = mux.pins.get_mut("input_0") {
-            pin.signal = Some(Signal::new(BusWidth(1), Value::One));
-        }
-        
-        // Reset should clear all signals
+        mux.pins.get_mut("input_0").unwrap().signal = Signal::new_single(Value::High);
+
         mux.reset();
-        
         for pin in mux.pins().values() {
-            assert!(pin.signal.is_none());
+            assert_eq!(pin.signal, Signal::unknown(pin.width));
         }
     }
 
+    #[test]
+    fn test_multiplexer_selects_input() {
+        let mut mux = Multiplexer::new(ComponentId(1));
+        mux.set_select_bits(2);
+        mux.set_data_width(BusWidth(4));
+        for i in 0..4 {
+            let pin = mux.get_pin_mut(&format!("input_{}", i)).unwrap();
+            pin.set_signal(Signal::from_u64(i + 5, BusWidth(4)))
+                .unwrap();
+        }
+
+        let select = |mux: &mut Multiplexer, signal: Signal| {
+            mux.get_pin_mut("select")
+                .unwrap()
+                .set_signal(signal)
+                .unwrap();
+            mux.update(Timestamp(0)).outputs["output"].clone()
+        };
+        assert_eq!(
+            select(&mut mux, Signal::from_u64(2, BusWidth(2))).to_u64(),
+            Some(7)
+        );
+        assert_eq!(
+            select(&mut mux, Signal::unknown(BusWidth(2))),
+            Signal::unknown(BusWidth(4))
+        );
+        assert!(select(&mut mux, Signal::error(BusWidth(2))).has_error());
+    }
+
+    #[test]
+    fn test_multiplexer_disabled() {
+        let mut mux = Multiplexer::new(ComponentId(1));
+        mux.set_enable(true);
+        mux.get_pin_mut("enable")
+            .unwrap()
+            .set_signal(Signal::new_single(Value::Low))
+            .unwrap();
+        assert_eq!(
+            mux.update(Timestamp(0)).outputs["output"],
+            Signal::all_low(BusWidth(1))
+        );
+
+        mux.set_tristate(true);
+        assert!(mux.update(Timestamp(0)).outputs["output"].is_high_z());
+    }
+
     #[test]
     fn test_multiplexer_propagation_delay() {
         let mux = Multiplexer::new(ComponentId(1));
         assert_eq!(mux.propagation_delay(), 3);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,263 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Bit extender component - widens or narrows a bus
+//!
+//! A bit extender copies its input to an output of another width. Narrowing
+//! drops the high bits; widening fills the new high bits with zeros, ones,
+//! copies of the sign bit or the value of an extra input.
+
+use crate::{
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    signal::{BusWidth, Lanes, Signal, Timestamp, Value},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Unique identifier for the Bit Extender component
+/// Do NOT change as it will prevent project files from loading.
+pub const BIT_EXTENDER_ID: &str = "Bit Extender";
+
+/// What a bit extender fills the new high bits with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BitExtension {
+    /// Zeros
+    Zero,
+    /// Ones
+    One,
+    /// Copies of the highest input bit
+    #[default]
+    Sign,
+    /// The value of the one-bit `extend` input
+    Input,
+}
+
+impl BitExtension {
+    /// Parse the value of a `type` attribute (`zero`, `one`, `sign`, `input`)
+    pub fn parse(s: &str) -> Result<BitExtension, String> {
+        match s {
+            "zero" => Ok(BitExtension::Zero),
+            "one" => Ok(BitExtension::One),
+            "sign" => Ok(BitExtension::Sign),
+            "input" => Ok(BitExtension::Input),
+            _ => Err(format!("Invalid extension type: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for BitExtension {
+    /// Write the value of the `type` attribute
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BitExtension::Zero => "zero",
+            BitExtension::One => "one",
+            BitExtension::Sign => "sign",
+            BitExtension::Input => "input",
+        })
+    }
+}
+
+/// Bit extender component implementation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitExtender {
+    id: ComponentId,
+    pins: HashMap<String, Pin>,
+    in_width: BusWidth,
+    out_width: BusWidth,
+    extension: BitExtension,
+}
+
+impl BitExtender {
+    /// Create a new sign extender from 8 to 16 bits
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_widths(id, BusWidth(8), BusWidth(16), BitExtension::Sign)
+    }
+
+    /// Create a new bit extender between the given widths
+    pub fn new_with_widths(
+        id: ComponentId,
+        in_width: BusWidth,
+        out_width: BusWidth,
+        extension: BitExtension,
+    ) -> Self {
+        let mut pins = HashMap::new();
+        pins.insert("in".to_string(), Pin::new_input("in", in_width));
+        pins.insert("out".to_string(), Pin::new_output("out", out_width));
+        if extension == BitExtension::Input {
+            pins.insert("extend".to_string(), Pin::new_input("extend", BusWidth(1)));
+        }
+
+        Self {
+            id,
+            pins,
+            in_width,
+            out_width,
+            extension,
+        }
+    }
+
+    /// Get the input width
+    pub fn in_width(&self) -> BusWidth {
+        self.in_width
+    }
+
+    /// Get the output width
+    pub fn out_width(&self) -> BusWidth {
+        self.out_width
+    }
+
+    /// Get what the new high bits are filled with
+    pub fn extension(&self) -> BitExtension {
+        self.extension
+    }
+
+    /// The value the new high bits take, ported from `BitExtender.propagate`
+    fn fill(&self) -> Value {
+        match self.extension {
+            BitExtension::Zero => Value::Low,
+            BitExtension::One => Value::High,
+            BitExtension::Sign => self.in_width.0.checked_sub(1).map_or(Value::Error, |bit| {
+                self.pins["in"].signal.get_bit(bit).unwrap_or(Value::Error)
+            }),
+            BitExtension::Input => self.pins["extend"]
+                .signal
+                .as_single()
+                .unwrap_or(Value::Error),
+        }
+    }
+}
+
+impl Component for BitExtender {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        BIT_EXTENDER_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        let input = &self.pins["in"].signal;
+        let fill = self.fill();
+        let bits = (0..self.out_width.0)
+            .map(|bit| match bit < self.in_width.0 {
+                true => input.get_bit(bit).unwrap_or(Value::Error),
+                false => fill,
+            })
+            .collect();
+
+        let mut result = UpdateResult::new();
+        result.add_output("out".to_string(), Signal::new_bus(bits));
+        result.set_delay(self.propagation_delay());
+        result
+    }
+
+    fn reset(&mut self) {
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn evaluate_lanes(
+        &self,
+        inputs: &HashMap<String, Vec<Lanes>>,
+    ) -> Option<HashMap<String, Vec<Lanes>>> {
+        let input = inputs.get("in")?;
+        let fill = match self.extension {
+            BitExtension::Zero => Lanes::splat(Value::Low),
+            BitExtension::One => Lanes::splat(Value::High),
+            BitExtension::Sign => input.last().copied().unwrap_or(Lanes::splat(Value::Error)),
+            BitExtension::Input => inputs.get("extend")?.first().copied()?,
+        };
+        let output = (0..self.out_width.0 as usize)
+            .map(|bit| input.get(bit).copied().unwrap_or(fill))
+            .collect();
+        Some(HashMap::from([("out".to_string(), output)]))
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        1
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Wiring",
+            BIT_EXTENDER_ID,
+            vec![
+                ("in_width", self.in_width.0.to_string()),
+                ("out_width", self.out_width.0.to_string()),
+                ("type", self.extension.to_string()),
+            ],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extend(extender: &mut BitExtender, input: Signal, extra: Option<Value>) -> Signal {
+        extender
+            .get_pin_mut("in")
+            .unwrap()
+            .set_signal(input)
+            .unwrap();
+        if let Some(extra) = extra {
+            extender
+                .get_pin_mut("extend")
+                .unwrap()
+                .set_signal(Signal::new_single(extra))
+                .unwrap();
+        }
+        extender.update(Timestamp(0)).outputs["out"].clone()
+    }
+
+    #[test]
+    fn test_bit_extender_fills() {
+        let (four, eight) = (BusWidth(4), BusWidth(8));
+        let input = || Signal::from_u64(0b1010, four);
+        let cases = [
+            (BitExtension::Zero, 0x0a),
+            (BitExtension::One, 0xfa),
+            (BitExtension::Sign, 0xfa),
+        ];
+        for (extension, expected) in cases {
+            let mut extender = BitExtender::new_with_widths(ComponentId(1), four, eight, extension);
+            assert_eq!(
+                extend(&mut extender, input(), None).to_u64(),
+                Some(expected)
+            );
+        }
+
+        let mut extender =
+            BitExtender::new_with_widths(ComponentId(1), four, eight, BitExtension::Input);
+        assert_eq!(
+            extend(&mut extender, input(), Some(Value::Low)).to_u64(),
+            Some(0x0a)
+        );
+        assert!(extend(&mut extender, input(), Some(Value::Error)).has_error());
+
+        // Narrowing drops the high bits
+        let mut extender =
+            BitExtender::new_with_widths(ComponentId(1), eight, four, BitExtension::Zero);
+        assert_eq!(
+            extend(&mut extender, Signal::from_u64(0x5c, eight), None).to_u64(),
+            Some(0xc)
+        );
+    }
+}
@@ -10,14 +10,17 @@
 //! Clock component - Clock signal generation
 //!
 //! Clock components generate periodic square wave signals that can be used
-//! to drive sequential logic circuits.
+//! to drive sequential logic circuits. Each clock drives its own output net
+//! and schedules its own edges, so a circuit may contain several independent
+//! clock domains.
 
 use crate::{
-    comp::{Component, ComponentId, Pin, UpdateResult},
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
     data::Direction,
     signal::{BusWidth, Signal, Timestamp, Value},
     std::wiring::WiringComponentFactory,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Unique identifier for the Clock component
@@ -30,6 +33,7 @@ pub struct ClockAttributes {
     pub facing: Direction,
     pub high_duration: u64, // Duration in time units that clock stays high
     pub low_duration: u64,  // Duration in time units that clock stays low
+    pub phase: u64,         // Time units the waveform is advanced by at reset
 }
 
 impl Default for ClockAttributes {
@@ -38,12 +42,13 @@ impl Default for ClockAttributes {
             facing: Direction::East,
             high_duration: 1,
             low_duration: 1,
+            phase: 0,
         }
     }
 }
 
 /// Internal state of a clock component
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClockState {
     /// Current output value
     pub current_value: Value,
@@ -51,6 +56,8 @@ pub struct ClockState {
     pub next_transition_time: u64,
     /// Whether the clock is running
     pub running: bool,
+    /// Whether the output has been driven since the last reset
+    pub started: bool,
 }
 
 impl Default for ClockState {
@@ -59,6 +66,7 @@ impl Default for ClockState {
             current_value: Value::Low,
             next_transition_time: 1, // Start with low duration for first transition
             running: true,
+            started: false,
         }
     }
 }
@@ -98,14 +106,19 @@ impl Clock {
         }
     }
 
-    /// Set the high duration (time clock stays high)
+    /// Set the high duration (time clock stays high, at least 1)
     pub fn set_high_duration(&mut self, duration: u64) {
-        self.attributes.high_duration = duration;
+        self.attributes.high_duration = duration.max(1);
     }
 
-    /// Set the low duration (time clock stays low)
+    /// Set the low duration (time clock stays low, at least 1)
     pub fn set_low_duration(&mut self, duration: u64) {
-        self.attributes.low_duration = duration;
+        self.attributes.low_duration = duration.max(1);
+    }
+
+    /// Set the phase, applied on the next reset
+    pub fn set_phase(&mut self, phase: u64) {
+        self.attributes.phase = phase;
     }
 
     /// Get the high duration
@@ -118,6 +131,11 @@ impl Clock {
         self.attributes.low_duration
     }
 
+    /// Get the phase
+    pub fn get_phase(&self) -> u64 {
+        self.attributes.phase
+    }
+
     /// Toggle the clock output and schedule next transition
     fn toggle_output(&mut self, current_time: u64) -> bool {
         let old_value = self.state.current_value;
@@ -147,6 +165,15 @@ impl Clock {
         old_value != self.state.current_value
     }
 
+    /// Add the current clock level to an update result
+    fn drive_output(&self, result: &mut UpdateResult) {
+        result.add_output(
+            "out".to_string(),
+            Signal::new_single(self.state.current_value),
+        );
+        result.set_delay(self.propagation_delay());
+    }
+
     /// Get the current clock value
     pub fn get_current_value(&self) -> Value {
         self.state.current_value
@@ -188,31 +215,40 @@ impl Component for Clock {
     fn update(&mut self, current_time: Timestamp) -> UpdateResult {
         let mut result = UpdateResult::new();
 
-        // Check if it's time to toggle
-        if self.should_transition(current_time.0) {
-            if self.toggle_output(current_time.0) {
-                // Value changed, output the new signal
-                if let Some(pin) = self.pins.get("out") {
-                    result.add_output("out".to_string(), pin.signal.clone());
-                }
-
-                // Set the delay until next transition
-                result.set_delay(match self.state.current_value {
-                    Value::High => self.attributes.high_duration,
-                    Value::Low => self.attributes.low_duration,
-                    _ => self.attributes.low_duration,
-                });
+        // Updates caused by activity on the output net are not transitions
+        let due = self.should_transition(current_time.0);
+        if self.state.started && !due {
+            return result;
+        }
+        self.state.started = true;
+
+        if due {
+            // Catch up on every transition that fell due since the last one
+            while self.state.next_transition_time <= current_time.0 {
+                self.toggle_output(self.state.next_transition_time);
             }
         }
+        if self.state.running {
+            result.request_wakeup(self.state.next_transition_time - current_time.0);
+        }
 
+        self.drive_output(&mut result);
         result
     }
 
     fn reset(&mut self) {
-        // Reset to initial state
-        self.state.current_value = Value::Low;
-        self.state.next_transition_time = self.attributes.low_duration;
+        // Reset to the point of the waveform selected by the phase
+        let cycle = self.attributes.high_duration + self.attributes.low_duration;
+        let offset = self.attributes.phase % cycle;
+        if offset < self.attributes.low_duration {
+            self.state.current_value = Value::Low;
+            self.state.next_transition_time = self.attributes.low_duration - offset;
+        } else {
+            self.state.current_value = Value::High;
+            self.state.next_transition_time = cycle - offset;
+        }
         self.state.running = true;
+        self.state.started = false;
 
         // Update pin signal
         if let Some(pin) = self.pins.get_mut("out") {
@@ -220,9 +256,46 @@ impl Component for Clock {
         }
     }
 
+    fn is_sequential(&self) -> bool {
+        true
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(&self.state).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        self.state = ClockState::deserialize(state)?;
+        Ok(())
+    }
+
+    fn tick(&mut self, current_time: Timestamp) -> UpdateResult {
+        let mut result = UpdateResult::new();
+        self.state.started = true;
+        self.toggle_output(current_time.0);
+        if self.state.running {
+            result.request_wakeup(self.state.next_transition_time - current_time.0);
+        }
+
+        self.drive_output(&mut result);
+        result
+    }
+
     fn propagation_delay(&self) -> u64 {
         0 // Clock drives its output directly
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Wiring",
+            CLOCK_ID,
+            vec![
+                ("highDuration", self.get_high_duration().to_string()),
+                ("lowDuration", self.get_low_duration().to_string()),
+                ("phase", self.get_phase().to_string()),
+            ],
+        ))
+    }
 }
 
 /// Factory for creating Clock components
@@ -348,13 +421,47 @@ mod tests {
         let mut clock = Clock::new(ComponentId(1));
         clock.set_low_duration(1); // Set to match default
 
-        // Before transition time
+        // The first update drives the initial level and schedules the edge
         let result = clock.update(Timestamp(0));
-        assert_eq!(result.outputs.len(), 0); // No change
+        assert_eq!(result.outputs["out"].as_single(), Some(Value::Low));
+        assert_eq!(result.wakeup, Some(1));
+
+        // Updates before the next transition are ignored
+        let result = clock.update(Timestamp(0));
+        assert_eq!(result.outputs.len(), 0);
+        assert_eq!(result.wakeup, None);
 
         // At transition time
         let result = clock.update(Timestamp(1));
         assert_eq!(result.outputs.len(), 1); // Should have output
-        assert!(result.outputs.contains_key("out"));
+        assert_eq!(result.outputs["out"].as_single(), Some(Value::High));
+        assert_eq!(result.wakeup, Some(1));
+    }
+
+    #[test]
+    fn test_clock_phase() {
+        let mut clock = Clock::new(ComponentId(1));
+        clock.set_high_duration(2);
+        clock.set_low_duration(3);
+        clock.set_phase(4);
+        clock.reset();
+
+        // Four units into the five unit cycle the clock is high, with one
+        // unit left before it falls
+        assert_eq!(clock.get_current_value(), Value::High);
+        let result = clock.update(Timestamp(0));
+        assert_eq!(result.outputs["out"].as_single(), Some(Value::High));
+        assert_eq!(result.wakeup, Some(1));
+    }
+
+    #[test]
+    fn test_clock_tick() {
+        let mut clock = Clock::new(ComponentId(1));
+        clock.set_running(false);
+
+        let result = clock.tick(Timestamp(7));
+        assert_eq!(result.outputs["out"].as_single(), Some(Value::High));
+        assert_eq!(result.wakeup, None);
+        assert_eq!(result.delay, 0);
     }
 }
@@ -13,7 +13,7 @@
 //! They are useful for providing known signals to circuits.
 
 use crate::{
-    comp::{Component, ComponentId, Pin, UpdateResult},
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
     data::Direction,
     signal::{BusWidth, Signal, Timestamp},
     std::wiring::WiringComponentFactory,
@@ -144,6 +144,17 @@ impl Component for Constant {
     fn propagation_delay(&self) -> u64 {
         0 // Constants have no propagation delay
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Wiring",
+            CONSTANT_ID,
+            vec![
+                ("width", self.get_width().0.to_string()),
+                ("value", format!("{:#x}", self.get_value())),
+            ],
+        ))
+    }
 }
 
 /// Factory for creating Constant components
@@ -0,0 +1,151 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Do Not Connect component - marks a pin deliberately left open
+//!
+//! The marker neither drives nor reads the net it is placed on; it only
+//! documents that the pin it sits on is meant to be unconnected.
+
+use crate::{
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    signal::{BusWidth, Timestamp},
+    std::wiring::WiringComponentFactory,
+};
+use std::collections::HashMap;
+
+/// Unique identifier for the Do Not Connect component
+/// Do NOT change as it will prevent project files from loading.
+pub const DO_NOT_CONNECT_ID: &str = "NoConnect";
+
+/// Do Not Connect component attributes
+#[derive(Debug, Clone)]
+pub struct DoNotConnectAttributes {
+    pub width: BusWidth,
+}
+
+impl Default for DoNotConnectAttributes {
+    fn default() -> Self {
+        Self { width: BusWidth(1) }
+    }
+}
+
+/// Do Not Connect component implementation
+#[derive(Debug)]
+pub struct DoNotConnect {
+    id: ComponentId,
+    attributes: DoNotConnectAttributes,
+    pins: HashMap<String, Pin>,
+}
+
+impl DoNotConnect {
+    /// Create a new marker
+    pub fn new(id: ComponentId) -> Self {
+        let attributes = DoNotConnectAttributes::default();
+
+        let mut pins = HashMap::new();
+        pins.insert("pin".to_string(), Pin::new_inout("pin", attributes.width));
+
+        Self {
+            id,
+            attributes,
+            pins,
+        }
+    }
+
+    /// Set the bit width
+    pub fn set_width(&mut self, width: BusWidth) {
+        self.attributes.width = width;
+        if let Some(pin) = self.pins.get_mut("pin") {
+            pin.width = width;
+        }
+    }
+
+    /// Get the bit width
+    pub fn get_width(&self) -> BusWidth {
+        self.attributes.width
+    }
+}
+
+impl Component for DoNotConnect {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        DO_NOT_CONNECT_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        UpdateResult::new()
+    }
+
+    fn reset(&mut self) {}
+
+    fn propagation_delay(&self) -> u64 {
+        0
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Wiring",
+            DO_NOT_CONNECT_ID,
+            vec![("width", self.get_width().0.to_string())],
+        ))
+    }
+}
+
+/// Factory for creating Do Not Connect components
+pub struct DoNotConnectFactory;
+
+impl WiringComponentFactory for DoNotConnectFactory {
+    fn id(&self) -> &'static str {
+        DO_NOT_CONNECT_ID
+    }
+
+    fn display_name(&self) -> &str {
+        "Do Not Connect"
+    }
+
+    fn description(&self) -> &str {
+        "Marks a pin that is deliberately left unconnected"
+    }
+
+    fn icon_path(&self) -> Option<&str> {
+        Some("noconnect.gif")
+    }
+
+    fn create_component(&self, id: ComponentId) -> Box<dyn crate::Component> {
+        Box::new(DoNotConnect::new(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_do_not_connect_is_inert() {
+        let mut marker = DoNotConnect::new(ComponentId(1));
+        assert_eq!(marker.name(), DO_NOT_CONNECT_ID);
+        marker.set_width(BusWidth(4));
+        assert_eq!(marker.get_pin("pin").unwrap().width, BusWidth(4));
+
+        let result = marker.update(Timestamp(0));
+        assert!(result.outputs.is_empty());
+        assert!(!result.state_changed);
+    }
+}
@@ -10,19 +10,34 @@
 //! Wiring components for circuit connections and signal management
 //!
 //! This module contains all the wiring-related components such as pins,
-//! tunnels, splitters, clocks, and power/ground components.
+//! tunnels, splitters, clocks, and power/ground components, along with the
+//! switch-level transistors, transmission gates and pull resistors.
 
+pub mod bit_extender;
 pub mod clock;
 pub mod constant;
+pub mod do_not_connect;
 pub mod ground;
 pub mod pin;
 pub mod power;
+pub mod power_on_reset;
+pub mod probe;
+pub mod pull_resistor;
+pub mod transistor;
+pub mod transmission_gate;
 pub mod wiring_library;
 
 // Export wiring components
+pub use bit_extender::*;
 pub use clock::*;
 pub use constant::*;
+pub use do_not_connect::*;
 pub use ground::*;
 pub use pin::*;
 pub use power::*;
+pub use power_on_reset::*;
+pub use probe::*;
+pub use pull_resistor::*;
+pub use transistor::*;
+pub use transmission_gate::*;
 pub use wiring_library::*;
@@ -13,11 +13,12 @@
 //! communication between subcircuits and external interfaces.
 
 use crate::{
-    comp::{Component, ComponentId, Pin as CompPin, PinDirection, UpdateResult},
+    comp::{CircTool, Component, ComponentId, Pin as CompPin, PinDirection, UpdateResult},
     data::Direction,
     signal::{BusWidth, Signal, Timestamp, Value},
     std::wiring::WiringComponentFactory,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Unique identifier for the Pin component
@@ -79,7 +80,7 @@ impl PinAttributes {
 }
 
 /// Internal state of a pin component
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PinState {
     /// The value the pin is trying to drive
     pub intended_value: Signal,
@@ -138,6 +139,60 @@ impl Pin {
         }
     }
 
+    /// Set the bit width of this pin
+    pub fn set_width(&mut self, width: BusWidth) {
+        self.attributes.width = width;
+        self.state.intended_value = Signal::unknown(width);
+        self.state.actual_value = Signal::unknown(width);
+
+        if let Some(pin) = self.pins.get_mut("pin") {
+            pin.width = width;
+            pin.signal = Signal::unknown(width);
+        }
+    }
+
+    /// Get the bit width of this pin
+    pub fn get_width(&self) -> BusWidth {
+        self.attributes.width
+    }
+
+    /// Set whether this pin is a circuit input or output
+    ///
+    /// Output pins read the value of the net they are attached to.
+    pub fn set_pin_type(&mut self, pin_type: PinType) {
+        self.attributes.pin_type = pin_type;
+        self.state.driving = pin_type == PinType::Input;
+        // The port always reads its net; input pins also drive it from update()
+        if let Some(pin) = self.pins.get_mut("pin") {
+            pin.direction = PinDirection::Input;
+        }
+    }
+
+    /// Get whether this pin is a circuit input or output
+    pub fn get_pin_type(&self) -> PinType {
+        self.attributes.pin_type
+    }
+
+    /// Set the pull behavior of this pin
+    pub fn set_behavior(&mut self, behavior: PinBehavior) {
+        self.attributes.behavior = behavior;
+    }
+
+    /// Get the pull behavior of this pin
+    pub fn get_behavior(&self) -> PinBehavior {
+        self.attributes.behavior
+    }
+
+    /// Set the label of this pin
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.attributes.label = label.into();
+    }
+
+    /// Get the label of this pin
+    pub fn get_label(&self) -> &str {
+        &self.attributes.label
+    }
+
     /// Set the intended value for this pin
     pub fn set_intended_value(&mut self, value: Signal) {
         self.state.intended_value = value.clone();
@@ -189,6 +244,9 @@ impl Component for Pin {
                 pin.signal = self.state.intended_value.clone();
                 result.add_output("pin".to_string(), pin.signal.clone());
             }
+        } else if let Some(pin) = self.pins.get("pin") {
+            // Output pins show whatever value reaches them
+            self.state.actual_value = pin.signal.clone();
         }
 
         result
@@ -196,10 +254,7 @@ impl Component for Pin {
 
     fn reset(&mut self) {
         // Reset to initial state
-        let initial_signal = Signal::from_u64(
-            self.attributes.initial_value,
-            self.state.intended_value.width(),
-        );
+        let initial_signal = Signal::from_u64(self.attributes.initial_value, self.attributes.width);
         self.state.intended_value = initial_signal.clone();
         self.state.actual_value = initial_signal;
         self.state.driving = self.attributes.is_input();
@@ -209,6 +264,38 @@ impl Component for Pin {
             pin.signal = self.state.intended_value.clone();
         }
     }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(&self.state).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        self.state = PinState::deserialize(state)?;
+        Ok(())
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let pin_type = match self.get_pin_type() {
+            PinType::Input => "input",
+            PinType::Output => "output",
+        };
+        let behavior = match self.get_behavior() {
+            PinBehavior::Simple => "simple",
+            PinBehavior::Tristate => "tristate",
+            PinBehavior::PullDown => "pulldown",
+            PinBehavior::PullUp => "pullup",
+        };
+        Some(CircTool::new(
+            "#Wiring",
+            PIN_ID,
+            vec![
+                ("width", self.get_width().0.to_string()),
+                ("type", pin_type.to_string()),
+                ("behavior", behavior.to_string()),
+                ("label", self.get_label().to_string()),
+            ],
+        ))
+    }
 }
 
 /// Factory for creating Pin components
@@ -281,4 +368,21 @@ mod tests {
         pin.set_intended_value(test_signal.clone());
         assert_eq!(pin.get_intended_value(), &test_signal);
     }
+
+    #[test]
+    fn test_output_pin_follows_net() {
+        let mut pin = Pin::new(ComponentId(1));
+        pin.set_width(BusWidth(4));
+        pin.set_pin_type(PinType::Output);
+        assert_eq!(pin.get_pin_type(), PinType::Output);
+
+        let value = Signal::from_u64(0b0110, BusWidth(4));
+        pin.get_pin_mut("pin")
+            .unwrap()
+            .set_signal(value.clone())
+            .unwrap();
+        let result = pin.update(Timestamp(0));
+        assert!(result.outputs.is_empty());
+        assert_eq!(pin.get_actual_value(), &value);
+    }
 }
@@ -13,7 +13,7 @@
 //! They are essential for providing known power levels.
 
 use crate::{
-    comp::{Component, ComponentId, Pin, UpdateResult},
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
     signal::{BusWidth, Signal, Timestamp},
     std::wiring::WiringComponentFactory,
 };
@@ -118,6 +118,14 @@ impl Component for Power {
     fn propagation_delay(&self) -> u64 {
         0 // Power has no propagation delay
     }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Wiring",
+            POWER_ID,
+            vec![("width", self.get_width().0.to_string())],
+        ))
+    }
 }
 
 /// Factory for creating Power components
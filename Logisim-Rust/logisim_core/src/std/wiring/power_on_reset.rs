@@ -0,0 +1,198 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Power-On Reset component - reset pulse at simulation start
+//!
+//! The output is high from the first update after a reset for a set number
+//! of time units, then drops low for good, so it can hold sequential logic
+//! in reset while the rest of the circuit settles.
+
+use crate::{
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    signal::{BusWidth, Signal, Timestamp, Value},
+    std::wiring::WiringComponentFactory,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Unique identifier for the Power-On Reset component
+/// Do NOT change as it will prevent project files from loading.
+pub const POWER_ON_RESET_ID: &str = "POR";
+
+/// Power-On Reset component attributes
+#[derive(Debug, Clone)]
+pub struct PowerOnResetAttributes {
+    /// Time units the output stays high
+    pub duration: u64,
+}
+
+impl Default for PowerOnResetAttributes {
+    fn default() -> Self {
+        Self { duration: 10 }
+    }
+}
+
+/// Power-On Reset component implementation
+#[derive(Debug)]
+pub struct PowerOnReset {
+    id: ComponentId,
+    attributes: PowerOnResetAttributes,
+    pins: HashMap<String, Pin>,
+    /// Time of the first update since the last reset
+    started: Option<Timestamp>,
+}
+
+impl PowerOnReset {
+    /// Create a new power-on reset
+    pub fn new(id: ComponentId) -> Self {
+        let mut output_pin = Pin::new_output("out", BusWidth(1));
+        output_pin.signal = Signal::new_single(Value::High);
+
+        let mut pins = HashMap::new();
+        pins.insert("out".to_string(), output_pin);
+
+        Self {
+            id,
+            attributes: PowerOnResetAttributes::default(),
+            pins,
+            started: None,
+        }
+    }
+
+    /// Set how many time units the output stays high
+    pub fn set_duration(&mut self, duration: u64) {
+        self.attributes.duration = duration;
+    }
+
+    /// Get how many time units the output stays high
+    pub fn get_duration(&self) -> u64 {
+        self.attributes.duration
+    }
+}
+
+impl Component for PowerOnReset {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        POWER_ON_RESET_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, current_time: Timestamp) -> UpdateResult {
+        let mut result = UpdateResult::new();
+        let started = *self.started.get_or_insert(current_time);
+        let elapsed = current_time.0.saturating_sub(started.0);
+
+        let value = if elapsed < self.attributes.duration {
+            result.request_wakeup(self.attributes.duration - elapsed);
+            Value::High
+        } else {
+            Value::Low
+        };
+        let signal = Signal::new_single(value);
+        if let Some(pin) = self.pins.get_mut("out") {
+            pin.signal = signal.clone();
+        }
+        result.add_output("out".to_string(), signal);
+        result
+    }
+
+    fn reset(&mut self) {
+        self.started = None;
+        if let Some(pin) = self.pins.get_mut("out") {
+            pin.signal = Signal::new_single(Value::High);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        0
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new("#Wiring", POWER_ON_RESET_ID, Vec::new()))
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self.started).ok()
+    }
+
+    fn restore_state(&mut self, state: &serde_json::Value) -> Result<(), serde_json::Error> {
+        self.started = Option::<Timestamp>::deserialize(state)?;
+        Ok(())
+    }
+}
+
+/// Factory for creating Power-On Reset components
+pub struct PowerOnResetFactory;
+
+impl WiringComponentFactory for PowerOnResetFactory {
+    fn id(&self) -> &'static str {
+        POWER_ON_RESET_ID
+    }
+
+    fn display_name(&self) -> &str {
+        "Power-On Reset"
+    }
+
+    fn description(&self) -> &str {
+        "Outputs 1 for a while after the simulation starts, then 0"
+    }
+
+    fn icon_path(&self) -> Option<&str> {
+        Some("por.gif")
+    }
+
+    fn create_component(&self, id: ComponentId) -> Box<dyn crate::Component> {
+        Box::new(PowerOnReset::new(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_on_reset_pulse() {
+        let mut por = PowerOnReset::new(ComponentId(1));
+        por.set_duration(5);
+
+        let result = por.update(Timestamp(2));
+        assert_eq!(result.outputs["out"], Signal::new_single(Value::High));
+        assert_eq!(result.wakeup, Some(5));
+
+        // An update in between only shortens the wait
+        let result = por.update(Timestamp(4));
+        assert_eq!(result.wakeup, Some(3));
+
+        let result = por.update(Timestamp(7));
+        assert_eq!(result.outputs["out"], Signal::new_single(Value::Low));
+        assert_eq!(result.wakeup, None);
+    }
+
+    #[test]
+    fn test_power_on_reset_state() {
+        let mut por = PowerOnReset::new(ComponentId(1));
+        por.update(Timestamp(3));
+        let state = por.save_state().unwrap();
+
+        por.reset();
+        assert_eq!(por.started, None);
+        por.restore_state(&state).unwrap();
+        assert_eq!(por.started, Some(Timestamp(3)));
+    }
+}
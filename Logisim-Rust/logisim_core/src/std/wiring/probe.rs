@@ -0,0 +1,165 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Probe component - shows the value on a net
+//!
+//! A probe only reads: it keeps the value last seen on its net so it can be
+//! displayed, without driving anything.
+
+use crate::{
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    signal::{BusWidth, Signal, Timestamp},
+    std::wiring::WiringComponentFactory,
+};
+use std::collections::HashMap;
+
+/// Unique identifier for the Probe component
+/// Do NOT change as it will prevent project files from loading.
+pub const PROBE_ID: &str = "Probe";
+
+/// Probe component attributes
+#[derive(Debug, Clone)]
+pub struct ProbeAttributes {
+    pub width: BusWidth,
+}
+
+impl Default for ProbeAttributes {
+    fn default() -> Self {
+        Self { width: BusWidth(1) }
+    }
+}
+
+/// Probe component implementation
+#[derive(Debug)]
+pub struct Probe {
+    id: ComponentId,
+    attributes: ProbeAttributes,
+    pins: HashMap<String, Pin>,
+}
+
+impl Probe {
+    /// Create a new probe
+    pub fn new(id: ComponentId) -> Self {
+        let attributes = ProbeAttributes::default();
+
+        let mut pins = HashMap::new();
+        pins.insert("in".to_string(), Pin::new_input("in", attributes.width));
+
+        Self {
+            id,
+            attributes,
+            pins,
+        }
+    }
+
+    /// Set the bit width
+    pub fn set_width(&mut self, width: BusWidth) {
+        self.attributes.width = width;
+        if let Some(pin) = self.pins.get_mut("in") {
+            pin.width = width;
+            pin.signal = Signal::unknown(width);
+        }
+    }
+
+    /// Get the bit width
+    pub fn get_width(&self) -> BusWidth {
+        self.attributes.width
+    }
+
+    /// Get the value last seen on the net
+    pub fn value(&self) -> &Signal {
+        &self.pins["in"].signal
+    }
+}
+
+impl Component for Probe {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        PROBE_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        // The simulation has already copied the net's value to the input
+        UpdateResult::new()
+    }
+
+    fn reset(&mut self) {
+        if let Some(pin) = self.pins.get_mut("in") {
+            pin.signal = Signal::unknown(self.attributes.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        0
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Wiring",
+            PROBE_ID,
+            vec![("width", self.get_width().0.to_string())],
+        ))
+    }
+}
+
+/// Factory for creating Probe components
+pub struct ProbeFactory;
+
+impl WiringComponentFactory for ProbeFactory {
+    fn id(&self) -> &'static str {
+        PROBE_ID
+    }
+
+    fn display_name(&self) -> &str {
+        "Probe"
+    }
+
+    fn description(&self) -> &str {
+        "Displays the value on a net"
+    }
+
+    fn icon_path(&self) -> Option<&str> {
+        Some("probe.gif")
+    }
+
+    fn create_component(&self, id: ComponentId) -> Box<dyn crate::Component> {
+        Box::new(Probe::new(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::Value;
+
+    #[test]
+    fn test_probe_keeps_value() {
+        let mut probe = Probe::new(ComponentId(1));
+        probe.set_width(BusWidth(2));
+        assert_eq!(probe.value(), &Signal::unknown(BusWidth(2)));
+
+        probe.get_pin_mut("in").unwrap().signal = Signal::from_u64(2, BusWidth(2));
+        assert!(probe.update(Timestamp(0)).outputs.is_empty());
+        assert_eq!(probe.value().to_u64(), Some(2));
+
+        probe.reset();
+        assert_eq!(probe.value().get_bit(0), Some(Value::Unknown));
+    }
+}
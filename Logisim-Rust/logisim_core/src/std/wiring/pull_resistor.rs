@@ -0,0 +1,216 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Pull resistor component - weak pull-up or pull-down
+//!
+//! A pull resistor gives the floating bits of the net it is connected to a
+//! value, 0, 1 or an error, without fighting any real driver. In IEEE 1164
+//! mode it is a weak driver instead, giving L, H or W.
+
+use crate::{
+    comp::{CircTool, Component, ComponentId, Pin, UpdateResult},
+    signal::{BusWidth, Signal, Timestamp, Value},
+    std::wiring::WiringComponentFactory,
+};
+use std::collections::HashMap;
+
+/// Unique identifier for the Pull Resistor component
+/// Do NOT change as it will prevent project files from loading.
+pub const PULL_RESISTOR_ID: &str = "Pull Resistor";
+
+/// Pull resistor component attributes
+#[derive(Debug, Clone)]
+pub struct PullResistorAttributes {
+    pub width: BusWidth,
+    pub pull: Value,
+}
+
+impl Default for PullResistorAttributes {
+    fn default() -> Self {
+        Self {
+            width: BusWidth(1),
+            pull: Value::Low,
+        }
+    }
+}
+
+/// Pull resistor component implementation
+#[derive(Debug)]
+pub struct PullResistor {
+    id: ComponentId,
+    attributes: PullResistorAttributes,
+    pins: HashMap<String, Pin>,
+}
+
+impl PullResistor {
+    /// Create a new pull-down resistor
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_pull(id, Value::Low)
+    }
+
+    /// Create a new resistor pulling to `Value::Low`, `Value::High` or
+    /// `Value::Error`
+    pub fn new_with_pull(id: ComponentId, pull: Value) -> Self {
+        let attributes = PullResistorAttributes {
+            pull: Self::valid_pull(pull),
+            ..PullResistorAttributes::default()
+        };
+
+        let mut pins = HashMap::new();
+        pins.insert("out".to_string(), Pin::new_output("out", attributes.width));
+
+        Self {
+            id,
+            attributes,
+            pins,
+        }
+    }
+
+    /// Pulling to anything but a level is an error
+    fn valid_pull(pull: Value) -> Value {
+        match pull {
+            Value::Low | Value::High => pull,
+            _ => Value::Error,
+        }
+    }
+
+    /// Set the value the resistor pulls to
+    ///
+    /// The simulation reads it when the resistor is connected.
+    pub fn set_pull(&mut self, pull: Value) {
+        self.attributes.pull = Self::valid_pull(pull);
+    }
+
+    /// Get the value the resistor pulls to
+    pub fn get_pull(&self) -> Value {
+        self.attributes.pull
+    }
+
+    /// Set the bit width
+    pub fn set_width(&mut self, width: BusWidth) {
+        self.attributes.width = width;
+        if let Some(pin) = self.pins.get_mut("out") {
+            pin.width = width;
+            pin.signal = Signal::high_z(width);
+        }
+    }
+
+    /// Get the bit width
+    pub fn get_width(&self) -> BusWidth {
+        self.attributes.width
+    }
+}
+
+impl Component for PullResistor {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        PULL_RESISTOR_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        // The pull is a property of the net, not a driver
+        UpdateResult::new()
+    }
+
+    fn reset(&mut self) {}
+
+    fn propagation_delay(&self) -> u64 {
+        0
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let pull = match self.get_pull() {
+            Value::High => "1",
+            Value::Error => "X",
+            _ => "0",
+        };
+        Some(CircTool::new(
+            "#Wiring",
+            PULL_RESISTOR_ID,
+            vec![
+                ("pull", pull.to_string()),
+                ("width", self.get_width().0.to_string()),
+            ],
+        ))
+    }
+
+    fn pull_value(&self, pin_name: &str) -> Option<Value> {
+        (pin_name == "out").then_some(self.attributes.pull)
+    }
+}
+
+/// Factory for creating Pull Resistor components
+pub struct PullResistorFactory;
+
+impl WiringComponentFactory for PullResistorFactory {
+    fn id(&self) -> &'static str {
+        PULL_RESISTOR_ID
+    }
+
+    fn display_name(&self) -> &str {
+        "Pull Resistor"
+    }
+
+    fn description(&self) -> &str {
+        "Pulls floating bits of a net to 0, 1 or an error"
+    }
+
+    fn icon_path(&self) -> Option<&str> {
+        Some("pullrect.gif")
+    }
+
+    fn create_component(&self, id: ComponentId) -> Box<dyn crate::Component> {
+        Box::new(PullResistor::new(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_resistor_creation() {
+        let resistor = PullResistor::new(ComponentId(1));
+        assert_eq!(resistor.name(), PULL_RESISTOR_ID);
+        assert_eq!(resistor.get_pull(), Value::Low);
+        assert_eq!(resistor.pull_value("out"), Some(Value::Low));
+        assert_eq!(resistor.pull_value("in"), None);
+    }
+
+    #[test]
+    fn test_pull_resistor_values() {
+        let mut resistor = PullResistor::new_with_pull(ComponentId(1), Value::High);
+        assert_eq!(resistor.get_pull(), Value::High);
+        resistor.set_pull(Value::Unknown);
+        assert_eq!(resistor.get_pull(), Value::Error);
+
+        resistor.set_width(BusWidth(8));
+        assert_eq!(resistor.get_pin("out").unwrap().width, BusWidth(8));
+        assert!(resistor.update(Timestamp(0)).outputs.is_empty());
+    }
+
+    #[test]
+    fn test_pull_resistor_factory() {
+        let factory = PullResistorFactory;
+        assert_eq!(factory.id(), PULL_RESISTOR_ID);
+        let component = factory.create_component(ComponentId(7));
+        assert_eq!(component.pull_value("out"), Some(Value::Low));
+    }
+}
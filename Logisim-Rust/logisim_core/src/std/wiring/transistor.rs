@@ -0,0 +1,313 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Transistor component - switch-level MOSFET
+//!
+//! A transistor connects its source and drain while its gate turns it on: a
+//! high gate for an N-type transistor, a low gate for a P-type one. The
+//! connection conducts in both directions, so the nets on either side
+//! resolve together, pull resistors included. Together with Power and
+//! Ground this is enough to build CMOS gates.
+
+use crate::{
+    comp::{CircTool, Component, ComponentId, Pin, SwitchState, UpdateResult},
+    signal::{BusWidth, Signal, Timestamp, Value},
+    std::wiring::WiringComponentFactory,
+};
+use std::collections::HashMap;
+
+/// Unique identifier for the Transistor component
+/// Do NOT change as it will prevent project files from loading.
+pub const TRANSISTOR_ID: &str = "Transistor";
+
+/// Polarity of a transistor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransistorType {
+    /// P-type: conducts while the gate is low
+    P,
+    /// N-type: conducts while the gate is high
+    N,
+}
+
+/// Transistor component attributes
+#[derive(Debug, Clone)]
+pub struct TransistorAttributes {
+    pub transistor_type: TransistorType,
+    pub width: BusWidth,
+}
+
+impl Default for TransistorAttributes {
+    fn default() -> Self {
+        Self {
+            transistor_type: TransistorType::P,
+            width: BusWidth(1),
+        }
+    }
+}
+
+/// What a switch does for the levels on its control inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Conduction {
+    /// The switch connects its two sides
+    On,
+    /// The switch is open and its sides float apart
+    Off,
+    /// The switch may or may not conduct; both sides are driven with this
+    /// value to show it
+    Undefined(Value),
+}
+
+impl Conduction {
+    /// The switch state of a component conducting between two pins
+    pub(crate) fn switch_state(self, a: &str, b: &str) -> SwitchState {
+        SwitchState {
+            a: a.to_string(),
+            b: b.to_string(),
+            closed: self == Conduction::On,
+        }
+    }
+
+    /// Drive both sides of a switch: nothing unless it is undefined
+    pub(crate) fn outputs(self, a: &str, b: &str, width: BusWidth) -> UpdateResult {
+        let signal = match self {
+            Conduction::Undefined(value) => Signal::new_bus(vec![value; width.as_u32() as usize]),
+            _ => Signal::high_z(width),
+        };
+        let mut result = UpdateResult::new();
+        result.add_output(a.to_string(), signal.clone());
+        result.add_output(b.to_string(), signal);
+        result
+    }
+}
+
+/// Level of the sides of a switch whose gate is neither high nor low: an
+/// uninitialized gate leaves them unknown, anything else is an error
+pub(crate) fn undefined_gate(gate: Value) -> Conduction {
+    match gate {
+        Value::Unknown => Conduction::Undefined(Value::Unknown),
+        _ => Conduction::Undefined(Value::Error),
+    }
+}
+
+/// Transistor component implementation
+#[derive(Debug)]
+pub struct Transistor {
+    id: ComponentId,
+    attributes: TransistorAttributes,
+    pins: HashMap<String, Pin>,
+}
+
+impl Transistor {
+    /// Create a new P-type transistor
+    pub fn new(id: ComponentId) -> Self {
+        Self::new_with_type(id, TransistorType::P)
+    }
+
+    /// Create a new transistor of the given type
+    pub fn new_with_type(id: ComponentId, transistor_type: TransistorType) -> Self {
+        let attributes = TransistorAttributes {
+            transistor_type,
+            ..TransistorAttributes::default()
+        };
+
+        let mut pins = HashMap::new();
+        pins.insert("gate".to_string(), Pin::new_input("gate", BusWidth(1)));
+        pins.insert(
+            "source".to_string(),
+            Pin::new_inout("source", attributes.width),
+        );
+        pins.insert(
+            "drain".to_string(),
+            Pin::new_inout("drain", attributes.width),
+        );
+
+        Self {
+            id,
+            attributes,
+            pins,
+        }
+    }
+
+    /// Set the bit width of the source and drain
+    pub fn set_width(&mut self, width: BusWidth) {
+        self.attributes.width = width;
+        for name in ["source", "drain"] {
+            if let Some(pin) = self.pins.get_mut(name) {
+                pin.width = width;
+                pin.signal = Signal::unknown(width);
+            }
+        }
+    }
+
+    /// Get the bit width of the source and drain
+    pub fn get_width(&self) -> BusWidth {
+        self.attributes.width
+    }
+
+    /// Get the transistor type
+    pub fn get_type(&self) -> TransistorType {
+        self.attributes.transistor_type
+    }
+
+    /// Whether the transistor conducts for its current gate level
+    fn conduction(&self) -> Conduction {
+        let gate = self
+            .pins
+            .get("gate")
+            .and_then(|pin| pin.signal.get_bit(0))
+            .unwrap_or(Value::Unknown);
+        match (self.attributes.transistor_type, gate) {
+            (TransistorType::N, Value::High) | (TransistorType::P, Value::Low) => Conduction::On,
+            (TransistorType::N, Value::Low) | (TransistorType::P, Value::High) => Conduction::Off,
+            (_, gate) => undefined_gate(gate),
+        }
+    }
+}
+
+impl Component for Transistor {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        TRANSISTOR_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        // The simulation joins the source and drain nets through the switch
+        // state; only an undefined gate drives anything
+        self.conduction()
+            .outputs("source", "drain", self.attributes.width)
+    }
+
+    fn reset(&mut self) {
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        0 // The switch opens and closes as soon as the gate changes
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        let transistor_type = match self.get_type() {
+            TransistorType::P => "p",
+            TransistorType::N => "n",
+        };
+        Some(CircTool::new(
+            "#Wiring",
+            TRANSISTOR_ID,
+            vec![
+                ("type", transistor_type.to_string()),
+                ("width", self.get_width().0.to_string()),
+            ],
+        ))
+    }
+
+    fn switch_state(&self) -> Option<SwitchState> {
+        Some(self.conduction().switch_state("source", "drain"))
+    }
+}
+
+/// Factory for creating Transistor components
+pub struct TransistorFactory;
+
+impl WiringComponentFactory for TransistorFactory {
+    fn id(&self) -> &'static str {
+        TRANSISTOR_ID
+    }
+
+    fn display_name(&self) -> &str {
+        "Transistor"
+    }
+
+    fn description(&self) -> &str {
+        "MOSFET switch between source and drain, controlled by the gate"
+    }
+
+    fn icon_path(&self) -> Option<&str> {
+        Some("trans0.gif")
+    }
+
+    fn create_component(&self, id: ComponentId) -> Box<dyn crate::Component> {
+        Box::new(Transistor::new(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_gate(transistor: &mut Transistor, value: Value) {
+        transistor.pins.get_mut("gate").unwrap().signal = Signal::new_single(value);
+    }
+
+    #[test]
+    fn test_transistor_creation() {
+        let transistor = Transistor::new(ComponentId(1));
+        assert_eq!(transistor.id(), ComponentId(1));
+        assert_eq!(transistor.name(), TRANSISTOR_ID);
+        assert_eq!(transistor.get_type(), TransistorType::P);
+        assert_eq!(transistor.get_width(), BusWidth(1));
+        assert_eq!(transistor.pins().len(), 3);
+    }
+
+    #[test]
+    fn test_transistor_conducts_by_type() {
+        let mut n = Transistor::new_with_type(ComponentId(1), TransistorType::N);
+        let mut p = Transistor::new_with_type(ComponentId(2), TransistorType::P);
+        for (gate, n_closed) in [(Value::High, true), (Value::Low, false)] {
+            set_gate(&mut n, gate);
+            set_gate(&mut p, gate);
+            assert_eq!(n.switch_state().unwrap().closed, n_closed);
+            assert_eq!(p.switch_state().unwrap().closed, !n_closed);
+
+            // A defined gate never drives the sides
+            let result = n.update(Timestamp(0));
+            assert_eq!(result.outputs["drain"], Signal::high_z(BusWidth(1)));
+        }
+    }
+
+    #[test]
+    fn test_transistor_undefined_gate() {
+        let mut transistor = Transistor::new_with_type(ComponentId(1), TransistorType::N);
+        transistor.set_width(BusWidth(2));
+        let result = transistor.update(Timestamp(0));
+        assert_eq!(result.outputs["source"], Signal::unknown(BusWidth(2)));
+        assert!(!transistor.switch_state().unwrap().closed);
+
+        set_gate(&mut transistor, Value::HighZ);
+        let result = transistor.update(Timestamp(0));
+        assert_eq!(
+            result.outputs["drain"],
+            Signal::new_bus(vec![Value::Error; 2])
+        );
+    }
+
+    #[test]
+    fn test_transistor_factory() {
+        let factory = TransistorFactory;
+        assert_eq!(factory.id(), TRANSISTOR_ID);
+        let component = factory.create_component(ComponentId(42));
+        assert_eq!(component.id(), ComponentId(42));
+        assert_eq!(
+            component.switch_state().map(|switch| (switch.a, switch.b)),
+            Some(("source".to_string(), "drain".to_string()))
+        );
+    }
+}
@@ -0,0 +1,230 @@
+/*
+ * Logisim-evolution - digital logic design tool and simulator
+ * Copyright by the Logisim-evolution developers
+ *
+ * https://github.com/logisim-evolution/
+ *
+ * This is free software released under GNU GPLv3 license
+ */
+
+//! Transmission gate component - complementary pair of transistors
+//!
+//! A transmission gate is a P-type and an N-type transistor in parallel
+//! between its source and drain, driven by complementary gate inputs. It
+//! conducts in both directions while either transistor is on, and only
+//! isolates its sides while both are off.
+
+use crate::{
+    comp::{CircTool, Component, ComponentId, Pin, SwitchState, UpdateResult},
+    signal::{BusWidth, Signal, Timestamp, Value},
+    std::wiring::transistor::{undefined_gate, Conduction},
+    std::wiring::WiringComponentFactory,
+};
+use std::collections::HashMap;
+
+/// Unique identifier for the Transmission Gate component
+/// Do NOT change as it will prevent project files from loading.
+pub const TRANSMISSION_GATE_ID: &str = "Transmission Gate";
+
+/// Transmission gate component attributes
+#[derive(Debug, Clone)]
+pub struct TransmissionGateAttributes {
+    pub width: BusWidth,
+}
+
+impl Default for TransmissionGateAttributes {
+    fn default() -> Self {
+        Self { width: BusWidth(1) }
+    }
+}
+
+/// Transmission gate component implementation
+#[derive(Debug)]
+pub struct TransmissionGate {
+    id: ComponentId,
+    attributes: TransmissionGateAttributes,
+    pins: HashMap<String, Pin>,
+}
+
+impl TransmissionGate {
+    /// Create a new transmission gate
+    pub fn new(id: ComponentId) -> Self {
+        let attributes = TransmissionGateAttributes::default();
+
+        let mut pins = HashMap::new();
+        pins.insert("p_gate".to_string(), Pin::new_input("p_gate", BusWidth(1)));
+        pins.insert("n_gate".to_string(), Pin::new_input("n_gate", BusWidth(1)));
+        pins.insert(
+            "source".to_string(),
+            Pin::new_inout("source", attributes.width),
+        );
+        pins.insert(
+            "drain".to_string(),
+            Pin::new_inout("drain", attributes.width),
+        );
+
+        Self {
+            id,
+            attributes,
+            pins,
+        }
+    }
+
+    /// Set the bit width of the source and drain
+    pub fn set_width(&mut self, width: BusWidth) {
+        self.attributes.width = width;
+        for name in ["source", "drain"] {
+            if let Some(pin) = self.pins.get_mut(name) {
+                pin.width = width;
+                pin.signal = Signal::unknown(width);
+            }
+        }
+    }
+
+    /// Get the bit width of the source and drain
+    pub fn get_width(&self) -> BusWidth {
+        self.attributes.width
+    }
+
+    fn gate(&self, name: &str) -> Value {
+        self.pins
+            .get(name)
+            .and_then(|pin| pin.signal.get_bit(0))
+            .unwrap_or(Value::Unknown)
+    }
+
+    /// Whether either transistor conducts for the current gate levels
+    fn conduction(&self) -> Conduction {
+        match (self.gate("p_gate"), self.gate("n_gate")) {
+            (Value::Low, _) | (_, Value::High) => Conduction::On,
+            (Value::High, Value::Low) => Conduction::Off,
+            (Value::High, gate) | (gate, _) => undefined_gate(gate),
+        }
+    }
+}
+
+impl Component for TransmissionGate {
+    fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        TRANSMISSION_GATE_ID
+    }
+
+    fn pins(&self) -> &HashMap<String, Pin> {
+        &self.pins
+    }
+
+    fn pins_mut(&mut self) -> &mut HashMap<String, Pin> {
+        &mut self.pins
+    }
+
+    fn update(&mut self, _current_time: Timestamp) -> UpdateResult {
+        self.conduction()
+            .outputs("source", "drain", self.attributes.width)
+    }
+
+    fn reset(&mut self) {
+        for pin in self.pins.values_mut() {
+            pin.signal = Signal::unknown(pin.width);
+        }
+    }
+
+    fn propagation_delay(&self) -> u64 {
+        0 // The switch opens and closes as soon as a gate changes
+    }
+
+    fn circ_tool(&self) -> Option<CircTool> {
+        Some(CircTool::new(
+            "#Wiring",
+            TRANSMISSION_GATE_ID,
+            vec![("width", self.get_width().0.to_string())],
+        ))
+    }
+
+    fn switch_state(&self) -> Option<SwitchState> {
+        Some(self.conduction().switch_state("source", "drain"))
+    }
+}
+
+/// Factory for creating Transmission Gate components
+pub struct TransmissionGateFactory;
+
+impl WiringComponentFactory for TransmissionGateFactory {
+    fn id(&self) -> &'static str {
+        TRANSMISSION_GATE_ID
+    }
+
+    fn display_name(&self) -> &str {
+        "Transmission Gate"
+    }
+
+    fn description(&self) -> &str {
+        "Bidirectional switch controlled by complementary gate inputs"
+    }
+
+    fn icon_path(&self) -> Option<&str> {
+        Some("transmis.gif")
+    }
+
+    fn create_component(&self, id: ComponentId) -> Box<dyn crate::Component> {
+        Box::new(TransmissionGate::new(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_gates(gate: &mut TransmissionGate, p: Value, n: Value) {
+        gate.pins.get_mut("p_gate").unwrap().signal = Signal::new_single(p);
+        gate.pins.get_mut("n_gate").unwrap().signal = Signal::new_single(n);
+    }
+
+    #[test]
+    fn test_transmission_gate_creation() {
+        let gate = TransmissionGate::new(ComponentId(1));
+        assert_eq!(gate.name(), TRANSMISSION_GATE_ID);
+        assert_eq!(gate.get_width(), BusWidth(1));
+        assert_eq!(gate.pins().len(), 4);
+    }
+
+    #[test]
+    fn test_transmission_gate_conduction() {
+        let mut gate = TransmissionGate::new(ComponentId(1));
+        for (p, n, expected) in [
+            (Value::Low, Value::High, Conduction::On),
+            (Value::High, Value::Low, Conduction::Off),
+            // One transistor on is enough
+            (Value::Low, Value::Low, Conduction::On),
+            (Value::Unknown, Value::High, Conduction::On),
+            (
+                Value::High,
+                Value::Unknown,
+                Conduction::Undefined(Value::Unknown),
+            ),
+            (
+                Value::Error,
+                Value::Low,
+                Conduction::Undefined(Value::Error),
+            ),
+        ] {
+            set_gates(&mut gate, p, n);
+            assert_eq!(gate.conduction(), expected, "p={:?} n={:?}", p, n);
+        }
+    }
+
+    #[test]
+    fn test_transmission_gate_update() {
+        let mut gate = TransmissionGate::new(ComponentId(1));
+        gate.set_width(BusWidth(4));
+        set_gates(&mut gate, Value::Low, Value::High);
+        let result = gate.update(Timestamp(0));
+        assert_eq!(result.outputs["source"], Signal::high_z(BusWidth(4)));
+        assert!(gate.switch_state().unwrap().closed);
+
+        gate.reset();
+        assert!(!gate.switch_state().unwrap().closed);
+    }
+}
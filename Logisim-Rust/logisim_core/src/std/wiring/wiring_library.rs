@@ -123,7 +123,25 @@ impl WiringLibrary {
         // Register Clock component
         self.register_factory(Box::new(crate::std::wiring::clock::ClockFactory));
 
-        // TODO: Register other wiring components as they are implemented
+        // Register switch-level components
+        self.register_factory(Box::new(crate::std::wiring::transistor::TransistorFactory));
+        self.register_factory(Box::new(
+            crate::std::wiring::transmission_gate::TransmissionGateFactory,
+        ));
+        self.register_factory(Box::new(
+            crate::std::wiring::pull_resistor::PullResistorFactory,
+        ));
+
+        // Register marker and helper components
+        self.register_factory(Box::new(
+            crate::std::wiring::do_not_connect::DoNotConnectFactory,
+        ));
+        self.register_factory(Box::new(
+            crate::std::wiring::power_on_reset::PowerOnResetFactory,
+        ));
+        self.register_factory(Box::new(crate::std::wiring::probe::ProbeFactory));
+
+        // TODO: Register tunnels, splitters and the remaining wiring components
     }
 }
 
@@ -164,6 +182,16 @@ mod tests {
         assert!(component_ids.contains(&"Ground"));
         assert!(component_ids.contains(&"Power"));
         assert!(component_ids.contains(&"Clock"));
+        for id in [
+            "Transistor",
+            "Transmission Gate",
+            "Pull Resistor",
+            "NoConnect",
+            "POR",
+            "Probe",
+        ] {
+            assert!(component_ids.contains(&id), "{} is not registered", id);
+        }
     }
 
     #[test]
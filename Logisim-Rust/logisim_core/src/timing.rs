@@ -0,0 +1,473 @@
+//! Static timing analysis.
+//!
+//! The analysis walks the combinational logic of a circuit in levelized
+//! order and computes the latest time every node can settle after a clock
+//! edge, using the same per-instance delays the simulation applies. Paths
+//! start at sequential outputs (after their clock-to-output delay), at
+//! components without inputs such as pins and constants, and at nodes
+//! nothing drives. They end at the sampled inputs of sequential components
+//! (plus their setup time), at components without outputs and at nodes
+//! nothing reads.
+//!
+//! The longest register-to-register path sets the minimum clock period.
+//! Subcircuits are flattened when a project is loaded, so hierarchical
+//! designs are analysed as a whole, and the node names along a path carry
+//! the instance path of the subcircuit they belong to.
+
+use crate::comp::{Component, ComponentId};
+use crate::levelized::{linked_nodes, Schedule};
+use crate::netlist::{Netlist, NodeId};
+use crate::simulation::SimulationError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Role of the point a timing path starts or ends at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointKind {
+    /// A circuit input: a node nothing drives or a component without inputs
+    Input,
+    /// A pin of a sequential component
+    Register,
+    /// A circuit output: a component without outputs or a node nothing reads
+    Output,
+}
+
+/// Start or end of a timing path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathPoint {
+    /// Role of the point
+    pub kind: PointKind,
+    /// Component at the point, if any
+    pub component: Option<ComponentId>,
+    /// Name of that component
+    pub name: Option<String>,
+    /// Pin of that component
+    pub pin: Option<String>,
+    /// Node at the point
+    pub node: NodeId,
+    /// Name of that node
+    pub node_name: Option<String>,
+}
+
+/// One combinational component along a timing path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathStep {
+    /// The component
+    pub component: ComponentId,
+    /// Name of the component
+    pub name: String,
+    /// Input pin the path enters through
+    pub input: String,
+    /// Output pin the path leaves through
+    pub output: String,
+    /// Node the output drives
+    pub node: NodeId,
+    /// Name of that node
+    pub node_name: Option<String>,
+    /// Propagation delay of the component
+    pub delay: u64,
+    /// Time the output settles, counted from the start of the path
+    pub arrival: u64,
+}
+
+/// A path through the combinational logic with its delays
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingPath {
+    /// Where the path starts
+    pub start: PathPoint,
+    /// Where the path ends
+    pub end: PathPoint,
+    /// Clock-to-output delay of the starting register, 0 for inputs
+    pub launch: u64,
+    /// Combinational components along the path, in order
+    pub steps: Vec<PathStep>,
+    /// Setup time of the ending register, 0 for outputs
+    pub setup: u64,
+    /// Total delay of the path, launch and setup included
+    pub delay: u64,
+}
+
+/// Result of a static timing analysis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingReport {
+    /// Longest path of all
+    pub critical_path: Option<TimingPath>,
+    /// Longest path from a register to a register
+    pub register_path: Option<TimingPath>,
+    /// Shortest clock period the registers can run at
+    pub min_period: Option<u64>,
+    /// Highest clock frequency in MHz, taking one time unit to be one
+    /// nanosecond as the library delay tables do
+    pub max_frequency_mhz: Option<f64>,
+}
+
+impl TimingReport {
+    /// Serialize the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// How the latest change reaches a node
+#[derive(Debug, Clone)]
+enum Via {
+    /// The node is where a path starts, at a component output or nowhere
+    Launch(Option<(ComponentId, String)>),
+    /// Through a combinational component
+    Component {
+        component: ComponentId,
+        input: (String, NodeId),
+        output: String,
+    },
+    /// Through the bits shared with another node
+    Link(NodeId),
+}
+
+#[derive(Debug, Clone)]
+struct Arrival {
+    time: u64,
+    via: Via,
+}
+
+/// A path end with the node it reads and its setup time
+struct Endpoint {
+    point: PathPoint,
+    setup: u64,
+}
+
+/// Latest settling time of every node reached from a path start
+struct Arrivals<'a> {
+    netlist: &'a Netlist,
+    arrivals: HashMap<NodeId, Arrival>,
+}
+
+impl Arrivals<'_> {
+    /// Record an arrival at a node and every node sharing bits with it,
+    /// keeping the later one
+    fn set(&mut self, node_id: NodeId, time: u64, via: Via) {
+        if self.update(node_id, time, via) {
+            for linked in linked_nodes(self.netlist, node_id) {
+                if linked != node_id {
+                    self.update(linked, time, Via::Link(node_id));
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, node_id: NodeId, time: u64, via: Via) -> bool {
+        if self
+            .arrivals
+            .get(&node_id)
+            .is_some_and(|arrival| arrival.time >= time)
+        {
+            return false;
+        }
+        self.arrivals.insert(node_id, Arrival { time, via });
+        true
+    }
+
+    /// Arrival at a node, treating nodes nothing drives as circuit inputs
+    fn get(&mut self, node_id: NodeId) -> u64 {
+        if !self.arrivals.contains_key(&node_id) {
+            self.set(node_id, 0, Via::Launch(None));
+        }
+        self.arrivals[&node_id].time
+    }
+}
+
+/// Analyse the combinational paths of a circuit
+///
+/// `delay_of` gives the propagation delay of every component instance.
+pub(crate) fn analyze(
+    netlist: &Netlist,
+    components: &HashMap<ComponentId, Box<dyn Component>>,
+    delay_of: impl Fn(ComponentId) -> u64,
+) -> Result<TimingReport, SimulationError> {
+    if let Some(switch) = components.values().find(|c| c.switch_state().is_some()) {
+        return Err(SimulationError::TimingAnalysisError(format!(
+            "switch component {} {} has no direction to time",
+            switch.id(),
+            switch.name()
+        )));
+    }
+    let schedule = Schedule::compile(netlist, components).ok_or_else(|| {
+        SimulationError::TimingAnalysisError("the combinational logic contains a loop".to_string())
+    })?;
+    let mut arrivals = Arrivals {
+        netlist,
+        arrivals: HashMap::new(),
+    };
+    let mut sequential: Vec<ComponentId> = components
+        .iter()
+        .filter(|(_, component)| component.is_sequential())
+        .map(|(&component_id, _)| component_id)
+        .collect();
+    sequential.sort();
+
+    // Registers launch their outputs one clock-to-output delay after the edge
+    for &component_id in &sequential {
+        let launch = delay_of(component_id);
+        for (pin_name, node_id) in schedule.outputs(component_id) {
+            let via = Via::Launch(Some((component_id, pin_name.to_string())));
+            arrivals.set(node_id, launch, via);
+        }
+    }
+
+    for position in 0..schedule.len() {
+        let component_id = schedule.component_at(position);
+        let mut inputs = schedule.inputs(component_id).to_vec();
+        inputs.sort();
+        let latest = inputs
+            .into_iter()
+            .map(|input| (arrivals.get(input.1), input))
+            .fold(
+                None,
+                |latest: Option<(u64, (String, NodeId))>, next| match latest {
+                    Some(latest) if latest.0 >= next.0 => Some(latest),
+                    _ => Some(next),
+                },
+            );
+        for (pin_name, node_id) in schedule.outputs(component_id) {
+            match &latest {
+                Some((time, input)) => {
+                    let via = Via::Component {
+                        component: component_id,
+                        input: input.clone(),
+                        output: pin_name.to_string(),
+                    };
+                    arrivals.set(node_id, time + delay_of(component_id), via);
+                }
+                None => {
+                    let via = Via::Launch(Some((component_id, pin_name.to_string())));
+                    arrivals.set(node_id, 0, via);
+                }
+            }
+        }
+    }
+
+    let node_name = |node_id: NodeId| netlist.get_node(node_id).and_then(|node| node.name.clone());
+    let component_name = |component_id: ComponentId| {
+        components
+            .get(&component_id)
+            .map(|component| component.name().to_string())
+    };
+
+    // Path ends: sampled register inputs, inputs of components without
+    // outputs and nodes nothing reads
+    let mut endpoints = Vec::new();
+    let mut sinks: Vec<ComponentId> = components.keys().copied().collect();
+    sinks.sort();
+    for component_id in sinks {
+        let component = &components[&component_id];
+        let register = component.is_sequential();
+        if !register && !schedule.outputs(component_id).is_empty() {
+            continue;
+        }
+        let constraints = register.then(|| component.timing_constraints()).flatten();
+        let mut inputs = schedule.inputs(component_id).to_vec();
+        inputs.sort();
+        for (pin_name, node_id) in inputs {
+            let setup = match &constraints {
+                Some(constraints) if constraints.inputs.contains(&pin_name) => constraints.setup,
+                Some(_) => continue,
+                None => 0,
+            };
+            endpoints.push(Endpoint {
+                point: PathPoint {
+                    kind: if register {
+                        PointKind::Register
+                    } else {
+                        PointKind::Output
+                    },
+                    component: Some(component_id),
+                    name: component_name(component_id),
+                    pin: Some(pin_name),
+                    node: node_id,
+                    node_name: node_name(node_id),
+                },
+                setup,
+            });
+        }
+    }
+    let mut unread: Vec<NodeId> = arrivals
+        .arrivals
+        .keys()
+        .copied()
+        .filter(|&node_id| {
+            linked_nodes(netlist, node_id)
+                .into_iter()
+                .all(|linked| schedule.readers(linked).is_empty())
+        })
+        .collect();
+    unread.sort();
+    for node_id in unread {
+        endpoints.push(Endpoint {
+            point: PathPoint {
+                kind: PointKind::Output,
+                component: None,
+                name: None,
+                pin: None,
+                node: node_id,
+                node_name: node_name(node_id),
+            },
+            setup: 0,
+        });
+    }
+
+    // Trace every end back to its start
+    let trace = |endpoint: Endpoint| -> TimingPath {
+        let mut steps = Vec::new();
+        let mut node_id = endpoint.point.node;
+        let start = loop {
+            let arrival = &arrivals.arrivals[&node_id];
+            match &arrival.via {
+                Via::Link(previous) => node_id = *previous,
+                Via::Component {
+                    component,
+                    input,
+                    output,
+                } => {
+                    let delay = delay_of(*component);
+                    steps.push(PathStep {
+                        component: *component,
+                        name: component_name(*component).unwrap_or_default(),
+                        input: input.0.clone(),
+                        output: output.clone(),
+                        node: node_id,
+                        node_name: node_name(node_id),
+                        delay,
+                        arrival: arrival.time,
+                    });
+                    node_id = input.1;
+                }
+                Via::Launch(source) => {
+                    let (component, pin) = source.clone().unzip();
+                    let register = component
+                        .and_then(|component| components.get(&component))
+                        .is_some_and(|component| component.is_sequential());
+                    break (
+                        PathPoint {
+                            kind: if register {
+                                PointKind::Register
+                            } else {
+                                PointKind::Input
+                            },
+                            component,
+                            name: component.and_then(component_name),
+                            pin,
+                            node: node_id,
+                            node_name: node_name(node_id),
+                        },
+                        arrival.time,
+                    );
+                }
+            }
+        };
+        steps.reverse();
+        let arrival = arrivals.arrivals[&endpoint.point.node].time;
+        TimingPath {
+            start: start.0,
+            end: endpoint.point,
+            launch: start.1,
+            steps,
+            setup: endpoint.setup,
+            delay: arrival + endpoint.setup,
+        }
+    };
+
+    let mut critical_path: Option<TimingPath> = None;
+    let mut register_path: Option<TimingPath> = None;
+    for endpoint in endpoints {
+        if !arrivals.arrivals.contains_key(&endpoint.point.node) {
+            continue;
+        }
+        let path = trace(endpoint);
+        let registers =
+            path.start.kind == PointKind::Register && path.end.kind == PointKind::Register;
+        if registers
+            && register_path
+                .as_ref()
+                .is_none_or(|worst| path.delay > worst.delay)
+        {
+            register_path = Some(path.clone());
+        }
+        if critical_path
+            .as_ref()
+            .is_none_or(|worst| path.delay > worst.delay)
+        {
+            critical_path = Some(path);
+        }
+    }
+
+    let min_period = register_path.as_ref().map(|path| path.delay);
+    Ok(TimingReport {
+        critical_path,
+        register_path,
+        min_period,
+        max_frequency_mhz: min_period
+            .filter(|&period| period > 0)
+            .map(|period| 1000.0 / period as f64),
+    })
+}
+
+impl fmt::Display for PathPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.name, self.component, &self.pin) {
+            (Some(name), Some(component), Some(pin)) => {
+                write!(f, "{} {} pin {}", name, component, pin)?
+            }
+            _ => write!(f, "node {}", self.node)?,
+        }
+        if let Some(node_name) = &self.node_name {
+            write!(f, " ({})", node_name)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TimingPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  from {:?} {}", self.start.kind, self.start)?;
+        writeln!(f, "  {:>8}  {:>6}", "arrival", "delay")?;
+        writeln!(f, "  {:>8}  {:>6}  launch", self.launch, self.launch)?;
+        for step in &self.steps {
+            write!(
+                f,
+                "  {:>8}  {:>6}  {} {} {} -> {}",
+                step.arrival, step.delay, step.name, step.component, step.input, step.output
+            )?;
+            match &step.node_name {
+                Some(node_name) => writeln!(f, " ({})", node_name)?,
+                None => writeln!(f)?,
+            }
+        }
+        if self.setup > 0 {
+            writeln!(f, "  {:>8}  {:>6}  setup", self.delay, self.setup)?;
+        }
+        writeln!(f, "  to {:?} {}", self.end.kind, self.end)?;
+        write!(f, "  total delay {}", self.delay)
+    }
+}
+
+impl fmt::Display for TimingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.critical_path {
+            Some(path) => writeln!(f, "Critical path:\n{}", path)?,
+            None => writeln!(f, "Critical path: none")?,
+        }
+        if let Some(path) = &self.register_path {
+            if Some(path) != self.critical_path.as_ref() {
+                writeln!(f, "Longest register to register path:\n{}", path)?;
+            }
+        }
+        match (self.min_period, self.max_frequency_mhz) {
+            (Some(period), Some(frequency)) => write!(
+                f,
+                "Minimum clock period: {}\nMaximum clock frequency: {:.2} MHz",
+                period, frequency
+            ),
+            _ => write!(f, "Minimum clock period: no register to register path"),
+        }
+    }
+}
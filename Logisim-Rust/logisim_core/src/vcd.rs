@@ -0,0 +1,447 @@
+//! Value Change Dump (VCD) waveform recording.
+//!
+//! A [`VcdRecorder`] listens to a simulation through its signal callbacks
+//! and streams every change of the recorded nets to an IEEE 1364 VCD file,
+//! which waveform viewers such as GTKWave open next to traces from other
+//! simulators and logic analyzers. It needs no user interface, so batch
+//! runs and tests can dump waveforms too.
+//!
+//! Nets built from a .circ file are named after their path in the circuit
+//! hierarchy, as in `alu/carry`; every subcircuit instance on that path
+//! becomes a VCD scope. Buses are written as vectors, most significant bit
+//! first, with unknown and error bits as `x` and floating bits as `z`.
+
+use crate::build_info::BuildInfo;
+use crate::netlist::NodeId;
+use crate::signal::{BusWidth, Signal, Timestamp, Value};
+use crate::simulation::{SignalChangeCallback, Simulation};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Unit of the VCD timescale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    S,
+    Ms,
+    Us,
+    Ns,
+    Ps,
+    Fs,
+}
+
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self {
+            TimeUnit::S => "s",
+            TimeUnit::Ms => "ms",
+            TimeUnit::Us => "us",
+            TimeUnit::Ns => "ns",
+            TimeUnit::Ps => "ps",
+            TimeUnit::Fs => "fs",
+        };
+        write!(f, "{}", unit)
+    }
+}
+
+/// Length of one simulation time unit, such as `10 ps`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timescale {
+    magnitude: u32,
+    unit: TimeUnit,
+}
+
+impl Timescale {
+    /// Create a timescale; VCD only allows a magnitude of 1, 10 or 100
+    pub fn new(magnitude: u32, unit: TimeUnit) -> Option<Self> {
+        matches!(magnitude, 1 | 10 | 100).then_some(Timescale { magnitude, unit })
+    }
+
+    /// Get the magnitude
+    pub fn magnitude(&self) -> u32 {
+        self.magnitude
+    }
+
+    /// Get the unit
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+}
+
+impl Default for Timescale {
+    /// Nanoseconds, the time unit of the delay models
+    fn default() -> Self {
+        Timescale {
+            magnitude: 1,
+            unit: TimeUnit::Ns,
+        }
+    }
+}
+
+impl fmt::Display for Timescale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.magnitude, self.unit)
+    }
+}
+
+/// What a VCD file records and how
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VcdOptions {
+    /// Length of one simulation time unit
+    pub timescale: Timescale,
+    /// Name of the outermost scope; the main circuit of the simulation's
+    /// .circ file, or `top`, when not given
+    pub top: Option<String>,
+    /// Nets to record; all of them when not given
+    pub nodes: Option<Vec<NodeId>>,
+}
+
+/// A recorded net
+#[derive(Debug)]
+struct Var {
+    code: String,
+    width: BusWidth,
+    /// Value last written, to leave out changes to the same value
+    value: String,
+}
+
+/// Nets and nested scopes of one VCD scope, by name
+///
+/// Names must be unique within a scope, so a net named like another net or
+/// a scope next to it gets its node id appended, as in `carry_12`.
+#[derive(Debug, Default)]
+struct Scope {
+    vars: BTreeMap<String, NodeId>,
+    scopes: BTreeMap<String, Scope>,
+}
+
+impl Scope {
+    fn insert(&mut self, path: &[&str], node: NodeId) {
+        match path {
+            [name] => {
+                let name = if self.vars.contains_key(*name) {
+                    format!("{}_{}", name, node.0)
+                } else {
+                    name.to_string()
+                };
+                self.vars.insert(name, node);
+            }
+            [scope, rest @ ..] => self
+                .scopes
+                .entry(scope.to_string())
+                .or_default()
+                .insert(rest, node),
+            [] => {}
+        }
+    }
+
+    fn write(
+        &self,
+        out: &mut dyn Write,
+        name: &str,
+        vars: &HashMap<NodeId, Var>,
+    ) -> io::Result<()> {
+        writeln!(out, "$scope module {} $end", name)?;
+        for (name, node) in &self.vars {
+            let var = &vars[node];
+            let name = if self.scopes.contains_key(name) {
+                format!("{}_{}", name, node.0)
+            } else {
+                name.clone()
+            };
+            let width = var.width.as_u32();
+            if width == 1 {
+                writeln!(out, "$var wire 1 {} {} $end", var.code, name)?;
+            } else {
+                writeln!(
+                    out,
+                    "$var wire {} {} {} [{}:0] $end",
+                    width,
+                    var.code,
+                    name,
+                    width - 1
+                )?;
+            }
+        }
+        for (name, scope) in &self.scopes {
+            scope.write(out, name, vars)?;
+        }
+        writeln!(out, "$upscope $end")
+    }
+}
+
+/// Identifier code of the `index`th variable, from the 94 printable ASCII
+/// characters
+fn identifier_code(mut index: usize) -> String {
+    let mut code = String::new();
+    loop {
+        code.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            return code;
+        }
+        index -= 1;
+    }
+}
+
+fn value_char(value: Value) -> char {
+    match value {
+        Value::Low => '0',
+        Value::High => '1',
+        Value::Unknown | Value::Error => 'x',
+        Value::HighZ => 'z',
+    }
+}
+
+/// A value change line without its identifier code
+fn format_value(signal: &Signal, width: BusWidth) -> String {
+    let bits: String = (0..width.as_u32())
+        .rev()
+        .map(|bit| value_char(signal.get_bit(bit).unwrap_or(Value::Unknown)))
+        .collect();
+    if width.as_u32() == 1 {
+        bits
+    } else {
+        format!("b{} ", bits)
+    }
+}
+
+struct Dump {
+    out: Box<dyn Write + Send>,
+    vars: HashMap<NodeId, Var>,
+    /// Last time written to the file
+    time: u64,
+    /// Added to simulation times once the simulation went back in time
+    shift: u64,
+    /// First error writing the file, reported by `finish`
+    error: Option<io::Error>,
+}
+
+impl fmt::Debug for Dump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dump")
+            .field("vars", &self.vars)
+            .field("time", &self.time)
+            .field("shift", &self.shift)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Dump {
+    /// Move the file to a simulation time
+    ///
+    /// VCD times only go forward, so when the simulation was stepped back
+    /// or restored to an earlier time, the waveforms go on from the last
+    /// time written, and every later time is shifted by the time rewound.
+    fn seek(&mut self, time: u64) -> io::Result<()> {
+        let shifted = time + self.shift;
+        if shifted < self.time {
+            writeln!(self.out, "$comment rewound to time {} $end", time)?;
+            self.shift = self.time - time;
+        } else if shifted > self.time {
+            writeln!(self.out, "#{}", shifted)?;
+            self.time = shifted;
+        }
+        Ok(())
+    }
+
+    fn change(&mut self, node: NodeId, time: u64, signal: &Signal) -> io::Result<()> {
+        let Some(var) = self.vars.get(&node) else {
+            return Ok(());
+        };
+        let value = format_value(signal, var.width);
+        if value == var.value {
+            return Ok(());
+        }
+        self.seek(time)?;
+        if let Some(var) = self.vars.get_mut(&node) {
+            writeln!(self.out, "{}{}", value, var.code)?;
+            var.value = value;
+        }
+        Ok(())
+    }
+}
+
+/// Streams the signal changes of a simulation to a VCD file
+///
+/// The recorder is a shared handle: clones write to the same file, so one
+/// can be handed to the simulation while another finishes the file.
+#[derive(Debug, Clone)]
+pub struct VcdRecorder {
+    dump: Arc<Mutex<Dump>>,
+}
+
+impl VcdRecorder {
+    /// Write the VCD header and current values of a simulation, then record
+    /// its changes
+    pub fn attach<W: Write + Send + 'static>(
+        sim: &mut Simulation,
+        out: W,
+        options: &VcdOptions,
+    ) -> io::Result<Self> {
+        let recorder = Self::new(sim, Box::new(out), options)?;
+        sim.add_signal_callback(recorder.callback());
+        Ok(recorder)
+    }
+
+    /// Record a simulation to a VCD file
+    pub fn create<P: AsRef<Path>>(
+        sim: &mut Simulation,
+        path: P,
+        options: &VcdOptions,
+    ) -> io::Result<Self> {
+        Self::attach(sim, BufWriter::new(File::create(path)?), options)
+    }
+
+    /// Write the VCD header and current values of a simulation
+    ///
+    /// Changes are only recorded once [`callback`](Self::callback) is
+    /// installed; [`attach`](Self::attach) does both.
+    pub fn new(
+        sim: &Simulation,
+        mut out: Box<dyn Write + Send>,
+        options: &VcdOptions,
+    ) -> io::Result<Self> {
+        let netlist = sim.netlist();
+        let mut nodes: Vec<NodeId> = match &options.nodes {
+            Some(nodes) => nodes.clone(),
+            None => netlist.get_all_nodes().keys().copied().collect(),
+        };
+        nodes.sort();
+        nodes.dedup();
+
+        let mut top = Scope::default();
+        let mut vars = HashMap::new();
+        for node_id in nodes {
+            let Some(node) = netlist.get_node(node_id) else {
+                continue;
+            };
+            let name = node
+                .name
+                .as_deref()
+                .filter(|name| !name.is_empty())
+                .map_or_else(|| node_id.to_string(), |name| name.replace(' ', "_"));
+            let path: Vec<&str> = name.split('/').filter(|part| !part.is_empty()).collect();
+            top.insert(&path, node_id);
+            vars.insert(
+                node_id,
+                Var {
+                    code: identifier_code(vars.len()),
+                    width: node.width,
+                    value: format_value(node.get_signal(), node.width),
+                },
+            );
+        }
+
+        let top_name = options
+            .top
+            .clone()
+            .or_else(|| {
+                sim.circuit_source()
+                    .and_then(|source| source.circuit_file.main_circuit.clone())
+            })
+            .unwrap_or_else(|| "top".to_string());
+        let time = sim.current_time().as_u64();
+
+        writeln!(out, "$version Logisim-RUST {} $end", BuildInfo::VERSION)?;
+        writeln!(out, "$timescale {} $end", options.timescale)?;
+        top.write(&mut out, &top_name.replace(' ', "_"), &vars)?;
+        writeln!(out, "$enddefinitions $end")?;
+        writeln!(out, "#{}", time)?;
+        writeln!(out, "$dumpvars")?;
+        let mut initial: Vec<&Var> = vars.values().collect();
+        initial.sort_by_key(|var| (var.code.len(), var.code.clone()));
+        for var in initial {
+            writeln!(out, "{}{}", var.value, var.code)?;
+        }
+        writeln!(out, "$end")?;
+
+        Ok(VcdRecorder {
+            dump: Arc::new(Mutex::new(Dump {
+                out,
+                vars,
+                time,
+                shift: 0,
+                error: None,
+            })),
+        })
+    }
+
+    /// Get a signal callback that feeds this recorder
+    pub fn callback(&self) -> SignalChangeCallback {
+        let dump = Arc::clone(&self.dump);
+        Box::new(move |node_id: NodeId, time: Timestamp, signal: &Signal| {
+            if let Ok(mut dump) = dump.lock() {
+                if dump.error.is_none() {
+                    if let Err(e) = dump.change(node_id, time.as_u64(), signal) {
+                        dump.error = Some(e);
+                    }
+                }
+            }
+        })
+    }
+
+    /// Record the values of a simulation that was stepped back or restored
+    ///
+    /// Signal callbacks are not told about the values a simulation goes
+    /// back to, so without this the waveforms keep the values they had
+    /// until each net changes again.
+    pub fn sync(&self, sim: &Simulation) -> io::Result<()> {
+        let Ok(mut dump) = self.dump.lock() else {
+            return Err(io::Error::other("VCD recorder poisoned"));
+        };
+        let mut nodes: Vec<NodeId> = dump.vars.keys().copied().collect();
+        nodes.sort();
+        let time = sim.current_time().as_u64();
+        for node_id in nodes {
+            if let Some(signal) = sim.netlist().get_signal(node_id) {
+                dump.change(node_id, time, signal)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark the simulation's current time as the end of the waveforms and
+    /// flush the file
+    ///
+    /// Returns the first error met while writing changes, if any.
+    pub fn finish(&self, sim: &Simulation) -> io::Result<()> {
+        let Ok(mut dump) = self.dump.lock() else {
+            return Err(io::Error::other("VCD recorder poisoned"));
+        };
+        if let Some(e) = dump.error.take() {
+            return Err(e);
+        }
+        dump.seek(sim.current_time().as_u64())?;
+        dump.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_codes() {
+        assert_eq!(identifier_code(0), "!");
+        assert_eq!(identifier_code(93), "~");
+        assert_eq!(identifier_code(94), "!!");
+        assert_eq!(identifier_code(94 + 94 * 94), "!!!");
+    }
+
+    #[test]
+    fn test_values() {
+        let bus = Signal::new_bus(vec![Value::High, Value::HighZ, Value::Error, Value::Low]);
+        assert_eq!(format_value(&bus, BusWidth(4)), "b0xz1 ");
+        let bit = Signal::new_single(Value::Unknown);
+        assert_eq!(format_value(&bit, BusWidth(1)), "x");
+        assert_eq!(
+            Timescale::new(10, TimeUnit::Ps).unwrap().to_string(),
+            "10 ps"
+        );
+        assert!(Timescale::new(5, TimeUnit::Ns).is_none());
+    }
+}
@@ -0,0 +1,317 @@
+//! Setup, hold and glitch violation detection.
+//!
+//! Sequential components describe the inputs they sample on a clock edge
+//! with [`TimingConstraints`](crate::comp::TimingConstraints). While the
+//! simulation runs, every node change is checked against those constraints,
+//! and against the configured glitch width, and each breach is reported as a
+//! [`Violation`].
+//!
+//! Only changes between fully defined values count: a node leaving an
+//! unknown or floating state, as every node does after a reset, is not a
+//! data change and cannot end a glitch.
+
+use crate::comp::{Component, ComponentId};
+use crate::netlist::{Netlist, NodeId};
+use crate::signal::{Signal, Timestamp, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Kind of timing violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ViolationKind {
+    /// A sampled input changed too shortly before the active clock edge
+    Setup,
+    /// A sampled input changed too shortly after the active clock edge
+    Hold,
+    /// A node went back to its previous value too quickly
+    Glitch,
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::Setup => write!(f, "Setup"),
+            ViolationKind::Hold => write!(f, "Hold"),
+            ViolationKind::Glitch => write!(f, "Glitch"),
+        }
+    }
+}
+
+/// A timing violation found during simulation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    /// What was violated
+    pub kind: ViolationKind,
+    /// Simulation time the violation was detected at: the clock edge for
+    /// setup, the data change for hold and the end of the pulse for glitches
+    pub time: Timestamp,
+    /// Node that changed too early, too late or too briefly
+    pub node: NodeId,
+    /// Component whose constraint was violated (none for glitches)
+    pub component: Option<ComponentId>,
+    /// Input pin of that component on the node
+    pub pin: Option<String>,
+    /// Time between the clock edge and the data change, or the pulse width
+    pub actual: u64,
+    /// Minimum time required
+    pub required: u64,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} violation at time {} on {}",
+            self.kind, self.time, self.node
+        )?;
+        if let (Some(component), Some(pin)) = (self.component, &self.pin) {
+            write!(f, " ({} pin {})", component, pin)?;
+        }
+        write!(f, ": {} < {}", self.actual, self.required)
+    }
+}
+
+/// What the simulation does once it detects a timing violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViolationAction {
+    /// Add the violation to the statistics and carry on
+    #[default]
+    Record,
+    /// Record the violation and stop with `SimulationError::TimingViolation`
+    Stop,
+}
+
+/// One component's constraints with its pins resolved to nodes
+#[derive(Debug)]
+struct Watch {
+    component: ComponentId,
+    rising: bool,
+    setup: u64,
+    hold: u64,
+    /// Sampled input pins and their nodes
+    inputs: Vec<(NodeId, String)>,
+    last_edge: Option<Timestamp>,
+}
+
+/// Recent changes of a node
+#[derive(Debug, Clone)]
+struct NodeHistory {
+    /// Time of the last change between defined values
+    changed: Option<Timestamp>,
+    /// Value before that change
+    before: Option<Signal>,
+    /// Current value
+    current: Signal,
+}
+
+/// Checks node changes against the components' timing constraints
+#[derive(Debug)]
+pub(crate) struct TimingMonitor {
+    /// Whether the watches must be rebuilt from the circuit
+    stale: bool,
+    /// Whether there is anything to check at all
+    enabled: bool,
+    watches: Vec<Watch>,
+    /// Watches clocked by each node
+    clocks: HashMap<NodeId, Vec<usize>>,
+    /// Watches and pins sampling each node
+    inputs: HashMap<NodeId, Vec<(usize, String)>>,
+    history: HashMap<NodeId, NodeHistory>,
+}
+
+impl Default for TimingMonitor {
+    fn default() -> Self {
+        Self {
+            stale: true,
+            enabled: false,
+            watches: Vec::new(),
+            clocks: HashMap::new(),
+            inputs: HashMap::new(),
+            history: HashMap::new(),
+        }
+    }
+}
+
+impl TimingMonitor {
+    /// Rebuild the watches on the next check
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Resolve the components' constraints if the circuit changed
+    pub fn prepare(
+        &mut self,
+        netlist: &Netlist,
+        components: &HashMap<ComponentId, Box<dyn Component>>,
+        glitch_width: u64,
+    ) {
+        if !self.stale {
+            return;
+        }
+        *self = Self {
+            stale: false,
+            ..Self::default()
+        };
+
+        for (&component_id, component) in components {
+            let Some(constraints) = component.timing_constraints() else {
+                continue;
+            };
+            let connections = netlist.get_component_connections(component_id);
+            let node_of = |pin: &str| {
+                connections
+                    .iter()
+                    .find(|connection| connection.pin_name == pin)
+                    .map(|connection| connection.node_id)
+            };
+            let Some(clock) = node_of(&constraints.clock) else {
+                continue;
+            };
+            let inputs: Vec<(NodeId, String)> = constraints
+                .inputs
+                .into_iter()
+                .filter_map(|pin| Some((node_of(&pin)?, pin)))
+                .collect();
+            let index = self.watches.len();
+            for (node, pin) in &inputs {
+                self.inputs
+                    .entry(*node)
+                    .or_default()
+                    .push((index, pin.clone()));
+            }
+            self.clocks.entry(clock).or_default().push(index);
+            self.watches.push(Watch {
+                component: component_id,
+                rising: constraints.rising,
+                setup: constraints.setup,
+                hold: constraints.hold,
+                inputs,
+                last_edge: None,
+            });
+        }
+
+        self.enabled = !self.watches.is_empty() || glitch_width > 0;
+        if self.enabled {
+            self.history = netlist
+                .get_all_nodes()
+                .iter()
+                .map(|(&node_id, node)| {
+                    let history = NodeHistory {
+                        changed: None,
+                        before: None,
+                        current: node.signal.clone(),
+                    };
+                    (node_id, history)
+                })
+                .collect();
+        }
+    }
+
+    /// Record a node change and return the violations it causes
+    ///
+    /// Pulses shorter than `glitch_width` are glitches; 0 disables the
+    /// glitch check.
+    pub fn node_changed(
+        &mut self,
+        time: Timestamp,
+        node: NodeId,
+        signal: &Signal,
+        glitch_width: u64,
+    ) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if !self.enabled {
+            return violations;
+        }
+        let Some(history) = self.history.remove(&node) else {
+            self.history.insert(
+                node,
+                NodeHistory {
+                    changed: None,
+                    before: None,
+                    current: signal.clone(),
+                },
+            );
+            return violations;
+        };
+        let defined = history.current.is_fully_defined() && signal.is_fully_defined();
+
+        if defined && glitch_width > 0 && history.before.as_ref() == Some(signal) {
+            if let Some(width) = history.changed.map(|changed| time.0 - changed.0) {
+                if width < glitch_width {
+                    violations.push(Violation {
+                        kind: ViolationKind::Glitch,
+                        time,
+                        node,
+                        component: None,
+                        pin: None,
+                        actual: width,
+                        required: glitch_width,
+                    });
+                }
+            }
+        }
+
+        // An active edge checks how long ago each sampled input changed
+        let level = |signal: &Signal| signal.as_single().unwrap_or(Value::Unknown);
+        let (old, new) = (level(&history.current), level(signal));
+        for &index in self.clocks.get(&node).into_iter().flatten() {
+            let watch = &mut self.watches[index];
+            let edge = if watch.rising {
+                (old, new) == (Value::Low, Value::High)
+            } else {
+                (old, new) == (Value::High, Value::Low)
+            };
+            if !edge {
+                continue;
+            }
+            watch.last_edge = Some(time);
+            for (input, pin) in &watch.inputs {
+                let changed = self.history.get(input).and_then(|input| input.changed);
+                let Some(setup) = changed.map(|changed| time.0 - changed.0) else {
+                    continue;
+                };
+                if setup < watch.setup {
+                    violations.push(Violation {
+                        kind: ViolationKind::Setup,
+                        time,
+                        node: *input,
+                        component: Some(watch.component),
+                        pin: Some(pin.clone()),
+                        actual: setup,
+                        required: watch.setup,
+                    });
+                }
+            }
+        }
+
+        // A change of a sampled input checks how long ago the last edge was
+        if defined {
+            for (index, pin) in self.inputs.get(&node).into_iter().flatten() {
+                let watch = &self.watches[*index];
+                let Some(hold) = watch.last_edge.map(|edge| time.0 - edge.0) else {
+                    continue;
+                };
+                if hold < watch.hold {
+                    violations.push(Violation {
+                        kind: ViolationKind::Hold,
+                        time,
+                        node,
+                        component: Some(watch.component),
+                        pin: Some(pin.clone()),
+                        actual: hold,
+                        required: watch.hold,
+                    });
+                }
+            }
+        }
+
+        let history = NodeHistory {
+            changed: defined.then_some(time),
+            before: defined.then_some(history.current),
+            current: signal.clone(),
+        };
+        self.history.insert(node, history);
+        violations
+    }
+}
@@ -0,0 +1,209 @@
+//! Switching activity and power tests
+//!
+//! These tests record a 4-bit bus counting in binary next to one counting
+//! in Gray code, then a clocked counter, and check the toggle counts, the
+//! time spent at each value and the estimated dynamic power. They also check
+//! that a wire driven from two pins is charged once, and that a
+//! `capacitance` attribute of a loaded .circ file is used.
+
+use logisim_core::activity::{ActivityRecorder, PowerModel};
+use logisim_core::circ_format::{CircIntegration, CircParser};
+use logisim_core::std::memory::Counter;
+use logisim_core::{BusWidth, ComponentId, GrayIncrementer, Signal, Simulation, Timestamp, Value};
+
+#[test]
+fn test_gray_code_toggles_less_than_binary() {
+    let mut sim = Simulation::new();
+    let width = BusWidth(4);
+    let binary = sim
+        .netlist_mut()
+        .create_named_node(width, "binary".to_string());
+    let gray = sim
+        .netlist_mut()
+        .create_named_node(width, "gray".to_string());
+    sim.reset();
+    let recorder = ActivityRecorder::attach(&mut sim);
+
+    for count in 0..16 {
+        let time = Timestamp(count * 10);
+        let code = GrayIncrementer::binary_to_gray(count, width);
+        sim.schedule_signal_change(time, binary, Signal::from_u64(count, width), ComponentId(0));
+        sim.schedule_signal_change(time, gray, Signal::from_u64(code, width), ComponentId(0));
+    }
+    sim.run().unwrap();
+
+    let report = recorder.report(&sim, &PowerModel::new());
+    assert_eq!(report.duration, 150);
+    // Counting to 15 flips 26 bits in binary and one per step in Gray code
+    assert_eq!(report.nets[0].node, binary);
+    assert_eq!(report.nets[0].toggles, 26);
+    assert_eq!(report.net(gray).unwrap().toggles, 15);
+    assert_eq!(report.total_toggles, 41);
+
+    // The bus is defined from the start, and every bit is high for 7 of the
+    // 15 counts that last 10 units
+    let time = report.net(binary).unwrap().time;
+    assert_eq!(time.low + time.high, 4 * 150);
+    assert_eq!(time.get(Value::Unknown), 0);
+    assert_eq!(time.high, 4 * 70);
+
+    // Nothing drives them, so no component is charged for the switching
+    assert!(report.components.is_empty());
+    assert_eq!(report.dynamic_power, 0.0);
+    let text = report.to_string();
+    assert!(
+        text.starts_with("Switching activity over 150 time units: 41 toggles\n"),
+        "{}",
+        text
+    );
+    assert!(text.contains("(binary): 26\n"), "{}", text);
+}
+
+#[test]
+fn test_counter_dynamic_power() {
+    let mut sim = Simulation::new();
+    let counter = sim.add_component(Box::new(Counter::new_with_width(
+        ComponentId(1),
+        BusWidth(4),
+    )));
+    let clk = sim.netlist_mut().create_node(BusWidth(1));
+    let out = sim.netlist_mut().create_node(BusWidth(4));
+    let carry = sim.netlist_mut().create_node(BusWidth(1));
+    for (pin, node) in [("CLK", clk), ("OUT", out), ("CARRY", carry)] {
+        sim.connect(counter, pin.to_string(), node).unwrap();
+    }
+    sim.reset();
+    let recorder = ActivityRecorder::attach(&mut sim);
+
+    sim.schedule_signal_change(
+        Timestamp(0),
+        clk,
+        Signal::new_single(Value::Low),
+        ComponentId(0),
+    );
+    for cycle in 0..16 {
+        for (offset, value) in [(10, Value::High), (20, Value::Low)] {
+            sim.schedule_signal_change(
+                Timestamp(cycle * 20 + offset),
+                clk,
+                Signal::new_single(value),
+                ComponentId(0),
+            );
+        }
+    }
+    sim.run().unwrap();
+
+    let model = PowerModel::new().with("Counter", 20e-12);
+    let report = recorder.report(&sim, &model);
+    assert_eq!(report.duration, 320);
+    assert_eq!(report.net(clk).unwrap().toggles, 32);
+    // A full wrap flips 30 output bits, and the carry rises and falls
+    assert_eq!(report.net(out).unwrap().toggles, 30);
+    assert_eq!(report.net(carry).unwrap().toggles, 2);
+
+    // 32 toggles of 20 pF at 5 V over 320 ns
+    let activity = report.component(counter).unwrap();
+    assert_eq!(activity.toggles, 32);
+    assert_eq!(activity.capacitance, 20e-12);
+    assert!((activity.energy - 8e-9).abs() < 1e-15);
+    assert!((report.dynamic_power - 0.025).abs() < 1e-9);
+    assert!(report
+        .to_string()
+        .ends_with("C1 Counter: 32 toggles, 25.000 mW\nEstimated dynamic power: 25.000 mW"));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["components"][0]["toggles"], 32);
+
+    // Restarting forgets the run
+    recorder.restart(&sim);
+    assert_eq!(recorder.report(&sim, &model).total_toggles, 0);
+}
+
+#[test]
+fn test_shared_wire_is_charged_once() {
+    let mut sim = Simulation::new();
+    // A 1-bit counter carries whenever it is at 1, so its output and carry
+    // can be wired together
+    let counter = sim.add_component(Box::new(Counter::new_with_width(
+        ComponentId(1),
+        BusWidth(1),
+    )));
+    let clk = sim.netlist_mut().create_node(BusWidth(1));
+    let q = sim.netlist_mut().create_node(BusWidth(1));
+    for (pin, node) in [("CLK", clk), ("OUT", q), ("CARRY", q)] {
+        sim.connect(counter, pin.to_string(), node).unwrap();
+    }
+    sim.reset();
+    let recorder = ActivityRecorder::attach(&mut sim);
+
+    for cycle in 0..8 {
+        for (offset, value) in [(0, Value::Low), (10, Value::High)] {
+            sim.schedule_signal_change(
+                Timestamp(cycle * 20 + offset),
+                clk,
+                Signal::new_single(value),
+                ComponentId(0),
+            );
+        }
+    }
+    sim.run().unwrap();
+
+    // Each swing of the wire is charged once, not once per pin
+    let report = recorder.report(&sim, &PowerModel::new());
+    let toggles = report.net(q).unwrap().toggles;
+    assert_eq!(toggles, 8);
+    assert_eq!(report.component(counter).unwrap().toggles, toggles);
+}
+
+/// A NOT gate with a load capacitance of its own, its input named by a
+/// tunnel so that nothing else drives it
+const NOT_WITH_CAPACITANCE: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+    "<project source=\"3.8.0\" version=\"1.0\">\n",
+    "  <lib desc=\"#Wiring\" name=\"0\"/>\n",
+    "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+    "  <main name=\"main\"/>\n",
+    "  <circuit name=\"main\">\n",
+    "    <comp lib=\"0\" loc=\"(270,100)\" name=\"Tunnel\">\n",
+    "      <a name=\"label\" val=\"A\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"1\" loc=\"(300,100)\" name=\"NOT Gate\">\n",
+    "      <a name=\"capacitance\" val=\"4e-12\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"0\" loc=\"(300,100)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"type\" val=\"output\"/>\n",
+    "      <a name=\"label\" val=\"Y\"/>\n",
+    "    </comp>\n",
+    "  </circuit>\n",
+    "</project>\n",
+);
+
+#[test]
+fn test_capacitance_attribute() {
+    let circuit_file = CircParser::parse_string(NOT_WITH_CAPACITANCE).unwrap();
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    sim.reset();
+    let recorder = ActivityRecorder::attach(&mut sim);
+    let a = sim.netlist().find_node_by_name("A").unwrap();
+    for (time, value) in [(0, Value::Low), (10, Value::High), (20, Value::Low)] {
+        sim.schedule_signal_change(
+            Timestamp(time),
+            a,
+            Signal::new_single(value),
+            ComponentId(0),
+        );
+    }
+    sim.run().unwrap();
+
+    // The attribute takes precedence over the model
+    let model = PowerModel::new().with("NOT", 20e-12);
+    let report = recorder.report(&sim, &model);
+    let gate = report
+        .components
+        .iter()
+        .find(|component| component.name == "NOT")
+        .unwrap();
+    assert_eq!(gate.toggles, 2);
+    assert_eq!(gate.capacitance, 4e-12);
+}
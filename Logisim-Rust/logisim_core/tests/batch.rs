@@ -0,0 +1,256 @@
+//! Bit-parallel batch evaluation tests
+//!
+//! These tests evaluate circuits for many input vectors at once and check
+//! the results against truth tables and against the event-driven engine.
+
+mod common;
+
+use common::{gate, node, simulation};
+use logisim_core::simulation::{SimulationEngine, SimulationError};
+use logisim_core::std::memory::DFlipFlop;
+use logisim_core::std::plexers::Multiplexer;
+use logisim_core::{
+    AndGate, BusWidth, ComponentId, ControlledBuffer, NodeId, NorGate, OrGate, Signal, Simulation,
+    Timestamp, Value, XorGate,
+};
+
+/// Drive every vector through the event engine one after the other
+fn event_results(
+    sim: &mut Simulation,
+    inputs: &[NodeId],
+    outputs: &[NodeId],
+    vectors: &[Vec<Signal>],
+) -> Vec<Vec<Signal>> {
+    sim.reset();
+    vectors
+        .iter()
+        .enumerate()
+        .map(|(index, vector)| {
+            for (&input, signal) in inputs.iter().zip(vector) {
+                sim.schedule_signal_change(
+                    Timestamp(100 * (index as u64 + 1)),
+                    input,
+                    signal.clone(),
+                    ComponentId(0),
+                );
+            }
+            sim.run().unwrap();
+            outputs
+                .iter()
+                .map(|&output| sim.get_node_signal(output).unwrap().clone())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_exhaustive_sixteen_inputs() {
+    let mut sim = simulation(SimulationEngine::EventDriven);
+    let inputs: Vec<NodeId> = (0..16).map(|_| node(&mut sim)).collect();
+    let (any, none) = (node(&mut sim), node(&mut sim));
+
+    // Pairwise XOR, then AND of neighbouring pairs, then OR / NOR of halves
+    let mut id = 1;
+    let mut next_id = || {
+        id += 1;
+        ComponentId(id)
+    };
+    let xors: Vec<NodeId> = (0..8).map(|_| node(&mut sim)).collect();
+    for (i, &xor) in xors.iter().enumerate() {
+        let pins = [("A", inputs[2 * i]), ("B", inputs[2 * i + 1]), ("Y", xor)];
+        gate(&mut sim, Box::new(XorGate::new(next_id())), &pins);
+    }
+    let ands: Vec<NodeId> = (0..4).map(|_| node(&mut sim)).collect();
+    for (i, &and) in ands.iter().enumerate() {
+        let pins = [("A", xors[2 * i]), ("B", xors[2 * i + 1]), ("Y", and)];
+        gate(&mut sim, Box::new(AndGate::new(next_id())), &pins);
+    }
+    let pins = [("A", ands[0]), ("B", ands[1]), ("Y", any)];
+    gate(&mut sim, Box::new(OrGate::new(next_id())), &pins);
+    let pins = [("A", ands[2]), ("B", ands[3]), ("Y", none)];
+    gate(&mut sim, Box::new(NorGate::new(next_id())), &pins);
+
+    let evaluator = sim.batch(&inputs, &[any, none]).unwrap();
+    assert_eq!(evaluator.input_bits(), 16);
+    let results = evaluator.evaluate_exhaustive().unwrap();
+    assert_eq!(results.len(), 1 << 16);
+
+    for (assignment, result) in results.iter().enumerate() {
+        let xor = |i: usize| (assignment >> (2 * i) & 1) != (assignment >> (2 * i + 1) & 1);
+        let and = |i: usize| xor(2 * i) && xor(2 * i + 1);
+        assert_eq!(
+            result,
+            &vec![
+                Signal::new_single(Value::from_bool(and(0) || and(1))),
+                Signal::new_single(Value::from_bool(!(and(2) || and(3)))),
+            ],
+            "assignment {:#06x}",
+            assignment
+        );
+    }
+}
+
+#[test]
+fn test_shared_bus_matches_event_engine() {
+    // Two buffers share a pulled-down bus that feeds an XOR with `c`
+    let mut sim = simulation(SimulationEngine::EventDriven);
+    let (a, en_a, b, en_b, c) = (
+        node(&mut sim),
+        node(&mut sim),
+        node(&mut sim),
+        node(&mut sim),
+        node(&mut sim),
+    );
+    let (bus, out) = (node(&mut sim), node(&mut sim));
+    sim.netlist_mut()
+        .set_node_pull(bus, Some(Value::Low))
+        .unwrap();
+    let buffer = Box::new(ControlledBuffer::new(ComponentId(1)));
+    gate(&mut sim, buffer, &[("A", a), ("EN", en_a), ("Y", bus)]);
+    let buffer = Box::new(ControlledBuffer::new(ComponentId(2)));
+    gate(&mut sim, buffer, &[("A", b), ("EN", en_b), ("Y", bus)]);
+    let xor = Box::new(XorGate::new(ComponentId(3)));
+    gate(&mut sim, xor, &[("A", bus), ("B", c), ("Y", out)]);
+
+    // Every combination of defined, unknown and floating inputs
+    let values = [Value::Low, Value::High, Value::Unknown, Value::HighZ];
+    let inputs = [a, en_a, b, en_b, c];
+    let vectors: Vec<Vec<Signal>> = (0..values.len().pow(5))
+        .map(|n| {
+            (0..5)
+                .map(|i| Signal::new_single(values[n / values.len().pow(i) % values.len()]))
+                .collect()
+        })
+        .collect();
+
+    let batch = sim.evaluate_batch(&inputs, &[bus, out], &vectors).unwrap();
+    assert_eq!(batch.len(), vectors.len());
+    assert_eq!(
+        batch,
+        event_results(&mut sim, &inputs, &[bus, out], &vectors)
+    );
+}
+
+#[test]
+fn test_multiplexer_selects_input() {
+    let mut sim = simulation(SimulationEngine::EventDriven);
+    let width = BusWidth(4);
+    let mut mux = Multiplexer::new(ComponentId(1));
+    mux.set_select_bits(2);
+    mux.set_data_width(width);
+    mux.set_enable(true);
+    mux.set_tristate(true);
+
+    let data: Vec<NodeId> = (0..4)
+        .map(|_| sim.netlist_mut().create_node(width))
+        .collect();
+    let select = sim.netlist_mut().create_node(BusWidth(2));
+    let enable = node(&mut sim);
+    let out = sim.netlist_mut().create_node(width);
+    let mut pins: Vec<(String, NodeId)> = data
+        .iter()
+        .enumerate()
+        .map(|(i, &node)| (format!("input_{}", i), node))
+        .collect();
+    pins.extend([
+        ("select".to_string(), select),
+        ("enable".to_string(), enable),
+        ("output".to_string(), out),
+    ]);
+    let pins: Vec<(&str, NodeId)> = pins
+        .iter()
+        .map(|(pin, node)| (pin.as_str(), *node))
+        .collect();
+    gate(&mut sim, Box::new(mux), &pins);
+
+    let mut inputs = data.clone();
+    inputs.extend([select, enable]);
+    let vector = |select: Signal, enable: Value| {
+        let mut vector: Vec<Signal> = (0..4).map(|i| Signal::from_u64(3 * i + 1, width)).collect();
+        vector.extend([select, Signal::new_single(enable)]);
+        vector
+    };
+    let mut vectors: Vec<Vec<Signal>> = (0..4)
+        .map(|index| vector(Signal::from_u64(index, BusWidth(2)), Value::High))
+        .collect();
+    vectors.push(vector(Signal::from_u64(2, BusWidth(2)), Value::Low));
+    vectors.push(vector(
+        Signal::new_bus(vec![Value::High, Value::Unknown]),
+        Value::High,
+    ));
+    vectors.push(vector(
+        Signal::new_bus(vec![Value::Error, Value::Low]),
+        Value::High,
+    ));
+
+    let results = sim.evaluate_batch(&inputs, &[out], &vectors).unwrap();
+    let outputs: Vec<Signal> = results
+        .into_iter()
+        .map(|mut result| result.remove(0))
+        .collect();
+    assert_eq!(
+        outputs,
+        vec![
+            Signal::from_u64(1, width),
+            Signal::from_u64(4, width),
+            Signal::from_u64(7, width),
+            Signal::from_u64(10, width),
+            Signal::high_z(width),
+            Signal::unknown(width),
+            Signal::error(width),
+        ]
+    );
+}
+
+#[test]
+fn test_unsupported_circuits_are_rejected() {
+    // A NOR latch loops back on itself
+    let mut sim = simulation(SimulationEngine::EventDriven);
+    let (set, reset, q, nq) = (
+        node(&mut sim),
+        node(&mut sim),
+        node(&mut sim),
+        node(&mut sim),
+    );
+    gate(
+        &mut sim,
+        Box::new(NorGate::new(ComponentId(1))),
+        &[("A", reset), ("B", nq), ("Y", q)],
+    );
+    gate(
+        &mut sim,
+        Box::new(NorGate::new(ComponentId(2))),
+        &[("A", set), ("B", q), ("Y", nq)],
+    );
+    assert!(matches!(
+        sim.batch(&[set, reset], &[q]),
+        Err(SimulationError::BatchError(_))
+    ));
+
+    // Flip-flops hold state between vectors
+    let mut sim = simulation(SimulationEngine::EventDriven);
+    let (clk, d, q) = (node(&mut sim), node(&mut sim), node(&mut sim));
+    let ff = Box::new(DFlipFlop::new(ComponentId(1)));
+    gate(&mut sim, ff, &[("CLK", clk), ("D", d), ("Q", q)]);
+    assert!(matches!(
+        sim.batch(&[clk, d], &[q]),
+        Err(SimulationError::BatchError(_))
+    ));
+
+    // Vectors must match the input widths
+    let mut sim = simulation(SimulationEngine::EventDriven);
+    let (a, b, y) = (node(&mut sim), node(&mut sim), node(&mut sim));
+    gate(
+        &mut sim,
+        Box::new(AndGate::new(ComponentId(1))),
+        &[("A", a), ("B", b), ("Y", y)],
+    );
+    let vectors = vec![vec![
+        Signal::new_single(Value::High),
+        Signal::unknown(BusWidth(2)),
+    ]];
+    assert!(matches!(
+        sim.evaluate_batch(&[a, b], &[y], &vectors),
+        Err(SimulationError::WidthMismatch { .. })
+    ));
+}
@@ -0,0 +1,127 @@
+//! Breakpoint tests
+//!
+//! These tests run a clocked counter standing in for a program counter and
+//! stop it with breakpoints on its named nets.
+
+use logisim_core::simulation::{SimulationConfig, SimulationError};
+use logisim_core::std::memory::Counter;
+use logisim_core::std::wiring::Clock;
+use logisim_core::{
+    BusWidth, ComponentId, Condition, NodeId, Signal, Simulation, Timestamp, Value,
+};
+
+struct Cpu {
+    sim: Simulation,
+    pc: NodeId,
+}
+
+/// A counter named `pc` driven by a clock named `clk` with a period of 40
+fn cpu() -> Cpu {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        max_time: None,
+        max_events: None,
+        ..SimulationConfig::default()
+    });
+    let clk = sim
+        .netlist_mut()
+        .create_named_node(BusWidth(1), "clk".to_string());
+    let pc = sim
+        .netlist_mut()
+        .create_named_node(BusWidth(8), "pc".to_string());
+
+    let mut clock = Clock::new(ComponentId(1));
+    clock.set_high_duration(20);
+    clock.set_low_duration(20);
+    let clock = sim.add_component(Box::new(clock));
+    sim.connect(clock, "out".to_string(), clk).unwrap();
+    let counter = sim.add_component(Box::new(Counter::new(ComponentId(2))));
+    sim.connect(counter, "CLK".to_string(), clk).unwrap();
+    sim.connect(counter, "OUT".to_string(), pc).unwrap();
+
+    sim.reset();
+    Cpu { sim, pc }
+}
+
+impl Cpu {
+    fn pc(&self) -> u64 {
+        self.sim.get_node_signal(self.pc).unwrap().to_u64().unwrap()
+    }
+}
+
+#[test]
+fn test_run_until_value() {
+    let mut cpu = cpu();
+    let id = cpu.sim.add_breakpoint(Condition::equals("pc", 5)).unwrap();
+
+    cpu.sim.run_until(Timestamp(1000)).unwrap();
+    assert_eq!(cpu.pc(), 5);
+    let hits = cpu.sim.breakpoint_hits().to_vec();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, id);
+    assert_eq!(hits[0].time, cpu.sim.current_time());
+
+    // The value holds for a while, but the breakpoint only fires on reaching it
+    cpu.sim.run_until(Timestamp(1000)).unwrap();
+    assert!(cpu.sim.breakpoint_hits().is_empty());
+    assert!(cpu.pc() > 5);
+}
+
+#[test]
+fn test_edges_and_expressions() {
+    let mut cpu = cpu();
+    let edge = cpu
+        .sim
+        .add_breakpoint(Condition::rising("clk").and(!Condition::equals("pc", 0)))
+        .unwrap();
+
+    // The first rising edge sees the counter still at zero
+    cpu.sim.run_until(Timestamp(1000)).unwrap();
+    assert_eq!(cpu.sim.breakpoint_hits()[0].id, edge);
+    assert_eq!(cpu.pc(), 1);
+    let first = cpu.sim.current_time();
+
+    // Every further edge fires again
+    cpu.sim.run_until(Timestamp(1000)).unwrap();
+    assert_eq!(cpu.sim.current_time(), first.add_delay(40));
+    assert_eq!(cpu.pc(), 2);
+
+    assert!(cpu.sim.remove_breakpoint(edge).is_some());
+    cpu.sim.run_until(Timestamp(1000)).unwrap();
+    assert!(cpu.sim.breakpoint_hits().is_empty());
+    assert!(cpu.pc() > 20);
+}
+
+#[test]
+fn test_error_anywhere() {
+    let mut cpu = cpu();
+    let bus = cpu
+        .sim
+        .netlist_mut()
+        .create_named_node(BusWidth(1), "bus".to_string());
+    cpu.sim.add_breakpoint(Condition::ErrorAppears).unwrap();
+
+    // Two drivers fight over the bus
+    for (source, value) in [(10, Value::High), (11, Value::Low)] {
+        cpu.sim.schedule_signal_change(
+            Timestamp(300),
+            bus,
+            Signal::new_single(value),
+            ComponentId(source),
+        );
+    }
+    cpu.sim.run_until(Timestamp(1000)).unwrap();
+    assert_eq!(cpu.sim.breakpoint_hits().len(), 1);
+    assert_eq!(cpu.sim.current_time(), Timestamp(300));
+    assert!(cpu.sim.get_node_signal(bus).unwrap().has_error());
+}
+
+#[test]
+fn test_unknown_node() {
+    let mut cpu = cpu();
+
+    assert!(matches!(
+        cpu.sim.add_breakpoint(Condition::changes("acc")),
+        Err(SimulationError::NamedNodeNotFound(name)) if name == "acc"
+    ));
+    assert!(cpu.sim.breakpoints().is_empty());
+}
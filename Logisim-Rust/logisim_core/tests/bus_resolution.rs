@@ -0,0 +1,107 @@
+//! Multi-driver bus resolution tests
+//!
+//! These tests drive a shared bus from several controlled buffers and check
+//! that the simulation resolves the contributions bit by bit.
+
+use logisim_core::{
+    BusWidth, ComponentId, ControlledBuffer, NodeId, Signal, Simulation, Timestamp, Value,
+};
+
+struct SharedBus {
+    sim: Simulation,
+    data: [NodeId; 2],
+    enable: [NodeId; 2],
+    bus: NodeId,
+}
+
+fn shared_bus() -> SharedBus {
+    let mut sim = Simulation::new();
+    let width = BusWidth(8);
+    let bus = sim
+        .netlist_mut()
+        .create_named_node(width, "DATA".to_string());
+    let mut data = [NodeId(0); 2];
+    let mut enable = [NodeId(0); 2];
+
+    for i in 0..2 {
+        let id = sim.add_component(Box::new(ControlledBuffer::new_with_width(
+            ComponentId(i as u64 + 1),
+            width,
+        )));
+        data[i] = sim.netlist_mut().create_node(width);
+        enable[i] = sim.netlist_mut().create_node(BusWidth(1));
+        sim.connect(id, "A".to_string(), data[i]).unwrap();
+        sim.connect(id, "EN".to_string(), enable[i]).unwrap();
+        sim.connect(id, "Y".to_string(), bus).unwrap();
+    }
+
+    sim.reset();
+    SharedBus {
+        sim,
+        data,
+        enable,
+        bus,
+    }
+}
+
+impl SharedBus {
+    fn drive(&mut self, time: u64, values: [u64; 2], enables: [Value; 2]) -> Signal {
+        for i in 0..2 {
+            self.sim.schedule_signal_change(
+                Timestamp(time),
+                self.data[i],
+                Signal::from_u64(values[i], BusWidth(8)),
+                ComponentId(0),
+            );
+            self.sim.schedule_signal_change(
+                Timestamp(time),
+                self.enable[i],
+                Signal::new_single(enables[i]),
+                ComponentId(0),
+            );
+        }
+        self.sim.run().unwrap();
+        self.sim.get_node_signal(self.bus).unwrap()
+    }
+}
+
+#[test]
+fn test_single_enabled_driver_owns_bus() {
+    let mut bus = shared_bus();
+
+    let signal = bus.drive(10, [0x12, 0x34], [Value::High, Value::Low]);
+    assert_eq!(signal.to_u64(), Some(0x12));
+
+    let signal = bus.drive(20, [0x12, 0x34], [Value::Low, Value::High]);
+    assert_eq!(signal.to_u64(), Some(0x34));
+}
+
+#[test]
+fn test_contention_produces_errors() {
+    let mut bus = shared_bus();
+
+    // 0x0f and 0x3c agree on bits 2-3 and 6-7 only
+    let signal = bus.drive(10, [0x0f, 0x3c], [Value::High, Value::High]);
+    assert_eq!(signal.to_string(), "00EE11EE");
+
+    // Releasing one driver clears the conflict
+    let signal = bus.drive(20, [0x0f, 0x3c], [Value::Low, Value::High]);
+    assert_eq!(signal.to_u64(), Some(0x3c));
+}
+
+#[test]
+fn test_released_bus_floats_or_is_pulled() {
+    let mut bus = shared_bus();
+
+    let signal = bus.drive(10, [0x12, 0x34], [Value::Low, Value::Low]);
+    assert!(signal.is_high_z());
+
+    bus.sim
+        .netlist_mut()
+        .set_node_pull(bus.bus, Some(Value::High))
+        .unwrap();
+    assert_eq!(
+        bus.sim.get_node_signal(bus.bus).unwrap().to_u64(),
+        Some(0xff)
+    );
+}
@@ -0,0 +1,299 @@
+//! Export tests for .circ files
+//!
+//! These tests write parsed files and loaded simulations back to .circ and
+//! check that nothing the parser reads is lost on the way, and that changes
+//! made to a simulation end up in the exported file.
+
+use logisim_core::circ_format::{CircIntegration, CircParser, CircWriter, CircuitFile};
+use logisim_core::std::arith::Adder;
+use logisim_core::std::gates::{AndGate, Pla};
+use logisim_core::std::memory::Ram;
+use logisim_core::{BusWidth, Component, ComponentId, Simulation};
+
+const HEADER: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+    "<project source=\"3.8.0\" version=\"1.0\">\n",
+    "  This file is intended to be loaded by Logisim-evolution v3.8.0(https://github.com/logisim-evolution/).\n",
+    "\n",
+    "  <lib desc=\"#Wiring\" name=\"0\">\n",
+    "    <tool name=\"Pin\">\n",
+    "      <a name=\"appearance\" val=\"classic\"/>\n",
+    "    </tool>\n",
+    "  </lib>\n",
+    "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+    "  <lib desc=\"#Memory\" name=\"4\"/>\n",
+    "  <lib desc=\"#Base\" name=\"8\"/>\n",
+    "  <main name=\"top\"/>\n",
+    "  <options>\n",
+    "    <a name=\"gateUndefined\" val=\"error\"/>\n",
+    "    <a name=\"simlimit\" val=\"500\"/>\n",
+    "    <a name=\"simrand\" val=\"0\"/>\n",
+    "  </options>\n",
+    "  <mappings>\n",
+    "    <tool lib=\"8\" map=\"Button2\" name=\"Menu Tool\"/>\n",
+    "    <tool lib=\"8\" map=\"Ctrl Button1\" name=\"Poke Tool\"/>\n",
+    "  </mappings>\n",
+    "  <toolbar>\n",
+    "    <tool lib=\"8\" name=\"Poke Tool\"/>\n",
+    "    <sep/>\n",
+    "    <tool lib=\"1\" name=\"AND Gate\">\n",
+    "      <a name=\"facing\" val=\"south\"/>\n",
+    "    </tool>\n",
+    "  </toolbar>\n",
+);
+
+/// Two inverter instances driven by constants, plus a gate with a delay
+const TOP: &str = concat!(
+    "  <circuit name=\"top\">\n",
+    "    <a name=\"circuit\" val=\"top\"/>\n",
+    "    <comp lib=\"0\" loc=\"(270,100)\" name=\"Constant\"/>\n",
+    "    <comp loc=\"(300,100)\" name=\"INV\"/>\n",
+    "    <comp lib=\"0\" loc=\"(300,100)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"type\" val=\"output\"/>\n",
+    "      <a name=\"label\" val=\"Y\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"0\" loc=\"(270,200)\" name=\"Constant\">\n",
+    "      <a name=\"value\" val=\"0x0\"/>\n",
+    "    </comp>\n",
+    "    <comp loc=\"(300,200)\" name=\"INV\"/>\n",
+    "    <comp lib=\"0\" loc=\"(300,200)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"type\" val=\"output\"/>\n",
+    "      <a name=\"label\" val=\"Z\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"1\" loc=\"(500,100)\" name=\"AND Gate\">\n",
+    "      <a name=\"delay\" val=\"3\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"8\" loc=\"(500,300)\" name=\"Text\">\n",
+    "      <a name=\"text\" val=\"a &lt; b &amp; &quot;c&quot;\"/>\n",
+    "    </comp>\n",
+    "  </circuit>\n",
+);
+
+/// An inverter with a custom appearance
+const INV: &str = concat!(
+    "  <circuit name=\"INV\">\n",
+    "    <a name=\"circuit\" val=\"INV\"/>\n",
+    "    <appear>\n",
+    "      <rect height=\"20\" width=\"30\" x=\"50\" y=\"50\"/>\n",
+    "      <text font-family=\"SansSerif\" x=\"65\" y=\"64\">NOT</text>\n",
+    "      <circ-port height=\"8\" pin=\"100,100\" width=\"8\" x=\"46\" y=\"56\"/>\n",
+    "      <circ-port height=\"10\" pin=\"180,100\" width=\"10\" x=\"75\" y=\"55\"/>\n",
+    "      <circ-anchor facing=\"east\" height=\"6\" width=\"6\" x=\"77\" y=\"57\"/>\n",
+    "    </appear>\n",
+    "    <wire from=\"(100,100)\" to=\"(150,100)\"/>\n",
+    "    <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\">\n",
+    "      <a name=\"label\" val=\"A\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"1\" loc=\"(180,100)\" name=\"NOT Gate\"/>\n",
+    "    <comp lib=\"0\" loc=\"(180,100)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"type\" val=\"output\"/>\n",
+    "      <a name=\"label\" val=\"Y\"/>\n",
+    "    </comp>\n",
+    "  </circuit>\n",
+);
+
+/// A circuit with a RAM that keeps its contents over a reset
+const RAM: &str = concat!(
+    "  <circuit name=\"top\">\n",
+    "    <comp lib=\"4\" loc=\"(200,100)\" name=\"RAM\">\n",
+    "      <a name=\"addrWidth\" val=\"4\"/>\n",
+    "      <a name=\"type\" val=\"nonvolatile\"/>\n",
+    "    </comp>\n",
+    "  </circuit>\n",
+);
+
+/// A circuit with a ROM, whose contents span several lines
+const ROM: &str = concat!(
+    "  <circuit name=\"rom\">\n",
+    "    <comp lib=\"4\" loc=\"(200,100)\" name=\"ROM\">\n",
+    "      <a name=\"addrWidth\" val=\"4\"/>\n",
+    "      <a name=\"contents\">addr/data: 4 8\n",
+    "12 34 4*ab\n",
+    "</a>\n",
+    "    </comp>\n",
+    "  </circuit>\n",
+);
+
+fn project(circuits: &[&str]) -> CircuitFile {
+    let xml = format!("{}{}</project>\n", HEADER, circuits.concat());
+    CircParser::parse_string(&xml).expect("Failed to parse XML")
+}
+
+fn output(sim: &Simulation, name: &str) -> Option<u64> {
+    let node = sim.netlist().find_node_by_name(name).unwrap();
+    sim.get_node_signal(node).unwrap().to_u64()
+}
+
+/// The simulated instances of a component of `circuit`, by its index
+fn instances(sim: &Simulation, circuit: &str, index: usize) -> Vec<ComponentId> {
+    let source = sim.circuit_source().unwrap();
+    let mut ids: Vec<ComponentId> = source
+        .components
+        .iter()
+        .filter(|(_, (c, i))| c == circuit && *i == index)
+        .map(|(&id, _)| id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn test_writer_is_lossless() {
+    let circuit_file = project(&[TOP, INV, ROM]);
+    let xml = CircWriter::serialize_to_string(&circuit_file).unwrap();
+    let reparsed = CircParser::parse_string(&xml).unwrap();
+    assert_eq!(reparsed, circuit_file);
+    assert_eq!(CircWriter::serialize_to_string(&reparsed).unwrap(), xml);
+
+    // Circuits keep their file order rather than being sorted
+    let names: Vec<&str> = reparsed.circuits.keys().map(String::as_str).collect();
+    assert_eq!(names, vec!["top", "INV", "rom"]);
+
+    let text = &reparsed.circuits["top"].components[7];
+    assert_eq!(text.attributes["text"], "a < b & \"c\"");
+    let label = &reparsed.circuits["INV"]
+        .appearance
+        .as_ref()
+        .unwrap()
+        .elements[1];
+    assert_eq!(label.text.as_deref(), Some("NOT"));
+    let rom = &reparsed.circuits["rom"].components[0];
+    assert_eq!(rom.attributes["contents"], "addr/data: 4 8\n12 34 4*ab\n");
+
+    let options = &reparsed.options;
+    assert_eq!(options.canvas.gate_undefined, "error");
+    assert_eq!(options.simulation.sim_limit, 500);
+    assert_eq!(options.mappings[1].map, "Ctrl Button1");
+    assert!(options.toolbar_items[1].is_none());
+    assert_eq!(
+        options.toolbar_items[2].as_ref().unwrap().attributes["facing"],
+        "south"
+    );
+}
+
+#[test]
+fn test_simulation_export_applies_changes() {
+    let circuit_file = project(&[TOP, INV]);
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+
+    // Unchanged, the file comes back as it was loaded
+    let exported = CircIntegration::simulation_to_circuit_file(&sim).unwrap();
+    assert_eq!(exported, circuit_file);
+
+    // The NOT gate of the first inverter instance only
+    let not_gates = instances(&sim, "INV", 1);
+    assert_eq!(not_gates.len(), 2);
+    sim.remove_component(not_gates[0]);
+    let and_gate = instances(&sim, "top", 6)[0];
+    sim.set_component_delay(and_gate, 7).unwrap();
+
+    // Instance delays are not saved, so the file keeps the one it had
+    let exported = CircIntegration::simulation_to_circuit_file(&sim).unwrap();
+    assert_eq!(exported.circuits["top"], circuit_file.circuits["top"]);
+    // The other instance still simulates the gate, so the file keeps it
+    assert_eq!(exported.circuits["INV"], circuit_file.circuits["INV"]);
+
+    // Written out, the file loads and simulates like the original
+    let xml = CircWriter::serialize_to_string(&exported).unwrap();
+    let reloaded = CircParser::parse_string(&xml).unwrap();
+    let mut sim_reloaded = CircIntegration::circuit_file_to_simulation(&reloaded).unwrap();
+    sim_reloaded.reset();
+    sim_reloaded.run().unwrap();
+    assert_eq!(output(&sim_reloaded, "Y"), Some(0));
+    assert_eq!(output(&sim_reloaded, "Z"), Some(1));
+    let and_gate = instances(&sim_reloaded, "top", 6)[0];
+    assert_eq!(sim_reloaded.instance_delay(and_gate), Some(3));
+
+    // Once no instance is left, the gate goes
+    sim.remove_component(not_gates[1]);
+    let exported = CircIntegration::simulation_to_circuit_file(&sim).unwrap();
+    let inv = &exported.circuits["INV"];
+    assert_eq!(inv.components.len(), 2);
+    assert!(inv.components.iter().all(|c| c.name == "Pin"));
+    assert_eq!(inv.wires.len(), 1);
+}
+
+#[test]
+fn test_simulation_export_saves_memory_contents() {
+    let circuit_file = project(&[RAM]);
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    let ram = instances(&sim, "top", 0)[0];
+    let exported = CircIntegration::simulation_to_circuit_file(&sim).unwrap();
+    assert_eq!(exported, circuit_file);
+
+    // Fill the simulated RAM with the contents of another one
+    let mut written = Ram::new_with_widths(ComponentId(1), BusWidth(4), BusWidth(8));
+    written.contents_mut().set(2, 0x5a);
+    let state = written.save_state().unwrap();
+    sim.get_component_mut(ram)
+        .unwrap()
+        .restore_state(&state)
+        .unwrap();
+
+    // Only the contents are added, after the attributes the file had
+    let exported = CircIntegration::simulation_to_circuit_file(&sim).unwrap();
+    let attributes = &exported.circuits["top"].components[0].attributes;
+    let names: Vec<&str> = attributes.keys().map(String::as_str).collect();
+    assert_eq!(names, vec!["addrWidth", "type", "contents"]);
+    assert_eq!(attributes["contents"], "addr/data: 4 8\n0 0 5a\n");
+
+    // Written out, the file loads into a RAM holding the same contents
+    let xml = CircWriter::serialize_to_string(&exported).unwrap();
+    let reloaded = CircParser::parse_string(&xml).unwrap();
+    let mut sim_reloaded = CircIntegration::circuit_file_to_simulation(&reloaded).unwrap();
+    sim_reloaded.reset();
+    let ram = instances(&sim_reloaded, "top", 0)[0];
+    let tool = sim_reloaded
+        .get_component(ram)
+        .unwrap()
+        .circ_tool()
+        .unwrap();
+    assert_eq!(tool.attribute("contents"), Some("addr/data: 4 8\n0 0 5a\n"));
+}
+
+#[test]
+fn test_simulation_export_places_added_components() {
+    let err = CircIntegration::simulation_to_circuit_file(&Simulation::new()).unwrap_err();
+    assert!(err.to_string().contains("not loaded from a .circ file"));
+
+    let circuit_file = project(&[TOP, INV]);
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    sim.add_component(Box::new(AndGate::new_with_inputs(ComponentId(100), 3)));
+    sim.add_component(Box::new(Adder::new(ComponentId(101))));
+    let exported = CircIntegration::simulation_to_circuit_file(&sim).unwrap();
+
+    // Added below the drawing, whose lowest point is the text at y=300
+    let top = &exported.circuits["top"];
+    assert_eq!(top.components.len(), 10);
+    let and_gate = &top.components[8];
+    assert_eq!(and_gate.library.as_deref(), Some("1"));
+    assert_eq!(and_gate.name, "AND Gate");
+    assert_eq!(and_gate.attributes["inputs"], "3");
+    let adder = &top.components[9];
+    assert!(and_gate.location.1 > 300 && adder.location.1 > 300);
+    assert!(adder.location.0 > and_gate.location.0);
+    // The arithmetic library was not declared yet
+    let library = exported.libraries.last().unwrap();
+    assert_eq!(library.description, "#Arithmetic");
+    assert_eq!(adder.library.as_ref(), Some(&library.name));
+    assert_eq!(adder.attributes["width"], "8");
+
+    // The added components stay unconnected in the reloaded file
+    let xml = CircWriter::serialize_to_string(&exported).unwrap();
+    let reloaded = CircParser::parse_string(&xml).unwrap();
+    let mut sim_reloaded = CircIntegration::circuit_file_to_simulation(&reloaded).unwrap();
+    assert_eq!(instances(&sim_reloaded, "top", 9).len(), 1);
+    sim_reloaded.reset();
+    sim_reloaded.run().unwrap();
+    assert_eq!(output(&sim_reloaded, "Y"), Some(0));
+
+    // Components without a .circ counterpart cannot be saved
+    sim.add_component(Box::new(Pla::new(ComponentId(102))));
+    let err = CircIntegration::simulation_to_circuit_file(&sim).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Components C102 have no .circ counterpart"));
+}
@@ -0,0 +1,179 @@
+//! External library tests for .circ files
+//!
+//! These tests split a design over several .circ files joined by `file#`
+//! libraries, and check that library circuits are simulated as subcircuits,
+//! that missing and circular libraries are reported, and that the
+//! dependencies are written back when the file is saved.
+
+use std::fs;
+use std::path::Path;
+
+use logisim_core::circ_format::{CircFormatError, CircIntegration, CircParser, CircWriter};
+use logisim_core::file::Loader;
+use logisim_core::Simulation;
+use tempfile::TempDir;
+
+/// An inverter with a custom appearance, declaring its libraries in an
+/// order of its own so that its `lib` indices differ from the main file's
+const INVERTER_LIBRARY: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+    "<project source=\"3.8.0\" version=\"1.0\">\n",
+    "  <lib desc=\"#Gates\" name=\"0\"/>\n",
+    "  <lib desc=\"#Wiring\" name=\"1\"/>\n",
+    "  <circuit name=\"INV\">\n",
+    "    <appear>\n",
+    "      <circ-port height=\"8\" pin=\"100,100\" width=\"8\" x=\"46\" y=\"56\"/>\n",
+    "      <circ-port height=\"10\" pin=\"180,100\" width=\"10\" x=\"75\" y=\"55\"/>\n",
+    "      <circ-anchor facing=\"east\" height=\"6\" width=\"6\" x=\"77\" y=\"57\"/>\n",
+    "    </appear>\n",
+    "    <wire from=\"(100,100)\" to=\"(150,100)\"/>\n",
+    "    <comp lib=\"1\" loc=\"(100,100)\" name=\"Pin\"/>\n",
+    "    <comp lib=\"0\" loc=\"(180,100)\" name=\"NOT Gate\"/>\n",
+    "    <comp lib=\"1\" loc=\"(180,100)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"type\" val=\"output\"/>\n",
+    "    </comp>\n",
+    "  </circuit>\n",
+    "</project>\n",
+);
+
+/// A project using `INV` from `library`, declared as library 2
+fn project(library: &str) -> String {
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+            "<project source=\"3.8.0\" version=\"1.0\">\n",
+            "  <lib desc=\"#Wiring\" name=\"0\"/>\n",
+            "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+            "  <lib desc=\"file#{}\" name=\"2\"/>\n",
+            "  <main name=\"top\"/>\n",
+            "  <circuit name=\"top\">\n",
+            "    <comp lib=\"0\" loc=\"(270,100)\" name=\"Constant\"/>\n",
+            "    <comp lib=\"2\" loc=\"(300,100)\" name=\"INV\"/>\n",
+            "    <comp lib=\"0\" loc=\"(300,100)\" name=\"Pin\">\n",
+            "      <a name=\"facing\" val=\"west\"/>\n",
+            "      <a name=\"output\" val=\"true\"/>\n",
+            "      <a name=\"label\" val=\"Y\"/>\n",
+            "    </comp>\n",
+            "  </circuit>\n",
+            "</project>\n",
+        ),
+        library
+    )
+}
+
+fn write(dir: &Path, name: &str, contents: &str) {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn output(sim: &mut Simulation, name: &str) -> Option<u64> {
+    sim.reset();
+    sim.run().unwrap();
+    let node = sim.netlist().find_node_by_name(name).unwrap();
+    sim.get_node_signal(node).unwrap().to_u64()
+}
+
+#[test]
+fn test_library_circuits_are_subcircuits() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "lib/inverter.circ", INVERTER_LIBRARY);
+    write(dir.path(), "cpu.circ", &project("lib/inverter.circ"));
+
+    let path = dir.path().join("cpu.circ");
+    let circuit_file = CircParser::load_file(&path).unwrap();
+    assert!(circuit_file.external_circuits["2"]
+        .circuits
+        .contains_key("INV"));
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    assert_eq!(output(&mut sim, "Y"), Some(0));
+
+    // The library's gate is placed under its file's name
+    let source = sim.circuit_source().unwrap();
+    assert!(source
+        .components
+        .values()
+        .any(|(circuit, index)| circuit == "lib/inverter.circ:INV" && *index == 1));
+
+    // Saving keeps the dependency, but not the library's circuits
+    let exported = CircIntegration::simulation_to_circuit_file(&sim).unwrap();
+    let xml = CircWriter::serialize_to_string(&exported).unwrap();
+    assert!(xml.contains("<lib desc=\"file#lib/inverter.circ\" name=\"2\"/>"));
+    assert!(!xml.contains("<circuit name=\"INV\">"));
+
+    // The file loader records the libraries it loaded
+    let mut loader = Loader::new();
+    loader.open_logisim_file(&path).unwrap();
+    assert!(loader.is_library_loaded("lib/inverter.circ"));
+}
+
+#[test]
+fn test_added_library_is_saved() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "inverter.circ", INVERTER_LIBRARY);
+    let library = CircParser::load_file(dir.path().join("inverter.circ")).unwrap();
+
+    // A project whose inverter library has not been declared yet
+    let mut circuit_file = CircParser::parse_string(&project("inverter.circ")).unwrap();
+    circuit_file
+        .libraries
+        .retain(|lib| lib.external_file.is_none());
+    assert_eq!(
+        circuit_file.add_external_library("inverter.circ", library.clone()),
+        "2"
+    );
+    // Adding it again replaces its circuits under the same index
+    assert_eq!(
+        circuit_file.add_external_library("inverter.circ", library),
+        "2"
+    );
+    assert_eq!(circuit_file.libraries.len(), 3);
+
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    assert_eq!(output(&mut sim, "Y"), Some(0));
+
+    let path = dir.path().join("top.circ");
+    CircWriter::save_file(&circuit_file, &path).unwrap();
+    let reloaded = CircParser::load_file(&path).unwrap();
+    assert_eq!(reloaded.libraries, circuit_file.libraries);
+    assert_eq!(reloaded.external_circuits, circuit_file.external_circuits);
+}
+
+#[test]
+fn test_missing_library() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "cpu.circ", &project("alu.circ"));
+
+    let path = dir.path().join("cpu.circ");
+    let err = CircParser::load_file(&path).unwrap_err();
+    let CircFormatError::LibraryNotFound(message) = &err else {
+        panic!("Expected a missing library, got {}", err);
+    };
+    assert!(message.starts_with("file#alu.circ used by "));
+    assert!(message.contains(&dir.path().join("alu.circ").display().to_string()));
+
+    // Without a path to resolve it, the library is reported as unsupported
+    let circuit_file = CircParser::parse_string(&project("alu.circ")).unwrap();
+    let Err(err) = CircIntegration::circuit_file_to_simulation(&circuit_file) else {
+        panic!("Expected the library's circuit to be unsupported");
+    };
+    assert!(err
+        .to_string()
+        .contains("file#alu.circ INV at (300,100) in circuit 'top'"));
+}
+
+#[test]
+fn test_circular_libraries() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "a.circ", &project("b.circ"));
+    write(dir.path(), "b.circ", &project("c.circ"));
+    write(dir.path(), "c.circ", &project("b.circ"));
+
+    let err = CircParser::load_file(dir.path().join("a.circ")).unwrap_err();
+    assert!(matches!(err, CircFormatError::CircularReference(_)));
+    assert_eq!(
+        err.to_string(),
+        "Circular circuit reference: b.circ -> c.circ -> b.circ"
+    );
+}
@@ -0,0 +1,261 @@
+//! Migration tests for .circ files of older Logisim releases
+//!
+//! These tests load files as Logisim 2.7.1 wrote them and check that they
+//! are rewritten to the current names, that every change is listed, and that
+//! the result simulates and saves like a current file.
+
+use logisim_core::circ_format::{CircIntegration, CircParser, CircWriter};
+
+/// A Logisim 2.7.1 file using the Legacy library, a register of the classic
+/// appearance and an output pin marked by `output="true"`
+const LOGISIM_2_7: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+    "<project source=\"2.7.1\" version=\"1.0\">\n",
+    "  <lib desc=\"#Wiring\" name=\"0\"/>\n",
+    "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+    "  <lib desc=\"#Memory\" name=\"4\"/>\n",
+    "  <lib desc=\"#Base\" name=\"5\"/>\n",
+    "  <lib desc=\"#Legacy\" name=\"6\">\n",
+    "    <tool name=\"Logisim 1.0 D Flip-Flop\"/>\n",
+    "  </lib>\n",
+    "  <main name=\"main\"/>\n",
+    "  <toolbar>\n",
+    "    <tool lib=\"5\" name=\"Poke Tool\"/>\n",
+    "    <tool lib=\"6\" name=\"Logisim 1.0 D Flip-Flop\"/>\n",
+    "  </toolbar>\n",
+    "  <circuit name=\"main\">\n",
+    "    <a name=\"circuit\" val=\"main\"/>\n",
+    "    <comp lib=\"0\" loc=\"(270,100)\" name=\"Constant\"/>\n",
+    "    <comp lib=\"1\" loc=\"(300,100)\" name=\"NOT Gate\"/>\n",
+    "    <comp lib=\"0\" loc=\"(300,100)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"output\" val=\"true\"/>\n",
+    "      <a name=\"label\" val=\"Y\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"4\" loc=\"(500,200)\" name=\"Register\">\n",
+    "      <a name=\"width\" val=\"4\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"6\" loc=\"(500,400)\" name=\"Logisim 1.0 D Flip-Flop\"/>\n",
+    "  </circuit>\n",
+    "</project>\n",
+);
+
+#[test]
+fn test_logisim_2_7_file_is_migrated() {
+    let circuit_file = CircParser::parse_string(LOGISIM_2_7).unwrap();
+    let changes: Vec<String> = circuit_file
+        .migrations
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            "Logisim 1.0 D Flip-Flop renamed to D Flip-Flop (tool of lib 6)",
+            "Logisim 1.0 D Flip-Flop renamed to D Flip-Flop at (500,400) in circuit 'main'",
+            "Logisim 1.0 D Flip-Flop renamed to D Flip-Flop (toolbar or mapping tool)",
+            "Library #Legacy (lib 6) merged into #Memory (lib 4)",
+            "D Flip-Flop: appearance=\"classic\" added (tool of lib 4)",
+            "D Flip-Flop: appearance=\"classic\" added at (500,400) in circuit 'main'",
+            "D Flip-Flop: appearance=\"classic\" added (toolbar or mapping tool)",
+            "Register: appearance=\"classic\" added at (500,200) in circuit 'main'",
+            "Pin: output=\"true\" replaced by type=\"output\" at (300,100) in circuit 'main'",
+        ]
+    );
+    assert_eq!(circuit_file.source_version, "3.8.0");
+
+    let main = &circuit_file.circuits["main"];
+    let flip_flop = &main.components[4];
+    assert_eq!(flip_flop.library.as_deref(), Some("4"));
+    assert_eq!(flip_flop.name, "D Flip-Flop");
+    // Attributes keep their place
+    let pin: Vec<&str> = main.components[2]
+        .attributes
+        .keys()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(pin, vec!["facing", "type", "label"]);
+    let toolbar = &circuit_file.options.toolbar_items[1];
+    assert_eq!(toolbar.as_ref().unwrap().library.as_deref(), Some("4"));
+    assert!(circuit_file
+        .libraries
+        .iter()
+        .all(|lib| lib.description != "#Legacy"));
+    let memory = circuit_file.libraries.iter().find(|lib| lib.name == "4");
+    assert_eq!(memory.unwrap().tools[0].name, "D Flip-Flop");
+
+    // The migrated file simulates, with the register as wide as it was
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    sim.reset();
+    sim.run().unwrap();
+    let y = sim.netlist().find_node_by_name("Y").unwrap();
+    assert_eq!(sim.get_node_signal(y).unwrap().to_u64(), Some(0));
+    let source = sim.circuit_source().unwrap();
+    let (&register, _) = source
+        .components
+        .iter()
+        .find(|(_, (circuit, index))| circuit == "main" && *index == 3)
+        .unwrap();
+    let q = sim.get_component(register).unwrap().get_pin("Q").unwrap();
+    assert_eq!(q.width.0, 4);
+
+    // Saved, it is a current file with nothing left to migrate
+    let xml = CircWriter::serialize_to_string(&circuit_file).unwrap();
+    assert!(xml.contains("<project source=\"3.8.0\" version=\"1.0\">"));
+    let reloaded = CircParser::parse_string(&xml).unwrap();
+    assert!(reloaded.migrations.is_empty());
+    assert_eq!(reloaded.circuits, circuit_file.circuits);
+}
+
+#[test]
+fn test_legacy_library_without_memory_is_renamed() {
+    let xml = LOGISIM_2_7.replace("  <lib desc=\"#Memory\" name=\"4\"/>\n", "");
+    let xml = xml.replace(
+        "    <comp lib=\"4\" loc=\"(500,200)\" name=\"Register\">\n      <a name=\"width\" val=\"4\"/>\n    </comp>\n",
+        "",
+    );
+    let circuit_file = CircParser::parse_string(&xml).unwrap();
+    let legacy = circuit_file
+        .libraries
+        .iter()
+        .find(|lib| lib.name == "6")
+        .unwrap();
+    assert_eq!(legacy.description, "#Memory");
+    assert_eq!(legacy.tools[0].name, "D Flip-Flop");
+    assert!(circuit_file
+        .migrations
+        .iter()
+        .any(|change| change.description == "Library #Legacy (lib 6) renamed to #Memory"));
+
+    // Files of current releases keep the old names, but for the pin
+    // attributes Logisim-Evolution converts up to 4.0.0
+    let current = xml.replace("source=\"2.7.1\"", "source=\"3.8.0\"");
+    let circuit_file = CircParser::parse_string(&current).unwrap();
+    let changes: Vec<String> = circuit_file
+        .migrations
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        vec!["Pin: output=\"true\" replaced by type=\"output\" at (300,100) in circuit 'main'"]
+    );
+    assert!(circuit_file
+        .libraries
+        .iter()
+        .any(|lib| lib.description == "#Legacy"));
+}
+
+/// A Logisim 2.6 file, with the wiring components still in the Base
+/// library and the circuit appearance attributes named `label...`
+const LOGISIM_2_6: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+    "<project source=\"2.6.0\" version=\"1.0\">\n",
+    "  <lib desc=\"#Base\" name=\"0\">\n",
+    "    <tool name=\"Text Tool\"/>\n",
+    "    <tool name=\"Pin\">\n",
+    "      <a name=\"tristate\" val=\"false\"/>\n",
+    "    </tool>\n",
+    "  </lib>\n",
+    "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+    "  <lib desc=\"#Plexers\" name=\"2\"/>\n",
+    "  <main name=\"main\"/>\n",
+    "  <toolbar>\n",
+    "    <tool lib=\"0\" name=\"Poke Tool\"/>\n",
+    "    <tool lib=\"0\" name=\"Pin\"/>\n",
+    "  </toolbar>\n",
+    "  <circuit name=\"main\">\n",
+    "    <a name=\"circuit\" val=\"main\"/>\n",
+    "    <a name=\"labelup\" val=\"north\"/>\n",
+    "    <wire from=\"(270,100)\" to=\"(300,100)\"/>\n",
+    "    <comp lib=\"1\" loc=\"(270,100)\" name=\"Constant\"/>\n",
+    "    <comp lib=\"0\" loc=\"(300,100)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"output\" val=\"true\"/>\n",
+    "      <a name=\"tristate\" val=\"false\"/>\n",
+    "      <a name=\"pull\" val=\"down\"/>\n",
+    "      <a name=\"label\" val=\"Y\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"2\" loc=\"(500,200)\" name=\"Decoder\"/>\n",
+    "  </circuit>\n",
+    "</project>\n",
+);
+
+#[test]
+fn test_logisim_2_6_base_library_is_split() {
+    let circuit_file = CircParser::parse_string(LOGISIM_2_6).unwrap();
+    let changes: Vec<String> = circuit_file
+        .migrations
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            "Circuit attribute labelup renamed to clabelup in circuit 'main'",
+            "Library #Base (lib 0) renamed to #Wiring",
+            "Library #Base (lib 3) added",
+            "Text Tool moved from lib 0 to lib 3 (tool of lib 0)",
+            "Poke Tool moved from lib 0 to lib 3 (toolbar or mapping tool)",
+            "Constant moved from lib 1 to lib 0 at (270,100) in circuit 'main'",
+            "Decoder: enable=\"false\" added at (500,200) in circuit 'main'",
+            "Pin: output=\"true\" replaced by type=\"output\" at (300,100) in circuit 'main'",
+            "Pin: tristate=\"false\" removed (tool of lib 0)",
+            "Pin: tristate=\"false\" pull=\"down\" replaced by behavior=\"pulldown\" at (300,100) in circuit 'main'",
+        ]
+    );
+
+    let libraries: Vec<(&str, &str)> = circuit_file
+        .libraries
+        .iter()
+        .map(|lib| (lib.name.as_str(), lib.description.as_str()))
+        .collect();
+    assert_eq!(
+        libraries,
+        vec![
+            ("0", "#Wiring"),
+            ("1", "#Gates"),
+            ("2", "#Plexers"),
+            ("3", "#Base")
+        ]
+    );
+    assert_eq!(circuit_file.libraries[3].tools[0].name, "Text Tool");
+    let main = &circuit_file.circuits["main"];
+    assert!(main.attributes.contains_key("clabelup"));
+    assert_eq!(main.components[0].library.as_deref(), Some("0"));
+    let pin: Vec<(&str, &str)> = main.components[1]
+        .attributes
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(
+        pin,
+        vec![
+            ("facing", "west"),
+            ("type", "output"),
+            ("behavior", "pulldown"),
+            ("label", "Y")
+        ]
+    );
+    let toolbar = &circuit_file.options.toolbar_items;
+    assert_eq!(toolbar[0].as_ref().unwrap().library.as_deref(), Some("3"));
+    assert_eq!(toolbar[1].as_ref().unwrap().library.as_deref(), Some("0"));
+
+    // The constant drives the pin, and the decoder has no enable input
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    sim.reset();
+    sim.run().unwrap();
+    let y = sim.netlist().find_node_by_name("Y").unwrap();
+    assert_eq!(sim.get_node_signal(y).unwrap().to_u64(), Some(1));
+    let source = sim.circuit_source().unwrap();
+    let (&decoder, _) = source
+        .components
+        .iter()
+        .find(|(_, (circuit, index))| circuit == "main" && *index == 2)
+        .unwrap();
+    assert!(sim
+        .get_component(decoder)
+        .unwrap()
+        .get_pin("enable")
+        .is_none());
+}
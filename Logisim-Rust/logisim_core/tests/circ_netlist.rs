@@ -0,0 +1,356 @@
+//! Netlist extraction tests for .circ files
+//!
+//! These tests load small circuits through `CircIntegration` and check that
+//! wires, junctions, tunnels, splitters and subcircuits end up connecting the
+//! right ports, that components the loader cannot build are reported, and
+//! that the shipped example schematics load.
+
+use logisim_core::circ_format::{CircFormatError, CircIntegration, CircParser};
+use logisim_core::{Signal, Simulation, Timestamp};
+
+fn project(circuits: &[(&str, &str)]) -> String {
+    let mut xml = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+        "<project source=\"3.8.0\" version=\"1.0\">\n",
+        "  <lib desc=\"#Wiring\" name=\"0\"/>\n",
+        "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+        "  <lib desc=\"#Plexers\" name=\"2\"/>\n",
+        "  <lib desc=\"#Arithmetic\" name=\"3\"/>\n",
+        "  <lib desc=\"#Memory\" name=\"4\"/>\n",
+        "  <main name=\"main\"/>\n",
+    )
+    .to_string();
+    for (name, body) in circuits {
+        xml.push_str(&format!(
+            "  <circuit name=\"{}\">\n{}  </circuit>\n",
+            name, body
+        ));
+    }
+    xml.push_str("</project>\n");
+    xml
+}
+
+fn load(body: &str) -> Result<Simulation, CircFormatError> {
+    load_project(&[("main", body)])
+}
+
+fn load_project(circuits: &[(&str, &str)]) -> Result<Simulation, CircFormatError> {
+    let circuit_file = CircParser::parse_string(&project(circuits)).expect("Failed to parse XML");
+    CircIntegration::circuit_file_to_simulation(&circuit_file)
+}
+
+fn signal(sim: &Simulation, name: &str) -> Signal {
+    let node = sim
+        .netlist()
+        .find_node_by_name(name)
+        .unwrap_or_else(|| panic!("No node named {}", name));
+    sim.get_node_signal(node).unwrap()
+}
+
+/// A 2-bit constant is split into two wires that feed an AND gate; the gate
+/// output reaches the `Y` pin only through a pair of tunnels.
+fn split_and_circuit(value: &str) -> String {
+    format!(
+        concat!(
+            "    <wire from=\"(80,80)\" to=\"(150,80)\"/>\n",
+            "    <wire from=\"(80,90)\" to=\"(130,90)\"/>\n",
+            "    <wire from=\"(130,90)\" to=\"(130,120)\"/>\n",
+            "    <wire from=\"(130,120)\" to=\"(150,120)\"/>\n",
+            "    <comp lib=\"0\" loc=\"(60,100)\" name=\"Constant\">\n",
+            "      <a name=\"width\" val=\"2\"/>\n",
+            "      <a name=\"value\" val=\"{}\"/>\n",
+            "    </comp>\n",
+            "    <comp lib=\"0\" loc=\"(60,100)\" name=\"Splitter\">\n",
+            "      <a name=\"fanout\" val=\"2\"/>\n",
+            "      <a name=\"incoming\" val=\"2\"/>\n",
+            "    </comp>\n",
+            "    <comp lib=\"1\" loc=\"(200,100)\" name=\"AND Gate\"/>\n",
+            "    <comp lib=\"0\" loc=\"(200,100)\" name=\"Tunnel\">\n",
+            "      <a name=\"label\" val=\"OUT\"/>\n",
+            "    </comp>\n",
+            "    <comp lib=\"0\" loc=\"(300,200)\" name=\"Tunnel\">\n",
+            "      <a name=\"facing\" val=\"east\"/>\n",
+            "      <a name=\"label\" val=\"OUT\"/>\n",
+            "    </comp>\n",
+            "    <comp lib=\"0\" loc=\"(300,200)\" name=\"Pin\">\n",
+            "      <a name=\"facing\" val=\"west\"/>\n",
+            "      <a name=\"output\" val=\"true\"/>\n",
+            "      <a name=\"label\" val=\"Y\"/>\n",
+            "    </comp>\n",
+        ),
+        value
+    )
+}
+
+#[test]
+fn test_splitter_and_tunnel_connectivity() {
+    for (value, expected) in [("0x3", 1), ("0x1", 0), ("0x2", 0)] {
+        let mut sim = load(&split_and_circuit(value)).unwrap();
+        sim.reset();
+        sim.run().unwrap();
+
+        assert_eq!(
+            signal(&sim, "Y").to_u64(),
+            Some(expected),
+            "value {}",
+            value
+        );
+    }
+}
+
+#[test]
+fn test_pulled_pin_on_floating_net() {
+    let body = concat!(
+        "    <wire from=\"(150,100)\" to=\"(200,100)\"/>\n",
+        "    <comp lib=\"0\" loc=\"(200,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"type\" val=\"output\"/>\n",
+        "      <a name=\"behavior\" val=\"pullup\"/>\n",
+        "      <a name=\"width\" val=\"4\"/>\n",
+        "      <a name=\"label\" val=\"BUS\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"1\" loc=\"(150,100)\" name=\"Controlled Buffer\">\n",
+        "      <a name=\"width\" val=\"4\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"0\" loc=\"(140,110)\" name=\"Constant\">\n",
+        "      <a name=\"value\" val=\"0x0\"/>\n",
+        "    </comp>\n",
+    );
+    let mut sim = load(body).unwrap();
+    sim.reset();
+    sim.run().unwrap();
+
+    assert_eq!(signal(&sim, "BUS").to_u64(), Some(0xf));
+}
+
+#[test]
+fn test_width_mismatch_is_reported() {
+    let body = concat!(
+        "    <wire from=\"(100,80)\" to=\"(150,80)\"/>\n",
+        "    <comp lib=\"0\" loc=\"(100,80)\" name=\"Constant\">\n",
+        "      <a name=\"width\" val=\"2\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"1\" loc=\"(200,100)\" name=\"AND Gate\"/>\n",
+    );
+    match load(body) {
+        Err(CircFormatError::ConnectionError(message)) => assert!(message.contains("widths")),
+        other => panic!("Expected a connection error, got {:?}", other.err()),
+    }
+}
+
+/// An inverter circuit whose input port sits 30 units left of its output
+const INVERTER: &str = concat!(
+    "    <wire from=\"(100,100)\" to=\"(150,100)\"/>\n",
+    "    <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\">\n",
+    "      <a name=\"label\" val=\"A\"/>\n",
+    "    </comp>\n",
+    "    <comp lib=\"1\" loc=\"(180,100)\" name=\"NOT Gate\"/>\n",
+    "    <comp lib=\"0\" loc=\"(180,100)\" name=\"Pin\">\n",
+    "      <a name=\"facing\" val=\"west\"/>\n",
+    "      <a name=\"type\" val=\"output\"/>\n",
+    "      <a name=\"label\" val=\"Y\"/>\n",
+    "    </comp>\n",
+);
+
+/// An output pin labelled `label` at `(x,y)` with a constant feeding the
+/// subcircuit input port 30 units to its left
+fn probe(x: i32, y: i32, value: &str, label: &str) -> String {
+    format!(
+        concat!(
+            "    <comp lib=\"0\" loc=\"({x},{y})\" name=\"Constant\">\n",
+            "      <a name=\"value\" val=\"{value}\"/>\n",
+            "    </comp>\n",
+            "    <comp lib=\"0\" loc=\"({px},{y})\" name=\"Pin\">\n",
+            "      <a name=\"facing\" val=\"west\"/>\n",
+            "      <a name=\"type\" val=\"output\"/>\n",
+            "      <a name=\"label\" val=\"{label}\"/>\n",
+            "    </comp>\n",
+        ),
+        x = x - 30,
+        px = x,
+        y = y,
+        value = value,
+        label = label
+    )
+}
+
+fn run(sim: &mut Simulation) {
+    sim.reset();
+    sim.run().unwrap();
+}
+
+#[test]
+fn test_subcircuit_instances_are_independent() {
+    let main = format!(
+        "{}{}{}{}",
+        "    <comp loc=\"(300,100)\" name=\"INV\"/>\n",
+        "    <comp loc=\"(300,200)\" name=\"INV\"/>\n",
+        probe(300, 100, "0x1", "Q1"),
+        probe(300, 200, "0x0", "Q2"),
+    );
+    let mut sim = load_project(&[("main", &main), ("INV", INVERTER)]).unwrap();
+    run(&mut sim);
+
+    assert_eq!(signal(&sim, "Q1").to_u64(), Some(0));
+    assert_eq!(signal(&sim, "Q2").to_u64(), Some(1));
+    assert_eq!(signal(&sim, "INV(300,100)/A").to_u64(), Some(1));
+    assert_eq!(signal(&sim, "INV(300,200)/Y").to_u64(), Some(1));
+}
+
+#[test]
+fn test_nested_subcircuits() {
+    let double_inverter = concat!(
+        "    <wire from=\"(100,100)\" to=\"(130,100)\"/>\n",
+        "    <wire from=\"(160,100)\" to=\"(190,100)\"/>\n",
+        "    <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\">\n",
+        "      <a name=\"label\" val=\"A\"/>\n",
+        "    </comp>\n",
+        "    <comp loc=\"(160,100)\" name=\"INV\">\n",
+        "      <a name=\"label\" val=\"first\"/>\n",
+        "    </comp>\n",
+        "    <comp loc=\"(220,100)\" name=\"INV\"/>\n",
+        "    <comp lib=\"0\" loc=\"(220,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"type\" val=\"output\"/>\n",
+        "      <a name=\"label\" val=\"Y\"/>\n",
+        "    </comp>\n",
+    );
+    let main = format!(
+        "    <comp loc=\"(300,100)\" name=\"BUF\"/>\n{}",
+        probe(300, 100, "0x1", "Q")
+    );
+    let mut sim =
+        load_project(&[("main", &main), ("BUF", double_inverter), ("INV", INVERTER)]).unwrap();
+    run(&mut sim);
+
+    assert_eq!(signal(&sim, "Q").to_u64(), Some(1));
+    assert_eq!(signal(&sim, "BUF(300,100)/first/Y").to_u64(), Some(0));
+}
+
+#[test]
+fn test_circular_subcircuit_is_rejected() {
+    let outer = "    <comp loc=\"(100,100)\" name=\"inner\"/>\n";
+    let inner = "    <comp loc=\"(100,100)\" name=\"main\"/>\n";
+
+    match load_project(&[("main", outer), ("inner", inner)]) {
+        Err(CircFormatError::CircularReference(chain)) => {
+            assert_eq!(chain, "main -> inner -> main")
+        }
+        other => panic!("Expected a circular reference, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_plexer_and_memory_components() {
+    let body = concat!(
+        "    <comp lib=\"0\" loc=\"(170,90)\" name=\"Constant\">\n",
+        "      <a name=\"width\" val=\"2\"/>\n",
+        "      <a name=\"value\" val=\"0x2\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"0\" loc=\"(170,110)\" name=\"Constant\">\n",
+        "      <a name=\"width\" val=\"2\"/>\n",
+        "      <a name=\"value\" val=\"0x3\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"0\" loc=\"(180,120)\" name=\"Constant\"/>\n",
+        "    <comp lib=\"2\" loc=\"(200,100)\" name=\"Multiplexer\">\n",
+        "      <a name=\"width\" val=\"2\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"0\" loc=\"(200,100)\" name=\"Probe\"/>\n",
+        "    <comp lib=\"0\" loc=\"(200,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"output\" val=\"true\"/>\n",
+        "      <a name=\"width\" val=\"2\"/>\n",
+        "      <a name=\"label\" val=\"Y\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"4\" loc=\"(300,200)\" name=\"Register\">\n",
+        "      <a name=\"appearance\" val=\"classic\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"0\" loc=\"(300,200)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"output\" val=\"true\"/>\n",
+        "      <a name=\"width\" val=\"8\"/>\n",
+        "      <a name=\"label\" val=\"Q\"/>\n",
+        "    </comp>\n",
+    );
+    let mut sim = load(body).unwrap();
+    run(&mut sim);
+
+    // The probe took the 2-bit width of the multiplexer output
+    assert_eq!(signal(&sim, "Y").to_u64(), Some(3));
+    // Without a clock edge the register keeps its cleared value
+    assert_eq!(signal(&sim, "Q").to_u64(), Some(0));
+}
+
+#[test]
+fn test_unsupported_components_are_listed() {
+    let main = concat!(
+        "    <comp lib=\"3\" loc=\"(100,100)\" name=\"FPAdder\"/>\n",
+        "    <comp lib=\"4\" loc=\"(200,100)\" name=\"RAM\">\n",
+        "      <a name=\"databus\" val=\"bidir\"/>\n",
+        "    </comp>\n",
+        "    <comp loc=\"(300,100)\" name=\"SUB\"/>\n",
+        "    <comp loc=\"(300,200)\" name=\"SUB\"/>\n",
+    );
+    let sub = concat!(
+        "    <comp lib=\"3\" loc=\"(50,50)\" name=\"FPNegator\"/>\n",
+        "    <comp lib=\"1\" loc=\"(100,50)\" name=\"OR Gate\"/>\n",
+    );
+
+    let unsupported = match load_project(&[("main", main), ("SUB", sub)]) {
+        Err(CircFormatError::UnsupportedComponents(unsupported)) => unsupported,
+        other => panic!("Expected unsupported components, got {:?}", other.err()),
+    };
+    // The subcircuit is instantiated twice but its component is listed once
+    let listed: Vec<String> = unsupported.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        listed,
+        vec![
+            "#Arithmetic FPAdder at (100,100) in circuit 'main'",
+            "#Memory RAM at (200,100) in circuit 'main' (RAM with a bidirectional data bus)",
+            "#Arithmetic FPNegator at (50,50) in circuit 'SUB'",
+        ]
+    );
+
+    let message = load_project(&[("main", main), ("SUB", sub)])
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(message.starts_with("Unsupported components: #Arithmetic FPAdder"));
+}
+
+#[test]
+fn test_shipped_examples_load() {
+    let dir = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../example_schematics/logisim_evolution"
+    );
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "circ"))
+        .collect();
+    paths.sort();
+    assert_eq!(paths.len(), 15);
+
+    for path in paths {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let circuit_file = CircParser::load_file(&path).unwrap();
+        let result = CircIntegration::circuit_file_to_simulation(&circuit_file);
+        if name == "STACK2.circ" {
+            // The file itself drives the 4-bit select of a multiplexer
+            // from a 3-bit splitter
+            match result {
+                Err(CircFormatError::ConnectionError(message)) => {
+                    assert_eq!(message, "Incompatible widths at (2030,410): 3 and 4 bits")
+                }
+                other => panic!("Expected a width error, got {:?}", other.err()),
+            }
+            continue;
+        }
+        let mut sim = result.unwrap_or_else(|e| panic!("{}: {}", name, e));
+        // Some examples have a running clock and never settle
+        sim.reset();
+        sim.run_until(Timestamp(50))
+            .unwrap_or_else(|e| panic!("{}: {}", name, e));
+    }
+}
@@ -0,0 +1,158 @@
+//! Clocked circuit tests
+//!
+//! These tests wire clocks to sequential components through ordinary nets
+//! and check that each component follows the clock on its own `CLK` pin.
+
+use logisim_core::std::memory::{Counter, DFlipFlop};
+use logisim_core::std::wiring::Clock;
+use logisim_core::{AndGate, BusWidth, ComponentId, NodeId, Signal, Simulation, Timestamp, Value};
+use std::sync::{Arc, Mutex};
+
+fn clock(sim: &mut Simulation, id: u64, high: u64, low: u64) -> NodeId {
+    let mut clock = Clock::new(ComponentId(id));
+    clock.set_high_duration(high);
+    clock.set_low_duration(low);
+    let id = sim.add_component(Box::new(clock));
+    let node = sim.netlist_mut().create_node(BusWidth(1));
+    sim.connect(id, "out".to_string(), node).unwrap();
+    node
+}
+
+fn counter(sim: &mut Simulation, id: u64, clk: NodeId) -> NodeId {
+    let id = sim.add_component(Box::new(Counter::new_with_width(
+        ComponentId(id),
+        BusWidth(8),
+    )));
+    let out = sim.netlist_mut().create_node(BusWidth(8));
+    sim.connect(id, "CLK".to_string(), clk).unwrap();
+    sim.connect(id, "OUT".to_string(), out).unwrap();
+    out
+}
+
+/// A flip-flop feeding its complement back to `D`, halving its clock
+fn divider(sim: &mut Simulation, id: u64, clk: NodeId) -> NodeId {
+    let id = sim.add_component(Box::new(DFlipFlop::new(ComponentId(id))));
+    let q = sim.netlist_mut().create_node(BusWidth(1));
+    let nq = sim.netlist_mut().create_node(BusWidth(1));
+    sim.connect(id, "CLK".to_string(), clk).unwrap();
+    sim.connect(id, "D".to_string(), nq).unwrap();
+    sim.connect(id, "NQ".to_string(), nq).unwrap();
+    sim.connect(id, "Q".to_string(), q).unwrap();
+    q
+}
+
+/// Record the times at which `node` settles to a new defined level
+fn transitions(sim: &mut Simulation, node: NodeId) -> Arc<Mutex<Vec<(u64, Value)>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    sim.add_signal_callback(Box::new(move |changed, time, signal: &Signal| {
+        if changed == node {
+            if let Some(value) = signal.as_single().filter(|value| value.is_definite()) {
+                log.lock().unwrap().push((time.0, value));
+            }
+        }
+    }));
+    seen
+}
+
+fn count(sim: &Simulation, node: NodeId) -> u64 {
+    sim.get_node_signal(node).unwrap().to_u64().unwrap()
+}
+
+#[test]
+fn test_independent_clock_domains() {
+    let mut sim = Simulation::new();
+    let fast = clock(&mut sim, 1, 20, 20);
+    let slow = clock(&mut sim, 2, 35, 35);
+    let fast_count = counter(&mut sim, 3, fast);
+    let slow_count = counter(&mut sim, 4, slow);
+
+    sim.reset();
+    sim.run_until(Timestamp(400)).unwrap();
+
+    // Rising edges at 20, 60, ..., 380 and at 35, 105, ..., 385
+    assert_eq!(count(&sim, fast_count), 10);
+    assert_eq!(count(&sim, slow_count), 6);
+}
+
+#[test]
+fn test_divided_clock() {
+    let mut sim = Simulation::new();
+    let clk = clock(&mut sim, 1, 10, 10);
+    let half = divider(&mut sim, 2, clk);
+    let quarter = divider(&mut sim, 3, half);
+    let edges = transitions(&mut sim, quarter);
+
+    sim.reset();
+    sim.run_until(Timestamp(200)).unwrap();
+
+    // Each stage toggles one flip-flop delay (5 units) after its clock
+    // rises: the first at 15, 35, ... and the second at 20, 60, ...
+    let levels: Vec<_> = edges.lock().unwrap().clone();
+    assert_eq!(
+        levels,
+        vec![
+            (5, Value::Low),
+            (20, Value::High),
+            (60, Value::Low),
+            (100, Value::High),
+            (140, Value::Low),
+            (180, Value::High),
+        ]
+    );
+}
+
+#[test]
+fn test_gated_clock() {
+    let mut sim = Simulation::new();
+    let clk = clock(&mut sim, 1, 10, 10);
+    let enable = sim.netlist_mut().create_node(BusWidth(1));
+    let gated = sim.netlist_mut().create_node(BusWidth(1));
+    let gate = sim.add_component(Box::new(AndGate::new(ComponentId(2))));
+    sim.connect(gate, "A".to_string(), clk).unwrap();
+    sim.connect(gate, "B".to_string(), enable).unwrap();
+    sim.connect(gate, "Y".to_string(), gated).unwrap();
+    let out = counter(&mut sim, 3, gated);
+
+    sim.reset();
+    sim.schedule_signal_change(
+        Timestamp(0),
+        enable,
+        Signal::new_single(Value::High),
+        ComponentId(0),
+    );
+    // Close the gate while the clock is low, between the edges at 50 and 70
+    sim.schedule_signal_change(
+        Timestamp(60),
+        enable,
+        Signal::new_single(Value::Low),
+        ComponentId(0),
+    );
+    sim.run_until(Timestamp(200)).unwrap();
+
+    // Only the edges at 10, 30 and 50 get through
+    assert_eq!(count(&sim, out), 3);
+}
+
+#[test]
+fn test_manual_tick_shifts_clock() {
+    let mut sim = Simulation::new();
+    let clk = clock(&mut sim, 1, 50, 50);
+    let edges = transitions(&mut sim, clk);
+
+    sim.reset();
+    sim.schedule_clock_tick(Timestamp(10));
+    sim.run_until(Timestamp(150)).unwrap();
+
+    let levels: Vec<_> = edges.lock().unwrap().clone();
+    assert_eq!(
+        levels,
+        vec![
+            (0, Value::Low),
+            (10, Value::High),
+            (60, Value::Low),
+            (110, Value::High),
+        ]
+    );
+    assert_eq!(sim.stats().clock_ticks, 1);
+}
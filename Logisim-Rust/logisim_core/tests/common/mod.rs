@@ -0,0 +1,35 @@
+//! Helpers shared by the integration tests that build circuits gate by gate
+
+// Each test file uses only some of the helpers
+#![allow(dead_code)]
+
+use logisim_core::simulation::{SimulationConfig, SimulationEngine};
+use logisim_core::{BusWidth, Component, NodeId, Simulation};
+
+/// A simulation on `engine` that runs without time or event limits
+pub fn simulation(engine: SimulationEngine) -> Simulation {
+    Simulation::with_config(SimulationConfig {
+        max_time: None,
+        max_events: None,
+        engine,
+        ..SimulationConfig::default()
+    })
+}
+
+/// A new single-bit node
+pub fn node(sim: &mut Simulation) -> NodeId {
+    sim.netlist_mut().create_node(BusWidth(1))
+}
+
+/// A new single-bit node named `name`
+pub fn named_node(sim: &mut Simulation, name: String) -> NodeId {
+    sim.netlist_mut().create_named_node(BusWidth(1), name)
+}
+
+/// Add a gate and connect its pins
+pub fn gate(sim: &mut Simulation, gate: Box<dyn Component>, pins: &[(&str, NodeId)]) {
+    let id = sim.add_component(gate);
+    for (pin, node) in pins {
+        sim.connect(id, pin.to_string(), *node).unwrap();
+    }
+}
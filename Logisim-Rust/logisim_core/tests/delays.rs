@@ -0,0 +1,134 @@
+//! Propagation delay tests
+//!
+//! These tests check that output changes arrive after the delay resolved
+//! for each component instance, and that the inertial delay model swallows
+//! the glitch a static hazard produces while the transport model lets it
+//! through.
+
+use logisim_core::circ_format::{CircIntegration, CircParser};
+use logisim_core::simulation::SimulationConfig;
+use logisim_core::{
+    AndGate, BusWidth, ComponentId, DelayModel, DelayTable, NodeId, NotGate, Signal, Simulation,
+    Timestamp, Value,
+};
+use std::sync::{Arc, Mutex};
+
+/// Record every defined level `node` takes, with its time
+fn transitions(sim: &mut Simulation, node: NodeId) -> Arc<Mutex<Vec<(u64, Value)>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    sim.add_signal_callback(Box::new(move |changed, time, signal: &Signal| {
+        if changed == node {
+            if let Some(value) = signal.as_single().filter(|value| value.is_definite()) {
+                log.lock().unwrap().push((time.0, value));
+            }
+        }
+    }));
+    seen
+}
+
+struct Hazard {
+    sim: Simulation,
+    a: NodeId,
+    y: NodeId,
+}
+
+/// `A AND NOT A`, with the inverter 1 unit and the AND gate 3 units slow
+fn hazard(delay_model: DelayModel) -> Hazard {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        delay_model,
+        delays: DelayTable::new().with("AND", 3),
+        ..SimulationConfig::default()
+    });
+    let mut node = || sim.netlist_mut().create_node(BusWidth(1));
+    let (a, not_a, y) = (node(), node(), node());
+    let not = sim.add_component(Box::new(NotGate::new(ComponentId(1))));
+    sim.connect(not, "A".to_string(), a).unwrap();
+    sim.connect(not, "Y".to_string(), not_a).unwrap();
+    let and = sim.add_component(Box::new(AndGate::new(ComponentId(2))));
+    sim.connect(and, "A".to_string(), a).unwrap();
+    sim.connect(and, "B".to_string(), not_a).unwrap();
+    sim.connect(and, "Y".to_string(), y).unwrap();
+    Hazard { sim, a, y }
+}
+
+impl Hazard {
+    /// Hold `A` low, raise it at 20 and return the levels `Y` went through
+    fn rise(&mut self) -> Vec<(u64, Value)> {
+        let levels = transitions(&mut self.sim, self.y);
+        self.sim.reset();
+        for (time, value) in [(0, Value::Low), (20, Value::High)] {
+            self.sim.schedule_signal_change(
+                Timestamp(time),
+                self.a,
+                Signal::new_single(value),
+                ComponentId(0),
+            );
+        }
+        self.sim.run().unwrap();
+        let levels = levels.lock().unwrap().clone();
+        levels
+    }
+}
+
+#[test]
+fn test_delays_resolve_by_precedence() {
+    let mut hazard = hazard(DelayModel::Transport);
+    let (not, and) = (ComponentId(1), ComponentId(2));
+
+    // The component's own delay, then the table, then the instance
+    assert_eq!(hazard.sim.component_delay(not), Some(1));
+    assert_eq!(hazard.sim.component_delay(and), Some(3));
+    hazard.sim.set_component_delay(and, 4).unwrap();
+    assert_eq!(hazard.sim.component_delay(and), Some(4));
+    assert_eq!(hazard.sim.clear_component_delay(and), Some(4));
+    assert_eq!(hazard.sim.component_delay(and), Some(3));
+    assert!(hazard.sim.set_component_delay(ComponentId(9), 1).is_err());
+}
+
+#[test]
+fn test_transport_delay_shows_the_glitch() {
+    let mut hazard = hazard(DelayModel::Transport);
+
+    // Both inputs are high from 20 until the inverter catches up at 21
+    assert_eq!(
+        hazard.rise(),
+        vec![(3, Value::Low), (23, Value::High), (24, Value::Low)]
+    );
+}
+
+#[test]
+fn test_inertial_delay_swallows_the_glitch() {
+    let mut short = hazard(DelayModel::Inertial);
+    assert_eq!(short.rise(), vec![(3, Value::Low)]);
+
+    // A pulse as long as the gate delay gets through
+    let mut long = hazard(DelayModel::Inertial);
+    long.sim.set_component_delay(ComponentId(1), 3).unwrap();
+    assert_eq!(
+        long.rise(),
+        vec![(3, Value::Low), (23, Value::High), (26, Value::Low)]
+    );
+}
+
+#[test]
+fn test_delay_attribute_sets_instance_delay() {
+    let xml = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+        "<project source=\"3.8.0\" version=\"1.0\">\n",
+        "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+        "  <main name=\"main\"/>\n",
+        "  <circuit name=\"main\">\n",
+        "    <comp lib=\"1\" loc=\"(100,100)\" name=\"NOT Gate\">\n",
+        "      <a name=\"delay\" val=\"7\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"1\" loc=\"(100,200)\" name=\"NOT Gate\"/>\n",
+        "  </circuit>\n",
+        "</project>\n",
+    );
+    let circuit_file = CircParser::parse_string(xml).unwrap();
+    let sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+
+    assert_eq!(sim.component_delay(ComponentId(1)), Some(7));
+    assert_eq!(sim.component_delay(ComponentId(2)), Some(1));
+}
@@ -0,0 +1,201 @@
+//! Stuck-at fault simulation tests
+//!
+//! These tests inject stuck-at faults on the nets and pins of a small gate
+//! circuit, grade exhaustive and partial test sets by fault coverage and
+//! check that a faulty pin only affects its own component.
+
+use logisim_core::fault::TestSet;
+use logisim_core::simulation::{SimulationConfig, SimulationError};
+use logisim_core::{
+    AndGate, BusWidth, ComponentId, Fault, NodeId, NotGate, OrGate, Signal, Simulation, StuckAt,
+    Timestamp, Value,
+};
+
+/// `y = (a AND b) OR c` with `na = NOT a` sharing the `a` net
+struct Circuit {
+    sim: Simulation,
+    a: NodeId,
+    b: NodeId,
+    c: NodeId,
+    y: NodeId,
+    na: NodeId,
+}
+
+fn circuit() -> Circuit {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        max_time: None,
+        max_events: None,
+        ..SimulationConfig::default()
+    });
+    let mut node = || sim.netlist_mut().create_node(BusWidth(1));
+    let (a, b, c, ab, y, na) = (node(), node(), node(), node(), node(), node());
+    let and = sim.add_component(Box::new(AndGate::new(ComponentId(1))));
+    let or = sim.add_component(Box::new(OrGate::new(ComponentId(2))));
+    let not = sim.add_component(Box::new(NotGate::new(ComponentId(3))));
+    for (id, pin, node) in [
+        (and, "A", a),
+        (and, "B", b),
+        (and, "Y", ab),
+        (or, "A", ab),
+        (or, "B", c),
+        (or, "Y", y),
+        (not, "A", a),
+        (not, "Y", na),
+    ] {
+        sim.connect(id, pin.to_string(), node).unwrap();
+    }
+    sim.reset();
+    Circuit {
+        sim,
+        a,
+        b,
+        c,
+        y,
+        na,
+    }
+}
+
+fn vector(bits: &[u64]) -> Vec<Signal> {
+    bits.iter()
+        .map(|&bit| Signal::from_u64(bit, BusWidth(1)))
+        .collect()
+}
+
+fn test_set(circuit: &Circuit, vectors: Vec<Vec<Signal>>) -> TestSet {
+    TestSet::new(
+        vec![circuit.a, circuit.b, circuit.c],
+        vec![circuit.y, circuit.na],
+        vectors,
+    )
+}
+
+#[test]
+fn test_exhaustive_vectors_detect_every_fault() {
+    let mut circuit = circuit();
+    let faults = circuit.sim.fault_list();
+    // Six nets and eight connected pins, each stuck at either level
+    assert_eq!(faults.len(), 28);
+
+    let vectors = (0..8)
+        .map(|i| vector(&[i >> 2 & 1, i >> 1 & 1, i & 1]))
+        .collect();
+    let tests = test_set(&circuit, vectors);
+    let report = circuit.sim.fault_coverage(&faults, &tests).unwrap();
+    assert_eq!(report.detected.len(), 28);
+    assert_eq!(report.coverage(), 1.0);
+    assert_eq!(report.to_string(), "Fault coverage: 28/28 (100.0%)");
+
+    // The output stuck low is first seen when the vectors drive it high
+    let y_low = report
+        .detected
+        .iter()
+        .find(|detected| detected.fault == Fault::node(circuit.y, StuckAt::Zero))
+        .unwrap();
+    assert_eq!((y_low.vector, y_low.output), (1, circuit.y));
+}
+
+#[test]
+fn test_partial_vectors_leave_faults_undetected() {
+    let mut circuit = circuit();
+    let faults = circuit.sim.fault_list();
+    let tests = test_set(&circuit, vec![vector(&[1, 1, 0])]);
+    let report = circuit.sim.fault_coverage(&faults, &tests).unwrap();
+
+    // With y high and na low, only faults pulling y low or na high show
+    assert_eq!(report.detected.len(), 12);
+    assert!(report.detected.iter().all(|detected| detected.vector == 0));
+    for fault in [
+        Fault::node(circuit.c, StuckAt::Zero),
+        Fault::node(circuit.c, StuckAt::One),
+        Fault::node(circuit.y, StuckAt::One),
+    ] {
+        assert!(report.undetected.contains(&fault), "{}", fault);
+    }
+    let text = report.to_string();
+    assert!(
+        text.starts_with("Fault coverage: 12/28 (42.9%)\nUndetected faults:\n"),
+        "{}",
+        text
+    );
+
+    // The simulation is left fault-free
+    assert!(circuit.sim.injected_faults().is_empty());
+
+    let bad = test_set(&circuit, vec![vector(&[1, 1])]);
+    assert!(matches!(
+        circuit.sim.fault_coverage(&faults, &bad),
+        Err(SimulationError::FaultSimulationError(_))
+    ));
+}
+
+#[test]
+fn test_pin_fault_only_affects_its_component() {
+    let mut circuit = circuit();
+    let tests = test_set(&circuit, vec![vector(&[1, 1, 0])]);
+
+    // The AND input stuck low changes y but not the inverter sharing the net
+    let pin = Fault::pin(ComponentId(1), "A", StuckAt::Zero);
+    let net = Fault::node(circuit.a, StuckAt::Zero);
+    let report = circuit
+        .sim
+        .fault_coverage(&[pin.clone(), net.clone()], &tests)
+        .unwrap();
+    assert_eq!(report.detected.len(), 2);
+    assert_eq!(report.detected[0].output, circuit.y);
+    assert_eq!(report.detected[1].output, circuit.y);
+
+    let tests = TestSet::new(vec![circuit.a], vec![circuit.na], vec![vector(&[1])]);
+    let report = circuit
+        .sim
+        .fault_coverage(&[pin.clone(), net.clone()], &tests)
+        .unwrap();
+    assert_eq!(report.undetected, vec![pin]);
+    assert_eq!(report.detected[0].fault, net);
+}
+
+#[test]
+fn test_inject_and_remove_fault() {
+    let Circuit {
+        mut sim,
+        a,
+        b,
+        c,
+        y,
+        ..
+    } = circuit();
+    for (node, value) in [(a, Value::High), (b, Value::High), (c, Value::Low)] {
+        sim.schedule_signal_change(
+            Timestamp(0),
+            node,
+            Signal::new_single(value),
+            ComponentId(0),
+        );
+    }
+    sim.run().unwrap();
+    assert_eq!(
+        sim.get_node_signal(y),
+        Some(Signal::new_single(Value::High))
+    );
+
+    let fault = Fault::node(b, StuckAt::Zero);
+    sim.inject_fault(&fault).unwrap();
+    sim.run().unwrap();
+    assert_eq!(sim.get_node_signal(b), Some(Signal::new_single(Value::Low)));
+    assert_eq!(sim.get_node_signal(y), Some(Signal::new_single(Value::Low)));
+    assert_eq!(sim.injected_faults(), vec![fault.clone()]);
+
+    sim.remove_fault(&fault).unwrap();
+    sim.run().unwrap();
+    assert_eq!(
+        sim.get_node_signal(y),
+        Some(Signal::new_single(Value::High))
+    );
+    assert!(sim.injected_faults().is_empty());
+
+    let missing = Fault::pin(ComponentId(1), "Q", StuckAt::One);
+    assert!(sim.inject_fault(&missing).is_err());
+    assert!(matches!(
+        sim.inject_fault(&Fault::node(NodeId(99), StuckAt::One)),
+        Err(SimulationError::NodeNotFound(_))
+    ));
+}
@@ -0,0 +1,116 @@
+//! IEEE 1164 logic mode tests
+//!
+//! These tests resolve pull resistors and conflicting drivers with the
+//! nine-valued resolution table and compare the levels seen with those of
+//! Logisim's own rules.
+
+use logisim_core::ieee1164::{format_vector, parse_vector, to_signal};
+use logisim_core::simulation::SimulationConfig;
+use logisim_core::{
+    BusWidth, ComponentId, LogicMode, NodeId, NotGate, Signal, Simulation, StdLogic, Timestamp,
+    Value,
+};
+
+fn simulation(logic_mode: LogicMode) -> Simulation {
+    Simulation::with_config(SimulationConfig {
+        logic_mode,
+        ..SimulationConfig::default()
+    })
+}
+
+fn drive(sim: &mut Simulation, time: u64, node: NodeId, value: Value, source: u64) {
+    sim.schedule_signal_change(
+        Timestamp(time),
+        node,
+        Signal::new_single(value),
+        ComponentId(source),
+    );
+}
+
+fn levels(sim: &Simulation, node: NodeId) -> String {
+    format_vector(&sim.get_node_std_logic(node).unwrap())
+}
+
+#[test]
+fn test_pull_up_is_a_weak_driver() {
+    let mut sim = simulation(LogicMode::Ieee1164);
+    let a = sim.netlist_mut().create_node(BusWidth(1));
+    let y = sim.netlist_mut().create_node(BusWidth(1));
+    let not = sim.add_component(Box::new(NotGate::new(ComponentId(1))));
+    sim.connect(not, "A".to_string(), a).unwrap();
+    sim.connect(not, "Y".to_string(), y).unwrap();
+    sim.netlist_mut()
+        .set_node_pull(a, Some(Value::High))
+        .unwrap();
+    sim.reset();
+
+    // A released driver leaves the weak high, which the gate reads as 1
+    drive(&mut sim, 0, a, Value::HighZ, 0);
+    sim.run_until(Timestamp(10)).unwrap();
+    assert_eq!(levels(&sim, a), "H");
+    assert_eq!(
+        sim.get_node_signal(a),
+        Some(Signal::new_single(Value::High))
+    );
+    assert_eq!(levels(&sim, y), "0");
+
+    // A forcing 0 overrides it
+    drive(&mut sim, 10, a, Value::Low, 0);
+    sim.run().unwrap();
+    assert_eq!(levels(&sim, a), "0");
+    assert_eq!(levels(&sim, y), "1");
+}
+
+#[test]
+fn test_conflicting_pulls_resolve_to_weak_unknown() {
+    for (logic_mode, expected, value) in [
+        (LogicMode::Ieee1164, "W", Value::Error),
+        (LogicMode::Logisim, "1", Value::High),
+    ] {
+        let mut sim = simulation(logic_mode);
+        let up = sim.netlist_mut().create_node(BusWidth(1));
+        let down = sim.netlist_mut().create_node(BusWidth(1));
+        let netlist = sim.netlist_mut();
+        netlist.set_node_pull(up, Some(Value::High)).unwrap();
+        netlist.set_node_pull(down, Some(Value::Low)).unwrap();
+        netlist.link_bits(up, 0, down, 0).unwrap();
+
+        assert_eq!(levels(&sim, up), expected, "{:?}", logic_mode);
+        assert_eq!(sim.get_node_signal(down), Some(Signal::new_single(value)));
+    }
+}
+
+#[test]
+fn test_uninitialized_dominates_conflicts() {
+    let mut results = Vec::new();
+    for logic_mode in [LogicMode::Ieee1164, LogicMode::Logisim] {
+        let mut sim = simulation(logic_mode);
+        let bus = sim.netlist_mut().create_node(BusWidth(3));
+        sim.reset();
+        let drivers = [parse_vector("10U").unwrap(), parse_vector("Z1X").unwrap()];
+        for (source, driver) in drivers.iter().enumerate() {
+            let signal = to_signal(driver);
+            sim.schedule_signal_change(Timestamp(0), bus, signal, ComponentId(source as u64 + 1));
+        }
+        sim.run().unwrap();
+        results.push(levels(&sim, bus));
+    }
+
+    // 0 against 1 is a conflict either way, but X against U is U in VHDL
+    // and an error in Logisim
+    assert_eq!(results, vec!["1XU", "1XX"]);
+}
+
+#[test]
+fn test_switching_mode_resolves_again() {
+    let mut sim = simulation(LogicMode::Logisim);
+    let node = sim.netlist_mut().create_node(BusWidth(1));
+    sim.netlist_mut()
+        .set_node_pull(node, Some(Value::Low))
+        .unwrap();
+    assert_eq!(levels(&sim, node), "0");
+
+    let changed = sim.netlist_mut().set_logic_mode(LogicMode::Ieee1164);
+    assert!(changed.is_empty());
+    assert_eq!(sim.get_node_std_logic(node), Some(vec![StdLogic::L]));
+}
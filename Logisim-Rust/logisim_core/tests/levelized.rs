@@ -0,0 +1,169 @@
+//! Levelized engine tests
+//!
+//! These tests run the same circuits with the event-driven and the levelized
+//! engine and check that they settle to the same values.
+
+mod common;
+
+use common::{gate, node, simulation};
+use logisim_core::simulation::SimulationEngine;
+use logisim_core::std::memory::DFlipFlop;
+use logisim_core::std::wiring::Clock;
+use logisim_core::{
+    AndGate, ComponentId, NodeId, NorGate, NotGate, Signal, Simulation, Timestamp, Value, XorGate,
+};
+
+struct Logic {
+    sim: Simulation,
+    inputs: [NodeId; 3],
+    out: NodeId,
+}
+
+/// `(a AND b) XOR (NOT c)` followed by a chain of inverters
+fn logic(engine: SimulationEngine) -> Logic {
+    let mut sim = simulation(engine);
+    let inputs = [node(&mut sim), node(&mut sim), node(&mut sim)];
+    let (and, not_c, xor) = (node(&mut sim), node(&mut sim), node(&mut sim));
+
+    // Added back to front so that insertion order does not match data flow
+    let mut last = xor;
+    let mut chain = Vec::new();
+    for _ in 0..20 {
+        let next = node(&mut sim);
+        chain.push((last, next));
+        last = next;
+    }
+    for (i, (input, output)) in chain.into_iter().enumerate().rev() {
+        let not = Box::new(NotGate::new(ComponentId(10 + i as u64)));
+        gate(&mut sim, not, &[("A", input), ("Y", output)]);
+    }
+    let xor_gate = Box::new(XorGate::new(ComponentId(3)));
+    gate(&mut sim, xor_gate, &[("A", and), ("B", not_c), ("Y", xor)]);
+    let not_gate = Box::new(NotGate::new(ComponentId(2)));
+    gate(&mut sim, not_gate, &[("A", inputs[2]), ("Y", not_c)]);
+    let and_gate = Box::new(AndGate::new(ComponentId(1)));
+    gate(
+        &mut sim,
+        and_gate,
+        &[("A", inputs[0]), ("B", inputs[1]), ("Y", and)],
+    );
+
+    sim.reset();
+    Logic {
+        sim,
+        inputs,
+        out: last,
+    }
+}
+
+impl Logic {
+    fn evaluate(&mut self, time: u64, pattern: u8) -> Option<Value> {
+        for (bit, &input) in self.inputs.iter().enumerate() {
+            let value = Value::from_bool(pattern & (1 << bit) != 0);
+            self.sim.schedule_signal_change(
+                Timestamp(time),
+                input,
+                Signal::new_single(value),
+                ComponentId(0),
+            );
+        }
+        self.sim.run().unwrap();
+        self.sim.get_node_signal(self.out).unwrap().as_single()
+    }
+}
+
+#[test]
+fn test_combinational_logic_matches_event_engine() {
+    let mut event = logic(SimulationEngine::EventDriven);
+    let mut levelized = logic(SimulationEngine::Levelized);
+    assert_eq!(event.sim.active_engine(), SimulationEngine::EventDriven);
+    assert_eq!(levelized.sim.active_engine(), SimulationEngine::Levelized);
+    assert_eq!(levelized.sim.levelized_depth(), Some(22));
+
+    for pattern in 0..8u8 {
+        let time = 100 * (pattern as u64 + 1);
+        let expected = (pattern & 3 == 3) ^ (pattern & 4 == 0);
+        assert_eq!(
+            levelized.evaluate(time, pattern),
+            Some(Value::from_bool(expected))
+        );
+        assert_eq!(
+            event.evaluate(time, pattern),
+            levelized
+                .sim
+                .get_node_signal(levelized.out)
+                .unwrap()
+                .as_single()
+        );
+    }
+
+    // Settling in rank order needs far fewer events than gate by gate
+    assert!(levelized.sim.stats().events_processed * 4 < event.sim.stats().events_processed);
+}
+
+/// A clocked flip-flop toggling through an inverter, sampled on every edge
+fn divider(engine: SimulationEngine) -> Vec<Option<Value>> {
+    let mut sim = simulation(engine);
+    let (clk, q, d) = (node(&mut sim), node(&mut sim), node(&mut sim));
+    let mut clock = Clock::new(ComponentId(1));
+    clock.set_high_duration(20);
+    clock.set_low_duration(20);
+    gate(&mut sim, Box::new(clock), &[("out", clk)]);
+    let ff = Box::new(DFlipFlop::new(ComponentId(2)));
+    gate(&mut sim, ff, &[("CLK", clk), ("D", d), ("Q", q)]);
+    let not = Box::new(NotGate::new(ComponentId(3)));
+    gate(&mut sim, not, &[("A", q), ("Y", d)]);
+
+    sim.reset();
+    assert_eq!(sim.active_engine(), engine);
+    (1..10)
+        .map(|cycle| {
+            sim.run_until(Timestamp(cycle * 40 + 10)).unwrap();
+            sim.get_node_signal(q).unwrap().as_single()
+        })
+        .collect()
+}
+
+#[test]
+fn test_sequential_logic_matches_event_engine() {
+    let levelized = divider(SimulationEngine::Levelized);
+    assert_eq!(levelized, divider(SimulationEngine::EventDriven));
+    assert!(levelized.windows(2).all(|pair| pair[0] != pair[1]));
+}
+
+#[test]
+fn test_combinational_loop_falls_back_to_events() {
+    let mut sim = simulation(SimulationEngine::Levelized);
+    let (set, reset, q, nq) = (
+        node(&mut sim),
+        node(&mut sim),
+        node(&mut sim),
+        node(&mut sim),
+    );
+    let upper = Box::new(NorGate::new(ComponentId(1)));
+    gate(&mut sim, upper, &[("A", reset), ("B", nq), ("Y", q)]);
+    let lower = Box::new(NorGate::new(ComponentId(2)));
+    gate(&mut sim, lower, &[("A", set), ("B", q), ("Y", nq)]);
+
+    sim.reset();
+    assert_eq!(sim.active_engine(), SimulationEngine::EventDriven);
+    assert_eq!(sim.levelized_depth(), None);
+
+    sim.schedule_signal_change(
+        Timestamp(10),
+        set,
+        Signal::new_single(Value::High),
+        ComponentId(0),
+    );
+    sim.schedule_signal_change(
+        Timestamp(10),
+        reset,
+        Signal::new_single(Value::Low),
+        ComponentId(0),
+    );
+    sim.run().unwrap();
+    assert_eq!(
+        sim.get_node_signal(q).unwrap().as_single(),
+        Some(Value::High)
+    );
+}
@@ -0,0 +1,139 @@
+//! Oscillation detection tests
+//!
+//! These tests release a cross-coupled NOR latch from the forbidden state so
+//! that it oscillates forever, and check that the simulation points at the
+//! latch instead of running into its event limit.
+
+use logisim_core::simulation::{OscillationAction, SimulationConfig, SimulationError};
+use logisim_core::std::memory::Counter;
+use logisim_core::std::wiring::Clock;
+use logisim_core::{
+    BusWidth, ComponentId, NodeId, NorGate, NotGate, Signal, Simulation, Timestamp, Value,
+};
+
+struct Latch {
+    sim: Simulation,
+    set: NodeId,
+    reset: NodeId,
+    q: NodeId,
+    nq: NodeId,
+    probe: NodeId,
+}
+
+/// A NOR latch with an inverter reading `Q`
+fn latch(action: OscillationAction) -> Latch {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        oscillation_action: action,
+        ..SimulationConfig::default()
+    });
+    let mut node = || sim.netlist_mut().create_node(BusWidth(1));
+    let (set, reset, q, nq, probe) = (node(), node(), node(), node(), node());
+
+    let upper = sim.add_component(Box::new(NorGate::new(ComponentId(1))));
+    sim.connect(upper, "A".to_string(), reset).unwrap();
+    sim.connect(upper, "B".to_string(), nq).unwrap();
+    sim.connect(upper, "Y".to_string(), q).unwrap();
+    let lower = sim.add_component(Box::new(NorGate::new(ComponentId(2))));
+    sim.connect(lower, "A".to_string(), set).unwrap();
+    sim.connect(lower, "B".to_string(), q).unwrap();
+    sim.connect(lower, "Y".to_string(), nq).unwrap();
+    let inverter = sim.add_component(Box::new(NotGate::new(ComponentId(3))));
+    sim.connect(inverter, "A".to_string(), q).unwrap();
+    sim.connect(inverter, "Y".to_string(), probe).unwrap();
+
+    Latch {
+        sim,
+        set,
+        reset,
+        q,
+        nq,
+        probe,
+    }
+}
+
+impl Latch {
+    fn drive(&mut self, time: u64, set: Value, reset: Value) {
+        for (node, value) in [(self.set, set), (self.reset, reset)] {
+            self.sim.schedule_signal_change(
+                Timestamp(time),
+                node,
+                Signal::new_single(value),
+                ComponentId(0),
+            );
+        }
+    }
+
+    /// Hold both inputs high, then release them at the same time
+    fn release(&mut self) -> Result<(), SimulationError> {
+        self.sim.reset();
+        self.drive(0, Value::High, Value::High);
+        self.drive(20, Value::Low, Value::Low);
+        self.sim.run()
+    }
+
+    fn value(&self, node: NodeId) -> Option<Value> {
+        self.sim.get_node_signal(node).unwrap().as_single()
+    }
+}
+
+#[test]
+fn test_oscillation_names_the_loop() {
+    let mut latch = latch(OscillationAction::Stop);
+
+    match latch.release() {
+        Err(SimulationError::OscillationDetected(oscillation)) => {
+            assert_eq!(oscillation.components, vec![ComponentId(1), ComponentId(2)]);
+            assert_eq!(oscillation.nodes, {
+                let mut nodes = vec![latch.q, latch.nq];
+                nodes.sort();
+                nodes
+            });
+            assert!(oscillation.time > Timestamp(20));
+        }
+        other => panic!("Expected an oscillation, got {:?}", other),
+    }
+    assert_eq!(latch.sim.oscillations().len(), 1);
+}
+
+#[test]
+fn test_oscillating_nodes_can_be_forced_to_error() {
+    let mut latch = latch(OscillationAction::ForceError);
+
+    latch.release().unwrap();
+    assert_eq!(latch.sim.oscillations().len(), 1);
+    assert_eq!(latch.value(latch.q), Some(Value::Error));
+    assert_eq!(latch.value(latch.nq), Some(Value::Error));
+    assert_eq!(latch.value(latch.probe), Some(Value::Error));
+
+    // A reset releases the forced nodes
+    latch.sim.reset();
+    latch.drive(0, Value::High, Value::Low);
+    latch.sim.run().unwrap();
+    assert!(latch.sim.oscillations().is_empty());
+    assert_eq!(latch.value(latch.q), Some(Value::High));
+    assert_eq!(latch.value(latch.probe), Some(Value::Low));
+}
+
+#[test]
+fn test_fast_clock_is_not_an_oscillation() {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        oscillation_threshold: 10,
+        max_events: None,
+        ..SimulationConfig::default()
+    });
+    let clk = sim.netlist_mut().create_node(BusWidth(1));
+    let out = sim.netlist_mut().create_node(BusWidth(8));
+    let clock = sim.add_component(Box::new(Clock::new(ComponentId(1))));
+    sim.connect(clock, "out".to_string(), clk).unwrap();
+    // The counter takes longer to respond than the clock stays at one level
+    let counter = sim.add_component(Box::new(Counter::new(ComponentId(2))));
+    sim.connect(counter, "CLK".to_string(), clk).unwrap();
+    sim.connect(counter, "OUT".to_string(), out).unwrap();
+
+    sim.reset();
+    sim.run_until(Timestamp(200)).unwrap();
+
+    assert!(sim.stats().events_processed > 100);
+    assert!(sim.oscillations().is_empty());
+    assert!(sim.get_node_signal(out).unwrap().to_u64().unwrap() > 10);
+}
@@ -0,0 +1,128 @@
+//! Snapshot and reverse stepping tests
+//!
+//! These tests run a clocked counter, capture and restore its state, and step
+//! it backwards through the bounded history.
+
+use logisim_core::simulation::{SimulationConfig, SimulationError};
+use logisim_core::std::memory::Counter;
+use logisim_core::std::wiring::Clock;
+use logisim_core::{BusWidth, ComponentId, NodeId, Simulation, SimulationSnapshot, Timestamp};
+
+struct Circuit {
+    sim: Simulation,
+    clk: NodeId,
+    out: NodeId,
+}
+
+/// A counter driven by a clock with a period of 40
+fn circuit(history_depth: usize) -> Circuit {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        max_time: None,
+        max_events: None,
+        history_depth,
+        ..SimulationConfig::default()
+    });
+    let clk = sim.netlist_mut().create_node(BusWidth(1));
+    let out = sim.netlist_mut().create_node(BusWidth(8));
+
+    let mut clock = Clock::new(ComponentId(1));
+    clock.set_high_duration(20);
+    clock.set_low_duration(20);
+    let clock = sim.add_component(Box::new(clock));
+    sim.connect(clock, "out".to_string(), clk).unwrap();
+    let counter = sim.add_component(Box::new(Counter::new(ComponentId(2))));
+    sim.connect(counter, "CLK".to_string(), clk).unwrap();
+    sim.connect(counter, "OUT".to_string(), out).unwrap();
+
+    sim.reset();
+    Circuit { sim, clk, out }
+}
+
+impl Circuit {
+    fn count(&self) -> u64 {
+        self.sim
+            .get_node_signal(self.out)
+            .unwrap()
+            .to_u64()
+            .unwrap()
+    }
+}
+
+#[test]
+fn test_snapshot_round_trips_through_json() {
+    let mut circuit = circuit(0);
+    circuit.sim.run_until(Timestamp(200)).unwrap();
+    let saved_count = circuit.count();
+    let json = serde_json::to_string(&circuit.sim.snapshot()).unwrap();
+
+    circuit.sim.run_until(Timestamp(400)).unwrap();
+    let later_count = circuit.count();
+    assert!(later_count > saved_count);
+
+    let snapshot: SimulationSnapshot = serde_json::from_str(&json).unwrap();
+    circuit.sim.restore(&snapshot).unwrap();
+    assert_eq!(circuit.sim.current_time(), snapshot.time());
+    assert_eq!(circuit.count(), saved_count);
+
+    // The counter's own state came back too, so it resumes identically
+    circuit.sim.run_until(Timestamp(400)).unwrap();
+    assert_eq!(circuit.count(), later_count);
+}
+
+#[test]
+fn test_restore_into_another_circuit_fails() {
+    let circuit = circuit(0);
+    let mut other = Simulation::new();
+
+    assert!(matches!(
+        other.restore(&circuit.sim.snapshot()),
+        Err(SimulationError::NodeNotFound(_))
+    ));
+}
+
+#[test]
+fn test_step_back_events() {
+    let mut circuit = circuit(100);
+    circuit.sim.run_until(Timestamp(200)).unwrap();
+    let events = circuit.sim.stats().events_processed;
+    let time = circuit.sim.current_time();
+    let count = circuit.count();
+
+    assert_eq!(circuit.sim.step_back(10).unwrap(), 10);
+    assert_eq!(circuit.sim.stats().events_processed, events - 10);
+    assert!(circuit.sim.current_time() <= time);
+
+    // Replaying the same events ends up in the same place
+    assert_eq!(circuit.sim.run_steps(10).unwrap(), 10);
+    assert_eq!(circuit.sim.stats().events_processed, events);
+    assert_eq!(circuit.sim.current_time(), time);
+    assert_eq!(circuit.count(), count);
+}
+
+#[test]
+fn test_step_back_cycles() {
+    let mut circuit = circuit(100);
+    circuit.sim.run_until(Timestamp(300)).unwrap();
+    let count = circuit.count();
+
+    assert_eq!(circuit.sim.step_back_cycles(circuit.clk, 2).unwrap(), 2);
+    assert_eq!(circuit.count(), count - 2);
+    assert_eq!(circuit.sim.current_time(), Timestamp(220));
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let mut circuit = circuit(5);
+    circuit.sim.run_until(Timestamp(200)).unwrap();
+    let events = circuit.sim.stats().events_processed;
+
+    assert_eq!(circuit.sim.history_len(), 5);
+    assert_eq!(circuit.sim.step_back(10).unwrap(), 5);
+    assert_eq!(circuit.sim.stats().events_processed, events - 5);
+    assert_eq!(circuit.sim.history_len(), 0);
+
+    // A reset forgets the history
+    circuit.sim.run_until(Timestamp(300)).unwrap();
+    circuit.sim.reset();
+    assert_eq!(circuit.sim.history_len(), 0);
+}
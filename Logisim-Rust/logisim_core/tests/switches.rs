@@ -0,0 +1,260 @@
+//! Switch-level simulation tests
+//!
+//! These tests build CMOS gates out of transistors between Power and
+//! Ground, pull a net through a transistor against a pull resistor and pass
+//! values both ways through transmission gates.
+
+use logisim_core::simulation::{SimulationConfig, SimulationEngine};
+use logisim_core::std::wiring::Power;
+use logisim_core::std::wiring::{
+    Ground, PowerOnReset, Probe, PullResistor, Transistor, TransistorType, TransmissionGate,
+};
+use logisim_core::{
+    BusWidth, Component, ComponentId, LogicMode, NodeId, Signal, Simulation, Timestamp, Value,
+};
+
+/// Add a component and connect its pins
+fn place(sim: &mut Simulation, component: Box<dyn Component>, pins: &[(&str, NodeId)]) {
+    let id = sim.add_component(component);
+    for (pin, node) in pins {
+        sim.connect(id, pin.to_string(), *node).unwrap();
+    }
+}
+
+/// A transistor id and type and the nodes on its gate, source and drain
+type Placement<'a> = (u64, TransistorType, [(&'a str, NodeId); 3]);
+
+fn transistor(id: u64, transistor_type: TransistorType) -> Box<dyn Component> {
+    Box::new(Transistor::new_with_type(ComponentId(id), transistor_type))
+}
+
+/// Power and Ground rails
+fn rails(sim: &mut Simulation) -> (NodeId, NodeId) {
+    let vdd = sim.netlist_mut().create_node(BusWidth(1));
+    let gnd = sim.netlist_mut().create_node(BusWidth(1));
+    place(sim, Box::new(Power::new(ComponentId(100))), &[("out", vdd)]);
+    place(
+        sim,
+        Box::new(Ground::new(ComponentId(101))),
+        &[("out", gnd)],
+    );
+    (vdd, gnd)
+}
+
+fn drive(sim: &mut Simulation, time: u64, node: NodeId, value: Value) {
+    sim.schedule_signal_change(
+        Timestamp(time),
+        node,
+        Signal::new_single(value),
+        ComponentId(0),
+    );
+}
+
+fn value(sim: &Simulation, node: NodeId) -> Value {
+    sim.get_node_signal(node).unwrap().get_bit(0).unwrap()
+}
+
+#[test]
+fn test_cmos_inverter() {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        engine: SimulationEngine::Levelized,
+        ..SimulationConfig::default()
+    });
+    let (vdd, gnd) = rails(&mut sim);
+    let a = sim.netlist_mut().create_node(BusWidth(1));
+    let y = sim.netlist_mut().create_node(BusWidth(1));
+    place(
+        &mut sim,
+        transistor(1, TransistorType::P),
+        &[("gate", a), ("source", vdd), ("drain", y)],
+    );
+    place(
+        &mut sim,
+        transistor(2, TransistorType::N),
+        &[("gate", a), ("source", gnd), ("drain", y)],
+    );
+    sim.reset();
+    // Switches join nets both ways, which has no levelized order
+    assert_eq!(sim.active_engine(), SimulationEngine::EventDriven);
+
+    for (time, input, output) in [(0, Value::Low, Value::High), (10, Value::High, Value::Low)] {
+        drive(&mut sim, time, a, input);
+        sim.run_until(Timestamp(time + 5)).unwrap();
+        assert_eq!(value(&sim, y), output, "a={:?}", input);
+        // The rails hold their own level whichever transistor conducts
+        assert_eq!(value(&sim, vdd), Value::High);
+        assert_eq!(value(&sim, gnd), Value::Low);
+    }
+
+    // A floating gate leaves the output in error
+    drive(&mut sim, 20, a, Value::HighZ);
+    sim.run_until(Timestamp(25)).unwrap();
+    assert_eq!(value(&sim, y), Value::Error);
+}
+
+#[test]
+fn test_cmos_nand() {
+    let mut sim = Simulation::new();
+    let (vdd, gnd) = rails(&mut sim);
+    let mut node = || sim.netlist_mut().create_node(BusWidth(1));
+    let (a, b, y, middle) = (node(), node(), node(), node());
+
+    // Two P-type pull-ups in parallel, two N-type pull-downs in series
+    let pins: [Placement; 4] = [
+        (
+            1,
+            TransistorType::P,
+            [("gate", a), ("source", vdd), ("drain", y)],
+        ),
+        (
+            2,
+            TransistorType::P,
+            [("gate", b), ("source", vdd), ("drain", y)],
+        ),
+        (
+            3,
+            TransistorType::N,
+            [("gate", a), ("source", middle), ("drain", y)],
+        ),
+        (
+            4,
+            TransistorType::N,
+            [("gate", b), ("source", gnd), ("drain", middle)],
+        ),
+    ];
+    for (id, transistor_type, pins) in pins {
+        place(&mut sim, transistor(id, transistor_type), &pins);
+    }
+    sim.reset();
+
+    let mut outputs = Vec::new();
+    for (i, (va, vb)) in [
+        (Value::Low, Value::Low),
+        (Value::Low, Value::High),
+        (Value::High, Value::Low),
+        (Value::High, Value::High),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let time = i as u64 * 10;
+        drive(&mut sim, time, a, va);
+        drive(&mut sim, time, b, vb);
+        sim.run_until(Timestamp(time + 5)).unwrap();
+        outputs.push(value(&sim, y));
+    }
+    assert_eq!(
+        outputs,
+        vec![Value::High, Value::High, Value::High, Value::Low]
+    );
+
+    // With only the top N-type transistor on, the middle node floats
+    drive(&mut sim, 40, b, Value::Low);
+    sim.run_until(Timestamp(45)).unwrap();
+    assert_eq!(value(&sim, y), Value::High);
+    assert_eq!(value(&sim, middle), Value::High);
+}
+
+#[test]
+fn test_pull_resistor_through_transistor() {
+    for (logic_mode, released, held) in [
+        (LogicMode::Logisim, "0", "1"),
+        (LogicMode::Ieee1164, "L", "1"),
+    ] {
+        let mut sim = Simulation::with_config(SimulationConfig {
+            logic_mode,
+            ..SimulationConfig::default()
+        });
+        let (vdd, _) = rails(&mut sim);
+        let gate = sim.netlist_mut().create_node(BusWidth(1));
+        let y = sim.netlist_mut().create_node(BusWidth(1));
+        place(
+            &mut sim,
+            transistor(1, TransistorType::P),
+            &[("gate", gate), ("source", vdd), ("drain", y)],
+        );
+        place(
+            &mut sim,
+            Box::new(PullResistor::new(ComponentId(2))),
+            &[("out", y)],
+        );
+        place(&mut sim, Box::new(Probe::new(ComponentId(3))), &[("in", y)]);
+        sim.reset();
+
+        let mut levels = Vec::new();
+        for (time, input) in [(0, Value::High), (10, Value::Low)] {
+            drive(&mut sim, time, gate, input);
+            sim.run_until(Timestamp(time + 5)).unwrap();
+            let level = sim.get_node_std_logic(y).unwrap()[0];
+            levels.push(level.to_string());
+        }
+        assert_eq!(levels, vec![released, held], "{:?}", logic_mode);
+
+        // The probe saw the net's last value
+        let probe = sim.get_component(ComponentId(3)).unwrap();
+        assert_eq!(
+            probe.get_pin("in").unwrap().signal,
+            Signal::new_single(Value::High)
+        );
+
+        // Removing the resistor releases the net once the switch opens
+        sim.remove_component(ComponentId(2));
+        drive(&mut sim, 20, gate, Value::High);
+        sim.run_until(Timestamp(25)).unwrap();
+        assert_eq!(value(&sim, y), Value::HighZ);
+    }
+}
+
+#[test]
+fn test_transmission_gates_pass_both_ways() {
+    let mut sim = Simulation::new();
+    let width = BusWidth(4);
+    let mut node = |width| sim.netlist_mut().create_node(width);
+    let (a, b, y, select, select_n) = (
+        node(width),
+        node(width),
+        node(width),
+        node(BusWidth(1)),
+        node(BusWidth(1)),
+    );
+
+    // A 2:1 multiplexer: select high passes a, low passes b
+    for (id, input, p_gate, n_gate) in [(1, a, select_n, select), (2, b, select, select_n)] {
+        let mut gate = TransmissionGate::new(ComponentId(id));
+        gate.set_width(width);
+        place(
+            &mut sim,
+            Box::new(gate),
+            &[
+                ("p_gate", p_gate),
+                ("n_gate", n_gate),
+                ("source", input),
+                ("drain", y),
+            ],
+        );
+    }
+    let mut por = PowerOnReset::new(ComponentId(3));
+    por.set_duration(15);
+    place(&mut sim, Box::new(por), &[("out", select)]);
+    sim.reset();
+
+    let bus = |value| Signal::from_u64(value, width);
+    sim.schedule_signal_change(Timestamp(0), a, bus(0xA), ComponentId(0));
+    sim.schedule_signal_change(Timestamp(0), b, bus(0x5), ComponentId(0));
+    drive(&mut sim, 0, select_n, Value::Low);
+    sim.run_until(Timestamp(5)).unwrap();
+    assert_eq!(sim.get_node_signal(y), Some(bus(0xA)));
+
+    // The power-on reset drops the select line, swapping the inputs
+    drive(&mut sim, 15, select_n, Value::High);
+    sim.run_until(Timestamp(20)).unwrap();
+    assert_eq!(value(&sim, select), Value::Low);
+    assert_eq!(sim.get_node_signal(y), Some(bus(0x5)));
+
+    // Released, b is driven back from the output side
+    sim.schedule_signal_change(Timestamp(20), b, Signal::high_z(width), ComponentId(0));
+    sim.schedule_signal_change(Timestamp(20), y, bus(0x3), ComponentId(0));
+    sim.run_until(Timestamp(25)).unwrap();
+    assert_eq!(sim.get_node_signal(b), Some(bus(0x3)));
+    assert_eq!(sim.get_node_signal(a), Some(bus(0xA)));
+}
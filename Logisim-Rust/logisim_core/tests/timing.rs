@@ -0,0 +1,250 @@
+//! Static timing analysis tests
+//!
+//! These tests analyse a ripple-carry adder built from gates, a pipeline
+//! stage between two flip-flops and a hierarchical project, and check the
+//! critical paths and clock frequencies reported.
+
+mod common;
+
+use common::{gate, named_node};
+use logisim_core::circ_format::{CircIntegration, CircParser};
+use logisim_core::simulation::{SimulationConfig, SimulationError};
+use logisim_core::std::memory::DFlipFlop;
+use logisim_core::timing::PointKind;
+use logisim_core::{
+    AndGate, Component, ComponentId, DelayTable, NodeId, NorGate, NotGate, OrGate, Simulation,
+    XorGate,
+};
+
+/// A gate and the nodes on its two inputs and its output
+type Gate<'a> = (Box<dyn Component>, [(&'a str, NodeId); 3]);
+
+/// A `bits`-wide ripple-carry adder with 3-unit XOR gates and 1-unit AND and
+/// OR gates
+fn ripple_carry_adder(bits: usize) -> Simulation {
+    let mut sim = Simulation::with_config(SimulationConfig {
+        delays: DelayTable::new()
+            .with("XOR", 3)
+            .with("AND", 1)
+            .with("OR", 1),
+        ..SimulationConfig::default()
+    });
+    let mut id = 0;
+    let mut next_id = || {
+        id += 1;
+        ComponentId(id)
+    };
+    let mut carry = named_node(&mut sim, "c0".to_string());
+    for bit in 0..bits {
+        let mut net = |name: &str| named_node(&mut sim, format!("{}{}", name, bit));
+        let (a, b, p, g, t, s) = (net("a"), net("b"), net("p"), net("g"), net("t"), net("s"));
+        let carry_out = named_node(&mut sim, format!("c{}", bit + 1));
+        let full_adder: [Gate; 5] = [
+            (
+                Box::new(XorGate::new(next_id())),
+                [("A", a), ("B", b), ("Y", p)],
+            ),
+            (
+                Box::new(XorGate::new(next_id())),
+                [("A", p), ("B", carry), ("Y", s)],
+            ),
+            (
+                Box::new(AndGate::new(next_id())),
+                [("A", a), ("B", b), ("Y", g)],
+            ),
+            (
+                Box::new(AndGate::new(next_id())),
+                [("A", p), ("B", carry), ("Y", t)],
+            ),
+            (
+                Box::new(OrGate::new(next_id())),
+                [("A", g), ("B", t), ("Y", carry_out)],
+            ),
+        ];
+        for (component, pins) in full_adder {
+            gate(&mut sim, component, &pins);
+        }
+        carry = carry_out;
+    }
+    sim
+}
+
+#[test]
+fn test_ripple_carry_critical_path() {
+    let sim = ripple_carry_adder(4);
+    let report = sim.timing_report().unwrap();
+    let path = report.critical_path.unwrap();
+
+    // a0/b0 -> p0, then the carry ripples up to c3, then s3
+    assert_eq!(path.delay, 12);
+    assert_eq!(path.start.kind, PointKind::Input);
+    assert_eq!(path.end.kind, PointKind::Output);
+    assert_eq!(path.end.node_name.as_deref(), Some("s3"));
+    let steps: Vec<(&str, &str, u64)> = path
+        .steps
+        .iter()
+        .map(|step| {
+            (
+                step.name.as_str(),
+                step.node_name.as_deref().unwrap(),
+                step.arrival,
+            )
+        })
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("XOR", "p0", 3),
+            ("AND", "t0", 4),
+            ("OR", "c1", 5),
+            ("AND", "t1", 6),
+            ("OR", "c2", 7),
+            ("AND", "t2", 8),
+            ("OR", "c3", 9),
+            ("XOR", "s3", 12),
+        ]
+    );
+
+    // No registers, so no clock frequency
+    assert_eq!(report.register_path, None);
+    assert_eq!(report.min_period, None);
+
+    // Each extra bit adds one AND and one OR to the carry chain
+    let report = ripple_carry_adder(8).timing_report().unwrap();
+    assert_eq!(report.critical_path.unwrap().delay, 20);
+}
+
+#[test]
+fn test_register_to_register_frequency() {
+    let mut sim = Simulation::new();
+    let names = ["d", "q", "nq", "y", "clk", "out"];
+    let [d, q, nq, y, clk, out] = names.map(|name| named_node(&mut sim, name.to_string()));
+    let launch = Box::new(DFlipFlop::new(ComponentId(1)));
+    gate(&mut sim, launch, &[("D", d), ("CLK", clk), ("Q", q)]);
+    gate(
+        &mut sim,
+        Box::new(NotGate::new(ComponentId(2))),
+        &[("A", q), ("Y", nq)],
+    );
+    gate(
+        &mut sim,
+        Box::new(NotGate::new(ComponentId(3))),
+        &[("A", nq), ("Y", y)],
+    );
+    let capture = Box::new(DFlipFlop::new(ComponentId(4)));
+    gate(&mut sim, capture, &[("D", y), ("CLK", clk), ("Q", out)]);
+
+    let report = sim.timing_report().unwrap();
+    let path = report.register_path.clone().unwrap();
+    assert_eq!(report.critical_path.as_ref(), Some(&path));
+
+    // 5 clock-to-output, two 1-unit inverters, 2 setup
+    assert_eq!((path.launch, path.setup, path.delay), (5, 2, 9));
+    assert_eq!(path.start.component, Some(ComponentId(1)));
+    assert_eq!(path.end.component, Some(ComponentId(4)));
+    assert_eq!(path.end.pin.as_deref(), Some("D"));
+    assert_eq!(report.min_period, Some(9));
+    let text = report.to_string();
+    assert!(text.contains("Minimum clock period: 9"), "{}", text);
+    assert!(
+        text.contains("Maximum clock frequency: 111.11 MHz"),
+        "{}",
+        text
+    );
+
+    // Instance delays are charged like in simulation
+    sim.set_component_delay(ComponentId(3), 4).unwrap();
+    assert_eq!(sim.timing_report().unwrap().min_period, Some(12));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["min_period"], 9);
+    assert_eq!(json["register_path"]["steps"][1]["node_name"], "y");
+}
+
+#[test]
+fn test_hierarchical_design() {
+    let inverter = concat!(
+        "    <wire from=\"(100,100)\" to=\"(150,100)\"/>\n",
+        "    <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\">\n",
+        "      <a name=\"label\" val=\"A\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"1\" loc=\"(180,100)\" name=\"NOT Gate\"/>\n",
+        "    <comp lib=\"0\" loc=\"(180,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"type\" val=\"output\"/>\n",
+        "      <a name=\"label\" val=\"Y\"/>\n",
+        "    </comp>\n",
+    );
+    let double_inverter = concat!(
+        "    <wire from=\"(100,100)\" to=\"(130,100)\"/>\n",
+        "    <wire from=\"(160,100)\" to=\"(190,100)\"/>\n",
+        "    <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\">\n",
+        "      <a name=\"label\" val=\"A\"/>\n",
+        "    </comp>\n",
+        "    <comp loc=\"(160,100)\" name=\"INV\">\n",
+        "      <a name=\"label\" val=\"first\"/>\n",
+        "    </comp>\n",
+        "    <comp loc=\"(220,100)\" name=\"INV\"/>\n",
+        "    <comp lib=\"0\" loc=\"(220,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"type\" val=\"output\"/>\n",
+        "      <a name=\"label\" val=\"Y\"/>\n",
+        "    </comp>\n",
+    );
+    let main = concat!(
+        "    <comp lib=\"0\" loc=\"(270,100)\" name=\"Constant\"/>\n",
+        "    <comp loc=\"(300,100)\" name=\"BUF\"/>\n",
+        "    <comp lib=\"0\" loc=\"(300,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"type\" val=\"output\"/>\n",
+        "      <a name=\"label\" val=\"Q\"/>\n",
+        "    </comp>\n",
+    );
+    let mut xml = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+        "<project source=\"3.8.0\" version=\"1.0\">\n",
+        "  <lib desc=\"#Wiring\" name=\"0\"/>\n",
+        "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+        "  <main name=\"main\"/>\n",
+    )
+    .to_string();
+    for (name, body) in [("main", main), ("BUF", double_inverter), ("INV", inverter)] {
+        xml.push_str(&format!(
+            "  <circuit name=\"{}\">\n{}  </circuit>\n",
+            name, body
+        ));
+    }
+    xml.push_str("</project>\n");
+    let circuit_file = CircParser::parse_string(&xml).unwrap();
+    let sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+
+    let path = sim.timing_report().unwrap().critical_path.unwrap();
+    assert_eq!(path.delay, 2);
+    assert_eq!(path.start.name.as_deref(), Some("Constant"));
+    assert_eq!(path.end.node_name.as_deref(), Some("Q"));
+    let nodes: Vec<&str> = path
+        .steps
+        .iter()
+        .map(|step| step.node_name.as_deref().unwrap())
+        .collect();
+    assert_eq!(
+        nodes,
+        vec!["BUF(300,100)/first/Y", "BUF(300,100)/INV(220,100)/Y"]
+    );
+}
+
+#[test]
+fn test_combinational_loop_is_rejected() {
+    let mut sim = Simulation::new();
+    let names = ["set", "reset", "q", "nq"];
+    let [set, reset, q, nq] = names.map(|name| named_node(&mut sim, name.to_string()));
+    let pins = [("A", reset), ("B", nq), ("Y", q)];
+    gate(&mut sim, Box::new(NorGate::new(ComponentId(1))), &pins);
+    let pins = [("A", set), ("B", q), ("Y", nq)];
+    gate(&mut sim, Box::new(NorGate::new(ComponentId(2))), &pins);
+
+    assert!(matches!(
+        sim.timing_report(),
+        Err(SimulationError::TimingAnalysisError(_))
+    ));
+}
@@ -0,0 +1,251 @@
+//! VCD waveform export tests
+//!
+//! These tests stream a hand-built simulation and a circuit loaded from a
+//! .circ file to VCD files, and check the header, the scopes of the circuit
+//! hierarchy, bus and X/Z values, and the value changes with their times,
+//! also when names collide and the simulation goes back in time.
+
+use std::fs;
+
+use logisim_core::circ_format::{CircIntegration, CircParser};
+use logisim_core::vcd::{TimeUnit, Timescale, VcdOptions, VcdRecorder};
+use logisim_core::{BusWidth, ComponentId, Signal, Simulation, Timestamp, Value};
+use tempfile::TempDir;
+
+#[test]
+fn test_bus_values_and_timescale() {
+    let mut sim = Simulation::new();
+    let clk = sim
+        .netlist_mut()
+        .create_named_node(BusWidth(1), "clk".to_string());
+    let data = sim
+        .netlist_mut()
+        .create_named_node(BusWidth(4), "data bus".to_string());
+    let carry = sim
+        .netlist_mut()
+        .create_named_node(BusWidth(1), "cpu/alu/carry".to_string());
+    let ignored = sim
+        .netlist_mut()
+        .create_named_node(BusWidth(1), "ignored".to_string());
+    sim.reset();
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("bench.vcd");
+    let options = VcdOptions {
+        timescale: Timescale::new(10, TimeUnit::Ps).unwrap(),
+        top: Some("bench".to_string()),
+        nodes: Some(vec![clk, data, carry]),
+    };
+    let recorder = VcdRecorder::create(&mut sim, &path, &options).unwrap();
+
+    let changes = [
+        (0, clk, Signal::new_single(Value::Low)),
+        (0, data, Signal::new_uniform(BusWidth(4), Value::HighZ)),
+        (0, carry, Signal::new_single(Value::Low)),
+        (10, clk, Signal::new_single(Value::High)),
+        (10, data, Signal::from_u64(0b1010, BusWidth(4))),
+        (10, ignored, Signal::new_single(Value::High)),
+        (20, clk, Signal::new_single(Value::Low)),
+        // The same value again is not a change
+        (20, data, Signal::from_u64(0b1010, BusWidth(4))),
+        (
+            30,
+            data,
+            Signal::new_bus(vec![Value::High, Value::Error, Value::HighZ, Value::Low]),
+        ),
+        (30, carry, Signal::new_single(Value::Unknown)),
+        // Not recorded, but the waveforms last until then
+        (50, ignored, Signal::new_single(Value::Low)),
+    ];
+    for (time, node, signal) in changes {
+        sim.schedule_signal_change(Timestamp(time), node, signal, ComponentId(0));
+    }
+    sim.run().unwrap();
+    recorder.finish(&sim).unwrap();
+
+    let vcd = fs::read_to_string(&path).unwrap();
+    let expected = concat!(
+        "$timescale 10 ps $end\n",
+        "$scope module bench $end\n",
+        "$var wire 1 ! clk $end\n",
+        "$var wire 4 \" data_bus [3:0] $end\n",
+        "$scope module cpu $end\n",
+        "$scope module alu $end\n",
+        "$var wire 1 # carry $end\n",
+        "$upscope $end\n",
+        "$upscope $end\n",
+        "$upscope $end\n",
+        "$enddefinitions $end\n",
+        "#0\n",
+        "$dumpvars\n",
+        "x!\n",
+        "bxxxx \"\n",
+        "x#\n",
+        "$end\n",
+        "0!\n",
+        "bzzzz \"\n",
+        "0#\n",
+        "#10\n",
+        "1!\n",
+        "b1010 \"\n",
+        "#20\n",
+        "0!\n",
+        "#30\n",
+        "b0zx1 \"\n",
+        "x#\n",
+        "#50\n",
+    );
+    assert!(vcd.starts_with("$version Logisim-RUST "), "{}", vcd);
+    assert!(vcd.ends_with(expected), "{}", vcd);
+}
+
+#[test]
+fn test_circuit_hierarchy_scopes() {
+    let xml = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n",
+        "<project source=\"3.8.0\" version=\"1.0\">\n",
+        "  <lib desc=\"#Wiring\" name=\"0\"/>\n",
+        "  <lib desc=\"#Gates\" name=\"1\"/>\n",
+        "  <main name=\"cpu\"/>\n",
+        "  <circuit name=\"cpu\">\n",
+        "    <comp lib=\"0\" loc=\"(270,100)\" name=\"Constant\"/>\n",
+        "    <comp loc=\"(300,100)\" name=\"INV\">\n",
+        "      <a name=\"label\" val=\"inv\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"0\" loc=\"(300,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"type\" val=\"output\"/>\n",
+        "      <a name=\"label\" val=\"Y\"/>\n",
+        "    </comp>\n",
+        "  </circuit>\n",
+        "  <circuit name=\"INV\">\n",
+        "    <wire from=\"(100,100)\" to=\"(150,100)\"/>\n",
+        "    <comp lib=\"0\" loc=\"(100,100)\" name=\"Pin\">\n",
+        "      <a name=\"label\" val=\"A\"/>\n",
+        "    </comp>\n",
+        "    <comp lib=\"1\" loc=\"(180,100)\" name=\"NOT Gate\"/>\n",
+        "    <comp lib=\"0\" loc=\"(180,100)\" name=\"Pin\">\n",
+        "      <a name=\"facing\" val=\"west\"/>\n",
+        "      <a name=\"type\" val=\"output\"/>\n",
+        "      <a name=\"label\" val=\"Y\"/>\n",
+        "    </comp>\n",
+        "  </circuit>\n",
+        "</project>\n",
+    );
+    let circuit_file = CircParser::parse_string(xml).unwrap();
+    let mut sim = CircIntegration::circuit_file_to_simulation(&circuit_file).unwrap();
+    sim.reset();
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("cpu.vcd");
+    let recorder = VcdRecorder::create(&mut sim, &path, &VcdOptions::default()).unwrap();
+    sim.run().unwrap();
+    recorder.finish(&sim).unwrap();
+
+    let vcd = fs::read_to_string(&path).unwrap();
+    assert!(vcd.contains("$timescale 1 ns $end\n"), "{}", vcd);
+    // The top scope is the main circuit, holding the instance's scope
+    let header = vcd.split("$enddefinitions").next().unwrap();
+    let scopes: Vec<&str> = header
+        .lines()
+        .filter(|line| line.starts_with("$scope") || line.starts_with("$upscope"))
+        .collect();
+    assert_eq!(
+        scopes,
+        vec![
+            "$scope module cpu $end",
+            "$scope module inv $end",
+            "$upscope $end",
+            "$upscope $end",
+        ]
+    );
+    let code = |name: &str| {
+        let var = header
+            .lines()
+            .find(|line| line.ends_with(&format!(" {} $end", name)))
+            .unwrap_or_else(|| panic!("No variable {} in {}", name, vcd));
+        var.split_whitespace().nth(3).unwrap().to_string()
+    };
+
+    // The inverter's output drives the top-level pin low
+    let y = code("Y");
+    let changes = vcd.split("$end\n").last().unwrap();
+    assert!(
+        changes.lines().any(|line| line == format!("0{}", y)),
+        "{}",
+        vcd
+    );
+    let a = code("A");
+    assert!(
+        changes.lines().any(|line| line == format!("1{}", a)),
+        "{}",
+        vcd
+    );
+}
+
+#[test]
+fn test_colliding_names_and_restore() {
+    let mut sim = Simulation::new();
+    let one = BusWidth(1);
+    let mut node = |name: &str| sim.netlist_mut().create_named_node(one, name.to_string());
+    let (first, second, alu) = (node("bus"), node("bus"), node("alu"));
+    node("alu/carry");
+    sim.reset();
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("restore.vcd");
+    let recorder = VcdRecorder::create(&mut sim, &path, &VcdOptions::default()).unwrap();
+    let change = |sim: &mut Simulation, time: u64, node, value| {
+        sim.schedule_signal_change(
+            Timestamp(time),
+            node,
+            Signal::new_single(value),
+            ComponentId(0),
+        );
+        sim.run().unwrap();
+    };
+    change(&mut sim, 0, first, Value::Low);
+    change(&mut sim, 10, first, Value::High);
+    let snapshot = sim.snapshot();
+    change(&mut sim, 20, first, Value::Low);
+    change(&mut sim, 30, alu, Value::High);
+
+    // Back at 10, the waveforms go on from 30
+    sim.restore(&snapshot).unwrap();
+    recorder.sync(&sim).unwrap();
+    change(&mut sim, 15, second, Value::High);
+    recorder.finish(&sim).unwrap();
+
+    let vcd = fs::read_to_string(&path).unwrap();
+    let expected = concat!(
+        "$scope module top $end\n",
+        "$var wire 1 # alu_3 $end\n",
+        "$var wire 1 ! bus $end\n",
+        "$var wire 1 \" bus_2 $end\n",
+        "$scope module alu $end\n",
+        "$var wire 1 $ carry $end\n",
+        "$upscope $end\n",
+        "$upscope $end\n",
+        "$enddefinitions $end\n",
+        "#0\n",
+        "$dumpvars\n",
+        "x!\n",
+        "x\"\n",
+        "x#\n",
+        "x$\n",
+        "$end\n",
+        "0!\n",
+        "#10\n",
+        "1!\n",
+        "#20\n",
+        "0!\n",
+        "#30\n",
+        "1#\n",
+        "$comment rewound to time 10 $end\n",
+        "1!\n",
+        "x#\n",
+        "#35\n",
+        "1\"\n",
+    );
+    assert!(vcd.ends_with(expected), "{}", vcd);
+}
@@ -0,0 +1,152 @@
+//! Timing violation tests
+//!
+//! These tests drive the data and clock inputs of sequential components at
+//! chosen times and check the setup and hold violations reported, then look
+//! for the glitch a static hazard produces.
+
+use logisim_core::simulation::{SimulationConfig, SimulationError};
+use logisim_core::std::memory::{DFlipFlop, SetupHold, ShiftRegister};
+use logisim_core::{
+    AndGate, BusWidth, Component, ComponentId, NodeId, NotGate, Signal, Simulation, Timestamp,
+    Value, ViolationAction, ViolationKind,
+};
+
+/// Wire `component`'s `D` and `CLK` pins to fresh nodes
+fn clocked(sim: &mut Simulation, component: Box<dyn Component>) -> (NodeId, NodeId) {
+    let id = sim.add_component(component);
+    let d = sim.netlist_mut().create_node(BusWidth(1));
+    let clk = sim.netlist_mut().create_node(BusWidth(1));
+    sim.connect(id, "D".to_string(), d).unwrap();
+    sim.connect(id, "CLK".to_string(), clk).unwrap();
+    (d, clk)
+}
+
+fn drive(sim: &mut Simulation, node: NodeId, changes: &[(u64, Value)]) {
+    for &(time, value) in changes {
+        sim.schedule_signal_change(
+            Timestamp(time),
+            node,
+            Signal::new_single(value),
+            ComponentId(0),
+        );
+    }
+}
+
+/// `A AND NOT A` raising `A` at 20; the AND output pulses high from 23 to 24
+fn hazard(config: SimulationConfig) -> Simulation {
+    let mut sim = Simulation::with_config(config);
+    let mut node = || sim.netlist_mut().create_node(BusWidth(1));
+    let (a, not_a, y) = (node(), node(), node());
+    let not = sim.add_component(Box::new(NotGate::new(ComponentId(1))));
+    sim.connect(not, "A".to_string(), a).unwrap();
+    sim.connect(not, "Y".to_string(), not_a).unwrap();
+    let and = sim.add_component(Box::new(AndGate::new(ComponentId(2))));
+    sim.connect(and, "A".to_string(), a).unwrap();
+    sim.connect(and, "B".to_string(), not_a).unwrap();
+    sim.connect(and, "Y".to_string(), y).unwrap();
+    sim.set_component_delay(and, 3).unwrap();
+    sim.reset();
+    drive(&mut sim, a, &[(0, Value::Low), (20, Value::High)]);
+    sim
+}
+
+#[test]
+fn test_setup_violation() {
+    let mut sim = Simulation::new();
+    let (d, clk) = clocked(&mut sim, Box::new(DFlipFlop::new(ComponentId(1))));
+    sim.reset();
+
+    // D settles in time for the first edge but not for the second
+    drive(
+        &mut sim,
+        d,
+        &[(0, Value::Low), (5, Value::High), (29, Value::Low)],
+    );
+    drive(
+        &mut sim,
+        clk,
+        &[
+            (0, Value::Low),
+            (10, Value::High),
+            (20, Value::Low),
+            (30, Value::High),
+        ],
+    );
+    sim.run().unwrap();
+
+    let violations = sim.violations();
+    assert_eq!(violations.len(), 1);
+    let violation = &violations[0];
+    assert_eq!(violation.kind, ViolationKind::Setup);
+    assert_eq!(violation.time, Timestamp(30));
+    assert_eq!(violation.node, d);
+    assert_eq!(violation.component, Some(ComponentId(1)));
+    assert_eq!(violation.pin.as_deref(), Some("D"));
+    assert_eq!((violation.actual, violation.required), (1, 2));
+
+    sim.reset();
+    assert!(sim.violations().is_empty());
+}
+
+#[test]
+fn test_hold_violation() {
+    let mut sim = Simulation::new();
+    let mut register = ShiftRegister::new(ComponentId(1));
+    register.set_setup_hold(SetupHold::new(2, 3));
+    let (d, clk) = clocked(&mut sim, Box::new(register));
+    sim.reset();
+
+    drive(
+        &mut sim,
+        d,
+        &[(0, Value::Low), (5, Value::High), (12, Value::Low)],
+    );
+    drive(&mut sim, clk, &[(0, Value::Low), (10, Value::High)]);
+    sim.run().unwrap();
+
+    let kinds: Vec<_> = sim
+        .violations()
+        .iter()
+        .map(|violation| (violation.kind, violation.time, violation.actual))
+        .collect();
+    assert_eq!(kinds, vec![(ViolationKind::Hold, Timestamp(12), 2)]);
+}
+
+#[test]
+fn test_glitch_detection() {
+    let mut sim = hazard(SimulationConfig {
+        glitch_width: 2,
+        ..SimulationConfig::default()
+    });
+    sim.run().unwrap();
+
+    let violations = sim.violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind, ViolationKind::Glitch);
+    assert_eq!(violations[0].time, Timestamp(24));
+    assert_eq!((violations[0].actual, violations[0].required), (1, 2));
+
+    // Without a glitch width nothing is checked
+    let mut sim = hazard(SimulationConfig::default());
+    sim.run().unwrap();
+    assert!(sim.violations().is_empty());
+}
+
+#[test]
+fn test_violation_stops_run() {
+    let mut sim = hazard(SimulationConfig {
+        glitch_width: 2,
+        violation_action: ViolationAction::Stop,
+        ..SimulationConfig::default()
+    });
+
+    match sim.run() {
+        Err(SimulationError::TimingViolation(violation)) => {
+            assert_eq!(violation.kind, ViolationKind::Glitch);
+            assert_eq!(violation.time, Timestamp(24));
+        }
+        other => panic!("expected a timing violation, got {:?}", other),
+    }
+    assert_eq!(sim.current_time(), Timestamp(24));
+    assert_eq!(sim.violations().len(), 1);
+}